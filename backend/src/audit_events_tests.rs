@@ -10,9 +10,11 @@ use actix_web::http::StatusCode;
 use actix_web::middleware::Compat;
 use actix_web::web::{self, Data};
 use actix_web::{App, test};
+use serde::Deserialize;
 use serde_json::json;
-use shared::team::{TeamMemberInput, TeamRole, TeamUserRef, UpdateTeam};
+use shared::team::{CreateTeamInvitation, TeamMemberInput, TeamRole, TeamUserRef, UpdateTeam};
 use shared::user::{Role, Session, User};
+use surrealdb::types::SurrealValue;
 use tracing_test::traced_test;
 
 use crate::auth::otp::Model;
@@ -21,10 +23,11 @@ use crate::governor_audit::AuditRateLimit429;
 use crate::governor_peer::PeerOrFallbackIpKeyExtractor;
 use crate::mail::MailService;
 use crate::request_id::WorshipRootSpan;
+use crate::resources::user::passkey::soft_authenticator::SoftAuthenticator;
 use crate::settings::{CookieConfig, OtpConfig};
 use crate::test_helpers::{
-    TeamFixture, create_user, invitation_service, session_service, team_service, test_db,
-    user_service,
+    TeamFixture, create_user, invitation_service, passkey_service, session_service, team_service,
+    test_db, user_service,
 };
 use crate::{auth, http_tests};
 
@@ -40,8 +43,12 @@ fn auth_scope_otp_logout(auth_rate_limit_rps: u64, auth_rate_limit_burst: u32) -
         web::scope("")
             .wrap(Governor::new(&governor_conf))
             .wrap(AuditRateLimit429)
+            .service(auth::oidc::rest::providers)
+            .service(auth::oidc::rest::login)
             .service(auth::otp::rest::otp_request)
             .service(auth::otp::rest::otp_verify)
+            .service(auth::passkey::rest::passkey_options)
+            .service(auth::passkey::rest::passkey_verify)
             .service(auth::rest::logout),
     )
 }
@@ -84,6 +91,11 @@ fn build_auth_app(
         )))
         .app_data(Data::new(user_service(&db)))
         .app_data(Data::new(session_service(&db)))
+        .app_data(Data::new(passkey_service(&db)))
+        .app_data(Data::new(Arc::new(auth::oidc::OidcClients::for_tests(&[
+            ("google", "Google"),
+            ("keycloak", "Church SSO"),
+        ]))))
         .app_data(cookie_cfg)
         .app_data(otp_cfg)
         .app_data(crate::error::json_config())
@@ -187,9 +199,14 @@ async fn audit_invitation_accepted_emits_event() {
     let db = test_db().await.expect("db");
     let fx = TeamFixture::build(&db).await.expect("fixture");
     let inv = invitation_service(&db)
-        .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+        .create_invitation_for_user(
+            &fx.admin_user,
+            &fx.shared_team_id,
+            CreateTeamInvitation::default(),
+        )
         .await
         .expect("invitation");
+    assert!(logs_contain("audit.team.invitation.created"));
     let invitee = create_user(&db, "audit-invitee@test.local")
         .await
         .expect("invitee");
@@ -198,6 +215,11 @@ async fn audit_invitation_accepted_emits_event() {
         .await
         .expect("accept");
     assert!(logs_contain("audit.team.invitation.accepted"));
+    invitation_service(&db)
+        .revoke_invitation_for_user(&fx.admin_user, &fx.shared_team_id, &inv.id)
+        .await
+        .expect("revoke");
+    assert!(logs_contain("audit.team.invitation.revoked"));
 }
 
 #[tokio::test]
@@ -256,6 +278,139 @@ async fn audit_auth_login_failure_emits_event() {
     assert!(logs_contain("audit.auth.login.failure"));
 }
 
+/// BLC-MON-012: a failed OTP attempt for an existing account is persisted against that user.
+#[tokio::test]
+#[traced_test]
+async fn audit_auth_login_failure_is_persisted_for_known_user() {
+    #[derive(Deserialize, SurrealValue)]
+    struct Row {
+        user_id: Option<String>,
+        fields: std::collections::BTreeMap<String, String>,
+    }
+
+    let db = test_db().await.expect("db");
+    let user = create_user(&db, "otp-known-bad@test.local")
+        .await
+        .expect("user");
+    db.remember_otp(&user.email, "111111", "audit-test-pepper", 300)
+        .await
+        .expect("seed otp");
+
+    let app = test::init_service(build_auth_app(db.clone(), 50, 200)).await;
+    let req = test::TestRequest::post()
+        .uri("/auth/otp/verify")
+        .insert_header(("Content-Type", "application/json"))
+        .set_payload(json!({ "email": user.email, "code": "999999" }).to_string())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let rows: Vec<Row> = db
+        .query(
+            "SELECT user_id, fields FROM security_event WHERE event = 'audit.auth.login.failure'",
+        )
+        .await
+        .expect("query")
+        .take(0)
+        .expect("rows");
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].user_id.as_deref(), Some(user.id.as_str()));
+    assert_eq!(rows[0].fields["provider"], "otp");
+    assert_eq!(rows[0].fields["reason"], "otp_invalid");
+}
+
+/// BLC-PASSKEY-002: a passkey assertion logs in like OTP (session cookie plus `SessionBody`),
+/// and replaying it is rejected.
+#[tokio::test]
+#[traced_test]
+async fn audit_auth_passkey_login_emits_events() {
+    let db = test_db().await.expect("db");
+    let user = create_user(&db, "passkey-login@test.local")
+        .await
+        .expect("user");
+    let svc = passkey_service(&db);
+    let mut authenticator = SoftAuthenticator::new("localhost", "http://localhost:8080");
+    let options = svc
+        .registration_options_for_user(&user)
+        .await
+        .expect("options");
+    svc.register_passkey_for_user(
+        &user,
+        shared::user::RegisterPasskey {
+            name: "Laptop".into(),
+            credential: authenticator.register(&options),
+        },
+    )
+    .await
+    .expect("register");
+    assert!(logs_contain("audit.passkey.registered"));
+
+    let app = test::init_service(build_auth_app(db, 50, 200)).await;
+    let req = test::TestRequest::post()
+        .uri("/auth/passkey/options")
+        .to_request();
+    let options = test::call_and_read_body_json(&app, req).await;
+    let assertion = authenticator.login(&options);
+    let req = test::TestRequest::post()
+        .uri("/auth/passkey/verify")
+        .set_json(&assertion)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.response().cookies().any(|c| c.name() == "sso_session"));
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["user"]["id"], user.id.as_str());
+    assert!(logs_contain("audit.auth.login.success"));
+    assert!(logs_contain("provider=passkey"));
+
+    let req = test::TestRequest::post()
+        .uri("/auth/passkey/verify")
+        .set_json(&assertion)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert!(logs_contain("passkey_rejected"));
+}
+
+/// BLC-AUTH-OIDC-001, BLC-AUTH-OIDC-005: `/auth/login` redirects to the provider chosen with
+/// `?provider=` (the first configured one by default); unknown providers are rejected.
+#[tokio::test]
+#[traced_test]
+async fn oidc_login_selects_provider() {
+    let db = test_db().await.expect("db");
+    let app = test::init_service(build_auth_app(db, 50, 200)).await;
+
+    let req = test::TestRequest::get().uri("/auth/providers").to_request();
+    let providers: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        providers,
+        json!([
+            { "id": "google", "name": "Google" },
+            { "id": "keycloak", "name": "Church SSO" }
+        ])
+    );
+
+    for (uri, host) in [
+        ("/auth/login", "google.example.test"),
+        ("/auth/login?provider=Keycloak", "keycloak.example.test"),
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FOUND, "{uri}");
+        let location = resp.headers().get("location").unwrap().to_str().unwrap();
+        let url = openidconnect::url::Url::parse(location).unwrap();
+        assert_eq!(url.host_str(), Some(host));
+        assert!(url.query_pairs().any(|(k, _)| k == "state"));
+        assert!(url.query_pairs().any(|(k, _)| k == "nonce"));
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/auth/login?provider=microsoft")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 #[traced_test]
 async fn audit_auth_logout_and_session_revoked_emit_events() {
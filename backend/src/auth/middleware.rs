@@ -11,6 +11,7 @@ use crate::error::AppError;
 use crate::http_audit::AuditSessionId;
 use crate::resources::User;
 use crate::resources::user::Role as UserRole;
+use crate::resources::user::api_token::{ApiTokenServiceHandle, is_api_token};
 use crate::resources::user::session::service::SessionServiceHandle;
 use crate::settings::CookieConfig;
 use tracing::debug;
@@ -63,6 +64,7 @@ where
             .cloned()
             .ok_or_else(|| AppError::Internal("cookie config missing".into()))
             .map_err(Error::from);
+        let token_svc = req.app_data::<Data<ApiTokenServiceHandle>>().cloned();
         let service = Rc::clone(&self.service);
 
         Box::pin(async move {
@@ -75,7 +77,40 @@ where
                 Err(err) => return Err(err),
             };
 
-            let session_id = match authorization_bearer(&req).or_else(|| {
+            let bearer = authorization_bearer(&req);
+            if let Some(token) = bearer.as_deref().filter(|b| is_api_token(b)) {
+                let token_svc = token_svc
+                    .ok_or_else(|| AppError::Internal("api token service handle missing".into()))?;
+                let auth = match token_svc.validate_token(token).await {
+                    Ok(Some(auth)) => auth,
+                    Ok(None) => {
+                        debug!(
+                            reason = "invalid_api_token",
+                            "api token not found or expired"
+                        );
+                        return Err(AppError::unauthorized().into());
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                // Routing matches the percent-decoded path (`%74okens` reaches `tokens`), so the
+                // scopes must allow both the raw and the decoded form.
+                let decoded = req.match_info().get_ref().path();
+                if !auth.permits(req.method(), req.path()) || !auth.permits(req.method(), decoded) {
+                    debug!(
+                        reason = "api_token_scope",
+                        token_id = %auth.token_id,
+                        user_id = %auth.user.id,
+                        "forbidden: api token scope does not cover this request"
+                    );
+                    return Err(AppError::forbidden().into());
+                }
+                tracing::Span::current().record("user_id", tracing::field::display(&auth.user.id));
+                req.extensions_mut().insert(auth.user.clone());
+                req.extensions_mut().insert(auth);
+                return service.call(req).await;
+            }
+
+            let session_id = match bearer.or_else(|| {
                 req.cookie(&cookie_cfg.name)
                     .map(|cookie| cookie.value().to_owned())
             }) {
@@ -1,5 +1,6 @@
 pub mod middleware;
 pub mod otp;
+pub mod passkey;
 pub mod rest;
 
 pub mod oidc;
@@ -1,15 +1,18 @@
+use std::collections::HashSet;
 use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use anyhow::{Context, Result as AnyResult};
+use anyhow::{Context, Result as AnyResult, anyhow, bail};
+use futures_util::future::try_join_all;
 use openidconnect::core::{CoreClient, CoreProviderMetadata};
 use openidconnect::{ClientId, ClientSecret, IssuerUrl, RedirectUrl};
 use openidconnect::{EndpointMaybeSet, EndpointNotSet, EndpointSet};
 use reqwest::Client as ReqwestClient;
+use shared::auth::OidcProviderInfo;
 use tracing::info;
 
-use crate::settings::Settings;
+use crate::settings::{LEGACY_OIDC_PROVIDER_ID, OidcProviderSettings, Settings};
 
 /// [`CoreClient`] after discovery: auth endpoint set, token endpoint may be present from metadata.
 pub type DiscoveredOidcClient = CoreClient<
@@ -21,17 +24,19 @@ pub type DiscoveredOidcClient = CoreClient<
     EndpointMaybeSet,
 >;
 
-/// Supported OIDC identity provider (Google only in this deployment).
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub enum OidcProvider {
-    Google,
-}
+/// Id of a configured OIDC provider, as used in `/auth/login?provider=` and stored on
+/// `oidc_state` and `user_identity` rows.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct OidcProvider(String);
 
 impl OidcProvider {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Self::Google => "google",
-        }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Provider of the single-provider `OIDC_*` settings.
+    pub fn legacy() -> Self {
+        Self(LEGACY_OIDC_PROVIDER_ID.to_owned())
     }
 }
 
@@ -44,22 +49,37 @@ impl fmt::Display for OidcProvider {
 impl FromStr for OidcProvider {
     type Err = ();
 
+    /// Ids are lowercased; they must be 1–32 ASCII letters, digits, `-` or `_`.
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        match value.to_ascii_lowercase().as_str() {
-            "google" => Ok(Self::Google),
-            _ => Err(()),
-        }
+        let id = value.trim().to_ascii_lowercase();
+        let valid = !id.is_empty()
+            && id.len() <= 32
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        valid.then_some(Self(id)).ok_or(())
     }
 }
 
 #[derive(Debug)]
 pub struct OidcClientRegistration {
+    provider: OidcProvider,
+    name: String,
     client: Arc<DiscoveredOidcClient>,
     http: ReqwestClient,
     scopes: Vec<String>,
+    trusted_email: bool,
 }
 
 impl OidcClientRegistration {
+    pub fn provider(&self) -> &OidcProvider {
+        &self.provider
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn client(&self) -> &DiscoveredOidcClient {
         self.client.as_ref()
     }
@@ -71,59 +91,161 @@ impl OidcClientRegistration {
     pub fn scopes(&self) -> &[String] {
         &self.scopes
     }
+
+    /// Whether the provider vouches for the `email` claim, given its `email_verified` claim. A
+    /// missing claim counts only for providers configured with `trusted_email`.
+    pub fn vouches_for_email(&self, email_verified: Option<bool>) -> bool {
+        email_verified.unwrap_or(self.trusted_email)
+    }
 }
 
+/// Discovered clients in configuration order.
 #[derive(Debug)]
 pub struct OidcClients {
-    google: OidcClientRegistration,
+    registrations: Vec<OidcClientRegistration>,
 }
 
 impl OidcClients {
     pub fn get(&self, provider: &OidcProvider) -> Option<&OidcClientRegistration> {
+        self.registrations
+            .iter()
+            .find(|registration| &registration.provider == provider)
+    }
+
+    /// Resolves `?provider=`; without one, the first configured provider is used.
+    pub fn resolve(&self, provider: Option<&str>) -> Option<&OidcClientRegistration> {
         match provider {
-            OidcProvider::Google => Some(&self.google),
+            None => self.registrations.first(),
+            Some(id) => self.get(&id.parse().ok()?),
         }
     }
 
-    pub fn default_provider(&self) -> OidcProvider {
-        OidcProvider::Google
+    /// Ids and display names for login buttons, in configuration order.
+    pub fn provider_infos(&self) -> Vec<OidcProviderInfo> {
+        self.registrations
+            .iter()
+            .map(|registration| OidcProviderInfo {
+                id: registration.provider.to_string(),
+                name: registration.name.clone(),
+            })
+            .collect()
     }
 
-    /// Short names of OIDC providers with clients in this process (keep in sync with [`build_clients`]).
-    pub fn registered_provider_ids(&self) -> Vec<&'static str> {
-        vec![OidcProvider::Google.as_str()]
+    /// Short names of OIDC providers with clients in this process.
+    pub fn registered_provider_ids(&self) -> Vec<String> {
+        self.registrations
+            .iter()
+            .map(|registration| registration.provider.to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl OidcClients {
+    /// Clients for `(id, name)` pairs with made-up endpoints under `https://{id}.example.test`;
+    /// enough to build authorization URLs without network discovery.
+    pub fn for_tests(providers: &[(&str, &str)]) -> Self {
+        use openidconnect::core::{
+            CoreJwsSigningAlgorithm, CoreResponseType, CoreSubjectIdentifierType,
+        };
+        use openidconnect::{
+            AuthUrl, EmptyAdditionalProviderMetadata, JsonWebKeySetUrl, ResponseTypes, TokenUrl,
+        };
+
+        let registrations = providers
+            .iter()
+            .map(|&(id, name)| {
+                let base = format!("https://{id}.example.test");
+                let metadata = CoreProviderMetadata::new(
+                    IssuerUrl::new(base.clone()).unwrap(),
+                    AuthUrl::new(format!("{base}/authorize")).unwrap(),
+                    JsonWebKeySetUrl::new(format!("{base}/jwks")).unwrap(),
+                    vec![ResponseTypes::new(vec![CoreResponseType::Code])],
+                    vec![CoreSubjectIdentifierType::Public],
+                    vec![CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256],
+                    EmptyAdditionalProviderMetadata {},
+                )
+                .set_token_endpoint(Some(TokenUrl::new(format!("{base}/token")).unwrap()));
+                let client = CoreClient::from_provider_metadata(
+                    metadata,
+                    ClientId::new(format!("{id}-client")),
+                    None,
+                )
+                .set_redirect_uri(
+                    RedirectUrl::new("http://localhost:8080/auth/callback".into()).unwrap(),
+                );
+                OidcClientRegistration {
+                    provider: id.parse().expect("provider id"),
+                    name: name.to_owned(),
+                    client: Arc::new(client),
+                    http: ReqwestClient::new(),
+                    scopes: vec!["openid".into(), "email".into()],
+                    trusted_email: false,
+                }
+            })
+            .collect();
+        Self { registrations }
     }
 }
 
 pub async fn build_clients(settings: &Settings) -> AnyResult<OidcClients> {
-    let (google_client, google_http) = build_client(
-        OidcProvider::Google,
-        &settings.oidc_issuer_url,
-        &settings.oidc_client_id,
-        settings.oidc_client_secret.as_deref(),
-        &settings.oidc_redirect_url,
+    let mut seen = HashSet::new();
+    let mut providers = Vec::new();
+    for provider_settings in settings.oidc_provider_settings() {
+        let provider: OidcProvider = provider_settings
+            .id
+            .parse()
+            .map_err(|_| anyhow!("invalid OIDC provider id `{}`", provider_settings.id))?;
+        if !seen.insert(provider.clone()) {
+            bail!("OIDC provider `{provider}` is configured twice");
+        }
+        providers.push((provider, provider_settings));
+    }
+
+    let registrations = try_join_all(
+        providers
+            .into_iter()
+            .map(|(provider, settings)| build_registration(provider, settings)),
     )
     .await?;
+    Ok(OidcClients { registrations })
+}
+
+async fn build_registration(
+    provider: OidcProvider,
+    settings: OidcProviderSettings,
+) -> AnyResult<OidcClientRegistration> {
+    let redirect_url = settings.redirect_url.unwrap_or_default();
+    let (client, http) = build_client(
+        &provider,
+        &settings.issuer_url,
+        &settings.client_id,
+        settings.client_secret.as_deref(),
+        &redirect_url,
+    )
+    .await?;
+    let scopes = settings.scopes.unwrap_or_default();
 
     info!(
         event = "oidc.provider.registered",
-        provider = %OidcProvider::Google,
-        issuer = %settings.oidc_issuer_url,
-        scopes = ?settings.oidc_scopes.as_slice(),
+        provider = %provider,
+        issuer = %settings.issuer_url,
+        scopes = ?scopes.as_slice(),
         "registered OIDC provider"
     );
 
-    Ok(OidcClients {
-        google: OidcClientRegistration {
-            client: Arc::new(google_client),
-            http: google_http,
-            scopes: settings.oidc_scopes.clone(),
-        },
+    Ok(OidcClientRegistration {
+        name: settings.name.unwrap_or_else(|| provider.to_string()),
+        provider,
+        client: Arc::new(client),
+        http,
+        scopes,
+        trusted_email: settings.trusted_email,
     })
 }
 
 async fn build_client(
-    _provider: OidcProvider,
+    provider: &OidcProvider,
     issuer_url: &str,
     client_id: &str,
     client_secret: Option<&str>,
@@ -135,12 +257,12 @@ async fn build_client(
         .build()
         .context("build OIDC HTTP client")?;
     let issuer = IssuerUrl::new(issuer_url.to_string())
-        .with_context(|| "invalid GOOGLE_ISSUER_URL value")?;
+        .with_context(|| format!("invalid issuer URL for OIDC provider `{provider}`"))?;
     let metadata = CoreProviderMetadata::discover_async(issuer, &http)
         .await
-        .with_context(|| "unable to fetch Google provider metadata")?;
+        .with_context(|| format!("unable to fetch metadata of OIDC provider `{provider}`"))?;
     let redirect = RedirectUrl::new(redirect_url.to_string())
-        .with_context(|| "invalid GOOGLE_REDIRECT_URL value")?;
+        .with_context(|| format!("invalid redirect URL for OIDC provider `{provider}`"))?;
 
     Ok((
         CoreClient::from_provider_metadata(
@@ -152,3 +274,42 @@ async fn build_client(
         http,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{OidcClients, OidcProvider};
+
+    #[test]
+    fn provider_ids_are_normalized_and_validated() {
+        let provider: OidcProvider = " Keycloak ".parse().expect("valid id");
+        assert_eq!(provider.as_str(), "keycloak");
+        assert_eq!(OidcProvider::legacy().as_str(), "google");
+        for invalid in ["", "with space", "../x", &"x".repeat(33)] {
+            assert!(invalid.parse::<OidcProvider>().is_err(), "{invalid:?}");
+        }
+    }
+
+    /// BLC-AUTH-OIDC-006: an email only matches accounts when the provider vouches for it.
+    #[test]
+    fn blc_auth_oidc_006_email_needs_verified_claim() {
+        let clients = OidcClients::for_tests(&[("keycloak", "Church SSO")]);
+        let registration = clients.resolve(None).expect("registration");
+        assert!(registration.vouches_for_email(Some(true)));
+        assert!(!registration.vouches_for_email(Some(false)));
+        assert!(
+            !registration.vouches_for_email(None),
+            "absent email_verified must not count as verified"
+        );
+    }
+
+    /// BLC-AUTH-OIDC-006: `trusted_email` covers only an absent claim, never an explicit false.
+    #[test]
+    fn blc_auth_oidc_006_trusted_email_provider_accepts_absent_claim() {
+        let mut clients = OidcClients::for_tests(&[("keycloak", "Church SSO")]);
+        clients.registrations[0].trusted_email = true;
+        let registration = &clients.registrations[0];
+        assert!(registration.vouches_for_email(None));
+        assert!(registration.vouches_for_email(Some(true)));
+        assert!(!registration.vouches_for_email(Some(false)));
+    }
+}
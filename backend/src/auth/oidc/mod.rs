@@ -6,5 +6,7 @@ pub use client::{OidcClients, OidcProvider, build_clients};
 mod model;
 pub use model::{Model, PendingOidc};
 
+pub(crate) use rest::start_authorization;
+
 #[cfg(test)]
 mod schema_contract_tests;
@@ -6,7 +6,7 @@ use openidconnect::PkceCodeVerifier;
 use serde::{Deserialize, Serialize};
 use surrealdb::types::{Datetime, RecordId, SurrealValue};
 
-use crate::database::Database;
+use crate::database::{Database, record_id_string};
 use crate::error::AppError;
 
 use super::OidcProvider;
@@ -19,6 +19,8 @@ pub struct PendingOidc {
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
     pub provider: OidcProvider,
+    /// Set when a signed-in user links this provider account instead of logging in.
+    pub link_user: Option<String>,
 }
 
 pub trait Model {
@@ -88,6 +90,8 @@ struct OidcStateRecord {
     redirect_to: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    link_user: Option<RecordId>,
     created_at: Datetime,
     expires_at: Datetime,
 }
@@ -101,6 +105,7 @@ impl OidcStateRecord {
             created_at,
             expires_at,
             provider,
+            link_user,
         } = pending;
 
         Self {
@@ -109,6 +114,7 @@ impl OidcStateRecord {
             nonce: nonce.secret().to_owned(),
             redirect_to,
             provider: Some(provider.to_string()),
+            link_user: link_user.map(|id| RecordId::new("user", id)),
             created_at: created_at.into(),
             expires_at: expires_at.into(),
         }
@@ -125,7 +131,8 @@ impl OidcStateRecord {
                 .provider
                 .as_deref()
                 .and_then(|value| OidcProvider::from_str(value).ok())
-                .unwrap_or(OidcProvider::Google),
+                .unwrap_or_else(OidcProvider::legacy),
+            link_user: self.link_user.as_ref().map(record_id_string),
         }
     }
 }
@@ -11,48 +11,81 @@ use chrono::{Duration as ChronoDuration, Utc};
 use openidconnect::core::CoreAuthenticationFlow;
 use openidconnect::{AuthorizationCode, CsrfToken, Nonce, PkceCodeChallenge, Scope, TokenResponse};
 use serde::Deserialize;
+#[allow(unused_imports)]
+use shared::auth::OidcProviderInfo;
 use time::Duration as CookieDuration;
 use tracing::instrument;
 use utoipa::IntoParams;
 
-use super::{Model as OidcModel, OidcClients, OidcProvider, PendingOidc};
+use super::client::OidcClientRegistration;
+use super::{Model as OidcModel, OidcClients, PendingOidc};
 use crate::database::Database;
 #[allow(unused_imports)]
 use crate::docs::Problem;
 use crate::error::AppError;
 use crate::resources::Session;
 use crate::resources::blob::service::BlobServiceHandle;
+use crate::resources::user::identity::UserIdentityServiceHandle;
 use crate::resources::user::service::UserServiceHandle;
 use crate::resources::user::session::service::SessionServiceHandle;
 use crate::settings::{CookieConfig, ProfilePictureLimits};
 
+#[utoipa::path(
+    get,
+    path = "/auth/providers",
+    responses(
+        (status = 200, description = "OIDC providers accepted by `/auth/login?provider=`, in configuration order; the first one is the default", body = [OidcProviderInfo]),
+        (status = 429, description = "Rate limit exceeded; slow down and retry", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Auth"
+)]
+#[get("/providers")]
+async fn providers(oidc_clients: Data<Arc<OidcClients>>) -> HttpResponse {
+    HttpResponse::Ok().json(oidc_clients.provider_infos())
+}
+
 #[utoipa::path(
     get,
     path = "/auth/login",
     params(LoginQuery),
     responses(
         (status = 302, description = "Redirect to OIDC provider login page"),
-        (status = 400, description = "Invalid login request", body = Problem, content_type = "application/problem+json"),
+        (status = 400, description = "Invalid login request or unknown provider", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "Rate limit exceeded; slow down and retry", body = Problem, content_type = "application/problem+json"),
         (status = 500, description = "Failed to prepare login flow", body = Problem, content_type = "application/problem+json")
     ),
     tag = "Auth"
 )]
-#[instrument(level = "debug", err, skip_all, fields(provider = "google"))]
+#[instrument(level = "debug", err, skip_all, fields(provider = tracing::field::Empty))]
 #[get("/login")]
 async fn login(
     db: Data<Database>,
     oidc_clients: Data<Arc<OidcClients>>,
     query: web::Query<LoginQuery>,
 ) -> Result<HttpResponse, AppError> {
-    db.cleanup_expired_oidc_states().await?;
-    let redirect_hint = query.redirect_to.as_deref().and_then(sanitize_redirect);
-
-    let oidc_clients = oidc_clients.get_ref();
-    let provider = OidcProvider::Google;
     let registration = oidc_clients
-        .get(&provider)
+        .resolve(query.provider.as_deref())
         .ok_or_else(|| AppError::invalid_request("oauth provider not configured"))?;
+    tracing::Span::current().record("provider", registration.provider().as_str());
+    let url = start_authorization(&db, registration, query.redirect_to.as_deref(), None).await?;
+
+    Ok(HttpResponse::Found()
+        .append_header((header::LOCATION, url))
+        .finish())
+}
+
+/// Remembers PKCE verifier, nonce and target for a new authorization request and returns the
+/// provider URL to send the browser to. With `link_user`, the callback links the provider
+/// account to that user instead of logging in.
+pub(crate) async fn start_authorization(
+    db: &Database,
+    registration: &OidcClientRegistration,
+    redirect_to: Option<&str>,
+    link_user: Option<String>,
+) -> Result<String, AppError> {
+    db.cleanup_expired_oidc_states().await?;
+    let redirect_hint = redirect_to.and_then(sanitize_redirect);
+
     let oidc_client = registration.client();
     let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
 
@@ -77,14 +110,13 @@ async fn login(
             redirect_to: redirect_hint,
             created_at: now,
             expires_at,
-            provider,
+            provider: registration.provider().clone(),
+            link_user,
         },
     )
     .await?;
 
-    Ok(HttpResponse::Found()
-        .append_header((header::LOCATION, url.as_ref()))
-        .finish())
+    Ok(url.to_string())
 }
 
 #[utoipa::path(
@@ -92,19 +124,21 @@ async fn login(
     path = "/auth/callback",
     params(AuthCallbackQuery),
     responses(
-        (status = 302, description = "Successful callback exchange; redirects back to frontend"),
+        (status = 302, description = "Successful callback exchange; redirects back to frontend. Login flows set the session cookie; link flows (started with `POST /api/v1/users/me/identities`) only add the identity to the account."),
         (status = 400, description = "Invalid OIDC state", body = Problem, content_type = "application/problem+json"),
-        (status = 401, description = "OIDC user info missing required claims", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "OIDC user info missing required claims, or the email is not verified by the provider", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Link flow: this provider account is already linked to another user", body = Problem, content_type = "application/problem+json"),
         (status = 500, description = "OIDC provider or database error", body = Problem, content_type = "application/problem+json")
     ),
     tag = "Auth"
 )]
-#[instrument(level = "debug", err, skip_all, fields(provider = "google"))]
+#[instrument(level = "debug", err, skip_all, fields(provider = tracing::field::Empty))]
 #[get("/callback")]
 #[allow(clippy::too_many_arguments)] // Actix injects one `Data<_>` per dependency.
 async fn callback(
     db: Data<Database>,
     user_svc: Data<UserServiceHandle>,
+    identity_svc: Data<UserIdentityServiceHandle>,
     session_svc: Data<SessionServiceHandle>,
     blob_svc: Data<BlobServiceHandle>,
     pic_limits: Data<ProfilePictureLimits>,
@@ -118,7 +152,7 @@ async fn callback(
         None => {
             crate::audit!(
                 "audit.auth.login.failure",
-                provider = tracing::field::display(&"google"),
+                provider = tracing::field::display(&"oidc"),
                 reason = tracing::field::display(&"invalid_oidc_state")
                 ; "oidc login failed"
             );
@@ -133,7 +167,9 @@ async fn callback(
         created_at: _,
         expires_at: _,
         provider,
+        link_user,
     } = pending;
+    tracing::Span::current().record("provider", provider.as_str());
 
     let oidc_clients = oidc_clients.get_ref();
     let registration = match oidc_clients.get(&provider) {
@@ -141,7 +177,7 @@ async fn callback(
         None => {
             crate::audit!(
                 "audit.auth.login.failure",
-                provider = tracing::field::display(&"google"),
+                provider = tracing::field::display(&provider),
                 reason = tracing::field::display(&"provider_not_configured")
                 ; "oidc login failed"
             );
@@ -159,7 +195,7 @@ async fn callback(
     let token_response = token_request.request_async(http).await.map_err(|e| {
         crate::audit!(
             "audit.auth.login.failure",
-            provider = tracing::field::display(&"google"),
+            provider = tracing::field::display(&provider),
             reason = tracing::field::display(&"token_exchange_failed")
             ; "oidc login failed"
         );
@@ -171,7 +207,7 @@ async fn callback(
         None => {
             crate::audit!(
                 "audit.auth.login.failure",
-                provider = tracing::field::display(&"google"),
+                provider = tracing::field::display(&provider),
                 reason = tracing::field::display(&"missing_id_token")
                 ; "oidc login failed"
             );
@@ -186,21 +222,77 @@ async fn callback(
         .map_err(|e| {
             crate::audit!(
                 "audit.auth.login.failure",
-                provider = tracing::field::display(&"google"),
+                provider = tracing::field::display(&provider),
                 reason = tracing::field::display(&"id_token_invalid")
                 ; "oidc login failed"
             );
             crate::log_and_convert!(AppError::oidc, "oidc.id_token_claims", e)
         })?;
 
-    let Some(email_addr) = claims.email() else {
-        crate::audit!(
-            "audit.auth.login.failure",
-            provider = tracing::field::display(&"google"),
-            reason = tracing::field::display(&"missing_email_claim")
-            ; "oidc login failed"
-        );
-        return Err(AppError::Unauthorized);
+    let subject = claims.subject().as_str();
+    let claimed_email = claims.email().map(|email| email.as_str());
+    let redirect_target =
+        resolve_frontend_redirect(&cookie_cfg.post_login_path, redirect_to.as_deref());
+
+    if let Some(link_user) = link_user {
+        let user = user_svc.get_user(&link_user).await?;
+        identity_svc
+            .link_identity(&user, provider.as_str(), subject, claimed_email)
+            .await?;
+        return Ok(HttpResponse::Found()
+            .append_header((header::LOCATION, redirect_target))
+            .finish());
+    }
+
+    let user = match identity_svc
+        .user_for_identity(provider.as_str(), subject, claimed_email)
+        .await?
+    {
+        Some(user) => user,
+        None => {
+            let Some(email_addr) = claimed_email else {
+                crate::audit!(
+                    "audit.auth.login.failure",
+                    provider = tracing::field::display(&provider),
+                    reason = tracing::field::display(&"missing_email_claim")
+                    ; "oidc login failed"
+                );
+                return Err(AppError::Unauthorized);
+            };
+            // An unlinked subject is matched to an account by email, so the provider must
+            // vouch for the address; a missing `email_verified` claim does not.
+            if !registration.vouches_for_email(claims.email_verified()) {
+                crate::audit!(
+                    "audit.auth.login.failure",
+                    provider = tracing::field::display(&provider),
+                    reason = tracing::field::display(&"email_not_verified"),
+                    email_hash = tracing::field::display(
+                        &crate::observability::audit_email_hash(email_addr)
+                    )
+                    ; "oidc login failed"
+                );
+                return Err(AppError::Unauthorized);
+            }
+            let user = match user_svc.get_user_by_email_or_create(email_addr).await {
+                Ok(u) => u,
+                Err(e) => {
+                    crate::audit!(
+                        "audit.auth.login.failure",
+                        provider = tracing::field::display(&provider),
+                        reason = tracing::field::display(&"user_provision_failed"),
+                        email_hash = tracing::field::display(
+                            &crate::observability::audit_email_hash(email_addr)
+                        )
+                        ; "oidc login failed"
+                    );
+                    return Err(e);
+                }
+            };
+            identity_svc
+                .link_identity(&user, provider.as_str(), subject, claimed_email)
+                .await?;
+            user
+        }
     };
 
     let picture_url = claims
@@ -208,25 +300,6 @@ async fn callback(
         .and_then(|p| p.get(None))
         .map(|u| u.to_string());
 
-    let user = match user_svc
-        .get_user_by_email_or_create(email_addr.as_str())
-        .await
-    {
-        Ok(u) => u,
-        Err(e) => {
-            crate::audit!(
-                "audit.auth.login.failure",
-                provider = tracing::field::display(&"google"),
-                reason = tracing::field::display(&"user_provision_failed"),
-                email_hash = tracing::field::display(
-                    &crate::observability::audit_email_hash(email_addr.as_str())
-                )
-                ; "oidc login failed"
-            );
-            return Err(e);
-        }
-    };
-
     let _ = user_svc
         .cache_oauth_profile_picture_if_needed(
             blob_svc.get_ref(),
@@ -241,11 +314,12 @@ async fn callback(
         Err(e) => {
             crate::audit!(
                 "audit.auth.login.failure",
-                provider = tracing::field::display(&"google"),
+                provider = tracing::field::display(&provider),
                 reason = tracing::field::display(&"user_reload_failed"),
                 email_hash = tracing::field::display(
-                    &crate::observability::audit_email_hash(email_addr.as_str())
-                )
+                    &crate::observability::audit_email_hash(&user.email)
+                ),
+                user_id = tracing::field::display(&user.id)
                 ; "oidc login failed"
             );
             return Err(e);
@@ -263,11 +337,12 @@ async fn callback(
         Err(e) => {
             crate::audit!(
                 "audit.auth.login.failure",
-                provider = tracing::field::display(&"google"),
+                provider = tracing::field::display(&provider),
                 reason = tracing::field::display(&"session_create_failed"),
                 email_hash = tracing::field::display(
-                    &crate::observability::audit_email_hash(email_addr.as_str())
-                )
+                    &crate::observability::audit_email_hash(&user.email)
+                ),
+                user_id = tracing::field::display(&user.id)
                 ; "oidc login failed"
             );
             return Err(e);
@@ -276,15 +351,12 @@ async fn callback(
 
     crate::audit!(
         "audit.auth.login.success",
-        provider = tracing::field::display(&"google"),
+        provider = tracing::field::display(&provider),
         user_id = tracing::field::display(&user.id),
         session_id = tracing::field::display(&session.id)
         ; "login succeeded"
     );
 
-    let redirect_target =
-        resolve_frontend_redirect(&cookie_cfg.post_login_path, redirect_to.as_deref());
-
     Ok(HttpResponse::Found()
         .append_header((header::LOCATION, redirect_target))
         .cookie(session_cookie(&session.id, &cookie_cfg))
@@ -321,6 +393,10 @@ fn sanitize_redirect(path: &str) -> Option<String> {
 #[derive(Debug, Deserialize, IntoParams)]
 #[into_params(parameter_in = Query)]
 struct LoginQuery {
+    /// Provider id from `GET /auth/providers`. Defaults to the first configured provider.
+    #[serde(default)]
+    #[param(required = false)]
+    provider: Option<String>,
     /// Optional same-origin path (`/…`) to use after login. Must start with `/` and must not be `//…` or `/http…`; otherwise it is ignored and the default post-login path is used (see `sanitize_redirect`).
     #[serde(default)]
     #[param(required = false)]
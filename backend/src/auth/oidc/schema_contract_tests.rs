@@ -4,8 +4,8 @@ use chrono::{Duration as ChronoDuration, Utc};
 use openidconnect::Nonce;
 use openidconnect::PkceCodeChallenge;
 
-use super::{Model as OidcModel, OidcProvider, PendingOidc};
-use crate::test_helpers::test_db;
+use super::{Model as OidcModel, PendingOidc};
+use crate::test_helpers::{create_user, test_db};
 
 #[tokio::test]
 async fn oidc_state_remember_and_take_round_trip() {
@@ -19,7 +19,8 @@ async fn oidc_state_remember_and_take_round_trip() {
         redirect_to: Some("/after-login".into()),
         created_at: now,
         expires_at: now + ChronoDuration::seconds(600),
-        provider: OidcProvider::Google,
+        provider: "keycloak".parse().expect("provider id"),
+        link_user: None,
     };
     let key = "contract-test-csrf";
 
@@ -34,6 +35,36 @@ async fn oidc_state_remember_and_take_round_trip() {
         panic!("expected stored oidc_state");
     };
 
-    assert_eq!(out.provider, OidcProvider::Google);
+    assert_eq!(out.provider.as_str(), "keycloak");
+    assert!(out.link_user.is_none());
     assert_eq!(out.redirect_to.as_deref(), Some("/after-login"));
 }
+
+#[tokio::test]
+async fn oidc_state_keeps_link_user() {
+    let db = test_db().await.expect("test db");
+    let user = create_user(&db, "oidc-link-state@test.local")
+        .await
+        .expect("user");
+
+    let (_, verifier) = PkceCodeChallenge::new_random_sha256();
+    let now = Utc::now();
+    let pending = PendingOidc {
+        pkce_verifier: verifier,
+        nonce: Nonce::new_random(),
+        redirect_to: None,
+        created_at: now,
+        expires_at: now + ChronoDuration::seconds(600),
+        provider: "google".parse().expect("provider id"),
+        link_user: Some(user.id.clone()),
+    };
+
+    OidcModel::remember_oidc_state(db.as_ref(), "contract-test-link", pending)
+        .await
+        .expect("remember_oidc_state with link_user");
+    let out = OidcModel::take_oidc_state(db.as_ref(), "contract-test-link")
+        .await
+        .expect("take_oidc_state")
+        .expect("stored oidc_state");
+    assert_eq!(out.link_user.as_deref(), Some(user.id.as_str()));
+}
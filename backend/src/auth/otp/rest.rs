@@ -117,15 +117,29 @@ pub(crate) async fn otp_verify(
                 AppError::InvalidRequest(_) => "otp_invalid",
                 _ => "otp_verify_failed",
             };
-            crate::audit!(
-                "audit.auth.login.failure",
-                provider = tracing::field::display(&"otp"),
-                reason = tracing::field::display(&reason),
-                email_hash = tracing::field::display(&crate::observability::audit_email_hash(
-                    &email
-                ))
-                ; "otp verify failed"
-            );
+            let email_hash = crate::observability::audit_email_hash(&email);
+            // Failed codes for an existing account show up in that user's security events.
+            match user_svc.get_user_by_email(&email).await.ok().flatten() {
+                Some(known) => {
+                    crate::audit!(
+                        "audit.auth.login.failure",
+                        provider = tracing::field::display(&"otp"),
+                        reason = tracing::field::display(&reason),
+                        email_hash = tracing::field::display(&email_hash),
+                        user_id = tracing::field::display(&known.id)
+                        ; "otp verify failed"
+                    );
+                }
+                None => {
+                    crate::audit!(
+                        "audit.auth.login.failure",
+                        provider = tracing::field::display(&"otp"),
+                        reason = tracing::field::display(&reason),
+                        email_hash = tracing::field::display(&email_hash)
+                        ; "otp verify failed"
+                    );
+                }
+            }
             return Err(e);
         }
     }
@@ -164,7 +178,8 @@ pub(crate) async fn otp_verify(
                 reason = tracing::field::display(&"session_create_failed"),
                 email_hash = tracing::field::display(&crate::observability::audit_email_hash(
                     &email
-                ))
+                )),
+                user_id = tracing::field::display(&user.id)
                 ; "otp verify failed"
             );
             return Err(e);
@@ -0,0 +1,122 @@
+use actix_web::{
+    HttpResponse,
+    cookie::{Cookie, SameSite},
+    post,
+    web::{self, Data},
+};
+use shared::auth::passkey::AuthenticationCredential;
+#[allow(unused_imports)]
+use shared::auth::passkey::PasskeyRequestOptions;
+use shared::user::{Session, SessionBody};
+use time::Duration as CookieDuration;
+use tracing::instrument;
+
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::resources::user::passkey::PasskeyServiceHandle;
+use crate::resources::user::session::service::SessionServiceHandle;
+use crate::settings::CookieConfig;
+
+#[utoipa::path(
+    post,
+    path = "/auth/passkey/options",
+    responses(
+        (status = 200, description = "Request options for `navigator.credentials.get({ publicKey })`. `allow_credentials` is empty so the authenticator offers its discoverable passkeys; the challenge is single-use and expires after five minutes.", body = PasskeyRequestOptions),
+        (status = 429, description = "Rate limit exceeded; slow down and retry", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to issue a challenge", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Auth"
+)]
+#[instrument(level = "debug", err, skip_all, fields(provider = "passkey"))]
+#[post("/passkey/options")]
+pub(crate) async fn passkey_options(
+    svc: Data<PasskeyServiceHandle>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.login_options().await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/passkey/verify",
+    request_body = AuthenticationCredential,
+    responses(
+        (status = 200, description = "Assertion verified; session cookie issued exactly as for OTP login. Response always embeds full `User` under `user`.", body = SessionBody),
+        (status = 400, description = "Malformed credential", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Unknown passkey, spent or expired challenge, wrong origin or relying party, bad signature, or a signature counter that did not increase", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; slow down and retry", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to create session", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Auth"
+)]
+#[instrument(level = "debug", err, skip_all, fields(provider = "passkey"))]
+#[post("/passkey/verify")]
+pub(crate) async fn passkey_verify(
+    svc: Data<PasskeyServiceHandle>,
+    session_svc: Data<SessionServiceHandle>,
+    cookie_cfg: Data<CookieConfig>,
+    payload: web::Json<AuthenticationCredential>,
+) -> Result<HttpResponse, AppError> {
+    let user = match svc.authenticate(payload.into_inner()).await {
+        Ok(user) => user,
+        Err(e) => {
+            let reason = match &e {
+                AppError::Unauthorized => "passkey_rejected",
+                AppError::InvalidRequest(_) => "passkey_malformed",
+                _ => "passkey_verify_failed",
+            };
+            crate::audit!(
+                "audit.auth.login.failure",
+                provider = tracing::field::display(&"passkey"),
+                reason = tracing::field::display(&reason)
+                ; "passkey verify failed"
+            );
+            return Err(e);
+        }
+    };
+
+    let session = match session_svc
+        .create_session(Session::new(
+            user.clone(),
+            cookie_cfg.session_ttl_seconds as i64,
+        ))
+        .await
+    {
+        Ok(s) => s,
+        Err(e) => {
+            crate::audit!(
+                "audit.auth.login.failure",
+                provider = tracing::field::display(&"passkey"),
+                reason = tracing::field::display(&"session_create_failed")
+                ; "passkey verify failed"
+            );
+            return Err(e);
+        }
+    };
+
+    crate::audit!(
+        "audit.auth.login.success",
+        provider = tracing::field::display(&"passkey"),
+        user_id = tracing::field::display(&user.id),
+        session_id = tracing::field::display(&session.id)
+        ; "login succeeded"
+    );
+
+    Ok(HttpResponse::Ok()
+        .cookie(session_cookie(&session.id, &cookie_cfg))
+        .json(SessionBody::from_session(session, true)))
+}
+
+fn session_cookie(session_id: &str, cfg: &CookieConfig) -> Cookie<'static> {
+    let mut builder = Cookie::build(cfg.name.clone(), session_id.to_owned())
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .path("/")
+        .secure(cfg.secure);
+
+    if cfg.session_ttl_seconds > 0 {
+        builder = builder.max_age(CookieDuration::seconds(cfg.session_ttl_seconds as i64));
+    }
+
+    builder.finish()
+}
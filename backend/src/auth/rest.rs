@@ -12,7 +12,7 @@ use time::Duration as CookieDuration;
 use tracing::warn;
 
 pub use super::authorization_bearer;
-use super::{oidc, otp};
+use super::{oidc, otp, passkey};
 use crate::resources::user::session::service::SessionServiceHandle;
 use crate::settings::CookieConfig;
 
@@ -31,9 +31,12 @@ pub fn scope(auth_rate_limit_rps: u64, auth_rate_limit_burst: u32) -> actix_web:
             web::scope("")
                 .wrap(Governor::new(&governor_conf))
                 .wrap(AuditRateLimit429)
+                .service(oidc::rest::providers)
                 .service(oidc::rest::login)
                 .service(otp::rest::otp_request)
                 .service(otp::rest::otp_verify)
+                .service(passkey::rest::passkey_options)
+                .service(passkey::rest::passkey_verify)
                 .service(logout),
         )
 }
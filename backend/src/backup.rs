@@ -0,0 +1,730 @@
+//! `backend backup`, `backend verify-backup` and `backend restore`: the whole database plus blob
+//! content in one versioned zip archive.
+//!
+//! Layout: `manifest.json` (written last), `db/<table>/<chunk>.surql` holding one SurrealQL array
+//! literal of rows each, and `blobs/<sha256>` per stored content. The manifest records the
+//! applied migrations and the SHA-256 of every other entry, so an archive can be checked without
+//! a database.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result as AnyResult, anyhow, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{ToSql, Value};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::database::Database;
+use crate::resources::blob::storage::{BlobStorage, content_sha256};
+use crate::resources::blob::{BlobRepository, SurrealBlobRepo};
+
+/// Bumped when the archive layout changes; restore refuses archives newer than it understands.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const ROWS_PER_CHUNK: u32 = 500;
+/// Recreated by the migrations a restore runs before loading rows.
+const SKIPPED_TABLES: &[&str] = &["migration_script"];
+
+/// Contents of `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    /// `CARGO_PKG_VERSION` of the backend that wrote the archive.
+    pub backend_version: String,
+    /// Applied migration scripts and their checksums at backup time.
+    pub migrations: BTreeMap<String, String>,
+    pub tables: Vec<TableDump>,
+    /// Content hashes stored under `blobs/`.
+    pub blobs: Vec<String>,
+    /// SHA-256 of every archive entry except the manifest.
+    pub entries: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDump {
+    pub name: String,
+    pub rows: u64,
+    /// Archive entries holding the rows, in order.
+    pub chunks: Vec<String>,
+}
+
+impl BackupManifest {
+    /// Last applied migration; restore migrates up to exactly this script.
+    pub fn schema_version(&self) -> Option<&str> {
+        self.migrations.keys().next_back().map(String::as_str)
+    }
+
+    pub fn total_rows(&self) -> u64 {
+        self.tables.iter().map(|t| t.rows).sum()
+    }
+}
+
+fn blob_entry(hash: &str) -> String {
+    format!("blobs/{hash}")
+}
+
+/// Table names come from the database or an archive; only plain identifiers are interpolated
+/// into queries.
+fn is_table_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+}
+
+fn is_content_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+struct ArchiveWriter {
+    zip: ZipWriter<File>,
+    entries: BTreeMap<String, String>,
+}
+
+impl ArchiveWriter {
+    fn create(path: &Path) -> AnyResult<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create backup file '{}'", path.display()))?;
+        Ok(Self {
+            zip: ZipWriter::new(file),
+            entries: BTreeMap::new(),
+        })
+    }
+
+    fn add(&mut self, name: &str, data: &[u8]) -> AnyResult<()> {
+        self.write(name, data)?;
+        self.entries.insert(name.to_owned(), content_sha256(data));
+        Ok(())
+    }
+
+    fn write(&mut self, name: &str, data: &[u8]) -> AnyResult<()> {
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(data.len() as u64 >= u64::from(u32::MAX));
+        self.zip
+            .start_file(name, options)
+            .with_context(|| format!("failed to start archive entry '{name}'"))?;
+        self.zip
+            .write_all(data)
+            .with_context(|| format!("failed to write archive entry '{name}'"))
+    }
+
+    fn finish(mut self, mut manifest: BackupManifest) -> AnyResult<BackupManifest> {
+        manifest.entries = std::mem::take(&mut self.entries);
+        let json = serde_json::to_vec_pretty(&manifest).context("failed to encode manifest")?;
+        self.write(MANIFEST, &json)?;
+        self.zip
+            .finish()
+            .context("failed to finish backup archive")?;
+        Ok(manifest)
+    }
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> AnyResult<Vec<u8>> {
+    let mut entry = archive
+        .by_name(name)
+        .with_context(|| format!("backup archive has no entry '{name}'"))?;
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry
+        .read_to_end(&mut data)
+        .with_context(|| format!("failed to read archive entry '{name}'"))?;
+    Ok(data)
+}
+
+async fn list_tables(db: &Database) -> AnyResult<Vec<String>> {
+    let mut response = db
+        .query("RETURN object::keys((INFO FOR DB).tables)")
+        .await
+        .context("failed to list tables")?;
+    let mut tables: Vec<String> = response.take(0).context("failed to decode table list")?;
+    tables.sort();
+    Ok(tables)
+}
+
+/// Writes a snapshot of `db` and the blob content its rows reference to `path`, then verifies the
+/// finished archive. Rows and the list of content to archive are read inside one transaction, so
+/// they reflect a single point in time; the archive is built under `<path>.partial` and only
+/// renamed once complete. Blobs whose files predate content addressing must be adopted first
+/// (see `BlobService::adopt_legacy_files`).
+pub async fn create_backup(
+    db: &Database,
+    storage: &dyn BlobStorage,
+    path: &Path,
+) -> AnyResult<BackupManifest> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let migrations = db.applied_migrations().await?;
+    if migrations.is_empty() {
+        bail!("database has no applied migrations; there is nothing a restore could rebuild");
+    }
+    let mut archive = ArchiveWriter::create(&partial)?;
+
+    let snapshot = Arc::new(db.begin().await?);
+    let mut tables = Vec::new();
+    for name in list_tables(&snapshot).await? {
+        if SKIPPED_TABLES.contains(&name.as_str()) {
+            continue;
+        }
+        if !is_table_name(&name) {
+            bail!("refusing to back up table with unexpected name '{name}'");
+        }
+        tables.push(dump_table(&snapshot, &mut archive, name).await?);
+    }
+    let content = snapshot_content(SurrealBlobRepo::new(snapshot.clone()), storage).await;
+    if let Some(snapshot) = Arc::into_inner(snapshot) {
+        snapshot.cancel().await?;
+    }
+
+    let mut blobs = Vec::new();
+    for hash in content? {
+        let data = storage
+            .read_content(&hash)
+            .await?
+            .ok_or_else(|| anyhow!("blob content {hash} referenced by a blob is not stored"))?;
+        if content_sha256(&data) != hash {
+            bail!("stored blob content {hash} does not match its checksum");
+        }
+        archive.add(&blob_entry(&hash), &data)?;
+        blobs.push(hash);
+    }
+
+    archive.finish(BackupManifest {
+        format_version: FORMAT_VERSION,
+        created_at: Utc::now(),
+        backend_version: env!("CARGO_PKG_VERSION").to_owned(),
+        migrations,
+        tables,
+        blobs,
+        entries: BTreeMap::new(),
+    })?;
+    std::fs::rename(&partial, path).with_context(|| {
+        format!(
+            "failed to move '{}' to '{}'",
+            partial.display(),
+            path.display()
+        )
+    })?;
+    verify_backup(path)
+}
+
+/// Content hashes the snapshot's blobs reference. Fails when a blob still has a file from before
+/// content addressing, since that file would be missing from the archive.
+async fn snapshot_content(
+    repo: SurrealBlobRepo,
+    storage: &dyn BlobStorage,
+) -> AnyResult<BTreeSet<String>> {
+    let mut unadopted = Vec::new();
+    for blob in repo.blobs_without_content().await? {
+        if storage
+            .read_legacy_file(&blob)
+            .await?
+            .is_some_and(|data| !data.is_empty())
+        {
+            unadopted.push(blob.id);
+        }
+    }
+    if !unadopted.is_empty() {
+        bail!(
+            "{} blob(s) still have files from before content addressing ({}); let the server adopt them, then back up again",
+            unadopted.len(),
+            unadopted.join(", ")
+        );
+    }
+    Ok(repo
+        .content_hashes()
+        .await?
+        .into_iter()
+        .map(|(_, hash)| hash)
+        .collect())
+}
+
+async fn dump_table(
+    db: &Database,
+    archive: &mut ArchiveWriter,
+    name: String,
+) -> AnyResult<TableDump> {
+    let mut dump = TableDump {
+        name,
+        rows: 0,
+        chunks: Vec::new(),
+    };
+    loop {
+        let mut response = db
+            .query(format!(
+                "SELECT * FROM {} ORDER BY id LIMIT $limit START $start",
+                dump.name
+            ))
+            .bind(("limit", ROWS_PER_CHUNK))
+            .bind(("start", dump.rows))
+            .await
+            .with_context(|| format!("failed to read table '{}'", dump.name))?;
+        let rows: Vec<Value> = response
+            .take(0)
+            .with_context(|| format!("failed to decode rows of '{}'", dump.name))?;
+        if rows.is_empty() {
+            return Ok(dump);
+        }
+        let count = rows.len();
+        let entry = format!("db/{}/{:06}.surql", dump.name, dump.chunks.len());
+        archive.add(&entry, Value::Array(rows.into()).to_sql().as_bytes())?;
+        dump.chunks.push(entry);
+        dump.rows += count as u64;
+        if count < ROWS_PER_CHUNK as usize {
+            return Ok(dump);
+        }
+    }
+}
+
+fn open_archive(path: &Path) -> AnyResult<(ZipArchive<File>, BackupManifest)> {
+    let file = File::open(path)
+        .with_context(|| format!("failed to open backup file '{}'", path.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("'{}' is not a backup archive", path.display()))?;
+    let manifest: BackupManifest = serde_json::from_slice(&read_entry(&mut archive, MANIFEST)?)
+        .context("backup manifest is malformed")?;
+    if manifest.format_version > FORMAT_VERSION {
+        bail!(
+            "backup format version {} is newer than the supported version {FORMAT_VERSION}",
+            manifest.format_version
+        );
+    }
+    Ok((archive, manifest))
+}
+
+/// Checks that every entry the manifest lists is present with its recorded checksum and that
+/// every blob entry holds the content its hash names.
+pub fn verify_backup(path: &Path) -> AnyResult<BackupManifest> {
+    let (mut archive, manifest) = open_archive(path)?;
+    let listed = manifest
+        .tables
+        .iter()
+        .flat_map(|t| t.chunks.iter().cloned())
+        .chain(manifest.blobs.iter().map(|hash| blob_entry(hash)));
+    for name in listed {
+        if !manifest.entries.contains_key(&name) {
+            bail!("backup manifest has no checksum for '{name}'");
+        }
+    }
+    for hash in &manifest.blobs {
+        if !is_content_hash(hash) {
+            bail!("backup lists invalid blob hash '{hash}'");
+        }
+        if manifest.entries.get(&blob_entry(hash)) != Some(hash) {
+            bail!("blob entry {hash} is recorded with a different checksum");
+        }
+    }
+    for (name, expected) in &manifest.entries {
+        let actual = content_sha256(&read_entry(&mut archive, name)?);
+        if &actual != expected {
+            bail!("archive entry '{name}' is corrupt: expected sha256 {expected}, got {actual}");
+        }
+    }
+    Ok(manifest)
+}
+
+/// Restores the archive at `path` into `db`, which must have no tables yet: verifies the archive,
+/// applies the migrations in `migration_path` up to the version the backup was taken at, loads
+/// every row, and writes the blob content to `storage`.
+///
+/// Rows are inserted in import mode, so `VALUE` clauses such as `updated_at` and table events do
+/// not rewrite them. Import mode is not available inside an interactive transaction, so a failure
+/// is undone instead: every table the restore created is removed again, leaving the database
+/// empty for a retry. Resized image variants are not archived; they are rendered again on first
+/// request.
+pub async fn restore_backup(
+    db: &Database,
+    storage: &dyn BlobStorage,
+    migration_path: &str,
+    path: &Path,
+) -> AnyResult<BackupManifest> {
+    let manifest = verify_backup(path)?;
+    let (mut archive, _) = open_archive(path)?;
+
+    let existing = list_tables(db).await?;
+    if !existing.is_empty() {
+        bail!(
+            "refusing to restore into a database that already has {} table(s) ({}); restore into an empty database",
+            existing.len(),
+            existing.join(", ")
+        );
+    }
+    if let Err(err) = restore_into_empty(db, storage, migration_path, &manifest, &mut archive).await
+    {
+        return Err(match remove_tables(db).await {
+            Ok(()) => err,
+            Err(cleanup) => err.context(format!(
+                "the partially restored database could not be emptied ({cleanup:#}); drop its tables before retrying"
+            )),
+        });
+    }
+    Ok(manifest)
+}
+
+async fn restore_into_empty(
+    db: &Database,
+    storage: &dyn BlobStorage,
+    migration_path: &str,
+    manifest: &BackupManifest,
+    archive: &mut ZipArchive<File>,
+) -> AnyResult<()> {
+    let version = manifest
+        .schema_version()
+        .ok_or_else(|| anyhow!("backup records no applied migrations"))?;
+    db.migrate_until(migration_path, version)
+        .await
+        .with_context(|| format!("failed to migrate to backup version '{version}'"))?;
+    let applied = db.applied_migrations().await?;
+    if applied != manifest.migrations {
+        let differing: Vec<&str> = manifest
+            .migrations
+            .iter()
+            .filter(|(name, checksum)| applied.get(*name) != Some(*checksum))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        bail!(
+            "migrations in '{migration_path}' do not match the backup: {}",
+            differing.join(", ")
+        );
+    }
+
+    for table in &manifest.tables {
+        if !is_table_name(&table.name) {
+            bail!("backup lists table with unexpected name '{}'", table.name);
+        }
+        for chunk in &table.chunks {
+            let rows = String::from_utf8(read_entry(archive, chunk)?)
+                .with_context(|| format!("archive entry '{chunk}' is not UTF-8"))?;
+            db.query(format!("OPTION IMPORT; INSERT INTO {} {rows};", table.name))
+                .await
+                .and_then(|r| r.check())
+                .with_context(|| format!("failed to restore '{chunk}'"))?;
+        }
+    }
+    db.query(
+        "OPTION IMPORT; UPDATE blob SET variants = [] WHERE variants != NONE AND variants != [];",
+    )
+    .await
+    .and_then(|r| r.check())
+    .context("failed to reset blob variants")?;
+
+    for hash in &manifest.blobs {
+        let data = read_entry(archive, &blob_entry(hash))?;
+        storage.write_content(hash, &data).await?;
+    }
+    Ok(())
+}
+
+/// Removes every table, undoing a failed restore. Functions and analyzers the migrations defined
+/// stay; migrations redefine them with `OVERWRITE`.
+async fn remove_tables(db: &Database) -> AnyResult<()> {
+    for name in list_tables(db).await? {
+        if !is_table_name(&name) {
+            bail!("refusing to remove table with unexpected name '{name}'");
+        }
+        db.query(format!("REMOVE TABLE {name};"))
+            .await
+            .and_then(|r| r.check())
+            .with_context(|| format!("failed to remove table '{name}'"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use shared::blob::{CreateBlob, FileType};
+    use surrealdb::types::{RecordId, ToSql, Value};
+
+    use crate::database::Database;
+    use crate::resources::blob::storage::{BlobStorage, content_sha256};
+    use crate::resources::blob::{BlobRepository, FsBlobStorage, SurrealBlobRepo};
+    use crate::test_helpers::{create_user, personal_team_id, test_db};
+
+    use super::{
+        BackupManifest, MANIFEST, create_backup, list_tables, restore_backup, verify_backup,
+    };
+
+    const MIGRATIONS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/db-migrations");
+
+    async fn dump(db: &Database, table: &str) -> String {
+        let rows: Vec<Value> = db
+            .query(format!("SELECT * FROM {table} ORDER BY id"))
+            .await
+            .expect("select rows")
+            .take(0)
+            .expect("decode rows");
+        Value::Array(rows.into()).to_sql()
+    }
+
+    async fn empty_db() -> Arc<Database> {
+        Arc::new(
+            Database::connect("mem://", "restore", "restore", None, None)
+                .await
+                .expect("connect empty database"),
+        )
+    }
+
+    /// A blob record in `team`; with `content`, that content is stored and referenced.
+    async fn blob_with_content(
+        db: &Arc<Database>,
+        storage: &FsBlobStorage,
+        team: &str,
+        content: Option<&[u8]>,
+    ) -> shared::blob::Blob {
+        let repo = SurrealBlobRepo::new(db.clone());
+        let blob = repo
+            .create_blob(
+                RecordId::new("team", team.to_owned()),
+                CreateBlob {
+                    owner: None,
+                    file_type: FileType::PNG,
+                    width: 1,
+                    height: 1,
+                    ocr: String::new(),
+                },
+            )
+            .await
+            .expect("create blob");
+        if let Some(content) = content {
+            let hash = content_sha256(content);
+            storage
+                .write_content(&hash, content)
+                .await
+                .expect("write content");
+            repo.set_blob_content(&blob.id, &hash)
+                .await
+                .expect("set content");
+        }
+        blob
+    }
+
+    /// Copies the archive at `from` to `to` with entry `name` replaced by `data`, keeping the
+    /// manifest checksums consistent so the archive still verifies.
+    fn rewrite_entry(from: &Path, to: &Path, name: &str, data: &[u8]) {
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(from).expect("open archive"))
+            .expect("read archive");
+        let mut manifest: BackupManifest =
+            serde_json::from_reader(zip.by_name(MANIFEST).expect("manifest")).expect("decode");
+        manifest
+            .entries
+            .insert(name.to_owned(), content_sha256(data));
+        let mut out = zip::ZipWriter::new(std::fs::File::create(to).expect("create archive"));
+        let options = zip::write::SimpleFileOptions::default();
+        for i in 0..zip.len() {
+            let entry = zip.by_index(i).expect("entry");
+            if entry.name() == name {
+                out.start_file(name, options).expect("start entry");
+                std::io::Write::write_all(&mut out, data).expect("write entry");
+            } else if entry.name() == MANIFEST {
+                out.start_file(MANIFEST, options).expect("start manifest");
+                let json = serde_json::to_vec(&manifest).expect("encode");
+                std::io::Write::write_all(&mut out, &json).expect("write manifest");
+            } else {
+                out.raw_copy_file(entry).expect("copy entry");
+            }
+        }
+        out.finish().expect("finish archive");
+    }
+
+    /// BLC-BACKUP-001, BLC-BACKUP-002: a backup restores rows unchanged (including `VALUE`
+    /// fields) and the blob content they reference into an empty database.
+    #[tokio::test]
+    async fn blc_backup_001_backup_round_trips_rows_and_blobs() {
+        let source = test_db().await.expect("db");
+        let user = create_user(&source, "backup@test.local")
+            .await
+            .expect("user");
+        let team = personal_team_id(&source, &user).await.expect("team");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let source_blobs = FsBlobStorage::new(dir.path().join("src").display().to_string());
+        let content = b"backup blob bytes".to_vec();
+        let hash = content_sha256(&content);
+        blob_with_content(&source, &source_blobs, &team, Some(&content)).await;
+        let orphan = b"nothing references this".to_vec();
+        source_blobs
+            .write_content(&content_sha256(&orphan), &orphan)
+            .await
+            .expect("write orphan");
+
+        let archive = dir.path().join("backup.zip");
+        let manifest = create_backup(&source, &source_blobs, &archive)
+            .await
+            .expect("backup");
+        assert_eq!(manifest.blobs, [hash.as_str()]);
+        assert!(
+            manifest
+                .tables
+                .iter()
+                .any(|t| t.name == "user" && t.rows == 1)
+        );
+        assert!(!manifest.tables.iter().any(|t| t.name == "migration_script"));
+        assert!(!archive.with_extension("zip.partial").exists());
+
+        let target = empty_db().await;
+        let target_blobs = FsBlobStorage::new(dir.path().join("dst").display().to_string());
+        restore_backup(&target, &target_blobs, MIGRATIONS, &archive)
+            .await
+            .expect("restore");
+        for table in ["user", "team", "session", "blob"] {
+            assert_eq!(
+                dump(&target, table).await,
+                dump(&source, table).await,
+                "{table}"
+            );
+        }
+        assert_eq!(
+            target.applied_migrations().await.expect("migrations"),
+            manifest.migrations
+        );
+        assert_eq!(
+            target_blobs.read_content(&hash).await.expect("read"),
+            Some(content)
+        );
+        assert!(dump(&target, "team").await.contains(&team));
+
+        let err = restore_backup(&target, &target_blobs, MIGRATIONS, &archive)
+            .await
+            .expect_err("restore into a non-empty database");
+        assert!(err.to_string().contains("empty database"), "{err}");
+    }
+
+    /// BLC-BACKUP-003: a damaged archive fails verification.
+    #[tokio::test]
+    async fn blc_backup_003_verify_detects_corruption() {
+        let source = test_db().await.expect("db");
+        create_user(&source, "backup-corrupt@test.local")
+            .await
+            .expect("user");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let blobs = FsBlobStorage::new(dir.path().join("blobs").display().to_string());
+        let archive = dir.path().join("backup.zip");
+        create_backup(&source, &blobs, &archive)
+            .await
+            .expect("backup");
+
+        let manifest = verify_backup(&archive).expect("verify");
+        let tampered = dir.path().join("tampered.zip");
+        {
+            let mut zip = zip::ZipArchive::new(std::fs::File::open(&archive).expect("open"))
+                .expect("read archive");
+            let mut out =
+                zip::ZipWriter::new(std::fs::File::create(&tampered).expect("create archive"));
+            let users = manifest
+                .tables
+                .iter()
+                .find(|t| t.name == "user")
+                .expect("user table");
+            let victim = users.chunks[0].clone();
+            for i in 0..zip.len() {
+                let entry = zip.by_index(i).expect("entry");
+                if entry.name() == victim {
+                    out.start_file(victim.as_str(), zip::write::SimpleFileOptions::default())
+                        .expect("start entry");
+                    std::io::Write::write_all(&mut out, b"[]").expect("write entry");
+                } else {
+                    out.raw_copy_file(entry).expect("copy entry");
+                }
+            }
+            out.finish().expect("finish archive");
+        }
+        let err = verify_backup(&tampered).expect_err("tampered archive");
+        assert!(err.to_string().contains("is corrupt"), "{err}");
+    }
+
+    /// BLC-BACKUP-004: a restore that fails midway leaves the database empty, so it can be
+    /// retried.
+    #[tokio::test]
+    async fn blc_backup_004_failed_restore_leaves_database_empty() {
+        let source = test_db().await.expect("db");
+        create_user(&source, "backup-failed-restore@test.local")
+            .await
+            .expect("user");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let blobs = FsBlobStorage::new(dir.path().join("blobs").display().to_string());
+        let archive = dir.path().join("backup.zip");
+        let manifest = create_backup(&source, &blobs, &archive)
+            .await
+            .expect("backup");
+        // `user` is restored after tables such as `session` and `team`, so rows are already
+        // loaded when its chunk fails.
+        let users = manifest
+            .tables
+            .iter()
+            .find(|t| t.name == "user")
+            .expect("user table");
+        assert!(
+            manifest
+                .tables
+                .iter()
+                .any(|t| t.name < users.name && t.rows > 0)
+        );
+        let broken = dir.path().join("broken.zip");
+        rewrite_entry(&archive, &broken, &users.chunks[0], b"[{ id: ");
+        verify_backup(&broken).expect("broken rows still verify");
+
+        let target = empty_db().await;
+        let target_blobs = FsBlobStorage::new(dir.path().join("dst").display().to_string());
+        let err = restore_backup(&target, &target_blobs, MIGRATIONS, &broken)
+            .await
+            .expect_err("restore of broken rows");
+        assert!(
+            format!("{err:#}").contains(&format!("failed to restore '{}'", users.chunks[0])),
+            "{err:#}"
+        );
+        assert!(
+            list_tables(&target).await.expect("tables").is_empty(),
+            "failed restore left tables behind"
+        );
+
+        restore_backup(&target, &target_blobs, MIGRATIONS, &archive)
+            .await
+            .expect("retry restore");
+        assert_eq!(dump(&target, "user").await, dump(&source, "user").await);
+    }
+
+    /// BLC-BACKUP-001: a backup refuses to leave out blob files that predate content addressing.
+    #[tokio::test]
+    async fn blc_backup_001_backup_refuses_unadopted_legacy_files() {
+        let source = test_db().await.expect("db");
+        let user = create_user(&source, "backup-legacy@test.local")
+            .await
+            .expect("user");
+        let team = personal_team_id(&source, &user).await.expect("team");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let blob_dir = dir.path().join("blobs");
+        std::fs::create_dir_all(&blob_dir).expect("blob dir");
+        let blobs = FsBlobStorage::new(blob_dir.display().to_string());
+        let legacy = blob_with_content(&source, &blobs, &team, None).await;
+        std::fs::write(
+            blob_dir.join(legacy.file_name().expect("file name")),
+            b"old scan",
+        )
+        .expect("write legacy file");
+
+        let archive = dir.path().join("backup.zip");
+        let err = create_backup(&source, &blobs, &archive)
+            .await
+            .expect_err("backup with a legacy file");
+        assert!(err.to_string().contains(&legacy.id), "{err}");
+        assert!(!archive.exists());
+
+        crate::test_helpers::blob_service(&source, blob_dir.display().to_string())
+            .adopt_legacy_files()
+            .await
+            .expect("adopt");
+        let manifest = create_backup(&source, &blobs, &archive)
+            .await
+            .expect("backup after adoption");
+        assert_eq!(manifest.blobs, [content_sha256(b"old scan")]);
+    }
+}
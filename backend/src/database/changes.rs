@@ -0,0 +1,37 @@
+//! In-process fan-out of committed library changes to `GET /api/v1/events` subscribers.
+
+use surrealdb::types::RecordId;
+use tokio::sync::broadcast;
+
+use shared::event::ChangeEvent;
+
+/// Notifications a subscriber may fall behind by before it is told to resynchronize.
+const CHANGE_BUS_CAPACITY: usize = 1024;
+
+/// A change together with the team whose readers are notified.
+#[derive(Clone, Debug)]
+pub struct ChangeNotice {
+    pub team: RecordId,
+    pub event: ChangeEvent,
+}
+
+#[derive(Clone)]
+pub(super) struct ChangeBus {
+    sender: broadcast::Sender<ChangeNotice>,
+}
+
+impl ChangeBus {
+    pub(super) fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANGE_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Sending only fails when nobody is subscribed, which is not an error.
+    pub(super) fn send(&self, notice: ChangeNotice) {
+        let _ = self.sender.send(notice);
+    }
+
+    pub(super) fn subscribe(&self) -> broadcast::Receiver<ChangeNotice> {
+        self.sender.subscribe()
+    }
+}
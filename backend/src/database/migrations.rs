@@ -1,24 +1,76 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-use anyhow::{Context, Result as AnyResult, anyhow};
+use anyhow::{Context, Result as AnyResult, anyhow, bail};
+use chrono::{DateTime, Utc};
 use ring::digest::{SHA256, digest};
 use serde::Deserialize;
 use surrealdb::Surreal;
 use surrealdb::engine::any::Any;
-use surrealdb::types::SurrealValue;
+use surrealdb::types::{Datetime, SurrealValue};
 use tracing::info;
 
+/// Subdirectory of the migration root holding optional down-scripts, named like the script they
+/// revert.
+pub const DOWN_DIR: &str = "down";
+
 #[derive(Debug, Deserialize, SurrealValue)]
 struct AppliedMigration {
     script_name: String,
     checksum: String,
 }
 
+#[derive(Debug, Deserialize, SurrealValue)]
+struct AppliedMigrationRow {
+    script_name: String,
+    checksum: String,
+    executed_at: Datetime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the script on disk no longer matches the recorded checksum.
+    Mismatched,
+    /// Recorded as applied, but no script with that name exists on disk.
+    Missing,
+}
+
+impl MigrationState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Pending => "pending",
+            Self::Mismatched => "mismatched",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+/// One line of [`status`].
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub script_name: String,
+    pub state: MigrationState,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub has_down_script: bool,
+}
+
 pub async fn run(db: &Surreal<Any>, migration_root: &str) -> AnyResult<()> {
+    run_until(db, migration_root, None).await
+}
+
+/// Like [`run`], but stops after the script named `last` (restoring a backup brings the schema to
+/// the version it was taken at before loading its rows).
+pub async fn run_until(
+    db: &Surreal<Any>,
+    migration_root: &str,
+    last: Option<&str>,
+) -> AnyResult<()> {
     ensure_migration_table(db).await?;
 
     let migration_dir = resolve_migration_dir(migration_root)?;
@@ -27,6 +79,9 @@ pub async fn run(db: &Surreal<Any>, migration_root: &str) -> AnyResult<()> {
 
     for path in files {
         let script_name = file_name(&path)?;
+        if last.is_some_and(|last| script_name.as_str() > last) {
+            break;
+        }
         let script = fs::read_to_string(&path)
             .with_context(|| format!("failed to read migration script '{}'", path.display()))?;
         let checksum = script_checksum(&script);
@@ -63,6 +118,191 @@ pub async fn run(db: &Surreal<Any>, migration_root: &str) -> AnyResult<()> {
     Ok(())
 }
 
+/// Every script on disk and every applied record, ordered by script name.
+pub async fn status(db: &Surreal<Any>, migration_root: &str) -> AnyResult<Vec<MigrationStatus>> {
+    ensure_migration_table(db).await?;
+    let migration_dir = resolve_migration_dir(migration_root)?;
+    let mut applied: BTreeMap<String, AppliedMigrationRow> = load_applied_rows(db)
+        .await?
+        .into_iter()
+        .map(|row| (row.script_name.clone(), row))
+        .collect();
+
+    let mut out = Vec::new();
+    for path in list_migration_files(&migration_dir)? {
+        let script_name = file_name(&path)?;
+        let script = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read migration script '{}'", path.display()))?;
+        let (state, executed_at) = match applied.remove(&script_name) {
+            Some(row) if row.checksum == script_checksum(&script) => {
+                (MigrationState::Applied, Some(row.executed_at))
+            }
+            Some(row) => (MigrationState::Mismatched, Some(row.executed_at)),
+            None => (MigrationState::Pending, None),
+        };
+        out.push(MigrationStatus {
+            has_down_script: down_script_path(&migration_dir, &script_name).is_file(),
+            script_name,
+            state,
+            executed_at: executed_at.map(DateTime::<Utc>::from),
+        });
+    }
+    out.extend(applied.into_values().map(|row| MigrationStatus {
+        has_down_script: down_script_path(&migration_dir, &row.script_name).is_file(),
+        script_name: row.script_name,
+        state: MigrationState::Missing,
+        executed_at: Some(row.executed_at.into()),
+    }));
+    out.sort_by(|a, b| a.script_name.cmp(&b.script_name));
+    Ok(out)
+}
+
+/// Runs every pending script inside one transaction and cancels it, so nothing is written.
+/// Fails like [`run`] on a checksum mismatch or a failing statement; returns the scripts that
+/// would be applied.
+pub async fn dry_run(db: &Surreal<Any>, migration_root: &str) -> AnyResult<Vec<String>> {
+    ensure_migration_table(db).await?;
+    let migration_dir = resolve_migration_dir(migration_root)?;
+    let applied = load_applied_migrations(db).await?;
+
+    let mut pending = Vec::new();
+    for path in list_migration_files(&migration_dir)? {
+        let script_name = file_name(&path)?;
+        let script = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read migration script '{}'", path.display()))?;
+        let checksum = script_checksum(&script);
+        match applied.get(&script_name) {
+            Some(existing) if existing != &checksum => {
+                bail!(
+                    "migration '{script_name}' checksum mismatch: expected {existing}, got {checksum}"
+                );
+            }
+            Some(_) => {}
+            None => pending.push((script_name, script)),
+        }
+    }
+    execute_and_cancel(db, &pending).await?;
+    Ok(pending.into_iter().map(|(name, _)| name).collect())
+}
+
+/// Reverts every applied script after `target`, newest first, by running its down-script from
+/// [`DOWN_DIR`] and deleting its `migration_script` record in one transaction per script. Fails
+/// before changing anything when `target` is not applied or a down-script is missing. With
+/// `dry_run`, all down-scripts run in one cancelled transaction instead. Returns the reverted
+/// scripts.
+pub async fn rollback_to(
+    db: &Surreal<Any>,
+    migration_root: &str,
+    target: &str,
+    dry_run: bool,
+) -> AnyResult<Vec<String>> {
+    ensure_migration_table(db).await?;
+    let migration_dir = resolve_migration_dir(migration_root)?;
+    let applied: BTreeMap<String, String> =
+        load_applied_migrations(db).await?.into_iter().collect();
+    if !applied.contains_key(target) {
+        bail!("cannot roll back to '{target}': it is not an applied migration");
+    }
+
+    let reverted: Vec<&String> = applied
+        .keys()
+        .rev()
+        .take_while(|name| *name != target)
+        .collect();
+    let missing: Vec<&str> = reverted
+        .iter()
+        .filter(|name| !down_script_path(&migration_dir, name).is_file())
+        .map(|name| name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "cannot roll back to '{target}': no down-script in '{DOWN_DIR}/' for {}",
+            missing.join(", ")
+        );
+    }
+
+    let mut scripts = Vec::with_capacity(reverted.len());
+    for name in reverted {
+        let path = down_script_path(&migration_dir, name);
+        let script = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read down-script '{}'", path.display()))?;
+        scripts.push((name.clone(), script));
+    }
+    if dry_run {
+        execute_and_cancel(db, &scripts).await?;
+    } else {
+        for (name, script) in &scripts {
+            let started = Instant::now();
+            info!(migration = %name, "reverting database migration");
+            revert_migration(db, name, script).await?;
+            info!(
+                migration = %name,
+                duration_ms = started.elapsed().as_millis() as u64,
+                status = "reverted",
+                "database migration reverted successfully"
+            );
+        }
+    }
+    Ok(scripts.into_iter().map(|(name, _)| name).collect())
+}
+
+fn down_script_path(migration_dir: &Path, script_name: &str) -> PathBuf {
+    migration_dir.join(DOWN_DIR).join(script_name)
+}
+
+async fn execute_and_cancel(db: &Surreal<Any>, scripts: &[(String, String)]) -> AnyResult<()> {
+    if scripts.is_empty() {
+        return Ok(());
+    }
+    let txn = db
+        .clone()
+        .begin()
+        .await
+        .map_err(|err| anyhow!(err))
+        .context("failed to begin dry-run transaction")?;
+    for (script_name, script) in scripts {
+        let result = match txn.query(script.as_str()).await {
+            Ok(mut response) => {
+                ensure_no_statement_errors(script_name, "dry run returned errors", &mut response)
+            }
+            Err(err) => Err(anyhow!(err))
+                .with_context(|| format!("failed to run migration '{script_name}' in dry run")),
+        };
+        if let Err(err) = result {
+            let _ = txn.cancel().await;
+            return Err(err);
+        }
+        info!(migration = %script_name, status = "dry_run", "database migration ran in dry run");
+    }
+    txn.cancel()
+        .await
+        .map_err(|err| anyhow!(err))
+        .context("failed to cancel dry-run transaction")
+        .map(|_| ())
+}
+
+async fn revert_migration(db: &Surreal<Any>, script_name: &str, script: &str) -> AnyResult<()> {
+    let tx = format!(
+        "BEGIN TRANSACTION;
+{};
+DELETE migration_script WHERE script_name = $script_name;
+COMMIT TRANSACTION;",
+        script
+    );
+    let mut response = db
+        .query(tx)
+        .bind(("script_name", script_name.to_owned()))
+        .await
+        .map_err(|err| anyhow!(err))
+        .with_context(|| format!("failed to revert migration '{}'", script_name))?;
+    ensure_no_statement_errors(script_name, "down-script returned errors", &mut response)?;
+    response
+        .check()
+        .map_err(|err| anyhow!(err))
+        .with_context(|| format!("down-script returned errors '{}'", script_name))?;
+    Ok(())
+}
+
 async fn ensure_migration_table(db: &Surreal<Any>) -> AnyResult<()> {
     db.query(
         "DEFINE TABLE OVERWRITE migration_script TYPE NORMAL SCHEMAFULL PERMISSIONS NONE;
@@ -77,6 +317,12 @@ DEFINE INDEX OVERWRITE migration_script_script_name_unique ON migration_script F
     .map(|_| ())
 }
 
+/// Applied scripts and their checksums, ordered by script name.
+pub async fn applied(db: &Surreal<Any>) -> AnyResult<BTreeMap<String, String>> {
+    ensure_migration_table(db).await?;
+    Ok(load_applied_migrations(db).await?.into_iter().collect())
+}
+
 async fn load_applied_migrations(db: &Surreal<Any>) -> AnyResult<HashMap<String, String>> {
     let mut response = db
         .query("SELECT script_name, checksum FROM migration_script;")
@@ -96,6 +342,16 @@ async fn load_applied_migrations(db: &Surreal<Any>) -> AnyResult<HashMap<String,
     Ok(out)
 }
 
+async fn load_applied_rows(db: &Surreal<Any>) -> AnyResult<Vec<AppliedMigrationRow>> {
+    db.query("SELECT script_name, checksum, executed_at FROM migration_script;")
+        .await
+        .map_err(|err| anyhow!(err))
+        .context("failed to read applied migration records")?
+        .take(0)
+        .map_err(|err| anyhow!(err))
+        .context("failed to decode applied migration records")
+}
+
 fn ensure_no_statement_errors(
     migration: &str,
     context: &str,
@@ -225,3 +481,198 @@ fn script_checksum(script: &str) -> String {
     let digest = digest(&SHA256, script.as_bytes());
     hex::encode(digest.as_ref())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use surrealdb::Surreal;
+    use surrealdb::engine::any::Any;
+    use surrealdb::types::Value;
+
+    use crate::database::Database;
+
+    use super::{DOWN_DIR, MigrationState, dry_run, rollback_to, run, run_until, status};
+
+    fn write(dir: &Path, name: &str, script: &str) {
+        fs::create_dir_all(dir.join(DOWN_DIR)).unwrap();
+        fs::write(dir.join(name), script).unwrap();
+    }
+
+    fn scripts() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "001_item.surql",
+            "DEFINE TABLE item SCHEMALESS; CREATE item:one SET n = 1;",
+        );
+        write(dir.path(), "002_tag.surql", "DEFINE TABLE tag SCHEMALESS;");
+        write(
+            &dir.path().join(DOWN_DIR),
+            "002_tag.surql",
+            "REMOVE TABLE tag;",
+        );
+        dir
+    }
+
+    async fn fresh_db() -> Surreal<Any> {
+        Database::connect("mem://", "migrations", "migrations", None, None)
+            .await
+            .unwrap()
+            .db
+    }
+
+    async fn tables(db: &Surreal<Any>) -> Vec<String> {
+        let value: Value = db
+            .query("RETURN object::keys((INFO FOR DB).tables)")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        let mut tables: Vec<String> = serde_json::from_value(value.into_json_value()).unwrap();
+        tables.sort();
+        tables
+    }
+
+    fn states(report: &[super::MigrationStatus]) -> Vec<(&str, MigrationState)> {
+        report
+            .iter()
+            .map(|m| (m.script_name.as_str(), m.state))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn status_reports_applied_pending_mismatched_and_missing_scripts() {
+        let dir = scripts();
+        let root = dir.path().to_str().unwrap();
+        let db = fresh_db().await;
+        run_until(&db, root, Some("001_item.surql")).await.unwrap();
+
+        let report = status(&db, root).await.unwrap();
+        assert_eq!(
+            states(&report),
+            [
+                ("001_item.surql", MigrationState::Applied),
+                ("002_tag.surql", MigrationState::Pending),
+            ]
+        );
+        assert!(report[0].executed_at.is_some() && report[1].executed_at.is_none());
+        assert!(!report[0].has_down_script && report[1].has_down_script);
+
+        run(&db, root).await.unwrap();
+        fs::write(dir.path().join("001_item.surql"), "DEFINE TABLE item;").unwrap();
+        fs::remove_file(dir.path().join("002_tag.surql")).unwrap();
+        assert_eq!(
+            states(&status(&db, root).await.unwrap()),
+            [
+                ("001_item.surql", MigrationState::Mismatched),
+                ("002_tag.surql", MigrationState::Missing),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn dry_run_executes_pending_scripts_without_keeping_changes() {
+        let dir = scripts();
+        let root = dir.path().to_str().unwrap();
+        let db = fresh_db().await;
+
+        assert_eq!(
+            dry_run(&db, root).await.unwrap(),
+            ["001_item.surql", "002_tag.surql"]
+        );
+        assert_eq!(tables(&db).await, ["migration_script"]);
+        assert!(
+            status(&db, root)
+                .await
+                .unwrap()
+                .iter()
+                .all(|m| m.state == MigrationState::Pending)
+        );
+
+        write(
+            dir.path(),
+            "003_broken.surql",
+            "DEFINE TABLE strict SCHEMAFULL; DEFINE FIELD n ON strict TYPE int; \
+             CREATE strict SET n = 'x';",
+        );
+        let err = dry_run(&db, root).await.unwrap_err();
+        assert!(format!("{err:#}").contains("003_broken.surql"), "{err:#}");
+        assert_eq!(tables(&db).await, ["migration_script"]);
+    }
+
+    #[tokio::test]
+    async fn rollback_runs_down_scripts_back_to_the_named_version() {
+        let dir = scripts();
+        let root = dir.path().to_str().unwrap();
+        let db = fresh_db().await;
+        run(&db, root).await.unwrap();
+
+        assert_eq!(
+            rollback_to(&db, root, "001_item.surql", true)
+                .await
+                .unwrap(),
+            ["002_tag.surql"]
+        );
+        assert_eq!(tables(&db).await, ["item", "migration_script", "tag"]);
+
+        assert_eq!(
+            rollback_to(&db, root, "001_item.surql", false)
+                .await
+                .unwrap(),
+            ["002_tag.surql"]
+        );
+        assert_eq!(tables(&db).await, ["item", "migration_script"]);
+        assert_eq!(
+            states(&status(&db, root).await.unwrap()),
+            [
+                ("001_item.surql", MigrationState::Applied),
+                ("002_tag.surql", MigrationState::Pending),
+            ]
+        );
+
+        write(
+            dir.path(),
+            "003_label.surql",
+            "DEFINE TABLE label SCHEMALESS;",
+        );
+        run(&db, root).await.unwrap();
+        let err = rollback_to(&db, root, "001_item.surql", false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("003_label.surql"), "{err}");
+        assert_eq!(
+            tables(&db).await,
+            ["item", "label", "migration_script", "tag"]
+        );
+
+        let err = rollback_to(&db, root, "000_unknown.surql", false)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("not an applied migration"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn shipped_down_scripts_revert_and_reapply_cleanly() {
+        let root = concat!(env!("CARGO_MANIFEST_DIR"), "/db-migrations");
+        let db = fresh_db().await;
+        run(&db, root).await.unwrap();
+        let before = tables(&db).await;
+        let report = status(&db, root).await.unwrap();
+        let target = report
+            .iter()
+            .rev()
+            .find(|m| !m.has_down_script)
+            .map(|m| m.script_name.clone())
+            .unwrap();
+        let reverted = rollback_to(&db, root, &target, false).await.unwrap();
+        assert!(!reverted.is_empty());
+        assert_eq!(dry_run(&db, root).await.unwrap().len(), reverted.len());
+        run(&db, root).await.unwrap();
+        assert_eq!(tables(&db).await, before);
+    }
+}
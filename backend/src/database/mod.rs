@@ -1,15 +1,26 @@
-mod migrations;
+mod changes;
+pub mod migrations;
+mod timed_query;
+
+use std::borrow::Cow;
+use std::sync::Mutex;
 
 use anyhow::{Context, Result as AnyResult, anyhow};
 use serde::Deserialize;
 use surrealdb::Surreal;
 use surrealdb::engine::any::{Any, connect};
+use surrealdb::method::{Create, Delete, Select, Transaction, Update, Upsert};
 use surrealdb::opt::auth::Database as DbAuth;
+use surrealdb::opt::{CreateResource, IntoResource};
 use surrealdb::types::{RecordId, RecordIdKey, SqlFormat, SurrealValue, ToSql};
 use tracing::instrument;
 
 use crate::error::AppError;
 
+use changes::ChangeBus;
+pub use changes::ChangeNotice;
+pub use timed_query::TimedQuery;
+
 /// Inspect Surreal [`surrealdb::IndexedResults`] for per-statement failures (mirrors migration checks).
 pub(crate) fn surreal_take_errors(
     context: &'static str,
@@ -56,6 +67,11 @@ pub fn record_id_string(rid: &RecordId) -> String {
 
 pub struct Database {
     pub db: Surreal<Any>,
+    /// Open interactive transaction; every query issued through the methods below joins it.
+    txn: Option<Transaction<Any>>,
+    changes: ChangeBus,
+    /// Changes published inside `txn`; broadcast once it commits, dropped if it is cancelled.
+    pending: Mutex<Vec<ChangeNotice>>,
 }
 
 impl Database {
@@ -110,7 +126,113 @@ impl Database {
                 )
             })?;
 
-        Ok(Self { db })
+        Ok(Self {
+            db,
+            txn: None,
+            changes: ChangeBus::new(),
+            pending: Mutex::default(),
+        })
+    }
+
+    /// Start an interactive transaction. Repositories built over the returned handle write into it
+    /// until [`Database::commit`] or [`Database::cancel`] is called.
+    pub async fn begin(&self) -> Result<Self, AppError> {
+        let txn =
+            self.db.clone().begin().await.map_err(|e| {
+                crate::log_and_convert!(AppError::database, "db.transaction.begin", e)
+            })?;
+        Ok(Self {
+            db: self.db.clone(),
+            txn: Some(txn),
+            changes: self.changes.clone(),
+            pending: Mutex::default(),
+        })
+    }
+
+    /// Persist the open transaction (no-op outside a transaction) and broadcast its changes.
+    pub async fn commit(self) -> Result<(), AppError> {
+        if let Some(txn) = self.txn {
+            txn.commit().await.map_err(|e| {
+                crate::log_and_convert!(AppError::database, "db.transaction.commit", e)
+            })?;
+        }
+        let pending = self.pending.into_inner().unwrap_or_else(|e| e.into_inner());
+        for notice in pending {
+            self.changes.send(notice);
+        }
+        Ok(())
+    }
+
+    /// Notify `GET /api/v1/events` subscribers of a written record; inside a transaction the
+    /// notice waits for the commit.
+    pub fn publish_change(&self, notice: ChangeNotice) {
+        if self.txn.is_some() {
+            self.pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(notice);
+        } else {
+            self.changes.send(notice);
+        }
+    }
+
+    pub fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<ChangeNotice> {
+        self.changes.subscribe()
+    }
+
+    /// Roll back the open transaction (no-op outside a transaction).
+    pub async fn cancel(self) -> Result<(), AppError> {
+        if let Some(txn) = self.txn {
+            txn.cancel().await.map_err(|e| {
+                crate::log_and_convert!(AppError::database, "db.transaction.cancel", e)
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn query<'a>(&'a self, query: impl Into<Cow<'a, str>>) -> TimedQuery<'a> {
+        let query = query.into();
+        let operation = crate::metrics::query_operation(&query);
+        let inner = match &self.txn {
+            Some(txn) => txn.query(query),
+            None => self.db.query(query),
+        };
+        TimedQuery::new(inner, operation)
+    }
+
+    pub fn select<O>(&self, resource: impl IntoResource<O>) -> Select<'_, Any, O> {
+        match &self.txn {
+            Some(txn) => txn.select(resource),
+            None => self.db.select(resource),
+        }
+    }
+
+    pub fn create<R>(&self, resource: impl CreateResource<R>) -> Create<'_, Any, R> {
+        match &self.txn {
+            Some(txn) => txn.create(resource),
+            None => self.db.create(resource),
+        }
+    }
+
+    pub fn update<O>(&self, resource: impl IntoResource<O>) -> Update<'_, Any, O> {
+        match &self.txn {
+            Some(txn) => txn.update(resource),
+            None => self.db.update(resource),
+        }
+    }
+
+    pub fn upsert<O>(&self, resource: impl IntoResource<O>) -> Upsert<'_, Any, O> {
+        match &self.txn {
+            Some(txn) => txn.upsert(resource),
+            None => self.db.upsert(resource),
+        }
+    }
+
+    pub fn delete<O>(&self, resource: impl IntoResource<O>) -> Delete<'_, Any, O> {
+        match &self.txn {
+            Some(txn) => txn.delete(resource),
+            None => self.db.delete(resource),
+        }
     }
 
     #[instrument(
@@ -124,11 +246,45 @@ impl Database {
         migrations::run(&self.db, migration_path).await
     }
 
+    /// Apply migrations up to and including the script named `last`.
+    pub async fn migrate_until(&self, migration_path: &str, last: &str) -> AnyResult<()> {
+        migrations::run_until(&self.db, migration_path, Some(last)).await
+    }
+
+    /// Applied migration scripts and their checksums, ordered by script name.
+    pub async fn applied_migrations(
+        &self,
+    ) -> AnyResult<std::collections::BTreeMap<String, String>> {
+        migrations::applied(&self.db).await
+    }
+
+    /// Applied, pending, mismatched and missing migration scripts.
+    pub async fn migration_status(
+        &self,
+        migration_path: &str,
+    ) -> AnyResult<Vec<migrations::MigrationStatus>> {
+        migrations::status(&self.db, migration_path).await
+    }
+
+    /// Run pending migrations in a cancelled transaction; returns the scripts that would apply.
+    pub async fn migrate_dry_run(&self, migration_path: &str) -> AnyResult<Vec<String>> {
+        migrations::dry_run(&self.db, migration_path).await
+    }
+
+    /// Revert applied migrations after `target` with their down-scripts.
+    pub async fn rollback_migrations(
+        &self,
+        migration_path: &str,
+        target: &str,
+        dry_run: bool,
+    ) -> AnyResult<Vec<String>> {
+        migrations::rollback_to(&self.db, migration_path, target, dry_run).await
+    }
+
     /// The `team` row where `owner` is this user (their personal team).
     pub async fn personal_team_thing_for_user(&self, user_id: &str) -> Result<RecordId, AppError> {
         let user = RecordId::new("user", user_id.to_owned());
         let mut response = self
-            .db
             .query("SELECT id FROM team WHERE owner = $user LIMIT 1")
             .bind(("user", user))
             .await
@@ -0,0 +1,54 @@
+//! [`Query`] wrapper that records how long a statement took in [`crate::metrics`].
+
+use std::borrow::Cow;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::time::Instant;
+
+use surrealdb::IndexedResults;
+use surrealdb::engine::any::Any;
+use surrealdb::method::{IntoVariables, Query};
+
+use crate::metrics::metrics;
+
+/// A pending query, timed under the label of its first statement once it is awaited.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TimedQuery<'a> {
+    inner: Query<'a, Any>,
+    operation: &'static str,
+}
+
+impl<'a> TimedQuery<'a> {
+    pub(super) fn new(inner: Query<'a, Any>, operation: &'static str) -> Self {
+        Self { inner, operation }
+    }
+
+    pub fn bind(self, vars: impl IntoVariables) -> Self {
+        Self {
+            inner: self.inner.bind(vars),
+            ..self
+        }
+    }
+
+    /// Append a statement; the query keeps the label of the first one.
+    pub fn query(self, query: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            inner: self.inner.query(query),
+            ..self
+        }
+    }
+}
+
+impl<'a> IntoFuture for TimedQuery<'a> {
+    type Output = surrealdb::Result<IndexedResults>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = self.inner.await;
+            metrics().observe_db_query(self.operation, started.elapsed());
+            result
+        })
+    }
+}
@@ -1,6 +1,6 @@
 use utoipa::openapi::external_docs::ExternalDocs;
 use utoipa::openapi::info::ContactBuilder;
-use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 
 use crate::about::AboutResponse;
@@ -9,11 +9,11 @@ use crate::settings::Settings;
 use crate::resources::blob::PatchBlob;
 use crate::resources::collection::PatchCollection;
 use crate::resources::monitoring::{
-    ActivityCalendarMetrics, AdminMonitoringMetrics, EngagementMetrics, FamilyErrorRates,
-    FamilyLatency, FeatureFamilyMetrics, HttpAuditLog, IdLike404Metrics, LatencyMetrics,
-    MethodLatency, MetricsWindowWire, MonitoringMetricsQuery, MonitoringMetricsResponse,
-    MutationHealthMetrics, NewUserActivationMetrics, ReliabilityMetrics, RouteFamily,
-    TopFailingRoute, TrafficMetrics, TrafficMixEntry,
+    ActivityCalendarMetrics, AdminMonitoringMetrics, AuditExportFormat, EngagementMetrics,
+    FamilyErrorRates, FamilyLatency, FeatureFamilyMetrics, HttpAuditLog, IdLike404Metrics,
+    LatencyMetrics, MethodLatency, MetricsWindowWire, MonitoringMetricsQuery,
+    MonitoringMetricsResponse, MutationHealthMetrics, NewUserActivationMetrics, ReliabilityMetrics,
+    RouteFamily, SecurityEvent, TopFailingRoute, TrafficMetrics, TrafficMixEntry,
 };
 use crate::resources::setlist::PatchSetlist;
 use crate::resources::song::{PatchSong, PatchSongData};
@@ -24,20 +24,38 @@ use crate::resources::{
 };
 use shared::MoveOwner;
 use shared::api::SongListQuery;
+use shared::auth::OidcProviderInfo;
 use shared::auth::otp::{OtpRequest, OtpVerify};
-use shared::blob::{BlobLink, FileType};
+use shared::auth::passkey::{
+    AssertionResponse, AttestationResponse, AuthenticationCredential, AuthenticatorSelection,
+    CredentialDescriptor, CredentialParameter, PasskeyCreationOptions, PasskeyRequestOptions,
+    PasskeyUserEntity, RegistrationCredential, RelyingParty,
+};
+use shared::batch::{BatchAction, BatchOperation, BatchRequest, BatchResponse, BatchResult};
+use shared::blob::{BlobContentProblem, BlobLink, BlobVariant, BlobVerifyReport, FileType};
 pub use shared::error::{ErrorResponse, Problem, ProblemDetails};
+use shared::event::ChangeEvent;
+use shared::job::{Job, JobKind, JobStatus};
 use shared::like::LikeStatus;
 use shared::player::{
     Orientation, Player, PlayerBlobItem, PlayerChordsItem, PlayerItem, ScrollType, TocItem,
 };
 use shared::song::SongDataSchema;
-use shared::song::{Link as SongLink, SongUserSpecificAddons};
+use shared::song::{Link as SongLink, SongDraft, SongUserSpecificAddons};
+use shared::sync::{SyncChanges, SyncTombstone};
 use shared::team::{
-    CreateTeam, PatchTeam, Team, TeamInvitation, TeamMember, TeamMemberInput, TeamRole, TeamUser,
-    TeamUserRef, UpdateTeam,
+    ActivityAction, ActivityResourceType, CreateOrganization, CreateTeam, CreateTeamInvitation,
+    CreateTeamRole, CreateWebhook, CreatedWebhook, Organization, OrganizationTeam, PatchTeam, Team,
+    TeamActivity, TeamInvitation, TeamInvitationStatus, TeamMember, TeamMemberInput,
+    TeamPermission, TeamRole, TeamRoleDefinition, TeamUser, TeamUserRef, TransferTeam, TrashItem,
+    TrashResourceType, UpdateOrganization, UpdateTeam, Webhook, WebhookDelivery,
+    WebhookDeliveryStatus, WebhookEvent,
+};
+use shared::user::{
+    ActivityDigest, ApiToken, ApiTokenScope, CreateApiToken, CreatedApiToken, DeleteAccount,
+    IdentityLinkStart, LinkIdentity, NotificationPreferences, Passkey, PatchUser, RegisterPasskey,
+    SessionBody, SessionUserBody, TeamHandover, UpdatePasskey, UserIdentity,
 };
-use shared::user::{SessionBody, SessionUserBody};
 
 pub mod rest {
     use super::{Settings, openapi_document};
@@ -104,7 +122,7 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
             **JSON naming:** Object keys use `snake_case`. Enum wire values use the casing shown in each schema (broader enum casing standardization is planned).\n\n\
             **Pagination:** List endpoints accept `page` (0-based) and `page_size` (1–500, default 50). Responses include `X-Total-Count` with the total matching rows before pagination and RFC 5988 `Link` headers (relations: first, prev, next, last) where applicable.\n\n\
             **Rate limiting:** Versioned `/api/v1/*` routes use token-bucket limits per client IP (`Retry-After`, `X-RateLimit-*` on **429**; configurable via server settings).\n\n\
-            **Errors:** Failed requests return `Content-Type: application/problem+json` ([RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)) with a `Problem` body (`type`, `title`, `status`, `code`, optional `detail` / `instance`). Use `detail` for human-readable text; stable machine-readable `code` values include: `unauthorized`, `forbidden`, `not_found`, `invalid_request`, `invalid_page_size`, `conflict`, `too_many_requests`, `not_acceptable`, `precondition_failed`, `internal`. Legacy schemas `ErrorResponse` and `ProblemDetails` remain listed for one release but are deprecated in favor of `Problem`.\n\n\
+            **Errors:** Failed requests return `Content-Type: application/problem+json` ([RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)) with a `Problem` body (`type`, `title`, `status`, `code`, optional `detail` / `instance`). Use `detail` for human-readable text; stable machine-readable `code` values include: `unauthorized`, `forbidden`, `not_found`, `invalid_request`, `invalid_page_size`, `conflict`, `too_many_requests`, `not_acceptable`, `precondition_failed`, `batch_aborted`, `internal`. Legacy schemas `ErrorResponse` and `ProblemDetails` remain listed for one release but are deprecated in favor of `Problem`.\n\n\
             **CSRF:** Cookie sessions use `SameSite=Lax`; state-changing methods are `POST`/`PUT`/`PATCH`/`DELETE` (not `GET`). Cross-site simple requests cannot mutate state via cookies under typical browser rules. Browser `fetch` from the SPA should use `credentials: 'same-origin'` (or include cookies only on same-site requests). API clients using bearer tokens should still avoid exposing tokens to third-party origins.\n\n\
             **Examples:** See schema `example` fields on core DTOs in the components section.",
         license(name = "MIT", url = "https://opensource.org/licenses/MIT")
@@ -115,14 +133,23 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
     ),
     paths(
         crate::about::get_about,
+        crate::auth::oidc::rest::providers,
         crate::auth::oidc::rest::login,
         crate::auth::oidc::rest::callback,
         crate::auth::otp::rest::otp_request,
         crate::auth::otp::rest::otp_verify,
+        crate::auth::passkey::rest::passkey_options,
+        crate::auth::passkey::rest::passkey_verify,
         crate::auth::rest::logout,
         crate::resources::user::rest::get_users_me,
+        crate::resources::user::account::rest::patch_users_me,
+        crate::resources::user::account::rest::delete_users_me,
+        crate::resources::user::account::rest::export_users_me,
+        crate::resources::user::account::rest::cancel_users_me_deletion,
         crate::resources::user::rest::put_profile_picture,
         crate::resources::user::rest::delete_profile_picture,
+        crate::resources::team::activity::rest::get_notification_preferences,
+        crate::resources::team::activity::rest::put_notification_preferences,
         crate::resources::user::rest::get_users,
         crate::resources::user::rest::get_user,
         crate::resources::user::rest::create_user,
@@ -135,6 +162,24 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
         crate::resources::user::session::rest::get_session_for_user,
         crate::resources::user::session::rest::create_session_for_user,
         crate::resources::user::session::rest::delete_session_for_user,
+        crate::resources::user::api_token::rest::create_api_token_for_current_user,
+        crate::resources::user::api_token::rest::get_api_tokens_for_current_user,
+        crate::resources::user::api_token::rest::delete_api_token_for_current_user,
+        crate::resources::job::rest::get_jobs_for_current_user,
+        crate::resources::monitoring::rest::get_security_events_for_current_user,
+        crate::resources::job::rest::get_job,
+        crate::resources::job::rest::cancel_job,
+        crate::resources::sync::rest::get_sync,
+        crate::resources::batch::rest::post_batch,
+        crate::resources::event::rest::get_events,
+        crate::resources::user::passkey::rest::create_passkey_options_for_current_user,
+        crate::resources::user::passkey::rest::create_passkey_for_current_user,
+        crate::resources::user::passkey::rest::get_passkeys_for_current_user,
+        crate::resources::user::passkey::rest::update_passkey_for_current_user,
+        crate::resources::user::passkey::rest::delete_passkey_for_current_user,
+        crate::resources::user::identity::rest::get_identities_for_current_user,
+        crate::resources::user::identity::rest::create_identity_link_for_current_user,
+        crate::resources::user::identity::rest::delete_identity_for_current_user,
         crate::resources::song::rest::get_songs,
         crate::resources::song::rest::get_song,
         crate::resources::song::rest::get_song_player,
@@ -142,6 +187,7 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
         crate::resources::song::rest::update_song,
         crate::resources::song::rest::patch_song,
         crate::resources::song::rest::move_song,
+        crate::resources::song::rest::draft_song_from_blobs,
         crate::resources::song::rest::delete_song,
         crate::resources::song::rest::get_song_like_status,
         crate::resources::song::rest::put_song_like,
@@ -164,6 +210,7 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
         crate::resources::blob::rest::delete_blob,
         crate::resources::blob::rest::download_blob_image,
         crate::resources::blob::rest::upload_blob_data,
+        crate::resources::blob::rest::verify_blob_storage,
         crate::resources::setlist::rest::get_setlists,
         crate::resources::setlist::rest::get_setlist,
         crate::resources::setlist::rest::get_setlist_player,
@@ -179,25 +226,86 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
         crate::resources::team::rest::update_team,
         crate::resources::team::rest::patch_team,
         crate::resources::team::rest::delete_team,
+        crate::resources::team::rest::transfer_team,
+        crate::resources::team::rest::create_team_role,
+        crate::resources::team::rest::update_team_role,
+        crate::resources::team::rest::delete_team_role,
         crate::resources::team::invitation::rest::create_team_invitation,
         crate::resources::team::invitation::rest::list_team_invitations,
         crate::resources::team::invitation::rest::get_team_invitation,
+        crate::resources::team::invitation::rest::revoke_team_invitation,
         crate::resources::team::invitation::rest::delete_team_invitation,
         crate::resources::team::invitation::rest::accept_team_invitation_under_team,
         crate::resources::team::invitation::rest::accept_team_invitation,
+        crate::resources::team::activity::rest::list_team_activity,
+        crate::resources::team::trash::rest::list_team_trash,
+        crate::resources::team::trash::rest::restore_trash_item,
+        crate::resources::team::webhook::rest::create_team_webhook,
+        crate::resources::team::webhook::rest::list_team_webhooks,
+        crate::resources::team::webhook::rest::get_team_webhook,
+        crate::resources::team::webhook::rest::update_team_webhook,
+        crate::resources::team::webhook::rest::delete_team_webhook,
+        crate::resources::team::webhook::rest::list_team_webhook_deliveries,
+        crate::resources::team::webhook::rest::test_team_webhook,
+        crate::resources::team::organization::rest::list_organizations,
+        crate::resources::team::organization::rest::create_organization,
+        crate::resources::team::organization::rest::get_organization,
+        crate::resources::team::organization::rest::update_organization,
+        crate::resources::team::organization::rest::delete_organization,
+        crate::resources::team::organization::rest::add_organization_team,
+        crate::resources::team::organization::rest::remove_organization_team,
         crate::resources::monitoring::rest::list_http_audit_logs,
-        crate::resources::monitoring::rest::get_monitoring_metrics
+        crate::resources::monitoring::rest::export_http_audit_logs,
+        crate::resources::monitoring::rest::list_security_events,
+        crate::resources::monitoring::rest::get_monitoring_metrics,
+        crate::metrics::rest::get_metrics
     ),
     components(
         schemas(
             AboutResponse,
             User,
             SessionBody,
+            ApiToken,
+            ApiTokenScope,
+            CreateApiToken,
+            CreatedApiToken,
+            Job,
+            JobKind,
+            JobStatus,
+            SyncChanges,
+            SyncTombstone,
+            BatchAction,
+            BatchOperation,
+            BatchRequest,
+            BatchResponse,
+            BatchResult,
+            ChangeEvent,
             SessionUserBody,
             Role,
             CreateUser,
+            PatchUser,
+            DeleteAccount,
+            TeamHandover,
             OtpRequest,
             OtpVerify,
+            Passkey,
+            RegisterPasskey,
+            UpdatePasskey,
+            UserIdentity,
+            LinkIdentity,
+            IdentityLinkStart,
+            OidcProviderInfo,
+            PasskeyCreationOptions,
+            PasskeyRequestOptions,
+            RelyingParty,
+            PasskeyUserEntity,
+            CredentialParameter,
+            CredentialDescriptor,
+            AuthenticatorSelection,
+            RegistrationCredential,
+            AttestationResponse,
+            AuthenticationCredential,
+            AssertionResponse,
             SongListQuery,
             Problem,
             ErrorResponse,
@@ -210,6 +318,7 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
             PatchSongData,
             SongDataSchema,
             SongUserSpecificAddons,
+            SongDraft,
             Collection,
             CreateCollection,
             UpdateCollection,
@@ -220,10 +329,13 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
             PatchSetlist,
             Blob,
             BlobLink,
+            BlobVerifyReport,
+            BlobContentProblem,
             CreateBlob,
             UpdateBlob,
             PatchBlob,
             FileType,
+            BlobVariant,
             SongLink,
             LikeStatus,
             Player,
@@ -236,14 +348,39 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
             Team,
             TeamMember,
             TeamRole,
+            TeamPermission,
+            TeamRoleDefinition,
+            CreateTeamRole,
             TeamUser,
             TeamUserRef,
             CreateTeam,
             UpdateTeam,
             PatchTeam,
+            TransferTeam,
             TeamMemberInput,
             TeamInvitation,
+            TeamInvitationStatus,
+            CreateTeamInvitation,
+            Organization,
+            OrganizationTeam,
+            CreateOrganization,
+            UpdateOrganization,
+            TeamActivity,
+            ActivityAction,
+            ActivityResourceType,
+            TrashItem,
+            TrashResourceType,
+            Webhook,
+            CreatedWebhook,
+            CreateWebhook,
+            WebhookEvent,
+            WebhookDelivery,
+            WebhookDeliveryStatus,
+            NotificationPreferences,
+            ActivityDigest,
             HttpAuditLog,
+            AuditExportFormat,
+            SecurityEvent,
             MonitoringMetricsQuery,
             MonitoringMetricsResponse,
             MetricsWindowWire,
@@ -267,14 +404,18 @@ fn apply_openapi_runtime_metadata(doc: &mut utoipa::openapi::OpenApi, settings:
     ),
     tags(
         (name = "About", description = "Public server build and environment metadata (`GET /api/v1/about`)."),
-        (name = "Auth", description = "OAuth/OIDC login, OTP email codes, and logout. Session cookies are set on successful auth (see authentication BLC)."),
-        (name = "Monitoring", description = "Admin-only operational metrics and request audit listings under `/monitoring/`."),
-        (name = "Users", description = "Current user (`/users/me`), directory listing, sessions (own and admin), and admin user lifecycle."),
+        (name = "Auth", description = "OAuth/OIDC login with any configured provider (`/auth/providers`), OTP email codes, passkey (WebAuthn) login, and logout. Session cookies are set on successful auth (see authentication BLC)."),
+        (name = "Monitoring", description = "Admin-only operational metrics, request audit listings and exports, and the security event trail under `/monitoring/`, plus the Prometheus scrape endpoint `/metrics`."),
+        (name = "Users", description = "Current user (`/users/me`), directory listing, sessions (own and admin), personal API tokens, background jobs (`/users/me/jobs`), passkeys and linked OIDC identities, and admin user lifecycle."),
+        (name = "Jobs", description = "Background work such as blob OCR: status and progress polling (`/jobs/{id}`) and cancellation. Jobs are persisted and retried with backoff, and survive a server restart."),
+        (name = "Sync", description = "Delta sync for offline clients (`/sync`): library changes since a cursor, with tombstones for deletions and lost access."),
+        (name = "Events", description = "Live change notifications (`/events`): a Server-Sent Events stream of song, collection, setlist and blob writes in the teams the user can read, for invalidating caches without polling."),
+        (name = "Batch", description = "Atomic batches (`/batch`): ordered song, collection, setlist and blob writes applied in one transaction, with `$ref` ids for resources created earlier in the batch."),
         (name = "Songs", description = "Song CRUD, player JSON, likes, search/sort listing."),
         (name = "Collections", description = "Owned song collections, nested songs, and player views."),
-        (name = "Blobs", description = "Binary image assets: metadata, byte upload/download with cache headers."),
+        (name = "Blobs", description = "Binary image assets: metadata, byte upload/download with cache headers. Bytes are stored once per SHA-256, with `thumb` and `display` variants generated for PNG and JPEG uploads; admins can check storage integrity via `/admin/blobs/verify`."),
         (name = "Setlists", description = "Ordered sets of songs and player payloads for services."),
-        (name = "Teams", description = "Team membership, roles, and invitations (nested under `/teams/{id}/invitations`).")
+        (name = "Teams", description = "Team membership, roles, team-defined roles with explicit content permissions (`/teams/{id}/roles`), invitations (nested under `/teams/{id}/invitations`), the team activity feed (`/teams/{id}/activity`), the trash of deleted library content with restore (`/teams/{id}/trash`), outbound webhooks (`/teams/{id}/webhooks`), admin transfer (`/teams/{id}/transfer`), and organizations grouping teams around a shared library (`/organizations`).")
     ),
     modifiers(&SessionSecurity)
 )]
@@ -296,9 +437,20 @@ impl Modify for SessionSecurity {
             "SessionToken",
             SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
                 "Authorization",
-                "Session override using `Authorization: Bearer <session>` header",
+                "Session override using `Authorization: Bearer <session>` header; personal API tokens (`wvp_…`) use the same header",
             ))),
         );
+        components.add_security_scheme(
+            "MetricsToken",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "`METRICS_BEARER_TOKEN`, sent by Prometheus to scrape `/metrics`",
+                    ))
+                    .build(),
+            ),
+        );
     }
 }
 
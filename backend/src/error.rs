@@ -134,6 +134,12 @@ impl From<surrealdb::Error> for AppError {
         if msg.contains("user_email_unique") && msg.contains("already contains") {
             return Self::conflict("email already exists");
         }
+        if msg.contains("passkey_credential_idx") && msg.contains("already contains") {
+            return Self::conflict("passkey is already registered");
+        }
+        if msg.contains("user_identity_subject_idx") && msg.contains("already contains") {
+            return Self::conflict("identity is already linked to an account");
+        }
         if msg.contains("field `email`") && msg.contains("string::is_email") {
             return Self::invalid_request("invalid email address");
         }
@@ -196,8 +202,13 @@ impl AppError {
         }
     }
 
-    fn problem_type_uri(&self) -> String {
-        format!("https://worshipviewer.invalid/problems/{}", self.code())
+    /// Problem body this error is rendered as (also embedded in batch results).
+    pub fn to_problem(&self) -> Problem {
+        problem(
+            self.status_code().as_u16(),
+            self.code(),
+            self.detail_message(),
+        )
     }
 
     fn detail_message(&self) -> String {
@@ -214,6 +225,18 @@ impl AppError {
     }
 }
 
+/// Problem with the type URI and title derived from `code` and `status`.
+pub(crate) fn problem(status: u16, code: &str, detail: String) -> Problem {
+    Problem::new(
+        format!("https://worshipviewer.invalid/problems/{code}"),
+        http_status_title(status).to_string(),
+        status,
+        code,
+        detail,
+        None,
+    )
+}
+
 fn http_status_title(status: u16) -> &'static str {
     match status {
         400 => "Bad Request",
@@ -224,6 +247,7 @@ fn http_status_title(status: u16) -> &'static str {
         409 => "Conflict",
         429 => "Too Many Requests",
         412 => "Precondition Failed",
+        424 => "Failed Dependency",
         500 => "Internal Server Error",
         _ => "Error",
     }
@@ -254,18 +278,8 @@ impl ResponseError for AppError {
                 "internal error"
             );
         }
-        let status = self.status_code().as_u16();
-        let detail = self.detail_message();
-        let problem = Problem::new(
-            self.problem_type_uri(),
-            http_status_title(status).to_string(),
-            status,
-            self.code(),
-            detail.clone(),
-            None,
-        );
         HttpResponse::build(self.status_code())
             .content_type("application/problem+json")
-            .json(problem)
+            .json(self.to_problem())
     }
 }
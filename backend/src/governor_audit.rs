@@ -34,7 +34,8 @@ pub fn rate_limit_problem_response(
     response.content_type(ContentType::json()).json(problem)
 }
 
-/// Logs [`crate::audit!("audit.rate_limit.rejected", ...)`] when the inner service returns 429.
+/// Logs [`crate::audit!("audit.rate_limit.rejected", ...)`] and counts the rejection in
+/// [`crate::metrics`] when the inner service returns 429.
 #[derive(Clone, Default)]
 pub struct AuditRateLimit429;
 
@@ -76,6 +77,7 @@ where
             .match_pattern()
             .map(|p| p.to_string())
             .unwrap_or_else(|| req.path().to_string());
+        let path = req.path().to_owned();
         let user_id = req.extensions().get::<User>().map(|u| u.id.clone());
         let client_ip = req
             .peer_addr()
@@ -86,6 +88,7 @@ where
         Box::pin(async move {
             let resp = svc.call(req).await?;
             if resp.status() == actix_web::http::StatusCode::TOO_MANY_REQUESTS {
+                crate::metrics::metrics().record_rate_limited(&path);
                 match &user_id {
                     Some(uid) => {
                         crate::audit!(
@@ -1,4 +1,5 @@
-//! Best-effort async persistence of one row per HTTP request (`http_request_audit`).
+//! Best-effort async persistence of one row per HTTP request (`http_request_audit`), plus the
+//! [`crate::security_event`] rows for audit events the request raised.
 
 use std::future::{Ready, ready};
 use std::rc::Rc;
@@ -16,6 +17,7 @@ use crate::client_attribution::{self, X_WORSHIP_CLIENT};
 use crate::database::Database;
 use crate::request_id::ApiRequestTarget;
 use crate::resources::User;
+use crate::security_event::{self, SecurityEventContext};
 
 /// Session id string for the authenticated request (set by [`crate::auth::middleware::RequireUser`]).
 #[derive(Clone)]
@@ -105,10 +107,12 @@ where
             user_agent.as_deref(),
             referer.as_deref(),
         );
+        let client_ip = req.peer_addr().map(|a| a.ip().to_string());
 
         Box::pin(async move {
-            let outcome = service.call(req).await;
-            let duration_ms = started.elapsed().as_millis() as i64;
+            let (outcome, security_events) = security_event::collect(service.call(req)).await;
+            let elapsed = started.elapsed();
+            let duration_ms = elapsed.as_millis() as i64;
 
             let (status_code, user_id, session_id, path_for_row) = match &outcome {
                 Ok(resp) => {
@@ -131,6 +135,19 @@ where
                 ),
             };
 
+            crate::metrics::metrics().observe_http(
+                &path_for_row,
+                &method,
+                status_code as u16,
+                elapsed,
+            );
+
+            let security_ctx = SecurityEventContext {
+                request_id: Some(request_id.clone()),
+                client_ip,
+                user_agent,
+                request_user_id: user_id.clone(),
+            };
             let db_inner = db_data.clone();
             let row = HttpAuditInsert {
                 request_id: request_id.clone(),
@@ -147,11 +164,23 @@ where
                 insert_row(db_inner.get_ref(), row)
                     .await
                     .expect("http_request_audit insert (test)");
+                security_event::insert_events(db_inner.get_ref(), security_events, &security_ctx)
+                    .await
+                    .expect("security_event insert (test)");
             } else {
                 tokio::spawn(async move {
                     if let Err(e) = insert_row(db_inner.get_ref(), row).await {
                         error!(error = %e, "http_request_audit insert failed");
                     }
+                    if let Err(e) = security_event::insert_events(
+                        db_inner.get_ref(),
+                        security_events,
+                        &security_ctx,
+                    )
+                    .await
+                    {
+                        error!(error = %e, "security_event insert failed");
+                    }
                 });
             }
 
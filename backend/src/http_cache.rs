@@ -1,7 +1,7 @@
 //! Weak ETags and conditional GET helpers (P2 REST review).
 
-use actix_web::HttpRequest;
-use actix_web::http::header::{IF_MATCH, IF_NONE_MATCH};
+use actix_web::http::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder};
 use ring::digest::{SHA256, digest};
 
 use crate::error::AppError;
@@ -11,11 +11,26 @@ pub fn weak_etag_from_bytes(bytes: &[u8]) -> String {
     format!("W/\"{}\"", hex::encode(d.as_ref()))
 }
 
+/// Strong ETag for bytes whose SHA-256 is already known (content-addressed blob data).
+pub fn strong_etag(sha256_hex: &str) -> String {
+    format!("\"{sha256_hex}\"")
+}
+
 pub fn weak_etag_json<T: serde::Serialize>(v: &T) -> Result<String, serde_json::Error> {
     let bytes = serde_json::to_vec(v)?;
     Ok(weak_etag_from_bytes(&bytes))
 }
 
+/// JSON response carrying the body's weak [`ETag`](weak_etag_json), so a write hands back the
+/// value its caller's next `If-Match` is checked against.
+pub fn json_with_etag<T: serde::Serialize>(
+    mut response: HttpResponseBuilder,
+    body: &T,
+) -> Result<HttpResponse, serde_json::Error> {
+    let etag = weak_etag_json(body)?;
+    Ok(response.insert_header((ETAG, etag)).json(body))
+}
+
 fn normalize_etag(s: &str) -> String {
     s.trim()
         .trim_start_matches("W/")
@@ -50,6 +65,11 @@ pub fn if_match_matches(req: &HttpRequest, current_weak_etag: &str) -> bool {
     let Ok(raw) = hdr.to_str() else {
         return false;
     };
+    if_match_value_matches(raw, current_weak_etag)
+}
+
+/// [`if_match_matches`] for an `If-Match` value that did not arrive as a header (batch operations).
+pub fn if_match_value_matches(raw: &str, current_weak_etag: &str) -> bool {
     let server = normalize_etag(current_weak_etag);
     for part in raw.split(',') {
         let client = normalize_etag(part);
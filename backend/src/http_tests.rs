@@ -72,8 +72,10 @@ fn build_app_with_api_limits(
     >,
 > {
     use crate::test_helpers::{
-        blob_service, collection_service, invitation_service, session_service, setlist_service,
-        song_service, team_service, user_service,
+        account_service, activity_service, api_token_service, batch_service, blob_service,
+        collection_service, event_service, identity_service, invitation_service, job_service,
+        organization_service, passkey_service, session_service, setlist_service, song_service,
+        sync_service, team_service, trash_service, user_service, webhook_service,
     };
 
     // Use a throwaway temp path for blob storage; blobs are not written in these tests.
@@ -96,19 +98,45 @@ fn build_app_with_api_limits(
             crate::request_id::WorshipRootSpan,
         >::new()))
         .app_data(Data::from(db.clone()))
-        .app_data(Data::new(blob_service(&db, blob_dir)))
+        .app_data(Data::new(blob_service(&db, blob_dir.clone())))
         .app_data(Data::new(collection_service(&db)))
         .app_data(Data::new(song_service(&db)))
         .app_data(Data::new(setlist_service(&db)))
         .app_data(Data::new(team_service(&db)))
         .app_data(Data::new(invitation_service(&db)))
+        .app_data(Data::new(organization_service(&db)))
+        .app_data(Data::new(activity_service(&db)))
+        .app_data(Data::new(webhook_service(&db)))
+        .app_data(Data::new(trash_service(&db, blob_dir.clone())))
+        .app_data(Data::new(account_service(&db, blob_dir.clone())))
+        .app_data(Data::new(job_service(&db)))
+        .app_data(Data::new(sync_service(&db)))
+        .app_data(Data::new(batch_service(&db, blob_dir)))
+        .app_data(Data::new(event_service(&db)))
         .app_data(Data::new(user_service(&db)))
         .app_data(Data::new(session_service(&db)))
+        .app_data(Data::new(api_token_service(&db)))
+        .app_data(Data::new(passkey_service(&db)))
+        .app_data(Data::new(identity_service(&db)))
+        .app_data(Data::new(crate::settings::Settings::default().otp_config()))
+        .app_data(Data::new(crate::mail::MailService::noop_for_tests(
+            "noreply@test.local".into(),
+        )))
+        .app_data(Data::new(Arc::new(
+            crate::auth::oidc::OidcClients::for_tests(&[
+                ("google", "Google"),
+                ("keycloak", "Church SSO"),
+            ]),
+        )))
         .app_data(Data::new(ProfilePictureLimits {
             max_bytes: 2 * 1024 * 1024,
         }))
         .app_data(cookie_cfg)
         .app_data(crate::error::json_config())
+        .app_data(Data::new(crate::metrics::rest::MetricsAccess::new(Some(
+            "test-metrics-token",
+        ))))
+        .service(crate::metrics::rest::get_metrics)
         .service(docs::rest::scope(Settings::default()))
         .service(resources::rest::scope(
             20 * 1024 * 1024,
@@ -438,7 +466,7 @@ mod openapi_problem_schema {
 #[cfg(test)]
 mod http_contract {
     use super::*;
-    use actix_web::http::StatusCode;
+    use actix_web::http::{StatusCode, header};
 
     async fn authed_token(db: &Arc<Database>, email: &str) -> String {
         let user = create_user(db, email).await.unwrap();
@@ -542,6 +570,99 @@ mod http_contract {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
+
+    /// BLC-HTTP-005: song and setlist writes return the weak `ETag` a following GET reports, so it
+    /// can be sent as the next `If-Match`; liking a song leaves its ETag unchanged.
+    #[actix_web::test]
+    async fn blc_http_005_write_etag_is_the_next_if_match() {
+        let db = test_db().await.unwrap();
+        let token = authed_token(&db, "http-etag@test.local").await;
+        let app = test::init_service(build_app(db.clone())).await;
+        let auth = ("Authorization", format!("Bearer {token}"));
+        let song_json = serde_json::json!({
+            "not_a_song": false,
+            "blobs": [],
+            "data": {"titles": ["Tagged"], "sections": []}
+        });
+        let etag_of = |resp: &actix_web::dev::ServiceResponse| {
+            resp.headers()
+                .get(header::ETAG)
+                .expect("etag header")
+                .to_str()
+                .unwrap()
+                .to_owned()
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/songs")
+            .insert_header(auth.clone())
+            .set_json(&song_json)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created = etag_of(&resp);
+        let song: serde_json::Value = test::read_body_json(resp).await;
+        let id = song["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/songs/{id}/like"))
+            .insert_header(auth.clone())
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/songs/{id}"))
+            .insert_header(auth.clone())
+            .to_request();
+        assert_eq!(etag_of(&test::call_service(&app, req).await), created);
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/songs/{id}"))
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, created))
+            .set_json(&song_json)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let updated = etag_of(&resp);
+        let req = test::TestRequest::patch()
+            .uri(&format!("/api/v1/songs/{id}"))
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, updated))
+            .set_json(serde_json::json!({"not_a_song": true}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let patched = etag_of(&resp);
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/songs/{id}"))
+            .insert_header(auth.clone())
+            .to_request();
+        assert_eq!(etag_of(&test::call_service(&app, req).await), patched);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/setlists")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({"title": "Sunday", "songs": []}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created = etag_of(&resp);
+        let setlist: serde_json::Value = test::read_body_json(resp).await;
+        let setlist_id = setlist["id"].as_str().unwrap().to_owned();
+        let req = test::TestRequest::patch()
+            .uri(&format!("/api/v1/setlists/{setlist_id}"))
+            .insert_header(auth.clone())
+            .insert_header((header::IF_MATCH, created))
+            .set_json(serde_json::json!({"title": "Sunday evening"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let patched = etag_of(&resp);
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/setlists/{setlist_id}"))
+            .insert_header(auth)
+            .to_request();
+        assert_eq!(etag_of(&test::call_service(&app, req).await), patched);
+    }
 }
 
 #[cfg(test)]
@@ -1584,6 +1705,252 @@ mod monitoring_http {
             "expected uuid-shaped 404 to count as id-like: {body:?}"
         );
     }
+
+    async fn seed_filter_rows(db: &Arc<Database>, user: &User) {
+        for (rid, path, status, with_user) in [
+            ("seed-filter-1", "/api/v1/songs", 200i64, true),
+            ("seed-filter-2", "/api/v1/songs/missing", 404, true),
+            ("seed-filter-3", "/api/v1/setlists/missing", 404, false),
+            ("seed-filter-4", "/auth/login", 401, true),
+        ] {
+            db.db
+                .query(
+                    "CREATE http_request_audit SET request_id = $rid, method = 'GET', path = $path, \
+                     status_code = $status, duration_ms = 10, session = NONE, \
+                     user = IF $with_user THEN type::record('user', $user) ELSE NONE END, \
+                     client_origin = 'unknown', created_at = d'2026-02-01T12:00:00Z'",
+                )
+                .bind(("rid", rid.to_string()))
+                .bind(("path", path.to_string()))
+                .bind(("status", status))
+                .bind(("with_user", with_user))
+                .bind(("user", user.id.clone()))
+                .await
+                .expect("seed audit")
+                .check()
+                .expect("seed audit statement ok");
+        }
+    }
+
+    /// BLC-MON-009: audit log filters combine and page links keep them.
+    #[actix_web::test]
+    async fn blc_mon_009_list_filters_by_user_status_family_and_time() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "mon-filter-user@test.local")
+            .await
+            .unwrap();
+        seed_filter_rows(&db, &user).await;
+        let (_, token) = make_admin(&db, "mon-filter-admin@test.local").await;
+        let app = test::init_service(build_app(db)).await;
+
+        let window = "since=2026-02-01T00:00:00Z&until=2026-02-02T00:00:00Z";
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/api/v1/monitoring/http-audit-logs?status=4xx&family=api_v1&{window}&page_size=1"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "2");
+        let link = resp
+            .headers()
+            .get("link")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned();
+        assert!(
+            link.contains("status=4xx") && link.contains("page=1"),
+            "{link}"
+        );
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/api/v1/monitoring/http-audit-logs?status=404&user_id={}&{window}",
+                user.id
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let body: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body.len(), 1);
+        assert_eq!(body[0]["request_id"], "seed-filter-2");
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/monitoring/http-audit-logs?status=6xx")
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+    }
+
+    /// BLC-MON-010: exports stream every matching row as NDJSON or CSV.
+    #[actix_web::test]
+    async fn blc_mon_010_export_ndjson_and_csv() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "mon-export-user@test.local")
+            .await
+            .unwrap();
+        seed_filter_rows(&db, &user).await;
+        let (_, token) = make_admin(&db, "mon-export-admin@test.local").await;
+        let app = test::init_service(build_app(db)).await;
+        let filter = "since=2026-02-01T00:00:00Z&until=2026-02-02T00:00:00Z&status=4xx";
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/api/v1/monitoring/http-audit-logs/export?{filter}"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/x-ndjson"
+        );
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        let rows: Vec<serde_json::Value> = body
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert!(
+            rows.iter()
+                .all(|row| row["status_code"].as_i64().unwrap() >= 400)
+        );
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/api/v1/monitoring/http-audit-logs/export?format=csv&family=auth&{filter}"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(
+            resp.headers()
+                .get("content-disposition")
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .contains("http-audit-logs.csv")
+        );
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        let lines: Vec<&str> = body.split_terminator("\r\n").collect();
+        assert_eq!(lines.len(), 2, "{body}");
+        assert!(lines[0].starts_with("id,request_id,created_at,"));
+        assert!(lines[1].contains(",seed-filter-4,") && lines[1].contains(",/auth/login,401,"));
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/monitoring/http-audit-logs/export?format=xml")
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+    }
+
+    /// BLC-MON-011, BLC-MON-012: admin actions are persisted with subject, actor and request id,
+    /// and the admin listing filters by event name or prefix.
+    #[actix_web::test]
+    async fn blc_mon_011_security_events_record_admin_actions() {
+        let db = test_db().await.unwrap();
+        let (admin, token) = make_admin(&db, "sec-admin@test.local").await;
+        let target = create_user(&db, "sec-target@test.local").await.unwrap();
+        let target_session = create_session_token(&db, target.clone()).await.unwrap();
+        let member = create_user(&db, "sec-member@test.local").await.unwrap();
+        let member_token = create_session_token(&db, member).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/api/v1/users/{}/sessions/{target_session}",
+                target.id
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/{}", target.id))
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/monitoring/security-events?event=audit.user.deleted")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "1");
+        let body: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(body[0]["user_id"], target.id.as_str());
+        assert_eq!(body[0]["actor_user_id"], admin.id.as_str());
+        assert!(body[0]["request_id"].is_string());
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/api/v1/monitoring/security-events?event=audit.session.&user_id={}",
+                target.id
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let body: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+        let events: Vec<&str> = body.iter().map(|e| e["event"].as_str().unwrap()).collect();
+        // The session was created outside a request, so only the revocation was captured.
+        assert_eq!(events, ["audit.session.revoked"]);
+        assert_eq!(body[0]["fields"]["session_id"], target_session.as_str());
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/monitoring/security-events?event=user.deleted")
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+        let req = test::TestRequest::get()
+            .uri("/api/v1/monitoring/security-events")
+            .insert_header(("Authorization", format!("Bearer {member_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN);
+        let req = test::TestRequest::get().uri("/api/v1/monitoring/security-events");
+        assert_eq!(call_status!(app, req), StatusCode::UNAUTHORIZED);
+    }
+
+    /// BLC-MON-013: users list only events about their own account, and see the client address
+    /// only for events they caused.
+    #[actix_web::test]
+    async fn blc_mon_013_users_list_their_own_security_events() {
+        let db = test_db().await.unwrap();
+        let (_, admin_token) = make_admin(&db, "sec-me-admin@test.local").await;
+        let user = create_user(&db, "sec-me@test.local").await.unwrap();
+        let token = create_session_token(&db, user.clone()).await.unwrap();
+        let spare = create_session_token(&db, user.clone()).await.unwrap();
+        let other = create_session_token(&db, user.clone()).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/me/sessions/{spare}"))
+            .peer_addr("198.51.100.7:4242".parse().unwrap())
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/{}/sessions/{other}", user.id))
+            .peer_addr("203.0.113.9:4242".parse().unwrap())
+            .insert_header(("Authorization", format!("Bearer {admin_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/security-events")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "2");
+        let body: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        let by_admin = &body[0];
+        assert_eq!(by_admin["fields"]["session_id"], other.as_str());
+        assert!(by_admin.get("client_ip").is_none(), "{by_admin}");
+        let by_self = &body[1];
+        assert_eq!(by_self["fields"]["session_id"], spare.as_str());
+        assert_eq!(by_self["client_ip"], "198.51.100.7");
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/security-events")
+            .insert_header(("Authorization", format!("Bearer {admin_token}")))
+            .to_request();
+        let body: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+        assert!(body.is_empty(), "{body:?}");
+    }
 }
 
 #[cfg(test)]
@@ -1751,6 +2118,26 @@ mod song_acl_http {
         let loc = resp.headers().get(LOCATION).unwrap().to_str().unwrap();
         assert!(loc.ends_with(&format!("/api/v1/songs/{new_id}")));
     }
+
+    /// BLC-SONG-024: drafting a song whose blobs have no OCR text returns **409**.
+    #[actix_web::test]
+    async fn blc_song_024_draft_without_ocr_text_returns_409() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "song-draft-http@test.local")
+            .await
+            .unwrap();
+        let song = create_song_with_title(&db, &user, "Typed Only")
+            .await
+            .unwrap();
+        let token = create_session_token(&db, user).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/songs/{}/draft-from-blobs", song.id))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+    }
 }
 
 #[cfg(test)]
@@ -1776,6 +2163,85 @@ mod blob_create_http {
     }
 }
 
+mod team_activity_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+
+    /// BLC-TACT-007: the feed is paginated with `X-Total-Count`; non-members get **404**.
+    #[actix_web::test]
+    async fn blc_tact_007_list_paginates_and_hides_foreign_teams() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "tact-http-owner@test.local")
+            .await
+            .unwrap();
+        let other = create_user(&db, "tact-http-other@test.local")
+            .await
+            .unwrap();
+        let team_id = crate::test_helpers::personal_team_id(&db, &owner)
+            .await
+            .unwrap();
+        crate::test_helpers::create_song_with_title(&db, &owner, "Feed")
+            .await
+            .unwrap();
+        let owner_token = create_session_token(&db, owner).await.unwrap();
+        let other_token = create_session_token(&db, other).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/teams/{team_id}/activity?page_size=1"))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let total = resp
+            .headers()
+            .get("x-total-count")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap();
+        assert!(total >= 2, "song and default collection, got {total}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body.as_array().map(Vec::len), Some(1));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/teams/{team_id}/activity"))
+            .insert_header(("Authorization", format!("Bearer {other_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+    }
+
+    /// BLC-TACT-005: notification preferences round-trip over HTTP; unknown values are **400**.
+    #[actix_web::test]
+    async fn blc_tact_005_notification_preferences_http() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "tact-http-prefs@test.local")
+            .await
+            .unwrap();
+        let token = create_session_token(&db, user).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/notification-preferences")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["activity_digest"], "off");
+
+        let req = test::TestRequest::put()
+            .uri("/api/v1/users/me/notification-preferences")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(serde_json::json!({ "activity_digest": "weekly" }))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["activity_digest"], "weekly");
+
+        let req = test::TestRequest::put()
+            .uri("/api/v1/users/me/notification-preferences")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(serde_json::json!({ "activity_digest": "hourly" }));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+    }
+}
+
 mod spa_fallback_guard {
     use actix_web::http::StatusCode;
     use actix_web::{App, ResponseError, test};
@@ -1842,3 +2308,1265 @@ mod spa_fallback_guard {
         assert_eq!(resp.status(), expected.status());
     }
 }
+
+mod team_webhooks_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+
+    /// BLC-WHK-001, BLC-WHK-007: the secret is only in the create response; test pings are logged.
+    #[actix_web::test]
+    async fn blc_whk_001_create_list_and_test_over_http() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "whk-http-owner@test.local").await.unwrap();
+        let other = create_user(&db, "whk-http-other@test.local").await.unwrap();
+        let team_id = crate::test_helpers::personal_team_id(&db, &owner)
+            .await
+            .unwrap();
+        let owner_token = create_session_token(&db, owner).await.unwrap();
+        let other_token = create_session_token(&db, other).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let base = format!("/api/v1/teams/{team_id}/webhooks");
+
+        let req = test::TestRequest::post()
+            .uri(&base)
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .set_json(serde_json::json!({
+                "url": "http://127.0.0.1:9/hook",
+                "events": ["song.created", "setlist.deleted"]
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(created["secret"].as_str().map(str::len), Some(64));
+        assert_eq!(created["active"], true);
+        let webhook_id = created["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::get()
+            .uri(&base)
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "1");
+        let listed: serde_json::Value = test::read_body_json(resp).await;
+        assert!(listed[0].get("secret").is_none());
+
+        let req = test::TestRequest::post()
+            .uri(&format!("{base}/{webhook_id}/test"))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")));
+        let req = req.to_request();
+        let delivery: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(delivery["event"], "ping");
+        assert_eq!(delivery["status"], "failed");
+        assert_eq!(delivery["attempts"], 1);
+        // Loopback targets are refused before connecting, so no status leaks back.
+        assert!(delivery["response_status"].is_null());
+        assert!(
+            delivery["last_error"]
+                .as_str()
+                .unwrap()
+                .contains("not a public address")
+        );
+
+        let req = test::TestRequest::get()
+            .uri(&format!("{base}/{webhook_id}/deliveries"))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")));
+        let req = req.to_request();
+        let log: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(log[0]["id"], delivery["id"]);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("{base}/{webhook_id}"))
+            .insert_header(("Authorization", format!("Bearer {other_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::post()
+            .uri(&base)
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .set_json(serde_json::json!({
+                "url": "https://example.com/hook",
+                "events": ["song.created"],
+                "secret": "mine"
+            }));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("{base}/{webhook_id}"))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+    }
+}
+
+mod team_invitation_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+
+    /// BLC-TINV-003, BLC-TINV-004, BLC-TINV-015: email invitations carry role and status; a
+    /// body-less POST still creates a guest link; revoked invitations cannot be accepted.
+    #[actix_web::test]
+    async fn blc_tinv_015_email_invitation_over_http() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "tinv-http-owner@test.local")
+            .await
+            .unwrap();
+        let invitee = create_user(&db, "tinv-http-invitee@test.local")
+            .await
+            .unwrap();
+        let stranger = create_user(&db, "tinv-http-stranger@test.local")
+            .await
+            .unwrap();
+        let team_id = crate::test_helpers::personal_team_id(&db, &owner)
+            .await
+            .unwrap();
+        let owner_token = create_session_token(&db, owner).await.unwrap();
+        let invitee_token = create_session_token(&db, invitee).await.unwrap();
+        let stranger_token = create_session_token(&db, stranger).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let base = format!("/api/v1/teams/{team_id}/invitations");
+
+        let req = test::TestRequest::post()
+            .uri(&base)
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .set_json(serde_json::json!({
+                "email": "Tinv-Http-Invitee@test.local",
+                "role": "content_maintainer"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let invitation: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(invitation["email"], "tinv-http-invitee@test.local");
+        assert_eq!(invitation["role"], "content_maintainer");
+        assert_eq!(invitation["max_uses"], 1);
+        assert_eq!(invitation["status"], "pending");
+        assert!(invitation["expires_at"].is_string());
+        let invitation_id = invitation["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::post()
+            .uri(&base)
+            .insert_header(("Authorization", format!("Bearer {owner_token}")));
+        let req = req.to_request();
+        let link: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(link["role"], "guest");
+        assert!(link["email"].is_null());
+        let link_id = link["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("{base}/{invitation_id}/accept"))
+            .insert_header(("Authorization", format!("Bearer {stranger_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("{base}/{invitation_id}/accept"))
+            .insert_header(("Authorization", format!("Bearer {invitee_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("{base}/{link_id}/revoke"))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")));
+        let req = req.to_request();
+        let revoked: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(revoked["status"], "revoked");
+
+        let req = test::TestRequest::post()
+            .uri(&format!("{base}/{link_id}/accept"))
+            .insert_header(("Authorization", format!("Bearer {stranger_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::get()
+            .uri(&base)
+            .insert_header(("Authorization", format!("Bearer {owner_token}")));
+        let req = req.to_request();
+        let listed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(listed[0]["status"], "accepted");
+        assert_eq!(listed[0]["use_count"], 1);
+        assert_eq!(listed[1]["status"], "revoked");
+
+        let req = test::TestRequest::post()
+            .uri(&base)
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .set_json(serde_json::json!({ "max_uses": 0 }));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+    }
+}
+
+mod api_token_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use shared::user::{ApiTokenScope, CreateApiToken};
+
+    const SONG_JSON: &str = r#"{
+        "not_a_song": false,
+        "blobs": [],
+        "data": { "titles": ["Imported"], "sections": [] }
+    }"#;
+
+    async fn create_token(db: &Arc<Database>, user: &User, scopes: &[ApiTokenScope]) -> String {
+        crate::test_helpers::api_token_service(db)
+            .create_token_for_user(
+                user,
+                CreateApiToken {
+                    name: "import script".into(),
+                    scopes: scopes.to_vec(),
+                    expires_at: None,
+                },
+            )
+            .await
+            .unwrap()
+            .token
+    }
+
+    /// BLC-TOK-004, BLC-TOK-005: tokens authenticate as their user, writes need a matching
+    /// scope, and token management is refused for token-authenticated requests.
+    #[actix_web::test]
+    async fn blc_tok_004_scopes_enforced_over_http() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "tok-http@test.local").await.unwrap();
+        let read = create_token(&db, &user, &[ApiTokenScope::Read]).await;
+        let songs = create_token(&db, &user, &[ApiTokenScope::SongsWrite]).await;
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me")
+            .insert_header(("Authorization", format!("Bearer {read}")))
+            .to_request();
+        let me: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(me["id"], user.id.as_str());
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/songs")
+            .insert_header(("Authorization", format!("Bearer {read}")))
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(SONG_JSON);
+        assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/songs")
+            .insert_header(("Authorization", format!("Bearer {songs}")))
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(SONG_JSON);
+        assert_eq!(call_status!(app, req), StatusCode::CREATED);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/setlists")
+            .insert_header(("Authorization", format!("Bearer {songs}")))
+            .set_json(serde_json::json!({ "title": "No", "songs": [] }));
+        assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN);
+
+        // Percent-encoded segments route like their decoded form and are refused the same way.
+        for (method, uri) in [
+            (actix_web::http::Method::GET, "/api/v1/users/me/tokens"),
+            (actix_web::http::Method::GET, "/api/v1/users/me/sessions"),
+            (actix_web::http::Method::GET, "/api/v1/users/me/%74okens"),
+            (actix_web::http::Method::POST, "/api/v1/users/me/%74okens"),
+            (actix_web::http::Method::GET, "/api/v1/users/me/%70asskeys"),
+            (actix_web::http::Method::GET, "/api/v1/users/me/se%73sions"),
+            (actix_web::http::Method::GET, "/api/v1/users/me/%65xport"),
+        ] {
+            let req = test::TestRequest::default()
+                .method(method)
+                .uri(uri)
+                .insert_header(("Authorization", format!("Bearer {songs}")));
+            assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN, "{uri}");
+        }
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/songs")
+            .insert_header(("Authorization", "Bearer wvp_0000"));
+        assert_eq!(call_status!(app, req), StatusCode::UNAUTHORIZED);
+    }
+
+    /// BLC-TOK-003: a revoked token stops authenticating immediately.
+    #[actix_web::test]
+    async fn blc_tok_003_revoked_token_is_rejected() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "tok-http-revoke@test.local")
+            .await
+            .unwrap();
+        let session = create_session_token(&db, user).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let req = test::TestRequest::post()
+            .uri("/api/v1/users/me/tokens")
+            .insert_header(("Authorization", format!("Bearer {session}")))
+            .set_json(serde_json::json!({ "name": "ci", "scopes": ["admin"] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created: serde_json::Value = test::read_body_json(resp).await;
+        let token = created["token"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/tokens")
+            .insert_header(("Authorization", format!("Bearer {session}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "1");
+        let listed: serde_json::Value = test::read_body_json(resp).await;
+        assert!(listed[0].get("token").is_none());
+        let id = listed[0]["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/me/tokens/{id}"))
+            .insert_header(("Authorization", format!("Bearer {session}")));
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/songs")
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod passkey_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use shared::auth::passkey::PasskeyCreationOptions;
+    use shared::user::{ApiTokenScope, CreateApiToken};
+
+    use crate::resources::user::passkey::soft_authenticator::SoftAuthenticator;
+
+    /// BLC-PASSKEY-001, BLC-TOK-005: passkeys are registered, listed, renamed and removed
+    /// under `/users/me/passkeys` with a login session; API tokens are refused.
+    #[actix_web::test]
+    async fn blc_passkey_001_manage_passkeys_over_http() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "passkey-http@test.local").await.unwrap();
+        let token = crate::test_helpers::api_token_service(&db)
+            .create_token_for_user(
+                &user,
+                CreateApiToken {
+                    name: "admin script".into(),
+                    scopes: vec![ApiTokenScope::Admin],
+                    expires_at: None,
+                },
+            )
+            .await
+            .unwrap()
+            .token;
+        let session = create_session_token(&db, user).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let auth = ("Authorization", format!("Bearer {session}"));
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/users/me/passkeys/options")
+            .insert_header(auth.clone())
+            .to_request();
+        let options: PasskeyCreationOptions = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(options.rp.id, "localhost");
+        let mut authenticator = SoftAuthenticator::new("localhost", "http://localhost:8080");
+        let credential = authenticator.register(&options);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/users/me/passkeys")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "name": "Phone", "credential": credential }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created: serde_json::Value = test::read_body_json(resp).await;
+        assert!(created.get("public_key").is_none());
+        let id = created["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/users/me/passkeys/{id}"))
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "name": "Old phone" }))
+            .to_request();
+        let renamed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(renamed["name"], "Old phone");
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/passkeys")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "1");
+
+        for (method, uri) in [
+            (actix_web::http::Method::GET, "/api/v1/users/me/passkeys"),
+            (
+                actix_web::http::Method::POST,
+                "/api/v1/users/me/passkeys/options",
+            ),
+        ] {
+            let req = test::TestRequest::default()
+                .method(method)
+                .uri(uri)
+                .insert_header(("Authorization", format!("Bearer {token}")));
+            assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN, "{uri}");
+        }
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/me/passkeys/{id}"))
+            .insert_header(auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/me/passkeys/{id}"))
+            .insert_header(auth);
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+    }
+}
+
+mod identity_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use shared::user::{ApiTokenScope, CreateApiToken, IdentityLinkStart};
+
+    use crate::auth::oidc::Model as OidcModel;
+
+    /// BLC-IDENT-004, BLC-TOK-005: `POST /users/me/identities` returns the provider URL and
+    /// remembers the user on the OIDC state; identities are listed and unlinked under
+    /// `/users/me/identities` with a login session, and API tokens are refused.
+    #[actix_web::test]
+    async fn blc_ident_004_link_and_unlink_over_http() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "ident-http@test.local").await.unwrap();
+        let identity = crate::test_helpers::identity_service(&db)
+            .link_identity(&user, "google", "g-http", None)
+            .await
+            .unwrap();
+        let token = crate::test_helpers::api_token_service(&db)
+            .create_token_for_user(
+                &user,
+                CreateApiToken {
+                    name: "admin script".into(),
+                    scopes: vec![ApiTokenScope::Admin],
+                    expires_at: None,
+                },
+            )
+            .await
+            .unwrap()
+            .token;
+        let session = create_session_token(&db, user.clone()).await.unwrap();
+        let app = test::init_service(build_app(db.clone())).await;
+        let auth = ("Authorization", format!("Bearer {session}"));
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/users/me/identities")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "provider": "microsoft" }));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/users/me/identities")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "provider": "keycloak", "redirect_to": "/settings" }))
+            .to_request();
+        let start: IdentityLinkStart = test::call_and_read_body_json(&app, req).await;
+        let url = openidconnect::url::Url::parse(&start.authorization_url).unwrap();
+        assert_eq!(url.host_str(), Some("keycloak.example.test"));
+        let state = url
+            .query_pairs()
+            .find(|(k, _)| k == "state")
+            .map(|(_, v)| v.into_owned())
+            .expect("state parameter");
+        let pending = OidcModel::take_oidc_state(db.as_ref(), &state)
+            .await
+            .unwrap()
+            .expect("stored state");
+        assert_eq!(pending.provider.as_str(), "keycloak");
+        assert_eq!(pending.link_user.as_deref(), Some(user.id.as_str()));
+        assert_eq!(pending.redirect_to.as_deref(), Some("/settings"));
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/identities")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "1");
+
+        for (method, uri) in [
+            (actix_web::http::Method::GET, "/api/v1/users/me/identities"),
+            (actix_web::http::Method::POST, "/api/v1/users/me/identities"),
+        ] {
+            let req = test::TestRequest::default()
+                .method(method)
+                .uri(uri)
+                .insert_header(("Authorization", format!("Bearer {token}")));
+            assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN, "{uri}");
+        }
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/me/identities/{}", identity.id))
+            .insert_header(auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/me/identities/{}", identity.id))
+            .insert_header(auth);
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+    }
+}
+
+mod organization_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+
+    /// BLC-ORG-002, BLC-ORG-004, BLC-ORG-008, BLC-TEAM-020: organization lifecycle and admin
+    /// transfer of its library team over HTTP.
+    #[actix_web::test]
+    async fn blc_org_002_organization_lifecycle_over_http() {
+        let db = test_db().await.unwrap();
+        let admin = create_user(&db, "org-http-admin@test.local").await.unwrap();
+        let member = create_user(&db, "org-http-member@test.local")
+            .await
+            .unwrap();
+        let admin_token = create_session_token(&db, admin).await.unwrap();
+        let member_token = create_session_token(&db, member.clone()).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let admin_auth = ("Authorization", format!("Bearer {admin_token}"));
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/organizations")
+            .insert_header(admin_auth.clone())
+            .set_json(serde_json::json!({ "name": "Grace" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let org: serde_json::Value = test::read_body_json(resp).await;
+        let org_id = org["id"].as_str().unwrap().to_owned();
+        let library_id = org["library_team_id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/organizations/{org_id}"))
+            .insert_header(("Authorization", format!("Bearer {member_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/teams/{library_id}"))
+            .insert_header(admin_auth.clone())
+            .set_json(serde_json::json!({
+                "name": "Grace Library",
+                "members": [
+                    { "user": { "id": org["admins"][0]["id"] }, "role": "admin" },
+                    { "user": { "id": member.id }, "role": "guest" }
+                ]
+            }));
+        assert_eq!(call_status!(app, req), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/organizations")
+            .insert_header(("Authorization", format!("Bearer {member_token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "1");
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/organizations/{org_id}"))
+            .insert_header(("Authorization", format!("Bearer {member_token}")))
+            .set_json(serde_json::json!({ "name": "Mine", "admins": [{ "id": member.id }] }));
+        assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/teams/{library_id}/transfer"))
+            .insert_header(admin_auth.clone())
+            .set_json(serde_json::json!({ "user": { "id": member.id } }))
+            .to_request();
+        let team: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let roles: Vec<(String, String)> = team["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| {
+                (
+                    m["user"]["id"].as_str().unwrap().to_owned(),
+                    m["role"].as_str().unwrap().to_owned(),
+                )
+            })
+            .collect();
+        assert!(roles.contains(&(member.id.clone(), "admin".to_owned())));
+        assert!(roles.iter().any(|(_, r)| r == "content_maintainer"));
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/organizations/{org_id}"))
+            .insert_header(admin_auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/teams/{library_id}"))
+            .insert_header(admin_auth)
+            .to_request();
+        let team: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert!(team["organization_id"].is_null());
+    }
+}
+
+mod team_role_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+
+    /// BLC-TROLE-001, BLC-TROLE-005: team roles are defined, assigned, shown on the team and
+    /// deleted over HTTP.
+    #[actix_web::test]
+    async fn blc_trole_001_team_role_lifecycle_over_http() {
+        let db = test_db().await.unwrap();
+        let fx = crate::test_helpers::TeamFixture::build(&db).await.unwrap();
+        let admin_token = create_session_token(&db, fx.admin_user.clone())
+            .await
+            .unwrap();
+        let writer_token = create_session_token(&db, fx.writer.clone()).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let admin_auth = ("Authorization", format!("Bearer {admin_token}"));
+        let team_id = fx.shared_team_id.clone();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/teams/{team_id}/roles"))
+            .insert_header(("Authorization", format!("Bearer {writer_token}")))
+            .set_json(serde_json::json!({ "name": "Planner", "permissions": [] }));
+        assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/teams/{team_id}/roles"))
+            .insert_header(admin_auth.clone())
+            .set_json(serde_json::json!({ "name": "Planner", "permissions": ["songs:read"] }));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/teams/{team_id}/roles"))
+            .insert_header(admin_auth.clone())
+            .set_json(serde_json::json!({
+                "name": "Planner",
+                "permissions": ["setlists:write"]
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let role: serde_json::Value = test::read_body_json(resp).await;
+        let role_id = role["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/teams/{team_id}"))
+            .insert_header(admin_auth.clone())
+            .set_json(serde_json::json!({
+                "name": "Fixture Shared Team",
+                "members": [
+                    { "user": { "id": fx.admin_user.id }, "role": "admin" },
+                    { "user": { "id": fx.writer.id }, "role": role_id },
+                    { "user": { "id": fx.guest.id }, "role": "guest" }
+                ]
+            }))
+            .to_request();
+        let team: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(team["roles"][0]["name"], "Planner");
+        assert_eq!(team["roles"][0]["permissions"][0], "setlists:write");
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/teams/{team_id}/roles/{role_id}"))
+            .insert_header(admin_auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/teams/{team_id}/roles/{role_id}"))
+            .insert_header(admin_auth.clone())
+            .set_json(serde_json::json!({
+                "name": "Setlist planner",
+                "permissions": ["setlists:write", "setlists:delete"]
+            }))
+            .to_request();
+        let role: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(role["name"], "Setlist planner");
+        assert_eq!(role["id"], role_id.as_str());
+    }
+}
+
+mod team_trash_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+
+    /// BLC-TRASH-001, BLC-TRASH-003, BLC-TRASH-004: a deleted setlist is listed in the team trash
+    /// and restored over HTTP.
+    #[actix_web::test]
+    async fn blc_trash_001_delete_list_and_restore_over_http() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "trash-http@test.local").await.unwrap();
+        let outsider = create_user(&db, "trash-http-out@test.local").await.unwrap();
+        let team_id = crate::test_helpers::personal_team_id(&db, &owner)
+            .await
+            .unwrap();
+        let token = create_session_token(&db, owner).await.unwrap();
+        let outsider_token = create_session_token(&db, outsider).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let auth = ("Authorization", format!("Bearer {token}"));
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/setlists")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "title": "Sunday", "songs": [] }))
+            .to_request();
+        let setlist: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let setlist_id = setlist["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/setlists/{setlist_id}"))
+            .insert_header(auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/setlists/{setlist_id}"))
+            .insert_header(auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/teams/{team_id}/trash"))
+            .insert_header(("Authorization", format!("Bearer {outsider_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/teams/{team_id}/trash"))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()
+                .get("x-total-count")
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "1"
+        );
+        let items: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(items[0]["resource_type"], "setlist");
+        assert_eq!(items[0]["resource_id"], setlist_id.as_str());
+        assert_eq!(items[0]["title"], "Sunday");
+        let entry_id = items[0]["id"].as_str().unwrap().to_owned();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/teams/{team_id}/trash/{entry_id}/restore"))
+            .insert_header(auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::NO_CONTENT);
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/setlists/{setlist_id}"))
+            .insert_header(auth.clone());
+        assert_eq!(call_status!(app, req), StatusCode::OK);
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/teams/{team_id}/trash/{entry_id}/restore"))
+            .insert_header(auth);
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+    }
+}
+
+mod blob_content_http {
+    use super::*;
+    use crate::resources::User;
+    use crate::resources::blob::storage::content_sha256;
+    use crate::test_helpers::user_service;
+    use actix_web::http::{StatusCode, header};
+    use shared::user::Role;
+
+    /// BLC-BLOB-016, BLC-BLOB-022: uploads answer with the content hash as a strong `ETag`,
+    /// which downloads repeat and `If-None-Match` honours.
+    #[actix_web::test]
+    async fn blc_blob_022_data_etag_is_content_sha256() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "blob-etag@test.local").await.unwrap();
+        let token = create_session_token(&db, user).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let auth = ("Authorization", format!("Bearer {token}"));
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/blobs")
+            .insert_header(auth.clone())
+            .set_json(
+                serde_json::json!({"file_type": "image/png", "width": 1, "height": 1, "ocr": ""}),
+            )
+            .to_request();
+        let blob: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let id = blob["id"].as_str().unwrap().to_owned();
+        assert!(blob.get("sha256").is_none());
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/blobs/{id}/data"))
+            .insert_header(auth.clone())
+            .insert_header(("Content-Type", "application/octet-stream"))
+            .set_payload(&b"etag bytes"[..])
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let etag = format!("\"{}\"", content_sha256(b"etag bytes"));
+        assert_eq!(resp.headers().get(header::ETAG).unwrap(), etag.as_str());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}/data"))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::ETAG).unwrap(), etag.as_str());
+        assert_eq!(test::read_body(resp).await.as_ref(), b"etag bytes");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}/data"))
+            .insert_header(auth.clone())
+            .insert_header((header::IF_NONE_MATCH, etag.clone()));
+        assert_eq!(call_status!(app, req), StatusCode::NOT_MODIFIED);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}"))
+            .insert_header(auth)
+            .to_request();
+        let blob: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(blob["sha256"], content_sha256(b"etag bytes"));
+    }
+
+    /// BLC-BLOB-026: `?variant=` negotiates WebP via `Accept` and rejects unknown names.
+    #[actix_web::test]
+    async fn blc_blob_026_variant_negotiates_webp() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "blob-variant@test.local").await.unwrap();
+        let token = create_session_token(&db, user).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+        let auth = ("Authorization", format!("Bearer {token}"));
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/blobs")
+            .insert_header(auth.clone())
+            .set_json(
+                serde_json::json!({"file_type": "image/png", "width": 1, "height": 1, "ocr": ""}),
+            )
+            .to_request();
+        let blob: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let id = blob["id"].as_str().unwrap().to_owned();
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgb8(640, 480)
+            .write_to(std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let hash = content_sha256(&png);
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/blobs/{id}/data"))
+            .insert_header(auth.clone())
+            .insert_header(("Content-Type", "application/octet-stream"))
+            .set_payload(png)
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NO_CONTENT
+        );
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}"))
+            .insert_header(auth.clone())
+            .to_request();
+        let blob: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(
+            (blob["width"].as_u64(), blob["height"].as_u64()),
+            (Some(640), Some(480))
+        );
+        assert_eq!(blob["variants"], serde_json::json!(["thumb", "display"]));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}/data?variant=thumb"))
+            .insert_header(auth.clone())
+            .insert_header((header::ACCEPT, "image/avif,image/webp,*/*;q=0.8"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/webp"
+        );
+        assert_eq!(resp.headers().get(header::VARY).unwrap(), "Accept");
+        let etag = format!("\"{hash}-thumb.webp\"");
+        assert_eq!(resp.headers().get(header::ETAG).unwrap(), etag.as_str());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}/data?variant=thumb"))
+            .insert_header(auth.clone())
+            .insert_header((header::ACCEPT, "image/png"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/png"
+        );
+        let thumb = image::load_from_memory(&test::read_body(resp).await).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (320, 240));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}/data?variant=original"))
+            .insert_header(auth.clone())
+            .insert_header((header::ACCEPT, "image/webp"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/png"
+        );
+        assert!(resp.headers().get(header::VARY).is_none());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/blobs/{id}/data?variant=poster"))
+            .insert_header(auth);
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+    }
+
+    /// BLC-BLOB-024: the storage scan is for platform admins only.
+    #[actix_web::test]
+    async fn blc_blob_024_verify_requires_platform_admin() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "blob-verify-user@test.local")
+            .await
+            .unwrap();
+        let user_token = create_session_token(&db, user).await.unwrap();
+        let mut admin = User::new("blob-verify-admin@test.local");
+        admin.role = Role::Admin;
+        let admin = user_service(&db).create_user(admin).await.unwrap();
+        let admin_token = create_session_token(&db, admin).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/admin/blobs/verify")
+            .insert_header(("Authorization", format!("Bearer {user_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/admin/blobs/verify")
+            .insert_header(("Authorization", format!("Bearer {admin_token}")))
+            .to_request();
+        let report: shared::blob::BlobVerifyReport = test::call_and_read_body_json(&app, req).await;
+        assert!(report.missing.is_empty());
+        assert!(report.corrupted.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod job_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use shared::job::{Job, JobKind, JobStatus};
+
+    /// BLC-JOB-001, BLC-JOB-004: users poll and cancel their own jobs; other users get 404
+    /// and finished jobs cannot be cancelled again.
+    #[actix_web::test]
+    async fn blc_job_001_poll_and_cancel_over_http() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "job-http-owner@test.local").await.unwrap();
+        let other = create_user(&db, "job-http-other@test.local").await.unwrap();
+        let job = crate::test_helpers::job_service(&db)
+            .enqueue(
+                JobKind::BlobOcr,
+                Some(&owner.id),
+                &serde_json::json!({ "blob_id": "b1" }),
+                3,
+            )
+            .await
+            .unwrap();
+        let owner_token = create_session_token(&db, owner).await.unwrap();
+        let other_token = create_session_token(&db, other).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/jobs?page=0&page_size=10")
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-total-count").unwrap(), "1");
+        let listed: Vec<Job> = test::read_body_json(resp).await;
+        assert_eq!(listed[0].id, job.id);
+        assert_eq!(listed[0].payload["blob_id"], "b1");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/jobs/{}", job.id))
+            .insert_header(("Authorization", format!("Bearer {other_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/jobs/{}", job.id))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .to_request();
+        let polled: Job = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(polled.status, JobStatus::Queued);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/jobs/{}/cancel", job.id))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .to_request();
+        let cancelled: Job = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/jobs/{}/cancel", job.id))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")));
+        assert_eq!(call_status!(app, req), StatusCode::CONFLICT);
+    }
+}
+
+mod sync_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use shared::sync::SyncChanges;
+
+    /// BLC-SYNC-001, BLC-SYNC-004: a sync without cursor is a full snapshot with a cursor to
+    /// continue from; a malformed cursor is a 400.
+    #[actix_web::test]
+    async fn blc_sync_001_snapshot_over_http() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "sync-http@test.local").await.unwrap();
+        let song = crate::test_helpers::create_song_with_title(&db, &owner, "Offline")
+            .await
+            .unwrap();
+        let token = create_session_token(&db, owner).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/sync")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let full: SyncChanges = test::call_and_read_body_json(&app, req).await;
+        assert!(full.full);
+        assert!(full.songs.iter().any(|s| s.id == song.id));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/sync?since={}", full.cursor))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let delta: SyncChanges = test::call_and_read_body_json(&app, req).await;
+        assert!(!delta.full);
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/sync?since=garbage")
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+    }
+}
+
+mod batch_http {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use shared::batch::BatchResponse;
+
+    /// BLC-BATCH-002, BLC-BATCH-004, BLC-BATCH-006: processed batches answer 200 whether they
+    /// were committed or rolled back; request-level errors are problems.
+    #[actix_web::test]
+    async fn blc_batch_004_rolled_back_batch_lists_results() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "batch-http@test.local").await.unwrap();
+        let token = create_session_token(&db, owner).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/batch")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(serde_json::json!({"operations": [
+                {"op": "create", "resource": "setlist", "ref": "s",
+                 "body": {"title": "Evening", "songs": []}},
+                {"op": "patch", "resource": "setlist", "id": "$s", "body": {"title": "Late"}}
+            ]}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: BatchResponse = test::read_body_json(resp).await;
+        assert!(body.committed);
+        assert_eq!(body.results[0].status, 201);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/batch")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(serde_json::json!({"operations": [
+                {"op": "create", "resource": "setlist", "body": {"title": "Gone", "songs": []}},
+                {"op": "delete", "resource": "collection", "id": "missing"}
+            ]}))
+            .to_request();
+        let body: BatchResponse = test::call_and_read_body_json(&app, req).await;
+        assert!(!body.committed);
+        assert_eq!(body.results.len(), 2);
+        assert_eq!(body.results[0].status, 424);
+        assert_eq!(body.results[1].status, 404);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/batch")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(serde_json::json!({"operations": []}));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+    }
+}
+
+mod event_http {
+    use super::*;
+    use actix_web::body::MessageBody;
+    use actix_web::http::{StatusCode, header};
+    use shared::event::ChangeEvent;
+    use shared::team::{ActivityAction, TrashResourceType};
+
+    /// BLC-EVENT-004, BLC-EVENT-005: the stream opens with a comment, then pushes a `change`
+    /// event for a batch write once it commits.
+    #[actix_web::test]
+    async fn blc_event_005_stream_frames_committed_batch_writes() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "events-http@test.local").await.unwrap();
+        let token = create_session_token(&db, owner).await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/events")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+        let mut body = std::pin::pin!(resp.into_body());
+        let mut next_chunk = async || {
+            let chunk = tokio::time::timeout(
+                std::time::Duration::from_secs(5),
+                std::future::poll_fn(|cx| body.as_mut().poll_next(cx)),
+            )
+            .await
+            .expect("chunk within timeout")
+            .expect("stream open")
+            .expect("chunk");
+            String::from_utf8(chunk.to_vec()).unwrap()
+        };
+        assert!(next_chunk().await.starts_with(": connected\n"));
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/batch")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(serde_json::json!({"operations": [
+                {"op": "create", "resource": "setlist", "body": {"title": "Live", "songs": []}}
+            ]}))
+            .to_request();
+        let batch: shared::batch::BatchResponse = test::call_and_read_body_json(&app, req).await;
+        assert!(batch.committed);
+
+        let frame = next_chunk().await;
+        let data = frame
+            .strip_prefix("event: change\ndata: ")
+            .and_then(|rest| rest.strip_suffix("\n\n"))
+            .expect("change frame");
+        let event: ChangeEvent = serde_json::from_str(data).unwrap();
+        assert_eq!(event.resource_type, TrashResourceType::Setlist);
+        assert_eq!(event.action, ActivityAction::Created);
+        assert_eq!(Some(event.id), batch.results[0].id);
+        assert!(event.etag.is_some());
+    }
+}
+
+mod metrics_http {
+    use super::*;
+    use actix_web::http::{StatusCode, header};
+
+    /// BLC-METRICS-001, BLC-METRICS-002: `/metrics` needs the scrape token and serves the
+    /// Prometheus text format with request counters and the session gauge.
+    #[actix_web::test]
+    async fn blc_metrics_002_scrape_requires_bearer_token() {
+        let db = test_db().await.unwrap();
+        let app = test::init_service(build_app(db)).await;
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get()
+            .uri("/metrics")
+            .insert_header(("Authorization", "Bearer wrong-token"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get().uri("/api/v1/about").to_request();
+        test::call_service(&app, req).await;
+
+        let req = test::TestRequest::get()
+            .uri("/metrics")
+            .insert_header(("Authorization", "Bearer test-metrics-token"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(
+            resp.headers()
+                .get(header::CONTENT_TYPE)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with("text/plain; version=0.0.4")
+        );
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("# TYPE worship_http_requests_total counter"));
+        assert!(body.contains("family=\"api_v1\""));
+        assert!(body.contains("worship_active_sessions "));
+    }
+}
+
+mod account_http {
+    use super::*;
+    use crate::auth::otp::Model as OtpModel;
+    use actix_web::http::{StatusCode, header};
+
+    /// BLC-USER-017, BLC-USER-020, BLC-USER-021: email change, export and deletion scheduling
+    /// over HTTP.
+    #[actix_web::test]
+    async fn blc_user_017_account_self_service_over_http() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "account-http@test.local").await.unwrap();
+        let token = create_session_token(&db, user.clone()).await.unwrap();
+        let app = test::init_service(build_app(db.clone())).await;
+        let auth = ("Authorization", format!("Bearer {token}"));
+        let otp = Settings::default().otp_config();
+
+        let req = test::TestRequest::patch()
+            .uri("/api/v1/users/me")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "otp_code": "123456" }));
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+        let req = test::TestRequest::patch()
+            .uri("/api/v1/users/me")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({ "email": "account-new@test.local" }));
+        assert_eq!(call_status!(app, req), StatusCode::ACCEPTED);
+        db.remember_otp(
+            &format!("email_change:{}:account-new@test.local", user.id),
+            "123456",
+            &otp.pepper,
+            otp.ttl_seconds,
+        )
+        .await
+        .unwrap();
+        let req = test::TestRequest::patch()
+            .uri("/api/v1/users/me")
+            .insert_header(auth.clone())
+            .set_json(serde_json::json!({
+                "email": "account-new@test.local",
+                "otp_code": "123456"
+            }))
+            .to_request();
+        let updated: User = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(updated.email, "account-new@test.local");
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/me/export")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/zip"
+        );
+        let body = test::read_body(resp).await;
+        let zip = zip::ZipArchive::new(std::io::Cursor::new(body.to_vec())).unwrap();
+        assert!(zip.file_names().any(|name| name == "profile.json"));
+
+        let req = test::TestRequest::delete()
+            .uri("/api/v1/users/me")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        let scheduled: User = test::read_body_json(resp).await;
+        assert!(scheduled.deletion_scheduled_at.is_some());
+        let req = test::TestRequest::delete()
+            .uri("/api/v1/users/me")
+            .insert_header(auth.clone())
+            .set_payload("{\"handovers\": 1}");
+        assert_eq!(call_status!(app, req), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::delete()
+            .uri("/api/v1/users/me/deletion")
+            .insert_header(auth.clone())
+            .to_request();
+        let cancelled: User = test::call_and_read_body_json(&app, req).await;
+        assert!(cancelled.deletion_scheduled_at.is_none());
+    }
+}
@@ -3,6 +3,7 @@
 pub mod about;
 pub mod accept;
 pub mod auth;
+pub mod backup;
 pub mod client_attribution;
 pub mod database;
 pub mod docs;
@@ -14,10 +15,12 @@ pub mod governor_peer;
 pub mod http_audit;
 pub mod http_cache;
 pub mod mail;
+pub mod metrics;
 pub mod observability;
 pub mod request_id;
 pub mod request_link;
 pub mod resources;
+pub mod security_event;
 pub mod settings;
 
 #[cfg(test)]
@@ -14,14 +14,30 @@ use backend::database;
 use backend::docs;
 use backend::frontend;
 use backend::mail::MailService;
+use backend::metrics::rest::MetricsAccess;
 use backend::resources;
 use backend::resources::Session;
+use backend::resources::batch::BatchServiceHandle;
+use backend::resources::blob::ocr::TesseractOcr;
 use backend::resources::blob::service::BlobServiceHandle;
+use backend::resources::blob::{BlobBackend, S3BlobStorage};
 use backend::resources::collection::service::CollectionServiceHandle;
+use backend::resources::event::EventServiceHandle;
+use backend::resources::job::{JobHandler, JobKind, JobServiceHandle};
+use backend::resources::monitoring::AuditRollupJob;
 use backend::resources::setlist::{SetlistService, SurrealSetlistRepo};
 use backend::resources::song::service::SongServiceHandle;
+use backend::resources::sync::SyncServiceHandle;
+use backend::resources::team::activity::{ActivityDigestJob, ActivityServiceHandle};
 use backend::resources::team::invitation::InvitationServiceHandle;
+use backend::resources::team::organization::OrganizationServiceHandle;
+use backend::resources::team::trash::TrashServiceHandle;
+use backend::resources::team::webhook::{ContentEventRecorder, WebhookServiceHandle};
 use backend::resources::team::{SurrealTeamResolver, TeamServiceHandle};
+use backend::resources::user::account::AccountServiceHandle;
+use backend::resources::user::api_token::ApiTokenServiceHandle;
+use backend::resources::user::identity::UserIdentityServiceHandle;
+use backend::resources::user::passkey::PasskeyServiceHandle;
 use backend::resources::user::service::UserServiceHandle;
 use backend::resources::user::session::service::SessionServiceHandle;
 use backend::resources::user::{Role as UserRole, User};
@@ -33,10 +49,28 @@ async fn main() -> AnyResult<()> {
         .install_default()
         .map_err(|e| anyhow::anyhow!("failed to install rustls ring crypto provider: {e:?}"))?;
 
-    backend::observability::init()?;
-
     let settings = Settings::from_env()?;
 
+    let _otlp = backend::observability::init(settings.otlp_config())?;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("migrate-blobs-to-s3") => return migrate_blobs_to_s3(&settings).await,
+        Some("migrate") => return migrate(&settings, &args[1..]).await,
+        Some(command @ ("backup" | "verify-backup" | "restore")) => {
+            let archive = args
+                .get(1)
+                .map(std::path::PathBuf::from)
+                .ok_or_else(|| anyhow::anyhow!("usage: backend {command} <archive.zip>"))?;
+            return match command {
+                "backup" => backup(&settings, &archive).await,
+                "verify-backup" => verify_backup(&archive),
+                _ => restore(&settings, &archive).await,
+            };
+        }
+        _ => {}
+    }
+
     let production = backend::observability::is_production();
     if production && settings.initial_admin_user_test_session {
         anyhow::bail!(
@@ -65,16 +99,7 @@ async fn main() -> AnyResult<()> {
     // has not opened `PORT` in time, even with `HOST=0.0.0.0`.
     let (db, oidc_inner) = tokio::try_join!(
         async {
-            let db = Arc::new(
-                database::Database::connect(
-                    &settings.db_address,
-                    &settings.db_namespace,
-                    &settings.db_database,
-                    settings.db_username.as_deref(),
-                    settings.db_password.as_deref(),
-                )
-                .await?,
-            );
+            let db = Arc::new(connect_database(&settings).await?);
             db.migrate(settings.db_migration_path.as_str())
                 .await
                 .context("database migration failed")?;
@@ -86,6 +111,9 @@ async fn main() -> AnyResult<()> {
 
     let user_service = UserServiceHandle::build(db.clone());
     let session_service = SessionServiceHandle::build(db.clone());
+    let api_token_service = ApiTokenServiceHandle::build(db.clone());
+    let passkey_service = PasskeyServiceHandle::build(db.clone(), settings.webauthn_config());
+    let identity_service = UserIdentityServiceHandle::build(db.clone());
 
     if let Some(email) = settings.initial_admin_user_email.as_ref() {
         let (admin, created_initial_admin) = if let Some(user) = user_service
@@ -111,6 +139,7 @@ async fn main() -> AnyResult<()> {
                     oauth_picture_url: None,
                     oauth_avatar_blob_id: None,
                     avatar_blob_id: None,
+                    deletion_scheduled_at: None,
                 })
                 .await
                 .context("failed to create admin user")?;
@@ -149,24 +178,57 @@ async fn main() -> AnyResult<()> {
         otp_ttl_seconds = settings.otp_ttl_seconds,
         otp_allow_self_signup = settings.otp_allow_self_signup,
         otp_max_attempts = settings.otp_max_attempts,
+        webauthn_rp_id = %settings.webauthn_rp_id,
+        webauthn_origin = %settings.webauthn_origin,
         auth_rate_limit_rps = settings.auth_rate_limit_rps,
         auth_rate_limit_burst = settings.auth_rate_limit_burst,
         api_rate_limit_rps = settings.api_rate_limit_rps,
         api_rate_limit_burst = settings.api_rate_limit_burst,
         blob_upload_max_bytes = settings.blob_upload_max_bytes,
         blob_dir = %settings.blob_dir,
+        blob_storage = ?settings.blob_storage,
+        ocr_command = %settings.ocr_command,
         production = production,
         static_dir = %static_dir,
         oidc_providers = ?oidc_provider_ids,
+        metrics_enabled = settings.metrics_bearer_token.is_some(),
+        otlp_endpoint = ?settings.otlp_endpoint,
         "backend starting"
     );
 
     let team_resolver = Arc::new(SurrealTeamResolver::new(db.clone()));
-    let blob_service = BlobServiceHandle::build_with_team_resolver(
+    let blob_storage = BlobBackend::from_settings(&settings)?;
+    let mut blob_service = BlobServiceHandle::build_with_team_resolver(
         db.clone(),
-        settings.blob_dir.clone(),
+        blob_storage.clone(),
         team_resolver.clone(),
     );
+    let mut job_service = JobServiceHandle::build(
+        db.clone(),
+        std::time::Duration::from_secs(settings.job_lease_seconds),
+    );
+    if let Some(tesseract) = TesseractOcr::from_settings(&settings)? {
+        blob_service = blob_service.with_ocr(
+            Arc::new(tesseract),
+            job_service.clone(),
+            settings.ocr_max_attempts,
+        );
+        job_service = job_service.with_handler(JobKind::BlobOcr, Arc::new(blob_service.clone()));
+    }
+    // Blobs uploaded before content addressing still sit in `<id><ext>` files; move them over
+    // in the background so startup is not delayed by large blob directories.
+    actix_web::rt::spawn({
+        let blob_service = blob_service.clone();
+        async move {
+            match blob_service.adopt_legacy_files().await {
+                Ok(adopted) if adopted > 0 => {
+                    info!(adopted, "legacy blob files moved to content storage");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "adopting legacy blob files failed"),
+            }
+        }
+    });
     let collection_service =
         CollectionServiceHandle::build_with_team_resolver(db.clone(), team_resolver.clone());
     let song_service =
@@ -175,15 +237,107 @@ async fn main() -> AnyResult<()> {
         SurrealSetlistRepo::new(db.clone()),
         team_resolver.clone(),
         db.clone(),
+        ContentEventRecorder::build(db.clone()),
     );
     let team_service =
         TeamServiceHandle::build_with_team_resolver(db.clone(), team_resolver.clone());
+    let batch_service =
+        BatchServiceHandle::new(db.clone(), team_resolver.clone(), blob_storage.clone());
+    let event_service = EventServiceHandle::new(db.clone(), team_resolver.clone());
+    // A gauge of this process, so every instance measures it itself rather than as a job.
+    if settings.metrics_blob_size_interval_seconds > 0 {
+        actix_web::rt::spawn(backend::metrics::run_blob_size_loop(
+            blob_storage.clone(),
+            std::time::Duration::from_secs(settings.metrics_blob_size_interval_seconds),
+        ));
+    }
+    let trash_service = TrashServiceHandle::build(
+        db.clone(),
+        team_resolver.clone(),
+        blob_storage.clone(),
+        settings.trash_retention_days,
+    );
+    let sync_service = SyncServiceHandle::build(
+        db.clone(),
+        team_resolver.clone(),
+        settings.sync_tombstone_retention_days,
+    );
+    let account_service = AccountServiceHandle::build(
+        db.clone(),
+        team_resolver.clone(),
+        blob_storage,
+        settings.account_deletion_grace_days,
+        settings.account_export_max_bytes,
+        settings.auth_rate_limit_rps,
+        settings.auth_rate_limit_burst,
+    );
     let team_resolver_data = Data::new(team_resolver);
     let invitation_service = InvitationServiceHandle::build(db.clone());
+    let organization_service = OrganizationServiceHandle::build(db.clone());
+    let activity_service = ActivityServiceHandle::build(db.clone());
+    let webhook_service = WebhookServiceHandle::build(
+        db.clone(),
+        settings.webhook_max_attempts,
+        std::time::Duration::from_secs(10),
+        settings.webhook_allow_private_targets,
+    )?;
+    // Periodic maintenance runs as scheduled jobs: each period runs once across all instances
+    // and survives restarts. An interval of 0 disables it.
+    let schedules: [(JobKind, u64, Arc<dyn JobHandler>); 6] = [
+        (
+            JobKind::ActivityDigest,
+            settings.activity_digest_interval_seconds,
+            Arc::new(ActivityDigestJob::new(
+                activity_service.clone(),
+                mail_service.clone(),
+            )),
+        ),
+        (
+            JobKind::WebhookDelivery,
+            settings.webhook_delivery_interval_seconds,
+            Arc::new(webhook_service.clone()),
+        ),
+        (
+            JobKind::TrashPurge,
+            settings.trash_purge_interval_seconds,
+            Arc::new(trash_service.clone()),
+        ),
+        (
+            JobKind::SyncPrune,
+            settings.sync_prune_interval_seconds,
+            Arc::new(sync_service.clone()),
+        ),
+        (
+            JobKind::AccountDeletion,
+            settings.account_deletion_interval_seconds,
+            Arc::new(account_service.clone()),
+        ),
+        (
+            JobKind::AuditRollup,
+            settings.audit_rollup_interval_seconds,
+            Arc::new(AuditRollupJob::new(
+                db.clone(),
+                settings.audit_retention_days,
+            )),
+        ),
+    ];
+    for (kind, seconds, handler) in schedules {
+        if seconds > 0 {
+            job_service =
+                job_service.with_schedule(kind, std::time::Duration::from_secs(seconds), handler);
+        }
+    }
+    if settings.job_poll_interval_seconds > 0 {
+        actix_web::rt::spawn(job_service.clone().run_workers(
+            settings.job_workers,
+            std::time::Duration::from_secs(settings.job_poll_interval_seconds),
+        ));
+    }
     let db_data = Data::from(db);
 
     let docs_settings = settings.clone();
     let profile_picture_limits = Data::new(settings.profile_picture_limits());
+    let metrics_access = Data::new(MetricsAccess::new(settings.metrics_bearer_token.as_deref()));
 
     HttpServer::new(move || {
         App::new()
@@ -203,11 +357,25 @@ async fn main() -> AnyResult<()> {
             .app_data(team_resolver_data.clone())
             .app_data(Data::new(team_service.clone()))
             .app_data(Data::new(invitation_service.clone()))
+            .app_data(Data::new(organization_service.clone()))
+            .app_data(Data::new(activity_service.clone()))
+            .app_data(Data::new(webhook_service.clone()))
+            .app_data(Data::new(trash_service.clone()))
+            .app_data(Data::new(job_service.clone()))
+            .app_data(Data::new(sync_service.clone()))
+            .app_data(Data::new(batch_service.clone()))
+            .app_data(Data::new(event_service.clone()))
             .app_data(Data::new(user_service.clone()))
             .app_data(Data::new(session_service.clone()))
+            .app_data(Data::new(api_token_service.clone()))
+            .app_data(Data::new(passkey_service.clone()))
+            .app_data(Data::new(identity_service.clone()))
+            .app_data(Data::new(account_service.clone()))
             .app_data(oidc_clients.clone())
             .app_data(cookie_config.clone())
             .app_data(otp_config.clone())
+            .app_data(metrics_access.clone())
+            .service(backend::metrics::rest::get_metrics)
             .service(auth::rest::scope(
                 settings.auth_rate_limit_rps,
                 settings.auth_rate_limit_burst,
@@ -226,3 +394,163 @@ async fn main() -> AnyResult<()> {
     .await
     .context("server exited unexpectedly")
 }
+
+/// `backend migrate-blobs-to-s3`: copies every file under `BLOB_DIR` into the configured S3
+/// bucket and verifies each upload by checksum. Run before switching `BLOB_STORAGE` to `s3`.
+async fn migrate_blobs_to_s3(settings: &Settings) -> AnyResult<()> {
+    let s3 = S3BlobStorage::new(settings.s3_config())?;
+    let report = s3
+        .copy_blob_dir(std::path::Path::new(&settings.blob_dir))
+        .await?;
+    info!(
+        copied = report.copied,
+        failed = report.failed.len(),
+        blob_dir = %settings.blob_dir,
+        bucket = %settings.s3_bucket,
+        "blob migration to S3 finished"
+    );
+    if !report.failed.is_empty() {
+        anyhow::bail!(
+            "{} blob file(s) were not copied: {}",
+            report.failed.len(),
+            report.failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+const MIGRATE_USAGE: &str =
+    "usage: backend migrate status | up [--dry-run] | down <script_name> [--dry-run]";
+
+/// `backend migrate ...`: migration status, forward migration and rollback without starting the
+/// HTTP server. `--dry-run` runs the scripts in a transaction that is cancelled afterwards.
+async fn migrate(settings: &Settings, args: &[String]) -> AnyResult<()> {
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let args: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--dry-run")
+        .collect();
+    let path = settings.db_migration_path.as_str();
+    let db = connect_database(settings).await?;
+    match args.as_slice() {
+        ["status"] => {
+            for m in db.migration_status(path).await? {
+                let executed_at = m
+                    .executed_at
+                    .map(|at| at.to_rfc3339())
+                    .unwrap_or_else(|| "-".to_owned());
+                let down = if m.has_down_script { "down" } else { "-" };
+                println!(
+                    "{:<10} {:<32} {:<4} {}",
+                    m.state.as_str(),
+                    executed_at,
+                    down,
+                    m.script_name
+                );
+            }
+        }
+        ["up"] if dry_run => {
+            let pending = db.migrate_dry_run(path).await?;
+            info!(
+                pending = pending.len(),
+                scripts = %pending.join(", "),
+                "migration dry run succeeded, nothing was written"
+            );
+        }
+        ["up"] => db
+            .migrate(path)
+            .await
+            .context("database migration failed")?,
+        ["down", target] => {
+            let reverted = db.rollback_migrations(path, target, dry_run).await?;
+            info!(
+                target = %target,
+                dry_run,
+                reverted = reverted.len(),
+                scripts = %reverted.join(", "),
+                "migration rollback finished"
+            );
+        }
+        _ => anyhow::bail!(MIGRATE_USAGE),
+    }
+    Ok(())
+}
+
+async fn connect_database(settings: &Settings) -> AnyResult<database::Database> {
+    database::Database::connect(
+        &settings.db_address,
+        &settings.db_namespace,
+        &settings.db_database,
+        settings.db_username.as_deref(),
+        settings.db_password.as_deref(),
+    )
+    .await
+}
+
+/// `backend backup <archive.zip>`: writes the configured database and every stored blob content
+/// into one archive and verifies it. The database is read as is, without running migrations.
+async fn backup(settings: &Settings, archive: &std::path::Path) -> AnyResult<()> {
+    let db = Arc::new(connect_database(settings).await?);
+    let storage = BlobBackend::from_settings(settings)?;
+    // Files from before content addressing are only archived once they are content-addressed.
+    let adopted = BlobServiceHandle::build_with_team_resolver(
+        db.clone(),
+        storage.clone(),
+        Arc::new(SurrealTeamResolver::new(db.clone())),
+    )
+    .adopt_legacy_files()
+    .await?;
+    if adopted > 0 {
+        info!(adopted, "legacy blob files moved to content storage");
+    }
+    let manifest = backend::backup::create_backup(&db, &storage, archive).await?;
+    info!(
+        archive = %archive.display(),
+        tables = manifest.tables.len(),
+        rows = manifest.total_rows(),
+        blobs = manifest.blobs.len(),
+        schema_version = manifest.schema_version().unwrap_or_default(),
+        "backup written and verified"
+    );
+    Ok(())
+}
+
+/// `backend verify-backup <archive.zip>`: checks every entry of an archive against its manifest
+/// checksum without touching the database.
+fn verify_backup(archive: &std::path::Path) -> AnyResult<()> {
+    let manifest = backend::backup::verify_backup(archive)?;
+    info!(
+        archive = %archive.display(),
+        created_at = %manifest.created_at,
+        backend_version = %manifest.backend_version,
+        rows = manifest.total_rows(),
+        blobs = manifest.blobs.len(),
+        schema_version = manifest.schema_version().unwrap_or_default(),
+        "backup verified"
+    );
+    Ok(())
+}
+
+/// `backend restore <archive.zip>`: loads an archive into the configured database, which must be
+/// empty, after migrating it to the schema version recorded in the backup. Start the server
+/// afterwards to apply any newer migrations.
+async fn restore(settings: &Settings, archive: &std::path::Path) -> AnyResult<()> {
+    let db = connect_database(settings).await?;
+    let storage = BlobBackend::from_settings(settings)?;
+    let manifest = backend::backup::restore_backup(
+        &db,
+        &storage,
+        settings.db_migration_path.as_str(),
+        archive,
+    )
+    .await?;
+    info!(
+        archive = %archive.display(),
+        rows = manifest.total_rows(),
+        blobs = manifest.blobs.len(),
+        schema_version = manifest.schema_version().unwrap_or_default(),
+        "backup restored"
+    );
+    Ok(())
+}
@@ -0,0 +1,303 @@
+//! Prometheus metrics for `GET /metrics`: in-process counters and histograms in the text
+//! exposition format, next to gauges read from the database and blob storage at scrape time.
+
+pub mod rest;
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use surrealdb::types::SurrealValue;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::resources::blob::BlobBackend;
+use crate::resources::blob::storage::BlobStorage;
+use crate::resources::monitoring::RouteFamily;
+
+/// Upper bounds in seconds of the HTTP request and database query duration buckets.
+const DURATION_BUCKETS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0,
+];
+
+/// Methods reported by name; anything else is counted as `OTHER` to bound label cardinality.
+const KNOWN_METHODS: [&str; 7] = ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+#[derive(Clone, Default)]
+struct Histogram {
+    /// Observations per bucket (not cumulative); the last slot is `+Inf`.
+    buckets: [u64; DURATION_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        let slot = DURATION_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(DURATION_BUCKETS.len());
+        self.buckets[slot] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (bound, count) in DURATION_BUCKETS.iter().zip(self.buckets) {
+            cumulative += count;
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {cumulative}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+/// `u64::MAX` marks a gauge that has not been measured yet.
+const UNMEASURED: u64 = u64::MAX;
+
+/// Process-wide metric values. Counters start at zero on every restart, as Prometheus expects.
+pub struct Metrics {
+    http_requests: Mutex<BTreeMap<(&'static str, &'static str, u16), u64>>,
+    http_durations: Mutex<BTreeMap<&'static str, Histogram>>,
+    db_queries: Mutex<BTreeMap<&'static str, Histogram>>,
+    rate_limited: Mutex<BTreeMap<&'static str, u64>>,
+    blob_storage_bytes: AtomicU64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            http_requests: Mutex::default(),
+            http_durations: Mutex::default(),
+            db_queries: Mutex::default(),
+            rate_limited: Mutex::default(),
+            blob_storage_bytes: AtomicU64::new(UNMEASURED),
+        }
+    }
+}
+
+static METRICS: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+/// The metrics of this process.
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Metrics {
+    /// Count a finished HTTP request and its latency under the route family of `path`.
+    pub fn observe_http(&self, path: &str, method: &str, status: u16, elapsed: Duration) {
+        let family = RouteFamily::of_path(path).as_str();
+        let method = KNOWN_METHODS
+            .into_iter()
+            .find(|known| *known == method)
+            .unwrap_or("OTHER");
+        *lock(&self.http_requests)
+            .entry((family, method, status))
+            .or_default() += 1;
+        lock(&self.http_durations)
+            .entry(family)
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn observe_db_query(&self, operation: &'static str, elapsed: Duration) {
+        lock(&self.db_queries)
+            .entry(operation)
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_rate_limited(&self, path: &str) {
+        *lock(&self.rate_limited)
+            .entry(RouteFamily::of_path(path).as_str())
+            .or_default() += 1;
+    }
+
+    pub fn set_blob_storage_bytes(&self, bytes: u64) {
+        self.blob_storage_bytes
+            .store(bytes.min(UNMEASURED - 1), Ordering::Relaxed);
+    }
+
+    /// Text exposition of every metric; `active_sessions` comes from the database per scrape.
+    pub fn render(&self, active_sessions: u64) -> String {
+        let mut out = String::new();
+
+        out.push_str(
+            "# HELP worship_http_requests_total HTTP requests by route family, method and status.\n\
+             # TYPE worship_http_requests_total counter\n",
+        );
+        for ((family, method, status), count) in lock(&self.http_requests).iter() {
+            let _ = writeln!(
+                out,
+                "worship_http_requests_total{{family=\"{family}\",method=\"{method}\",status=\"{status}\"}} {count}"
+            );
+        }
+
+        out.push_str(
+            "# HELP worship_http_request_duration_seconds HTTP request latency by route family.\n\
+             # TYPE worship_http_request_duration_seconds histogram\n",
+        );
+        for (family, histogram) in lock(&self.http_durations).iter() {
+            histogram.render(
+                &mut out,
+                "worship_http_request_duration_seconds",
+                &format!("family=\"{family}\""),
+            );
+        }
+
+        out.push_str(
+            "# HELP worship_db_query_duration_seconds Database query latency by leading statement.\n\
+             # TYPE worship_db_query_duration_seconds histogram\n",
+        );
+        for (operation, histogram) in lock(&self.db_queries).iter() {
+            histogram.render(
+                &mut out,
+                "worship_db_query_duration_seconds",
+                &format!("operation=\"{operation}\""),
+            );
+        }
+
+        out.push_str(
+            "# HELP worship_rate_limited_requests_total Requests rejected with 429 by the rate limiter.\n\
+             # TYPE worship_rate_limited_requests_total counter\n",
+        );
+        for (family, count) in lock(&self.rate_limited).iter() {
+            let _ = writeln!(
+                out,
+                "worship_rate_limited_requests_total{{family=\"{family}\"}} {count}"
+            );
+        }
+
+        let _ = write!(
+            out,
+            "# HELP worship_active_sessions Sessions that have not expired.\n\
+             # TYPE worship_active_sessions gauge\n\
+             worship_active_sessions {active_sessions}\n"
+        );
+
+        let blob_bytes = self.blob_storage_bytes.load(Ordering::Relaxed);
+        if blob_bytes != UNMEASURED {
+            let _ = write!(
+                out,
+                "# HELP worship_blob_storage_bytes Bytes held by blob storage at the last measurement.\n\
+                 # TYPE worship_blob_storage_bytes gauge\n\
+                 worship_blob_storage_bytes {blob_bytes}\n"
+            );
+        }
+        out
+    }
+}
+
+/// Label of a SurrealQL query: its first keyword when it is a common statement, else `other`.
+pub fn query_operation(query: &str) -> &'static str {
+    const OPERATIONS: [&str; 10] = [
+        "select", "create", "update", "upsert", "delete", "relate", "insert", "let", "begin",
+        "define",
+    ];
+    let keyword = query
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find(|word| !word.is_empty())
+        .unwrap_or_default();
+    OPERATIONS
+        .into_iter()
+        .find(|operation| keyword.eq_ignore_ascii_case(operation))
+        .unwrap_or("other")
+}
+
+/// Sessions whose `expires_at` lies in the future.
+pub async fn count_active_sessions(db: &Database) -> Result<u64, AppError> {
+    #[derive(Deserialize, SurrealValue)]
+    struct CountRow {
+        count: u64,
+    }
+    let mut response = db
+        .query("SELECT count() FROM session WHERE expires_at > time::now() GROUP ALL")
+        .await
+        .map_err(|e| crate::log_and_convert!(AppError::database, "metrics.sessions", e))?;
+    let rows: Vec<CountRow> = response
+        .take(0)
+        .map_err(|e| crate::log_and_convert!(AppError::database, "metrics.sessions", e))?;
+    Ok(rows.first().map_or(0, |row| row.count))
+}
+
+/// Measure the blob store every `interval`; listing it is too slow to do per scrape.
+pub async fn run_blob_size_loop(storage: BlobBackend, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match storage.stored_bytes().await {
+            Ok(bytes) => metrics().set_blob_storage_bytes(bytes),
+            Err(e) => tracing::warn!(error = %e, "measuring blob storage size failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Metrics, query_operation};
+
+    #[test]
+    fn render_groups_requests_by_route_family() {
+        let metrics = Metrics::default();
+        metrics.observe_http("/api/v1/songs", "GET", 200, Duration::from_millis(3));
+        metrics.observe_http("/api/v1/songs/1", "GET", 200, Duration::from_millis(40));
+        metrics.observe_http("/auth/login", "BREW", 405, Duration::from_secs(20));
+        metrics.record_rate_limited("/api/v1/songs");
+        metrics.observe_db_query("select", Duration::from_millis(2));
+
+        let text = metrics.render(3);
+        assert!(text.contains(
+            "worship_http_requests_total{family=\"api_v1\",method=\"GET\",status=\"200\"} 2\n"
+        ));
+        assert!(text.contains(
+            "worship_http_requests_total{family=\"auth\",method=\"OTHER\",status=\"405\"} 1\n"
+        ));
+        assert!(text.contains(
+            "worship_http_request_duration_seconds_bucket{family=\"api_v1\",le=\"0.005\"} 1\n"
+        ));
+        assert!(text.contains(
+            "worship_http_request_duration_seconds_bucket{family=\"api_v1\",le=\"0.05\"} 2\n"
+        ));
+        assert!(text.contains(
+            "worship_http_request_duration_seconds_bucket{family=\"auth\",le=\"10\"} 0\n"
+        ));
+        assert!(text.contains(
+            "worship_http_request_duration_seconds_bucket{family=\"auth\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(
+            text.contains("worship_http_request_duration_seconds_count{family=\"api_v1\"} 2\n")
+        );
+        assert!(text.contains("worship_db_query_duration_seconds_count{operation=\"select\"} 1\n"));
+        assert!(text.contains("worship_rate_limited_requests_total{family=\"api_v1\"} 1\n"));
+        assert!(text.contains("worship_active_sessions 3\n"));
+        assert!(!text.contains("worship_blob_storage_bytes"));
+
+        metrics.set_blob_storage_bytes(1024);
+        assert!(
+            metrics
+                .render(0)
+                .contains("worship_blob_storage_bytes 1024\n")
+        );
+    }
+
+    #[test]
+    fn query_operation_uses_leading_keyword() {
+        assert_eq!(query_operation("SELECT * FROM song"), "select");
+        assert_eq!(query_operation("\n  upsert type::record($id)"), "upsert");
+        assert_eq!(query_operation("LET $x = 1; SELECT * FROM $x"), "let");
+        assert_eq!(query_operation("RETURN 1"), "other");
+        assert_eq!(query_operation(""), "other");
+    }
+}
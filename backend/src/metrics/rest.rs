@@ -0,0 +1,69 @@
+use actix_web::{HttpRequest, HttpResponse, get, http::header, web::Data};
+use ring::digest;
+
+use crate::database::Database;
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+
+use super::{count_active_sessions, metrics};
+
+/// Who may scrape `GET /metrics`; built from `METRICS_BEARER_TOKEN`.
+#[derive(Clone)]
+pub struct MetricsAccess {
+    /// SHA-256 of the configured token, so requests are compared without timing leaks on it.
+    token_sha256: Option<digest::Digest>,
+}
+
+impl MetricsAccess {
+    pub fn new(bearer_token: Option<&str>) -> Self {
+        Self {
+            token_sha256: bearer_token
+                .filter(|token| !token.is_empty())
+                .map(|token| digest::digest(&digest::SHA256, token.as_bytes())),
+        }
+    }
+
+    fn check(&self, req: &HttpRequest) -> Result<(), AppError> {
+        let Some(expected) = &self.token_sha256 else {
+            return Err(AppError::NotFound("metrics are disabled".into()));
+        };
+        let presented = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| digest::digest(&digest::SHA256, token.trim().as_bytes()));
+        match presented {
+            Some(presented) if presented.as_ref() == expected.as_ref() => Ok(()),
+            _ => Err(AppError::unauthorized()),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Prometheus text exposition (version 0.0.4): `worship_http_requests_total` and `worship_http_request_duration_seconds` by route family, `worship_db_query_duration_seconds` by statement, `worship_rate_limited_requests_total`, `worship_active_sessions` and `worship_blob_storage_bytes`. Counters are per process and reset on restart.", body = String, content_type = "text/plain"),
+        (status = 401, description = "Missing or wrong metrics bearer token", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "`METRICS_BEARER_TOKEN` is not configured", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to read the metrics", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Monitoring",
+    security(
+        ("MetricsToken" = [])
+    )
+)]
+#[get("/metrics")]
+pub async fn get_metrics(
+    req: HttpRequest,
+    access: Data<MetricsAccess>,
+    db: Data<Database>,
+) -> Result<HttpResponse, AppError> {
+    access.check(&req)?;
+    let active_sessions = count_active_sessions(db.get_ref()).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(metrics().render(active_sessions)))
+}
@@ -1,9 +1,15 @@
 //! Tracing subscriber setup (`tracing` + `tracing-subscriber` + `tracing-log` bridge).
 
+mod otlp;
+
+pub use otlp::{OtlpConfig, OtlpGuard, set_remote_parent};
+
 use hex;
 use ring::digest;
 use surrealdb::Error as SurrealError;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
 
 /// Compile-time guard: audit [`crate::audit!`] events must use the `audit.` prefix.
 pub const fn audit_event_name_ok(name: &str) -> bool {
@@ -24,17 +30,29 @@ pub fn audit_email_hash(email: &str) -> String {
 }
 
 /// Structured audit line: sets `audit = true` and enforces `event` literals starting with `audit.`.
+/// Inside a request the event is also persisted to `security_event` (see [`crate::security_event`]).
 ///
 /// Fields use `ident = expr` only (use [`tracing::field::display`] / [`tracing::field::debug`] in the expr).
+/// Each expr is evaluated once; the persisted value is its `Debug` rendering.
 #[macro_export]
 macro_rules! audit {
     ($event:literal ; $msg:literal) => {
         const _: () = assert!($crate::observability::audit_event_name_ok($event));
         tracing::info!(audit = true, event = $event, $msg);
+        $crate::security_event::capture($event, &[]);
     };
     ($event:literal, $($key:ident = $value:expr),+ ; $msg:literal) => {
         const _: () = assert!($crate::observability::audit_event_name_ok($event));
-        tracing::info!(audit = true, event = $event, $($key = $value),+, $msg);
+        // `match` keeps temporaries borrowed by the exprs alive for both uses.
+        match ($($value,)+) {
+            ($($key,)+) => {
+                tracing::info!(audit = true, event = $event, $($key = $key),+, $msg);
+                $crate::security_event::capture(
+                    $event,
+                    &[$((stringify!($key), &$key as &dyn ::std::fmt::Debug)),+],
+                );
+            }
+        }
     };
 }
 
@@ -80,42 +98,48 @@ fn resolve_log_format() -> LogFormat {
     }
 }
 
-/// Installs the global `tracing` subscriber and bridges `log` crate output into it.
+/// Installs the global `tracing` subscriber and bridges `log` crate output into it. With `otlp`,
+/// spans are also exported to that OpenTelemetry collector; keep the returned guard alive until
+/// shutdown so queued spans are flushed.
 ///
 /// Call once at process startup. Safe to call from tests if no other subscriber is set;
 /// `tracing-log` init is best-effort if the global logger is already configured.
-pub fn init() -> anyhow::Result<()> {
+pub fn init(otlp: Option<OtlpConfig>) -> anyhow::Result<Option<OtlpGuard>> {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
-    match resolve_log_format() {
-        LogFormat::Json => {
-            tracing_subscriber::fmt()
-                .with_env_filter(filter)
-                .json()
-                .flatten_event(true)
-                .with_current_span(true)
-                .with_span_list(false)
-                .with_target(true)
-                .init();
-        }
-        LogFormat::Compact => {
-            tracing_subscriber::fmt()
-                .with_env_filter(filter)
-                .compact()
-                .with_target(true)
-                .init();
-        }
-        LogFormat::Pretty => {
-            tracing_subscriber::fmt()
-                .with_env_filter(filter)
-                .pretty()
-                .with_target(true)
-                .init();
+    let fmt_layer: Box<dyn Layer<Registry> + Send + Sync> = match resolve_log_format() {
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_target(true)
+            .boxed(),
+        LogFormat::Compact => tracing_subscriber::fmt::layer()
+            .compact()
+            .with_target(true)
+            .boxed(),
+        LogFormat::Pretty => tracing_subscriber::fmt::layer()
+            .pretty()
+            .with_target(true)
+            .boxed(),
+    };
+    let (otlp_layer, otlp_guard) = match otlp {
+        Some(config) => {
+            let (layer, guard) = otlp::layer(config)?;
+            (Some(layer), Some(guard))
         }
-    }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(otlp_layer)
+        .with(filter)
+        .try_init()?;
 
     let _ = tracing_log::LogTracer::init();
-    Ok(())
+    Ok(otlp_guard)
 }
 
 /// Joins [`std::error::Error::source`] links with `" <- "` (top-level message first).
@@ -0,0 +1,218 @@
+//! Export of `tracing` spans to an OpenTelemetry collector over OTLP/HTTP with JSON encoding.
+//!
+//! Spans are bridged by `tracing-opentelemetry` and exported by the SDK's batch processor, so
+//! `otel.name`, `otel.kind` and `otel.status_code` fields map to the span name, kind and status,
+//! and an `ERROR` event inside a span marks it failed. Request root spans continue the caller's
+//! trace from its W3C `traceparent` header (see [`set_remote_parent`]).
+
+use std::time::Duration;
+
+use actix_web::http::header::HeaderMap;
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Timeout for one export request to the collector.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Span targets that are not exported, so posting spans does not produce more spans to post.
+const IGNORED_TARGETS: [&str; 5] = ["hyper", "reqwest", "h2", "rustls", "tower"];
+
+/// Where spans go; see `OTLP_ENDPOINT` and `OTLP_SERVICE_NAME`.
+#[derive(Clone, Debug)]
+pub struct OtlpConfig {
+    /// Collector base URL; spans are posted to `<endpoint>/v1/traces`.
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+/// Keeps the tracer provider alive; dropping it flushes spans that are still queued.
+pub struct OtlpGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("OTLP trace exporter shutdown failed: {e}");
+        }
+    }
+}
+
+/// Build the exporter for `config` and return the layer feeding it, plus the guard that flushes
+/// it on shutdown.
+pub fn layer<S>(config: OtlpConfig) -> anyhow::Result<(impl Layer<S> + use<S>, OtlpGuard)>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpJson)
+        .with_endpoint(format!(
+            "{}/v1/traces",
+            config.endpoint.trim_end_matches('/')
+        ))
+        .with_timeout(EXPORT_TIMEOUT)
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name)
+                .build(),
+        )
+        .build();
+    let layer = layer_for(&provider);
+    Ok((layer, OtlpGuard { provider }))
+}
+
+fn layer_for<S>(provider: &SdkTracerProvider) -> impl Layer<S> + use<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+        .with_filter(filter_fn(|metadata| {
+            !IGNORED_TARGETS
+                .iter()
+                .any(|ignored| metadata.target().starts_with(ignored))
+        }))
+}
+
+/// Reads trace context from request headers.
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Make `span` a child of the remote span named by the W3C `traceparent` (and `tracestate`)
+/// headers, so it joins the caller's trace. Without a valid header the span starts a new trace.
+pub fn set_remote_parent(span: &tracing::Span, headers: &HeaderMap) {
+    let parent = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+    // Only fails when no OpenTelemetry layer is installed, i.e. export is off.
+    let _ = span.set_parent(parent);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+    use opentelemetry::trace::{SpanId, SpanKind, Status, TraceId};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{layer_for, set_remote_parent};
+
+    fn recording_provider() -> (SdkTracerProvider, InMemorySpanExporter) {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        (provider, exporter)
+    }
+
+    #[test]
+    fn spans_nest_into_one_trace_with_otel_fields() {
+        let (provider, exporter) = recording_provider();
+        let subscriber = tracing_subscriber::registry().with(layer_for(&provider));
+        tracing::subscriber::with_default(subscriber, || {
+            let root = tracing::info_span!(
+                "HTTP request",
+                otel.name = "GET /api/v1/songs",
+                otel.kind = "server",
+                http.status_code = tracing::field::Empty,
+            );
+            let _root = root.enter();
+            {
+                let child = tracing::info_span!("load", rows = 3u64);
+                let _child = child.enter();
+                tracing::error!("query failed");
+            }
+            root.record("http.status_code", 200);
+        });
+
+        let spans = exporter.get_finished_spans().expect("finished spans");
+        let [child, root] = spans.as_slice() else {
+            panic!("expected two spans, got {spans:?}");
+        };
+        assert_eq!(root.name, "GET /api/v1/songs");
+        assert_eq!(root.span_kind, SpanKind::Server);
+        assert_eq!(root.parent_span_id, SpanId::INVALID);
+        assert!(
+            root.attributes
+                .iter()
+                .any(|kv| kv.key.as_str() == "http.status_code" && kv.value.as_str() == "200")
+        );
+        assert_eq!(child.span_context.trace_id(), root.span_context.trace_id());
+        assert_eq!(child.parent_span_id, root.span_context.span_id());
+        assert!(matches!(child.status, Status::Error { .. }));
+    }
+
+    #[test]
+    fn root_span_continues_the_trace_from_traceparent() {
+        let (provider, exporter) = recording_provider();
+        let subscriber = tracing_subscriber::registry().with(layer_for(&provider));
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_static("traceparent"),
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let root = tracing::info_span!("http.request");
+            set_remote_parent(&root, &headers);
+            let _root = root.enter();
+            tracing::info_span!("load").in_scope(|| {});
+        });
+
+        let spans = exporter.get_finished_spans().expect("finished spans");
+        let [child, root] = spans.as_slice() else {
+            panic!("expected two spans, got {spans:?}");
+        };
+        let trace_id = TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").expect("trace id");
+        assert_eq!(root.span_context.trace_id(), trace_id);
+        assert_eq!(
+            root.parent_span_id,
+            SpanId::from_hex("00f067aa0ba902b7").expect("span id")
+        );
+        assert!(root.parent_span_is_remote);
+        assert_eq!(child.span_context.trace_id(), trace_id);
+        assert_eq!(child.parent_span_id, root.span_context.span_id());
+    }
+
+    #[test]
+    fn root_span_without_traceparent_starts_a_new_trace() {
+        let (provider, exporter) = recording_provider();
+        let subscriber = tracing_subscriber::registry().with(layer_for(&provider));
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_static("traceparent"),
+            HeaderValue::from_static("not-a-trace-context"),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let root = tracing::info_span!("http.request");
+            set_remote_parent(&root, &headers);
+            root.in_scope(|| {});
+        });
+
+        let spans = exporter.get_finished_spans().expect("finished spans");
+        let [root] = spans.as_slice() else {
+            panic!("expected one span, got {spans:?}");
+        };
+        assert_ne!(root.span_context.trace_id(), TraceId::INVALID);
+        assert_eq!(root.parent_span_id, SpanId::INVALID);
+    }
+}
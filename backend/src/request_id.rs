@@ -48,8 +48,8 @@ fn request_id_string(req: &ServiceRequest) -> String {
         .unwrap_or_else(|| Uuid::new_v4().to_string())
 }
 
-/// Root span for [`tracing_actix_web::TracingLogger`]: correlates logs with `traceparent` / `X-Request-Id`
-/// and records `status` / `latency_ms` on completion.
+/// Root span for [`tracing_actix_web::TracingLogger`]: correlates logs with `traceparent` / `X-Request-Id`,
+/// joins the caller's OpenTelemetry trace, and records `status` / `latency_ms` on completion.
 pub struct WorshipRootSpan;
 
 impl RootSpanBuilder for WorshipRootSpan {
@@ -70,7 +70,7 @@ impl RootSpanBuilder for WorshipRootSpan {
             .match_pattern()
             .unwrap_or_else(|| request.path().to_string());
 
-        tracing::info_span!(
+        let span = tracing::info_span!(
             "http.request",
             request_id = %id,
             method = %request.method(),
@@ -78,7 +78,9 @@ impl RootSpanBuilder for WorshipRootSpan {
             user_id = tracing::field::Empty,
             status = tracing::field::Empty,
             latency_ms = tracing::field::Empty,
-        )
+        );
+        crate::observability::set_remote_parent(&span, request.headers());
+        span
     }
 
     fn on_request_end<B: MessageBody>(span: Span, outcome: &Result<ServiceResponse<B>, Error>) {
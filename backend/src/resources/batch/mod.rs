@@ -0,0 +1,7 @@
+pub use shared::batch::{BatchOperation, BatchRequest, BatchResponse, BatchResult};
+
+pub mod service;
+
+pub use service::{BatchService, BatchServiceHandle};
+
+pub mod rest;
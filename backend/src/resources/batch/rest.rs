@@ -0,0 +1,42 @@
+use actix_web::{
+    HttpResponse, post,
+    web::{Data, Json, ReqData},
+};
+use shared::user::User;
+
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::resources::team::UserPermissions;
+
+use super::BatchRequest;
+#[allow(unused_imports)]
+use super::BatchResponse;
+use super::service::BatchServiceHandle;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/batch",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "The batch was processed. With `committed: true` every operation was applied in one transaction and `results[i]` holds the status, id and resulting resource of `operations[i]`, as the matching single-resource endpoint would have returned them. With `committed: false` an operation failed and nothing was applied: its result carries its `Problem`, every other result a `424` `batch_aborted` problem (BLC-BATCH-001..006).", body = BatchResponse),
+        (status = 400, description = "Empty batch, more than 100 operations, or a malformed request body", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to run the batch; nothing was applied", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Batch",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/batch")]
+pub async fn post_batch(
+    svc: Data<BatchServiceHandle>,
+    user: ReqData<User>,
+    payload: Json<BatchRequest>,
+) -> Result<HttpResponse, AppError> {
+    let perms = UserPermissions::from_ref(&user, &svc.teams);
+    Ok(HttpResponse::Ok().json(svc.run_for_user(&perms, payload.into_inner()).await?))
+}
@@ -0,0 +1,642 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::{error, instrument};
+
+use shared::MoveOwner;
+use shared::batch::{BatchAction, BatchOperation, BatchRequest, BatchResponse, BatchResult};
+use shared::blob::{CreateBlob, PatchBlob};
+use shared::collection::{CreateCollection, PatchCollection};
+use shared::setlist::{CreateSetlist, PatchSetlist};
+use shared::song::{CreateSong, PatchSong};
+use shared::team::TrashResourceType;
+use shared::validation_limits::MAX_BATCH_OPERATIONS;
+
+use crate::database::Database;
+use crate::error::{AppError, problem};
+use crate::http_cache::{if_match_value_matches, weak_etag_json};
+use crate::resources::blob::{BlobBackend, BlobServiceHandle};
+use crate::resources::collection::CollectionServiceHandle;
+use crate::resources::setlist::{SetlistService, SetlistServiceHandle, SurrealSetlistRepo};
+use crate::resources::song::{SongServiceHandle, song_etag};
+use crate::resources::team::webhook::ContentEventRecorder;
+use crate::resources::team::{SurrealTeamResolver, UserPermissions};
+
+type Perms = UserPermissions<SurrealTeamResolver>;
+
+/// Application service for `POST /api/v1/batch`: runs library writes through the regular
+/// services inside one database transaction.
+#[derive(Clone)]
+pub struct BatchService {
+    db: Arc<Database>,
+    pub teams: Arc<SurrealTeamResolver>,
+    blob_storage: BlobBackend,
+}
+
+pub type BatchServiceHandle = BatchService;
+
+/// Song, collection, setlist and blob services whose repositories write into one transaction.
+struct TransactionServices {
+    songs: SongServiceHandle,
+    collections: CollectionServiceHandle,
+    setlists: SetlistServiceHandle,
+    blobs: BlobServiceHandle,
+}
+
+impl TransactionServices {
+    fn new(txn: &Arc<Database>, teams: &Arc<SurrealTeamResolver>, storage: BlobBackend) -> Self {
+        Self {
+            songs: SongServiceHandle::build_with_team_resolver(txn.clone(), teams.clone()),
+            collections: CollectionServiceHandle::build_with_team_resolver(
+                txn.clone(),
+                teams.clone(),
+            ),
+            setlists: SetlistService::new(
+                SurrealSetlistRepo::new(txn.clone()),
+                teams.clone(),
+                txn.clone(),
+                ContentEventRecorder::build(txn.clone()),
+            ),
+            blobs: BlobServiceHandle::build_with_team_resolver(txn.clone(), storage, teams.clone()),
+        }
+    }
+}
+
+/// Result of one successful operation before it is rendered into a [`BatchResult`].
+struct Applied {
+    status: u16,
+    id: String,
+    resource: Option<Value>,
+}
+
+impl Applied {
+    fn written<T: Serialize>(status: u16, id: String, resource: &T) -> Result<Self, AppError> {
+        let resource = serde_json::to_value(resource)
+            .map_err(|e| AppError::internal_from_err("batch.result", e))?;
+        Ok(Self {
+            status,
+            id,
+            resource: Some(resource),
+        })
+    }
+
+    fn deleted(id: String) -> Self {
+        Self {
+            status: 204,
+            id,
+            resource: None,
+        }
+    }
+}
+
+fn payload<T: DeserializeOwned>(body: Value) -> Result<T, AppError> {
+    serde_json::from_value(body)
+        .map_err(|e| AppError::invalid_request(format!("invalid operation body: {e}")))
+}
+
+/// `$<ref>` names the id created by an earlier operation; anything else is a literal id.
+fn resolve_ref(refs: &HashMap<String, String>, id: &str) -> Result<String, AppError> {
+    match id.strip_prefix('$') {
+        Some(name) => refs
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AppError::invalid_request(format!("unknown batch reference `{id}`"))),
+        None => Ok(id.to_owned()),
+    }
+}
+
+/// Resolve references in the id-valued body fields: song links of collections and setlists and
+/// blob links of songs.
+fn resolve_body_refs(
+    refs: &HashMap<String, String>,
+    resource: TrashResourceType,
+    body: &mut Value,
+) -> Result<(), AppError> {
+    let field = match resource {
+        TrashResourceType::Song => "blobs",
+        TrashResourceType::Collection | TrashResourceType::Setlist => "songs",
+        TrashResourceType::Blob => return Ok(()),
+    };
+    let Some(links) = body.get_mut(field).and_then(Value::as_array_mut) else {
+        return Ok(());
+    };
+    for link in links {
+        if let Some(Value::String(id)) = link.get_mut("id") {
+            *id = resolve_ref(refs, id)?;
+        }
+    }
+    Ok(())
+}
+
+/// The operation's `if_match`, when given, must match the current representation.
+fn check_if_match<T: Serialize>(if_match: Option<&str>, current: &T) -> Result<(), AppError> {
+    check_etag(
+        if_match,
+        weak_etag_json(current).map_err(|e| AppError::internal_from_err("batch.etag", e))?,
+    )
+}
+
+/// The operation's `if_match`, when given, must match `etag`.
+fn check_etag(if_match: Option<&str>, etag: String) -> Result<(), AppError> {
+    let Some(if_match) = if_match else {
+        return Ok(());
+    };
+    if if_match_value_matches(if_match, &etag) {
+        Ok(())
+    } else {
+        Err(AppError::precondition_failed())
+    }
+}
+
+fn aborted(detail: String) -> BatchResult {
+    BatchResult {
+        status: 424,
+        id: None,
+        resource: None,
+        problem: Some(problem(424, "batch_aborted", detail)),
+    }
+}
+
+impl BatchService {
+    pub fn new(
+        db: Arc<Database>,
+        teams: Arc<SurrealTeamResolver>,
+        blob_storage: BlobBackend,
+    ) -> Self {
+        Self {
+            db,
+            teams,
+            blob_storage,
+        }
+    }
+
+    /// Apply every operation or none (BLC-BATCH-001..006). On failure the failing operation
+    /// carries its problem and every other operation a `batch_aborted` problem.
+    #[instrument(level = "debug", err, skip(self, perms, request),
+        fields(user_id = %perms.user().id, operations = request.operations.len()))]
+    pub async fn run_for_user(
+        &self,
+        perms: &Perms,
+        request: BatchRequest,
+    ) -> Result<BatchResponse, AppError> {
+        let total = request.operations.len();
+        if total == 0 {
+            return Err(AppError::invalid_request(
+                "a batch needs at least one operation",
+            ));
+        }
+        if total > MAX_BATCH_OPERATIONS {
+            return Err(AppError::invalid_request(format!(
+                "a batch may contain at most {MAX_BATCH_OPERATIONS} operations"
+            )));
+        }
+
+        let txn = Arc::new(self.db.begin().await?);
+        let services = TransactionServices::new(&txn, &self.teams, self.blob_storage.clone());
+        let mut refs = HashMap::new();
+        let mut applied = Vec::with_capacity(total);
+        let mut failure = None;
+        for (index, operation) in request.operations.into_iter().enumerate() {
+            match apply(&services, perms, &mut refs, operation).await {
+                Ok(done) => applied.push(done),
+                Err(err) => {
+                    failure = Some((index, err));
+                    break;
+                }
+            }
+        }
+        drop(services);
+        let txn = Arc::into_inner(txn)
+            .ok_or_else(|| AppError::database("batch transaction is still in use"))?;
+
+        let Some((failed, err)) = failure else {
+            txn.commit().await?;
+            let results = applied
+                .into_iter()
+                .map(|done| BatchResult {
+                    status: done.status,
+                    id: Some(done.id),
+                    resource: done.resource,
+                    problem: None,
+                })
+                .collect();
+            return Ok(BatchResponse {
+                committed: true,
+                results,
+            });
+        };
+
+        txn.cancel().await?;
+        if matches!(err, AppError::Internal(_)) {
+            error!(error = %err, operation = failed, "batch operation failed");
+        }
+        let mut results: Vec<BatchResult> = (0..failed)
+            .map(|_| aborted(format!("rolled back because operation {failed} failed")))
+            .collect();
+        let problem = err.to_problem();
+        results.push(BatchResult {
+            status: problem.status,
+            id: None,
+            resource: None,
+            problem: Some(problem),
+        });
+        results.extend(
+            (failed + 1..total)
+                .map(|_| aborted(format!("not attempted because operation {failed} failed"))),
+        );
+        Ok(BatchResponse {
+            committed: false,
+            results,
+        })
+    }
+}
+
+async fn apply(
+    services: &TransactionServices,
+    perms: &Perms,
+    refs: &mut HashMap<String, String>,
+    operation: BatchOperation,
+) -> Result<Applied, AppError> {
+    let BatchOperation {
+        op,
+        resource,
+        id,
+        reference,
+        if_match,
+        mut body,
+    } = operation;
+    if let Some(name) = &reference {
+        if op != BatchAction::Create {
+            return Err(AppError::invalid_request(
+                "`ref` is only allowed on create operations",
+            ));
+        }
+        if name.is_empty() || refs.contains_key(name) {
+            return Err(AppError::invalid_request(format!(
+                "batch reference `{name}` is empty or already defined"
+            )));
+        }
+    }
+    resolve_body_refs(refs, resource, &mut body)?;
+    let id = match (op, id) {
+        (BatchAction::Create, None) => None,
+        (BatchAction::Create, Some(_)) => {
+            return Err(AppError::invalid_request(
+                "`id` is not allowed on create operations; the server assigns it",
+            ));
+        }
+        (_, None) => {
+            return Err(AppError::invalid_request(
+                "`id` is required for patch, move and delete operations",
+            ));
+        }
+        (_, Some(id)) => Some(resolve_ref(refs, &id)?),
+    };
+
+    let if_match = if_match.as_deref();
+    let applied = match resource {
+        TrashResourceType::Song => apply_song(&services.songs, perms, op, id, if_match, body).await,
+        TrashResourceType::Collection => {
+            apply_collection(&services.collections, perms, op, id, if_match, body).await
+        }
+        TrashResourceType::Setlist => {
+            apply_setlist(&services.setlists, perms, op, id, if_match, body).await
+        }
+        TrashResourceType::Blob => apply_blob(&services.blobs, perms, op, id, if_match, body).await,
+    }?;
+    if let Some(name) = reference {
+        refs.insert(name, applied.id.clone());
+    }
+    Ok(applied)
+}
+
+async fn apply_song(
+    svc: &SongServiceHandle,
+    perms: &Perms,
+    op: BatchAction,
+    id: Option<String>,
+    if_match: Option<&str>,
+    body: Value,
+) -> Result<Applied, AppError> {
+    let Some(id) = id else {
+        let song: CreateSong = payload(body)?;
+        song.validate().map_err(AppError::invalid_request)?;
+        let created = svc.create_song_for_user(perms, song).await?;
+        return Applied::written(201, created.id.clone(), &created);
+    };
+    let current = svc.get_song_for_user(perms, &id).await?;
+    check_etag(
+        if_match,
+        song_etag(&current).map_err(|e| AppError::internal_from_err("batch.etag", e))?,
+    )?;
+    match op {
+        BatchAction::Patch => {
+            let patched = svc
+                .patch_song_for_user(perms, &id, payload::<PatchSong>(body)?)
+                .await?;
+            Applied::written(200, id, &patched)
+        }
+        BatchAction::Move => {
+            let moved = svc
+                .move_song_for_user(perms, &id, payload::<MoveOwner>(body)?)
+                .await?;
+            Applied::written(200, id, &moved)
+        }
+        BatchAction::Delete | BatchAction::Create => {
+            svc.delete_song_for_user(perms, &id).await?;
+            Ok(Applied::deleted(id))
+        }
+    }
+}
+
+async fn apply_collection(
+    svc: &CollectionServiceHandle,
+    perms: &Perms,
+    op: BatchAction,
+    id: Option<String>,
+    if_match: Option<&str>,
+    body: Value,
+) -> Result<Applied, AppError> {
+    let Some(id) = id else {
+        let created = svc
+            .create_collection_for_user(perms, payload::<CreateCollection>(body)?)
+            .await?;
+        return Applied::written(201, created.id.clone(), &created);
+    };
+    check_if_match(if_match, &svc.get_collection_for_user(perms, &id).await?)?;
+    match op {
+        BatchAction::Patch => {
+            let patched = svc
+                .patch_collection_for_user(perms, &id, payload::<PatchCollection>(body)?)
+                .await?;
+            Applied::written(200, id, &patched)
+        }
+        BatchAction::Move => {
+            let moved = svc
+                .move_collection_for_user(perms, &id, payload::<MoveOwner>(body)?)
+                .await?;
+            Applied::written(200, id, &moved)
+        }
+        BatchAction::Delete | BatchAction::Create => {
+            svc.delete_collection_for_user(perms, &id).await?;
+            Ok(Applied::deleted(id))
+        }
+    }
+}
+
+async fn apply_setlist(
+    svc: &SetlistServiceHandle,
+    perms: &Perms,
+    op: BatchAction,
+    id: Option<String>,
+    if_match: Option<&str>,
+    body: Value,
+) -> Result<Applied, AppError> {
+    let Some(id) = id else {
+        let created = svc
+            .create_setlist_for_user(perms, payload::<CreateSetlist>(body)?)
+            .await?;
+        return Applied::written(201, created.id.clone(), &created);
+    };
+    check_if_match(if_match, &svc.get_setlist_for_user(perms, &id).await?)?;
+    match op {
+        BatchAction::Patch => {
+            let patched = svc
+                .patch_setlist_for_user(perms, &id, payload::<PatchSetlist>(body)?)
+                .await?;
+            Applied::written(200, id, &patched)
+        }
+        BatchAction::Move => {
+            let moved = svc
+                .move_setlist_for_user(perms, &id, payload::<MoveOwner>(body)?)
+                .await?;
+            Applied::written(200, id, &moved)
+        }
+        BatchAction::Delete | BatchAction::Create => {
+            svc.delete_setlist_for_user(perms, &id).await?;
+            Ok(Applied::deleted(id))
+        }
+    }
+}
+
+async fn apply_blob(
+    svc: &BlobServiceHandle,
+    perms: &Perms,
+    op: BatchAction,
+    id: Option<String>,
+    if_match: Option<&str>,
+    body: Value,
+) -> Result<Applied, AppError> {
+    let Some(id) = id else {
+        let created = svc
+            .create_blob_for_user(perms, payload::<CreateBlob>(body)?)
+            .await?;
+        return Applied::written(201, created.id.clone(), &created);
+    };
+    check_if_match(if_match, &svc.get_blob_for_user(perms, &id).await?)?;
+    match op {
+        BatchAction::Patch => {
+            let patched = svc
+                .patch_blob_for_user(perms, &id, payload::<PatchBlob>(body)?)
+                .await?;
+            Applied::written(200, id, &patched)
+        }
+        BatchAction::Move => {
+            let moved = svc
+                .move_blob_for_user(perms, &id, payload::<MoveOwner>(body)?)
+                .await?;
+            Applied::written(200, id, &moved)
+        }
+        BatchAction::Delete | BatchAction::Create => {
+            svc.delete_blob_for_user(perms, &id).await?;
+            Ok(Applied::deleted(id))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use shared::api::ListQuery;
+    use shared::batch::{BatchRequest, BatchResponse};
+    use shared::collection::Collection;
+
+    use crate::resources::team::UserPermissions;
+    use crate::resources::user::User;
+    use crate::test_helpers::{
+        batch_service, collection_service, create_song_with_title, create_user, song_service,
+        test_db,
+    };
+
+    use super::BatchServiceHandle;
+
+    async fn run(
+        svc: &BatchServiceHandle,
+        user: &User,
+        operations: serde_json::Value,
+    ) -> BatchResponse {
+        let request: BatchRequest =
+            serde_json::from_value(json!({ "operations": operations })).expect("batch request");
+        let perms = UserPermissions::from_ref(user, &svc.teams);
+        svc.run_for_user(&perms, request).await.expect("batch")
+    }
+
+    fn blob_dir() -> String {
+        std::env::temp_dir()
+            .join("worshipviewer_batch_tests_blobs")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// BLC-BATCH-001, BLC-BATCH-003: later operations see ids created earlier via `$ref`, and
+    /// everything is committed together.
+    #[tokio::test]
+    async fn blc_batch_001_creates_and_links_with_refs() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "batch-refs@test.local").await.unwrap();
+        let svc = batch_service(&db, blob_dir());
+
+        let response = run(
+            &svc,
+            &user,
+            json!([
+                {"op": "create", "resource": "blob", "ref": "scan",
+                 "body": {"file_type": "image/png", "width": 10, "height": 10, "ocr": ""}},
+                {"op": "create", "resource": "song", "ref": "song",
+                 "body": {"not_a_song": false, "blobs": [{"id": "$scan"}],
+                          "data": {"titles": ["Batch"], "sections": []}}},
+                {"op": "create", "resource": "collection", "ref": "col",
+                 "body": {"title": "Sunday", "cover": "", "songs": [{"id": "$song"}]}},
+                {"op": "patch", "resource": "collection", "id": "$col", "body": {"title": "Sunday AM"}}
+            ]),
+        )
+        .await;
+
+        assert!(response.committed);
+        let statuses: Vec<u16> = response.results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, vec![201, 201, 201, 200]);
+        let song_id = response.results[1].id.clone().unwrap();
+        let collection: Collection =
+            serde_json::from_value(response.results[3].resource.clone().unwrap()).unwrap();
+        assert_eq!(collection.title, "Sunday AM");
+        assert_eq!(collection.songs[0].id, song_id);
+
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&user, &songs.teams);
+        let song = songs.get_song_for_user(&perms, &song_id).await.unwrap();
+        assert_eq!(Some(&song.blobs[0].id), response.results[0].id.as_ref());
+    }
+
+    /// BLC-BATCH-002, BLC-BATCH-004: a failing operation rolls back the ones before it; it carries
+    /// its own problem and every other operation `batch_aborted`.
+    #[tokio::test]
+    async fn blc_batch_002_failure_rolls_back_everything() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "batch-rollback@test.local").await.unwrap();
+        let existing = create_song_with_title(&db, &user, "Keep").await.unwrap();
+        let svc = batch_service(&db, blob_dir());
+
+        let response = run(
+            &svc,
+            &user,
+            json!([
+                {"op": "create", "resource": "collection",
+                 "body": {"title": "Ghost", "cover": "", "songs": []}},
+                {"op": "delete", "resource": "song", "id": existing.id},
+                {"op": "patch", "resource": "setlist", "id": "missing", "body": {"title": "x"}},
+                {"op": "delete", "resource": "song", "id": existing.id}
+            ]),
+        )
+        .await;
+
+        assert!(!response.committed);
+        let codes: Vec<&str> = response
+            .results
+            .iter()
+            .map(|r| r.problem.as_ref().unwrap().code.as_str())
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                "batch_aborted",
+                "batch_aborted",
+                "not_found",
+                "batch_aborted"
+            ]
+        );
+        assert_eq!(response.results[2].status, 404);
+
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&user, &songs.teams);
+        assert!(songs.get_song_for_user(&perms, &existing.id).await.is_ok());
+        let collections = collection_service(&db);
+        let listed = collections
+            .list_collections_for_user(&perms, ListQuery::default())
+            .await
+            .unwrap();
+        assert!(listed.iter().all(|c| c.title != "Ghost"));
+    }
+
+    /// BLC-BATCH-005: a stale `if_match` fails the batch with 412.
+    #[tokio::test]
+    async fn blc_batch_005_if_match_is_checked() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "batch-etag@test.local").await.unwrap();
+        let song = create_song_with_title(&db, &user, "Tagged").await.unwrap();
+        let svc = batch_service(&db, blob_dir());
+        let etag = crate::resources::song::song_etag(&song).unwrap();
+
+        let stale = run(
+            &svc,
+            &user,
+            json!([{"op": "delete", "resource": "song", "id": song.id, "if_match": "W/\"stale\""}]),
+        )
+        .await;
+        assert!(!stale.committed);
+        assert_eq!(stale.results[0].status, 412);
+
+        let current = run(
+            &svc,
+            &user,
+            json!([{"op": "delete", "resource": "song", "id": song.id, "if_match": etag}]),
+        )
+        .await;
+        assert!(current.committed);
+        assert_eq!(current.results[0].status, 204);
+    }
+
+    /// BLC-BATCH-003, BLC-BATCH-006: unknown references, `ref` outside create, `id` on create and
+    /// empty batches are invalid requests.
+    #[tokio::test]
+    async fn blc_batch_006_rejects_malformed_operations() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "batch-invalid@test.local").await.unwrap();
+        let svc = batch_service(&db, blob_dir());
+
+        for operation in [
+            json!({"op": "delete", "resource": "song", "id": "$nope"}),
+            json!({"op": "delete", "resource": "song", "id": "x", "ref": "a"}),
+            json!({"op": "create", "resource": "collection", "id": "x",
+                   "body": {"title": "t", "cover": "", "songs": []}}),
+            json!({"op": "patch", "resource": "song", "body": {}}),
+            json!({"op": "create", "resource": "setlist", "body": {"title": 3}}),
+        ] {
+            let response = run(&svc, &user, json!([operation])).await;
+            assert!(!response.committed);
+            assert_eq!(response.results[0].status, 400, "{operation}");
+        }
+
+        let perms = UserPermissions::from_ref(&user, &svc.teams);
+        let empty = svc
+            .run_for_user(&perms, BatchRequest { operations: vec![] })
+            .await;
+        assert!(matches!(
+            empty,
+            Err(crate::error::AppError::InvalidRequest(_))
+        ));
+    }
+}
@@ -1,14 +1,19 @@
 pub use shared::blob::{Blob, CreateBlob, PatchBlob, UpdateBlob};
 
 mod model;
+pub mod ocr;
 mod repository;
+pub mod s3;
 pub mod service;
 pub mod storage;
 mod surreal_repo;
+pub mod variants;
 
+pub(crate) use model::BlobRecord;
 pub use repository::BlobRepository;
+pub use s3::S3BlobStorage;
 pub use service::{BlobService, BlobServiceHandle};
-pub use storage::FsBlobStorage;
+pub use storage::{BlobBackend, FsBlobStorage};
 pub use surreal_repo::SurrealBlobRepo;
 
 pub mod rest;
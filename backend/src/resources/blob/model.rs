@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use surrealdb::types::{Datetime, Kind, RecordId, SurrealValue, Value, kind};
 
-use shared::blob::{Blob, CreateBlob, FileType};
+use shared::blob::{Blob, BlobOcrStatus, CreateBlob, FileType};
 
 use crate::database::record_id_string;
 
@@ -44,6 +44,28 @@ pub struct BlobRecord {
     pub ocr: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub created_at: Option<Datetime>,
+    /// Set while the blob is in its team's trash; its bytes are kept until purge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trashed_at: Option<Datetime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Wire names of the resized variants generated from `sha256`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_error: Option<String>,
+}
+
+fn ocr_status_from_str(status: &str) -> Option<BlobOcrStatus> {
+    match status {
+        "pending" => Some(BlobOcrStatus::Pending),
+        "running" => Some(BlobOcrStatus::Running),
+        "done" => Some(BlobOcrStatus::Done),
+        "failed" => Some(BlobOcrStatus::Failed),
+        _ => None,
+    }
 }
 
 impl BlobRecord {
@@ -55,6 +77,15 @@ impl BlobRecord {
             width: self.width,
             height: self.height,
             ocr: self.ocr,
+            sha256: self.sha256,
+            variants: self
+                .variants
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|v| v.parse().ok())
+                .collect(),
+            ocr_status: self.ocr_status.as_deref().and_then(ocr_status_from_str),
+            ocr_error: self.ocr_error,
         }
     }
 
@@ -79,6 +110,11 @@ impl BlobRecord {
             height,
             ocr,
             created_at,
+            trashed_at: None,
+            sha256: None,
+            variants: None,
+            ocr_status: None,
+            ocr_error: None,
         }
     }
 }
@@ -110,5 +146,8 @@ mod tests {
         assert_eq!(b.width, 640);
         assert_eq!(b.height, 480);
         assert_eq!(b.ocr, "text");
+        assert_eq!(b.sha256, None);
+        assert!(b.variants.is_empty());
+        assert_eq!(b.ocr_status, None);
     }
 }
@@ -0,0 +1,317 @@
+//! Text extraction from uploaded sheet-music images, run as `blob.ocr` background jobs.
+
+use std::io::{Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use shared::blob::FileType;
+
+use crate::error::AppError;
+use crate::settings::Settings;
+
+/// Recognises text in image bytes. Implementations block; the worker calls them on a blocking
+/// thread. Enables fakes in tests.
+pub trait OcrEngine: Send + Sync {
+    fn recognize(&self, file_type: &FileType, data: &[u8]) -> Result<String, AppError>;
+}
+
+/// How often a running Tesseract is checked for having exited.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Whether OCR runs for blobs of `file_type` (raster images only).
+pub fn supports(file_type: &FileType) -> bool {
+    matches!(file_type, FileType::PNG | FileType::JPEG)
+}
+
+/// Runs the Tesseract CLI with the image on stdin and reads plain text from stdout.
+pub struct TesseractOcr {
+    command: String,
+    languages: String,
+    timeout: Duration,
+}
+
+impl TesseractOcr {
+    /// `None` when `OCR_COMMAND` is empty. Otherwise checks that the command starts and has
+    /// data for every `OCR_LANGUAGES` entry, so a missing install fails at startup instead of
+    /// in every OCR job.
+    pub fn from_settings(settings: &Settings) -> Result<Option<Self>, AppError> {
+        let command = settings.ocr_command.trim();
+        if command.is_empty() {
+            return Ok(None);
+        }
+        let engine = Self {
+            command: command.to_owned(),
+            languages: settings.ocr_languages.trim().to_owned(),
+            timeout: Duration::from_secs(settings.ocr_timeout_seconds.max(1)),
+        };
+        engine.check_languages()?;
+        Ok(Some(engine))
+    }
+
+    fn check_languages(&self) -> Result<(), AppError> {
+        let output = Command::new(&self.command)
+            .arg("--list-langs")
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| {
+                AppError::Internal(format!(
+                    "OCR_COMMAND '{}' could not be started ({e}); install Tesseract or leave OCR_COMMAND empty to disable OCR",
+                    self.command
+                ))
+            })?;
+        if !output.status.success() {
+            return Err(AppError::Internal(format!(
+                "OCR_COMMAND '{} --list-langs' exited with {}: {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        // Tesseract 4+ lists languages on stdout, older versions on stderr; the first line is a
+        // heading.
+        let listing = [output.stdout, output.stderr]
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .join("\n");
+        let installed: Vec<&str> = listing
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("List of"))
+            .collect();
+        let missing: Vec<&str> = self
+            .languages
+            .split('+')
+            .map(str::trim)
+            .filter(|lang| !installed.contains(lang))
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::Internal(format!(
+                "OCR_LANGUAGES '{}' needs Tesseract data that is not installed: {} (installed: {})",
+                self.languages,
+                missing.join(", "),
+                installed.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl OcrEngine for TesseractOcr {
+    fn recognize(&self, _file_type: &FileType, data: &[u8]) -> Result<String, AppError> {
+        let mut child = Command::new(&self.command)
+            .args(["stdin", "stdout", "-l", &self.languages])
+            // Keeping runs of spaces preserves the column of chords written above lyrics.
+            .args(["-c", "preserve_interword_spaces=1"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| AppError::Internal(format!("failed to start {}: {e}", self.command)))?;
+        // Feed stdin and drain stdout and stderr on their own threads, so a full pipe on either
+        // side cannot block the other, while this thread enforces the timeout.
+        let (status, written, stdout, stderr) = thread::scope(|scope| {
+            let stdin = child.stdin.take();
+            let writer = scope.spawn(move || match stdin {
+                Some(mut stdin) => stdin.write_all(data),
+                None => Ok(()),
+            });
+            let stdout = child.stdout.take();
+            let stdout = scope.spawn(move || read_pipe(stdout));
+            let stderr = child.stderr.take();
+            let stderr = scope.spawn(move || read_pipe(stderr));
+            let status = wait_with_timeout(&mut child, self.timeout);
+            let join = |e| std::io::Error::other(format!("OCR pipe thread panicked: {e:?}"));
+            (
+                status,
+                writer.join().map_err(join).and_then(|r| r),
+                stdout.join().map_err(join).and_then(|r| r),
+                stderr.join().map_err(join).and_then(|r| r),
+            )
+        });
+        let status = status.map_err(|e| AppError::internal_from_err("blob.ocr.wait", e))?;
+        let Some(status) = status else {
+            return Err(AppError::Internal(format!(
+                "{} timed out after {}s",
+                self.command,
+                self.timeout.as_secs()
+            )));
+        };
+        let stderr = stderr.unwrap_or_default();
+        if !status.success() {
+            return Err(AppError::Internal(format!(
+                "{} exited with {}: {}",
+                self.command,
+                status,
+                String::from_utf8_lossy(&stderr).trim()
+            )));
+        }
+        written.map_err(|e| AppError::internal_from_err("blob.ocr.stdin", e))?;
+        let stdout = stdout.map_err(|e| AppError::internal_from_err("blob.ocr.stdout", e))?;
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+}
+
+fn read_pipe(pipe: Option<impl Read>) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut buf)?;
+    }
+    Ok(buf)
+}
+
+/// Waits for `child` to exit; `None` when it ran longer than `timeout` and was killed.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            // Killing closes its pipes, which ends the reader and writer threads.
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(EXIT_POLL_INTERVAL);
+    }
+}
+
+/// Drops trailing whitespace and the form feeds Tesseract emits between pages and collapses
+/// runs of blank lines, keeping leading spaces (chord alignment) and single blank lines
+/// (stanza breaks).
+pub fn normalize_text(raw: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in raw.lines() {
+        let line = line.trim_end_matches(|c: char| c.is_whitespace() || c == '\u{c}');
+        let line = line.trim_start_matches('\u{c}');
+        if line.trim().is_empty() {
+            if lines.last().is_some_and(|last| !last.is_empty()) {
+                lines.push("");
+            }
+        } else {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_keeps_alignment_and_stanza_breaks() {
+        assert_eq!(
+            normalize_text("\n\nAmazing grace  \n\n\n  G     C\nhow sweet\n\u{c}\n"),
+            "Amazing grace\n\n  G     C\nhow sweet"
+        );
+    }
+
+    #[test]
+    fn empty_command_disables_tesseract() {
+        assert!(
+            TesseractOcr::from_settings(&Settings::default())
+                .expect("no OCR")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn missing_command_fails_at_startup() {
+        let settings = Settings {
+            ocr_command: "/nonexistent/tesseract".into(),
+            ..Settings::default()
+        };
+        let err = TesseractOcr::from_settings(&settings)
+            .err()
+            .expect("missing executable");
+        assert!(err.to_string().contains("could not be started"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn missing_language_data_fails_at_startup() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let fake = dir.path().join("tesseract");
+        std::fs::write(
+            &fake,
+            "#!/bin/sh\necho 'List of available languages in \"/usr/share/tessdata/\" (2):'\necho eng\necho osd\n",
+        )
+        .expect("write fake tesseract");
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        let settings = |languages: &str| Settings {
+            ocr_command: fake.display().to_string(),
+            ocr_languages: languages.into(),
+            ..Settings::default()
+        };
+
+        assert!(
+            TesseractOcr::from_settings(&settings("eng"))
+                .expect("installed language")
+                .is_some()
+        );
+        let err = TesseractOcr::from_settings(&settings("eng+deu"))
+            .err()
+            .expect("missing language");
+        assert!(err.to_string().contains("not installed: deu"), "{err}");
+    }
+
+    #[test]
+    fn missing_executable_is_an_error() {
+        let engine = TesseractOcr {
+            command: "/nonexistent/tesseract".into(),
+            languages: "eng".into(),
+            timeout: Duration::from_secs(5),
+        };
+        let err = engine.recognize(&FileType::PNG, b"png").unwrap_err();
+        assert!(err.to_string().contains("failed to start"));
+    }
+
+    /// A fake `tesseract` running `script` for recognition, limited to `timeout`.
+    #[cfg(unix)]
+    fn fake_engine(dir: &std::path::Path, script: &str, timeout: Duration) -> TesseractOcr {
+        use std::os::unix::fs::PermissionsExt;
+
+        let fake = dir.join("tesseract");
+        std::fs::write(&fake, format!("#!/bin/sh\n{script}\n")).expect("write fake tesseract");
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        TesseractOcr {
+            command: fake.display().to_string(),
+            languages: "eng".into(),
+            timeout,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hanging_tesseract_is_killed_after_the_timeout() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let engine = fake_engine(dir.path(), "exec sleep 30", Duration::from_secs(1));
+        let started = Instant::now();
+        let err = engine.recognize(&FileType::PNG, b"png").unwrap_err();
+        assert!(err.to_string().contains("timed out after 1s"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn large_stderr_before_reading_stdin_does_not_deadlock() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // More than a pipe buffer on stderr before the image is read, while the image is larger
+        // than a pipe buffer too.
+        let engine = fake_engine(
+            dir.path(),
+            "head -c 262144 /dev/zero >&2\ncat >/dev/null\necho 'Amazing grace'",
+            Duration::from_secs(20),
+        );
+        let text = engine
+            .recognize(&FileType::PNG, &vec![0u8; 1 << 20])
+            .expect("recognize");
+        assert_eq!(text.trim(), "Amazing grace");
+    }
+}
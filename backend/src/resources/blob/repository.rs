@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use surrealdb::types::RecordId;
 
 use shared::api::ListQuery;
-use shared::blob::{Blob, CreateBlob};
+use shared::blob::{Blob, BlobVariant, CreateBlob};
 
 use crate::error::AppError;
 
@@ -33,7 +33,13 @@ pub trait BlobRepository: Send + Sync {
         blob: CreateBlob,
     ) -> Result<Blob, AppError>;
 
-    async fn delete_blob(&self, write_teams: &[RecordId], id: &str) -> Result<Blob, AppError>;
+    /// Moves the blob into its team's trash (see the trash service for restore and purge).
+    async fn trash_blob(
+        &self,
+        write_teams: &[RecordId],
+        actor_user_id: &str,
+        id: &str,
+    ) -> Result<Blob, AppError>;
 
     async fn move_blob_owner(
         &self,
@@ -41,4 +47,49 @@ pub trait BlobRepository: Send + Sync {
         id: &str,
         new_owner: RecordId,
     ) -> Result<Blob, AppError>;
+
+    /// Points the blob (trashed or not) at stored content, forgetting variants and OCR state of
+    /// earlier content, and returns the hash it referenced before. Callers check write access
+    /// first.
+    async fn set_blob_content(&self, id: &str, sha256: &str) -> Result<Option<String>, AppError>;
+
+    /// Records the decoded pixel size and the generated variants of content `sha256`; a no-op
+    /// when the blob points at other content by now.
+    async fn set_blob_image(
+        &self,
+        id: &str,
+        sha256: &str,
+        width: u32,
+        height: u32,
+        variants: &[BlobVariant],
+    ) -> Result<(), AppError>;
+
+    /// Queues OCR for content `sha256` of the blob; a no-op when it points at other content.
+    async fn queue_blob_ocr(&self, id: &str, sha256: &str) -> Result<(), AppError>;
+
+    /// Marks the blob's queued (or interrupted) OCR of content `sha256` as running and returns
+    /// the blob; `None` when it points at other content or nothing is queued for it.
+    async fn start_blob_ocr(&self, id: &str, sha256: &str) -> Result<Option<Blob>, AppError>;
+
+    /// Stores the extracted `text` in `ocr` unless the blob's content changed meanwhile.
+    async fn complete_blob_ocr(&self, id: &str, sha256: &str, text: &str) -> Result<(), AppError>;
+
+    /// Records a failed or cancelled attempt: pending again when `retry`, otherwise failed for
+    /// good.
+    async fn fail_blob_ocr(
+        &self,
+        id: &str,
+        sha256: &str,
+        error: &str,
+        retry: bool,
+    ) -> Result<(), AppError>;
+
+    /// Blob records, trashed ones included, whose bytes are `sha256`.
+    async fn count_content_references(&self, sha256: &str) -> Result<u64, AppError>;
+
+    /// `(blob id, sha256)` of every blob with stored content, across all teams.
+    async fn content_hashes(&self) -> Result<Vec<(String, String)>, AppError>;
+
+    /// Blobs without a `sha256`, trashed ones included, across all teams.
+    async fn blobs_without_content(&self) -> Result<Vec<Blob>, AppError>;
 }
@@ -3,22 +3,59 @@ use actix_web::{
     HttpRequest, HttpResponse, Scope, delete, get, patch, post, put,
     web::{self, Bytes, Data, Json, Path as PathParam, Query, ReqData},
 };
+use serde::Deserialize;
 
 #[allow(unused_imports)]
+use crate::auth::middleware::RequireAdmin;
 use crate::docs::Problem;
 use crate::error::AppError;
 use crate::http_cache::{
-    check_if_match, if_none_match_matches, weak_etag_from_bytes, weak_etag_json,
+    check_if_match, if_none_match_matches, strong_etag, weak_etag_from_bytes, weak_etag_json,
 };
 use crate::resources::User;
 #[allow(unused_imports)]
 use crate::resources::blob::Blob;
 use crate::resources::blob::PatchBlob;
 use crate::resources::blob::service::BlobServiceHandle;
+use crate::resources::blob::storage::{BlobData, attachment_disposition};
+use crate::resources::blob::variants::VariantFormat;
 use crate::resources::blob::{CreateBlob, UpdateBlob};
 use crate::resources::team::UserPermissions;
 use shared::MoveOwner;
 use shared::api::{ListQuery, PAGE_SIZE_DEFAULT};
+use shared::blob::BlobVariant;
+#[allow(unused_imports)] // Only referenced from `utoipa::path` response schemas
+use shared::blob::BlobVerifyReport;
+
+#[derive(Debug, Deserialize)]
+struct BlobDataQuery {
+    /// `thumb`, `display` or `original` (default).
+    variant: Option<String>,
+}
+
+/// Whether `Accept` lists `image/webp` (or `image/*`) without `q=0`.
+fn accepts_webp(req: &HttpRequest) -> bool {
+    let Some(accept) = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return false;
+    };
+    accept.split(',').any(|range| {
+        let mut parts = range.split(';').map(str::trim);
+        let media = parts.next().unwrap_or_default();
+        let refused = parts.any(|param| {
+            param
+                .strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q <= 0.0)
+        });
+        !refused
+            && (media.eq_ignore_ascii_case(VariantFormat::WebP.mime())
+                || media.eq_ignore_ascii_case("image/*"))
+    })
+}
 
 pub fn scope(blob_upload_max_bytes: usize) -> Scope {
     web::scope("/blobs")
@@ -37,6 +74,36 @@ pub fn scope(blob_upload_max_bytes: usize) -> Scope {
         )
 }
 
+/// Platform-admin blob storage maintenance under `/admin/blobs`.
+pub fn admin_scope() -> Scope {
+    web::scope("/admin/blobs").service(
+        web::scope("")
+            .wrap(RequireAdmin)
+            .service(verify_blob_storage),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/blobs/verify",
+    responses(
+        (status = 200, description = "Scan of blob storage against blob records: referenced content that is missing, stored content whose bytes no longer match their SHA-256, and stored content no blob references. Nothing is changed.", body = BlobVerifyReport),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to scan blob storage", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Blobs",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/verify")]
+async fn verify_blob_storage(svc: Data<BlobServiceHandle>) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.verify_storage().await?))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/blobs",
@@ -287,7 +354,7 @@ async fn move_blob(
         ("id" = String, Path, description = "Blob identifier")
     ),
     responses(
-        (status = 204, description = "Blob deleted"),
+        (status = 204, description = "Blob moved to its team's trash"),
         (status = 400, description = "Invalid blob identifier", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -321,20 +388,26 @@ async fn delete_blob(
     get,
     path = "/api/v1/blobs/{id}/data",
     params(
-        ("id" = String, Path, description = "Blob identifier")
+        ("id" = String, Path, description = "Blob identifier"),
+        ("variant" = Option<BlobVariant>, Query, description = "`thumb` (longest side at most 320 px), `display` (at most 1600 px) or `original` (default, the uploaded bytes). Resized variants are WebP when `Accept` lists `image/webp`, otherwise the upload's format; they are generated on first request for blobs uploaded before variants existed. SVGs and bytes that do not decode as their `file_type` always return the original.")
     ),
     responses(
         (
             status = 200,
             description = "Binary image data. `Content-Type` reflects the stored file type \
-                           (`image/png`, `image/jpeg`, or `image/svg+xml`).",
+                           (`image/png`, `image/jpeg`, or `image/svg+xml`), or `image/webp` for a \
+                           negotiated variant. `ETag` is the quoted SHA-256 of the uploaded bytes \
+                           (the blob's `sha256`), suffixed with the variant file name \
+                           (e.g. `-thumb.webp`) for resized variants, which also carry \
+                           `Vary: Accept`.",
             content_type = "image/*",
             body = Vec<u8>
         ),
-        (status = 400, description = "Invalid blob identifier", body = Problem, content_type = "application/problem+json"),
+        (status = 400, description = "Invalid blob identifier or unknown `variant`", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
-        (status = 304, description = "Not modified (`If-None-Match` matches weak ETag of bytes)"),
+        (status = 302, description = "With S3 blob storage: redirect to a short-lived presigned URL for the object (`Location`)"),
+        (status = 304, description = "Not modified (`If-None-Match` matches the `ETag` of the bytes)"),
         (status = 404, description = "Blob not found", body = Problem, content_type = "application/problem+json"),
         (status = 500, description = "Failed to download blob", body = Problem, content_type = "application/problem+json")
     ),
@@ -350,33 +423,66 @@ async fn download_blob_image(
     svc: Data<BlobServiceHandle>,
     user: ReqData<User>,
     id: PathParam<String>,
+    query: Query<BlobDataQuery>,
 ) -> Result<HttpResponse, AppError> {
     let perms = UserPermissions::from_ref(&user, &svc.teams);
     let id = id.into_inner();
-    let (blob, file) = svc.open_blob_data_file_for_user(&perms, &id).await?;
-    let filename = blob
-        .file_name()
-        .unwrap_or_else(|| format!("blob-{}", blob.id));
-    let path = file.path().to_path_buf();
-    let bytes =
-        std::fs::read(&path).map_err(|e| AppError::internal_from_err("blob.rest.read_data", e))?;
-    let etag = weak_etag_from_bytes(&bytes);
+    let variant = match query.into_inner().variant.as_deref() {
+        None => BlobVariant::Original,
+        Some(v) => v.parse().map_err(AppError::invalid_request)?,
+    };
+    let download = svc
+        .read_blob_data_for_user(&perms, &id, variant, accepts_webp(&req))
+        .await?;
+    let blob = download.blob;
+    // The representation of a resized variant depends on `Accept`.
+    let vary = download
+        .rendition
+        .is_some()
+        .then_some((header::VARY, header::HeaderValue::from_static("Accept")));
+    let bytes = match download.data {
+        BlobData::Bytes(bytes) => bytes,
+        BlobData::Redirect(url) => {
+            let mut response = HttpResponse::Found();
+            response
+                .insert_header((header::LOCATION, url))
+                .insert_header((
+                    header::CACHE_CONTROL,
+                    header::HeaderValue::from_static("private, no-store"),
+                ));
+            if let Some(vary) = vary {
+                response.insert_header(vary);
+            }
+            return Ok(response.finish());
+        }
+    };
+    let etag = match (&blob.sha256, download.rendition) {
+        (Some(hash), Some(rendition)) => strong_etag(&format!("{hash}-{}", rendition.file_name())),
+        (Some(hash), None) => strong_etag(hash),
+        (None, _) => weak_etag_from_bytes(&bytes),
+    };
     if if_none_match_matches(&req, &etag) {
-        return Ok(HttpResponse::NotModified()
-            .insert_header((header::ETAG, etag))
-            .insert_header((
-                header::CACHE_CONTROL,
-                header::HeaderValue::from_static("private, max-age=3600, immutable"),
-            ))
-            .finish());
+        let mut response = HttpResponse::NotModified();
+        response.insert_header((header::ETAG, etag)).insert_header((
+            header::CACHE_CONTROL,
+            header::HeaderValue::from_static("private, max-age=3600, immutable"),
+        ));
+        if let Some(vary) = vary {
+            response.insert_header(vary);
+        }
+        return Ok(response.finish());
     }
-    let ct = header::HeaderValue::from_static(blob.file_type.mime());
-    let cd = header::HeaderValue::from_str(&format!(
-        "attachment; filename=\"{}\"",
-        filename.replace('\\', "\\\\").replace('"', "\\\"")
-    ))
-    .map_err(|e| AppError::internal_from_err("blob.rest.content_disposition_header", e))?;
-    Ok(HttpResponse::Ok()
+    let ct = header::HeaderValue::from_static(match download.rendition {
+        Some(rendition) => rendition.format.mime(),
+        None => blob.file_type.mime(),
+    });
+    let cd = header::HeaderValue::from_str(&attachment_disposition(&blob, download.rendition))
+        .map_err(|e| AppError::internal_from_err("blob.rest.content_disposition_header", e))?;
+    let mut response = HttpResponse::Ok();
+    if let Some(vary) = vary {
+        response.insert_header(vary);
+    }
+    Ok(response
         .insert_header((header::ETAG, etag))
         .insert_header((header::CONTENT_TYPE, ct))
         .insert_header((header::CONTENT_DISPOSITION, cd))
@@ -400,7 +506,7 @@ async fn download_blob_image(
         description = "Raw binary content to store for this blob"
     ),
     responses(
-        (status = 204, description = "Blob content uploaded successfully"),
+        (status = 204, description = "Blob content uploaded successfully; `ETag` is the quoted SHA-256 of the stored bytes"),
         (status = 400, description = "Invalid blob identifier", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -428,6 +534,10 @@ async fn upload_blob_data(
     let blob = svc.get_blob_for_user(&perms, &id).await?;
     let etag = weak_etag_json(&blob).map_err(|e| AppError::internal_from_err("blob.rest", e))?;
     check_if_match(&req, &etag)?;
-    svc.upload_blob_data_for_user(&perms, &id, &body).await?;
-    Ok(HttpResponse::NoContent().finish())
+    let blob = svc.upload_blob_data_for_user(&perms, &id, &body).await?;
+    let mut response = HttpResponse::NoContent();
+    if let Some(hash) = &blob.sha256 {
+        response.insert_header((header::ETAG, strong_etag(hash)));
+    }
+    Ok(response.finish())
 }
@@ -1,42 +1,119 @@
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 
-use actix_files::NamedFile;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use shared::MoveOwner;
 use shared::api::ListQuery;
-use shared::blob::{Blob, CreateBlob, PatchBlob};
+use shared::blob::{
+    Blob, BlobContentProblem, BlobOcrStatus, BlobVariant, BlobVerifyReport, CreateBlob, FileType,
+    PatchBlob,
+};
+use shared::job::{Job, JobKind};
+use shared::team::{ActivityAction, ActivityResourceType, TeamPermission};
 
 use crate::database::Database;
 use crate::error::AppError;
+use crate::resources::job::{JobContext, JobHandler, JobServiceHandle};
+use crate::resources::team::activity::{
+    ActivityRecorder, NewTeamActivity, SurrealTeamActivityRepo,
+};
 use crate::resources::team::{
     TeamResolver, UserPermissions, parse_owner_record_id, thing_record_key,
 };
 
+use super::ocr::{self, OcrEngine};
 use super::repository::BlobRepository;
-use super::storage::BlobStorage;
-use super::storage::FsBlobStorage;
+use super::storage::{
+    BlobBackend, BlobData, BlobStorage, FsBlobStorage, content_sha256, lock_content,
+};
 use super::surreal_repo::SurrealBlobRepo;
+use super::variants::{self, RenderedImage, Rendition};
+
+/// Bytes selected for `GET /blobs/{id}/data`.
+#[derive(Debug)]
+pub struct BlobDownload {
+    pub blob: Blob,
+    pub data: BlobData,
+    /// The resized rendition served; `None` for the uploaded bytes.
+    pub rendition: Option<Rendition>,
+}
 
 /// Application service: team resolution, authorization, and orchestration for blobs.
 #[derive(Clone)]
-pub struct BlobService<R, T, S> {
+pub struct BlobService<R, T, S, A> {
     pub repo: R,
     pub teams: Arc<T>,
     pub storage: S,
+    pub activity: A,
+    /// Set when OCR is configured; uploads of raster images then enqueue a `blob.ocr` job.
+    pub ocr: Option<Arc<dyn OcrEngine>>,
+    pub ocr_jobs: Option<JobServiceHandle>,
+    pub ocr_max_attempts: u32,
+}
+
+/// Payload of a `blob.ocr` job.
+#[derive(Debug, Serialize, Deserialize)]
+struct OcrJobPayload {
+    blob_id: String,
+    sha256: String,
 }
 
-impl<R, T, S> BlobService<R, T, S> {
-    pub fn new(repo: R, teams: Arc<T>, storage: S) -> Self {
+impl<R, T, S, A> BlobService<R, T, S, A> {
+    pub fn new(repo: R, teams: Arc<T>, storage: S, activity: A) -> Self {
         Self {
             repo,
             teams,
             storage,
+            activity,
+            ocr: None,
+            ocr_jobs: None,
+            ocr_max_attempts: 1,
         }
     }
+
+    /// Enqueues a `blob.ocr` job on `jobs` for uploaded PNG and JPEG bytes, trying each upload
+    /// up to `max_attempts` times with `engine`. Register the service as the job kind's
+    /// handler on the job service that runs the workers.
+    pub fn with_ocr(
+        mut self,
+        engine: Arc<dyn OcrEngine>,
+        jobs: JobServiceHandle,
+        max_attempts: u32,
+    ) -> Self {
+        self.ocr = Some(engine);
+        self.ocr_jobs = Some(jobs);
+        self.ocr_max_attempts = max_attempts.max(1);
+        self
+    }
+}
+
+/// Decodes and resizes on a blocking thread; `None` when `data` has no variants.
+async fn render_variants(file_type: FileType, data: Vec<u8>) -> Option<RenderedImage> {
+    tokio::task::spawn_blocking(move || variants::render(&file_type, &data))
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!(error = %err, "blob variant rendering panicked");
+            None
+        })
 }
 
-impl<R: BlobRepository, T: TeamResolver, S: BlobStorage> BlobService<R, T, S> {
+fn blob_activity(actor_user_id: &str, blob: &Blob, action: ActivityAction) -> NewTeamActivity {
+    NewTeamActivity::new(
+        &blob.owner,
+        actor_user_id,
+        ActivityResourceType::Blob,
+        &blob.id,
+        action,
+    )
+    .with_etag(blob)
+}
+
+impl<R: BlobRepository, T: TeamResolver, S: BlobStorage, A: ActivityRecorder>
+    BlobService<R, T, S, A>
+{
     #[instrument(level = "debug", err, skip(self, perms))]
     pub async fn list_blobs_for_user(
         &self,
@@ -77,12 +154,20 @@ impl<R: BlobRepository, T: TeamResolver, S: BlobStorage> BlobService<R, T, S> {
             None => perms.personal_team().await?,
             Some(ref s) => {
                 let rid = parse_owner_record_id(s)?;
-                perms.require_write_access_to_owner(&rid).await?;
+                perms
+                    .require_permission_on_owner(&rid, TeamPermission::BlobsWrite)
+                    .await?;
                 rid
             }
         };
         let created = self.repo.create_blob(owner, blob).await?;
-        self.storage.write_blob_file(&created)?;
+        self.activity
+            .record_activity_or_warn(blob_activity(
+                &perms.user().id,
+                &created,
+                ActivityAction::Created,
+            ))
+            .await;
         Ok(created)
     }
 
@@ -93,9 +178,15 @@ impl<R: BlobRepository, T: TeamResolver, S: BlobStorage> BlobService<R, T, S> {
         id: &str,
         blob: CreateBlob,
     ) -> Result<Blob, AppError> {
-        let write_teams = perms.write_teams().await?;
-        let updated = self.repo.update_blob(write_teams, id, blob).await?;
-        self.storage.write_blob_file(&updated)?;
+        let write_teams = perms.teams_with(TeamPermission::BlobsWrite).await?;
+        let updated = self.repo.update_blob(&write_teams, id, blob).await?;
+        self.activity
+            .record_activity_or_warn(blob_activity(
+                &perms.user().id,
+                &updated,
+                ActivityAction::Updated,
+            ))
+            .await;
         Ok(updated)
     }
 
@@ -130,77 +221,366 @@ impl<R: BlobRepository, T: TeamResolver, S: BlobStorage> BlobService<R, T, S> {
         if thing_record_key(&current) == thing_record_key(&dest) {
             return Ok(blob);
         }
-        perms.require_write_access_to_owner(&current).await?;
-        perms.require_write_access_to_owner(&dest).await?;
-        let write_teams = perms.write_teams().await?;
-        self.repo.move_blob_owner(write_teams, id, dest).await
+        perms
+            .require_permission_on_owner(&current, TeamPermission::BlobsDelete)
+            .await?;
+        perms
+            .require_permission_on_owner(&dest, TeamPermission::BlobsWrite)
+            .await?;
+        let source_teams = perms.teams_with(TeamPermission::BlobsDelete).await?;
+        let moved = self.repo.move_blob_owner(&source_teams, id, dest).await?;
+        self.activity
+            .record_activity_or_warn(
+                blob_activity(&perms.user().id, &blob, ActivityAction::Moved)
+                    .with_other_team(&moved.owner)
+                    .with_etag(&moved),
+            )
+            .await;
+        self.activity
+            .record_activity_or_warn(
+                blob_activity(&perms.user().id, &moved, ActivityAction::Moved)
+                    .with_other_team(&blob.owner),
+            )
+            .await;
+        Ok(moved)
     }
 
+    /// Moves the blob into its team's trash; the bytes stay in storage until it is purged.
     #[instrument(level = "debug", err, skip(self, perms))]
     pub async fn delete_blob_for_user(
         &self,
         perms: &UserPermissions<T>,
         id: &str,
     ) -> Result<Blob, AppError> {
-        let write_teams = perms.write_teams().await?;
-        let deleted = self.repo.delete_blob(write_teams, id).await?;
-        self.storage.delete_blob_file(&deleted);
+        let delete_teams = perms.teams_with(TeamPermission::BlobsDelete).await?;
+        let deleted = self
+            .repo
+            .trash_blob(&delete_teams, &perms.user().id, id)
+            .await?;
+        self.activity
+            .record_activity_or_warn(blob_activity(
+                &perms.user().id,
+                &deleted,
+                ActivityAction::Deleted,
+            ))
+            .await;
         Ok(deleted)
     }
 
+    /// Stores `data` by its SHA-256 and points the blob at it. Identical bytes uploaded for
+    /// other blobs are stored once; the previous content is removed when nothing references it.
+    /// Raster images also get their pixel size recorded and their resized variants generated,
+    /// and are queued for OCR when it is configured.
     #[instrument(level = "debug", err, skip(self, perms, data))]
     pub async fn upload_blob_data_for_user(
         &self,
         perms: &UserPermissions<T>,
         id: &str,
         data: &[u8],
-    ) -> Result<(), AppError> {
+    ) -> Result<Blob, AppError> {
         // Reuse update_blob for the permission check: it scopes by write_teams and returns
         // 404 if the caller has no write access, which is exactly the right behavior here.
-        let write_teams = perms.write_teams().await?;
+        let write_teams = perms.teams_with(TeamPermission::BlobsWrite).await?;
         let blob = self
             .repo
-            .get_blob(write_teams, id)
+            .get_blob(&write_teams, id)
             .await
             .map_err(|_| AppError::NotFound("blob not found or write access denied".into()))?;
-        self.storage.write_blob_bytes(&blob, data)
+        let hash = content_sha256(data);
+        let rendered = render_variants(blob.file_type.clone(), data.to_vec()).await;
+        let previous = {
+            let _lock = lock_content(&hash).await;
+            self.storage.write_content(&hash, data).await?;
+            self.repo.set_blob_content(&blob.id, &hash).await?
+        };
+        match previous {
+            Some(previous) if previous != hash => self.release_content(&previous).await?,
+            Some(_) => {}
+            None => self.storage.delete_legacy_file(&blob).await,
+        }
+        let mut blob = Blob {
+            sha256: Some(hash.clone()),
+            variants: Vec::new(),
+            ocr_status: None,
+            ocr_error: None,
+            ..blob
+        };
+        if let Some(rendered) = rendered {
+            blob.width = rendered.width;
+            blob.height = rendered.height;
+            blob.variants = self.store_variants(&blob.id, &hash, rendered).await?;
+        }
+        if let Some(jobs) = self
+            .ocr_jobs
+            .as_ref()
+            .filter(|_| ocr::supports(&blob.file_type))
+        {
+            self.repo.queue_blob_ocr(&blob.id, &hash).await?;
+            jobs.enqueue(
+                JobKind::BlobOcr,
+                Some(&perms.user().id),
+                &OcrJobPayload {
+                    blob_id: blob.id.clone(),
+                    sha256: hash,
+                },
+                self.ocr_max_attempts,
+            )
+            .await?;
+            blob.ocr_status = Some(BlobOcrStatus::Pending);
+        }
+        Ok(blob)
     }
 
+    /// Serves `variant` of the blob, as WebP when `accepts_webp`. Variants missing for the
+    /// current content are generated from it first; SVGs and bytes that do not decode fall back
+    /// to the original.
     #[instrument(level = "debug", err, skip(self, perms))]
-    pub async fn open_blob_data_file_for_user(
+    pub async fn read_blob_data_for_user(
         &self,
         perms: &UserPermissions<T>,
         id: &str,
-    ) -> Result<(Blob, NamedFile), AppError> {
+        variant: BlobVariant,
+        accepts_webp: bool,
+    ) -> Result<BlobDownload, AppError> {
         let read_teams = perms.read_teams().await?;
         let blob = self.repo.get_blob(read_teams, id).await?;
-        let file = self.storage.open_blob_data_file(&blob)?;
-        Ok((blob, file))
+        let Some(hash) = blob.sha256.clone() else {
+            // Not uploaded yet, or a file from before content addressing not adopted yet.
+            let data = BlobData::Bytes(
+                self.storage
+                    .read_legacy_file(&blob)
+                    .await?
+                    .unwrap_or_default(),
+            );
+            return Ok(BlobDownload {
+                blob,
+                data,
+                rendition: None,
+            });
+        };
+        if let Some(rendition) = Rendition::negotiate(variant, &blob.file_type, accepts_webp)
+            && (blob.variants.contains(&variant) || self.generate_variants(&blob, &hash).await?)
+        {
+            let data = self.storage.variant_data(&blob, &hash, rendition).await?;
+            return Ok(BlobDownload {
+                blob,
+                data,
+                rendition: Some(rendition),
+            });
+        }
+        let data = self.storage.content_data(&blob, &hash).await?;
+        Ok(BlobDownload {
+            blob,
+            data,
+            rendition: None,
+        })
+    }
+
+    /// Renders the variants of content `hash` for a blob uploaded before variants existed (or
+    /// whose earlier attempt failed). `false` when the content is missing or does not decode.
+    async fn generate_variants(&self, blob: &Blob, hash: &str) -> Result<bool, AppError> {
+        let Some(data) = self.storage.read_content(hash).await? else {
+            return Ok(false);
+        };
+        match render_variants(blob.file_type.clone(), data).await {
+            Some(rendered) => {
+                self.store_variants(&blob.id, hash, rendered).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn store_variants(
+        &self,
+        blob_id: &str,
+        hash: &str,
+        rendered: RenderedImage,
+    ) -> Result<Vec<BlobVariant>, AppError> {
+        for (rendition, bytes) in &rendered.files {
+            self.storage.write_variant(hash, *rendition, bytes).await?;
+        }
+        let variants = BlobVariant::RESIZED.to_vec();
+        self.repo
+            .set_blob_image(blob_id, hash, rendered.width, rendered.height, &variants)
+            .await?;
+        Ok(variants)
+    }
+
+    /// Deletes stored content once no blob record (trashed ones included) references it.
+    async fn release_content(&self, hash: &str) -> Result<(), AppError> {
+        let _lock = lock_content(hash).await;
+        if self.repo.count_content_references(hash).await? == 0 {
+            self.storage.delete_content(hash).await;
+        }
+        Ok(())
+    }
+
+    /// Moves `<id><ext>` files written before content addressing into content storage and sets
+    /// `sha256` on their blobs. Empty placeholder files are removed. Returns the number of
+    /// blobs adopted.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn adopt_legacy_files(&self) -> Result<usize, AppError> {
+        let mut adopted = 0;
+        for blob in self.repo.blobs_without_content().await? {
+            let data = match self.storage.read_legacy_file(&blob).await {
+                Ok(Some(data)) => data,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!(blob_id = %blob.id, error = %e, "legacy blob file unreadable");
+                    continue;
+                }
+            };
+            if !data.is_empty() {
+                let hash = content_sha256(&data);
+                let _lock = lock_content(&hash).await;
+                self.storage.write_content(&hash, &data).await?;
+                self.repo.set_blob_content(&blob.id, &hash).await?;
+                adopted += 1;
+            }
+            self.storage.delete_legacy_file(&blob).await;
+        }
+        Ok(adopted)
+    }
+
+    /// Compares blob records with storage: referenced content that is missing, stored content
+    /// whose bytes do not match their hash, and stored content nothing references.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn verify_storage(&self) -> Result<BlobVerifyReport, AppError> {
+        let mut referenced: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (blob_id, hash) in self.repo.content_hashes().await? {
+            referenced.entry(hash).or_default().push(blob_id);
+        }
+        let stored: HashSet<String> = self.storage.list_content().await?.into_iter().collect();
+
+        let mut report = BlobVerifyReport {
+            referenced: referenced.len() as u64,
+            stored: stored.len() as u64,
+            ..Default::default()
+        };
+        for (hash, blob_ids) in &referenced {
+            let problem = || BlobContentProblem {
+                sha256: hash.clone(),
+                blob_ids: blob_ids.clone(),
+            };
+            if !stored.contains(hash) {
+                report.missing.push(problem());
+                continue;
+            }
+            match self.storage.read_content(hash).await? {
+                Some(data) if content_sha256(&data) == *hash => {}
+                Some(_) => report.corrupted.push(problem()),
+                None => report.missing.push(problem()),
+            }
+        }
+        let mut orphaned: Vec<String> = stored
+            .into_iter()
+            .filter(|hash| !referenced.contains_key(hash))
+            .collect();
+        orphaned.sort();
+        report.orphaned = orphaned;
+        Ok(report)
+    }
+
+    /// Marks the OCR of a `blob.ocr` job that ended without its handler as failed with `error`.
+    async fn give_up_ocr(&self, job: &Job, error: &str) -> Result<(), AppError> {
+        let payload: OcrJobPayload = serde_json::from_value(job.payload.clone())
+            .map_err(|e| AppError::internal_from_err("blob.ocr.payload", e))?;
+        self.repo
+            .fail_blob_ocr(&payload.blob_id, &payload.sha256, error, false)
+            .await
+    }
+}
+
+/// Runs `blob.ocr` jobs: extracts the text of the uploaded bytes into `ocr`. Jobs for bytes
+/// that were replaced since succeed without doing anything.
+#[async_trait]
+impl<R, T, S, A> JobHandler for BlobService<R, T, S, A>
+where
+    R: BlobRepository,
+    T: TeamResolver,
+    S: BlobStorage,
+    A: ActivityRecorder,
+{
+    async fn run(&self, ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+        let payload: OcrJobPayload = ctx.payload()?;
+        let engine = self
+            .ocr
+            .clone()
+            .ok_or_else(|| AppError::Internal("OCR is not configured".into()))?;
+        let Some(blob) = self
+            .repo
+            .start_blob_ocr(&payload.blob_id, &payload.sha256)
+            .await?
+        else {
+            return Ok(None);
+        };
+        ctx.set_progress(10).await?;
+        let hash = &payload.sha256;
+        let result = match self.storage.read_content(hash).await {
+            Ok(Some(data)) => {
+                let file_type = blob.file_type.clone();
+                tokio::task::spawn_blocking(move || engine.recognize(&file_type, &data))
+                    .await
+                    .unwrap_or_else(|e| Err(AppError::Internal(format!("OCR panicked: {e}"))))
+            }
+            Ok(None) => Err(AppError::Internal(format!("content {hash} is missing"))),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(text) => {
+                self.repo
+                    .complete_blob_ocr(&blob.id, hash, &ocr::normalize_text(&text))
+                    .await?;
+                Ok(None)
+            }
+            Err(e) => {
+                self.repo
+                    .fail_blob_ocr(&blob.id, hash, &e.to_string(), !ctx.is_last_attempt())
+                    .await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn cancelled(&self, job: &Job) -> Result<(), AppError> {
+        self.give_up_ocr(job, "cancelled").await
+    }
+
+    async fn interrupted(&self, job: &Job) -> Result<(), AppError> {
+        self.give_up_ocr(job, job.last_error.as_deref().unwrap_or("interrupted"))
+            .await
     }
 }
 
 /// Production type alias used in HTTP wiring.
-pub type BlobServiceHandle =
-    BlobService<SurrealBlobRepo, crate::resources::team::SurrealTeamResolver, FsBlobStorage>;
+pub type BlobServiceHandle = BlobService<
+    SurrealBlobRepo,
+    crate::resources::team::SurrealTeamResolver,
+    BlobBackend,
+    SurrealTeamActivityRepo,
+>;
 
 impl BlobServiceHandle {
+    /// Filesystem-backed service under `blob_dir`.
     pub fn build(db: Arc<Database>, blob_dir: String) -> Self {
         Self::build_with_team_resolver(
             db.clone(),
-            blob_dir,
+            FsBlobStorage::new(blob_dir).into(),
             Arc::new(crate::resources::team::SurrealTeamResolver::new(db.clone())),
         )
     }
 
     pub fn build_with_team_resolver(
         db: Arc<Database>,
-        blob_dir: String,
+        storage: BlobBackend,
         teams: Arc<crate::resources::team::SurrealTeamResolver>,
     ) -> Self {
         BlobService::new(
             SurrealBlobRepo::new(db.clone()),
             teams,
-            FsBlobStorage::new(blob_dir),
+            storage,
+            SurrealTeamActivityRepo::new(db),
         )
     }
 }
@@ -213,14 +593,16 @@ mod tests {
     use surrealdb::types::RecordId;
 
     use shared::api::ListQuery;
-    use shared::blob::{Blob, CreateBlob, FileType};
+    use shared::blob::{Blob, BlobVariant, CreateBlob, FileType};
 
     use crate::error::AppError;
     use crate::resources::User;
-    use crate::resources::team::{TeamResolver, UserPermissions};
+    use crate::resources::team::activity::DiscardActivity;
+    use crate::resources::team::{TeamGrant, TeamResolver, UserPermissions};
 
     use super::super::repository::BlobRepository;
-    use super::super::storage::BlobStorage;
+    use super::super::storage::{BlobData, BlobStorage};
+    use super::super::variants::Rendition;
     use super::BlobService;
 
     struct MockBlobRepo {
@@ -260,6 +642,10 @@ mod tests {
                 width: blob.width,
                 height: blob.height,
                 ocr: blob.ocr,
+                sha256: None,
+                variants: vec![],
+                ocr_status: None,
+                ocr_error: None,
             })
         }
 
@@ -275,9 +661,10 @@ mod tests {
                 .ok_or_else(|| AppError::NotFound("blob not found".into()))
         }
 
-        async fn delete_blob(
+        async fn trash_blob(
             &self,
             _write_teams: &[RecordId],
+            _actor_user_id: &str,
             _id: &str,
         ) -> Result<Blob, AppError> {
             self.blobs
@@ -294,6 +681,64 @@ mod tests {
         ) -> Result<Blob, AppError> {
             unreachable!("not used in these tests")
         }
+
+        async fn set_blob_content(
+            &self,
+            _id: &str,
+            _sha256: &str,
+        ) -> Result<Option<String>, AppError> {
+            Ok(None)
+        }
+
+        async fn set_blob_image(
+            &self,
+            _id: &str,
+            _sha256: &str,
+            _width: u32,
+            _height: u32,
+            _variants: &[BlobVariant],
+        ) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        async fn queue_blob_ocr(&self, _id: &str, _sha256: &str) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        async fn start_blob_ocr(&self, _id: &str, _sha256: &str) -> Result<Option<Blob>, AppError> {
+            Ok(None)
+        }
+
+        async fn complete_blob_ocr(
+            &self,
+            _id: &str,
+            _sha256: &str,
+            _text: &str,
+        ) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        async fn fail_blob_ocr(
+            &self,
+            _id: &str,
+            _sha256: &str,
+            _error: &str,
+            _retry: bool,
+        ) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        async fn count_content_references(&self, _sha256: &str) -> Result<u64, AppError> {
+            Ok(0)
+        }
+
+        async fn content_hashes(&self) -> Result<Vec<(String, String)>, AppError> {
+            Ok(vec![])
+        }
+
+        async fn blobs_without_content(&self) -> Result<Vec<Blob>, AppError> {
+            Ok(self.blobs.clone())
+        }
     }
 
     struct MockTeams;
@@ -303,7 +748,7 @@ mod tests {
         async fn content_read_teams(&self, _user: &User) -> Result<Vec<RecordId>, AppError> {
             Ok(vec![])
         }
-        async fn content_write_teams(&self, _user: &User) -> Result<Vec<RecordId>, AppError> {
+        async fn content_grants(&self, _user: &User) -> Result<Vec<TeamGrant>, AppError> {
             Ok(vec![])
         }
         async fn personal_team(&self, user_id: &str) -> Result<RecordId, AppError> {
@@ -313,17 +758,44 @@ mod tests {
 
     struct NullStorage;
 
+    #[async_trait]
     impl BlobStorage for NullStorage {
-        fn write_blob_file(&self, _blob: &Blob) -> Result<(), AppError> {
+        async fn write_content(&self, _hash: &str, _data: &[u8]) -> Result<(), AppError> {
             Ok(())
         }
-        fn write_blob_bytes(&self, _blob: &Blob, _data: &[u8]) -> Result<(), AppError> {
+        async fn read_content(&self, _hash: &str) -> Result<Option<Vec<u8>>, AppError> {
+            Ok(None)
+        }
+        async fn delete_content(&self, _hash: &str) {}
+        async fn list_content(&self) -> Result<Vec<String>, AppError> {
+            Ok(vec![])
+        }
+        async fn stored_bytes(&self) -> Result<u64, AppError> {
+            Ok(0)
+        }
+        async fn content_data(&self, _blob: &Blob, _hash: &str) -> Result<BlobData, AppError> {
+            Err(AppError::NotFound("no file".into()))
+        }
+        async fn write_variant(
+            &self,
+            _hash: &str,
+            _rendition: Rendition,
+            _data: &[u8],
+        ) -> Result<(), AppError> {
             Ok(())
         }
-        fn delete_blob_file(&self, _blob: &Blob) {}
-        fn open_blob_data_file(&self, _blob: &Blob) -> Result<actix_files::NamedFile, AppError> {
+        async fn variant_data(
+            &self,
+            _blob: &Blob,
+            _hash: &str,
+            _rendition: Rendition,
+        ) -> Result<BlobData, AppError> {
             Err(AppError::NotFound("no file".into()))
         }
+        async fn read_legacy_file(&self, _blob: &Blob) -> Result<Option<Vec<u8>>, AppError> {
+            Ok(None)
+        }
+        async fn delete_legacy_file(&self, _blob: &Blob) {}
     }
 
     fn test_user() -> User {
@@ -337,6 +809,7 @@ mod tests {
             MockBlobRepo { blobs: vec![] },
             Arc::new(MockTeams),
             NullStorage,
+            DiscardActivity,
         );
         let perms = UserPermissions::from_ref(&user, &svc.teams);
         let r = svc.get_blob_for_user(&perms, "b1").await;
@@ -344,12 +817,13 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn create_calls_storage_write() {
+    async fn create_succeeds_before_bytes_are_stored() {
         let user = test_user();
         let svc = BlobService::new(
             MockBlobRepo { blobs: vec![] },
             Arc::new(MockTeams),
             NullStorage,
+            DiscardActivity,
         );
         let perms = UserPermissions::from_ref(&user, &svc.teams);
         let r = svc
@@ -374,6 +848,7 @@ mod tests {
             MockBlobRepo { blobs: vec![] },
             Arc::new(MockTeams),
             NullStorage,
+            DiscardActivity,
         );
         let perms = UserPermissions::from_ref(&user, &svc.teams);
         let r = svc.delete_blob_for_user(&perms, "missing").await;
@@ -859,6 +1334,7 @@ mod tests {
             MockBlobRepo { blobs: vec![] },
             Arc::new(MockTeams),
             NullStorage,
+            DiscardActivity,
         );
         let perms = UserPermissions::from_ref(&user, &svc.teams);
         let r = svc
@@ -1001,4 +1477,564 @@ mod tests {
             .expect("idem");
         assert_eq!(idem.owner, team_b);
     }
+
+    /// BLC-BLOB-021: with S3 storage, readers get a presigned URL; non-members still get 404.
+    #[tokio::test]
+    async fn blc_blob_021_s3_download_redirects_to_presigned_url() {
+        use crate::resources::blob::{BlobBackend, S3BlobStorage};
+        use crate::resources::team::SurrealTeamResolver;
+        use crate::settings::S3Config;
+        use crate::test_helpers::blob_service;
+
+        use super::super::storage::BlobData;
+        use super::BlobServiceHandle;
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let (db, owner, _cm, guest, non_member, _tid) = four_user_blob_fixture().await;
+        let fs = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner_perms = UserPermissions::from_ref(&owner, &fs.teams);
+        let blob = fs
+            .create_blob_for_user(
+                &owner_perms,
+                CreateBlob {
+                    owner: None,
+                    file_type: FileType::PNG,
+                    width: 1,
+                    height: 1,
+                    ocr: String::new(),
+                },
+            )
+            .await
+            .expect("create");
+        let blob = fs
+            .upload_blob_data_for_user(&owner_perms, &blob.id, b"png bytes")
+            .await
+            .expect("upload");
+        let hash = blob.sha256.clone().expect("sha256");
+
+        // Presigning is local, so the endpoint is never contacted.
+        let s3 = S3BlobStorage::new(S3Config {
+            endpoint: "http://minio.invalid:9000".into(),
+            region: "us-east-1".into(),
+            bucket: "worship".into(),
+            access_key_id: "minio".into(),
+            secret_access_key: "minio-secret".into(),
+            path_style: true,
+            key_prefix: "blobs/".into(),
+            presign_ttl_seconds: 120,
+        })
+        .expect("s3");
+        let svc = BlobServiceHandle::build_with_team_resolver(
+            db.clone(),
+            BlobBackend::S3(Arc::new(s3)),
+            Arc::new(SurrealTeamResolver::new(db.clone())),
+        );
+
+        let data = svc
+            .read_blob_data_for_user(
+                &UserPermissions::from_ref(&guest, &svc.teams),
+                &blob.id,
+                BlobVariant::Original,
+                false,
+            )
+            .await
+            .expect("guest read")
+            .data;
+        let BlobData::Redirect(url) = data else {
+            panic!("expected a redirect, got {data:?}");
+        };
+        assert!(url.starts_with(&format!(
+            "http://minio.invalid:9000/worship/blobs/sha256/{}/{hash}?",
+            &hash[..2]
+        )));
+        assert!(url.contains("response-content-type=image%2Fpng"));
+        assert!(url.contains("X-Amz-Expires=120"));
+        assert!(url.contains("&X-Amz-Signature="));
+
+        let r = svc
+            .read_blob_data_for_user(
+                &UserPermissions::from_ref(&non_member, &svc.teams),
+                &blob.id,
+                BlobVariant::Original,
+                false,
+            )
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+    }
+
+    async fn png_blob(
+        svc: &super::BlobServiceHandle,
+        perms: &UserPermissions<crate::resources::team::SurrealTeamResolver>,
+    ) -> Blob {
+        svc.create_blob_for_user(
+            perms,
+            CreateBlob {
+                owner: None,
+                file_type: FileType::PNG,
+                width: 1,
+                height: 1,
+                ocr: String::new(),
+            },
+        )
+        .await
+        .expect("create")
+    }
+
+    /// BLC-BLOB-022: identical bytes are stored once and kept while any blob references them.
+    #[tokio::test]
+    async fn blc_blob_022_identical_bytes_stored_once() {
+        use crate::resources::blob::storage::{content_path, content_sha256};
+        use crate::test_helpers::{blob_service, create_user, test_db};
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let svc = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner = create_user(&db, "dedupe@test.local").await.expect("user");
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+
+        let a = png_blob(&svc, &perms).await;
+        let b = png_blob(&svc, &perms).await;
+        assert_eq!(a.sha256, None);
+        let a = svc
+            .upload_blob_data_for_user(&perms, &a.id, b"hymn page")
+            .await
+            .expect("upload a");
+        let b = svc
+            .upload_blob_data_for_user(&perms, &b.id, b"hymn page")
+            .await
+            .expect("upload b");
+        let hash = a.sha256.clone().expect("sha256");
+        assert_eq!(b.sha256.as_deref(), Some(hash.as_str()));
+        assert_eq!(hash, content_sha256(b"hymn page"));
+        assert_eq!(
+            svc.get_blob_for_user(&perms, &a.id)
+                .await
+                .expect("get")
+                .sha256
+                .as_deref(),
+            Some(hash.as_str())
+        );
+        let shared_file = blob_dir.path().join(content_path(&hash));
+        assert!(shared_file.is_file());
+        assert_eq!(
+            svc.storage.list_content().await.expect("list"),
+            std::slice::from_ref(&hash)
+        );
+
+        // Replacing one blob's bytes keeps the content the other still uses.
+        let a = svc
+            .upload_blob_data_for_user(&perms, &a.id, b"other page")
+            .await
+            .expect("replace a");
+        assert!(shared_file.is_file());
+        // Replacing the last reference removes it.
+        svc.upload_blob_data_for_user(&perms, &b.id, b"other page")
+            .await
+            .expect("replace b");
+        assert!(!shared_file.exists());
+        assert_eq!(
+            svc.storage.list_content().await.expect("list"),
+            [a.sha256.expect("sha256")]
+        );
+    }
+
+    /// BLC-BLOB-022: an upload of bytes whose last reference is being released at the same time
+    /// keeps that content stored.
+    #[tokio::test]
+    async fn blc_blob_022_concurrent_upload_and_release_keep_content() {
+        use crate::resources::blob::storage::content_sha256;
+        use crate::test_helpers::{blob_service, create_user, test_db};
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let svc = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner = create_user(&db, "dedupe-race@test.local")
+            .await
+            .expect("user");
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+
+        for round in 0..20 {
+            let shared = format!("shared page {round}");
+            let hash = content_sha256(shared.as_bytes());
+            let releasing = png_blob(&svc, &perms).await;
+            let adopting = png_blob(&svc, &perms).await;
+            svc.upload_blob_data_for_user(&perms, &releasing.id, shared.as_bytes())
+                .await
+                .expect("upload shared");
+
+            // `releasing` drops the last reference to `shared` while `adopting` adds one.
+            let replacement = format!("replacement {round}");
+            let (replaced, adopted) = tokio::join!(
+                svc.upload_blob_data_for_user(&perms, &releasing.id, replacement.as_bytes()),
+                svc.upload_blob_data_for_user(&perms, &adopting.id, shared.as_bytes()),
+            );
+            replaced.expect("replace");
+            let adopted = adopted.expect("reuse");
+
+            assert_eq!(adopted.sha256.as_deref(), Some(hash.as_str()));
+            assert_eq!(
+                svc.storage.read_content(&hash).await.expect("read"),
+                Some(shared.into_bytes()),
+                "round {round}: content referenced by {} was deleted",
+                adopted.id
+            );
+        }
+    }
+
+    /// BLC-BLOB-023: `<id><ext>` files from before content addressing are adopted.
+    #[tokio::test]
+    async fn blc_blob_023_legacy_files_adopted() {
+        use crate::resources::blob::storage::content_sha256;
+        use crate::test_helpers::{blob_service, create_user, test_db};
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let svc = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner = create_user(&db, "legacy@test.local").await.expect("user");
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+
+        let legacy = png_blob(&svc, &perms).await;
+        let placeholder = png_blob(&svc, &perms).await;
+        let legacy_file = blob_dir.path().join(legacy.file_name().expect("name"));
+        let placeholder_file = blob_dir.path().join(placeholder.file_name().expect("name"));
+        std::fs::write(&legacy_file, b"old scan").expect("write");
+        std::fs::write(&placeholder_file, b"").expect("write");
+
+        // Served from the old file until adopted.
+        let data = svc
+            .read_blob_data_for_user(&perms, &legacy.id, BlobVariant::Original, false)
+            .await
+            .expect("read")
+            .data;
+        assert!(matches!(data, BlobData::Bytes(ref b) if b == b"old scan"));
+
+        assert_eq!(svc.adopt_legacy_files().await.expect("adopt"), 1);
+        assert!(!legacy_file.exists());
+        assert!(!placeholder_file.exists());
+        let adopted = svc
+            .get_blob_for_user(&perms, &legacy.id)
+            .await
+            .expect("get");
+        assert_eq!(adopted.sha256, Some(content_sha256(b"old scan")));
+        let data = svc
+            .read_blob_data_for_user(&perms, &legacy.id, BlobVariant::Original, false)
+            .await
+            .expect("read")
+            .data;
+        assert!(matches!(data, BlobData::Bytes(ref b) if b == b"old scan"));
+        let data = svc
+            .read_blob_data_for_user(&perms, &placeholder.id, BlobVariant::Original, false)
+            .await
+            .expect("read placeholder")
+            .data;
+        assert!(matches!(data, BlobData::Bytes(ref b) if b.is_empty()));
+        assert_eq!(svc.adopt_legacy_files().await.expect("again"), 0);
+    }
+
+    /// BLC-BLOB-024: the storage scan reports missing, corrupted and orphaned content.
+    #[tokio::test]
+    async fn blc_blob_024_verify_reports_storage_problems() {
+        use crate::resources::blob::storage::{content_path, content_sha256};
+        use crate::test_helpers::{blob_service, create_user, test_db};
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let svc = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner = create_user(&db, "verify@test.local").await.expect("user");
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+
+        let mut blobs = Vec::new();
+        for data in [&b"intact"[..], b"lost", b"damaged"] {
+            let blob = png_blob(&svc, &perms).await;
+            blobs.push(
+                svc.upload_blob_data_for_user(&perms, &blob.id, data)
+                    .await
+                    .expect("upload"),
+            );
+        }
+        let report = svc.verify_storage().await.expect("verify");
+        assert!(report.is_healthy(), "{report:?}");
+        assert_eq!((report.referenced, report.stored), (3, 3));
+
+        let path = |hash: &str| blob_dir.path().join(content_path(hash));
+        let lost = blobs[1].sha256.clone().expect("sha");
+        let damaged = blobs[2].sha256.clone().expect("sha");
+        std::fs::remove_file(path(&lost)).expect("remove");
+        std::fs::write(path(&damaged), b"bit rot").expect("corrupt");
+        let orphan = content_sha256(b"nobody");
+        svc.storage
+            .write_content(&orphan, b"nobody")
+            .await
+            .expect("orphan");
+
+        let report = svc.verify_storage().await.expect("verify");
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].sha256, lost);
+        assert_eq!(report.missing[0].blob_ids, [blobs[1].id.clone()]);
+        assert_eq!(report.corrupted.len(), 1);
+        assert_eq!(report.corrupted[0].sha256, damaged);
+        assert_eq!(report.orphaned, [orphan]);
+    }
+
+    fn encoded_png(width: u32, height: u32) -> Vec<u8> {
+        let mut buf = Vec::new();
+        image::DynamicImage::new_rgb8(width, height)
+            .write_to(std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+            .expect("encode");
+        buf
+    }
+
+    /// BLC-BLOB-025: uploads record the decoded size and generate resized variants.
+    #[tokio::test]
+    async fn blc_blob_025_upload_generates_variants() {
+        use crate::resources::blob::storage::variants_path;
+        use crate::test_helpers::{blob_service, create_user, test_db};
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let svc = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner = create_user(&db, "variants@test.local").await.expect("user");
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+
+        let blob = png_blob(&svc, &perms).await;
+        let blob = svc
+            .upload_blob_data_for_user(&perms, &blob.id, &encoded_png(2400, 1200))
+            .await
+            .expect("upload");
+        assert_eq!((blob.width, blob.height), (2400, 1200));
+        assert_eq!(blob.variants, BlobVariant::RESIZED);
+        assert_eq!(
+            svc.get_blob_for_user(&perms, &blob.id).await.expect("get"),
+            blob
+        );
+        let hash = blob.sha256.clone().expect("sha256");
+        let mut files: Vec<String> = std::fs::read_dir(blob_dir.path().join(variants_path(&hash)))
+            .expect("variants dir")
+            .map(|e| e.expect("entry").file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            ["display.png", "display.webp", "thumb.png", "thumb.webp"]
+        );
+
+        let download = svc
+            .read_blob_data_for_user(&perms, &blob.id, BlobVariant::Thumb, true)
+            .await
+            .expect("thumb");
+        assert_eq!(
+            download.rendition.map(|r| r.file_name()).as_deref(),
+            Some("thumb.webp")
+        );
+        let BlobData::Bytes(thumb) = download.data else {
+            panic!("expected bytes");
+        };
+        let thumb = image::load_from_memory(&thumb).expect("decode thumb");
+        assert_eq!((thumb.width(), thumb.height()), (320, 160));
+
+        // Bytes that do not decode keep their metadata and drop the old variants.
+        let replaced = svc
+            .upload_blob_data_for_user(&perms, &blob.id, b"not a png")
+            .await
+            .expect("replace");
+        assert_eq!((replaced.width, replaced.height), (2400, 1200));
+        assert!(replaced.variants.is_empty());
+        assert!(!blob_dir.path().join(variants_path(&hash)).exists());
+        let download = svc
+            .read_blob_data_for_user(&perms, &blob.id, BlobVariant::Display, true)
+            .await
+            .expect("display falls back");
+        assert!(download.rendition.is_none());
+        assert!(matches!(download.data, BlobData::Bytes(ref b) if b == b"not a png"));
+    }
+
+    /// BLC-BLOB-026: variants missing for existing content are generated on first request.
+    #[tokio::test]
+    async fn blc_blob_026_missing_variants_generated_on_request() {
+        use crate::resources::blob::storage::variants_path;
+        use crate::test_helpers::{blob_service, create_user, test_db};
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let svc = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner = create_user(&db, "lazy-variants@test.local")
+            .await
+            .expect("user");
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+
+        let blob = png_blob(&svc, &perms).await;
+        let blob = svc
+            .upload_blob_data_for_user(&perms, &blob.id, &encoded_png(100, 40))
+            .await
+            .expect("upload");
+        let hash = blob.sha256.clone().expect("sha256");
+        // As if uploaded before variants existed.
+        svc.repo
+            .set_blob_content(&blob.id, &hash)
+            .await
+            .expect("reset");
+        std::fs::remove_dir_all(blob_dir.path().join(variants_path(&hash))).expect("rm");
+        assert!(
+            svc.get_blob_for_user(&perms, &blob.id)
+                .await
+                .expect("get")
+                .variants
+                .is_empty()
+        );
+
+        let download = svc
+            .read_blob_data_for_user(&perms, &blob.id, BlobVariant::Display, false)
+            .await
+            .expect("display");
+        assert_eq!(
+            download.rendition.map(|r| r.file_name()).as_deref(),
+            Some("display.png")
+        );
+        let BlobData::Bytes(display) = download.data else {
+            panic!("expected bytes");
+        };
+        let display = image::load_from_memory(&display).expect("decode display");
+        assert_eq!((display.width(), display.height()), (100, 40));
+        assert_eq!(
+            svc.get_blob_for_user(&perms, &blob.id)
+                .await
+                .expect("get")
+                .variants,
+            BlobVariant::RESIZED
+        );
+        assert!(
+            blob_dir
+                .path()
+                .join(variants_path(&hash))
+                .join("thumb.webp")
+                .is_file()
+        );
+    }
+
+    /// Recognises a fixed text, or fails while `failures_left` is non-zero.
+    struct ScriptedOcr {
+        failures_left: std::sync::Mutex<u32>,
+    }
+
+    impl crate::resources::blob::ocr::OcrEngine for ScriptedOcr {
+        fn recognize(&self, _file_type: &FileType, _data: &[u8]) -> Result<String, AppError> {
+            let mut failures = self.failures_left.lock().expect("lock");
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(AppError::Internal("engine crashed".into()));
+            }
+            Ok("  G       C\nAmazing grace\n\n\n\u{c}".into())
+        }
+    }
+
+    /// BLC-BLOB-027: uploads of raster images enqueue a `blob.ocr` job; the job stores the
+    /// text and is retried up to the configured attempts.
+    #[tokio::test]
+    async fn blc_blob_027_ocr_job_lifecycle() {
+        use chrono::{Duration, Utc};
+        use shared::blob::BlobOcrStatus;
+        use shared::job::{JobKind, JobStatus};
+
+        use crate::test_helpers::{blob_service, create_user, job_service, test_db};
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db);
+        let svc = blob_service(&db, blob_dir.path().to_string_lossy().into_owned()).with_ocr(
+            Arc::new(ScriptedOcr {
+                failures_left: std::sync::Mutex::new(1),
+            }),
+            jobs.clone(),
+            2,
+        );
+        let workers = jobs
+            .clone()
+            .with_handler(JobKind::BlobOcr, Arc::new(svc.clone()));
+        let owner = create_user(&db, "ocr@test.local").await.expect("user");
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+        let status = |blob: Blob| (blob.ocr_status, blob.ocr_error, blob.ocr);
+        let later = || Utc::now() + Duration::hours(2);
+
+        let blob = png_blob(&svc, &perms).await;
+        let blob = svc
+            .upload_blob_data_for_user(&perms, &blob.id, b"scan")
+            .await
+            .expect("upload");
+        assert_eq!(blob.ocr_status, Some(BlobOcrStatus::Pending));
+        let (queued, _) = jobs
+            .list_jobs_for_user(&owner, ListQuery::new())
+            .await
+            .expect("jobs");
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].kind, JobKind::BlobOcr);
+        assert_eq!(queued[0].payload["blob_id"], blob.id.as_str());
+
+        // First attempt fails; the blob is pending again and the job waits for its retry.
+        assert!(workers.run_next(Utc::now()).await.expect("run"));
+        let (state, error, _) = status(svc.get_blob_for_user(&perms, &blob.id).await.expect("get"));
+        assert_eq!(state, Some(BlobOcrStatus::Pending));
+        assert!(error.expect("error").contains("engine crashed"));
+        assert!(!workers.run_next(Utc::now()).await.expect("backoff"));
+
+        assert!(workers.run_next(later()).await.expect("retry"));
+        assert_eq!(
+            status(svc.get_blob_for_user(&perms, &blob.id).await.expect("get")),
+            (
+                Some(BlobOcrStatus::Done),
+                None,
+                "  G       C\nAmazing grace".to_owned()
+            )
+        );
+        let job = jobs
+            .get_job_for_user(&owner, &queued[0].id)
+            .await
+            .expect("job");
+        assert_eq!(
+            (job.status, job.attempts, job.progress),
+            (JobStatus::Succeeded, 2, 100)
+        );
+        assert!(!workers.run_next(later()).await.expect("idle"));
+
+        // New bytes queue a fresh job; giving up after the last attempt marks it failed.
+        let failing = blob_service(&db, blob_dir.path().to_string_lossy().into_owned()).with_ocr(
+            Arc::new(ScriptedOcr {
+                failures_left: std::sync::Mutex::new(5),
+            }),
+            jobs.clone(),
+            1,
+        );
+        let failing_workers = jobs
+            .clone()
+            .with_handler(JobKind::BlobOcr, Arc::new(failing.clone()));
+        failing
+            .upload_blob_data_for_user(&perms, &blob.id, b"other scan")
+            .await
+            .expect("replace");
+        assert!(failing_workers.run_next(Utc::now()).await.expect("run"));
+        let (state, error, text) =
+            status(svc.get_blob_for_user(&perms, &blob.id).await.expect("get"));
+        assert_eq!(state, Some(BlobOcrStatus::Failed));
+        assert!(error.is_some());
+        assert_eq!(text, "  G       C\nAmazing grace", "old text is kept");
+
+        // SVGs are not queued.
+        let svg = svc
+            .create_blob_for_user(
+                &perms,
+                CreateBlob {
+                    owner: None,
+                    file_type: FileType::SVG,
+                    width: 1,
+                    height: 1,
+                    ocr: String::new(),
+                },
+            )
+            .await
+            .expect("svg");
+        let svg = svc
+            .upload_blob_data_for_user(&perms, &svg.id, b"<svg/>")
+            .await
+            .expect("upload svg");
+        assert_eq!(svg.ocr_status, None);
+    }
 }
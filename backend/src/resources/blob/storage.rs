@@ -1,18 +1,128 @@
+use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
 
-use actix_files::NamedFile;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use ring::digest;
+use tokio::sync::OwnedMutexGuard;
 
 use shared::blob::Blob;
 
 use crate::error::AppError;
+use crate::settings::{BlobStorageKind, Settings};
 
-/// Abstracts blob file I/O. Enables mocking in tests.
+use super::s3::S3BlobStorage;
+use super::variants::Rendition;
+
+/// Blob bytes as handed to `GET /blobs/{id}/data`.
+#[derive(Debug)]
+pub enum BlobData {
+    /// Served by the backend itself.
+    Bytes(Vec<u8>),
+    /// Short-lived URL the client is redirected to (presigned object-store GET).
+    Redirect(String),
+}
+
+/// Abstracts blob byte storage. Content is addressed by its SHA-256 so identical uploads are
+/// stored once; which blobs reference a hash is tracked on the blob records. Enables mocking in
+/// tests.
+#[async_trait]
 pub trait BlobStorage: Send + Sync {
-    fn write_blob_file(&self, blob: &Blob) -> Result<(), AppError>;
-    fn write_blob_bytes(&self, blob: &Blob, data: &[u8]) -> Result<(), AppError>;
-    fn delete_blob_file(&self, blob: &Blob);
-    fn open_blob_data_file(&self, blob: &Blob) -> Result<NamedFile, AppError>;
+    /// Stores `data` as content `hash`; a no-op when that content is already stored.
+    async fn write_content(&self, hash: &str, data: &[u8]) -> Result<(), AppError>;
+    /// `None` when nothing is stored under `hash`.
+    async fn read_content(&self, hash: &str) -> Result<Option<Vec<u8>>, AppError>;
+    /// Deletes content `hash` together with the variants generated from it.
+    async fn delete_content(&self, hash: &str);
+    /// Hashes of all stored content.
+    async fn list_content(&self) -> Result<Vec<String>, AppError>;
+    /// Total size of everything in the store: content, variants and not yet adopted files.
+    async fn stored_bytes(&self) -> Result<u64, AppError>;
+    /// What `GET /blobs/{id}/data` serves for `blob`, whose content is `hash`.
+    async fn content_data(&self, blob: &Blob, hash: &str) -> Result<BlobData, AppError>;
+    /// Stores `data` as `rendition` of content `hash`, replacing an earlier one.
+    async fn write_variant(
+        &self,
+        hash: &str,
+        rendition: Rendition,
+        data: &[u8],
+    ) -> Result<(), AppError>;
+    /// What `GET /blobs/{id}/data?variant=` serves once `rendition` of content `hash` is stored.
+    async fn variant_data(
+        &self,
+        blob: &Blob,
+        hash: &str,
+        rendition: Rendition,
+    ) -> Result<BlobData, AppError>;
+    /// Bytes of the `<id><ext>` file written before content addressing, if it still exists.
+    async fn read_legacy_file(&self, blob: &Blob) -> Result<Option<Vec<u8>>, AppError>;
+    async fn delete_legacy_file(&self, blob: &Blob);
+}
+
+/// Lowercase hex SHA-256 of `data`, the content address of blob bytes.
+pub fn content_sha256(data: &[u8]) -> String {
+    hex::encode(digest::digest(&digest::SHA256, data))
+}
+
+/// Locks of the content hashes currently being written or released, shared by every storage.
+static CONTENT_LOCKS: Lazy<Mutex<HashMap<String, Weak<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(Mutex::default);
+
+/// Held while content is written and pointed at, or while its references are counted and it is
+/// deleted, so a release cannot delete content that an upload of the same bytes has just found
+/// already stored. The lock is per process.
+pub struct ContentLock {
+    _guard: OwnedMutexGuard<()>,
+}
+
+/// Waits until no other task holds the lock of content `hash`, then takes it.
+pub async fn lock_content(hash: &str) -> ContentLock {
+    let lock = {
+        let mut locks = CONTENT_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+        locks.retain(|_, lock| lock.strong_count() > 0);
+        match locks.get(hash).and_then(Weak::upgrade) {
+            Some(lock) => lock,
+            None => {
+                let lock = Arc::new(tokio::sync::Mutex::new(()));
+                locks.insert(hash.to_owned(), Arc::downgrade(&lock));
+                lock
+            }
+        }
+    };
+    ContentLock {
+        _guard: lock.lock_owned().await,
+    }
+}
+
+/// Location of content `hash` relative to the storage root: `sha256/<first two digits>/<hash>`.
+pub fn content_path(hash: &str) -> String {
+    format!("sha256/{}/{hash}", hash.get(..2).unwrap_or_default())
+}
+
+/// Directory of the variants generated from content `hash`: `variants/<first two digits>/<hash>`.
+pub fn variants_path(hash: &str) -> String {
+    format!("variants/{}/{hash}", hash.get(..2).unwrap_or_default())
+}
+
+/// Location of `rendition` of content `hash`, e.g. `variants/ab/<hash>/thumb.webp`.
+pub fn variant_path(hash: &str, rendition: Rendition) -> String {
+    format!("{}/{}", variants_path(hash), rendition.file_name())
+}
+
+/// `Content-Disposition` value used when a blob, or `rendition` of it, is downloaded.
+pub fn attachment_disposition(blob: &Blob, rendition: Option<Rendition>) -> String {
+    let filename = match rendition {
+        None => blob
+            .file_name()
+            .unwrap_or_else(|| format!("blob-{}", blob.id)),
+        Some(rendition) => format!("{}-{}", blob.id, rendition.file_name()),
+    };
+    format!(
+        "attachment; filename=\"{}\"",
+        filename.replace('\\', "\\\\").replace('"', "\\\"")
+    )
 }
 
 /// Production filesystem-backed blob storage.
@@ -25,55 +135,266 @@ impl FsBlobStorage {
     pub fn new(blob_dir: String) -> Self {
         Self { blob_dir }
     }
+
+    fn content_file(&self, hash: &str) -> PathBuf {
+        Path::new(&self.blob_dir).join(content_path(hash))
+    }
+
+    fn variant_file(&self, hash: &str, rendition: Rendition) -> PathBuf {
+        Path::new(&self.blob_dir).join(variant_path(hash, rendition))
+    }
+
+    fn legacy_file(&self, blob: &Blob) -> Option<PathBuf> {
+        blob.file_name()
+            .map(|name| Path::new(&self.blob_dir).join(name))
+    }
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>, AppError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(AppError::internal_from_err("blob.storage.read", err)),
+    }
+}
+
+/// Writes next to `path` and renames, so a half-written file is never served.
+fn write_atomically(path: &Path, data: &[u8]) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AppError::internal_from_err("blob.storage.create_dir_all", e))?;
+    }
+    let partial = path.with_extension("partial");
+    std::fs::write(&partial, data)
+        .and_then(|()| std::fs::rename(&partial, path))
+        .map_err(|e| AppError::internal_from_err("blob.storage.write", e))
+}
+
+/// Summed length of the files below `dir`; a missing directory is empty.
+fn dir_size(dir: &Path) -> Result<u64, AppError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(AppError::internal_from_err("blob.storage.size", err)),
+    };
+    let mut total = 0;
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::internal_from_err("blob.storage.size", e))?;
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => total += dir_size(&entry.path())?,
+            Ok(t) if t.is_file() => total += entry.metadata().map_or(0, |m| m.len()),
+            _ => {}
+        }
+    }
+    Ok(total)
+}
+
+fn remove_if_exists(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            tracing::debug!(
+                path = %path.display(),
+                "blob file already absent during delete"
+            );
+        }
+        Err(err) => {
+            tracing::warn!(
+                path = %path.display(),
+                error = %err,
+                "failed to delete blob file"
+            );
+        }
+    }
 }
 
+#[async_trait]
 impl BlobStorage for FsBlobStorage {
-    fn write_blob_file(&self, blob: &Blob) -> Result<(), AppError> {
-        self.write_blob_bytes(blob, &[])
+    async fn write_content(&self, hash: &str, data: &[u8]) -> Result<(), AppError> {
+        let path = self.content_file(hash);
+        if path.is_file() {
+            return Ok(());
+        }
+        write_atomically(&path, data)
     }
 
-    fn write_blob_bytes(&self, blob: &Blob, data: &[u8]) -> Result<(), AppError> {
-        let file_name = blob
-            .file_name()
-            .ok_or_else(|| AppError::Internal("blob has no id".into()))?;
-        let path = Path::new(&self.blob_dir).join(file_name);
-        if let Some(dir) = path.parent() {
-            std::fs::create_dir_all(dir)
-                .map_err(|e| AppError::internal_from_err("blob.storage.create_dir_all", e))?;
+    async fn read_content(&self, hash: &str) -> Result<Option<Vec<u8>>, AppError> {
+        read_if_exists(&self.content_file(hash))
+    }
+
+    async fn delete_content(&self, hash: &str) {
+        remove_if_exists(&self.content_file(hash));
+        let variants = Path::new(&self.blob_dir).join(variants_path(hash));
+        match std::fs::remove_dir_all(&variants) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => {
+                tracing::warn!(
+                    path = %variants.display(),
+                    error = %err,
+                    "failed to delete blob variants"
+                );
+            }
         }
-        std::fs::write(&path, data)
-            .map_err(|e| AppError::internal_from_err("blob.storage.write", e))
-    }
-
-    fn delete_blob_file(&self, blob: &Blob) {
-        if let Some(name) = blob.file_name() {
-            let path = Path::new(&self.blob_dir).join(name);
-            match std::fs::remove_file(&path) {
-                Ok(()) => {}
-                Err(err) if err.kind() == ErrorKind::NotFound => {
-                    tracing::debug!(
-                        blob_id = %blob.id,
-                        path = %path.display(),
-                        "blob file already absent during delete"
-                    );
-                }
-                Err(err) => {
-                    tracing::warn!(
-                        blob_id = %blob.id,
-                        path = %path.display(),
-                        error = %err,
-                        "failed to delete blob file"
-                    );
+    }
+
+    async fn list_content(&self) -> Result<Vec<String>, AppError> {
+        let root = Path::new(&self.blob_dir).join("sha256");
+        let shards = match std::fs::read_dir(&root) {
+            Ok(shards) => shards,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(AppError::internal_from_err("blob.storage.list", err)),
+        };
+        let mut hashes = Vec::new();
+        for shard in shards {
+            let shard = shard.map_err(|e| AppError::internal_from_err("blob.storage.list", e))?;
+            if !shard.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            for file in std::fs::read_dir(shard.path())
+                .map_err(|e| AppError::internal_from_err("blob.storage.list", e))?
+            {
+                let file = file.map_err(|e| AppError::internal_from_err("blob.storage.list", e))?;
+                let name = file.file_name().to_string_lossy().into_owned();
+                if file.file_type().is_ok_and(|t| t.is_file()) && !name.contains('.') {
+                    hashes.push(name);
                 }
             }
         }
+        Ok(hashes)
     }
 
-    fn open_blob_data_file(&self, blob: &Blob) -> Result<NamedFile, AppError> {
-        let file_name = blob
-            .file_name()
-            .ok_or_else(|| AppError::NotFound("blob has no id".into()))?;
-        NamedFile::open(Path::new(&self.blob_dir).join(PathBuf::from(file_name)))
-            .map_err(|err| AppError::internal_from_err("blob.storage.open", err))
+    async fn stored_bytes(&self) -> Result<u64, AppError> {
+        dir_size(Path::new(&self.blob_dir))
+    }
+
+    async fn content_data(&self, blob: &Blob, hash: &str) -> Result<BlobData, AppError> {
+        self.read_content(hash)
+            .await?
+            .map(BlobData::Bytes)
+            .ok_or_else(|| {
+                AppError::Internal(format!("content {hash} of blob {} is missing", blob.id))
+            })
+    }
+
+    async fn write_variant(
+        &self,
+        hash: &str,
+        rendition: Rendition,
+        data: &[u8],
+    ) -> Result<(), AppError> {
+        write_atomically(&self.variant_file(hash, rendition), data)
+    }
+
+    async fn variant_data(
+        &self,
+        blob: &Blob,
+        hash: &str,
+        rendition: Rendition,
+    ) -> Result<BlobData, AppError> {
+        read_if_exists(&self.variant_file(hash, rendition))?
+            .map(BlobData::Bytes)
+            .ok_or_else(|| {
+                AppError::Internal(format!(
+                    "variant {} of content {hash} of blob {} is missing",
+                    rendition.file_name(),
+                    blob.id
+                ))
+            })
+    }
+
+    async fn read_legacy_file(&self, blob: &Blob) -> Result<Option<Vec<u8>>, AppError> {
+        match self.legacy_file(blob) {
+            Some(path) => read_if_exists(&path),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_legacy_file(&self, blob: &Blob) {
+        if let Some(path) = self.legacy_file(blob) {
+            remove_if_exists(&path);
+        }
+    }
+}
+
+/// The storage backend selected by `BLOB_STORAGE`.
+#[derive(Clone)]
+pub enum BlobBackend {
+    Fs(FsBlobStorage),
+    S3(Arc<S3BlobStorage>),
+}
+
+impl BlobBackend {
+    pub fn from_settings(settings: &Settings) -> Result<Self, AppError> {
+        Ok(match settings.blob_storage {
+            BlobStorageKind::Fs => Self::Fs(FsBlobStorage::new(settings.blob_dir.clone())),
+            BlobStorageKind::S3 => Self::S3(Arc::new(S3BlobStorage::new(settings.s3_config())?)),
+        })
+    }
+
+    fn inner(&self) -> &dyn BlobStorage {
+        match self {
+            Self::Fs(fs) => fs,
+            Self::S3(s3) => s3.as_ref(),
+        }
+    }
+}
+
+impl From<FsBlobStorage> for BlobBackend {
+    fn from(fs: FsBlobStorage) -> Self {
+        Self::Fs(fs)
+    }
+}
+
+#[async_trait]
+impl BlobStorage for BlobBackend {
+    async fn write_content(&self, hash: &str, data: &[u8]) -> Result<(), AppError> {
+        self.inner().write_content(hash, data).await
+    }
+
+    async fn read_content(&self, hash: &str) -> Result<Option<Vec<u8>>, AppError> {
+        self.inner().read_content(hash).await
+    }
+
+    async fn delete_content(&self, hash: &str) {
+        self.inner().delete_content(hash).await
+    }
+
+    async fn list_content(&self) -> Result<Vec<String>, AppError> {
+        self.inner().list_content().await
+    }
+
+    async fn stored_bytes(&self) -> Result<u64, AppError> {
+        self.inner().stored_bytes().await
+    }
+
+    async fn content_data(&self, blob: &Blob, hash: &str) -> Result<BlobData, AppError> {
+        self.inner().content_data(blob, hash).await
+    }
+
+    async fn write_variant(
+        &self,
+        hash: &str,
+        rendition: Rendition,
+        data: &[u8],
+    ) -> Result<(), AppError> {
+        self.inner().write_variant(hash, rendition, data).await
+    }
+
+    async fn variant_data(
+        &self,
+        blob: &Blob,
+        hash: &str,
+        rendition: Rendition,
+    ) -> Result<BlobData, AppError> {
+        self.inner().variant_data(blob, hash, rendition).await
+    }
+
+    async fn read_legacy_file(&self, blob: &Blob) -> Result<Option<Vec<u8>>, AppError> {
+        self.inner().read_legacy_file(blob).await
+    }
+
+    async fn delete_legacy_file(&self, blob: &Blob) {
+        self.inner().delete_legacy_file(blob).await
     }
 }
@@ -8,7 +8,7 @@ use serde::Deserialize;
 use surrealdb::types::SurrealValue;
 
 use shared::api::ListQuery;
-use shared::blob::{Blob, CreateBlob};
+use shared::blob::{Blob, BlobVariant, CreateBlob};
 
 use crate::database::Database;
 use crate::error::AppError;
@@ -55,20 +55,19 @@ impl BlobRepository for SurrealBlobRepo {
         let needle = blob_list_q_needle(&pagination);
 
         let mut response = if let Some(needle) = needle {
-            db.db
-                .query(
-                    "SELECT * FROM blob WHERE owner IN $teams AND \
+            db.query(
+                "SELECT * FROM blob WHERE owner IN $teams AND trashed_at = NONE AND \
                      string::contains(string::lowercase(ocr), $needle) LIMIT $limit START $start",
-                )
-                .bind(("teams", read_teams.to_vec()))
-                .bind(("needle", needle))
-                .bind(("limit", limit))
-                .bind(("start", offset))
-                .await
-                .map_err(|e| crate::log_and_convert!(AppError::database, "blob.list.query", e))?
+            )
+            .bind(("teams", read_teams.to_vec()))
+            .bind(("needle", needle))
+            .bind(("limit", limit))
+            .bind(("start", offset))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "blob.list.query", e))?
         } else {
-            db.db
-                .query("SELECT * FROM blob WHERE owner IN $teams LIMIT $limit START $start")
+            db
+                .query("SELECT * FROM blob WHERE owner IN $teams AND trashed_at = NONE LIMIT $limit START $start")
                 .bind(("teams", read_teams.to_vec()))
                 .bind(("limit", limit))
                 .bind(("start", offset))
@@ -95,9 +94,8 @@ impl BlobRepository for SurrealBlobRepo {
         let needle = blob_list_q_needle(pagination);
         let mut response = if let Some(needle) = needle {
             self.inner()
-                .db
                 .query(
-                    "SELECT count() FROM blob WHERE owner IN $teams AND \
+                    "SELECT count() FROM blob WHERE owner IN $teams AND trashed_at = NONE AND \
                      string::contains(string::lowercase(ocr), $needle) GROUP ALL",
                 )
                 .bind(("teams", read_teams.to_vec()))
@@ -105,8 +103,7 @@ impl BlobRepository for SurrealBlobRepo {
                 .await?
         } else {
             self.inner()
-                .db
-                .query("SELECT count() FROM blob WHERE owner IN $teams GROUP ALL")
+                .query("SELECT count() FROM blob WHERE owner IN $teams AND trashed_at = NONE GROUP ALL")
                 .bind(("teams", read_teams.to_vec()))
                 .await?
         };
@@ -120,17 +117,18 @@ impl BlobRepository for SurrealBlobRepo {
 
     async fn get_blob(&self, read_teams: &[RecordId], id: &str) -> Result<Blob, AppError> {
         let db = self.inner();
-        let record: Option<BlobRecord> = db.db.select(resource_id("blob", id)?).await?;
+        let record: Option<BlobRecord> = db.select(resource_id("blob", id)?).await?;
         match record {
-            Some(r) if belongs_to(&r.owner, read_teams) => Ok(r.into_blob()),
+            Some(r) if r.trashed_at.is_none() && belongs_to(&r.owner, read_teams) => {
+                Ok(r.into_blob())
+            }
             _ => Err(AppError::NotFound("blob not found".into())),
         }
     }
 
     async fn create_blob(&self, owner: RecordId, blob: CreateBlob) -> Result<Blob, AppError> {
         let db = self.inner();
-        db.db
-            .create("blob")
+        db.create("blob")
             .content(BlobRecord::from_payload(
                 None,
                 Some(owner),
@@ -152,10 +150,9 @@ impl BlobRepository for SurrealBlobRepo {
         let (tb, sid) = resource_id("blob", id)?;
 
         let mut response = db
-            .db
             .query(
                 "UPDATE type::record($tb, $sid) SET file_type = $file_type, width = $width, \
-                 height = $height, ocr = $ocr WHERE owner IN $teams RETURN AFTER",
+                 height = $height, ocr = $ocr WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
             )
             .bind(("tb", tb))
             .bind(("sid", sid))
@@ -173,13 +170,21 @@ impl BlobRepository for SurrealBlobRepo {
             .ok_or_else(|| AppError::NotFound("blob not found".into()))
     }
 
-    async fn delete_blob(&self, write_teams: &[RecordId], id: &str) -> Result<Blob, AppError> {
+    async fn trash_blob(
+        &self,
+        write_teams: &[RecordId],
+        actor_user_id: &str,
+        id: &str,
+    ) -> Result<Blob, AppError> {
         let db = self.inner();
         let (tb, sid) = resource_id("blob", id)?;
         let mut response = db
-            .db
-            .query("DELETE FROM type::record($tb, $sid) WHERE owner IN $teams RETURN BEFORE")
+            .query(
+                "UPDATE type::record($tb, $sid) SET trashed_at = time::now(), trashed_by = $actor \
+                 WHERE owner IN $teams AND trashed_at = NONE RETURN BEFORE",
+            )
             .bind(("tb", tb))
+            .bind(("actor", RecordId::new("user", actor_user_id.to_owned())))
             .bind(("sid", sid))
             .bind(("teams", write_teams.to_vec()))
             .await?;
@@ -200,9 +205,8 @@ impl BlobRepository for SurrealBlobRepo {
         let db = self.inner();
         let (tb, sid) = resource_id("blob", id)?;
         let mut response = db
-            .db
             .query(
-                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams RETURN AFTER",
+                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
             )
             .bind(("tb", tb))
             .bind(("sid", sid))
@@ -216,4 +220,160 @@ impl BlobRepository for SurrealBlobRepo {
             .map(BlobRecord::into_blob)
             .ok_or_else(|| AppError::NotFound("blob not found".into()))
     }
+
+    async fn set_blob_content(&self, id: &str, sha256: &str) -> Result<Option<String>, AppError> {
+        let (tb, sid) = resource_id("blob", id)?;
+        let rows: Vec<BlobRecord> = self
+            .inner()
+            .query(
+                "UPDATE type::record($tb, $sid) SET sha256 = $sha256, variants = NONE, \
+                 ocr_status = NONE, ocr_error = NONE RETURN BEFORE",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("sha256", sha256.to_owned()))
+            .await?
+            .take(0)?;
+        rows.into_iter()
+            .next()
+            .map(|r| r.sha256)
+            .ok_or_else(|| AppError::NotFound("blob not found".into()))
+    }
+
+    async fn set_blob_image(
+        &self,
+        id: &str,
+        sha256: &str,
+        width: u32,
+        height: u32,
+        variants: &[BlobVariant],
+    ) -> Result<(), AppError> {
+        let (tb, sid) = resource_id("blob", id)?;
+        self.inner()
+            .query(
+                "UPDATE type::record($tb, $sid) SET width = $width, height = $height, \
+                 variants = $variants WHERE sha256 = $sha256",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("sha256", sha256.to_owned()))
+            .bind(("width", width))
+            .bind(("height", height))
+            .bind((
+                "variants",
+                variants
+                    .iter()
+                    .map(|v| v.as_str().to_owned())
+                    .collect::<Vec<_>>(),
+            ))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn queue_blob_ocr(&self, id: &str, sha256: &str) -> Result<(), AppError> {
+        let (tb, sid) = resource_id("blob", id)?;
+        self.inner()
+            .query(
+                "UPDATE type::record($tb, $sid) SET ocr_status = 'pending', ocr_error = NONE \
+                 WHERE sha256 = $sha256",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("sha256", sha256.to_owned()))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn start_blob_ocr(&self, id: &str, sha256: &str) -> Result<Option<Blob>, AppError> {
+        let (tb, sid) = resource_id("blob", id)?;
+        let rows: Vec<BlobRecord> = self
+            .inner()
+            .query(
+                "UPDATE type::record($tb, $sid) SET ocr_status = 'running' WHERE sha256 = $sha256 \
+                 AND ocr_status IN ['pending', 'running'] RETURN AFTER",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("sha256", sha256.to_owned()))
+            .await?
+            .take(0)?;
+        Ok(rows.into_iter().next().map(BlobRecord::into_blob))
+    }
+
+    async fn complete_blob_ocr(&self, id: &str, sha256: &str, text: &str) -> Result<(), AppError> {
+        let (tb, sid) = resource_id("blob", id)?;
+        self.inner()
+            .query(
+                "UPDATE type::record($tb, $sid) SET ocr = $text, ocr_status = 'done', \
+                 ocr_error = NONE WHERE sha256 = $sha256 AND ocr_status = 'running'",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("sha256", sha256.to_owned()))
+            .bind(("text", text.to_owned()))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn fail_blob_ocr(
+        &self,
+        id: &str,
+        sha256: &str,
+        error: &str,
+        retry: bool,
+    ) -> Result<(), AppError> {
+        let (tb, sid) = resource_id("blob", id)?;
+        self.inner()
+            .query(
+                "UPDATE type::record($tb, $sid) SET ocr_status = $status, ocr_error = $error \
+                 WHERE sha256 = $sha256 AND ocr_status IN ['pending', 'running']",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("sha256", sha256.to_owned()))
+            .bind(("status", if retry { "pending" } else { "failed" }))
+            .bind(("error", error.to_owned()))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn count_content_references(&self, sha256: &str) -> Result<u64, AppError> {
+        #[derive(Deserialize, SurrealValue)]
+        struct CountResult {
+            count: u64,
+        }
+        let rows: Vec<CountResult> = self
+            .inner()
+            .query("SELECT count() FROM blob WHERE sha256 = $sha256 GROUP ALL")
+            .bind(("sha256", sha256.to_owned()))
+            .await?
+            .take(0)?;
+        Ok(rows.into_iter().next().map(|r| r.count).unwrap_or(0))
+    }
+
+    async fn content_hashes(&self) -> Result<Vec<(String, String)>, AppError> {
+        let rows: Vec<BlobRecord> = self
+            .inner()
+            .query("SELECT * FROM blob WHERE sha256 != NONE")
+            .await?
+            .take(0)?;
+        Ok(rows
+            .into_iter()
+            .map(BlobRecord::into_blob)
+            .filter_map(|b| b.sha256.map(|sha| (b.id, sha)))
+            .collect())
+    }
+
+    async fn blobs_without_content(&self) -> Result<Vec<Blob>, AppError> {
+        let rows: Vec<BlobRecord> = self
+            .inner()
+            .query("SELECT * FROM blob WHERE sha256 = NONE")
+            .await?
+            .take(0)?;
+        Ok(rows.into_iter().map(BlobRecord::into_blob).collect())
+    }
 }
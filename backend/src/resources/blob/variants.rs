@@ -0,0 +1,215 @@
+//! Resized renditions of uploaded raster images (`thumb` and `display`).
+//!
+//! Each resized variant is stored as WebP and in the format of the upload (PNG or JPEG), next to
+//! the content it was generated from; `GET /blobs/{id}/data?variant=` picks one by `Accept`.
+//! SVG blobs have no variants: every request serves the original.
+
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader};
+
+use shared::blob::{BlobVariant, FileType};
+
+const JPEG_QUALITY: u8 = 85;
+
+/// Encoding of a stored variant file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantFormat {
+    WebP,
+    Png,
+    Jpeg,
+}
+
+impl VariantFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::WebP => "webp",
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Self::WebP => "image/webp",
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+        }
+    }
+
+    /// Format used for clients that do not accept WebP; `None` for file types without variants.
+    fn fallback_for(file_type: &FileType) -> Option<Self> {
+        match file_type {
+            FileType::PNG => Some(Self::Png),
+            FileType::JPEG => Some(Self::Jpeg),
+            FileType::SVG => None,
+        }
+    }
+
+    fn encode(&self, img: &DynamicImage) -> image::ImageResult<Vec<u8>> {
+        let mut buf = Vec::new();
+        match self {
+            // Neither encoder accepts every pixel layout the decoders produce (16-bit PNG, CMYK).
+            Self::WebP => DynamicImage::ImageRgba8(img.to_rgba8())
+                .write_to(Cursor::new(&mut buf), ImageFormat::WebP)?,
+            Self::Png => img.write_to(Cursor::new(&mut buf), ImageFormat::Png)?,
+            Self::Jpeg => DynamicImage::ImageRgb8(img.to_rgb8())
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, JPEG_QUALITY))?,
+        }
+        Ok(buf)
+    }
+}
+
+/// One stored file of a resized variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rendition {
+    pub variant: BlobVariant,
+    pub format: VariantFormat,
+}
+
+impl Rendition {
+    /// The rendition served for `variant` of a `file_type` blob; `None` means the original.
+    pub fn negotiate(
+        variant: BlobVariant,
+        file_type: &FileType,
+        accepts_webp: bool,
+    ) -> Option<Self> {
+        variant.max_side()?;
+        let fallback = VariantFormat::fallback_for(file_type)?;
+        Some(Self {
+            variant,
+            format: if accepts_webp {
+                VariantFormat::WebP
+            } else {
+                fallback
+            },
+        })
+    }
+
+    /// Name of the stored file, e.g. `thumb.webp`.
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.variant, self.format.extension())
+    }
+}
+
+/// Decoded size of an upload and the encoded files of its resized variants.
+pub struct RenderedImage {
+    pub width: u32,
+    pub height: u32,
+    pub files: Vec<(Rendition, Vec<u8>)>,
+}
+
+/// Decodes `data` as `file_type` and encodes every resized variant. `None` for SVG and for bytes
+/// that do not decode (within the decoder's default memory limits). CPU-bound: run it on a
+/// blocking thread.
+pub fn render(file_type: &FileType, data: &[u8]) -> Option<RenderedImage> {
+    let fallback = VariantFormat::fallback_for(file_type)?;
+    let source_format = match file_type {
+        FileType::PNG => ImageFormat::Png,
+        FileType::JPEG => ImageFormat::Jpeg,
+        FileType::SVG => return None,
+    };
+    let img = match ImageReader::with_format(Cursor::new(data), source_format).decode() {
+        Ok(img) => img,
+        Err(err) => {
+            tracing::debug!(error = %err, "blob bytes do not decode; no variants generated");
+            return None;
+        }
+    };
+    let mut files = Vec::new();
+    for variant in BlobVariant::RESIZED {
+        let max = variant.max_side()?;
+        // Never upscale: small images keep their size and only change encoding.
+        let resized = if img.width() > max || img.height() > max {
+            match variant {
+                BlobVariant::Thumb => img.thumbnail(max, max),
+                _ => img.resize(max, max, FilterType::Triangle),
+            }
+        } else {
+            img.clone()
+        };
+        for format in [VariantFormat::WebP, fallback] {
+            match format.encode(&resized) {
+                Ok(bytes) => files.push((Rendition { variant, format }, bytes)),
+                Err(err) => {
+                    tracing::warn!(error = %err, variant = %variant, "failed to encode blob variant");
+                    return None;
+                }
+            }
+        }
+    }
+    Some(RenderedImage {
+        width: img.width(),
+        height: img.height(),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let img = DynamicImage::new_rgb8(width, height);
+        let mut buf = Vec::new();
+        img.write_to(Cursor::new(&mut buf), ImageFormat::Png)
+            .unwrap();
+        buf
+    }
+
+    fn decoded_size(bytes: &[u8]) -> (u32, u32) {
+        let img = image::load_from_memory(bytes).unwrap();
+        (img.width(), img.height())
+    }
+
+    #[test]
+    fn renders_bounded_variants_in_webp_and_upload_format() {
+        let rendered = render(&FileType::PNG, &png(2000, 1000)).unwrap();
+        assert_eq!((rendered.width, rendered.height), (2000, 1000));
+        let names: Vec<String> = rendered.files.iter().map(|(r, _)| r.file_name()).collect();
+        assert_eq!(
+            names,
+            ["thumb.webp", "thumb.png", "display.webp", "display.png"]
+        );
+        for (rendition, bytes) in &rendered.files {
+            let expected = match rendition.variant {
+                BlobVariant::Thumb => (320, 160),
+                _ => (1600, 800),
+            };
+            assert_eq!(decoded_size(bytes), expected, "{}", rendition.file_name());
+        }
+    }
+
+    #[test]
+    fn small_images_are_not_upscaled() {
+        let rendered = render(&FileType::PNG, &png(100, 50)).unwrap();
+        for (_, bytes) in &rendered.files {
+            assert_eq!(decoded_size(bytes), (100, 50));
+        }
+    }
+
+    #[test]
+    fn svg_and_undecodable_bytes_have_no_variants() {
+        assert!(render(&FileType::SVG, b"<svg/>").is_none());
+        assert!(render(&FileType::PNG, b"not a png").is_none());
+        assert!(render(&FileType::JPEG, &png(10, 10)).is_none());
+    }
+
+    #[test]
+    fn negotiation_prefers_webp_and_keeps_svg_original() {
+        let thumb = Rendition::negotiate(BlobVariant::Thumb, &FileType::JPEG, true).unwrap();
+        assert_eq!(thumb.file_name(), "thumb.webp");
+        let thumb = Rendition::negotiate(BlobVariant::Thumb, &FileType::JPEG, false).unwrap();
+        assert_eq!(thumb.file_name(), "thumb.jpeg");
+        assert_eq!(
+            Rendition::negotiate(BlobVariant::Original, &FileType::PNG, true),
+            None
+        );
+        assert_eq!(
+            Rendition::negotiate(BlobVariant::Display, &FileType::SVG, true),
+            None
+        );
+    }
+}
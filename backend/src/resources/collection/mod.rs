@@ -5,6 +5,7 @@ mod repository;
 pub mod service;
 mod surreal_repo;
 
+pub(crate) use model::CollectionRecord;
 pub use repository::CollectionRepository;
 pub use service::{CollectionService, CollectionServiceHandle};
 pub use surreal_repo::SurrealCollectionRepo;
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use surrealdb::types::{RecordId, SurrealValue};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
 
 use shared::collection::{Collection, CreateCollection};
 
@@ -16,6 +16,9 @@ pub struct CollectionRecord {
     pub cover: Option<RecordId>,
     #[serde(default)]
     pub songs: Vec<SongLinkRecord>,
+    /// Set while the collection is in its team's trash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trashed_at: Option<Datetime>,
 }
 
 impl CollectionRecord {
@@ -46,6 +49,7 @@ impl CollectionRecord {
             title,
             cover: Some(blob_thing(&cover)),
             songs: songs.into_iter().map(Into::into).collect(),
+            trashed_at: None,
         }
     }
 }
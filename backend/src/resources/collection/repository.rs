@@ -49,9 +49,11 @@ pub trait CollectionRepository: Send + Sync {
         owner: Option<RecordId>,
     ) -> Result<Collection, AppError>;
 
-    async fn delete_collection(
+    /// Moves the collection into its team's trash (see the trash service for restore and purge).
+    async fn trash_collection(
         &self,
         write_teams: &[RecordId],
+        actor_user_id: &str,
         id: &str,
     ) -> Result<Collection, AppError>;
 
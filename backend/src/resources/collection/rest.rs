@@ -393,7 +393,7 @@ async fn move_collection(
         ("id" = String, Path, description = "Collection identifier")
     ),
     responses(
-        (status = 204, description = "Collection deleted"),
+        (status = 204, description = "Collection moved to its team's trash"),
         (status = 400, description = "Invalid collection identifier", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
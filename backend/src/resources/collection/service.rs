@@ -5,12 +5,15 @@ use shared::api::ListQuery;
 use shared::collection::{Collection, CreateCollection, PatchCollection};
 use shared::player::Player;
 use shared::song::Song;
+use shared::team::{ActivityAction, ActivityResourceType, TeamPermission};
 use tracing::instrument;
 
 use crate::database::Database;
 use crate::error::AppError;
 use crate::resources::common::{player_from_song_links, resolve_owner_team};
 use crate::resources::song::LikedSongIds;
+use crate::resources::team::activity::{ActivityRecorder, NewTeamActivity};
+use crate::resources::team::webhook::ContentEventRecorder;
 use crate::resources::team::{
     TeamResolver, UserPermissions, parse_owner_record_id, thing_record_key,
 };
@@ -20,19 +23,43 @@ use super::surreal_repo::SurrealCollectionRepo;
 
 /// Application service: team resolution, authorization, and orchestration for collections.
 #[derive(Clone)]
-pub struct CollectionService<R, T, L> {
+pub struct CollectionService<R, T, L, A> {
     pub repo: R,
     pub teams: Arc<T>,
     pub likes: L,
+    pub activity: A,
 }
 
-impl<R, T, L> CollectionService<R, T, L> {
-    pub fn new(repo: R, teams: Arc<T>, likes: L) -> Self {
-        Self { repo, teams, likes }
+impl<R, T, L, A> CollectionService<R, T, L, A> {
+    pub fn new(repo: R, teams: Arc<T>, likes: L, activity: A) -> Self {
+        Self {
+            repo,
+            teams,
+            likes,
+            activity,
+        }
     }
 }
 
-impl<R: CollectionRepository, T: TeamResolver, L: LikedSongIds> CollectionService<R, T, L> {
+fn collection_activity(
+    actor_user_id: &str,
+    collection: &Collection,
+    action: ActivityAction,
+) -> NewTeamActivity {
+    NewTeamActivity::new(
+        &collection.owner,
+        actor_user_id,
+        ActivityResourceType::Collection,
+        &collection.id,
+        action,
+    )
+    .with_title(Some(&collection.title))
+    .with_etag(collection)
+}
+
+impl<R: CollectionRepository, T: TeamResolver, L: LikedSongIds, A: ActivityRecorder>
+    CollectionService<R, T, L, A>
+{
     #[instrument(level = "debug", err, skip(self, perms))]
     pub async fn list_collections_for_user(
         &self,
@@ -112,11 +139,21 @@ impl<R: CollectionRepository, T: TeamResolver, L: LikedSongIds> CollectionServic
             None => perms.personal_team().await?,
             Some(ref s) => {
                 let rid = parse_owner_record_id(s)?;
-                perms.require_write_access_to_owner(&rid).await?;
+                perms
+                    .require_permission_on_owner(&rid, TeamPermission::CollectionsWrite)
+                    .await?;
                 rid
             }
         };
-        self.repo.create_collection(owner, collection).await
+        let created = self.repo.create_collection(owner, collection).await?;
+        self.activity
+            .record_activity_or_warn(collection_activity(
+                &perms.user().id,
+                &created,
+                ActivityAction::Created,
+            ))
+            .await;
+        Ok(created)
     }
 
     #[instrument(level = "debug", err, skip(self, perms, collection))]
@@ -127,11 +164,20 @@ impl<R: CollectionRepository, T: TeamResolver, L: LikedSongIds> CollectionServic
         collection: CreateCollection,
         owner: Option<String>,
     ) -> Result<Collection, AppError> {
-        let write_teams = perms.write_teams().await?;
-        let owner = resolve_owner_team(write_teams, owner)?;
-        self.repo
-            .update_collection(write_teams, id, collection, owner)
-            .await
+        let write_teams = perms.teams_with(TeamPermission::CollectionsWrite).await?;
+        let owner = resolve_owner_team(&write_teams, owner)?;
+        let updated = self
+            .repo
+            .update_collection(&write_teams, id, collection, owner)
+            .await?;
+        self.activity
+            .record_activity_or_warn(collection_activity(
+                &perms.user().id,
+                &updated,
+                ActivityAction::Updated,
+            ))
+            .await;
+        Ok(updated)
     }
 
     #[instrument(level = "debug", err, skip(self, perms, patch))]
@@ -166,10 +212,31 @@ impl<R: CollectionRepository, T: TeamResolver, L: LikedSongIds> CollectionServic
         if thing_record_key(&current) == thing_record_key(&dest) {
             return Ok(collection);
         }
-        perms.require_write_access_to_owner(&current).await?;
-        perms.require_write_access_to_owner(&dest).await?;
-        let write_teams = perms.write_teams().await?;
-        self.repo.move_collection_owner(write_teams, id, dest).await
+        perms
+            .require_permission_on_owner(&current, TeamPermission::CollectionsDelete)
+            .await?;
+        perms
+            .require_permission_on_owner(&dest, TeamPermission::CollectionsWrite)
+            .await?;
+        let source_teams = perms.teams_with(TeamPermission::CollectionsDelete).await?;
+        let moved = self
+            .repo
+            .move_collection_owner(&source_teams, id, dest)
+            .await?;
+        self.activity
+            .record_activity_or_warn(
+                collection_activity(&perms.user().id, &collection, ActivityAction::Moved)
+                    .with_other_team(&moved.owner)
+                    .with_etag(&moved),
+            )
+            .await;
+        self.activity
+            .record_activity_or_warn(
+                collection_activity(&perms.user().id, &moved, ActivityAction::Moved)
+                    .with_other_team(&collection.owner),
+            )
+            .await;
+        Ok(moved)
     }
 
     #[instrument(level = "debug", err, skip(self, perms))]
@@ -178,8 +245,19 @@ impl<R: CollectionRepository, T: TeamResolver, L: LikedSongIds> CollectionServic
         perms: &UserPermissions<T>,
         id: &str,
     ) -> Result<Collection, AppError> {
-        let write_teams = perms.write_teams().await?;
-        self.repo.delete_collection(write_teams, id).await
+        let delete_teams = perms.teams_with(TeamPermission::CollectionsDelete).await?;
+        let deleted = self
+            .repo
+            .trash_collection(&delete_teams, &perms.user().id, id)
+            .await?;
+        self.activity
+            .record_activity_or_warn(collection_activity(
+                &perms.user().id,
+                &deleted,
+                ActivityAction::Deleted,
+            ))
+            .await;
+        Ok(deleted)
     }
 }
 
@@ -188,6 +266,7 @@ pub type CollectionServiceHandle = CollectionService<
     SurrealCollectionRepo,
     crate::resources::team::SurrealTeamResolver,
     Arc<Database>,
+    ContentEventRecorder,
 >;
 
 impl CollectionServiceHandle {
@@ -202,7 +281,12 @@ impl CollectionServiceHandle {
         db: Arc<Database>,
         teams: Arc<crate::resources::team::SurrealTeamResolver>,
     ) -> Self {
-        CollectionService::new(SurrealCollectionRepo::new(db.clone()), teams, db.clone())
+        CollectionService::new(
+            SurrealCollectionRepo::new(db.clone()),
+            teams,
+            db.clone(),
+            ContentEventRecorder::build(db.clone()),
+        )
     }
 }
 
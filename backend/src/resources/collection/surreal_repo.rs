@@ -13,7 +13,8 @@ use shared::song::{Link as SongLink, LinkOwned as SongLinkOwned};
 use crate::database::{Database, surreal_take_errors};
 use crate::error::AppError;
 use crate::resources::common::{
-    SongLinkListRow, SongLinkRecord, belongs_to, blob_thing, resource_id, song_links_to_owned,
+    LIVE_SONG_LINKS, SongLinkListRow, SongLinkRecord, belongs_to, blob_thing, resource_id,
+    song_links_to_owned,
 };
 
 use super::model::CollectionRecord;
@@ -44,11 +45,13 @@ impl CollectionRepository for SurrealCollectionRepo {
         let db = self.inner();
         let q_nonempty = pagination.q.as_ref().is_some_and(|q| !q.trim().is_empty());
         let mut query = if q_nonempty {
-            String::from(
-                "SELECT *, (search::score(0) ?? 0) AS score FROM collection WHERE owner IN $teams",
+            format!(
+                "SELECT *, {LIVE_SONG_LINKS}, (search::score(0) ?? 0) AS score FROM collection WHERE owner IN $teams AND trashed_at = NONE",
             )
         } else {
-            String::from("SELECT * FROM collection WHERE owner IN $teams")
+            format!(
+                "SELECT *, {LIVE_SONG_LINKS} FROM collection WHERE owner IN $teams AND trashed_at = NONE"
+            )
         };
         if q_nonempty {
             query.push_str(" AND title @0@ $q ORDER BY score DESC");
@@ -56,7 +59,7 @@ impl CollectionRepository for SurrealCollectionRepo {
         let (offset, limit) = pagination.effective_offset_limit();
         query.push_str(" LIMIT $limit START $start");
 
-        let mut request = db.db.query(query).bind(("teams", read_teams.to_vec()));
+        let mut request = db.query(query).bind(("teams", read_teams.to_vec()));
         if let Some(ref q) = pagination.q
             && !q.trim().is_empty()
         {
@@ -83,7 +86,9 @@ impl CollectionRepository for SurrealCollectionRepo {
             count: u64,
         }
         let q_nonempty = q.is_some_and(|s| !s.trim().is_empty());
-        let mut query = String::from("SELECT count() FROM collection WHERE owner IN $teams");
+        let mut query = String::from(
+            "SELECT count() FROM collection WHERE owner IN $teams AND trashed_at = NONE",
+        );
         if q_nonempty {
             query.push_str(" AND title @0@ $q");
         }
@@ -91,7 +96,6 @@ impl CollectionRepository for SurrealCollectionRepo {
 
         let mut request = self
             .inner()
-            .db
             .query(query)
             .bind(("teams", read_teams.to_vec()));
         if q_nonempty {
@@ -112,9 +116,16 @@ impl CollectionRepository for SurrealCollectionRepo {
         id: &str,
     ) -> Result<Collection, AppError> {
         let db = self.inner();
-        let record: Option<CollectionRecord> = db.db.select(resource_id("collection", id)?).await?;
+        let resource = resource_id("collection", id)?;
+        let record: Option<CollectionRecord> = db
+            .query(format!("SELECT *, {LIVE_SONG_LINKS} FROM $id"))
+            .bind(("id", RecordId::new(resource.0, resource.1)))
+            .await?
+            .take(0)?;
         match record {
-            Some(r) if belongs_to(&r.owner, read_teams) => Ok(r.into_collection()),
+            Some(r) if r.trashed_at.is_none() && belongs_to(&r.owner, read_teams) => {
+                Ok(r.into_collection())
+            }
             _ => Err(AppError::NotFound("collection not found".into())),
         }
     }
@@ -127,8 +138,7 @@ impl CollectionRepository for SurrealCollectionRepo {
         let db = self.inner();
         let resource = resource_id("collection", id)?;
         let mut response = db
-            .db
-            .query("SELECT owner, songs FROM collection WHERE id = $id")
+            .query("SELECT owner, songs, trashed_at FROM collection WHERE id = $id")
             .bind(("id", RecordId::new(resource.0.clone(), resource.1.clone())))
             .await?;
 
@@ -136,11 +146,11 @@ impl CollectionRepository for SurrealCollectionRepo {
             .take::<Option<SongLinkListRow>>(0)?
             .ok_or_else(|| AppError::NotFound("collection not found".into()))?;
 
-        if !belongs_to(&record.owner, read_teams) {
+        if record.trashed_at.is_some() || !belongs_to(&record.owner, read_teams) {
             return Err(AppError::NotFound("collection not found".into()));
         }
 
-        song_links_to_owned(&db.db, record.songs).await
+        song_links_to_owned(db, record.songs).await
     }
 
     async fn create_collection(
@@ -149,8 +159,7 @@ impl CollectionRepository for SurrealCollectionRepo {
         collection: CreateCollection,
     ) -> Result<Collection, AppError> {
         let db = self.inner();
-        db.db
-            .create("collection")
+        db.create("collection")
             .content(CollectionRecord::from_payload(
                 None,
                 Some(owner),
@@ -175,10 +184,10 @@ impl CollectionRepository for SurrealCollectionRepo {
         let title = collection.title;
 
         let mut response = if let Some(ref owner_rid) = owner {
-            db.db
+            db
                 .query(
                     "UPDATE type::record($tb, $sid) SET title = $title, cover = $cover, songs = $songs, \
-                     owner = $owner WHERE owner IN $teams RETURN AFTER",
+                     owner = $owner WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
                 )
                 .bind(("tb", tb))
                 .bind(("sid", sid))
@@ -189,18 +198,17 @@ impl CollectionRepository for SurrealCollectionRepo {
                 .bind(("teams", write_teams.to_vec()))
                 .await?
         } else {
-            db.db
-                .query(
-                    "UPDATE type::record($tb, $sid) SET title = $title, cover = $cover, songs = $songs \
-                     WHERE owner IN $teams RETURN AFTER",
-                )
-                .bind(("tb", tb))
-                .bind(("sid", sid))
-                .bind(("title", title))
-                .bind(("cover", cover))
-                .bind(("songs", songs))
-                .bind(("teams", write_teams.to_vec()))
-                .await?
+            db.query(
+                "UPDATE type::record($tb, $sid) SET title = $title, cover = $cover, songs = $songs \
+                     WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("title", title))
+            .bind(("cover", cover))
+            .bind(("songs", songs))
+            .bind(("teams", write_teams.to_vec()))
+            .await?
         };
 
         let rows: Vec<CollectionRecord> = response.take(0)?;
@@ -210,17 +218,21 @@ impl CollectionRepository for SurrealCollectionRepo {
             .ok_or_else(|| AppError::NotFound("collection not found".into()))
     }
 
-    async fn delete_collection(
+    async fn trash_collection(
         &self,
         write_teams: &[RecordId],
+        actor_user_id: &str,
         id: &str,
     ) -> Result<Collection, AppError> {
         let db = self.inner();
         let (tb, sid) = resource_id("collection", id)?;
         let mut response = db
-            .db
-            .query("DELETE FROM type::record($tb, $sid) WHERE owner IN $teams RETURN BEFORE")
+            .query(
+                "UPDATE type::record($tb, $sid) SET trashed_at = time::now(), trashed_by = $actor \
+                 WHERE owner IN $teams AND trashed_at = NONE RETURN BEFORE",
+            )
             .bind(("tb", tb))
+            .bind(("actor", RecordId::new("user", actor_user_id.to_owned())))
             .bind(("sid", sid))
             .bind(("teams", write_teams.to_vec()))
             .await?;
@@ -241,9 +253,8 @@ impl CollectionRepository for SurrealCollectionRepo {
         let db = self.inner();
         let (tb, sid) = resource_id("collection", id)?;
         let mut response = db
-            .db
             .query(
-                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams RETURN AFTER",
+                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
             )
             .bind(("tb", tb))
             .bind(("sid", sid))
@@ -266,9 +277,8 @@ impl CollectionRepository for SurrealCollectionRepo {
     ) -> Result<(), AppError> {
         let db = self.inner();
         let mut response = db
-            .db
             .query(
-                r#"UPDATE type::record("collection", $id) SET songs = array::append(songs, $song) WHERE owner IN $teams;"#,
+                r#"UPDATE type::record("collection", $id) SET songs = array::append(songs, $song) WHERE owner IN $teams AND trashed_at = NONE;"#,
             )
             .bind(("id", id.to_owned()))
             .bind(("teams", write_teams.to_vec()))
@@ -2,14 +2,12 @@ use std::collections::{HashMap, HashSet};
 
 use chordlib::types::SimpleChord;
 use serde::{Deserialize, Serialize};
-use surrealdb::Surreal;
-use surrealdb::engine::any::Any;
-use surrealdb::types::{Kind, RecordId, SurrealValue, Value, kind};
+use surrealdb::types::{Datetime, Kind, RecordId, SurrealValue, Value, kind};
 
 use shared::player::Player;
 use shared::song::{Link as SongLink, LinkOwned as SongLinkOwned};
 
-use crate::database::record_id_string;
+use crate::database::{Database, record_id_string};
 use crate::error::AppError;
 use crate::resources::song::SongRecord;
 
@@ -126,13 +124,20 @@ pub struct SongLinkListRow {
     pub owner: Option<RecordId>,
     #[serde(default)]
     pub songs: Vec<SongLinkRecord>,
+    #[serde(default)]
+    pub trashed_at: Option<Datetime>,
 }
 
+/// Setlist / collection projection that hides links to trashed songs. The stored links are kept,
+/// so restoring a song brings it back into every list it was part of.
+pub(crate) const LIVE_SONG_LINKS: &str = "songs[WHERE id.trashed_at = NONE] AS songs";
+
 /// Load full [`Song`] values for setlist/collection link rows (`array<object>` with `id: record<song>`).
+/// Links to trashed songs are skipped but stay stored, so restoring the song brings them back.
 ///
 /// SurrealDB 3.0.x does not apply multi-part `FETCH` paths per array element the way 2.x did, so we batch `song` rows.
 pub async fn song_links_to_owned(
-    db: &Surreal<Any>,
+    db: &Database,
     links: Vec<SongLinkRecord>,
 ) -> Result<Vec<SongLinkOwned>, AppError> {
     if links.is_empty() {
@@ -162,6 +167,9 @@ pub async fn song_links_to_owned(
                 "referenced song not found (collection or setlist data may be inconsistent)",
             )
         })?;
+        if rec.trashed_at.is_some() {
+            continue;
+        }
         out.push(SongLinkOwned {
             song: rec.into_song(),
             nr: link.nr,
@@ -0,0 +1,7 @@
+pub use shared::event::ChangeEvent;
+
+pub mod service;
+
+pub use service::{ChangeSubscription, EventService, EventServiceHandle, StreamItem};
+
+pub mod rest;
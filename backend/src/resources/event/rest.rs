@@ -0,0 +1,64 @@
+use actix_web::{
+    HttpResponse, get,
+    http::header,
+    web::{Bytes, Data, ReqData},
+};
+use futures_util::stream::{self, StreamExt};
+#[allow(unused_imports)]
+use shared::event::ChangeEvent;
+use shared::user::User;
+
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+
+use super::service::{EventServiceHandle, StreamItem};
+
+/// Sent once on connect; `retry` is how long `EventSource` waits before reconnecting.
+const STREAM_PREAMBLE: &str = ": connected\nretry: 5000\n\n";
+
+fn sse_frame(item: &StreamItem) -> String {
+    match item {
+        StreamItem::Change(event) => format!(
+            "event: change\ndata: {}\n\n",
+            serde_json::to_string(event).unwrap_or_else(|_| "{}".to_owned())
+        ),
+        StreamItem::Resync => "event: resync\ndata: {}\n\n".to_owned(),
+        StreamItem::KeepAlive => ": keep-alive\n\n".to_owned(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    responses(
+        (status = 200, description = "Server-Sent Events stream of library changes the user can read. Each `change` event carries a `ChangeEvent` as JSON data (resource type, id, action and, except for deletions, the new weak ETag). A `resync` event means notifications were dropped and the client should refetch. Comment lines are keep-alives. There is no replay: after reconnecting, refetch or call `/sync` (BLC-EVENT-001..006).", body = ChangeEvent, content_type = "text/event-stream"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to open the stream", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Events",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/events")]
+pub async fn get_events(
+    svc: Data<EventServiceHandle>,
+    user: ReqData<User>,
+) -> Result<HttpResponse, AppError> {
+    let subscription = svc.subscribe_for_user(user.into_inner()).await?;
+    let events = stream::unfold(subscription, |mut subscription| async move {
+        let item = subscription.next().await?;
+        Some((Bytes::from(sse_frame(&item)), subscription))
+    });
+    let body = stream::once(async { Bytes::from_static(STREAM_PREAMBLE.as_bytes()) })
+        .chain(events)
+        .map(Ok::<_, actix_web::Error>);
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(body))
+}
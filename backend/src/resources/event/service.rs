@@ -0,0 +1,359 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use surrealdb::types::RecordId;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{Instant, Interval, MissedTickBehavior, interval_at};
+use tracing::{instrument, warn};
+
+use shared::event::ChangeEvent;
+use shared::user::User;
+
+use crate::database::{ChangeNotice, Database};
+use crate::error::AppError;
+use crate::resources::team::{SurrealTeamResolver, TeamResolver};
+
+/// Idle time after which a stream sends a keep-alive comment and re-resolves the user's teams,
+/// so proxies keep the connection open and revoked memberships stop delivering events.
+const KEEP_ALIVE_SECONDS: u64 = 15;
+
+/// Application service for `GET /api/v1/events`: live change notifications for the library a
+/// user can read.
+#[derive(Clone)]
+pub struct EventService<T> {
+    db: Arc<Database>,
+    pub teams: Arc<T>,
+    keep_alive: Duration,
+}
+
+pub type EventServiceHandle = EventService<SurrealTeamResolver>;
+
+impl<T> EventService<T> {
+    pub fn new(db: Arc<Database>, teams: Arc<T>) -> Self {
+        Self {
+            db,
+            teams,
+            keep_alive: Duration::from_secs(KEEP_ALIVE_SECONDS),
+        }
+    }
+}
+
+impl EventServiceHandle {
+    pub fn build(db: Arc<Database>) -> Self {
+        let teams = Arc::new(SurrealTeamResolver::new(db.clone()));
+        Self::new(db, teams)
+    }
+}
+
+/// What a change stream delivers next.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamItem {
+    Change(ChangeEvent),
+    /// Notifications were dropped because the client fell behind; it should refetch.
+    Resync,
+    KeepAlive,
+}
+
+/// One user's view of the change bus, filtered to the teams they can read.
+pub struct ChangeSubscription<T> {
+    receiver: broadcast::Receiver<ChangeNotice>,
+    teams: Arc<T>,
+    user: User,
+    read_teams: Vec<RecordId>,
+    keep_alive: Interval,
+}
+
+impl<T: TeamResolver> EventService<T> {
+    /// Subscribe before resolving the readable teams, so no change committed in between is lost.
+    #[instrument(level = "debug", err, skip(self, user), fields(user_id = %user.id))]
+    pub async fn subscribe_for_user(&self, user: User) -> Result<ChangeSubscription<T>, AppError> {
+        let receiver = self.db.subscribe_changes();
+        let read_teams = self.teams.content_read_teams(&user).await?;
+        let mut keep_alive = interval_at(Instant::now() + self.keep_alive, self.keep_alive);
+        keep_alive.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Ok(ChangeSubscription {
+            receiver,
+            teams: self.teams.clone(),
+            user,
+            read_teams,
+            keep_alive,
+        })
+    }
+}
+
+impl<T: TeamResolver> ChangeSubscription<T> {
+    /// Wait for the next event for this user; `None` once the bus is gone.
+    pub async fn next(&mut self) -> Option<StreamItem> {
+        loop {
+            tokio::select! {
+                received = self.receiver.recv() => match received {
+                    Ok(notice) if self.read_teams.contains(&notice.team) => {
+                        self.keep_alive.reset();
+                        return Some(StreamItem::Change(notice.event));
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(_)) => {
+                        self.keep_alive.reset();
+                        return Some(StreamItem::Resync);
+                    }
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = self.keep_alive.tick() => {
+                    self.refresh_read_teams().await;
+                    return Some(StreamItem::KeepAlive);
+                }
+            }
+        }
+    }
+
+    /// A failed lookup keeps the previous teams; the next keep-alive tries again.
+    async fn refresh_read_teams(&mut self) {
+        match self.teams.content_read_teams(&self.user).await {
+            Ok(teams) => self.read_teams = teams,
+            Err(err) => {
+                warn!(error = %err, user_id = %self.user.id, "event stream team refresh failed")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use shared::MoveOwner;
+    use shared::event::ChangeEvent;
+    use shared::song::CreateSong;
+    use shared::team::{ActivityAction, TrashResourceType};
+
+    use crate::resources::team::UserPermissions;
+    use crate::test_helpers::{
+        TeamFixture, create_user, event_service, minimal_song_data, personal_team_id, song_service,
+        test_db,
+    };
+
+    use surrealdb::types::RecordId;
+
+    use crate::database::ChangeNotice;
+
+    use super::StreamItem;
+
+    /// Next item of `sub`, failing the test instead of hanging when nothing arrives.
+    async fn next_item<T: crate::resources::team::TeamResolver>(
+        sub: &mut super::ChangeSubscription<T>,
+    ) -> StreamItem {
+        tokio::time::timeout(Duration::from_secs(5), sub.next())
+            .await
+            .expect("event within timeout")
+            .expect("bus open")
+    }
+
+    /// Next change to a resource of `resource_type`, skipping side effects such as the default
+    /// collection a first song creates.
+    async fn next_change<T: crate::resources::team::TeamResolver>(
+        sub: &mut super::ChangeSubscription<T>,
+        resource_type: TrashResourceType,
+    ) -> ChangeEvent {
+        loop {
+            if let StreamItem::Change(event) = next_item(sub).await
+                && event.resource_type == resource_type
+            {
+                return event;
+            }
+        }
+    }
+
+    /// BLC-EVENT-001, BLC-EVENT-002: writes to a readable team are pushed with the new ETag;
+    /// writes to other users' teams are not.
+    #[tokio::test]
+    async fn blc_event_001_pushes_readable_changes_only() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "events-owner@test.local")
+            .await
+            .expect("u");
+        let stranger = create_user(&db, "events-stranger@test.local")
+            .await
+            .expect("u");
+        let events = event_service(&db);
+        let mut sub = events
+            .subscribe_for_user(owner.clone())
+            .await
+            .expect("subscribe");
+
+        let songs = song_service(&db);
+        let stranger_perms = UserPermissions::from_ref(&stranger, &songs.teams);
+        songs
+            .create_song_for_user(
+                &stranger_perms,
+                CreateSong {
+                    owner: None,
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .expect("stranger song");
+        let perms = UserPermissions::from_ref(&owner, &songs.teams);
+        let song = songs
+            .create_song_for_user(
+                &perms,
+                CreateSong {
+                    owner: None,
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .expect("song");
+
+        let event = next_change(&mut sub, TrashResourceType::Song).await;
+        assert_eq!(event.id, song.id);
+        assert_eq!(event.action, ActivityAction::Created);
+        assert_eq!(
+            event.etag,
+            Some(crate::resources::song::song_etag(&song).expect("etag"))
+        );
+
+        songs
+            .delete_song_for_user(&perms, &song.id)
+            .await
+            .expect("delete");
+        let event = next_change(&mut sub, TrashResourceType::Song).await;
+        assert_eq!(event.action, ActivityAction::Deleted);
+        assert_eq!(event.etag, None);
+    }
+
+    /// BLC-EVENT-003: a move is announced to readers of the source and of the destination team.
+    #[tokio::test]
+    async fn blc_event_003_move_reaches_both_teams() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let songs = song_service(&db);
+        let admin_perms = UserPermissions::from_ref(&fx.admin_user, &songs.teams);
+        let song = songs
+            .create_song_for_user(
+                &admin_perms,
+                CreateSong {
+                    owner: Some(fx.shared_team_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .expect("song");
+        let events = event_service(&db);
+        let mut writer = events
+            .subscribe_for_user(fx.writer.clone())
+            .await
+            .expect("subscribe");
+
+        let admin_team = personal_team_id(&db, &fx.admin_user).await.expect("team");
+        let moved = songs
+            .move_song_for_user(&admin_perms, &song.id, MoveOwner { owner: admin_team })
+            .await
+            .expect("move");
+
+        let StreamItem::Change(event) = next_item(&mut writer).await else {
+            panic!("expected a change");
+        };
+        assert_eq!(event.id, song.id);
+        assert_eq!(event.action, ActivityAction::Moved);
+        assert_eq!(
+            event.etag,
+            Some(crate::resources::song::song_etag(&moved).expect("etag"))
+        );
+    }
+
+    /// BLC-EVENT-001: the pushed ETag of a song is the one every reader's GET returns, even when
+    /// the user who wrote it likes the song.
+    #[tokio::test]
+    async fn blc_event_001_song_etag_ignores_the_writers_like() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let songs = song_service(&db);
+        let admin_perms = UserPermissions::from_ref(&fx.admin_user, &songs.teams);
+        let song = songs
+            .create_song_for_user(
+                &admin_perms,
+                CreateSong {
+                    owner: Some(fx.shared_team_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .expect("song");
+        songs
+            .set_song_like_status_for_user(&admin_perms, &song.id, true)
+            .await
+            .expect("like");
+        let events = event_service(&db);
+        let mut writer = events
+            .subscribe_for_user(fx.writer.clone())
+            .await
+            .expect("subscribe");
+
+        let updated = songs
+            .update_song_for_user(
+                &admin_perms,
+                &song.id,
+                CreateSong {
+                    owner: None,
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+                None,
+            )
+            .await
+            .expect("update")
+            .into_song();
+        assert!(updated.user_specific_addons.liked);
+
+        let event = next_change(&mut writer, TrashResourceType::Song).await;
+        assert_eq!(event.action, ActivityAction::Updated);
+        let writer_perms = UserPermissions::from_ref(&fx.writer, &songs.teams);
+        let fetched = songs
+            .get_song_for_user(&writer_perms, &song.id)
+            .await
+            .expect("get");
+        assert!(!fetched.user_specific_addons.liked);
+        assert_eq!(
+            event.etag,
+            Some(crate::resources::song::song_etag(&fetched).expect("etag"))
+        );
+    }
+
+    /// BLC-EVENT-004: changes published inside a transaction are broadcast on commit and dropped
+    /// when it is cancelled.
+    #[tokio::test]
+    async fn blc_event_004_transactions_publish_on_commit() {
+        let db = test_db().await.expect("db");
+        let mut receiver = db.subscribe_changes();
+        let notice = || ChangeNotice {
+            team: RecordId::new("team", "t"),
+            event: ChangeEvent {
+                resource_type: TrashResourceType::Setlist,
+                id: "s".into(),
+                action: ActivityAction::Created,
+                etag: None,
+            },
+        };
+
+        let txn = db.begin().await.expect("begin");
+        txn.publish_change(notice());
+        assert!(receiver.try_recv().is_err());
+        txn.cancel().await.expect("cancel");
+        assert!(receiver.try_recv().is_err());
+
+        let txn = db.begin().await.expect("begin");
+        txn.publish_change(notice());
+        assert!(receiver.try_recv().is_err());
+        txn.commit().await.expect("commit");
+        assert_eq!(receiver.try_recv().expect("notice").event.id, "s");
+    }
+}
@@ -0,0 +1,18 @@
+pub use shared::job::{Job, JobKind, JobStatus};
+
+mod model;
+pub use model::retry_delay;
+
+pub mod repository;
+pub use repository::JobRepository;
+
+mod surreal_repo;
+pub use surreal_repo::SurrealJobRepo;
+
+pub mod worker;
+pub use worker::{JobContext, JobHandler};
+
+pub mod service;
+pub use service::{JobService, JobServiceHandle};
+
+pub mod rest;
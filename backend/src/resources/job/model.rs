@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::job::{Job, JobKind, JobStatus};
+
+use crate::database::record_id_string;
+use crate::error::AppError;
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct JobRow {
+    pub id: RecordId,
+    pub kind: String,
+    #[serde(default)]
+    pub user: Option<RecordId>,
+    pub status: String,
+    pub payload: String,
+    #[serde(default)]
+    pub result: Option<String>,
+    pub progress: u8,
+    pub attempts: u32,
+    pub max_attempts: u32,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    pub cancel_requested: bool,
+    pub run_at: Datetime,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub started_at: Option<Datetime>,
+    #[serde(default)]
+    pub finished_at: Option<Datetime>,
+}
+
+impl JobRow {
+    pub fn into_job(self) -> Result<Job, AppError> {
+        Ok(Job {
+            id: record_id_string(&self.id),
+            kind: JobKind::parse(&self.kind)
+                .ok_or_else(|| AppError::database(format!("unknown job.kind {:?}", self.kind)))?,
+            user_id: self.user.as_ref().map(record_id_string),
+            status: JobStatus::parse(&self.status).ok_or_else(|| {
+                AppError::database(format!("unknown job.status {:?}", self.status))
+            })?,
+            payload: parse_json("job.payload", &self.payload)?,
+            result: self
+                .result
+                .as_deref()
+                .map(|r| parse_json("job.result", r))
+                .transpose()?,
+            progress: self.progress,
+            attempts: self.attempts,
+            max_attempts: self.max_attempts,
+            last_error: self.last_error,
+            cancel_requested: self.cancel_requested,
+            run_at: self.run_at.into(),
+            created_at: self.created_at.into(),
+            started_at: self.started_at.map(Into::into),
+            finished_at: self.finished_at.map(Into::into),
+        })
+    }
+}
+
+fn parse_json(field: &str, value: &str) -> Result<serde_json::Value, AppError> {
+    serde_json::from_str(value).map_err(|e| AppError::database(format!("invalid {field}: {e}")))
+}
+
+#[derive(Serialize, SurrealValue)]
+pub struct JobCreate {
+    pub kind: String,
+    pub user: Option<RecordId>,
+    pub payload: String,
+    pub max_attempts: u32,
+}
+
+/// Final state written when a running job ends. Succeeded jobs get progress 100; others keep
+/// the progress of their last attempt.
+#[derive(Serialize, SurrealValue)]
+pub struct JobFinish {
+    pub status: String,
+    pub result: Option<String>,
+    pub last_error: Option<String>,
+    pub finished_at: Datetime,
+}
+
+/// Delay before retry number `attempt` (1-based count of failed attempts so far): 10s, 20s,
+/// 40s, … capped at one hour.
+pub fn retry_delay(attempt: u32) -> chrono::Duration {
+    const BASE_SECONDS: i64 = 10;
+    const MAX_SECONDS: i64 = 60 * 60;
+    let factor = 1i64 << attempt.saturating_sub(1).min(20);
+    chrono::Duration::seconds((BASE_SECONDS * factor).min(MAX_SECONDS))
+}
+
+/// Whether a failed attempt of `job` is retried, and if so when.
+pub fn retry_at(job: &Job, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    (job.attempts < job.max_attempts).then(|| now + retry_delay(job.attempts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// BLC-JOB-003: retries back off exponentially up to an hour.
+    #[test]
+    fn blc_job_003_retry_delay_doubles_and_caps() {
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(10));
+        assert_eq!(retry_delay(2), chrono::Duration::seconds(20));
+        assert_eq!(retry_delay(4), chrono::Duration::seconds(80));
+        assert_eq!(retry_delay(30), chrono::Duration::hours(1));
+    }
+}
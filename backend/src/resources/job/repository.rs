@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use surrealdb::types::{Datetime, RecordId};
+
+use crate::error::AppError;
+
+use super::model::{JobCreate, JobFinish, JobRow};
+
+/// Pure job queue data access — no authorization.
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    async fn create_job(&self, create: JobCreate) -> Result<JobRow, AppError>;
+
+    async fn get_job(&self, job: RecordId) -> Result<Option<JobRow>, AppError>;
+
+    /// Jobs of a user, newest first.
+    async fn list_jobs_for_user(
+        &self,
+        user: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<JobRow>, AppError>;
+
+    async fn count_jobs_for_user(&self, user: RecordId) -> Result<u64, AppError>;
+
+    /// Marks the queued job of one of `kinds` that has been due longest as running (one more
+    /// attempt, progress reset), leased to the caller until `lease_until`, and returns it; `None`
+    /// when nothing is due. Safe to call from several workers at once.
+    async fn claim_job(
+        &self,
+        kinds: &[String],
+        now: Datetime,
+        lease_until: Datetime,
+    ) -> Result<Option<JobRow>, AppError>;
+
+    // The writes of a running attempt below are fenced by `attempt`, the job's `attempts` as
+    // claimed: once the lease ran out and the job was claimed again, they match nothing.
+
+    /// Moves the lease of a running attempt to `lease_until`; `false` when the attempt lost it.
+    async fn extend_lease(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        lease_until: Datetime,
+    ) -> Result<bool, AppError>;
+
+    /// Stores the progress of a running attempt and returns the job as updated; `None` when the
+    /// attempt lost its lease.
+    async fn set_progress(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        progress: u8,
+    ) -> Result<Option<JobRow>, AppError>;
+
+    /// Ends a running attempt; `None` when the job is not running it (any more).
+    async fn finish_job(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        finish: JobFinish,
+    ) -> Result<Option<JobRow>, AppError>;
+
+    /// Puts a job back into the queue until `run_at` after its running attempt failed; `None`
+    /// when the job is not running that attempt (any more).
+    async fn retry_job(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        error: &str,
+        run_at: Datetime,
+    ) -> Result<Option<JobRow>, AppError>;
+
+    /// Queues the scheduled job `id` (of `create.kind`) for the period starting at `period`,
+    /// creating it on first use. A job that is queued or running, or already ran for `period`,
+    /// is left alone. Returns whether it was queued. Safe to call from several instances.
+    async fn schedule_job(
+        &self,
+        id: &str,
+        create: JobCreate,
+        period: Datetime,
+    ) -> Result<bool, AppError>;
+
+    /// Cancels a queued job; `None` when it is not queued.
+    async fn cancel_queued_job(&self, job: RecordId) -> Result<Option<JobRow>, AppError>;
+
+    /// Flags a running job for cancellation; `None` when it is not running.
+    async fn request_cancel(&self, job: RecordId) -> Result<Option<JobRow>, AppError>;
+
+    /// Ends running jobs whose lease ran out by `now` and that have no attempts left as failed
+    /// with `error`, and returns them. Jobs flagged for cancellation are left to the requeue.
+    async fn fail_expired(&self, now: Datetime, error: &str) -> Result<Vec<JobRow>, AppError>;
+
+    /// Queues running jobs whose lease ran out by `now` and that have attempts left again, due
+    /// at once: their worker stopped. Returns how many.
+    async fn requeue_expired(&self, now: Datetime) -> Result<u64, AppError>;
+}
@@ -0,0 +1,129 @@
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, Scope, get, post,
+    web::{self, Data, Path, Query, ReqData},
+};
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+#[allow(unused_imports)]
+use shared::job::Job;
+use shared::user::User;
+
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+
+use super::service::JobServiceHandle;
+
+pub fn scope() -> Scope {
+    web::scope("/jobs").service(get_job).service(cancel_job)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{id}",
+    params(
+        ("id" = String, Path, description = "Job identifier")
+    ),
+    responses(
+        (status = 200, description = "Status and progress of a background job", body = Job),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Job not found, or it belongs to another user (platform admins see every job)", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to load job", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Jobs",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/{id}")]
+pub async fn get_job(
+    svc: Data<JobServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.get_job_for_user(&user, &id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs/{id}/cancel",
+    params(
+        ("id" = String, Path, description = "Job identifier")
+    ),
+    responses(
+        (status = 200, description = "A queued job is cancelled at once; a running job gets `cancel_requested` and ends as `cancelled` when its worker stops", body = Job),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Job not found, or it belongs to another user", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Job already finished", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to cancel job", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Jobs",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/{id}/cancel")]
+pub async fn cancel_job(
+    svc: Data<JobServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.cancel_job_for_user(&user, &id).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/jobs",
+    params(
+        ("page" = Option<u32>, Query, description = "Page index, zero-based. Omit with `page_size` for full list.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50. Omit with `page` for full list.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Background jobs started for the current user, newest first. `X-Total-Count` is the total before paging.", body = [Job]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to list jobs", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/me/jobs")]
+pub async fn get_jobs_for_current_user(
+    req: HttpRequest,
+    svc: Data<JobServiceHandle>,
+    user: ReqData<User>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let q_link = query.clone();
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let (jobs, total) = svc.list_jobs_for_user(&user, query.as_list_query()).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                &req,
+                |p| q_link.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(jobs))
+}
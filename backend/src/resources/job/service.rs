@@ -0,0 +1,993 @@
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures_util::FutureExt;
+use serde::Serialize;
+use surrealdb::types::RecordId;
+use tracing::instrument;
+
+use shared::api::ListQuery;
+use shared::job::{Job, JobKind, JobStatus};
+use shared::user::{Role as UserRole, User};
+
+use crate::database::{Database, record_id_string};
+use crate::error::AppError;
+use crate::resources::common::resource_id;
+
+use super::model::{JobCreate, JobFinish, JobRow, retry_at};
+use super::repository::JobRepository;
+use super::surreal_repo::SurrealJobRepo;
+use super::worker::{JobContext, JobHandler};
+
+/// How long a claimed job stays leased to its worker unless the worker renews the lease.
+const DEFAULT_LEASE: Duration = Duration::from_secs(60);
+
+/// `last_error` of a job whose worker stopped during its last attempt.
+const INTERRUPTED_ERROR: &str = "worker stopped during the last attempt";
+
+/// Scheduled jobs get one attempt per period; the next period runs them again anyway.
+const SCHEDULED_MAX_ATTEMPTS: u32 = 1;
+
+/// Application service for the background job queue: enqueueing, status for users, and the
+/// worker pool that runs registered handlers.
+#[derive(Clone)]
+pub struct JobService<R> {
+    pub repo: R,
+    handlers: Arc<HashMap<JobKind, Arc<dyn JobHandler>>>,
+    schedules: Arc<Vec<(JobKind, Duration)>>,
+    lease: Duration,
+}
+
+impl<R> JobService<R> {
+    pub fn new(repo: R) -> Self {
+        Self {
+            repo,
+            handlers: Arc::new(HashMap::new()),
+            schedules: Arc::new(Vec::new()),
+            lease: DEFAULT_LEASE,
+        }
+    }
+
+    /// Leases claimed jobs for `lease` (at least one second). Workers renew the lease every third
+    /// of it while the handler runs; a job whose lease ran out is queued again.
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease.max(Duration::from_secs(1));
+        self
+    }
+
+    fn lease_until(&self, from: DateTime<Utc>) -> DateTime<Utc> {
+        TimeDelta::from_std(self.lease)
+            .ok()
+            .and_then(|lease| from.checked_add_signed(lease))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    /// Runs jobs of `kind` with `handler`. Jobs of kinds without a handler stay queued.
+    pub fn with_handler(mut self, kind: JobKind, handler: Arc<dyn JobHandler>) -> Self {
+        Arc::make_mut(&mut self.handlers).insert(kind, handler);
+        self
+    }
+
+    /// Runs `kind` with `handler` once per `every` (at least one second) as a system job that
+    /// [`run_workers`](JobService::run_workers) queues at the start of each period.
+    pub fn with_schedule(
+        mut self,
+        kind: JobKind,
+        every: Duration,
+        handler: Arc<dyn JobHandler>,
+    ) -> Self {
+        Arc::make_mut(&mut self.schedules).push((kind, every.max(Duration::from_secs(1))));
+        self.with_handler(kind, handler)
+    }
+}
+
+/// Id of the one job a schedule of `kind` reuses: `trash.purge` runs as `job:trash_purge`.
+fn scheduled_job_id(kind: JobKind) -> String {
+    kind.as_str().replace('.', "_")
+}
+
+fn job_thing(id: &str) -> Result<RecordId, AppError> {
+    let (tb, sid) = resource_id("job", id)?;
+    Ok(RecordId::new(tb, sid))
+}
+
+/// Logs an attempt whose lease ran out and whose job was claimed again before it ended; its
+/// outcome is dropped.
+fn lost_lease(job: &Job) {
+    tracing::warn!(job_id = %job.id, kind = job.kind.as_str(), attempts = job.attempts, "job attempt lost its lease");
+}
+
+impl<R: JobRepository> JobService<R> {
+    /// Queues a job for `user_id` (`None` for work the server starts on its own), due at once.
+    #[instrument(level = "debug", err, skip(self, payload))]
+    pub async fn enqueue(
+        &self,
+        kind: JobKind,
+        user_id: Option<&str>,
+        payload: &(impl Serialize + Sync),
+        max_attempts: u32,
+    ) -> Result<Job, AppError> {
+        let payload = serde_json::to_string(payload)
+            .map_err(|e| AppError::internal_from_err("job.payload", e))?;
+        self.repo
+            .create_job(JobCreate {
+                kind: kind.as_str().to_owned(),
+                user: user_id.map(|id| RecordId::new("user", id.to_owned())),
+                payload,
+                max_attempts: max_attempts.max(1),
+            })
+            .await?
+            .into_job()
+    }
+
+    /// A job of the user; platform admins may read any job.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn get_job_for_user(&self, user: &User, id: &str) -> Result<Job, AppError> {
+        self.load_job_for_user(user, id).await?.into_job()
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_jobs_for_user(
+        &self,
+        user: &User,
+        pagination: ListQuery,
+    ) -> Result<(Vec<Job>, u64), AppError> {
+        let owner = RecordId::new("user", user.id.clone());
+        let (offset, limit) = pagination.effective_offset_limit();
+        let total = self.repo.count_jobs_for_user(owner.clone()).await?;
+        let jobs = self
+            .repo
+            .list_jobs_for_user(owner, offset, limit)
+            .await?
+            .into_iter()
+            .map(JobRow::into_job)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((jobs, total))
+    }
+
+    /// Cancels a queued job right away; a running job is flagged and stops at its handler's
+    /// next progress report. Finished jobs are a conflict.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn cancel_job_for_user(&self, user: &User, id: &str) -> Result<Job, AppError> {
+        let row = self.load_job_for_user(user, id).await?;
+        if let Some(cancelled) = self.repo.cancel_queued_job(row.id.clone()).await? {
+            let job = cancelled.into_job()?;
+            self.notify_cancelled(&job).await;
+            return Ok(job);
+        }
+        if let Some(flagged) = self.repo.request_cancel(row.id.clone()).await? {
+            return flagged.into_job();
+        }
+        let current = self
+            .repo
+            .get_job(row.id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("job not found".into()))?
+            .into_job()?;
+        Err(AppError::conflict(format!(
+            "job already {}",
+            current.status.as_str()
+        )))
+    }
+
+    /// Queues the scheduled job of `kind` for the period of length `every` that `now` falls in,
+    /// unless it is queued or running or already ran in that period. Periods count from the
+    /// Unix epoch, so all instances agree on them and each period runs once. Returns whether
+    /// the job was queued.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn schedule_due(
+        &self,
+        kind: JobKind,
+        every: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<bool, AppError> {
+        let seconds = i64::try_from(every.as_secs().max(1)).unwrap_or(i64::MAX);
+        let period = DateTime::from_timestamp(now.timestamp().div_euclid(seconds) * seconds, 0)
+            .unwrap_or(now);
+        self.repo
+            .schedule_job(
+                &scheduled_job_id(kind),
+                JobCreate {
+                    kind: kind.as_str().to_owned(),
+                    user: None,
+                    payload: "{}".to_owned(),
+                    max_attempts: SCHEDULED_MAX_ATTEMPTS,
+                },
+                period.into(),
+            )
+            .await
+    }
+
+    /// Queues running jobs whose lease ran out by `now` again: the process running them
+    /// stopped. Jobs whose interrupted attempt was their last fail instead. Jobs that live
+    /// workers keep leased are left alone. Returns how many were queued again.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn requeue_interrupted(&self, now: DateTime<Utc>) -> Result<u64, AppError> {
+        let failed = self
+            .repo
+            .fail_expired(now.into(), INTERRUPTED_ERROR)
+            .await?;
+        for row in failed {
+            let job = row.into_job()?;
+            tracing::warn!(job_id = %job.id, kind = job.kind.as_str(), attempts = job.attempts, "job failed, worker stopped during its last attempt");
+            let Some(handler) = self.handlers.get(&job.kind) else {
+                continue;
+            };
+            if let Err(e) = handler.interrupted(&job).await {
+                tracing::warn!(job_id = %job.id, error = %e, "job interruption hook failed");
+            }
+        }
+        self.repo.requeue_expired(now.into()).await
+    }
+
+    /// Claims the next job due at `now` that has a handler and runs one attempt of it.
+    /// Returns `false` when nothing was due.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn run_next(&self, now: DateTime<Utc>) -> Result<bool, AppError> {
+        let kinds: Vec<String> = self
+            .handlers
+            .keys()
+            .map(|k| k.as_str().to_owned())
+            .collect();
+        if kinds.is_empty() {
+            return Ok(false);
+        }
+        let Some(row) = self
+            .repo
+            .claim_job(&kinds, now.into(), self.lease_until(now).into())
+            .await?
+        else {
+            return Ok(false);
+        };
+        let thing = row.id.clone();
+        let job = row.into_job()?;
+        let handler = self.handlers[&job.kind].clone();
+
+        if job.cancel_requested {
+            // Cancelled while running in a process that stopped before it noticed.
+            if self
+                .finish(&job, JobStatus::Cancelled, None, job.last_error.clone())
+                .await?
+            {
+                self.notify_cancelled(&job).await;
+            }
+            return Ok(true);
+        }
+
+        let ctx = JobContext::new(job.clone(), &self.repo);
+        let attempt = AssertUnwindSafe(handler.run(&ctx)).catch_unwind();
+        let outcome = tokio::select! {
+            outcome = attempt => outcome
+                .unwrap_or_else(|_| Err(AppError::Internal("job handler panicked".into()))),
+            () = self.keep_leased(thing.clone(), job.attempts) => {
+                // Another worker owns the job now; its attempt decides the outcome.
+                lost_lease(&job);
+                return Ok(true);
+            }
+        };
+        match outcome {
+            Ok(result) => {
+                let result = result
+                    .map(|r| serde_json::to_string(&r))
+                    .transpose()
+                    .map_err(|e| AppError::internal_from_err("job.result", e))?;
+                self.finish(&job, JobStatus::Succeeded, result, None)
+                    .await?;
+            }
+            Err(e) => {
+                let error = e.to_string();
+                let cancel_requested = self
+                    .repo
+                    .get_job(thing.clone())
+                    .await?
+                    .is_some_and(|r| r.cancel_requested);
+                if cancel_requested {
+                    if self
+                        .finish(&job, JobStatus::Cancelled, None, Some(error))
+                        .await?
+                    {
+                        self.notify_cancelled(&job).await;
+                    }
+                } else if let Some(at) = retry_at(&job, Utc::now()) {
+                    tracing::warn!(job_id = %job.id, kind = job.kind.as_str(), attempts = job.attempts, error = %error, "job attempt failed, retrying");
+                    if self
+                        .repo
+                        .retry_job(thing, job.attempts, &error, at.into())
+                        .await?
+                        .is_none()
+                    {
+                        lost_lease(&job);
+                    }
+                } else {
+                    tracing::warn!(job_id = %job.id, kind = job.kind.as_str(), attempts = job.attempts, error = %error, "job failed");
+                    self.finish(&job, JobStatus::Failed, None, Some(error))
+                        .await?;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Ends the running attempt of `job`; `false` when the attempt had lost its lease and
+    /// nothing was written.
+    async fn finish(
+        &self,
+        job: &Job,
+        status: JobStatus,
+        result: Option<String>,
+        last_error: Option<String>,
+    ) -> Result<bool, AppError> {
+        let finished = self
+            .repo
+            .finish_job(
+                RecordId::new("job", job.id.clone()),
+                job.attempts,
+                JobFinish {
+                    status: status.as_str().to_owned(),
+                    result,
+                    last_error,
+                    finished_at: Utc::now().into(),
+                },
+            )
+            .await?;
+        if finished.is_none() {
+            lost_lease(job);
+        }
+        Ok(finished.is_some())
+    }
+
+    /// Renews the lease of the running attempt every third of the lease while its handler
+    /// runs, so other instances do not take it over. Returns once the attempt lost the lease.
+    async fn keep_leased(&self, thing: RecordId, attempt: u32) {
+        let mut ticker = tokio::time::interval(self.lease / 3);
+        // The first tick completes at once; the claim just set the lease.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let until = self.lease_until(Utc::now());
+            match self
+                .repo
+                .extend_lease(thing.clone(), attempt, until.into())
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => {
+                    tracing::warn!(job_id = %record_id_string(&thing), error = %e, "renewing job lease failed")
+                }
+            }
+        }
+    }
+
+    async fn notify_cancelled(&self, job: &Job) {
+        let Some(handler) = self.handlers.get(&job.kind) else {
+            return;
+        };
+        if let Err(e) = handler.cancelled(job).await {
+            tracing::warn!(job_id = %job.id, error = %e, "job cancellation hook failed");
+        }
+    }
+
+    async fn load_job_for_user(&self, user: &User, id: &str) -> Result<JobRow, AppError> {
+        let row = self
+            .repo
+            .get_job(job_thing(id)?)
+            .await?
+            .ok_or_else(|| AppError::NotFound("job not found".into()))?;
+        let owned = row
+            .user
+            .as_ref()
+            .is_some_and(|u| *u == RecordId::new("user", user.id.clone()));
+        if !owned && user.role != UserRole::Admin {
+            return Err(AppError::NotFound("job not found".into()));
+        }
+        Ok(row)
+    }
+}
+
+impl<R: JobRepository + Clone + 'static> JobService<R> {
+    /// Runs `workers` workers that each take the next due job, polling every `every` while the
+    /// queue is empty. Jobs whose lease ran out are queued again now and once per lease period,
+    /// and scheduled jobs at the start of each of their periods.
+    pub async fn run_workers(self, workers: usize, every: Duration) {
+        for &(kind, period) in self.schedules.iter() {
+            let svc = self.clone();
+            tokio::spawn(async move {
+                // Twice per period, so timer drift never skips one; a period runs only once.
+                let mut ticker = tokio::time::interval(period / 2);
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = svc.schedule_due(kind, period, Utc::now()).await {
+                        tracing::warn!(kind = kind.as_str(), error = %e, "scheduling job failed");
+                    }
+                }
+            });
+        }
+        let svc = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(svc.lease);
+            loop {
+                ticker.tick().await;
+                match svc.requeue_interrupted(Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(requeued) => tracing::info!(requeued, "requeued interrupted jobs"),
+                    Err(e) => tracing::warn!(error = %e, "requeueing interrupted jobs failed"),
+                }
+            }
+        });
+        for _ in 0..workers.max(1) {
+            let svc = self.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(every);
+                loop {
+                    ticker.tick().await;
+                    loop {
+                        match svc.run_next(Utc::now()).await {
+                            Ok(true) => continue,
+                            Ok(false) => break,
+                            Err(e) => {
+                                tracing::warn!(error = %e, "job worker run failed");
+                                break;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Production type alias used in HTTP wiring.
+pub type JobServiceHandle = JobService<SurrealJobRepo>;
+
+impl JobServiceHandle {
+    pub fn build(db: Arc<Database>, lease: Duration) -> Self {
+        JobService::new(SurrealJobRepo::new(db)).with_lease(lease)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use chrono::{Duration, DurationRound, Utc};
+    use serde_json::json;
+
+    use shared::api::ListQuery;
+    use shared::job::{Job, JobKind, JobStatus};
+    use shared::user::{Role as UserRole, User};
+
+    use super::JobServiceHandle;
+    use crate::error::AppError;
+    use crate::resources::job::{JobContext, JobHandler};
+    use crate::test_helpers::{create_user, job_service, test_db, user_service};
+
+    /// Fails while `failures_left` is non-zero; optionally cancels its own job mid-run, lets
+    /// another worker take it over mid-run, or takes `hold` to finish.
+    #[derive(Default)]
+    struct ScriptedHandler {
+        failures_left: Mutex<u32>,
+        cancel_as: Option<(JobServiceHandle, User)>,
+        take_over: Option<JobServiceHandle>,
+        panic: bool,
+        hold: Option<std::time::Duration>,
+        runs: Mutex<Vec<u32>>,
+        cancelled: Mutex<Vec<String>>,
+        interrupted: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl JobHandler for ScriptedHandler {
+        async fn run(&self, ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+            self.runs.lock().expect("lock").push(ctx.job().attempts);
+            if self.panic {
+                panic!("handler bug");
+            }
+            if let Some((jobs, user)) = &self.cancel_as {
+                jobs.cancel_job_for_user(user, &ctx.job().id).await?;
+            }
+            if let Some(jobs) = &self.take_over {
+                use crate::resources::job::JobRepository;
+                // The lease runs out while this attempt stalls, and another worker claims it.
+                let later = Utc::now() + Duration::hours(1);
+                jobs.repo.requeue_expired(later.into()).await?;
+                jobs.repo
+                    .claim_job(
+                        &["blob.ocr".to_owned()],
+                        later.into(),
+                        (later + Duration::minutes(1)).into(),
+                    )
+                    .await?;
+            }
+            if let Some(hold) = self.hold {
+                tokio::time::sleep(hold).await;
+            }
+            ctx.set_progress(50).await?;
+            {
+                let mut failures = self.failures_left.lock().expect("lock");
+                if *failures > 0 {
+                    *failures -= 1;
+                    return Err(AppError::Internal("flaky".into()));
+                }
+            }
+            Ok(Some(json!({ "echo": ctx.job().payload["n"] })))
+        }
+
+        async fn cancelled(&self, job: &Job) -> Result<(), AppError> {
+            self.cancelled.lock().expect("lock").push(job.id.clone());
+            Ok(())
+        }
+
+        async fn interrupted(&self, job: &Job) -> Result<(), AppError> {
+            self.interrupted.lock().expect("lock").push(job.id.clone());
+            Ok(())
+        }
+    }
+
+    fn with(handler: &Arc<ScriptedHandler>, jobs: &JobServiceHandle) -> JobServiceHandle {
+        jobs.clone()
+            .with_handler(JobKind::BlobOcr, handler.clone() as Arc<dyn JobHandler>)
+    }
+
+    /// BLC-JOB-001: jobs are visible to the user they run for and to platform admins only.
+    #[tokio::test]
+    async fn blc_job_001_owner_and_admin_only() {
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db);
+        let owner = create_user(&db, "job-001-owner@test.local")
+            .await
+            .expect("owner");
+        let other = create_user(&db, "job-001-other@test.local")
+            .await
+            .expect("other");
+        let mut admin = User::new("job-001-admin@test.local");
+        admin.role = UserRole::Admin;
+        let admin = user_service(&db).create_user(admin).await.expect("admin");
+
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({ "n": 1 }), 3)
+            .await
+            .expect("enqueue");
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!((job.attempts, job.max_attempts, job.progress), (0, 3, 0));
+        assert_eq!(job.user_id.as_deref(), Some(owner.id.as_str()));
+        jobs.enqueue(JobKind::BlobOcr, None, &json!({ "n": 2 }), 1)
+            .await
+            .expect("system job");
+
+        assert_eq!(
+            jobs.get_job_for_user(&owner, &job.id).await.expect("own"),
+            job
+        );
+        assert_eq!(
+            jobs.get_job_for_user(&admin, &job.id).await.expect("admin"),
+            job
+        );
+        assert!(matches!(
+            jobs.get_job_for_user(&other, &job.id).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            jobs.cancel_job_for_user(&other, &job.id).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            jobs.get_job_for_user(&owner, "job:x").await,
+            Err(AppError::InvalidRequest(_))
+        ));
+
+        let (own, total) = jobs
+            .list_jobs_for_user(&owner, ListQuery::new())
+            .await
+            .expect("list");
+        assert_eq!((own.len(), total), (1, 1));
+        let (none, total) = jobs
+            .list_jobs_for_user(&other, ListQuery::new())
+            .await
+            .expect("list other");
+        assert!(none.is_empty());
+        assert_eq!(total, 0);
+    }
+
+    /// BLC-JOB-002 / BLC-JOB-003: failed attempts are retried after a backoff until
+    /// `max_attempts`, then the job fails; successes store the handler's result.
+    #[tokio::test]
+    async fn blc_job_002_retries_then_succeeds_or_fails() {
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db);
+        let owner = create_user(&db, "job-002@test.local").await.expect("user");
+        assert!(
+            !jobs.run_next(Utc::now()).await.expect("no handlers"),
+            "jobs without a handler stay queued"
+        );
+
+        let handler = Arc::new(ScriptedHandler {
+            failures_left: Mutex::new(1),
+            ..Default::default()
+        });
+        let workers = with(&handler, &jobs);
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({ "n": 7 }), 2)
+            .await
+            .expect("enqueue");
+
+        assert!(workers.run_next(Utc::now()).await.expect("first"));
+        let retried = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(retried.status, JobStatus::Queued);
+        assert_eq!(retried.attempts, 1);
+        assert!(retried.last_error.expect("error").contains("flaky"));
+        assert!(retried.run_at > Utc::now() + Duration::seconds(5));
+        assert!(!workers.run_next(Utc::now()).await.expect("backing off"));
+
+        assert!(
+            workers
+                .run_next(Utc::now() + Duration::minutes(1))
+                .await
+                .expect("retry")
+        );
+        let done = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(done.status, JobStatus::Succeeded);
+        assert_eq!((done.attempts, done.progress), (2, 100));
+        assert_eq!(done.result, Some(json!({ "echo": 7 })));
+        assert!(done.finished_at.is_some());
+        assert_eq!(*handler.runs.lock().expect("lock"), vec![1, 2]);
+
+        let handler = Arc::new(ScriptedHandler {
+            failures_left: Mutex::new(5),
+            ..Default::default()
+        });
+        let workers = with(&handler, &jobs);
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({ "n": 8 }), 1)
+            .await
+            .expect("enqueue");
+        assert!(workers.run_next(Utc::now()).await.expect("only attempt"));
+        let failed = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.progress, 50, "progress of the last attempt is kept");
+        assert!(failed.last_error.is_some());
+
+        let panicking = Arc::new(ScriptedHandler {
+            panic: true,
+            ..Default::default()
+        });
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({}), 1)
+            .await
+            .expect("enqueue");
+        assert!(
+            with(&panicking, &jobs)
+                .run_next(Utc::now())
+                .await
+                .expect("panic")
+        );
+        let failed = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert!(failed.last_error.expect("error").contains("panicked"));
+    }
+
+    /// BLC-JOB-004: queued jobs are cancelled at once, running ones at their next progress
+    /// report; the handler hears about both. Finished jobs cannot be cancelled.
+    #[tokio::test]
+    async fn blc_job_004_cancellation() {
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db);
+        let owner = create_user(&db, "job-004@test.local").await.expect("user");
+        let handler = Arc::new(ScriptedHandler::default());
+        let workers = with(&handler, &jobs);
+
+        let queued = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({}), 3)
+            .await
+            .expect("enqueue");
+        let cancelled = workers
+            .cancel_job_for_user(&owner, &queued.id)
+            .await
+            .expect("cancel queued");
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+        assert!(cancelled.finished_at.is_some());
+        assert!(!workers.run_next(Utc::now()).await.expect("nothing left"));
+        assert!(matches!(
+            workers.cancel_job_for_user(&owner, &queued.id).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        let cancelling = Arc::new(ScriptedHandler {
+            cancel_as: Some((jobs.clone(), owner.clone())),
+            ..Default::default()
+        });
+        let running = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({}), 3)
+            .await
+            .expect("enqueue");
+        assert!(
+            with(&cancelling, &jobs)
+                .run_next(Utc::now())
+                .await
+                .expect("run")
+        );
+        let stopped = jobs
+            .get_job_for_user(&owner, &running.id)
+            .await
+            .expect("get");
+        assert_eq!(stopped.status, JobStatus::Cancelled);
+        assert!(stopped.cancel_requested);
+        assert_eq!(stopped.attempts, 1, "a cancelled attempt is not retried");
+
+        assert_eq!(*handler.cancelled.lock().expect("lock"), vec![queued.id]);
+        assert_eq!(
+            *cancelling.cancelled.lock().expect("lock"),
+            vec![running.id]
+        );
+    }
+
+    /// BLC-JOB-005: only running jobs whose lease ran out are queued again; the interrupted
+    /// attempt counts.
+    #[tokio::test]
+    async fn blc_job_005_expired_leases_are_requeued() {
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db);
+        let owner = create_user(&db, "job-005@test.local").await.expect("user");
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({ "n": 1 }), 3)
+            .await
+            .expect("enqueue");
+        // A worker claims the job for a minute, then its process dies before it finishes.
+        use crate::resources::job::JobRepository;
+        let claimed_at = Utc::now();
+        jobs.repo
+            .claim_job(
+                &["blob.ocr".to_owned()],
+                claimed_at.into(),
+                (claimed_at + Duration::minutes(1)).into(),
+            )
+            .await
+            .expect("claim")
+            .expect("claimed");
+
+        assert_eq!(
+            jobs.requeue_interrupted(claimed_at + Duration::seconds(30))
+                .await
+                .expect("requeue"),
+            0,
+            "another instance starting up leaves the leased job alone"
+        );
+        assert_eq!(
+            jobs.get_job_for_user(&owner, &job.id)
+                .await
+                .expect("get")
+                .status,
+            JobStatus::Running
+        );
+
+        let expired = claimed_at + Duration::minutes(2);
+        assert_eq!(jobs.requeue_interrupted(expired).await.expect("requeue"), 1);
+        let handler = Arc::new(ScriptedHandler::default());
+        assert!(with(&handler, &jobs).run_next(expired).await.expect("run"));
+        let done = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(done.status, JobStatus::Succeeded);
+        assert_eq!(done.attempts, 2, "the interrupted attempt counts");
+        assert_eq!(
+            jobs.requeue_interrupted(expired + Duration::minutes(5))
+                .await
+                .expect("requeue"),
+            0
+        );
+    }
+
+    /// BLC-JOB-005: a job whose worker stopped during its last attempt fails instead of being
+    /// queued again, and its handler hears about it.
+    #[tokio::test]
+    async fn blc_job_005_expired_last_attempt_fails() {
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db);
+        let owner = create_user(&db, "job-005-last@test.local")
+            .await
+            .expect("user");
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({ "n": 1 }), 1)
+            .await
+            .expect("enqueue");
+        use crate::resources::job::JobRepository;
+        let claimed_at = Utc::now();
+        jobs.repo
+            .claim_job(
+                &["blob.ocr".to_owned()],
+                claimed_at.into(),
+                (claimed_at + Duration::minutes(1)).into(),
+            )
+            .await
+            .expect("claim")
+            .expect("claimed");
+
+        let handler = Arc::new(ScriptedHandler::default());
+        let workers = with(&handler, &jobs);
+        let expired = claimed_at + Duration::minutes(2);
+        assert_eq!(
+            workers.requeue_interrupted(expired).await.expect("requeue"),
+            0
+        );
+        let failed = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.attempts, 1);
+        assert_eq!(failed.last_error.as_deref(), Some(super::INTERRUPTED_ERROR));
+        assert!(failed.finished_at.is_some());
+        assert!(!workers.run_next(expired).await.expect("nothing queued"));
+        assert_eq!(*handler.interrupted.lock().expect("lock"), vec![job.id]);
+        assert!(handler.runs.lock().expect("lock").is_empty());
+    }
+
+    /// BLC-JOB-005: an attempt that lost its lease to another worker writes nothing: its
+    /// progress, retry and result are dropped and the new attempt owns the job.
+    #[tokio::test]
+    async fn blc_job_005_attempt_that_lost_its_lease_is_fenced() {
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db);
+        let owner = create_user(&db, "job-005-fence@test.local")
+            .await
+            .expect("user");
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({ "n": 1 }), 3)
+            .await
+            .expect("enqueue");
+        let handler = Arc::new(ScriptedHandler {
+            take_over: Some(jobs.clone()),
+            ..Default::default()
+        });
+        assert!(
+            with(&handler, &jobs)
+                .run_next(Utc::now())
+                .await
+                .expect("run")
+        );
+
+        let taken = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(
+            taken.status,
+            JobStatus::Running,
+            "the new attempt still runs"
+        );
+        assert_eq!((taken.attempts, taken.progress), (2, 0));
+        assert!(
+            taken.last_error.is_none(),
+            "the stale attempt did not retry"
+        );
+
+        use crate::resources::job::JobRepository;
+        use crate::resources::job::model::JobFinish;
+        let thing = surrealdb::types::RecordId::new("job", job.id.clone());
+        let finish = || JobFinish {
+            status: "succeeded".into(),
+            result: None,
+            last_error: None,
+            finished_at: Utc::now().into(),
+        };
+        let stale = jobs.repo.clone();
+        assert!(
+            !stale
+                .extend_lease(thing.clone(), 1, Utc::now().into())
+                .await
+                .expect("extend")
+        );
+        assert!(
+            stale
+                .set_progress(thing.clone(), 1, 80)
+                .await
+                .expect("progress")
+                .is_none()
+        );
+        assert!(
+            stale
+                .finish_job(thing.clone(), 1, finish())
+                .await
+                .expect("finish")
+                .is_none()
+        );
+        let done = stale
+            .finish_job(thing, 2, finish())
+            .await
+            .expect("finish")
+            .expect("current attempt");
+        assert_eq!(done.status, "succeeded");
+    }
+
+    /// BLC-JOB-007: a scheduled job runs once per period however many instances schedule it,
+    /// as one system job that is queued again for the next period.
+    #[tokio::test]
+    async fn blc_job_007_scheduled_job_runs_once_per_period() {
+        let db = test_db().await.expect("db");
+        let handler = Arc::new(ScriptedHandler::default());
+        let hour = std::time::Duration::from_secs(3600);
+        let instance = |jobs: JobServiceHandle| {
+            jobs.with_schedule(
+                JobKind::TrashPurge,
+                hour,
+                handler.clone() as Arc<dyn JobHandler>,
+            )
+        };
+        let (a, b) = (instance(job_service(&db)), instance(job_service(&db)));
+
+        // Ten minutes into the current hour, so a second later is still the same period.
+        let now = Utc::now()
+            .duration_trunc(Duration::hours(1))
+            .expect("truncate")
+            + Duration::minutes(10);
+        assert!(
+            a.schedule_due(JobKind::TrashPurge, hour, now)
+                .await
+                .expect("a")
+        );
+        assert!(
+            !b.schedule_due(JobKind::TrashPurge, hour, now)
+                .await
+                .expect("b"),
+            "the other instance finds it queued"
+        );
+        assert!(a.run_next(now).await.expect("run"));
+        assert!(!b.run_next(now).await.expect("nothing left"));
+        assert!(
+            !b.schedule_due(JobKind::TrashPurge, hour, now + Duration::seconds(1))
+                .await
+                .expect("same period"),
+            "the period already ran"
+        );
+
+        let next = now + Duration::hours(1);
+        assert!(
+            b.schedule_due(JobKind::TrashPurge, hour, next)
+                .await
+                .expect("next")
+        );
+        assert!(b.run_next(next).await.expect("run again"));
+        assert_eq!(*handler.runs.lock().expect("lock"), vec![1, 1]);
+
+        use crate::resources::job::JobRepository;
+        let job = a
+            .repo
+            .get_job(surrealdb::types::RecordId::new("job", "trash_purge"))
+            .await
+            .expect("get")
+            .expect("scheduled job")
+            .into_job()
+            .expect("job");
+        assert_eq!(job.kind, JobKind::TrashPurge);
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert!(job.user_id.is_none());
+    }
+
+    /// BLC-JOB-005: the worker renews the lease while a long attempt runs, so it is not taken
+    /// over.
+    #[tokio::test]
+    async fn blc_job_005_running_attempt_keeps_its_lease() {
+        let db = test_db().await.expect("db");
+        let jobs = job_service(&db).with_lease(std::time::Duration::from_secs(2));
+        let owner = create_user(&db, "job-005-lease@test.local")
+            .await
+            .expect("user");
+        let job = jobs
+            .enqueue(JobKind::BlobOcr, Some(&owner.id), &json!({ "n": 1 }), 3)
+            .await
+            .expect("enqueue");
+        let handler = Arc::new(ScriptedHandler {
+            hold: Some(std::time::Duration::from_secs(4)),
+            ..Default::default()
+        });
+        let workers = with(&handler, &jobs);
+        let run = tokio::spawn(async move { workers.run_next(Utc::now()).await });
+
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        assert_eq!(
+            jobs.requeue_interrupted(Utc::now()).await.expect("requeue"),
+            0,
+            "the lease was renewed past its first two seconds"
+        );
+        assert!(run.await.expect("join").expect("run"));
+        let done = jobs.get_job_for_user(&owner, &job.id).await.expect("get");
+        assert_eq!(done.status, JobStatus::Succeeded);
+        assert_eq!(done.attempts, 1);
+    }
+}
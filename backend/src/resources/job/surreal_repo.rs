@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::{JobCreate, JobFinish, JobRow};
+use super::repository::JobRepository;
+
+#[derive(Deserialize, SurrealValue)]
+struct CountResult {
+    count: u64,
+}
+
+/// Queued jobs looked at per claim; more than one so a job another worker just took does not
+/// leave this one idle.
+const CLAIM_CANDIDATES: u32 = 8;
+
+#[derive(Clone)]
+pub struct SurrealJobRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealJobRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl JobRepository for SurrealJobRepo {
+    async fn create_job(&self, create: JobCreate) -> Result<JobRow, AppError> {
+        self.inner()
+            .query("CREATE job CONTENT $create RETURN AFTER")
+            .bind(("create", create))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "job.create", e))?
+            .take::<Option<JobRow>>(0)?
+            .ok_or_else(|| AppError::database("failed to create job"))
+    }
+
+    async fn get_job(&self, job: RecordId) -> Result<Option<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM $jid")
+            .bind(("jid", job))
+            .await?
+            .take::<Vec<JobRow>>(0)?
+            .into_iter()
+            .next())
+    }
+
+    async fn list_jobs_for_user(
+        &self,
+        user: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM job WHERE user = $user ORDER BY created_at DESC LIMIT $limit START $start",
+            )
+            .bind(("user", user))
+            .bind(("limit", limit))
+            .bind(("start", offset))
+            .await?
+            .take(0)?)
+    }
+
+    async fn count_jobs_for_user(&self, user: RecordId) -> Result<u64, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT count() FROM job WHERE user = $user GROUP ALL")
+            .bind(("user", user))
+            .await?
+            .take::<Vec<CountResult>>(0)?
+            .into_iter()
+            .next()
+            .map(|r| r.count)
+            .unwrap_or(0))
+    }
+
+    async fn claim_job(
+        &self,
+        kinds: &[String],
+        now: Datetime,
+        lease_until: Datetime,
+    ) -> Result<Option<JobRow>, AppError> {
+        let candidates: Vec<RecordId> = self
+            .inner()
+            .query(
+                "SELECT VALUE id FROM job WHERE status = 'queued' AND run_at <= $now AND kind IN $kinds \
+                 ORDER BY run_at ASC LIMIT $limit",
+            )
+            .bind(("now", now))
+            .bind(("kinds", kinds.to_vec()))
+            .bind(("limit", CLAIM_CANDIDATES))
+            .await?
+            .take(0)?;
+        for candidate in candidates {
+            let claimed = self
+                .inner()
+                .query(
+                    "UPDATE $jid SET status = 'running', attempts += 1, progress = 0, \
+                     started_at = time::now(), lease_until = $lease_until \
+                     WHERE status = 'queued' RETURN AFTER",
+                )
+                .bind(("jid", candidate))
+                .bind(("lease_until", lease_until))
+                .await
+                .map_err(|e| crate::log_and_convert!(AppError::database, "job.claim", e))?
+                .take::<Vec<JobRow>>(0)?
+                .into_iter()
+                .next();
+            if claimed.is_some() {
+                return Ok(claimed);
+            }
+        }
+        Ok(None)
+    }
+
+    async fn extend_lease(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        lease_until: Datetime,
+    ) -> Result<bool, AppError> {
+        let extended: Vec<RecordId> = self
+            .inner()
+            .query(
+                "UPDATE $jid SET lease_until = $lease_until \
+                 WHERE status = 'running' AND attempts = $attempt RETURN VALUE id",
+            )
+            .bind(("jid", job))
+            .bind(("attempt", attempt))
+            .bind(("lease_until", lease_until))
+            .await?
+            .take(0)?;
+        Ok(!extended.is_empty())
+    }
+
+    async fn set_progress(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        progress: u8,
+    ) -> Result<Option<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "UPDATE $jid SET progress = $progress \
+                 WHERE status = 'running' AND attempts = $attempt RETURN AFTER",
+            )
+            .bind(("jid", job))
+            .bind(("attempt", attempt))
+            .bind(("progress", progress.min(100)))
+            .await?
+            .take::<Vec<JobRow>>(0)?
+            .into_iter()
+            .next())
+    }
+
+    async fn finish_job(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        finish: JobFinish,
+    ) -> Result<Option<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "UPDATE $jid SET status = $finish.status, result = $finish.result, \
+                 last_error = $finish.last_error, finished_at = $finish.finished_at, \
+                 progress = IF $finish.status = 'succeeded' { 100 } ELSE { progress } \
+                 WHERE status = 'running' AND attempts = $attempt RETURN AFTER",
+            )
+            .bind(("jid", job))
+            .bind(("attempt", attempt))
+            .bind(("finish", finish))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "job.finish", e))?
+            .take::<Vec<JobRow>>(0)?
+            .into_iter()
+            .next())
+    }
+
+    async fn retry_job(
+        &self,
+        job: RecordId,
+        attempt: u32,
+        error: &str,
+        run_at: Datetime,
+    ) -> Result<Option<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "UPDATE $jid SET status = 'queued', last_error = $error, run_at = $run_at, \
+                 lease_until = NONE WHERE status = 'running' AND attempts = $attempt RETURN AFTER",
+            )
+            .bind(("jid", job))
+            .bind(("attempt", attempt))
+            .bind(("error", error.to_owned()))
+            .bind(("run_at", run_at))
+            .await?
+            .take::<Vec<JobRow>>(0)?
+            .into_iter()
+            .next())
+    }
+
+    async fn schedule_job(
+        &self,
+        id: &str,
+        create: JobCreate,
+        period: Datetime,
+    ) -> Result<bool, AppError> {
+        let mut response = self
+            .inner()
+            .query(
+                "INSERT IGNORE INTO job { id: $jid, kind: $create.kind, user: $create.user, \
+                 payload: $create.payload, max_attempts: $create.max_attempts, run_at: $period } \
+                 RETURN VALUE id; \
+                 UPDATE $jid SET status = 'queued', run_at = $period, attempts = 0, progress = 0, \
+                 cancel_requested = false, result = NONE, last_error = NONE, started_at = NONE, \
+                 finished_at = NONE, lease_until = NONE \
+                 WHERE status IN ['succeeded', 'failed', 'cancelled'] AND run_at < $period \
+                 RETURN VALUE id",
+            )
+            .bind(("jid", RecordId::new("job", id.to_owned())))
+            .bind(("create", create))
+            .bind(("period", period))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "job.schedule", e))?;
+        let created: Vec<RecordId> = response.take(0)?;
+        let requeued: Vec<RecordId> = response.take(1)?;
+        Ok(!created.is_empty() || !requeued.is_empty())
+    }
+
+    async fn cancel_queued_job(&self, job: RecordId) -> Result<Option<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "UPDATE $jid SET status = 'cancelled', cancel_requested = true, \
+                 finished_at = time::now() WHERE status = 'queued' RETURN AFTER",
+            )
+            .bind(("jid", job))
+            .await?
+            .take::<Vec<JobRow>>(0)?
+            .into_iter()
+            .next())
+    }
+
+    async fn request_cancel(&self, job: RecordId) -> Result<Option<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("UPDATE $jid SET cancel_requested = true WHERE status = 'running' RETURN AFTER")
+            .bind(("jid", job))
+            .await?
+            .take::<Vec<JobRow>>(0)?
+            .into_iter()
+            .next())
+    }
+
+    async fn fail_expired(&self, now: Datetime, error: &str) -> Result<Vec<JobRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "UPDATE job SET status = 'failed', last_error = $error, finished_at = $now, \
+                 lease_until = NONE \
+                 WHERE status = 'running' AND (lease_until = NONE OR lease_until <= $now) \
+                 AND attempts >= max_attempts AND cancel_requested = false RETURN AFTER",
+            )
+            .bind(("now", now))
+            .bind(("error", error.to_owned()))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "job.fail_expired", e))?
+            .take(0)?)
+    }
+
+    async fn requeue_expired(&self, now: Datetime) -> Result<u64, AppError> {
+        let requeued: Vec<RecordId> = self
+            .inner()
+            .query(
+                "UPDATE job SET status = 'queued', run_at = $now, lease_until = NONE \
+                 WHERE status = 'running' AND (lease_until = NONE OR lease_until <= $now) \
+                 AND (attempts < max_attempts OR cancel_requested = true) RETURN VALUE id",
+            )
+            .bind(("now", now))
+            .await?
+            .take(0)?;
+        Ok(requeued.len() as u64)
+    }
+}
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use surrealdb::types::RecordId;
+
+use shared::job::Job;
+
+use crate::error::AppError;
+
+use super::repository::JobRepository;
+
+/// Work of one [`JobKind`](shared::job::JobKind), registered with
+/// [`JobService::with_handler`](super::JobService::with_handler).
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    /// Runs one attempt. `Ok` finishes the job as succeeded with an optional result; `Err` is
+    /// retried after a backoff until `max_attempts`, or ends the job as cancelled when
+    /// cancellation was requested meanwhile.
+    async fn run(&self, ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError>;
+
+    /// Called once when a job of this kind ends as cancelled, queued or running.
+    async fn cancelled(&self, _job: &Job) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Called once when a job of this kind ends as failed without [`run`](Self::run) returning:
+    /// its worker stopped during the last attempt.
+    async fn interrupted(&self, _job: &Job) -> Result<(), AppError> {
+        Ok(())
+    }
+}
+
+/// The running job as seen by its [`JobHandler`].
+pub struct JobContext<'a> {
+    job: Job,
+    repo: &'a dyn JobRepository,
+}
+
+impl<'a> JobContext<'a> {
+    pub(crate) fn new(job: Job, repo: &'a dyn JobRepository) -> Self {
+        Self { job, repo }
+    }
+
+    pub fn job(&self) -> &Job {
+        &self.job
+    }
+
+    pub fn payload<T: DeserializeOwned>(&self) -> Result<T, AppError> {
+        serde_json::from_value(self.job.payload.clone()).map_err(|e| {
+            AppError::Internal(format!("invalid {} payload: {e}", self.job.kind.as_str()))
+        })
+    }
+
+    /// Whether a failure of this attempt ends the job as failed.
+    pub fn is_last_attempt(&self) -> bool {
+        self.job.attempts >= self.job.max_attempts
+    }
+
+    /// Reports progress in percent. Fails with a conflict once cancellation was requested or
+    /// the attempt lost its lease, so handlers stop at their next progress report.
+    pub async fn set_progress(&self, percent: u8) -> Result<(), AppError> {
+        let row = self
+            .repo
+            .set_progress(
+                RecordId::new("job", self.job.id.clone()),
+                self.job.attempts,
+                percent,
+            )
+            .await?
+            .ok_or_else(|| AppError::conflict("job lease was lost"))?;
+        if row.cancel_requested {
+            return Err(AppError::conflict("job was cancelled"));
+        }
+        Ok(())
+    }
+}
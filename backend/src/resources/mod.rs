@@ -16,6 +16,14 @@ pub use song::{CreateSong, Song, UpdateSong};
 
 pub mod team;
 
+pub mod job;
+
+pub mod sync;
+
+pub mod batch;
+
+pub mod event;
+
 pub mod monitoring;
 
 pub mod user;
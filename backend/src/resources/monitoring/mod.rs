@@ -1,11 +1,14 @@
 mod model;
 mod repo;
 pub mod rest;
+mod rollup;
 
 pub use model::{
-    ActivityCalendarMetrics, AdminMonitoringMetrics, EngagementMetrics, FamilyErrorRates,
-    FamilyLatency, FeatureFamilyMetrics, HttpAuditLog, IdLike404Metrics, LatencyMetrics,
-    MethodLatency, MetricsWindow, MetricsWindowWire, MonitoringMetricsQuery,
-    MonitoringMetricsResponse, MutationHealthMetrics, NewUserActivationMetrics, ReliabilityMetrics,
-    RouteFamily, TopFailingRoute, TrafficMetrics, TrafficMixEntry,
+    ActivityCalendarMetrics, AdminMonitoringMetrics, AuditExportFormat, EngagementMetrics,
+    FamilyErrorRates, FamilyLatency, FeatureFamilyMetrics, HttpAuditLog, HttpAuditLogQuery,
+    IdLike404Metrics, LatencyMetrics, MethodLatency, MetricsWindow, MetricsWindowWire,
+    MonitoringMetricsQuery, MonitoringMetricsResponse, MutationHealthMetrics,
+    NewUserActivationMetrics, ReliabilityMetrics, RouteFamily, SecurityEvent, SecurityEventQuery,
+    TopFailingRoute, TrafficMetrics, TrafficMixEntry,
 };
+pub use rollup::AuditRollupJob;
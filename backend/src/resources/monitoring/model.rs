@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use surrealdb::types::{Datetime, RecordId, SurrealValue};
 use utoipa::{IntoParams, ToSchema};
 
+use shared::api::PageQuery;
+
 use crate::database::record_id_string;
 
 /// Maximum length of `[start, end)` for metrics queries (avoids unbounded table scans).
@@ -130,6 +134,125 @@ impl HttpAuditRecord {
     }
 }
 
+/// Filters of `GET /monitoring/http-audit-logs` and its export; all are combined with AND.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HttpAuditLogQuery {
+    /// Page index, zero-based. Defaults to 0.
+    #[param(minimum = 0, nullable = true)]
+    pub page: Option<u32>,
+    /// Items per page. Must be 1–500. Defaults to 50.
+    #[param(minimum = 1, maximum = 500, example = 50, nullable = true)]
+    pub page_size: Option<u32>,
+    /// Only requests made by this user id.
+    #[param(nullable = true)]
+    pub user_id: Option<String>,
+    /// Only this status code (`404`) or status class (`4xx`).
+    #[param(nullable = true, example = "5xx")]
+    pub status: Option<String>,
+    /// Only requests in this route family.
+    #[param(nullable = true)]
+    pub family: Option<RouteFamily>,
+    /// Inclusive lower bound on `created_at` (UTC, RFC 3339).
+    #[param(nullable = true)]
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `created_at` (UTC, RFC 3339).
+    #[param(nullable = true)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl HttpAuditLogQuery {
+    pub fn validate(self) -> Result<(PageQuery, HttpAuditLogFilter), String> {
+        let page = PageQuery {
+            page: self.page,
+            page_size: self.page_size,
+        }
+        .validate()?;
+        let status = self
+            .status
+            .as_deref()
+            .map(parse_status_filter)
+            .transpose()?;
+        if let (Some(since), Some(until)) = (self.since, self.until)
+            && since >= until
+        {
+            return Err("since must be before until".into());
+        }
+        let filter = HttpAuditLogFilter {
+            user: self.user_id.map(|id| RecordId::new("user", id)),
+            status,
+            family: self.family,
+            since: self.since,
+            until: self.until,
+        };
+        Ok((page, filter))
+    }
+}
+
+/// `404` → `[404, 405)`, `4xx` → `[400, 500)`.
+fn parse_status_filter(raw: &str) -> Result<(i64, i64), String> {
+    let invalid = || format!("status must be a code like 404 or a class like 4xx, got `{raw}`");
+    let raw = raw.trim();
+    if let Some(class) = raw.strip_suffix("xx").or_else(|| raw.strip_suffix("XX")) {
+        let class: i64 = class.parse().map_err(|_| invalid())?;
+        if !(1..=5).contains(&class) {
+            return Err(invalid());
+        }
+        return Ok((class * 100, class * 100 + 100));
+    }
+    let code: i64 = raw.parse().map_err(|_| invalid())?;
+    if !(100..=599).contains(&code) {
+        return Err(invalid());
+    }
+    Ok((code, code + 1))
+}
+
+/// Validated [`HttpAuditLogQuery`] filters.
+#[derive(Debug, Clone, Default)]
+pub struct HttpAuditLogFilter {
+    pub user: Option<RecordId>,
+    /// Half-open `status_code` range.
+    pub status: Option<(i64, i64)>,
+    pub family: Option<RouteFamily>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Body format of `GET /monitoring/http-audit-logs/export`.
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditExportFormat {
+    /// One JSON [`HttpAuditLog`] per line.
+    #[default]
+    Ndjson,
+    /// RFC 4180 CSV with a header row.
+    Csv,
+}
+
+impl AuditExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditExportFormatQuery {
+    /// `ndjson` (default) or `csv`.
+    #[param(nullable = true)]
+    pub format: Option<AuditExportFormat>,
+}
+
 /// One persisted HTTP request audit row (admin monitoring API).
 #[derive(Debug, Serialize, ToSchema)]
 pub struct HttpAuditLog {
@@ -149,6 +272,185 @@ pub struct HttpAuditLog {
     pub created_at: DateTime<Utc>,
 }
 
+impl HttpAuditLog {
+    pub const CSV_HEADER: &'static str = "id,request_id,created_at,method,path,status_code,duration_ms,user_id,session_id,client_origin,client_version\r\n";
+
+    /// This row as one CSV record, including the trailing CRLF.
+    pub fn to_csv_record(&self) -> String {
+        let fields = [
+            csv_field(&self.id),
+            csv_field(&self.request_id),
+            self.created_at.to_rfc3339(),
+            csv_field(&self.method),
+            csv_field(&self.path),
+            self.status_code.to_string(),
+            self.duration_ms.to_string(),
+            csv_field(self.user_id.as_deref().unwrap_or_default()),
+            csv_field(self.session_id.as_deref().unwrap_or_default()),
+            csv_field(&self.client_origin),
+            csv_field(self.client_version.as_deref().unwrap_or_default()),
+        ];
+        let mut record = fields.join(",");
+        record.push_str("\r\n");
+        record
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+// --- Security events (GET /monitoring/security-events, GET /users/me/security-events) ---
+
+#[derive(Debug, serde::Deserialize, SurrealValue)]
+pub struct SecurityEventRecord {
+    pub id: RecordId,
+    pub event: String,
+    #[serde(default)]
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub actor_user_id: Option<String>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    #[serde(default)]
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub client_ip: Option<String>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    pub created_at: Datetime,
+}
+
+impl SecurityEventRecord {
+    pub fn into_wire(self) -> SecurityEvent {
+        SecurityEvent {
+            id: record_id_string(&self.id),
+            event: self.event,
+            user_id: self.user_id,
+            actor_user_id: self.actor_user_id,
+            fields: self.fields,
+            request_id: self.request_id,
+            client_ip: self.client_ip,
+            user_agent: self.user_agent,
+            created_at: self.created_at.into(),
+        }
+    }
+}
+
+/// One persisted `audit.*` event: a login, failed login, session revocation, role or membership
+/// change, user deletion and the like.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SecurityEvent {
+    pub id: String,
+    /// Audit event name, e.g. `audit.auth.login.failure`.
+    pub event: String,
+    /// Account the event is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// User who caused the event, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_user_id: Option<String>,
+    /// Remaining audit fields (`provider`, `reason`, `session_id`, `team_id`, ...) as strings.
+    pub fields: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SecurityEvent {
+    /// Drops the client address and user agent of events another user caused, so a user reviewing
+    /// their account does not see where an admin acted from.
+    pub fn for_subject(mut self, user_id: &str) -> Self {
+        if self.actor_user_id.as_deref() != Some(user_id) {
+            self.client_ip = None;
+            self.user_agent = None;
+        }
+        self
+    }
+}
+
+/// Filters of `GET /monitoring/security-events`; all are combined with AND.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SecurityEventQuery {
+    /// Page index, zero-based. Defaults to 0.
+    #[param(minimum = 0, nullable = true)]
+    pub page: Option<u32>,
+    /// Items per page. Must be 1–500. Defaults to 50.
+    #[param(minimum = 1, maximum = 500, example = 50, nullable = true)]
+    pub page_size: Option<u32>,
+    /// Only events about this user id.
+    #[param(nullable = true)]
+    pub user_id: Option<String>,
+    /// Only this event (`audit.auth.login.failure`), or every event under a prefix ending in `.`
+    /// (`audit.auth.`).
+    #[param(nullable = true, example = "audit.auth.")]
+    pub event: Option<String>,
+    /// Inclusive lower bound on `created_at` (UTC, RFC 3339).
+    #[param(nullable = true)]
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `created_at` (UTC, RFC 3339).
+    #[param(nullable = true)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl SecurityEventQuery {
+    pub fn validate(self) -> Result<(PageQuery, SecurityEventFilter), String> {
+        let page = PageQuery {
+            page: self.page,
+            page_size: self.page_size,
+        }
+        .validate()?;
+        let event = match self.event.map(|e| e.trim().to_owned()) {
+            None => None,
+            Some(e) if !e.starts_with("audit.") => {
+                return Err(format!("event must start with `audit.`, got `{e}`"));
+            }
+            Some(e) => match e.strip_suffix('.') {
+                Some(prefix) => Some(EventMatch::Prefix(format!("{prefix}."))),
+                None => Some(EventMatch::Exact(e)),
+            },
+        };
+        if let (Some(since), Some(until)) = (self.since, self.until)
+            && since >= until
+        {
+            return Err("since must be before until".into());
+        }
+        let filter = SecurityEventFilter {
+            user_id: self.user_id,
+            event,
+            since: self.since,
+            until: self.until,
+        };
+        Ok((page, filter))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventMatch {
+    Exact(String),
+    /// Includes the trailing `.`.
+    Prefix(String),
+}
+
+/// Validated [`SecurityEventQuery`] filters.
+#[derive(Debug, Clone, Default)]
+pub struct SecurityEventFilter {
+    pub user_id: Option<String>,
+    pub event: Option<EventMatch>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
 // --- Metrics bundle (GET /monitoring/metrics) ---
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -176,6 +478,9 @@ pub struct MetricsWindowWire {
     pub days_spanned: i64,
     pub total_requests: u64,
     pub requests_per_day: f64,
+    /// Requests in `total_requests` read from the daily rollup of audit rows past the retention.
+    /// Latency percentiles only cover the other requests.
+    pub rolled_up_requests: u64,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -208,7 +513,7 @@ pub struct FamilyErrorRates {
     pub rate_5xx: f64,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, ToSchema, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum RouteFamily {
     ApiV1,
@@ -217,6 +522,42 @@ pub enum RouteFamily {
     Other,
 }
 
+impl RouteFamily {
+    /// Family of a request path, using the same prefixes as the audit-log metrics queries.
+    pub fn of_path(path: &str) -> Self {
+        if path.starts_with("/api/v1/") {
+            Self::ApiV1
+        } else if path.starts_with("/auth/") {
+            Self::Auth
+        } else if path.starts_with("/api/docs") {
+            Self::Docs
+        } else {
+            Self::Other
+        }
+    }
+
+    /// SurrealQL condition on `path` matching [`Self::of_path`].
+    pub fn path_condition(self) -> &'static str {
+        match self {
+            Self::ApiV1 => "string::starts_with(path, '/api/v1/')",
+            Self::Auth => "string::starts_with(path, '/auth/')",
+            Self::Docs => "string::starts_with(path, '/api/docs')",
+            Self::Other => {
+                "NOT (string::starts_with(path, '/api/v1/') OR string::starts_with(path, '/auth/') OR string::starts_with(path, '/api/docs'))"
+            }
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ApiV1 => "api_v1",
+            Self::Auth => "auth",
+            Self::Docs => "docs",
+            Self::Other => "other",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LatencyMetrics {
     pub p95_ms_all: Option<f64>,
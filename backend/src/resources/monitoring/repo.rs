@@ -6,18 +6,23 @@ use surrealdb::types::{Datetime, RecordId, SurrealValue};
 
 use shared::api::ListQuery;
 
-use crate::database::Database;
 use crate::database::record_id_string;
+use crate::database::{Database, TimedQuery};
 use crate::error::AppError;
 
 use super::model::{
-    ActivityCalendarMetrics, AdminMonitoringMetrics, EngagementMetrics, FamilyErrorRates,
-    FamilyLatency, FeatureFamilyMetrics, HttpAuditLog, HttpAuditRecord, IdLike404Metrics,
-    LatencyMetrics, METRICS_ACTIVATION_NEW_USER_CAP, MetricsWindow, MetricsWindowWire,
-    MonitoringMetricsResponse, MutationHealthMetrics, NewUserActivationMetrics, ReliabilityMetrics,
-    RouteFamily, TopFailingRoute, TrafficMetrics, TrafficMixEntry,
+    ActivityCalendarMetrics, AdminMonitoringMetrics, EngagementMetrics, EventMatch,
+    FamilyErrorRates, FamilyLatency, FeatureFamilyMetrics, HttpAuditLog, HttpAuditLogFilter,
+    HttpAuditRecord, IdLike404Metrics, LatencyMetrics, METRICS_ACTIVATION_NEW_USER_CAP,
+    MetricsWindow, MetricsWindowWire, MonitoringMetricsResponse, MutationHealthMetrics,
+    NewUserActivationMetrics, ReliabilityMetrics, RouteFamily, SecurityEvent, SecurityEventFilter,
+    SecurityEventRecord, TopFailingRoute, TrafficMetrics, TrafficMixEntry,
 };
 
+/// Requests by a signed-in user to the product API (everything under `/api/v1/` but monitoring).
+const PRODUCT_USER_CONDITION: &str = "user IS NOT NONE AND string::starts_with(path, '/api/v1/') \
+     AND NOT (string::starts_with(path, '/api/v1/monitoring/'))";
+
 pub struct MonitoringRepo;
 
 fn surreal_query_err(ctx: &'static str, err: surrealdb::Error) -> AppError {
@@ -62,14 +67,19 @@ struct AuditActivationRow {
 }
 
 impl MonitoringRepo {
-    pub async fn count_http_audit_logs(db: &Database) -> Result<u64, AppError> {
+    pub async fn count_http_audit_logs(
+        db: &Database,
+        filter: &HttpAuditLogFilter,
+    ) -> Result<u64, AppError> {
         #[derive(Deserialize, SurrealValue)]
         struct CountResult {
             count: u64,
         }
-        let mut response = db
-            .db
-            .query("SELECT count() FROM http_request_audit GROUP ALL")
+        let q = format!(
+            "SELECT count() FROM http_request_audit{} GROUP ALL",
+            filter_clause(filter, &[])
+        );
+        let mut response = bind_filter(db.query(q), filter)
             .await
             .map_err(|e| surreal_query_err("http_audit.count", e))?;
         Ok(response
@@ -84,13 +94,14 @@ impl MonitoringRepo {
     pub async fn list_http_audit_logs(
         db: &Database,
         query: ListQuery,
+        filter: &HttpAuditLogFilter,
     ) -> Result<Vec<HttpAuditLog>, AppError> {
         let (offset, limit) = query.effective_offset_limit();
-        let mut response = db
-            .db
-            .query(
-                "SELECT * FROM http_request_audit ORDER BY created_at DESC LIMIT $limit START $start",
-            )
+        let q = format!(
+            "SELECT * FROM http_request_audit{} ORDER BY created_at DESC LIMIT $limit START $start",
+            filter_clause(filter, &[])
+        );
+        let mut response = bind_filter(db.query(q), filter)
             .bind(("limit", limit))
             .bind(("start", offset))
             .await
@@ -101,6 +112,135 @@ impl MonitoringRepo {
         Ok(rows.into_iter().map(|r| r.into_wire()).collect())
     }
 
+    /// Next `limit` filtered rows, newest first, strictly after `after` (the `created_at` and id
+    /// of the last row of the previous page) so rows written meanwhile do not shift pages.
+    pub async fn export_http_audit_page(
+        db: &Database,
+        filter: &HttpAuditLogFilter,
+        after: Option<(Datetime, RecordId)>,
+        limit: u32,
+    ) -> Result<Vec<HttpAuditRecord>, AppError> {
+        let keyset: &[&str] = if after.is_some() {
+            &["(created_at < $after_at OR (created_at = $after_at AND id < $after_id))"]
+        } else {
+            &[]
+        };
+        let q = format!(
+            "SELECT * FROM http_request_audit{} ORDER BY created_at DESC, id DESC LIMIT $limit",
+            filter_clause(filter, keyset)
+        );
+        let (after_at, after_id) = after.unzip();
+        let mut response = bind_filter(db.query(q), filter)
+            .bind(("after_at", after_at))
+            .bind(("after_id", after_id))
+            .bind(("limit", limit))
+            .await
+            .map_err(|e| surreal_query_err("http_audit.export", e))?;
+        response
+            .take(0)
+            .map_err(|e| surreal_query_err("http_audit.export.take", e))
+    }
+
+    pub async fn count_security_events(
+        db: &Database,
+        filter: &SecurityEventFilter,
+    ) -> Result<u64, AppError> {
+        let q = format!(
+            "SELECT count() AS count FROM security_event{} GROUP ALL",
+            security_filter_clause(filter)
+        );
+        let mut response = bind_security_filter(db.query(q), filter)
+            .await
+            .map_err(|e| surreal_query_err("security_event.count", e))?;
+        let rows: Vec<CountRow> = response
+            .take(0)
+            .map_err(|e| surreal_query_err("security_event.count.take", e))?;
+        Ok(rows.first().map_or(0, |r| r.count as u64))
+    }
+
+    pub async fn list_security_events(
+        db: &Database,
+        query: ListQuery,
+        filter: &SecurityEventFilter,
+    ) -> Result<Vec<SecurityEvent>, AppError> {
+        let (offset, limit) = query.effective_offset_limit();
+        let q = format!(
+            "SELECT * FROM security_event{} ORDER BY created_at DESC, id DESC LIMIT $limit START $start",
+            security_filter_clause(filter)
+        );
+        let mut response = bind_security_filter(db.query(q), filter)
+            .bind(("limit", limit))
+            .bind(("start", offset))
+            .await
+            .map_err(|e| surreal_query_err("security_event.list", e))?;
+        let rows: Vec<SecurityEventRecord> = response
+            .take(0)
+            .map_err(|e| surreal_query_err("security_event.list.take", e))?;
+        Ok(rows.into_iter().map(|r| r.into_wire()).collect())
+    }
+
+    /// `created_at` of the oldest raw audit row before `cutoff`, if any.
+    pub async fn oldest_audit_before(
+        db: &Database,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        #[derive(Deserialize, SurrealValue)]
+        struct CreatedAtRow {
+            created_at: Datetime,
+        }
+        let mut response = db
+            .query(
+                "SELECT created_at FROM http_request_audit WHERE created_at < $cutoff \
+                 ORDER BY created_at ASC LIMIT 1",
+            )
+            .bind(("cutoff", Datetime::from(cutoff)))
+            .await
+            .map_err(|e| surreal_query_err("http_audit.oldest", e))?;
+        let rows: Vec<CreatedAtRow> = response
+            .take(0)
+            .map_err(|e| surreal_query_err("http_audit.oldest.take", e))?;
+        Ok(rows.into_iter().next().map(|r| r.created_at.into()))
+    }
+
+    /// Fold the raw audit rows of `[day, day + 1d)` into `http_request_audit_daily` and delete
+    /// them, in one transaction. Returns how many raw rows were rolled up.
+    pub async fn roll_up_audit_day(db: &Database, day: DateTime<Utc>) -> Result<u64, AppError> {
+        let txn = db.begin().await?;
+        let result = txn
+            .query(
+                "LET $groups = SELECT method, path, status_code, user, session, client_origin, \
+                   count() AS count, math::sum(duration_ms) AS duration_ms_sum, \
+                   math::max(duration_ms) AS duration_ms_max, time::max(created_at) AS last_at \
+                   FROM http_request_audit WHERE created_at >= $day AND created_at < $next \
+                   GROUP BY method, path, status_code, user, session, client_origin; \
+                 FOR $group IN $groups { \
+                   CREATE http_request_audit_daily CONTENT { \
+                     day: $day, method: $group.method, path: $group.path, \
+                     status_code: $group.status_code, user: $group.user, \
+                     session: $group.session, client_origin: $group.client_origin, \
+                     count: $group.count, duration_ms_sum: $group.duration_ms_sum, \
+                     duration_ms_max: $group.duration_ms_max, last_at: $group.last_at \
+                   } RETURN NONE; \
+                 }; \
+                 DELETE http_request_audit WHERE created_at >= $day AND created_at < $next; \
+                 RETURN math::sum($groups.count);",
+            )
+            .bind(("day", Datetime::from(day)))
+            .bind(("next", Datetime::from(day + Duration::days(1))))
+            .await
+            .and_then(|mut response| response.take::<Option<i64>>(3));
+        match result {
+            Ok(rolled) => {
+                txn.commit().await?;
+                Ok(rolled.unwrap_or(0).max(0) as u64)
+            }
+            Err(e) => {
+                txn.cancel().await?;
+                Err(surreal_query_err("http_audit.rollup", e))
+            }
+        }
+    }
+
     pub async fn fetch_metrics(
         db: &Database,
         window: MetricsWindow,
@@ -117,15 +257,8 @@ impl MonitoringRepo {
 
         let days_spanned = window.utc_days_spanned();
 
-        let total_requests = count_audit(
-            db,
-            "metrics.total",
-            "SELECT count() AS count FROM http_request_audit \
-             WHERE created_at >= $start AND created_at < $end GROUP ALL",
-            &start,
-            &end,
-        )
-        .await?;
+        let total_requests = count_audit_where(db, "metrics.total", "true", &start, &end).await?;
+        let rolled_up_requests = count_rollup(db, &start, &end).await?;
         let total_f = total_requests as f64;
 
         let error_count_all =
@@ -386,6 +519,7 @@ impl MonitoringRepo {
                 days_spanned,
                 total_requests,
                 requests_per_day,
+                rolled_up_requests,
             },
             reliability: ReliabilityMetrics {
                 error_rate_all,
@@ -514,42 +648,78 @@ fn family_rates(family: RouteFamily, total: u64, five: u64) -> FamilyErrorRates
     }
 }
 
-async fn count_audit(
+/// Requests in `[start, end)` matching `cond`, from raw rows plus rolled-up days starting in it.
+async fn count_audit_where(
     db: &Database,
     ctx: &'static str,
-    q: &str,
+    cond: &str,
     start: &Datetime,
     end: &Datetime,
 ) -> Result<u64, AppError> {
+    let q = format!(
+        "SELECT count() AS count FROM http_request_audit \
+         WHERE created_at >= $start AND created_at < $end AND {cond} GROUP ALL; \
+         SELECT math::sum(count) AS count FROM http_request_audit_daily \
+         WHERE day >= $start AND day < $end AND {cond} GROUP ALL;"
+    );
     let mut response = db
-        .db
         .query(q)
         .bind(("start", *start))
         .bind(("end", *end))
         .await
         .map_err(|e| surreal_query_err(ctx, e))?;
+    let mut total = 0;
+    for index in 0..2 {
+        let rows: Vec<CountRow> = response
+            .take(index)
+            .map_err(|e| surreal_query_err("metrics.surreal.take", e))?;
+        total += rows.first().map_or(0, |r| r.count.max(0) as u64);
+    }
+    Ok(total)
+}
+
+async fn count_rollup(db: &Database, start: &Datetime, end: &Datetime) -> Result<u64, AppError> {
+    let mut response = db
+        .query(
+            "SELECT math::sum(count) AS count FROM http_request_audit_daily \
+             WHERE day >= $start AND day < $end GROUP ALL",
+        )
+        .bind(("start", *start))
+        .bind(("end", *end))
+        .await
+        .map_err(|e| surreal_query_err("metrics.rolled_up", e))?;
     let rows: Vec<CountRow> = response
         .take(0)
-        .map_err(|e| surreal_query_err("metrics.surreal.take", e))?;
-    Ok(rows
-        .into_iter()
-        .next()
-        .map(|r| r.count.max(0) as u64)
-        .unwrap_or(0))
+        .map_err(|e| surreal_query_err("metrics.rolled_up.take", e))?;
+    Ok(rows.first().map_or(0, |r| r.count.max(0) as u64))
 }
 
-async fn count_audit_where(
+/// Distinct values of `field` (`user` or `session`) over raw and rolled-up rows matching `cond`.
+async fn count_distinct(
     db: &Database,
     ctx: &'static str,
+    field: &str,
     cond: &str,
     start: &Datetime,
     end: &Datetime,
 ) -> Result<u64, AppError> {
     let q = format!(
-        "SELECT count() AS count FROM http_request_audit \
-         WHERE created_at >= $start AND created_at < $end AND {cond} GROUP ALL"
+        "LET $raw = (SELECT {field} FROM http_request_audit \
+           WHERE created_at >= $start AND created_at < $end AND {cond} GROUP BY {field}).{field}; \
+         LET $rolled = (SELECT {field} FROM http_request_audit_daily \
+           WHERE day >= $start AND day < $end AND {cond} GROUP BY {field}).{field}; \
+         RETURN array::len(array::union($raw, $rolled));"
     );
-    count_audit(db, ctx, &q, start, end).await
+    let mut response = db
+        .query(q)
+        .bind(("start", *start))
+        .bind(("end", *end))
+        .await
+        .map_err(|e| surreal_query_err(ctx, e))?;
+    let count: Option<i64> = response
+        .take(2)
+        .map_err(|e| surreal_query_err("metrics.surreal.take", e))?;
+    Ok(count.unwrap_or(0).max(0) as u64)
 }
 
 async fn distinct_users_product(
@@ -558,26 +728,15 @@ async fn distinct_users_product(
     range_start: &Datetime,
     range_end: &Datetime,
 ) -> Result<u64, AppError> {
-    let q = "SELECT count() AS count FROM (SELECT user FROM http_request_audit \
-             WHERE created_at >= $start AND created_at < $end \
-             AND user IS NOT NONE \
-             AND string::starts_with(path, '/api/v1/') AND NOT (string::starts_with(path, '/api/v1/monitoring/')) \
-             GROUP BY user)";
-    let mut response = db
-        .db
-        .query(q)
-        .bind(("start", *range_start))
-        .bind(("end", *range_end))
-        .await
-        .map_err(|e| surreal_query_err(ctx, e))?;
-    let rows: Vec<CountRow> = response
-        .take(0)
-        .map_err(|e| surreal_query_err("metrics.surreal.take", e))?;
-    Ok(rows
-        .into_iter()
-        .next()
-        .map(|r| r.count.max(0) as u64)
-        .unwrap_or(0))
+    count_distinct(
+        db,
+        ctx,
+        "user",
+        PRODUCT_USER_CONDITION,
+        range_start,
+        range_end,
+    )
+    .await
 }
 
 async fn distinct_sessions_in_range(
@@ -592,27 +751,15 @@ async fn distinct_sessions_in_range(
     } else {
         ""
     };
-    let q = format!(
-        "SELECT count() AS count FROM (SELECT session FROM http_request_audit \
-         WHERE created_at >= $start AND created_at < $end \
-         AND session IS NOT NONE {extra} \
-         GROUP BY session)"
-    );
-    let mut response = db
-        .db
-        .query(q)
-        .bind(("start", *range_start))
-        .bind(("end", *range_end))
-        .await
-        .map_err(|e| surreal_query_err(ctx, e))?;
-    let rows: Vec<CountRow> = response
-        .take(0)
-        .map_err(|e| surreal_query_err("metrics.surreal.take", e))?;
-    Ok(rows
-        .into_iter()
-        .next()
-        .map(|r| r.count.max(0) as u64)
-        .unwrap_or(0))
+    count_distinct(
+        db,
+        ctx,
+        "session",
+        &format!("session IS NOT NONE{extra}"),
+        range_start,
+        range_end,
+    )
+    .await
 }
 
 async fn distinct_admins_monitoring(
@@ -620,27 +767,17 @@ async fn distinct_admins_monitoring(
     start: &Datetime,
     end: &Datetime,
 ) -> Result<u64, AppError> {
-    let q = "SELECT count() AS count FROM (SELECT user FROM http_request_audit \
-             WHERE created_at >= $start AND created_at < $end \
-             AND string::starts_with(path, '/api/v1/monitoring/') \
-             AND user IS NOT NONE \
-             AND user IN (SELECT id FROM user WHERE role = 'admin') \
-             GROUP BY user)";
-    let mut response = db
-        .db
-        .query(q)
-        .bind(("start", *start))
-        .bind(("end", *end))
-        .await
-        .map_err(|e| surreal_query_err("metrics.adm_dist", e))?;
-    let rows: Vec<CountRow> = response
-        .take(0)
-        .map_err(|e| surreal_query_err("metrics.adm_dist.take", e))?;
-    Ok(rows
-        .into_iter()
-        .next()
-        .map(|r| r.count.max(0) as u64)
-        .unwrap_or(0))
+    count_distinct(
+        db,
+        "metrics.adm_dist",
+        "user",
+        "string::starts_with(path, '/api/v1/monitoring/') \
+         AND user IS NOT NONE \
+         AND user IN (SELECT VALUE id FROM user WHERE role = 'admin')",
+        start,
+        end,
+    )
+    .await
 }
 
 fn json_to_f64(v: &serde_json::Value) -> Option<f64> {
@@ -676,7 +813,6 @@ async fn percentiles_global(
         and_cond = and_cond
     );
     let mut response = db
-        .db
         .query(q)
         .bind(("start", *start))
         .bind(("end", *end))
@@ -706,7 +842,6 @@ async fn percentiles_by_method(
          WHERE created_at >= $start AND created_at < $end \
          GROUP BY method";
     let mut response = db
-        .db
         .query(q_methods)
         .bind(("start", *start))
         .bind(("end", *end))
@@ -739,7 +874,6 @@ async fn percentiles_by_method(
             sql_string_literal(&row.method)
         );
         let mut response = db
-            .db
             .query(q)
             .bind(("start", *start))
             .bind(("end", *end))
@@ -773,31 +907,52 @@ fn sql_string_literal(s: &str) -> String {
     format!("'{escaped}'")
 }
 
-async fn top_failing_paths(
+/// Requests per path matching `cond`, merged across raw and rolled-up rows.
+async fn count_by_path(
     db: &Database,
+    ctx: &'static str,
+    cond: &str,
     start: &Datetime,
     end: &Datetime,
 ) -> Result<Vec<TopFailingRoute>, AppError> {
-    let q = "SELECT path, count() AS error_count FROM http_request_audit \
-             WHERE created_at >= $start AND created_at < $end AND status_code >= 400 \
-             GROUP BY path ORDER BY error_count DESC LIMIT 20";
+    let q = format!(
+        "SELECT path, count() AS error_count FROM http_request_audit \
+         WHERE created_at >= $start AND created_at < $end AND {cond} GROUP BY path; \
+         SELECT path, math::sum(count) AS error_count FROM http_request_audit_daily \
+         WHERE day >= $start AND day < $end AND {cond} GROUP BY path;"
+    );
     let mut response = db
-        .db
         .query(q)
         .bind(("start", *start))
         .bind(("end", *end))
         .await
-        .map_err(|e| surreal_query_err("metrics.fail_paths", e))?;
-    let rows: Vec<FailPathRow> = response
-        .take(0)
-        .map_err(|e| surreal_query_err("metrics.fail_paths.take", e))?;
-    Ok(rows
+        .map_err(|e| surreal_query_err(ctx, e))?;
+    let mut by_path: HashMap<String, u64> = HashMap::new();
+    for index in 0..2 {
+        let rows: Vec<FailPathRow> = response
+            .take(index)
+            .map_err(|e| surreal_query_err("metrics.paths.take", e))?;
+        for r in rows {
+            *by_path.entry(r.path).or_default() += r.error_count.max(0) as u64;
+        }
+    }
+    let mut out: Vec<TopFailingRoute> = by_path
         .into_iter()
-        .map(|r| TopFailingRoute {
-            path: r.path,
-            error_count: r.error_count.max(0) as u64,
-        })
-        .collect())
+        .map(|(path, error_count)| TopFailingRoute { path, error_count })
+        .collect();
+    out.sort_by(|a, b| b.error_count.cmp(&a.error_count).then(a.path.cmp(&b.path)));
+    Ok(out)
+}
+
+async fn top_failing_paths(
+    db: &Database,
+    start: &Datetime,
+    end: &Datetime,
+) -> Result<Vec<TopFailingRoute>, AppError> {
+    let mut rows =
+        count_by_path(db, "metrics.fail_paths", "status_code >= 400", start, end).await?;
+    rows.truncate(20);
+    Ok(rows)
 }
 
 fn path_looks_like_id_probe_404(path: &str) -> bool {
@@ -832,33 +987,12 @@ async fn id_like_404_metrics(
     start: &Datetime,
     end: &Datetime,
 ) -> Result<(u64, Vec<TopFailingRoute>), AppError> {
-    let q = "SELECT path, count() AS error_count FROM http_request_audit \
-             WHERE created_at >= $start AND created_at < $end AND status_code = 404 \
-             GROUP BY path";
-    let mut response = db
-        .db
-        .query(q)
-        .bind(("start", *start))
-        .bind(("end", *end))
-        .await
-        .map_err(|e| surreal_query_err("metrics.id404_group", e))?;
-    let rows: Vec<FailPathRow> = response
-        .take(0)
-        .map_err(|e| surreal_query_err("metrics.id404_group.take", e))?;
-
-    let mut id_like: u64 = 0;
-    let mut top: Vec<TopFailingRoute> = Vec::new();
-    for r in rows {
-        let n = r.error_count.max(0) as u64;
-        if path_looks_like_id_probe_404(&r.path) {
-            id_like += n;
-            top.push(TopFailingRoute {
-                path: r.path,
-                error_count: n,
-            });
-        }
-    }
-    top.sort_by_key(|r| std::cmp::Reverse(r.error_count));
+    let rows = count_by_path(db, "metrics.id404_group", "status_code = 404", start, end).await?;
+    let mut top: Vec<TopFailingRoute> = rows
+        .into_iter()
+        .filter(|r| path_looks_like_id_probe_404(&r.path))
+        .collect();
+    let id_like = top.iter().map(|r| r.error_count).sum();
     top.truncate(20);
     Ok((id_like, top))
 }
@@ -890,23 +1024,25 @@ async fn feature_families(
             win_end,
         )
         .await?;
-        let q_dist_win = format!(
-            "SELECT count() AS count FROM (SELECT user FROM http_request_audit \
-             WHERE created_at >= $start AND created_at < $end \
-             AND user IS NOT NONE AND string::starts_with(path, '{pat}') \
-             GROUP BY user)"
-        );
-        let dist_win =
-            distinct_from_subquery(db, "metrics.feat_dw", &q_dist_win, win_start, win_end).await?;
-        let q_dist_mo = format!(
-            "SELECT count() AS count FROM (SELECT user FROM http_request_audit \
-             WHERE created_at >= $start AND created_at < $end \
-             AND user IS NOT NONE AND string::starts_with(path, '{pat}') \
-             GROUP BY user)"
-        );
-        let dist_mo =
-            distinct_from_subquery(db, "metrics.feat_dm", &q_dist_mo, month_start, month_end)
-                .await?;
+        let user_cond = format!("user IS NOT NONE AND string::starts_with(path, '{pat}')");
+        let dist_win = count_distinct(
+            db,
+            "metrics.feat_dw",
+            "user",
+            &user_cond,
+            win_start,
+            win_end,
+        )
+        .await?;
+        let dist_mo = count_distinct(
+            db,
+            "metrics.feat_dm",
+            "user",
+            &user_cond,
+            month_start,
+            month_end,
+        )
+        .await?;
         let pct = if mau_users > 0 {
             dist_mo as f64 / mau_users as f64
         } else {
@@ -924,30 +1060,6 @@ async fn feature_families(
     Ok(out)
 }
 
-async fn distinct_from_subquery(
-    db: &Database,
-    ctx: &'static str,
-    q: &str,
-    start: &Datetime,
-    end: &Datetime,
-) -> Result<u64, AppError> {
-    let mut response = db
-        .db
-        .query(q)
-        .bind(("start", *start))
-        .bind(("end", *end))
-        .await
-        .map_err(|e| surreal_query_err(ctx, e))?;
-    let rows: Vec<CountRow> = response
-        .take(0)
-        .map_err(|e| surreal_query_err("metrics.surreal.take", e))?;
-    Ok(rows
-        .into_iter()
-        .next()
-        .map(|r| r.count.max(0) as u64)
-        .unwrap_or(0))
-}
-
 fn is_product_path(path: &str) -> bool {
     path.starts_with("/api/v1/") && !path.starts_with("/api/v1/monitoring/")
 }
@@ -964,7 +1076,6 @@ async fn compute_activation(
          LIMIT {cap}"
     );
     let mut response = db
-        .db
         .query(q)
         .bind(("start", *start))
         .bind(("end", *end))
@@ -1007,19 +1118,26 @@ async fn compute_activation(
     let audit_max = Datetime::from(max_t.expect("non-empty rows") + Duration::days(7));
 
     let user_things: Vec<RecordId> = rows.iter().map(|r| r.id.clone()).collect();
+    // Rolled-up rows only keep the time of their latest request.
     let q2 = "SELECT user, path, created_at FROM http_request_audit \
-              WHERE user IN $users AND created_at >= $a_start AND created_at < $a_end";
+              WHERE user IN $users AND created_at >= $a_start AND created_at < $a_end; \
+              SELECT user, path, last_at AS created_at FROM http_request_audit_daily \
+              WHERE user IN $users AND last_at >= $a_start AND last_at < $a_end;";
     let mut response = db
-        .db
         .query(q2)
         .bind(("users", user_things))
         .bind(("a_start", audit_min))
         .bind(("a_end", audit_max))
         .await
         .map_err(|e| surreal_query_err("metrics.act_audit", e))?;
-    let hits: Vec<AuditActivationRow> = response
+    let mut hits: Vec<AuditActivationRow> = response
         .take(0)
         .map_err(|e| surreal_query_err("metrics.act_audit.take", e))?;
+    hits.extend(
+        response
+            .take::<Vec<AuditActivationRow>>(1)
+            .map_err(|e| surreal_query_err("metrics.act_audit.take", e))?,
+    );
 
     let mut activated: HashSet<String> = HashSet::new();
     for h in hits {
@@ -1049,3 +1167,73 @@ async fn compute_activation(
         None,
     ))
 }
+
+/// ` WHERE …` for `filter` plus `extra` conditions, or an empty string when there are none.
+fn filter_clause(filter: &HttpAuditLogFilter, extra: &[&str]) -> String {
+    let mut conditions: Vec<&str> = Vec::new();
+    if filter.user.is_some() {
+        conditions.push("user = $user");
+    }
+    if filter.status.is_some() {
+        conditions.push("status_code >= $status_min AND status_code < $status_max");
+    }
+    if let Some(family) = filter.family {
+        conditions.push(family.path_condition());
+    }
+    if filter.since.is_some() {
+        conditions.push("created_at >= $since");
+    }
+    if filter.until.is_some() {
+        conditions.push("created_at < $until");
+    }
+    conditions.extend_from_slice(extra);
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+fn bind_filter<'a>(query: TimedQuery<'a>, filter: &HttpAuditLogFilter) -> TimedQuery<'a> {
+    let (status_min, status_max) = filter.status.unzip();
+    query
+        .bind(("user", filter.user.clone()))
+        .bind(("status_min", status_min))
+        .bind(("status_max", status_max))
+        .bind(("since", filter.since.map(Datetime::from)))
+        .bind(("until", filter.until.map(Datetime::from)))
+}
+
+fn security_filter_clause(filter: &SecurityEventFilter) -> String {
+    let mut conditions: Vec<&str> = Vec::new();
+    if filter.user_id.is_some() {
+        conditions.push("user_id = $user_id");
+    }
+    match filter.event {
+        Some(EventMatch::Exact(_)) => conditions.push("event = $event"),
+        Some(EventMatch::Prefix(_)) => conditions.push("string::starts_with(event, $event)"),
+        None => {}
+    }
+    if filter.since.is_some() {
+        conditions.push("created_at >= $since");
+    }
+    if filter.until.is_some() {
+        conditions.push("created_at < $until");
+    }
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+fn bind_security_filter<'a>(query: TimedQuery<'a>, filter: &SecurityEventFilter) -> TimedQuery<'a> {
+    let event = filter.event.as_ref().map(|e| match e {
+        EventMatch::Exact(name) | EventMatch::Prefix(name) => name.clone(),
+    });
+    query
+        .bind(("user_id", filter.user_id.clone()))
+        .bind(("event", event))
+        .bind(("since", filter.since.map(Datetime::from)))
+        .bind(("until", filter.until.map(Datetime::from)))
+}
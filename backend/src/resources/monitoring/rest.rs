@@ -7,34 +7,51 @@ use crate::request_link;
 use actix_web::http::header;
 use actix_web::{
     HttpRequest, HttpResponse, Scope, get,
-    web::{Data, Query},
+    web::{Bytes, Data, Query, ReqData},
 };
+use futures_util::stream::{self, StreamExt};
 use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+use shared::user::User;
+use surrealdb::types::{Datetime, RecordId};
 
-use super::MonitoringMetricsQuery;
+use super::model::{
+    AuditExportFormat, AuditExportFormatQuery, HttpAuditLogFilter, SecurityEventFilter,
+};
 use super::repo::MonitoringRepo;
 #[allow(unused_imports)] // Only referenced from `utoipa::path` response schemas
-use super::{HttpAuditLog, MonitoringMetricsResponse};
+use super::{HttpAuditLog, MonitoringMetricsResponse, SecurityEvent};
+use super::{HttpAuditLogQuery, MonitoringMetricsQuery, SecurityEventQuery};
+
+/// Rows fetched per database round trip while streaming an export.
+const EXPORT_PAGE_SIZE: u32 = 1000;
 
 pub fn scope() -> Scope {
     actix_web::web::scope("/monitoring").service(
         actix_web::web::scope("")
             .wrap(RequireAdmin)
+            .service(export_http_audit_logs)
             .service(list_http_audit_logs)
+            .service(list_security_events)
             .service(get_monitoring_metrics),
     )
 }
 
+/// `raw` with its `page` parameter replaced, so page links keep every filter as sent.
+fn query_string_for_page(raw: &str, page: u32) -> String {
+    raw.split('&')
+        .filter(|pair| !pair.is_empty() && !pair.starts_with("page="))
+        .chain(std::iter::once(format!("page={page}").as_str()))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/monitoring/http-audit-logs",
-    params(
-        ("page" = Option<u32>, Query, description = "Page index, zero-based. Defaults to 0.", minimum = 0, nullable = true),
-        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50.", minimum = 1, maximum = 500, example = 50, nullable = true),
-    ),
+    params(HttpAuditLogQuery),
     responses(
-        (status = 200, description = "Paginated HTTP request audit log (newest first). `X-Total-Count` is the total row count.", body = [HttpAuditLog]),
-        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 200, description = "Paginated HTTP request audit log (newest first), filtered by user, status, route family and time range. `X-Total-Count` is the number of matching rows. Rows past `AUDIT_RETENTION_DAYS` are only kept as daily rollups and are not listed.", body = [HttpAuditLog]),
+        (status = 400, description = "Invalid pagination or filter parameters", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -50,18 +67,17 @@ pub fn scope() -> Scope {
 async fn list_http_audit_logs(
     req: HttpRequest,
     db: Data<Database>,
-    query: Query<PageQuery>,
+    query: Query<HttpAuditLogQuery>,
 ) -> Result<HttpResponse, AppError> {
-    let query = query
+    let (query, filter) = query
         .into_inner()
         .validate()
         .map_err(crate::error::map_list_query_error)?;
     let list_query = query.as_list_query();
-    let q_link = list_query.clone();
     let page = list_query.page.unwrap_or(0);
     let page_size = list_query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
-    let items = MonitoringRepo::list_http_audit_logs(db.get_ref(), list_query.clone()).await?;
-    let total = MonitoringRepo::count_http_audit_logs(db.get_ref()).await?;
+    let items = MonitoringRepo::list_http_audit_logs(db.get_ref(), list_query, &filter).await?;
+    let total = MonitoringRepo::count_http_audit_logs(db.get_ref(), &filter).await?;
     Ok(HttpResponse::Ok()
         .insert_header((
             header::HeaderName::from_static("x-total-count"),
@@ -71,7 +87,7 @@ async fn list_http_audit_logs(
             header::LINK,
             request_link::list_link_header(
                 &req,
-                |p| q_link.query_string_for_page(p),
+                |p| query_string_for_page(req.query_string(), p),
                 page,
                 page_size,
                 total,
@@ -80,6 +96,114 @@ async fn list_http_audit_logs(
         .json(items))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/monitoring/http-audit-logs/export",
+    params(AuditExportFormatQuery, HttpAuditLogQuery),
+    responses(
+        (status = 200, description = "Every matching audit row, newest first, streamed as NDJSON (one `HttpAuditLog` per line) or CSV with a header row. `page` and `page_size` are ignored. Rolled-up days are not exported.", body = HttpAuditLog, content_type = "application/x-ndjson"),
+        (status = 400, description = "Invalid format or filter parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to export audit logs", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Monitoring",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/http-audit-logs/export")]
+async fn export_http_audit_logs(
+    db: Data<Database>,
+    format: Query<AuditExportFormatQuery>,
+    query: Query<HttpAuditLogQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (_, filter) = query
+        .into_inner()
+        .validate()
+        .map_err(AppError::invalid_request)?;
+    let format = format.into_inner().format.unwrap_or_default();
+    // Fetch the first page before answering so a failing query is still a Problem response.
+    let first =
+        MonitoringRepo::export_http_audit_page(db.get_ref(), &filter, None, EXPORT_PAGE_SIZE)
+            .await?;
+    let header_chunk = match format {
+        AuditExportFormat::Ndjson => None,
+        AuditExportFormat::Csv => Some(Ok::<_, actix_web::Error>(Bytes::from_static(
+            HttpAuditLog::CSV_HEADER.as_bytes(),
+        ))),
+    };
+    let pages = stream::try_unfold(ExportCursor::Page(first), move |cursor| {
+        let db = db.clone();
+        let filter = filter.clone();
+        async move { cursor.next(&db, &filter, format).await }
+    });
+    let body = stream::iter(header_chunk).chain(pages);
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"http-audit-logs.{}\"",
+                format.file_extension()
+            ),
+        ))
+        .streaming(body))
+}
+
+enum ExportCursor {
+    /// Rows fetched but not yet written.
+    Page(Vec<super::model::HttpAuditRecord>),
+    /// Fetch the page after this row.
+    After(Datetime, RecordId),
+    Done,
+}
+
+impl ExportCursor {
+    async fn next(
+        self,
+        db: &Database,
+        filter: &HttpAuditLogFilter,
+        format: AuditExportFormat,
+    ) -> Result<Option<(Bytes, Self)>, actix_web::Error> {
+        let rows = match self {
+            Self::Page(rows) => rows,
+            Self::After(at, id) => {
+                MonitoringRepo::export_http_audit_page(db, filter, Some((at, id)), EXPORT_PAGE_SIZE)
+                    .await?
+            }
+            Self::Done => return Ok(None),
+        };
+        let next = match rows.last() {
+            Some(last) if rows.len() == EXPORT_PAGE_SIZE as usize => match &last.id {
+                Some(id) => Self::After(last.created_at, id.clone()),
+                None => Self::Done,
+            },
+            _ => Self::Done,
+        };
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let mut chunk = String::new();
+        for row in rows {
+            let log = row.into_wire();
+            match format {
+                AuditExportFormat::Ndjson => {
+                    chunk.push_str(
+                        &serde_json::to_string(&log)
+                            .map_err(|e| AppError::Internal(e.to_string()))?,
+                    );
+                    chunk.push('\n');
+                }
+                AuditExportFormat::Csv => chunk.push_str(&log.to_csv_record()),
+            }
+        }
+        Ok(Some((Bytes::from(chunk), next)))
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/monitoring/metrics",
@@ -110,3 +234,115 @@ async fn get_monitoring_metrics(
     let body = MonitoringRepo::fetch_metrics(db.get_ref(), query.as_window()).await?;
     Ok(HttpResponse::Ok().json(body))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/monitoring/security-events",
+    params(SecurityEventQuery),
+    responses(
+        (status = 200, description = "Persisted audit events (logins and failed logins, OTP requests, session and API token changes, role and membership changes, user deletions), newest first, filtered by user, event and time range. `X-Total-Count` is the number of matching events. Rate-limit rejections are not included.", body = [SecurityEvent]),
+        (status = 400, description = "Invalid pagination or filter parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to list security events", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Monitoring",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/security-events")]
+async fn list_security_events(
+    req: HttpRequest,
+    db: Data<Database>,
+    query: Query<SecurityEventQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (query, filter) = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let list_query = query.as_list_query();
+    let page = list_query.page.unwrap_or(0);
+    let page_size = list_query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let items = MonitoringRepo::list_security_events(db.get_ref(), list_query, &filter).await?;
+    let total = MonitoringRepo::count_security_events(db.get_ref(), &filter).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            request_link::list_link_header(
+                &req,
+                |p| query_string_for_page(req.query_string(), p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(items))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/security-events",
+    params(
+        ("page" = Option<u32>, Query, description = "Page index, zero-based. Defaults to 0.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Security events about the current user's account (logins and failed logins, sessions, API tokens, passkeys, linked identities, team roles and memberships), newest first. `client_ip` and `user_agent` are only shown for events the user caused. `X-Total-Count` is the total before paging.", body = [SecurityEvent]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to list security events", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/me/security-events")]
+pub async fn get_security_events_for_current_user(
+    req: HttpRequest,
+    db: Data<Database>,
+    user: ReqData<User>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let filter = SecurityEventFilter {
+        user_id: Some(user.id.clone()),
+        ..SecurityEventFilter::default()
+    };
+    let items = MonitoringRepo::list_security_events(db.get_ref(), query.as_list_query(), &filter)
+        .await?
+        .into_iter()
+        .map(|event| event.for_subject(&user.id))
+        .collect::<Vec<_>>();
+    let total = MonitoringRepo::count_security_events(db.get_ref(), &filter).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            request_link::list_link_header(
+                &req,
+                |p| query.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(items))
+}
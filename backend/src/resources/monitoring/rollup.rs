@@ -0,0 +1,195 @@
+//! Folds `http_request_audit` rows past the audit retention into the daily rollup.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::resources::job::{JobContext, JobHandler};
+
+use super::repo::MonitoringRepo;
+
+/// Start of the oldest UTC day whose rows are still kept raw.
+fn rollup_cutoff(now: DateTime<Utc>, retention_days: u32) -> DateTime<Utc> {
+    start_of_day(now - Duration::days(i64::from(retention_days)))
+}
+
+fn start_of_day(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.date_naive().and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Rolls up every whole day before the retention cutoff, oldest first, one transaction per day.
+/// Returns how many raw rows were folded.
+pub(crate) async fn roll_up_expired(
+    db: &Database,
+    now: DateTime<Utc>,
+    retention_days: u32,
+) -> Result<u64, AppError> {
+    let cutoff = rollup_cutoff(now, retention_days);
+    let mut rolled = 0;
+    while let Some(oldest) = MonitoringRepo::oldest_audit_before(db, cutoff).await? {
+        rolled += MonitoringRepo::roll_up_audit_day(db, start_of_day(oldest)).await?;
+    }
+    Ok(rolled)
+}
+
+/// Runs scheduled `audit.rollup` jobs: [`roll_up_expired`] with the audit retention.
+pub struct AuditRollupJob {
+    db: Arc<Database>,
+    retention_days: u32,
+}
+
+impl AuditRollupJob {
+    pub fn new(db: Arc<Database>, retention_days: u32) -> Self {
+        Self { db, retention_days }
+    }
+}
+
+#[async_trait]
+impl JobHandler for AuditRollupJob {
+    async fn run(&self, _ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+        let rolled = roll_up_expired(&self.db, Utc::now(), self.retention_days).await?;
+        Ok(Some(serde_json::json!({ "rolled": rolled })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use serde::Deserialize;
+    use surrealdb::types::SurrealValue;
+
+    use crate::database::Database;
+    use crate::resources::monitoring::MetricsWindow;
+    use crate::resources::monitoring::repo::MonitoringRepo;
+    use crate::test_helpers::{create_user, test_db};
+
+    use super::roll_up_expired;
+
+    async fn seed(db: &Database, rid: &str, path: &str, status: i64, user: Option<&str>, at: &str) {
+        db.query(
+            "CREATE http_request_audit SET request_id = $rid, method = 'GET', path = $path, \
+             status_code = $status, duration_ms = 10, session = NONE, client_origin = 'cli', \
+             user = IF $user = NONE THEN NONE ELSE type::record('user', $user) END, \
+             created_at = <datetime> $at",
+        )
+        .bind(("rid", rid.to_owned()))
+        .bind(("path", path.to_owned()))
+        .bind(("status", status))
+        .bind(("user", user.map(str::to_owned)))
+        .bind(("at", at.to_owned()))
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    }
+
+    async fn table_count(db: &Database, table: &str) -> i64 {
+        #[derive(Deserialize, SurrealValue)]
+        struct CountRow {
+            count: i64,
+        }
+        let rows: Vec<CountRow> = db
+            .query(format!("SELECT count() AS count FROM {table} GROUP ALL"))
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        rows.first().map_or(0, |r| r.count)
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    /// BLC-MON-007, BLC-MON-008: whole days past the retention move into the rollup, and metrics
+    /// over a window spanning both tables count every request once.
+    #[tokio::test]
+    async fn blc_mon_007_rollup_folds_old_days_and_metrics_still_count_them() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "rollup@test.local").await.unwrap();
+        let user_id = user.id.as_str();
+        seed(
+            &db,
+            "r1",
+            "/api/v1/songs",
+            200,
+            Some(user_id),
+            "2026-03-01T08:00:00Z",
+        )
+        .await;
+        seed(
+            &db,
+            "r2",
+            "/api/v1/songs",
+            200,
+            Some(user_id),
+            "2026-03-01T09:00:00Z",
+        )
+        .await;
+        seed(
+            &db,
+            "r3",
+            "/api/v1/missing",
+            404,
+            None,
+            "2026-03-02T10:00:00Z",
+        )
+        .await;
+        seed(
+            &db,
+            "r4",
+            "/api/v1/songs",
+            500,
+            Some(user_id),
+            "2026-03-09T10:00:00Z",
+        )
+        .await;
+
+        // Retention of 7 days on 2026-03-10 keeps 2026-03-03 onwards raw.
+        let rolled = roll_up_expired(&db, at("2026-03-10T12:00:00Z"), 7)
+            .await
+            .unwrap();
+        assert_eq!(rolled, 3);
+        assert_eq!(table_count(&db, "http_request_audit").await, 1);
+        // The two identical requests of 2026-03-01 share one rollup row.
+        assert_eq!(table_count(&db, "http_request_audit_daily").await, 2);
+        assert_eq!(
+            roll_up_expired(&db, at("2026-03-10T12:00:00Z"), 7)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let metrics = MonitoringRepo::fetch_metrics(
+            &db,
+            MetricsWindow {
+                start: at("2026-03-01T00:00:00Z"),
+                end: at("2026-03-10T00:00:00Z"),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(metrics.window.total_requests, 4);
+        assert_eq!(metrics.window.rolled_up_requests, 3);
+        assert_eq!(metrics.reliability.error_count_all, 2);
+        assert_eq!(metrics.engagement.distinct_active_users_product, 1);
+        assert_eq!(metrics.engagement.product_requests_with_user, 3);
+        assert_eq!(metrics.top_failing_routes.len(), 2);
+
+        #[derive(Deserialize, SurrealValue)]
+        struct RequestCountRow {
+            request_count: i64,
+        }
+        let stats: Vec<RequestCountRow> = db
+            .query("SELECT request_count FROM type::record('user', $id)")
+            .bind(("id", user.id.clone()))
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+        assert_eq!(stats[0].request_count, 3);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{blob, collection, monitoring, setlist, song, team, user};
+use super::{batch, blob, collection, event, job, monitoring, setlist, song, sync, team, user};
 use crate::about;
 use crate::auth::middleware::RequireUser;
 use crate::governor_audit::AuditRateLimit429;
@@ -26,12 +26,18 @@ pub fn scope(
         .service(
             web::scope("")
                 .wrap(RequireUser)
+                .service(batch::rest::post_batch)
                 .service(blob::rest::scope(blob_upload_max_bytes))
+                .service(blob::rest::admin_scope())
                 .service(collection::rest::scope())
+                .service(event::rest::get_events)
+                .service(job::rest::scope())
                 .service(setlist::rest::scope())
                 .service(song::rest::scope())
+                .service(sync::rest::get_sync)
                 .service(team::rest::scope())
                 .service(team::invitations_accept_scope())
+                .service(team::organizations_scope())
                 .service(monitoring::rest::scope())
                 .service(user::rest::scope(avatar_upload_max_bytes)),
         )
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use surrealdb::types::{RecordId, SurrealValue};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
 
 use shared::setlist::{CreateSetlist, Setlist};
 
@@ -15,6 +15,9 @@ pub struct SetlistRecord {
     title: String,
     #[serde(default)]
     songs: Vec<SongLinkRecord>,
+    /// Set while the setlist is in its team's trash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trashed_at: Option<Datetime>,
 }
 
 impl SetlistRecord {
@@ -38,6 +41,7 @@ impl SetlistRecord {
             owner,
             title,
             songs: songs.into_iter().map(Into::into).collect(),
+            trashed_at: None,
         }
     }
 }
@@ -84,6 +88,7 @@ mod tests {
             SurrealSetlistRepo::new(db.clone()),
             std::sync::Arc::new(SurrealTeamResolver::new(db.clone())),
             db.clone(),
+            crate::resources::team::webhook::ContentEventRecorder::build(db.clone()),
         );
         let user = seed_user(&db).await.expect("seed user");
         let perms = UserPermissions::from_ref(&user, &svc.teams);
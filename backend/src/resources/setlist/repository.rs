@@ -45,8 +45,13 @@ pub trait SetlistRepository: Send + Sync {
         owner: Option<RecordId>,
     ) -> Result<Setlist, AppError>;
 
-    async fn delete_setlist(&self, write_teams: &[RecordId], id: &str)
-    -> Result<Setlist, AppError>;
+    /// Moves the setlist into its team's trash (see the trash service for restore and purge).
+    async fn trash_setlist(
+        &self,
+        write_teams: &[RecordId],
+        actor_user_id: &str,
+        id: &str,
+    ) -> Result<Setlist, AppError>;
 
     async fn move_setlist_owner(
         &self,
@@ -8,7 +8,7 @@ use crate::accept::accepts_worship_player_json;
 #[allow(unused_imports)]
 use crate::docs::Problem;
 use crate::error::AppError;
-use crate::http_cache::{check_if_match, if_none_match_matches, weak_etag_json};
+use crate::http_cache::{check_if_match, if_none_match_matches, json_with_etag, weak_etag_json};
 use crate::resources::User;
 use crate::resources::setlist::PatchSetlist;
 #[allow(unused_imports)]
@@ -238,7 +238,7 @@ async fn get_setlist_songs(
     path = "/api/v1/setlists",
     request_body = CreateSetlist,
     responses(
-        (status = 201, description = "Create a new setlist. Optional `owner` is a team id; omit for the caller's personal team. Library edit access is required on the target team.", body = Setlist),
+        (status = 201, description = "Create a new setlist. Optional `owner` is a team id; omit for the caller's personal team. Library edit access is required on the target team. Response includes the weak `ETag`.", body = Setlist),
         (status = 400, description = "Invalid setlist payload", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 404, description = "Target team not found or caller cannot edit that team's library", body = Problem, content_type = "application/problem+json"),
@@ -258,10 +258,11 @@ async fn create_setlist(
     payload: Json<CreateSetlist>,
 ) -> Result<HttpResponse, AppError> {
     let perms = UserPermissions::from_ref(&user, &svc.teams);
-    Ok(HttpResponse::Created().json(
-        svc.create_setlist_for_user(&perms, payload.into_inner())
-            .await?,
-    ))
+    let setlist = svc
+        .create_setlist_for_user(&perms, payload.into_inner())
+        .await?;
+    json_with_etag(HttpResponse::Created(), &setlist)
+        .map_err(|e| AppError::internal_from_err("setlist.rest", e))
 }
 
 #[utoipa::path(
@@ -272,7 +273,7 @@ async fn create_setlist(
     ),
     request_body = UpdateSetlist,
     responses(
-        (status = 200, description = "Replace setlist fields (`PUT` is full replacement, not upsert; missing id returns **404**).", body = Setlist),
+        (status = 200, description = "Replace setlist fields (`PUT` is full replacement, not upsert; missing id returns **404**). Response includes the new weak `ETag`.", body = Setlist),
         (status = 400, description = "Invalid setlist identifier", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -303,10 +304,11 @@ async fn update_setlist(
     let payload = payload.into_inner();
     let owner = payload.owner.clone();
     let payload = CreateSetlist::from(payload);
-    Ok(HttpResponse::Ok().json(
-        svc.update_setlist_for_user(&perms, &id, payload, owner)
-            .await?,
-    ))
+    let setlist = svc
+        .update_setlist_for_user(&perms, &id, payload, owner)
+        .await?;
+    json_with_etag(HttpResponse::Ok(), &setlist)
+        .map_err(|e| AppError::internal_from_err("setlist.rest", e))
 }
 
 #[utoipa::path(
@@ -317,7 +319,7 @@ async fn update_setlist(
     ),
     request_body = PatchSetlist,
     responses(
-        (status = 200, description = "Partially update an existing setlist", body = Setlist),
+        (status = 200, description = "Partially update an existing setlist. Response includes the new weak `ETag`.", body = Setlist),
         (status = 400, description = "Invalid setlist identifier or payload", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -345,10 +347,11 @@ async fn patch_setlist(
     let etag =
         weak_etag_json(&setlist).map_err(|e| AppError::internal_from_err("setlist.rest", e))?;
     check_if_match(&req, &etag)?;
-    Ok(HttpResponse::Ok().json(
-        svc.patch_setlist_for_user(&perms, &id, payload.into_inner())
-            .await?,
-    ))
+    let setlist = svc
+        .patch_setlist_for_user(&perms, &id, payload.into_inner())
+        .await?;
+    json_with_etag(HttpResponse::Ok(), &setlist)
+        .map_err(|e| AppError::internal_from_err("setlist.rest", e))
 }
 
 #[utoipa::path(
@@ -393,7 +396,7 @@ async fn move_setlist(
         ("id" = String, Path, description = "Setlist identifier")
     ),
     responses(
-        (status = 204, description = "Setlist deleted"),
+        (status = 204, description = "Setlist moved to its team's trash"),
         (status = 400, description = "Invalid setlist identifier", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
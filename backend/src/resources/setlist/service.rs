@@ -5,11 +5,13 @@ use shared::api::ListQuery;
 use shared::player::Player;
 use shared::setlist::{CreateSetlist, PatchSetlist, Setlist};
 use shared::song::Song;
+use shared::team::{ActivityAction, ActivityResourceType, TeamPermission};
 use tracing::instrument;
 
 use crate::error::AppError;
 use crate::resources::common::resolve_owner_team;
 use crate::resources::song::LikedSongIds;
+use crate::resources::team::activity::{ActivityRecorder, NewTeamActivity};
 use crate::resources::team::{
     TeamResolver, UserPermissions, parse_owner_record_id, thing_record_key,
 };
@@ -19,19 +21,43 @@ use crate::resources::common::player_from_song_links;
 
 /// Application service: team resolution, authorization, and orchestration for setlists.
 #[derive(Clone)]
-pub struct SetlistService<R, T, L> {
+pub struct SetlistService<R, T, L, A> {
     pub repo: R,
     pub teams: Arc<T>,
     pub likes: L,
+    pub activity: A,
 }
 
-impl<R, T, L> SetlistService<R, T, L> {
-    pub fn new(repo: R, teams: Arc<T>, likes: L) -> Self {
-        Self { repo, teams, likes }
+impl<R, T, L, A> SetlistService<R, T, L, A> {
+    pub fn new(repo: R, teams: Arc<T>, likes: L, activity: A) -> Self {
+        Self {
+            repo,
+            teams,
+            likes,
+            activity,
+        }
     }
 }
 
-impl<R: SetlistRepository, T: TeamResolver, L: LikedSongIds> SetlistService<R, T, L> {
+fn setlist_activity(
+    actor_user_id: &str,
+    setlist: &Setlist,
+    action: ActivityAction,
+) -> NewTeamActivity {
+    NewTeamActivity::new(
+        &setlist.owner,
+        actor_user_id,
+        ActivityResourceType::Setlist,
+        &setlist.id,
+        action,
+    )
+    .with_title(Some(&setlist.title))
+    .with_etag(setlist)
+}
+
+impl<R: SetlistRepository, T: TeamResolver, L: LikedSongIds, A: ActivityRecorder>
+    SetlistService<R, T, L, A>
+{
     #[instrument(level = "debug", err, skip(self, perms))]
     pub async fn list_setlists_for_user(
         &self,
@@ -111,11 +137,21 @@ impl<R: SetlistRepository, T: TeamResolver, L: LikedSongIds> SetlistService<R, T
             None => perms.personal_team().await?,
             Some(ref s) => {
                 let rid = parse_owner_record_id(s)?;
-                perms.require_write_access_to_owner(&rid).await?;
+                perms
+                    .require_permission_on_owner(&rid, TeamPermission::SetlistsWrite)
+                    .await?;
                 rid
             }
         };
-        self.repo.create_setlist(owner, setlist).await
+        let created = self.repo.create_setlist(owner, setlist).await?;
+        self.activity
+            .record_activity_or_warn(setlist_activity(
+                &perms.user().id,
+                &created,
+                ActivityAction::Created,
+            ))
+            .await;
+        Ok(created)
     }
 
     #[instrument(level = "debug", err, skip(self, perms, setlist))]
@@ -126,11 +162,20 @@ impl<R: SetlistRepository, T: TeamResolver, L: LikedSongIds> SetlistService<R, T
         setlist: CreateSetlist,
         owner: Option<String>,
     ) -> Result<Setlist, AppError> {
-        let write_teams = perms.write_teams().await?;
-        let owner = resolve_owner_team(write_teams, owner)?;
-        self.repo
-            .update_setlist(write_teams, id, setlist, owner)
-            .await
+        let write_teams = perms.teams_with(TeamPermission::SetlistsWrite).await?;
+        let owner = resolve_owner_team(&write_teams, owner)?;
+        let updated = self
+            .repo
+            .update_setlist(&write_teams, id, setlist, owner)
+            .await?;
+        self.activity
+            .record_activity_or_warn(setlist_activity(
+                &perms.user().id,
+                &updated,
+                ActivityAction::Updated,
+            ))
+            .await;
+        Ok(updated)
     }
 
     #[instrument(level = "debug", err, skip(self, perms, patch))]
@@ -163,10 +208,31 @@ impl<R: SetlistRepository, T: TeamResolver, L: LikedSongIds> SetlistService<R, T
         if thing_record_key(&current) == thing_record_key(&dest) {
             return Ok(setlist);
         }
-        perms.require_write_access_to_owner(&current).await?;
-        perms.require_write_access_to_owner(&dest).await?;
-        let write_teams = perms.write_teams().await?;
-        self.repo.move_setlist_owner(write_teams, id, dest).await
+        perms
+            .require_permission_on_owner(&current, TeamPermission::SetlistsDelete)
+            .await?;
+        perms
+            .require_permission_on_owner(&dest, TeamPermission::SetlistsWrite)
+            .await?;
+        let source_teams = perms.teams_with(TeamPermission::SetlistsDelete).await?;
+        let moved = self
+            .repo
+            .move_setlist_owner(&source_teams, id, dest)
+            .await?;
+        self.activity
+            .record_activity_or_warn(
+                setlist_activity(&perms.user().id, &setlist, ActivityAction::Moved)
+                    .with_other_team(&moved.owner)
+                    .with_etag(&moved),
+            )
+            .await;
+        self.activity
+            .record_activity_or_warn(
+                setlist_activity(&perms.user().id, &moved, ActivityAction::Moved)
+                    .with_other_team(&setlist.owner),
+            )
+            .await;
+        Ok(moved)
     }
 
     #[instrument(level = "debug", err, skip(self, perms))]
@@ -175,8 +241,19 @@ impl<R: SetlistRepository, T: TeamResolver, L: LikedSongIds> SetlistService<R, T
         perms: &UserPermissions<T>,
         id: &str,
     ) -> Result<Setlist, AppError> {
-        let write_teams = perms.write_teams().await?;
-        self.repo.delete_setlist(write_teams, id).await
+        let delete_teams = perms.teams_with(TeamPermission::SetlistsDelete).await?;
+        let deleted = self
+            .repo
+            .trash_setlist(&delete_teams, &perms.user().id, id)
+            .await?;
+        self.activity
+            .record_activity_or_warn(setlist_activity(
+                &perms.user().id,
+                &deleted,
+                ActivityAction::Deleted,
+            ))
+            .await;
+        Ok(deleted)
     }
 }
 
@@ -185,6 +262,7 @@ pub type SetlistServiceHandle = SetlistService<
     super::surreal_repo::SurrealSetlistRepo,
     crate::resources::team::SurrealTeamResolver,
     Arc<crate::database::Database>,
+    crate::resources::team::webhook::ContentEventRecorder,
 >;
 
 #[cfg(test)]
@@ -204,12 +282,14 @@ mod tests {
     use crate::error::AppError;
     use crate::resources::User;
     use crate::resources::song::LikedSongIds;
-    use crate::resources::team::{TeamResolver, UserPermissions};
+    use crate::resources::team::activity::{ActivityRecorder, DiscardActivity, NewTeamActivity};
+    use crate::resources::team::{TeamGrant, TeamResolver, UserPermissions};
     use crate::test_helpers::{
         TeamFixture, configure_personal_team_members, create_song_with_title, create_user,
         personal_team_id, setlist_service, setlist_with_songs, test_db, two_shared_teams_for_user,
     };
     use shared::MoveOwner;
+    use shared::team::TeamPermission;
 
     use super::{SetlistRepository, SetlistService};
 
@@ -282,9 +362,10 @@ mod tests {
             }
         }
 
-        async fn delete_setlist(
+        async fn trash_setlist(
             &self,
             _write_teams: &[RecordId],
+            _actor_user_id: &str,
             _id: &str,
         ) -> Result<Setlist, AppError> {
             Err(AppError::NotFound("setlist not found".into()))
@@ -311,8 +392,15 @@ mod tests {
             Ok(self.read.clone())
         }
 
-        async fn content_write_teams(&self, _user: &User) -> Result<Vec<RecordId>, AppError> {
-            Ok(self.write.clone())
+        async fn content_grants(&self, _user: &User) -> Result<Vec<TeamGrant>, AppError> {
+            Ok(self
+                .write
+                .iter()
+                .map(|team| TeamGrant {
+                    team: team.clone(),
+                    permissions: TeamPermission::ALL.to_vec(),
+                })
+                .collect())
         }
 
         async fn personal_team(&self, _user_id: &str) -> Result<RecordId, AppError> {
@@ -392,6 +480,7 @@ mod tests {
             MockLikes {
                 ids: HashSet::new(),
             },
+            DiscardActivity,
         );
         let perms = UserPermissions::from_ref(&user, &svc.teams);
         let r = svc.get_setlist_for_user(&perms, "nope").await;
@@ -415,6 +504,7 @@ mod tests {
             MockLikes {
                 ids: HashSet::new(),
             },
+            DiscardActivity,
         );
         let perms = UserPermissions::from_ref(&user, &svc.teams);
         let r = svc
@@ -449,6 +539,51 @@ mod tests {
             MockLikes {
                 ids: HashSet::new(),
             },
+            DiscardActivity,
+        );
+        let perms = UserPermissions::from_ref(&user, &svc.teams);
+        let r = svc
+            .update_setlist_for_user(
+                &perms,
+                "id",
+                CreateSetlist {
+                    owner: None,
+                    title: "t".into(),
+                    songs: vec![],
+                },
+                None,
+            )
+            .await;
+        assert!(r.is_ok());
+    }
+
+    struct FailingActivity;
+
+    #[async_trait]
+    impl ActivityRecorder for FailingActivity {
+        async fn record_activity(&self, _entry: NewTeamActivity) -> Result<(), AppError> {
+            Err(AppError::database("activity store unavailable"))
+        }
+    }
+
+    /// BLC-TACT-008: a failing activity recorder does not fail the update.
+    #[tokio::test]
+    async fn update_succeeds_when_activity_recording_fails() {
+        let user = test_user();
+        let svc = SetlistService::new(
+            MockRepo {
+                setlists: vec![],
+                get_returns: None,
+                update_ok: true,
+            },
+            Arc::new(MockTeams {
+                read: vec![team_a()],
+                write: vec![team_a()],
+            }),
+            MockLikes {
+                ids: HashSet::new(),
+            },
+            FailingActivity,
         );
         let perms = UserPermissions::from_ref(&user, &svc.teams);
         let r = svc
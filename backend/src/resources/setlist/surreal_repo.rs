@@ -14,7 +14,7 @@ use crate::database::Database;
 use crate::error::AppError;
 
 use crate::resources::common::{
-    SongLinkListRow, SongLinkRecord, belongs_to, resource_id, song_links_to_owned,
+    LIVE_SONG_LINKS, SongLinkListRow, SongLinkRecord, belongs_to, resource_id, song_links_to_owned,
 };
 
 use super::model::SetlistRecord;
@@ -45,11 +45,13 @@ impl SetlistRepository for SurrealSetlistRepo {
         let db = self.inner();
         let q_nonempty = pagination.q.as_ref().is_some_and(|q| !q.trim().is_empty());
         let mut query = if q_nonempty {
-            String::from(
-                "SELECT *, (search::score(0) ?? 0) AS score FROM setlist WHERE owner IN $teams",
+            format!(
+                "SELECT *, {LIVE_SONG_LINKS}, (search::score(0) ?? 0) AS score FROM setlist WHERE owner IN $teams AND trashed_at = NONE",
             )
         } else {
-            String::from("SELECT * FROM setlist WHERE owner IN $teams")
+            format!(
+                "SELECT *, {LIVE_SONG_LINKS} FROM setlist WHERE owner IN $teams AND trashed_at = NONE"
+            )
         };
         if q_nonempty {
             query.push_str(" AND title @0@ $q ORDER BY score DESC");
@@ -57,7 +59,7 @@ impl SetlistRepository for SurrealSetlistRepo {
         let (offset, limit) = pagination.effective_offset_limit();
         query.push_str(" LIMIT $limit START $start");
 
-        let mut request = db.db.query(query).bind(("teams", read_teams.to_vec()));
+        let mut request = db.query(query).bind(("teams", read_teams.to_vec()));
         if let Some(ref q) = pagination.q
             && !q.trim().is_empty()
         {
@@ -83,7 +85,8 @@ impl SetlistRepository for SurrealSetlistRepo {
             count: u64,
         }
         let q_nonempty = q.is_some_and(|s| !s.trim().is_empty());
-        let mut query = String::from("SELECT count() FROM setlist WHERE owner IN $teams");
+        let mut query =
+            String::from("SELECT count() FROM setlist WHERE owner IN $teams AND trashed_at = NONE");
         if q_nonempty {
             query.push_str(" AND title @0@ $q");
         }
@@ -91,7 +94,6 @@ impl SetlistRepository for SurrealSetlistRepo {
 
         let mut request = self
             .inner()
-            .db
             .query(query)
             .bind(("teams", read_teams.to_vec()));
         if q_nonempty {
@@ -108,9 +110,16 @@ impl SetlistRepository for SurrealSetlistRepo {
 
     async fn get_setlist(&self, read_teams: &[RecordId], id: &str) -> Result<Setlist, AppError> {
         let db = self.inner();
-        let record: Option<SetlistRecord> = db.db.select(resource_id("setlist", id)?).await?;
+        let resource = resource_id("setlist", id)?;
+        let record: Option<SetlistRecord> = db
+            .query(format!("SELECT *, {LIVE_SONG_LINKS} FROM $id"))
+            .bind(("id", RecordId::new(resource.0, resource.1)))
+            .await?
+            .take(0)?;
         match record {
-            Some(r) if belongs_to(&r.owner, read_teams) => Ok(r.into_setlist()),
+            Some(r) if r.trashed_at.is_none() && belongs_to(&r.owner, read_teams) => {
+                Ok(r.into_setlist())
+            }
             _ => Err(AppError::NotFound("setlist not found".into())),
         }
     }
@@ -123,8 +132,7 @@ impl SetlistRepository for SurrealSetlistRepo {
         let db = self.inner();
         let resource = resource_id("setlist", id)?;
         let mut response = db
-            .db
-            .query("SELECT owner, songs FROM setlist WHERE id = $id")
+            .query("SELECT owner, songs, trashed_at FROM setlist WHERE id = $id")
             .bind(("id", RecordId::new(resource.0.clone(), resource.1.clone())))
             .await?;
 
@@ -132,11 +140,11 @@ impl SetlistRepository for SurrealSetlistRepo {
             .take::<Option<SongLinkListRow>>(0)?
             .ok_or_else(|| AppError::NotFound("setlist not found".into()))?;
 
-        if !belongs_to(&record.owner, read_teams) {
+        if record.trashed_at.is_some() || !belongs_to(&record.owner, read_teams) {
             return Err(AppError::NotFound("setlist not found".into()));
         }
 
-        song_links_to_owned(&db.db, record.songs).await
+        song_links_to_owned(db, record.songs).await
     }
 
     async fn create_setlist(
@@ -145,8 +153,7 @@ impl SetlistRepository for SurrealSetlistRepo {
         setlist: CreateSetlist,
     ) -> Result<Setlist, AppError> {
         let db = self.inner();
-        db.db
-            .create("setlist")
+        db.create("setlist")
             .content(SetlistRecord::from_payload(None, Some(owner), setlist))
             .await?
             .map(SetlistRecord::into_setlist)
@@ -166,30 +173,28 @@ impl SetlistRepository for SurrealSetlistRepo {
         let title = setlist.title;
 
         let mut response = if let Some(ref owner_rid) = owner {
-            db.db
-                .query(
-                    "UPDATE type::record($tb, $sid) SET title = $title, songs = $songs, owner = $owner \
-                     WHERE owner IN $teams RETURN AFTER",
-                )
-                .bind(("tb", tb))
-                .bind(("sid", sid))
-                .bind(("title", title))
-                .bind(("songs", songs))
-                .bind(("owner", owner_rid.clone()))
-                .bind(("teams", write_teams.to_vec()))
-                .await?
+            db.query(
+                "UPDATE type::record($tb, $sid) SET title = $title, songs = $songs, owner = $owner \
+                     WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("title", title))
+            .bind(("songs", songs))
+            .bind(("owner", owner_rid.clone()))
+            .bind(("teams", write_teams.to_vec()))
+            .await?
         } else {
-            db.db
-                .query(
-                    "UPDATE type::record($tb, $sid) SET title = $title, songs = $songs \
-                     WHERE owner IN $teams RETURN AFTER",
-                )
-                .bind(("tb", tb))
-                .bind(("sid", sid))
-                .bind(("title", title))
-                .bind(("songs", songs))
-                .bind(("teams", write_teams.to_vec()))
-                .await?
+            db.query(
+                "UPDATE type::record($tb, $sid) SET title = $title, songs = $songs \
+                     WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
+            )
+            .bind(("tb", tb))
+            .bind(("sid", sid))
+            .bind(("title", title))
+            .bind(("songs", songs))
+            .bind(("teams", write_teams.to_vec()))
+            .await?
         };
 
         let rows: Vec<SetlistRecord> = response.take(0)?;
@@ -199,17 +204,21 @@ impl SetlistRepository for SurrealSetlistRepo {
             .ok_or_else(|| AppError::NotFound("setlist not found".into()))
     }
 
-    async fn delete_setlist(
+    async fn trash_setlist(
         &self,
         write_teams: &[RecordId],
+        actor_user_id: &str,
         id: &str,
     ) -> Result<Setlist, AppError> {
         let db = self.inner();
         let (tb, sid) = resource_id("setlist", id)?;
         let mut response = db
-            .db
-            .query("DELETE FROM type::record($tb, $sid) WHERE owner IN $teams RETURN BEFORE")
+            .query(
+                "UPDATE type::record($tb, $sid) SET trashed_at = time::now(), trashed_by = $actor \
+                 WHERE owner IN $teams AND trashed_at = NONE RETURN BEFORE",
+            )
             .bind(("tb", tb))
+            .bind(("actor", RecordId::new("user", actor_user_id.to_owned())))
             .bind(("sid", sid))
             .bind(("teams", write_teams.to_vec()))
             .await?;
@@ -230,9 +239,8 @@ impl SetlistRepository for SurrealSetlistRepo {
         let db = self.inner();
         let (tb, sid) = resource_id("setlist", id)?;
         let mut response = db
-            .db
             .query(
-                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams RETURN AFTER",
+                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
             )
             .bind(("tb", tb))
             .bind(("sid", sid))
@@ -0,0 +1,440 @@
+//! Song data recovered from the OCR text of scanned sheet music.
+//!
+//! The text is rewritten as ChordPro and parsed by chordlib, so a draft is exactly what the song
+//! editor would produce from the same source: chord lines are folded into the lyric line below
+//! them at their column, recognised headings and stanza numbers become `{section: …}`, and a
+//! one-line first paragraph is taken as the title.
+
+use chordlib::inputs::chord_pro;
+use chordlib::types::{Chord, Song as ChordSong};
+
+use crate::error::AppError;
+
+/// Lowercase first words of a line that names a section (`Chorus`, `[Verse 2]`, `Bridge:`).
+const SECTION_KEYWORDS: &[&str] = &[
+    "verse",
+    "chorus",
+    "pre-chorus",
+    "prechorus",
+    "refrain",
+    "bridge",
+    "intro",
+    "outro",
+    "interlude",
+    "instrumental",
+    "tag",
+    "ending",
+    "coda",
+    "strophe",
+    "vers",
+    "zwischenspiel",
+];
+
+/// Chord qualities accepted after the root when deciding whether a token is a chord; anything
+/// else (`Chorus`, `Be`, `Go`) makes the line lyrics.
+const CHORD_SUFFIXES: &[&str] = &[
+    "maj", "min", "dim", "aug", "sus", "add", "m", "M", "+", "°", "ø", "-", "#", "b", "(", ")",
+];
+
+/// Title used when neither the song nor the scan has one; chordlib rejects untitled songs.
+const FALLBACK_TITLE: &str = "Untitled";
+
+enum OcrLine<'a> {
+    Blank,
+    Heading(String),
+    /// Chord symbols with their column (in chars).
+    Chords(Vec<(usize, &'a str)>),
+    Lyrics(&'a str),
+}
+
+fn split_root(token: &str) -> Option<&str> {
+    let rest = token.strip_prefix(|c: char| ('A'..='G').contains(&c))?;
+    Some(rest.strip_prefix(['#', 'b']).unwrap_or(rest))
+}
+
+fn is_chord(token: &str) -> bool {
+    let inner = token
+        .strip_prefix('(')
+        .and_then(|t| t.strip_suffix(')'))
+        .unwrap_or(token);
+    let (body, bass) = match inner.split_once('/') {
+        Some((body, bass)) => (body, Some(bass)),
+        None => (inner, None),
+    };
+    let Some(mut suffix) = split_root(body) else {
+        return false;
+    };
+    while !suffix.is_empty() {
+        let digits = suffix.len()
+            - suffix
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits > 0 {
+            suffix = &suffix[digits..];
+        } else if let Some(quality) = CHORD_SUFFIXES.iter().find(|q| suffix.starts_with(*q)) {
+            suffix = &suffix[quality.len()..];
+        } else {
+            return false;
+        }
+    }
+    bass.is_none_or(|bass| split_root(bass) == Some(""))
+        && Chord::from_str_with_key(token, None).is_ok()
+}
+
+/// Bar lines and similar marks that may sit between chords without making the line lyrics.
+fn is_chord_line_mark(token: &str) -> bool {
+    token
+        .chars()
+        .all(|c| matches!(c, '|' | ':' | '/' | '-' | '%' | '.'))
+}
+
+/// `Chorus`, `[Verse 2]`, `Bridge:` → the section title; at most one short word may follow the
+/// keyword.
+fn heading(line: &str) -> Option<String> {
+    let text = line.trim();
+    let text = text
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .unwrap_or(text)
+        .trim_end_matches(':')
+        .trim();
+    let mut words = text.split_whitespace();
+    let keyword = words.next()?.trim_end_matches('.').to_lowercase();
+    if !SECTION_KEYWORDS.contains(&keyword.as_str()) {
+        return None;
+    }
+    let label: Vec<&str> = words.collect();
+    if label.len() > 1 || label.first().is_some_and(|w| w.chars().count() > 3) {
+        return None;
+    }
+    let mut chars = keyword.chars();
+    let keyword: String = chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default();
+    Some(
+        std::iter::once(keyword.as_str())
+            .chain(label)
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Hymn-style stanza number at the start of a lyric line (`1.`, `2)`): the number and the
+/// byte length of the prefix including the following spaces.
+fn stanza_number(line: &str) -> Option<(&str, usize)> {
+    let start = line.len() - line.trim_start().len();
+    let rest = &line[start..];
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits > 2 {
+        return None;
+    }
+    let after = rest[digits..].strip_prefix(['.', ')'])?;
+    let text = after.trim_start();
+    if text.len() == after.len() || text.is_empty() {
+        return None;
+    }
+    Some((&rest[..digits], line.len() - text.len()))
+}
+
+fn classify(line: &str) -> OcrLine<'_> {
+    if line.trim().is_empty() {
+        return OcrLine::Blank;
+    }
+    if let Some(title) = heading(line) {
+        return OcrLine::Heading(title);
+    }
+    let mut chords = Vec::new();
+    let mut column = 0;
+    let mut rest = line;
+    while let Some(offset) = rest.find(|c: char| !c.is_whitespace()) {
+        column += rest[..offset].chars().count();
+        rest = &rest[offset..];
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let token = &rest[..end];
+        if is_chord(token) {
+            chords.push((column, token));
+        } else if !is_chord_line_mark(token) {
+            return OcrLine::Lyrics(line);
+        }
+        column += token.chars().count();
+        rest = &rest[end..];
+    }
+    if chords.is_empty() {
+        OcrLine::Lyrics(line)
+    } else {
+        OcrLine::Chords(chords)
+    }
+}
+
+/// Keeps OCR noise from being read as ChordPro markup; one char per char so columns still line
+/// up with the chord line above.
+fn escape_lyrics(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '[' | '{' => '(',
+            ']' | '}' => ')',
+            c => c,
+        })
+        .collect()
+}
+
+/// `[G]Amazing [C]grace` from chords at their columns over `lyrics`.
+fn merge_chords(chords: &[(usize, &str)], lyrics: &str) -> String {
+    let mut chars: Vec<String> = lyrics.chars().map(String::from).collect();
+    let width = chords.last().map_or(0, |(column, _)| column + 1);
+    if chars.len() < width {
+        chars.resize(width, " ".to_owned());
+    }
+    for (column, chord) in chords.iter().rev() {
+        chars.insert(*column, format!("[{chord}]"));
+    }
+    chars.concat()
+}
+
+fn push_section(out: &mut String, title: &str) {
+    out.push_str(&format!("{{section: {title}}}\n"));
+}
+
+/// ChordPro source of a draft from the OCR texts of a song's blobs, in page order. A stanza
+/// without heading or number becomes `Verse <n>`; pages continue the section they break into.
+fn chord_pro_source(pages: &[&str], title: &str, key: Option<&str>) -> String {
+    let mut out = format!("{{title: {title}}}\n{{key: {}}}\n", key.unwrap_or("C"));
+    let mut unnamed = 0;
+    // A heading opens a section; a blank line after its first line closes it.
+    let mut open = false;
+    let mut has_lines = false;
+    for page in pages {
+        let lines: Vec<&str> = page.lines().collect();
+        let mut i = 0;
+        while i < lines.len() {
+            let line = classify(lines[i]);
+            i += 1;
+            let (text, number) = match line {
+                OcrLine::Blank => {
+                    open &= !has_lines;
+                    continue;
+                }
+                OcrLine::Heading(title) => {
+                    push_section(&mut out, &title);
+                    (open, has_lines) = (true, false);
+                    continue;
+                }
+                OcrLine::Chords(chords) => match lines.get(i).copied().map(classify) {
+                    Some(OcrLine::Lyrics(lyrics)) => {
+                        i += 1;
+                        let (lyrics, number) = strip_stanza_number(lyrics);
+                        (merge_chords(&chords, &escape_lyrics(&lyrics)), number)
+                    }
+                    _ => (
+                        chords
+                            .iter()
+                            .map(|(_, chord)| format!("[{chord}]"))
+                            .collect(),
+                        None,
+                    ),
+                },
+                OcrLine::Lyrics(lyrics) => {
+                    let (lyrics, number) = strip_stanza_number(lyrics);
+                    (escape_lyrics(&lyrics), number)
+                }
+            };
+            if let Some(number) = number {
+                push_section(&mut out, &format!("Verse {number}"));
+                open = true;
+            } else if !open {
+                unnamed += 1;
+                push_section(&mut out, &format!("Verse {unnamed}"));
+                open = true;
+            }
+            let text = text.trim();
+            // A leading `&` marks a translation line in ChordPro.
+            match text.strip_prefix('&') {
+                Some(rest) => out.push_str(&format!("and{rest}")),
+                None => out.push_str(text),
+            }
+            out.push('\n');
+            has_lines = true;
+        }
+    }
+    out
+}
+
+/// Blanks out a leading stanza number so chord columns still match; the number is returned.
+fn strip_stanza_number(lyrics: &str) -> (String, Option<String>) {
+    match stanza_number(lyrics) {
+        Some((number, prefix)) => (
+            format!("{}{}", " ".repeat(prefix), &lyrics[prefix..]),
+            Some(number.to_owned()),
+        ),
+        None => (lyrics.to_owned(), None),
+    }
+}
+
+/// Splits a one-line first paragraph off the first page as the song title.
+fn split_title(first_page: &str) -> (Option<&str>, &str) {
+    let trimmed = first_page.trim_start_matches(['\n', '\r']);
+    let Some((first, rest)) = trimmed.split_once('\n') else {
+        return (None, first_page);
+    };
+    let is_title = rest.trim_start_matches([' ', '\t']).starts_with('\n')
+        && !rest.trim().is_empty()
+        && stanza_number(first).is_none()
+        && matches!(classify(first), OcrLine::Lyrics(_));
+    if is_title {
+        (Some(first.trim()), rest)
+    } else {
+        (None, first_page)
+    }
+}
+
+/// Song data for `current` with sections read from `pages`, the OCR texts of its blobs in order.
+/// Metadata other than `sections` and `key` is kept; `titles` is filled from the scan only when
+/// the song has none, and `key` only changes when the scan shows chords (the first one's root).
+pub(crate) fn draft_from_ocr(pages: &[&str], current: &ChordSong) -> Result<ChordSong, AppError> {
+    let mut pages = pages.to_vec();
+    let scanned_title = match pages.first_mut() {
+        Some(first) => {
+            let (title, rest) = split_title(first);
+            *first = rest;
+            title
+        }
+        None => None,
+    };
+    let current_title = current.titles.iter().find(|t| !t.trim().is_empty());
+    let title = current_title
+        .map(String::as_str)
+        .or(scanned_title)
+        .unwrap_or(FALLBACK_TITLE);
+    let first_chord =
+        pages
+            .iter()
+            .flat_map(|page| page.lines())
+            .find_map(|line| match classify(line) {
+                OcrLine::Chords(chords) => chords.first().map(|(_, chord)| *chord),
+                _ => None,
+            });
+    let key = first_chord.map(|chord| {
+        let chord = chord.trim_start_matches('(');
+        let rest = split_root(chord).unwrap_or_default();
+        &chord[..chord.len() - rest.len()]
+    });
+    // Titles are single-line directives; stray braces would end them early.
+    let source = chord_pro_source(&pages, &escape_lyrics(title), key);
+    let parsed = chord_pro::load_string(&source)
+        .map_err(|e| AppError::Internal(format!("OCR draft is not valid ChordPro: {e}")))?;
+    let mut draft = current.clone();
+    draft.sections = parsed.sections;
+    if current_title.is_none() {
+        draft.titles = parsed.titles;
+    }
+    if key.is_some() {
+        draft.key = parsed.key;
+    }
+    Ok(draft)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section_text(song: &ChordSong, index: usize) -> Vec<String> {
+        song.sections[index]
+            .lines
+            .iter()
+            .map(|line| {
+                line.parts
+                    .iter()
+                    .map(|part| part.languages.first().cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn chords(song: &ChordSong, index: usize) -> Vec<usize> {
+        song.sections[index]
+            .lines
+            .iter()
+            .map(|line| line.parts.iter().filter(|p| p.chord.is_some()).count())
+            .collect()
+    }
+
+    #[test]
+    fn chord_tokens_need_a_chord_shape() {
+        for chord in [
+            "G", "Am", "F#m7", "Bb", "Cmaj7", "Dsus4", "C/E", "(Em)", "Gadd9",
+        ] {
+            assert!(is_chord(chord), "{chord}");
+        }
+        for word in ["Chorus", "Be", "Go", "Add", "Am.", "I", "A,"] {
+            assert!(!is_chord(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn detects_headings() {
+        assert_eq!(heading("[Verse 2]").as_deref(), Some("Verse 2"));
+        assert_eq!(heading("CHORUS:").as_deref(), Some("Chorus"));
+        assert_eq!(heading("  Bridge").as_deref(), Some("Bridge"));
+        assert_eq!(heading("Chorus of angels singing"), None);
+        assert_eq!(heading("Amazing grace"), None);
+    }
+
+    #[test]
+    fn folds_chords_into_lyrics_and_numbers_stanzas() {
+        let text = [
+            "Amazing Grace",
+            "",
+            "    G              C             G",
+            "1.  Amazing grace, how sweet the sound",
+            "That saved a wretch like me",
+            "",
+            "Chorus",
+            "D     G",
+            "My chains are gone",
+            "G  C | D",
+        ]
+        .join("\n");
+        let song = draft_from_ocr(&[&text], &ChordSong::default()).unwrap();
+        assert_eq!(song.titles, ["Amazing Grace"]);
+        let titles: Vec<&str> = song.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Verse 1", "Chorus"]);
+        assert_eq!(
+            section_text(&song, 0).concat(),
+            "Amazing grace, how sweet the soundThat saved a wretch like me"
+        );
+        assert_eq!(chords(&song, 0), [3, 0]);
+        let first_line: Vec<String> = song.sections[0].lines[0]
+            .parts
+            .iter()
+            .map(|part| part.languages[0].clone())
+            .collect();
+        assert_eq!(first_line, ["Amazing grace, ", "how sweet the ", "sound"]);
+        assert_eq!(chords(&song, 1), [2, 3]);
+        assert!(song.key.is_some());
+    }
+
+    #[test]
+    fn keeps_existing_metadata_and_numbers_unnamed_stanzas() {
+        let current = ChordSong {
+            titles: vec!["Known".into()],
+            artists: vec!["Someone".into()],
+            ..ChordSong::default()
+        };
+        let song = draft_from_ocr(
+            &[
+                "first [stanza]\nline two\n\n\nsecond stanza",
+                "still second",
+            ],
+            &current,
+        )
+        .unwrap();
+        assert_eq!(song.titles, ["Known"]);
+        assert_eq!(song.artists, ["Someone"]);
+        assert_eq!(song.key, None);
+        let titles: Vec<&str> = song.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Verse 1", "Verse 2"]);
+        assert_eq!(section_text(&song, 0)[0], "first (stanza)");
+        assert_eq!(section_text(&song, 1), ["second stanza", "still second"]);
+    }
+}
@@ -25,7 +25,6 @@ impl LikedSongIds for Arc<Database> {
     async fn liked_song_ids(&self, user_id: &str) -> Result<HashSet<String>, AppError> {
         let owner = RecordId::new("user", user_id);
         let mut response = self
-            .db
             .query("SELECT * FROM like WHERE owner = $owner")
             .bind(("owner", owner))
             .await?;
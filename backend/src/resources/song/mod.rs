@@ -1,5 +1,6 @@
 pub use shared::song::{CreateSong, PatchSong, PatchSongData, Song, UpdateSong};
 
+mod draft;
 mod liked;
 mod model;
 mod repository;
@@ -9,7 +10,7 @@ mod surreal_repo;
 pub use liked::LikedSongIds;
 pub use model::SongRecord;
 pub use repository::{SongRepository, SongUpsertOutcome};
-pub use service::{SongService, SongServiceHandle};
+pub use service::{SongService, SongServiceHandle, song_etag};
 pub use surreal_repo::SurrealSongRepo;
 
 pub mod rest;
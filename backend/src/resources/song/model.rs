@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use surrealdb::types::{Kind, RecordId, SurrealValue, Value, kind};
+use surrealdb::types::{Datetime, Kind, RecordId, SurrealValue, Value, kind};
 
 use chordlib::types::Song as SongData;
 use shared::blob::BlobLink;
@@ -81,6 +81,9 @@ pub struct SongRecord {
     pub data: SongDataField,
     #[serde(default)]
     pub search_content: String,
+    /// Set while the song is in its team's trash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trashed_at: Option<Datetime>,
 }
 
 impl SongRecord {
@@ -116,6 +119,7 @@ impl SongRecord {
             blobs: blobs.into_iter().map(|blob| blob_thing(&blob.id)).collect(),
             data: SongDataField(data),
             search_content,
+            trashed_at: None,
         }
     }
 }
@@ -171,6 +175,7 @@ mod tests {
             blobs: vec![RecordId::new("blob", "b1")],
             data: SongDataField(SongData::default()),
             search_content: String::new(),
+            trashed_at: None,
         };
         let song = record.into_song();
         assert_eq!(song.id, "s1");
@@ -59,7 +59,13 @@ pub trait SongRepository: Send + Sync {
         owner: Option<RecordId>,
     ) -> Result<SongUpsertOutcome, AppError>;
 
-    async fn delete_song(&self, write_teams: &[RecordId], id: &str) -> Result<Song, AppError>;
+    /// Moves the song into its team's trash (see the trash service for restore and purge).
+    async fn trash_song(
+        &self,
+        write_teams: &[RecordId],
+        actor_user_id: &str,
+        id: &str,
+    ) -> Result<Song, AppError>;
 
     async fn move_song_owner(
         &self,
@@ -1,6 +1,6 @@
 use actix_web::http::header;
 use actix_web::{
-    HttpRequest, HttpResponse, Scope, delete, get, patch, post, put,
+    HttpRequest, HttpResponse, HttpResponseBuilder, Scope, delete, get, patch, post, put,
     web::{self, Data, Json, Path, Query, ReqData},
 };
 
@@ -8,20 +8,22 @@ use crate::accept::accepts_worship_player_json;
 #[allow(unused_imports)]
 use crate::docs::Problem;
 use crate::error::AppError;
-use crate::http_cache::{check_if_match, if_none_match_matches, weak_etag_json};
+use crate::http_cache::{check_if_match, if_none_match_matches};
 use crate::resources::User;
+use crate::resources::blob::service::BlobServiceHandle;
 use crate::resources::song::PatchSong;
-#[allow(unused_imports)]
-use crate::resources::song::Song;
 use crate::resources::song::SongUpsertOutcome;
 use crate::resources::song::service::SongServiceHandle;
 use crate::resources::song::{CreateSong, UpdateSong};
+use crate::resources::song::{Song, song_etag};
 use crate::resources::team::UserPermissions;
 use shared::MoveOwner;
 use shared::api::{PAGE_SIZE_DEFAULT, SongListQuery};
 use shared::like::LikeStatus;
 #[allow(unused_imports)]
 use shared::player::Player;
+#[allow(unused_imports)]
+use shared::song::SongDraft;
 
 pub fn scope() -> Scope {
     web::scope("/songs")
@@ -32,12 +34,20 @@ pub fn scope() -> Scope {
         .service(update_song)
         .service(patch_song)
         .service(move_song)
+        .service(draft_song_from_blobs)
         .service(delete_song)
         .service(get_song_like_status)
         .service(put_song_like)
         .service(delete_song_like)
 }
 
+/// JSON response carrying the song's [`song_etag`], the value its caller's next `If-Match` is
+/// checked against.
+fn song_response(mut response: HttpResponseBuilder, song: &Song) -> Result<HttpResponse, AppError> {
+    let etag = song_etag(song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
+    Ok(response.insert_header((header::ETAG, etag)).json(song))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/songs",
@@ -127,7 +137,7 @@ async fn get_song(
 ) -> Result<HttpResponse, AppError> {
     let perms = UserPermissions::from_ref(&user, &svc.teams);
     let song = svc.get_song_for_user(&perms, &id).await?;
-    let etag = weak_etag_json(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
+    let etag = song_etag(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
     if if_none_match_matches(&req, &etag) {
         return Ok(HttpResponse::NotModified()
             .insert_header((header::ETAG, etag))
@@ -180,7 +190,7 @@ async fn get_song_player(
     path = "/api/v1/songs",
     request_body = CreateSong,
     responses(
-        (status = 201, description = "Create a new song. Optional `owner` is a team id; omit for the caller's personal team. When the effective target is the personal team, default-collection behavior may apply (BLC-SONG-010). When `owner` names a different team the song is created there without that default-collection side effect. Library edit access is required on the target team.", body = Song),
+        (status = 201, description = "Create a new song. Optional `owner` is a team id; omit for the caller's personal team. When the effective target is the personal team, default-collection behavior may apply (BLC-SONG-010). When `owner` names a different team the song is created there without that default-collection side effect. Library edit access is required on the target team. Response includes the weak `ETag`.", body = Song),
         (status = 400, description = "Invalid song payload", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 404, description = "Target team not found or caller cannot edit that team's library", body = Problem, content_type = "application/problem+json"),
@@ -202,7 +212,8 @@ async fn create_song(
     let perms = UserPermissions::from_ref(&user, &svc.teams);
     let payload = payload.into_inner();
     payload.validate().map_err(AppError::invalid_request)?;
-    Ok(HttpResponse::Created().json(svc.create_song_for_user(&perms, payload).await?))
+    let song = svc.create_song_for_user(&perms, payload).await?;
+    song_response(HttpResponse::Created(), &song)
 }
 
 #[utoipa::path(
@@ -213,8 +224,8 @@ async fn create_song(
     ),
     request_body = UpdateSong,
     responses(
-        (status = 200, description = "Updated an existing song. Upsert: if the id did not exist, responds **201** with `Location` (see BLC / `http-contract.md`).", body = Song),
-        (status = 201, description = "Created the song via PUT upsert (new id). Response includes `Location: /api/v1/songs/{id}`.", body = Song),
+        (status = 200, description = "Updated an existing song. Upsert: if the id did not exist, responds **201** with `Location` (see BLC / `http-contract.md`). Response includes the new weak `ETag`.", body = Song),
+        (status = 201, description = "Created the song via PUT upsert (new id). Response includes `Location: /api/v1/songs/{id}` and the weak `ETag`.", body = Song),
         (status = 400, description = "Invalid song identifier", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -244,22 +255,24 @@ async fn update_song(
     let id = id.into_inner();
     match svc.get_song_for_user(&perms, &id).await {
         Ok(song) => {
-            let etag =
-                weak_etag_json(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
+            let etag = song_etag(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
             check_if_match(&req, &etag)?;
         }
         Err(AppError::NotFound(_)) => {}
         Err(e) => return Err(e),
     }
-    match svc
+    let (response, song) = match svc
         .update_song_for_user(&perms, &id, payload, owner)
         .await?
     {
-        SongUpsertOutcome::Created(song) => Ok(HttpResponse::Created()
-            .insert_header((header::LOCATION, format!("/api/v1/songs/{}", song.id)))
-            .json(song)),
-        SongUpsertOutcome::Updated(song) => Ok(HttpResponse::Ok().json(song)),
-    }
+        SongUpsertOutcome::Created(song) => {
+            let mut response = HttpResponse::Created();
+            response.insert_header((header::LOCATION, format!("/api/v1/songs/{}", song.id)));
+            (response, song)
+        }
+        SongUpsertOutcome::Updated(song) => (HttpResponse::Ok(), song),
+    };
+    song_response(response, &song)
 }
 
 #[utoipa::path(
@@ -270,7 +283,7 @@ async fn update_song(
     ),
     request_body = PatchSong,
     responses(
-        (status = 200, description = "Partially update an existing song", body = Song),
+        (status = 200, description = "Partially update an existing song. Response includes the new weak `ETag`.", body = Song),
         (status = 400, description = "Invalid song identifier or payload", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -295,12 +308,12 @@ async fn patch_song(
     let perms = UserPermissions::from_ref(&user, &svc.teams);
     let id = id.into_inner();
     let song = svc.get_song_for_user(&perms, &id).await?;
-    let etag = weak_etag_json(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
+    let etag = song_etag(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
     check_if_match(&req, &etag)?;
-    Ok(HttpResponse::Ok().json(
-        svc.patch_song_for_user(&perms, &id, payload.into_inner())
-            .await?,
-    ))
+    let song = svc
+        .patch_song_for_user(&perms, &id, payload.into_inner())
+        .await?;
+    song_response(HttpResponse::Ok(), &song)
 }
 
 #[utoipa::path(
@@ -338,6 +351,41 @@ async fn move_song(
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/songs/{id}/draft-from-blobs",
+    params(
+        ("id" = String, Path, description = "Song identifier")
+    ),
+    responses(
+        (status = 200, description = "Song data drafted from the OCR text of the song's blobs (BLC-SONG-024): chord lines above lyrics become inline chords, headings and stanza numbers become sections. Nothing is saved; review the draft in the editor and save it with `PUT /api/v1/songs/{id}`.", body = SongDraft),
+        (status = 400, description = "Invalid song identifier", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Song not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "None of the song's blobs has OCR text yet", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to draft the song", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Songs",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/{id}/draft-from-blobs")]
+async fn draft_song_from_blobs(
+    svc: Data<SongServiceHandle>,
+    blob_svc: Data<BlobServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let perms = UserPermissions::from_ref(&user, &svc.teams);
+    Ok(HttpResponse::Ok().json(
+        svc.draft_song_from_blobs_for_user(blob_svc.get_ref(), &perms, &id)
+            .await?,
+    ))
+}
+
 #[utoipa::path(
     delete,
     path = "/api/v1/songs/{id}",
@@ -345,7 +393,7 @@ async fn move_song(
         ("id" = String, Path, description = "Song identifier")
     ),
     responses(
-        (status = 204, description = "Song deleted"),
+        (status = 204, description = "Song moved to its team's trash"),
         (status = 400, description = "Invalid song identifier", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -369,7 +417,7 @@ async fn delete_song(
     let perms = UserPermissions::from_ref(&user, &svc.teams);
     let id = id.into_inner();
     let song = svc.get_song_for_user(&perms, &id).await?;
-    let etag = weak_etag_json(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
+    let etag = song_etag(&song).map_err(|e| AppError::internal_from_err("song.rest", e))?;
     check_if_match(&req, &etag)?;
     svc.delete_song_for_user(&perms, &id).await?;
     Ok(HttpResponse::NoContent().finish())
@@ -9,13 +9,19 @@ use shared::patch::Patch;
 use shared::player::Player;
 use shared::song::{
     CreateSong, Link as SongLink, LinkOwned as SongLinkOwned, PatchSong, PatchSongData, Song,
+    SongDraft,
 };
+use shared::team::{ActivityAction, ActivityResourceType, TeamPermission};
 
 use crate::database::Database;
 use crate::error::AppError;
+use crate::http_cache::weak_etag_json;
+use crate::resources::blob::service::BlobServiceHandle;
 use crate::resources::collection::CollectionRepository;
 use crate::resources::common::resolve_owner_team;
 
+use crate::resources::team::activity::{ActivityRecorder, NewTeamActivity};
+use crate::resources::team::webhook::ContentEventRecorder;
 use crate::resources::team::{
     TeamResolver, UserPermissions, parse_owner_record_id, thing_record_key,
 };
@@ -59,33 +65,69 @@ impl UserCollectionUpdater for Arc<SurrealUserRepo> {
 
 /// Application service: team resolution, authorization, and orchestration for songs.
 #[derive(Clone)]
-pub struct SongService<R, T, L, C, U> {
+pub struct SongService<R, T, L, C, U, A> {
     pub repo: R,
     pub teams: Arc<T>,
     pub likes: L,
     pub collections: C,
     pub user_updater: U,
+    pub activity: A,
 }
 
-impl<R, T, L, C, U> SongService<R, T, L, C, U> {
-    pub fn new(repo: R, teams: Arc<T>, likes: L, collections: C, user_updater: U) -> Self {
+impl<R, T, L, C, U, A> SongService<R, T, L, C, U, A> {
+    pub fn new(
+        repo: R,
+        teams: Arc<T>,
+        likes: L,
+        collections: C,
+        user_updater: U,
+        activity: A,
+    ) -> Self {
         Self {
             repo,
             teams,
             likes,
             collections,
             user_updater,
+            activity,
         }
     }
 }
 
+/// `song` as every reader sees it, without the caller's `user_specific_addons`.
+fn user_neutral(song: &Song) -> Song {
+    Song {
+        user_specific_addons: Default::default(),
+        ..song.clone()
+    }
+}
+
+/// Weak ETag of a song. It leaves out the caller's `liked`, so every reader, the change stream
+/// and `If-Match` checks agree on it, and liking a song does not change it.
+pub fn song_etag(song: &Song) -> Result<String, serde_json::Error> {
+    weak_etag_json(&user_neutral(song))
+}
+
+fn song_activity(actor_user_id: &str, song: &Song, action: ActivityAction) -> NewTeamActivity {
+    NewTeamActivity::new(
+        &song.owner,
+        actor_user_id,
+        ActivityResourceType::Song,
+        &song.id,
+        action,
+    )
+    .with_title(song.data.titles.first().map(String::as_str))
+    .with_etag(&user_neutral(song))
+}
+
 impl<
     R: SongRepository,
     T: TeamResolver,
     L: LikedSongIds,
     C: CollectionRepository,
     U: UserCollectionUpdater,
-> SongService<R, T, L, C, U>
+    A: ActivityRecorder,
+> SongService<R, T, L, C, U, A>
 {
     fn merge_song_data(
         mut current: chordlib::types::Song,
@@ -208,13 +250,22 @@ impl<
             None => (personal.clone(), true),
             Some(ref s) => {
                 let rid = parse_owner_record_id(s)?;
-                perms.require_write_access_to_owner(&rid).await?;
+                perms
+                    .require_permission_on_owner(&rid, TeamPermission::SongsWrite)
+                    .await?;
                 let same_as_personal = thing_record_key(&rid) == thing_record_key(&personal);
                 (rid, same_as_personal)
             }
         };
 
         let created = self.repo.create_song(owner, song).await?;
+        self.activity
+            .record_activity_or_warn(song_activity(
+                &perms.user().id,
+                &created,
+                ActivityAction::Created,
+            ))
+            .await;
 
         if !use_default_collection_flow {
             return Ok(created);
@@ -223,12 +274,12 @@ impl<
         let mut default_id = perms.user().default_collection.clone();
 
         loop {
-            let write_teams = perms.write_teams().await?;
+            let collection_teams = perms.teams_with(TeamPermission::CollectionsWrite).await?;
             if let Some(collection_id) = default_id.as_ref() {
                 match self
                     .collections
                     .add_song_to_collection(
-                        write_teams,
+                        &collection_teams,
                         collection_id,
                         SongLink {
                             id: created.id.clone(),
@@ -264,6 +315,18 @@ impl<
                         },
                     )
                     .await?;
+                self.activity
+                    .record_activity_or_warn(
+                        NewTeamActivity::new(
+                            &collection.owner,
+                            &perms.user().id,
+                            ActivityResourceType::Collection,
+                            &collection.id,
+                            ActivityAction::Created,
+                        )
+                        .with_title(Some(&collection.title)),
+                    )
+                    .await;
                 self.user_updater
                     .set_default_collection(&perms.user().id, &collection.id)
                     .await?;
@@ -282,11 +345,26 @@ impl<
         song: CreateSong,
         owner: Option<String>,
     ) -> Result<SongUpsertOutcome, AppError> {
-        let write_teams = perms.write_teams().await?;
-        let owner = resolve_owner_team(write_teams, owner)?;
-        self.repo
-            .update_song(write_teams, &perms.user().id, id, song, owner)
-            .await
+        let write_teams = perms.teams_with(TeamPermission::SongsWrite).await?;
+        let owner = resolve_owner_team(&write_teams, owner)?;
+        let mut outcome = self
+            .repo
+            .update_song(&write_teams, &perms.user().id, id, song, owner)
+            .await?;
+        // Same body as a GET, so the response ETag is the one the next `If-Match` is checked against.
+        let liked = self.likes.liked_song_ids(&perms.user().id).await?;
+        let (SongUpsertOutcome::Created(s) | SongUpsertOutcome::Updated(s)) = &mut outcome;
+        s.user_specific_addons.liked = liked.contains(&s.id);
+        let entry = match &outcome {
+            SongUpsertOutcome::Created(s) => {
+                song_activity(&perms.user().id, s, ActivityAction::Created)
+            }
+            SongUpsertOutcome::Updated(s) => {
+                song_activity(&perms.user().id, s, ActivityAction::Updated)
+            }
+        };
+        self.activity.record_activity_or_warn(entry).await;
+        Ok(outcome)
     }
 
     #[instrument(level = "debug", err, skip(self, perms, patch))]
@@ -325,10 +403,28 @@ impl<
         if thing_record_key(&current) == thing_record_key(&dest) {
             return Ok(song);
         }
-        perms.require_write_access_to_owner(&current).await?;
-        perms.require_write_access_to_owner(&dest).await?;
-        let write_teams = perms.write_teams().await?;
-        self.repo.move_song_owner(write_teams, id, dest).await
+        perms
+            .require_permission_on_owner(&current, TeamPermission::SongsDelete)
+            .await?;
+        perms
+            .require_permission_on_owner(&dest, TeamPermission::SongsWrite)
+            .await?;
+        let source_teams = perms.teams_with(TeamPermission::SongsDelete).await?;
+        let moved = self.repo.move_song_owner(&source_teams, id, dest).await?;
+        self.activity
+            .record_activity_or_warn(
+                song_activity(&perms.user().id, &song, ActivityAction::Moved)
+                    .with_other_team(&moved.owner)
+                    .with_etag(&user_neutral(&moved)),
+            )
+            .await;
+        self.activity
+            .record_activity_or_warn(
+                song_activity(&perms.user().id, &moved, ActivityAction::Moved)
+                    .with_other_team(&song.owner),
+            )
+            .await;
+        Ok(moved)
     }
 
     #[instrument(level = "debug", err, skip(self, perms))]
@@ -337,8 +433,19 @@ impl<
         perms: &UserPermissions<T>,
         id: &str,
     ) -> Result<Song, AppError> {
-        let write_teams = perms.write_teams().await?;
-        self.repo.delete_song(write_teams, id).await
+        let delete_teams = perms.teams_with(TeamPermission::SongsDelete).await?;
+        let deleted = self
+            .repo
+            .trash_song(&delete_teams, &perms.user().id, id)
+            .await?;
+        self.activity
+            .record_activity_or_warn(song_activity(
+                &perms.user().id,
+                &deleted,
+                ActivityAction::Deleted,
+            ))
+            .await;
+        Ok(deleted)
     }
 
     #[instrument(level = "debug", err, skip(self, perms))]
@@ -378,6 +485,7 @@ pub type SongServiceHandle = SongService<
     Arc<Database>,
     crate::resources::collection::SurrealCollectionRepo,
     Arc<SurrealUserRepo>,
+    ContentEventRecorder,
 >;
 
 impl SongServiceHandle {
@@ -398,8 +506,45 @@ impl SongServiceHandle {
             db.clone(),
             crate::resources::collection::SurrealCollectionRepo::new(db.clone()),
             Arc::new(SurrealUserRepo::new(db.clone())),
+            ContentEventRecorder::build(db.clone()),
         )
     }
+
+    /// Draft song data from the OCR text of the song's blobs (BLC-SONG-024). Linked blobs the
+    /// caller cannot read, or that have no text yet, are reported as skipped.
+    #[instrument(level = "debug", err, skip(self, blob_svc, perms))]
+    pub async fn draft_song_from_blobs_for_user(
+        &self,
+        blob_svc: &BlobServiceHandle,
+        perms: &UserPermissions<crate::resources::team::SurrealTeamResolver>,
+        id: &str,
+    ) -> Result<SongDraft, AppError> {
+        let song = self.get_song_for_user(perms, id).await?;
+        let mut texts = Vec::new();
+        let mut blobs = Vec::new();
+        let mut skipped_blobs = Vec::new();
+        for link in &song.blobs {
+            match blob_svc.get_blob_for_user(perms, &link.id).await {
+                Ok(blob) if !blob.ocr.trim().is_empty() => {
+                    texts.push(blob.ocr);
+                    blobs.push(link.id.clone());
+                }
+                Ok(_) | Err(AppError::NotFound(_)) => skipped_blobs.push(link.id.clone()),
+                Err(e) => return Err(e),
+            }
+        }
+        if texts.is_empty() {
+            return Err(AppError::conflict(
+                "none of the song's blobs has OCR text yet",
+            ));
+        }
+        let pages: Vec<&str> = texts.iter().map(String::as_str).collect();
+        Ok(SongDraft {
+            data: super::draft::draft_from_ocr(&pages, &song.data)?,
+            blobs,
+            skipped_blobs,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -724,6 +869,153 @@ mod tests {
         assert_eq!(results[0].data.artists, vec!["UniqueArtistZZZ"]);
     }
 
+    /// BLC-SONG-023: `q` also matches the OCR text of a song's blobs.
+    #[tokio::test]
+    async fn blc_song_023_search_matches_blob_ocr() {
+        use shared::blob::{CreateBlob, FileType};
+
+        use crate::test_helpers::blob_service;
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let (db, owner, _cm, _guest, _nm, _tid) = four_user_song_fixture().await;
+        let svc = SongServiceHandle::build(db.clone());
+        let blobs = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let owner_p = UserPermissions::from_ref(&owner, &svc.teams);
+        let scan = blobs
+            .create_blob_for_user(
+                &UserPermissions::from_ref(&owner, &blobs.teams),
+                CreateBlob {
+                    owner: None,
+                    file_type: FileType::PNG,
+                    width: 1,
+                    height: 1,
+                    ocr: "Amazing grace how sweet the sound\nthat saved a wretch".into(),
+                },
+            )
+            .await
+            .expect("blob");
+
+        let mut data = crate::test_helpers::minimal_song_data();
+        data.titles = vec!["Scanned Hymn".into()];
+        let scanned = svc
+            .create_song_for_user(
+                &owner_p,
+                CreateSong {
+                    owner: None,
+                    not_a_song: false,
+                    blobs: vec![BlobLink {
+                        id: scan.id.clone(),
+                    }],
+                    data,
+                },
+            )
+            .await
+            .expect("scanned song");
+        create_song_with_title(&db, &owner, "Typed Song")
+            .await
+            .expect("typed song");
+
+        let results = svc
+            .list_songs_for_user(&owner_p, ListQuery::new().with_q("wretch").into())
+            .await
+            .expect("search");
+        assert_eq!(
+            results.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            [scanned.id.as_str()]
+        );
+        assert_eq!(
+            svc.count_songs_for_user(&owner_p, &ListQuery::new().with_q("wretch").into())
+                .await
+                .expect("count"),
+            1
+        );
+    }
+
+    /// BLC-SONG-024: a draft is built from the blobs with OCR text; the rest are skipped and
+    /// nothing is saved.
+    #[tokio::test]
+    async fn blc_song_024_draft_from_blob_ocr() {
+        use shared::blob::{CreateBlob, FileType};
+
+        use crate::test_helpers::blob_service;
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let (db, owner, _cm, _guest, _nm, _tid) = four_user_song_fixture().await;
+        let svc = SongServiceHandle::build(db.clone());
+        let blobs = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let blob_p = UserPermissions::from_ref(&owner, &blobs.teams);
+        let owner_p = UserPermissions::from_ref(&owner, &svc.teams);
+        let mut scans = Vec::new();
+        for ocr in ["Chorus\nG        D\nMy chains are gone", ""] {
+            let blob = blobs
+                .create_blob_for_user(
+                    &blob_p,
+                    CreateBlob {
+                        owner: None,
+                        file_type: FileType::PNG,
+                        width: 1,
+                        height: 1,
+                        ocr: ocr.into(),
+                    },
+                )
+                .await
+                .expect("blob");
+            scans.push(blob.id);
+        }
+        let mut data = crate::test_helpers::minimal_song_data();
+        data.titles = vec!["Scanned Hymn".into()];
+        let song = svc
+            .create_song_for_user(
+                &owner_p,
+                CreateSong {
+                    owner: None,
+                    not_a_song: false,
+                    blobs: scans.iter().map(|id| BlobLink { id: id.clone() }).collect(),
+                    data,
+                },
+            )
+            .await
+            .expect("song");
+
+        let draft = svc
+            .draft_song_from_blobs_for_user(&blobs, &owner_p, &song.id)
+            .await
+            .expect("draft");
+        assert_eq!(draft.blobs, [scans[0].clone()]);
+        assert_eq!(draft.skipped_blobs, [scans[1].clone()]);
+        assert_eq!(draft.data.titles, ["Scanned Hymn"]);
+        assert_eq!(draft.data.sections.len(), 1);
+        assert_eq!(draft.data.sections[0].title, "Chorus");
+        let stored = svc
+            .get_song_for_user(&owner_p, &song.id)
+            .await
+            .expect("get");
+        assert_eq!(stored.data, song.data, "drafting must not save");
+
+        let text_less = svc
+            .create_song_for_user(
+                &owner_p,
+                CreateSong {
+                    owner: None,
+                    not_a_song: false,
+                    blobs: vec![BlobLink {
+                        id: scans[1].clone(),
+                    }],
+                    data: crate::test_helpers::minimal_song_data(),
+                },
+            )
+            .await
+            .expect("song");
+        let err = svc
+            .draft_song_from_blobs_for_user(&blobs, &owner_p, &text_less.id)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, crate::error::AppError::Conflict(_)),
+            "{err:?}"
+        );
+    }
+
     /// BLC-SONG-012: GET song includes `liked: true` when the caller has liked it.
     #[tokio::test]
     async fn blc_song_012_liked_true_when_liked() {
@@ -71,6 +71,16 @@ const FULLTEXT_FRAGMENTS: &[&str] = &[
 
 const FULLTEXT_WEIGHTS: &[f64] = &[100.0, 10.0, 1.0];
 
+/// Weight of OCR text of a song's blobs; scans count like typed lyrics.
+const BLOB_OCR_WEIGHT: f64 = 1.0;
+
+#[derive(Deserialize, SurrealValue)]
+struct SongBlobsRow {
+    id: Option<RecordId>,
+    #[serde(default)]
+    blobs: Vec<RecordId>,
+}
+
 async fn song_fulltext_combined_scores(
     db: &Database,
     read_teams: &[RecordId],
@@ -81,10 +91,9 @@ async fn song_fulltext_combined_scores(
     let mut scores: HashMap<String, f64> = HashMap::new();
     for (&fragment, &weight) in FULLTEXT_FRAGMENTS.iter().zip(FULLTEXT_WEIGHTS.iter()) {
         let sql = format!(
-            "SELECT id, (search::score(0) ?? 0) AS rel_score FROM song WHERE owner IN $teams{extra_where} AND {fragment}",
+            "SELECT id, (search::score(0) ?? 0) AS rel_score FROM song WHERE owner IN $teams AND trashed_at = NONE{extra_where} AND {fragment}",
         );
         let mut request = db
-            .db
             .query(sql)
             .bind(("teams", read_teams.to_vec()))
             .bind(("q", q_trimmed.to_string()));
@@ -104,6 +113,56 @@ async fn song_fulltext_combined_scores(
             *scores.entry(id).or_insert(0.0) += row.rel_score * weight;
         }
     }
+
+    // Songs that exist only as scans are found through their blobs' OCR text; each song counts
+    // its best-matching blob.
+    let mut response = db
+        .query(
+            "SELECT id, (search::score(0) ?? 0) AS rel_score FROM blob WHERE owner IN $teams AND \
+             trashed_at = NONE AND ocr @0@ $q",
+        )
+        .bind(("teams", read_teams.to_vec()))
+        .bind(("q", q_trimmed.to_string()))
+        .await?;
+    let blob_rows: Vec<SongIdScoreRow> = response.take(0)?;
+    let blob_ids: Vec<RecordId> = blob_rows.iter().filter_map(|row| row.id.clone()).collect();
+    if blob_ids.is_empty() {
+        return Ok(scores);
+    }
+    let blob_scores: HashMap<String, f64> = blob_rows
+        .iter()
+        .filter_map(|row| {
+            row.id
+                .as_ref()
+                .map(|id| (record_id_string(id), row.rel_score))
+        })
+        .collect();
+    let sql = format!(
+        "SELECT id, blobs FROM song WHERE owner IN $teams AND trashed_at = NONE{extra_where} AND blobs CONTAINSANY $blobs",
+    );
+    let mut request = db
+        .query(sql)
+        .bind(("teams", read_teams.to_vec()))
+        .bind(("blobs", blob_ids));
+    for &(k, ref v) in extra_binds {
+        request = request.bind((k, v.clone()));
+    }
+    let rows: Vec<SongBlobsRow> = request.await?.take(0)?;
+    for row in rows {
+        let Some(ref rid) = row.id else {
+            continue;
+        };
+        let id = record_id_string(rid);
+        if id.is_empty() {
+            continue;
+        }
+        let best = row
+            .blobs
+            .iter()
+            .filter_map(|blob| blob_scores.get(&record_id_string(blob)))
+            .fold(0.0_f64, |best, &score| best.max(score));
+        *scores.entry(id).or_insert(0.0) += best * BLOB_OCR_WEIGHT;
+    }
     Ok(scores)
 }
 
@@ -115,7 +174,6 @@ async fn songs_by_ids(
         return Ok(HashMap::new());
     }
     let mut response = db
-        .db
         .query("SELECT * FROM song WHERE id INSIDE $ids")
         .bind(("ids", ids))
         .await?;
@@ -222,13 +280,14 @@ impl SongRepository for SurrealSongRepo {
                 .collect());
         }
 
-        let mut sql = format!("SELECT * FROM song WHERE owner IN $teams{extra_where}");
+        let mut sql =
+            format!("SELECT * FROM song WHERE owner IN $teams AND trashed_at = NONE{extra_where}");
         sql.push(' ');
         sql.push_str(song_order_clause(sort, false));
         let (offset, limit) = pagination.effective_offset_limit();
         sql.push_str(" LIMIT $limit START $start");
 
-        let mut request = db.db.query(sql).bind(("teams", read_teams.to_vec()));
+        let mut request = db.query(sql).bind(("teams", read_teams.to_vec()));
         for (k, v) in extra_binds {
             request = request.bind((k, v));
         }
@@ -245,9 +304,11 @@ impl SongRepository for SurrealSongRepo {
 
     async fn get_song(&self, read_teams: &[RecordId], id: &str) -> Result<Song, AppError> {
         let db = self.inner();
-        let record: Option<SongRecord> = db.db.select(resource_id("song", id)?).await?;
+        let record: Option<SongRecord> = db.select(resource_id("song", id)?).await?;
         match record {
-            Some(r) if belongs_to(&r.owner, read_teams) => Ok(r.into_song()),
+            Some(r) if r.trashed_at.is_none() && belongs_to(&r.owner, read_teams) => {
+                Ok(r.into_song())
+            }
             _ => Err(AppError::NotFound("song not found".into())),
         }
     }
@@ -278,10 +339,11 @@ impl SongRepository for SurrealSongRepo {
             return Ok(scores.len() as u64);
         }
 
-        let query_s =
-            format!("SELECT count() FROM song WHERE owner IN $teams{extra_where} GROUP ALL");
+        let query_s = format!(
+            "SELECT count() FROM song WHERE owner IN $teams AND trashed_at = NONE{extra_where} GROUP ALL"
+        );
 
-        let mut request = db.db.query(query_s).bind(("teams", read_teams.to_vec()));
+        let mut request = db.query(query_s).bind(("teams", read_teams.to_vec()));
         for (k, v) in extra_binds {
             request = request.bind((k, v));
         }
@@ -302,8 +364,7 @@ impl SongRepository for SurrealSongRepo {
 
     async fn create_song(&self, owner: RecordId, song: CreateSong) -> Result<Song, AppError> {
         let db = self.inner();
-        db.db
-            .create("song")
+        db.create("song")
             .content(SongRecord::from_payload(None, Some(owner), song))
             .await?
             .map(SongRecord::into_song)
@@ -311,7 +372,7 @@ impl SongRepository for SurrealSongRepo {
     }
 
     /// Three-step upsert:
-    /// 1. `UPDATE ... WHERE owner IN $teams` -- fast-path for existing songs the caller owns.
+    /// 1. `UPDATE ... WHERE owner IN $teams AND trashed_at = NONE` -- fast-path for existing songs the caller owns.
     /// 2. If empty: `SELECT` by ID -- if it exists the caller has no permission (`NotFound`).
     /// 3. If missing: `CREATE` with the given ID under the actor's personal team.
     async fn update_song(
@@ -329,26 +390,25 @@ impl SongRepository for SurrealSongRepo {
         let blobs: Vec<RecordId> = song.blobs.iter().map(|b| blob_thing(&b.id)).collect();
 
         let mut response = if let Some(ref owner_rid) = owner {
-            db.db
-                .query(
-                    "UPDATE type::record($tb, $sid) SET not_a_song = $not_a_song, blobs = $blobs, \
+            db.query(
+                "UPDATE type::record($tb, $sid) SET not_a_song = $not_a_song, blobs = $blobs, \
                      data = $data, search_content = $search_content, owner = $owner \
-                     WHERE owner IN $teams RETURN AFTER",
-                )
-                .bind(("tb", tb.clone()))
-                .bind(("sid", sid.clone()))
-                .bind(("not_a_song", song.not_a_song))
-                .bind(("blobs", blobs.clone()))
-                .bind(("data", SongDataField(song.data.clone())))
-                .bind(("search_content", search_content.clone()))
-                .bind(("owner", owner_rid.clone()))
-                .bind(("teams", write_teams.to_vec()))
-                .await?
+                     WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
+            )
+            .bind(("tb", tb.clone()))
+            .bind(("sid", sid.clone()))
+            .bind(("not_a_song", song.not_a_song))
+            .bind(("blobs", blobs.clone()))
+            .bind(("data", SongDataField(song.data.clone())))
+            .bind(("search_content", search_content.clone()))
+            .bind(("owner", owner_rid.clone()))
+            .bind(("teams", write_teams.to_vec()))
+            .await?
         } else {
-            db.db
+            db
                 .query(
                     "UPDATE type::record($tb, $sid) SET not_a_song = $not_a_song, blobs = $blobs, \
-                     data = $data, search_content = $search_content WHERE owner IN $teams RETURN AFTER",
+                     data = $data, search_content = $search_content WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
                 )
                 .bind(("tb", tb.clone()))
                 .bind(("sid", sid.clone()))
@@ -365,7 +425,7 @@ impl SongRepository for SurrealSongRepo {
             return Ok(SongUpsertOutcome::Updated(updated.into_song()));
         }
 
-        let existing: Option<SongRecord> = db.db.select(resource.clone()).await?;
+        let existing: Option<SongRecord> = db.select(resource.clone()).await?;
         if existing.is_some() {
             return Err(AppError::NotFound("song not found".into()));
         }
@@ -381,7 +441,6 @@ impl SongRepository for SurrealSongRepo {
         let record_id = RecordId::new(resource.0.clone(), resource.1.clone());
         let record = SongRecord::from_payload(Some(record_id), Some(owner_team), song);
         let created = db
-            .db
             .create(resource)
             .content(record)
             .await?
@@ -390,13 +449,21 @@ impl SongRepository for SurrealSongRepo {
         Ok(SongUpsertOutcome::Created(created))
     }
 
-    async fn delete_song(&self, write_teams: &[RecordId], id: &str) -> Result<Song, AppError> {
+    async fn trash_song(
+        &self,
+        write_teams: &[RecordId],
+        actor_user_id: &str,
+        id: &str,
+    ) -> Result<Song, AppError> {
         let db = self.inner();
         let (tb, sid) = resource_id("song", id)?;
         let mut response = db
-            .db
-            .query("DELETE FROM type::record($tb, $sid) WHERE owner IN $teams RETURN BEFORE")
+            .query(
+                "UPDATE type::record($tb, $sid) SET trashed_at = time::now(), trashed_by = $actor \
+                 WHERE owner IN $teams AND trashed_at = NONE RETURN BEFORE",
+            )
             .bind(("tb", tb))
+            .bind(("actor", RecordId::new("user", actor_user_id.to_owned())))
             .bind(("sid", sid))
             .bind(("teams", write_teams.to_vec()))
             .await?;
@@ -417,9 +484,8 @@ impl SongRepository for SurrealSongRepo {
         let db = self.inner();
         let (tb, sid) = resource_id("song", id)?;
         let mut response = db
-            .db
             .query(
-                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams RETURN AFTER",
+                "UPDATE type::record($tb, $sid) SET owner = $new_owner WHERE owner IN $teams AND trashed_at = NONE RETURN AFTER",
             )
             .bind(("tb", tb))
             .bind(("sid", sid))
@@ -443,12 +509,11 @@ impl SongRepository for SurrealSongRepo {
         let db = self.inner();
         let resource = resource_id("song", id)?;
         let existing: SongRecord = db
-            .db
             .select(resource.clone())
             .await?
             .ok_or_else(|| AppError::NotFound("song not found".into()))?;
 
-        if !belongs_to(&existing.owner, read_teams) {
+        if existing.trashed_at.is_some() || !belongs_to(&existing.owner, read_teams) {
             return Err(AppError::NotFound("song not found".into()));
         }
 
@@ -456,7 +521,6 @@ impl SongRepository for SurrealSongRepo {
         let song = RecordId::new(resource.0, resource.1);
 
         let mut response = db
-            .db
             .query("SELECT * FROM like WHERE owner = $owner AND song = $song LIMIT 1")
             .bind(("owner", owner))
             .bind(("song", song))
@@ -476,12 +540,11 @@ impl SongRepository for SurrealSongRepo {
         let db = self.inner();
         let resource = resource_id("song", id)?;
         let existing: SongRecord = db
-            .db
             .select(resource.clone())
             .await?
             .ok_or_else(|| AppError::NotFound("song not found".into()))?;
 
-        if !belongs_to(&existing.owner, read_teams) {
+        if existing.trashed_at.is_some() || !belongs_to(&existing.owner, read_teams) {
             return Err(AppError::NotFound("song not found".into()));
         }
 
@@ -489,7 +552,6 @@ impl SongRepository for SurrealSongRepo {
         let song = RecordId::new(resource.0, resource.1);
 
         let mut response = db
-            .db
             .query("SELECT * FROM like WHERE owner = $owner AND song = $song LIMIT 1")
             .bind(("owner", owner.clone()))
             .bind(("song", song.clone()))
@@ -501,14 +563,13 @@ impl SongRepository for SurrealSongRepo {
         if liked {
             if existing_like.is_none() {
                 let _: Option<LikeRecord> = db
-                    .db
                     .create("like")
                     .content(LikeRecord::new(owner, song))
                     .await?;
             }
             Ok(true)
         } else if let Some(record) = existing_like.and_then(|like| like.id) {
-            let _: Option<LikeRecord> = db.db.delete(record).await?;
+            let _: Option<LikeRecord> = db.delete(record).await?;
             Ok(false)
         } else {
             Ok(false)
@@ -518,7 +579,6 @@ impl SongRepository for SurrealSongRepo {
     async fn get_liked_set(&self, user_id: &str) -> Result<HashSet<String>, AppError> {
         let db = self.inner();
         let mut response = db
-            .db
             .query("SELECT * FROM like WHERE owner = $owner")
             .bind(("owner", owner_thing(user_id)))
             .await?;
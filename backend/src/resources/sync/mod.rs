@@ -0,0 +1,12 @@
+pub use shared::sync::{SyncChanges, SyncTombstone};
+
+mod model;
+mod repository;
+pub mod service;
+mod surreal_repo;
+
+pub use repository::SyncRepository;
+pub use service::{SyncService, SyncServiceHandle};
+pub use surreal_repo::SurrealSyncRepo;
+
+pub mod rest;
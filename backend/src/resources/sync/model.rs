@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use ring::digest::{SHA256, digest};
+use serde::Deserialize;
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+use utoipa::IntoParams;
+
+use shared::sync::SyncTombstone;
+use shared::team::TrashResourceType;
+
+use crate::database::record_id_string;
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SyncQuery {
+    /// `cursor` from the previous sync. Omit for a full snapshot.
+    pub since: Option<String>,
+}
+
+/// Row of `sync_tombstone`.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct TombstoneRow {
+    pub resource: RecordId,
+    pub resource_type: String,
+    pub deleted_at: Datetime,
+}
+
+impl TombstoneRow {
+    pub fn into_tombstone(self) -> Result<SyncTombstone, AppError> {
+        Ok(SyncTombstone {
+            resource_type: TrashResourceType::parse(&self.resource_type).ok_or_else(|| {
+                AppError::database(format!(
+                    "unknown sync_tombstone.resource_type {:?}",
+                    self.resource_type
+                ))
+            })?,
+            resource_id: record_id_string(&self.resource),
+            deleted_at: self.deleted_at.into(),
+        })
+    }
+}
+
+/// Position of a client's replica: when it was taken and which teams it covered.
+///
+/// Encoded as `<unix micros>.<teams digest>`; clients treat it as opaque.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncCursor {
+    pub at: DateTime<Utc>,
+    pub teams: String,
+}
+
+impl SyncCursor {
+    pub fn new(at: DateTime<Utc>, read_teams: &[RecordId]) -> Self {
+        Self {
+            at,
+            teams: teams_digest(read_teams),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{}.{}", self.at.timestamp_micros(), self.teams)
+    }
+
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::invalid_request("since is not a sync cursor");
+        let (micros, teams) = value.split_once('.').ok_or_else(invalid)?;
+        let at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        if teams.len() != 16 || !teams.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            at,
+            teams: teams.to_owned(),
+        })
+    }
+
+    /// Whether the cursor was issued for exactly this set of readable teams.
+    pub fn covers(&self, read_teams: &[RecordId]) -> bool {
+        self.teams == teams_digest(read_teams)
+    }
+}
+
+/// Order-independent fingerprint of a team set, short enough to keep cursors URL friendly.
+fn teams_digest(read_teams: &[RecordId]) -> String {
+    let mut ids: Vec<String> = read_teams.iter().map(record_id_string).collect();
+    ids.sort();
+    ids.dedup();
+    hex::encode(&digest(&SHA256, ids.join("\n").as_bytes()).as_ref()[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_and_ignores_team_order() {
+        let at: DateTime<Utc> = "2026-10-19T12:00:00.123456Z".parse().unwrap();
+        let a = RecordId::new("team", "a");
+        let b = RecordId::new("team", "b");
+        let cursor = SyncCursor::new(at, &[a.clone(), b.clone()]);
+        let parsed = SyncCursor::parse(&cursor.encode()).unwrap();
+        assert_eq!(parsed, cursor);
+        assert!(parsed.covers(&[b.clone(), a]));
+        assert!(!parsed.covers(&[b]));
+    }
+
+    #[test]
+    fn malformed_cursor_is_rejected() {
+        for value in ["", "abc", "123", "123.xyz", "x.0123456789abcdef", "1.0123"] {
+            assert!(
+                matches!(SyncCursor::parse(value), Err(AppError::InvalidRequest(_))),
+                "{value:?}"
+            );
+        }
+    }
+}
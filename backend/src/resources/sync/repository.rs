@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use surrealdb::types::{Datetime, RecordId};
+
+use shared::blob::Blob;
+use shared::collection::Collection;
+use shared::setlist::Setlist;
+use shared::song::Song;
+
+use crate::error::AppError;
+
+use super::model::TombstoneRow;
+
+/// Pure sync data access — no authorization. `since = None` selects everything that is not trashed;
+/// otherwise only records written after `since`. Tombstones are written by DB events.
+#[async_trait]
+pub trait SyncRepository: Send + Sync {
+    async fn changed_songs(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Song>, AppError>;
+
+    async fn changed_collections(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Collection>, AppError>;
+
+    async fn changed_setlists(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Setlist>, AppError>;
+
+    async fn changed_blobs(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Blob>, AppError>;
+
+    /// Tombstones left in `read_teams` after `since`, oldest first.
+    async fn tombstones(
+        &self,
+        read_teams: &[RecordId],
+        since: Datetime,
+    ) -> Result<Vec<TombstoneRow>, AppError>;
+
+    /// Deletes tombstones older than `cutoff` and returns how many were removed.
+    async fn prune_tombstones(&self, cutoff: Datetime) -> Result<usize, AppError>;
+}
@@ -0,0 +1,46 @@
+use actix_web::{
+    HttpResponse, get,
+    web::{Data, Query, ReqData},
+};
+use chrono::Utc;
+#[allow(unused_imports)]
+use shared::sync::SyncChanges;
+use shared::user::User;
+
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::resources::team::UserPermissions;
+
+use super::model::SyncQuery;
+use super::service::SyncServiceHandle;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/sync",
+    params(SyncQuery),
+    responses(
+        (status = 200, description = "Songs, collections, setlists and blob metadata the user can read that changed since `since`, plus tombstones for records that were deleted or moved out of reach. `full` marks a complete snapshot that replaces the local replica (no cursor, an expired cursor, or the user's teams changed).", body = SyncChanges),
+        (status = 400, description = "`since` is not a sync cursor", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to load changes", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Sync",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/sync")]
+pub async fn get_sync(
+    svc: Data<SyncServiceHandle>,
+    user: ReqData<User>,
+    query: Query<SyncQuery>,
+) -> Result<HttpResponse, AppError> {
+    let perms = UserPermissions::from_ref(&user, &svc.teams);
+    Ok(HttpResponse::Ok().json(
+        svc.sync_for_user(&perms, query.since.as_deref(), Utc::now())
+            .await?,
+    ))
+}
@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use surrealdb::types::Datetime;
+use tracing::instrument;
+
+use shared::sync::SyncChanges;
+use shared::team::TrashResourceType;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::http_cache::weak_etag_json;
+use crate::resources::job::{JobContext, JobHandler};
+use crate::resources::song::{LikedSongIds, song_etag};
+use crate::resources::team::{SurrealTeamResolver, TeamResolver, UserPermissions};
+
+use super::model::SyncCursor;
+use super::repository::SyncRepository;
+use super::surreal_repo::SurrealSyncRepo;
+
+/// How far a new cursor lags behind the sync that issued it, so writes that were still committing
+/// during the sync (with an earlier `updated_at`) are delivered next time. Clients see them twice.
+const CURSOR_OVERLAP_SECONDS: i64 = 5;
+
+/// Application service for `GET /api/v1/sync`: library changes visible to a user since a cursor.
+#[derive(Clone)]
+pub struct SyncService<R, T, L> {
+    pub repo: R,
+    pub teams: Arc<T>,
+    likes: L,
+    retention: ChronoDuration,
+    overlap: ChronoDuration,
+}
+
+impl<R, T, L> SyncService<R, T, L> {
+    pub fn new(repo: R, teams: Arc<T>, likes: L, retention_days: u32) -> Self {
+        Self {
+            repo,
+            teams,
+            likes,
+            retention: ChronoDuration::days(i64::from(retention_days)),
+            overlap: ChronoDuration::seconds(CURSOR_OVERLAP_SECONDS),
+        }
+    }
+}
+
+impl<R, T, L> SyncService<R, T, L>
+where
+    R: SyncRepository,
+    T: TeamResolver,
+    L: LikedSongIds,
+{
+    /// Changes since `since`, or a full snapshot when there is no cursor, it is older than the
+    /// tombstone retention, or the user's readable teams changed since it was issued.
+    #[instrument(level = "debug", err, skip(self, perms))]
+    pub async fn sync_for_user(
+        &self,
+        perms: &UserPermissions<T>,
+        since: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<SyncChanges, AppError> {
+        let cursor = since.map(SyncCursor::parse).transpose()?;
+        let (liked, read_teams) = tokio::try_join!(
+            self.likes.liked_song_ids(&perms.user().id),
+            perms.read_teams()
+        )?;
+        let since = cursor
+            .filter(|c| c.covers(read_teams) && c.at > now - self.retention)
+            .map(|c| c.at);
+        let changed_since = since.map(Datetime::from);
+        let (mut songs, collections, setlists, blobs) = tokio::try_join!(
+            self.repo.changed_songs(read_teams, changed_since),
+            self.repo.changed_collections(read_teams, changed_since),
+            self.repo.changed_setlists(read_teams, changed_since),
+            self.repo.changed_blobs(read_teams, changed_since),
+        )?;
+        for song in &mut songs {
+            song.user_specific_addons.liked = liked.contains(&song.id);
+        }
+
+        let deleted = match since {
+            None => Vec::new(),
+            Some(at) => {
+                // A record that was moved between readable teams, or trashed and restored, is
+                // still visible; its upsert supersedes the tombstone.
+                let mut known: HashSet<(TrashResourceType, String)> = songs
+                    .iter()
+                    .map(|s| (TrashResourceType::Song, s.id.clone()))
+                    .chain(
+                        collections
+                            .iter()
+                            .map(|c| (TrashResourceType::Collection, c.id.clone())),
+                    )
+                    .chain(
+                        setlists
+                            .iter()
+                            .map(|s| (TrashResourceType::Setlist, s.id.clone())),
+                    )
+                    .chain(
+                        blobs
+                            .iter()
+                            .map(|b| (TrashResourceType::Blob, b.id.clone())),
+                    )
+                    .collect();
+                let mut deleted = Vec::new();
+                for row in self.repo.tombstones(read_teams, at.into()).await? {
+                    let tombstone = row.into_tombstone()?;
+                    if known.insert((tombstone.resource_type, tombstone.resource_id.clone())) {
+                        deleted.push(tombstone);
+                    }
+                }
+                deleted
+            }
+        };
+
+        let etags = songs
+            .iter()
+            .map(|s| Ok((s.id.clone(), song_etag(s)?)))
+            .chain(
+                collections
+                    .iter()
+                    .map(|c| Ok((c.id.clone(), weak_etag_json(c)?))),
+            )
+            .chain(
+                setlists
+                    .iter()
+                    .map(|s| Ok((s.id.clone(), weak_etag_json(s)?))),
+            )
+            .collect::<Result<BTreeMap<_, _>, serde_json::Error>>()
+            .map_err(|e| AppError::internal_from_err("sync.etag", e))?;
+
+        Ok(SyncChanges {
+            cursor: SyncCursor::new(now - self.overlap, read_teams).encode(),
+            full: since.is_none(),
+            songs,
+            collections,
+            setlists,
+            blobs,
+            deleted,
+            etags,
+        })
+    }
+
+    /// Drops tombstones older than the retention; cursors that old get a full snapshot anyway.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn prune_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        self.repo
+            .prune_tombstones((now - self.retention).into())
+            .await
+    }
+}
+
+/// Runs scheduled `sync.prune` jobs: [`prune_expired`](SyncService::prune_expired).
+#[async_trait]
+impl<R, T, L> JobHandler for SyncService<R, T, L>
+where
+    R: SyncRepository,
+    T: TeamResolver,
+    L: LikedSongIds,
+{
+    async fn run(&self, _ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+        let pruned = self.prune_expired(Utc::now()).await?;
+        Ok(Some(serde_json::json!({ "pruned": pruned })))
+    }
+}
+
+pub type SyncServiceHandle = SyncService<SurrealSyncRepo, SurrealTeamResolver, Arc<Database>>;
+
+impl SyncServiceHandle {
+    pub fn build(
+        db: Arc<Database>,
+        team_resolver: Arc<SurrealTeamResolver>,
+        retention_days: u32,
+    ) -> Self {
+        SyncService::new(
+            SurrealSyncRepo::new(db.clone()),
+            team_resolver,
+            db,
+            retention_days,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration as ChronoDuration, Utc};
+
+    use shared::MoveOwner;
+    use shared::song::CreateSong;
+    use shared::sync::SyncChanges;
+    use shared::team::{CreateTeam, TrashResourceType};
+
+    use crate::error::AppError;
+    use crate::resources::song::song_etag;
+    use crate::resources::team::UserPermissions;
+    use crate::resources::user::User;
+    use crate::test_helpers::{
+        TeamFixture, create_song_with_title, create_user, minimal_song_data, personal_team_id,
+        song_service, sync_service, team_service, test_db,
+    };
+
+    use super::SyncServiceHandle;
+
+    /// Sync service without cursor overlap, so a delta holds exactly the writes after the last sync.
+    fn exact_sync_service(db: &std::sync::Arc<crate::database::Database>) -> SyncServiceHandle {
+        let mut svc = sync_service(db);
+        svc.overlap = ChronoDuration::zero();
+        svc
+    }
+
+    async fn sync(svc: &SyncServiceHandle, user: &User, since: Option<&str>) -> SyncChanges {
+        let perms = UserPermissions::from_ref(user, &svc.teams);
+        svc.sync_for_user(&perms, since, Utc::now())
+            .await
+            .expect("sync")
+    }
+
+    fn song_ids(changes: &SyncChanges) -> Vec<&str> {
+        changes.songs.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    /// BLC-SYNC-001, BLC-SYNC-002: a snapshot without cursor, then only the writes since it, with
+    /// a tombstone for a trashed song.
+    #[tokio::test]
+    async fn blc_sync_001_snapshot_then_delta_with_tombstone() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "sync-owner@test.local").await.expect("u");
+        let kept = create_song_with_title(&db, &owner, "Kept")
+            .await
+            .expect("s");
+        let trashed = create_song_with_title(&db, &owner, "Trashed")
+            .await
+            .expect("s");
+        let svc = exact_sync_service(&db);
+
+        let full = sync(&svc, &owner, None).await;
+        assert!(full.full);
+        assert!(full.deleted.is_empty());
+        let ids = song_ids(&full);
+        assert!(ids.contains(&kept.id.as_str()) && ids.contains(&trashed.id.as_str()));
+
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&owner, &songs.teams);
+        songs
+            .delete_song_for_user(&perms, &trashed.id)
+            .await
+            .expect("delete");
+        let added = create_song_with_title(&db, &owner, "Added")
+            .await
+            .expect("s");
+
+        let delta = sync(&svc, &owner, Some(&full.cursor)).await;
+        assert!(!delta.full);
+        assert_eq!(song_ids(&delta), vec![added.id.as_str()]);
+        assert_eq!(delta.deleted.len(), 1);
+        assert_eq!(delta.deleted[0].resource_type, TrashResourceType::Song);
+        assert_eq!(delta.deleted[0].resource_id, trashed.id);
+
+        let quiet = sync(&svc, &owner, Some(&delta.cursor)).await;
+        assert!(!quiet.full);
+        assert!(quiet.songs.is_empty() && quiet.deleted.is_empty());
+    }
+
+    /// BLC-SYNC-003: moving a song to a team a member cannot read leaves them a tombstone, while
+    /// someone who reads both teams just gets the moved song.
+    #[tokio::test]
+    async fn blc_sync_003_move_out_of_reach_is_a_tombstone() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let songs = song_service(&db);
+        let admin_perms = UserPermissions::from_ref(&fx.admin_user, &songs.teams);
+        let song = songs
+            .create_song_for_user(
+                &admin_perms,
+                CreateSong {
+                    owner: Some(fx.shared_team_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .expect("song");
+        let svc = exact_sync_service(&db);
+        let writer_full = sync(&svc, &fx.writer, None).await;
+        assert!(song_ids(&writer_full).contains(&song.id.as_str()));
+        let admin_full = sync(&svc, &fx.admin_user, None).await;
+
+        let admin_team = personal_team_id(&db, &fx.admin_user).await.expect("team");
+        songs
+            .move_song_for_user(&admin_perms, &song.id, MoveOwner { owner: admin_team })
+            .await
+            .expect("move");
+
+        let writer_delta = sync(&svc, &fx.writer, Some(&writer_full.cursor)).await;
+        assert!(!writer_delta.full);
+        assert!(writer_delta.songs.is_empty());
+        assert_eq!(writer_delta.deleted.len(), 1);
+        assert_eq!(writer_delta.deleted[0].resource_id, song.id);
+
+        let admin_delta = sync(&svc, &fx.admin_user, Some(&admin_full.cursor)).await;
+        assert_eq!(song_ids(&admin_delta), vec![song.id.as_str()]);
+        assert!(admin_delta.deleted.is_empty());
+    }
+
+    /// BLC-SYNC-004: a cursor from before the user's readable teams changed, or older than the
+    /// retention, yields a full snapshot; a malformed one is rejected.
+    #[tokio::test]
+    async fn blc_sync_004_stale_cursors_resync_in_full() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "sync-teams@test.local").await.expect("u");
+        let song = create_song_with_title(&db, &owner, "Old").await.expect("s");
+        let svc = exact_sync_service(&db);
+        let perms = UserPermissions::from_ref(&owner, &svc.teams);
+
+        let first = sync(&svc, &owner, None).await;
+        let expired = svc
+            .sync_for_user(
+                &perms,
+                Some(&first.cursor),
+                Utc::now() + ChronoDuration::days(91),
+            )
+            .await
+            .expect("sync");
+        assert!(expired.full);
+        assert!(song_ids(&expired).contains(&song.id.as_str()));
+
+        team_service(&db)
+            .create_shared_team_for_user(
+                &owner,
+                CreateTeam {
+                    name: "Joined later".into(),
+                    members: vec![],
+                },
+            )
+            .await
+            .expect("team");
+        let regained = sync(&svc, &owner, Some(&first.cursor)).await;
+        assert!(regained.full);
+        assert!(song_ids(&regained).contains(&song.id.as_str()));
+
+        assert!(matches!(
+            svc.sync_for_user(&perms, Some("not-a-cursor"), Utc::now())
+                .await,
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+
+    /// BLC-SYNC-006: each synced record carries the ETag its GET returns, and a write hands back
+    /// the ETag the following sync reports, whether or not the reader likes the song.
+    #[tokio::test]
+    async fn blc_sync_006_etags_match_get_and_write_responses() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "sync-etag@test.local").await.expect("u");
+        let song = create_song_with_title(&db, &owner, "Tagged")
+            .await
+            .expect("s");
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&owner, &songs.teams);
+        songs
+            .set_song_like_status_for_user(&perms, &song.id, true)
+            .await
+            .expect("like");
+        let svc = exact_sync_service(&db);
+
+        let full = sync(&svc, &owner, None).await;
+        let fetched = songs
+            .get_song_for_user(&perms, &song.id)
+            .await
+            .expect("get");
+        assert_eq!(
+            full.etags.get(&song.id),
+            Some(&song_etag(&fetched).expect("etag"))
+        );
+
+        let updated = songs
+            .update_song_for_user(
+                &perms,
+                &song.id,
+                CreateSong {
+                    owner: None,
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+                None,
+            )
+            .await
+            .expect("update")
+            .into_song();
+        assert!(updated.user_specific_addons.liked);
+        let delta = sync(&svc, &owner, Some(&full.cursor)).await;
+        assert_eq!(
+            delta.etags.get(&song.id),
+            Some(&song_etag(&updated).expect("etag"))
+        );
+    }
+
+    /// BLC-SYNC-005: tombstones are pruned once they are older than the retention.
+    #[tokio::test]
+    async fn blc_sync_005_prune_expired_tombstones() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "sync-prune@test.local").await.expect("u");
+        let song = create_song_with_title(&db, &owner, "Gone")
+            .await
+            .expect("s");
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&owner, &songs.teams);
+        songs
+            .delete_song_for_user(&perms, &song.id)
+            .await
+            .expect("delete");
+
+        let svc = sync_service(&db);
+        assert_eq!(svc.prune_expired(Utc::now()).await.expect("prune"), 0);
+        assert_eq!(
+            svc.prune_expired(Utc::now() + ChronoDuration::days(91))
+                .await
+                .expect("prune"),
+            1
+        );
+    }
+}
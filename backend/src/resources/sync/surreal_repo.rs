@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::blob::Blob;
+use shared::collection::Collection;
+use shared::setlist::Setlist;
+use shared::song::Song;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::resources::blob::BlobRecord;
+use crate::resources::collection::CollectionRecord;
+use crate::resources::setlist::SetlistRecord;
+use crate::resources::song::SongRecord;
+
+use super::model::TombstoneRow;
+use super::repository::SyncRepository;
+
+#[derive(Clone)]
+pub struct SurrealSyncRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealSyncRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+
+    async fn changed<T: SurrealValue>(
+        &self,
+        table: &'static str,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<T>, AppError> {
+        let since_clause = if since.is_some() {
+            " AND updated_at > $since"
+        } else {
+            ""
+        };
+        Ok(self
+            .inner()
+            .query(format!(
+                "SELECT * FROM {table} WHERE owner IN $teams AND trashed_at = NONE{since_clause} ORDER BY id"
+            ))
+            .bind(("teams", read_teams.to_vec()))
+            .bind(("since", since))
+            .await?
+            .take(0)?)
+    }
+}
+
+#[async_trait]
+impl SyncRepository for SurrealSyncRepo {
+    async fn changed_songs(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Song>, AppError> {
+        let rows: Vec<SongRecord> = self.changed("song", read_teams, since).await?;
+        Ok(rows.into_iter().map(SongRecord::into_song).collect())
+    }
+
+    async fn changed_collections(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Collection>, AppError> {
+        let rows: Vec<CollectionRecord> = self.changed("collection", read_teams, since).await?;
+        Ok(rows
+            .into_iter()
+            .map(CollectionRecord::into_collection)
+            .collect())
+    }
+
+    async fn changed_setlists(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Setlist>, AppError> {
+        let rows: Vec<SetlistRecord> = self.changed("setlist", read_teams, since).await?;
+        Ok(rows.into_iter().map(SetlistRecord::into_setlist).collect())
+    }
+
+    async fn changed_blobs(
+        &self,
+        read_teams: &[RecordId],
+        since: Option<Datetime>,
+    ) -> Result<Vec<Blob>, AppError> {
+        let rows: Vec<BlobRecord> = self.changed("blob", read_teams, since).await?;
+        Ok(rows.into_iter().map(BlobRecord::into_blob).collect())
+    }
+
+    async fn tombstones(
+        &self,
+        read_teams: &[RecordId],
+        since: Datetime,
+    ) -> Result<Vec<TombstoneRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT resource, resource_type, deleted_at FROM sync_tombstone WHERE owner IN $teams AND deleted_at > $since ORDER BY deleted_at",
+            )
+            .bind(("teams", read_teams.to_vec()))
+            .bind(("since", since))
+            .await?
+            .take(0)?)
+    }
+
+    async fn prune_tombstones(&self, cutoff: Datetime) -> Result<usize, AppError> {
+        let rows: Vec<TombstoneRow> = self
+            .inner()
+            .query("DELETE sync_tombstone WHERE deleted_at < $cutoff RETURN BEFORE")
+            .bind(("cutoff", cutoff))
+            .await?
+            .take(0)?;
+        Ok(rows.len())
+    }
+}
@@ -0,0 +1,15 @@
+mod model;
+pub use model::NewTeamActivity;
+
+pub mod repository;
+#[cfg(test)]
+pub use repository::DiscardActivity;
+pub use repository::{ActivityRecorder, TeamActivityRepository};
+
+mod surreal_repo;
+pub use surreal_repo::SurrealTeamActivityRepo;
+
+pub mod service;
+pub use service::{ActivityDigestJob, ActivityService, ActivityServiceHandle};
+
+pub mod rest;
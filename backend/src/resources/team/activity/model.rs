@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::event::ChangeEvent;
+use shared::team::{
+    ActivityAction, ActivityResourceType, TeamActivity, TeamUser, TrashResourceType,
+};
+use shared::user::{ActivityDigest, NotificationPreferences};
+
+use crate::database::{ChangeNotice, record_id_string};
+use crate::error::AppError;
+use crate::http_cache::weak_etag_json;
+use crate::resources::user::UserRecord;
+
+/// Activity entry as produced by the content services, before it is persisted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewTeamActivity {
+    pub team: RecordId,
+    pub actor: Option<RecordId>,
+    pub resource_type: ActivityResourceType,
+    pub resource_id: String,
+    pub action: ActivityAction,
+    pub title: Option<String>,
+    pub other_team: Option<RecordId>,
+    /// Weak ETag of the resource after the write; only pushed on the change stream, not stored.
+    pub etag: Option<String>,
+}
+
+impl NewTeamActivity {
+    /// `team_id` is the owning team as it appears in API `owner` fields (record key only).
+    pub fn new(
+        team_id: &str,
+        actor_user_id: &str,
+        resource_type: ActivityResourceType,
+        resource_id: &str,
+        action: ActivityAction,
+    ) -> Self {
+        Self {
+            team: RecordId::new("team", team_id.to_owned()),
+            actor: Some(RecordId::new("user", actor_user_id.to_owned())),
+            resource_type,
+            resource_id: resource_id.to_owned(),
+            action,
+            title: None,
+            other_team: None,
+            etag: None,
+        }
+    }
+
+    /// Attach a display label; blank labels are dropped.
+    pub fn with_title(mut self, title: Option<&str>) -> Self {
+        self.title = title
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_owned);
+        self
+    }
+
+    pub fn with_other_team(mut self, other_team_id: &str) -> Self {
+        self.other_team = Some(RecordId::new("team", other_team_id.to_owned()));
+        self
+    }
+
+    /// Attach the ETag clients would see for `resource` (the API representation after the write).
+    pub fn with_etag<T: Serialize>(mut self, resource: &T) -> Self {
+        self.etag = weak_etag_json(resource).ok();
+        self
+    }
+
+    /// Change-stream notice for library resources; membership entries are not streamed.
+    pub fn change_notice(&self) -> Option<ChangeNotice> {
+        let resource_type = match self.resource_type {
+            ActivityResourceType::Song => TrashResourceType::Song,
+            ActivityResourceType::Collection => TrashResourceType::Collection,
+            ActivityResourceType::Setlist => TrashResourceType::Setlist,
+            ActivityResourceType::Blob => TrashResourceType::Blob,
+            ActivityResourceType::Member => return None,
+        };
+        Some(ChangeNotice {
+            team: self.team.clone(),
+            event: ChangeEvent {
+                resource_type,
+                id: self.resource_id.clone(),
+                action: self.action,
+                etag: self
+                    .etag
+                    .clone()
+                    .filter(|_| self.action != ActivityAction::Deleted),
+            },
+        })
+    }
+}
+
+#[derive(Serialize, SurrealValue)]
+pub struct TeamActivityCreate {
+    pub team: RecordId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<RecordId>,
+    pub resource_type: String,
+    pub resource_id: String,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_team: Option<RecordId>,
+}
+
+impl From<NewTeamActivity> for TeamActivityCreate {
+    fn from(entry: NewTeamActivity) -> Self {
+        Self {
+            team: entry.team,
+            actor: entry.actor,
+            resource_type: entry.resource_type.as_str().to_owned(),
+            resource_id: entry.resource_id,
+            action: entry.action.as_str().to_owned(),
+            title: entry.title,
+            other_team: entry.other_team,
+        }
+    }
+}
+
+/// Row of `team_activity` with `actor` fetched.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct TeamActivityRow {
+    pub id: RecordId,
+    pub team: RecordId,
+    #[serde(default)]
+    pub actor: Option<UserRecord>,
+    pub resource_type: String,
+    pub resource_id: String,
+    pub action: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub other_team: Option<RecordId>,
+    pub created_at: Datetime,
+}
+
+impl TeamActivityRow {
+    pub fn into_activity(self) -> Result<TeamActivity, AppError> {
+        let resource_type = ActivityResourceType::parse(&self.resource_type).ok_or_else(|| {
+            AppError::database(format!(
+                "unknown team_activity.resource_type {:?}",
+                self.resource_type
+            ))
+        })?;
+        let action = ActivityAction::parse(&self.action).ok_or_else(|| {
+            AppError::database(format!("unknown team_activity.action {:?}", self.action))
+        })?;
+        Ok(TeamActivity {
+            id: record_id_string(&self.id),
+            team_id: record_id_string(&self.team),
+            actor: self.actor.map(|u| {
+                let u = u.into_user();
+                TeamUser {
+                    id: u.id,
+                    email: u.email,
+                }
+            }),
+            resource_type,
+            resource_id: self.resource_id,
+            action,
+            title: self.title,
+            other_team_id: self.other_team.as_ref().map(record_id_string),
+            created_at: self.created_at.into(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct NotificationPreferenceRow {
+    pub activity_digest: String,
+    #[serde(default)]
+    pub last_digest_at: Option<Datetime>,
+}
+
+impl NotificationPreferenceRow {
+    pub fn digest(&self) -> ActivityDigest {
+        ActivityDigest::parse(&self.activity_digest).unwrap_or_default()
+    }
+
+    pub fn into_preferences(self) -> NotificationPreferences {
+        NotificationPreferences {
+            activity_digest: self.digest(),
+        }
+    }
+}
+
+/// `notification_preference` row with `user` fetched, for the digest sender.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct DigestSubscriberRow {
+    pub user: UserRecord,
+    pub activity_digest: String,
+    #[serde(default)]
+    pub last_digest_at: Option<Datetime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_title_trims_and_drops_blank() {
+        let base = NewTeamActivity::new(
+            "t1",
+            "u1",
+            ActivityResourceType::Song,
+            "s1",
+            ActivityAction::Created,
+        );
+        assert_eq!(
+            base.clone().with_title(Some("  Amazing Grace ")).title,
+            Some("Amazing Grace".into())
+        );
+        assert_eq!(base.clone().with_title(Some("   ")).title, None);
+        assert_eq!(base.with_title(None).title, None);
+    }
+
+    #[test]
+    fn create_payload_uses_wire_names() {
+        let create = TeamActivityCreate::from(
+            NewTeamActivity::new(
+                "t1",
+                "u1",
+                ActivityResourceType::Member,
+                "u2",
+                ActivityAction::MemberRoleChanged,
+            )
+            .with_other_team("t2"),
+        );
+        assert_eq!(create.resource_type, "member");
+        assert_eq!(create.action, "member_role_changed");
+        assert_eq!(create.team, RecordId::new("team", "t1"));
+        assert_eq!(create.other_team, Some(RecordId::new("team", "t2")));
+    }
+}
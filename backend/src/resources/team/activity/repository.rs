@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use surrealdb::types::{Datetime, RecordId};
+
+use shared::user::ActivityDigest;
+
+use crate::database::record_id_string;
+use crate::error::AppError;
+
+use super::model::{
+    DigestSubscriberRow, NewTeamActivity, NotificationPreferenceRow, TeamActivityRow,
+};
+
+/// Write side of the team activity feed, injected into the content services.
+#[async_trait]
+pub trait ActivityRecorder: Send + Sync {
+    async fn record_activity(&self, entry: NewTeamActivity) -> Result<(), AppError>;
+
+    /// Like [`record_activity`](Self::record_activity) but only logs failures: the mutation the
+    /// entry describes has already been committed and must not be reported as failed.
+    async fn record_activity_or_warn(&self, entry: NewTeamActivity) {
+        let team_id = record_id_string(&entry.team);
+        let action = entry.action.as_str();
+        if let Err(e) = self.record_activity(entry).await {
+            tracing::warn!(
+                team_id = %team_id,
+                action = %action,
+                error = %e,
+                "failed to record team activity"
+            );
+        }
+    }
+}
+
+/// Pure activity / notification-preference data access — no authorization.
+#[async_trait]
+pub trait TeamActivityRepository: ActivityRecorder {
+    /// Entries for one team, newest first.
+    async fn list_team_activity(
+        &self,
+        team: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<TeamActivityRow>, AppError>;
+
+    async fn count_team_activity(&self, team: RecordId) -> Result<u64, AppError>;
+
+    /// Entries in any of `teams` created after `since` by someone other than `exclude_actor`,
+    /// oldest first (digest order).
+    async fn list_activity_since(
+        &self,
+        teams: Vec<RecordId>,
+        since: Datetime,
+        exclude_actor: RecordId,
+    ) -> Result<Vec<TeamActivityRow>, AppError>;
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<NotificationPreferenceRow>, AppError>;
+
+    async fn set_activity_digest(
+        &self,
+        user_id: &str,
+        digest: ActivityDigest,
+    ) -> Result<NotificationPreferenceRow, AppError>;
+
+    /// All users with a digest frequency other than `off`.
+    async fn digest_subscribers(&self) -> Result<Vec<DigestSubscriberRow>, AppError>;
+
+    async fn mark_digest_sent(&self, user_id: &str, at: Datetime) -> Result<(), AppError>;
+}
+
+/// Recorder that drops every entry, for service tests that do not assert on the feed.
+#[cfg(test)]
+pub struct DiscardActivity;
+
+#[cfg(test)]
+#[async_trait]
+impl ActivityRecorder for DiscardActivity {
+    async fn record_activity(&self, _entry: NewTeamActivity) -> Result<(), AppError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::resources::User;
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, Scope, get, put,
+    web::{self, Data, Json, Path, Query, ReqData},
+};
+
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+#[allow(unused_imports)]
+use shared::team::TeamActivity;
+use shared::user::NotificationPreferences;
+
+use super::service::ActivityServiceHandle;
+
+pub fn team_activity_scope() -> Scope {
+    web::scope("/{team_id}/activity").service(list_team_activity)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{team_id}/activity",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("page" = Option<u32>, Query, description = "Page index, zero-based.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Team activity, newest first. `X-Total-Count` is the total before paging.", body = [TeamActivity]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team not found or not readable", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("")]
+async fn list_team_activity(
+    req: HttpRequest,
+    svc: Data<ActivityServiceHandle>,
+    user: ReqData<User>,
+    team_id: Path<String>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let q_link = query.clone();
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let (entries, total) = svc
+        .list_activity_for_user(&user, team_id.as_str(), query.as_list_query())
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                &req,
+                |p| q_link.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(entries))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/notification-preferences",
+    responses(
+        (status = 200, description = "Notification preferences of the current user (defaults when never set)", body = NotificationPreferences),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/me/notification-preferences")]
+pub(crate) async fn get_notification_preferences(
+    svc: Data<ActivityServiceHandle>,
+    user: ReqData<User>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.get_notification_preferences_for_user(&user).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/notification-preferences",
+    request_body = NotificationPreferences,
+    responses(
+        (status = 200, description = "Stored notification preferences", body = NotificationPreferences),
+        (status = 400, description = "Invalid body", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[put("/me/notification-preferences")]
+pub(crate) async fn put_notification_preferences(
+    svc: Data<ActivityServiceHandle>,
+    user: ReqData<User>,
+    payload: Json<NotificationPreferences>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(
+        svc.set_notification_preferences_for_user(&user, payload.into_inner())
+            .await?,
+    ))
+}
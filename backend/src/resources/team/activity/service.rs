@@ -0,0 +1,566 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use surrealdb::types::RecordId;
+use tracing::instrument;
+
+use shared::api::ListQuery;
+use shared::team::{ActivityAction, ActivityResourceType, TeamActivity};
+use shared::user::{ActivityDigest, NotificationPreferences, Role as UserRole, User};
+
+use crate::database::{Database, record_id_string};
+use crate::error::AppError;
+use crate::mail::MailService;
+use crate::resources::job::{JobContext, JobHandler};
+use crate::resources::team::model::{
+    can_read_team, team_fetched_to_stored, team_resource_or_reject_public, user_thing,
+};
+use crate::resources::team::repository::TeamRepository;
+use crate::resources::team::surreal_repo::SurrealTeamRepo;
+
+use super::repository::TeamActivityRepository;
+use super::surreal_repo::SurrealTeamActivityRepo;
+
+/// Application service for reading team activity and delivering activity digests.
+#[derive(Clone)]
+pub struct ActivityService<R, A> {
+    pub team_repo: R,
+    pub activity_repo: A,
+}
+
+impl<R, A> ActivityService<R, A> {
+    pub fn new(team_repo: R, activity_repo: A) -> Self {
+        Self {
+            team_repo,
+            activity_repo,
+        }
+    }
+}
+
+impl<R: TeamRepository, A: TeamActivityRepository> ActivityService<R, A> {
+    /// Activity of one team, newest first. Readable by anyone who can read the team itself.
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_activity_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        pagination: ListQuery,
+    ) -> Result<(Vec<TeamActivity>, u64), AppError> {
+        let resource = team_resource_or_reject_public(team_id)?;
+        let row = self
+            .team_repo
+            .fetch_team(team_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("team not found".into()))?;
+        let stored = team_fetched_to_stored(&row)?;
+        if !can_read_team(&user.id, &stored, user.role == UserRole::Admin) {
+            return Err(AppError::NotFound("team not found".into()));
+        }
+
+        let team = RecordId::new(resource.0, resource.1);
+        let (offset, limit) = pagination.effective_offset_limit();
+        let (rows, total) = tokio::try_join!(
+            self.activity_repo
+                .list_team_activity(team.clone(), offset, limit),
+            self.activity_repo.count_team_activity(team),
+        )?;
+        let page = rows
+            .into_iter()
+            .map(|r| r.into_activity())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((page, total))
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn get_notification_preferences_for_user(
+        &self,
+        user: &User,
+    ) -> Result<NotificationPreferences, AppError> {
+        Ok(self
+            .activity_repo
+            .get_notification_preferences(&user.id)
+            .await?
+            .map(|row| row.into_preferences())
+            .unwrap_or_default())
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn set_notification_preferences_for_user(
+        &self,
+        user: &User,
+        preferences: NotificationPreferences,
+    ) -> Result<NotificationPreferences, AppError> {
+        Ok(self
+            .activity_repo
+            .set_activity_digest(&user.id, preferences.activity_digest)
+            .await?
+            .into_preferences())
+    }
+
+    /// Email every subscriber whose digest period has elapsed a summary of what other people
+    /// changed in their teams since the previous digest. Users without new activity are not
+    /// emailed but their period restarts. Returns the number of emails sent.
+    #[instrument(level = "debug", err, skip(self, mail))]
+    pub async fn send_due_digests(
+        &self,
+        mail: &MailService,
+        now: DateTime<Utc>,
+    ) -> Result<usize, AppError> {
+        let mut sent = 0;
+        for sub in self.activity_repo.digest_subscribers().await? {
+            let digest = ActivityDigest::parse(&sub.activity_digest).unwrap_or_default();
+            let Some(period) = digest.period_seconds().map(ChronoDuration::seconds) else {
+                continue;
+            };
+            let last = sub.last_digest_at.map(DateTime::<Utc>::from);
+            if last.is_some_and(|last| now - last < period) {
+                continue;
+            }
+            let since = last.unwrap_or(now - period);
+            let user = sub.user.into_user();
+
+            let teams = self.team_repo.fetch_teams_for_user(&user.id, false).await?;
+            let team_names: BTreeMap<String, String> = teams
+                .iter()
+                .map(|t| (record_id_string(&t.id), t.name.clone()))
+                .collect();
+            let activity = self
+                .activity_repo
+                .list_activity_since(
+                    teams.into_iter().map(|t| t.id).collect(),
+                    since.into(),
+                    user_thing(&user.id),
+                )
+                .await?
+                .into_iter()
+                .map(|r| r.into_activity())
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if !activity.is_empty() {
+                let body = digest_body(&user.email, since, &team_names, &activity);
+                if let Err(e) = mail
+                    .send(&user.email, "Your WorshipViewer team activity", &body)
+                    .await
+                {
+                    // Leave `last_digest_at` untouched so the next run retries this user.
+                    tracing::warn!(
+                        user_id = %user.id,
+                        error = %e,
+                        "failed to send activity digest"
+                    );
+                    continue;
+                }
+                sent += 1;
+            }
+            self.activity_repo
+                .mark_digest_sent(&user.id, now.into())
+                .await?;
+        }
+        Ok(sent)
+    }
+}
+
+/// Runs scheduled `activity.digest` jobs: [`send_due_digests`](ActivityService::send_due_digests)
+/// through `mail`.
+pub struct ActivityDigestJob<R, A> {
+    activity: ActivityService<R, A>,
+    mail: MailService,
+}
+
+impl<R, A> ActivityDigestJob<R, A> {
+    pub fn new(activity: ActivityService<R, A>, mail: MailService) -> Self {
+        Self { activity, mail }
+    }
+}
+
+#[async_trait]
+impl<R: TeamRepository, A: TeamActivityRepository> JobHandler for ActivityDigestJob<R, A> {
+    async fn run(&self, _ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+        let sent = self
+            .activity
+            .send_due_digests(&self.mail, Utc::now())
+            .await?;
+        Ok(Some(serde_json::json!({ "sent": sent })))
+    }
+}
+
+fn digest_body(
+    email: &str,
+    since: DateTime<Utc>,
+    team_names: &BTreeMap<String, String>,
+    activity: &[TeamActivity],
+) -> String {
+    let mut body = format!(
+        "Hello {email},\n\nhere is what happened in your WorshipViewer teams since {}:\n\n",
+        since.format("%Y-%m-%d %H:%M UTC")
+    );
+    for entry in activity {
+        body.push_str(&digest_line(team_names, entry));
+        body.push('\n');
+    }
+    body.push_str(
+        "\nYou can change how often you receive this email in your notification preferences.\n\nBlessings,\nThe WorshipViewer Team",
+    );
+    body
+}
+
+fn digest_line(team_names: &BTreeMap<String, String>, entry: &TeamActivity) -> String {
+    let team = team_names
+        .get(&entry.team_id)
+        .map(String::as_str)
+        .unwrap_or(entry.team_id.as_str());
+    let actor = entry
+        .actor
+        .as_ref()
+        .map(|u| u.email.as_str())
+        .unwrap_or("a former member");
+    let subject = entry.title.as_deref().unwrap_or(entry.resource_id.as_str());
+    let verb = match entry.action {
+        ActivityAction::Created => "created",
+        ActivityAction::Updated => "updated",
+        ActivityAction::Deleted => "deleted",
+        ActivityAction::Moved => "moved",
+        ActivityAction::MemberAdded => "added",
+        ActivityAction::MemberRemoved => "removed",
+        ActivityAction::MemberRoleChanged => "changed the role of",
+        ActivityAction::Restored => "restored",
+    };
+    let noun = match entry.resource_type {
+        ActivityResourceType::Member => "member",
+        other => other.as_str(),
+    };
+    format!("- [{team}] {actor} {verb} {noun} \"{subject}\"")
+}
+
+/// Production type alias used in HTTP wiring.
+pub type ActivityServiceHandle = ActivityService<SurrealTeamRepo, SurrealTeamActivityRepo>;
+
+impl ActivityServiceHandle {
+    pub fn build(db: Arc<Database>) -> Self {
+        ActivityService::new(
+            SurrealTeamRepo::new(db.clone()),
+            SurrealTeamActivityRepo::new(db.clone()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration as ChronoDuration, Utc};
+    use surrealdb::types::RecordId;
+
+    use shared::MoveOwner;
+    use shared::api::ListQuery;
+    use shared::team::{
+        ActivityAction, ActivityResourceType, TeamMemberInput, TeamRole, TeamUserRef, UpdateTeam,
+    };
+    use shared::user::{ActivityDigest, NotificationPreferences};
+
+    use crate::error::AppError;
+    use crate::mail::MailService;
+    use crate::resources::team::UserPermissions;
+    use crate::resources::team::activity::TeamActivityRepository;
+    use crate::test_helpers::{
+        TeamFixture, activity_service, configure_personal_team_members, create_song_with_title,
+        create_user, personal_team_id, song_service, test_db,
+    };
+
+    /// BLC-TACT-001: song create, update and delete show up newest first with actor and title.
+    #[tokio::test]
+    async fn blc_tact_001_song_lifecycle_is_recorded() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "act-owner@test.local").await.expect("u");
+        let team_id = personal_team_id(&db, &owner).await.expect("team");
+        let song = create_song_with_title(&db, &owner, "Amazing Grace")
+            .await
+            .expect("song");
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&owner, &songs.teams);
+        songs
+            .delete_song_for_user(&perms, &song.id)
+            .await
+            .expect("delete");
+
+        let (entries, total) = activity_service(&db)
+            .list_activity_for_user(&owner, &team_id, ListQuery::default())
+            .await
+            .expect("list");
+        // Song create also creates the default collection.
+        assert_eq!(total, 3);
+        assert_eq!(entries[0].action, ActivityAction::Deleted);
+        assert_eq!(entries[0].resource_type, ActivityResourceType::Song);
+        assert_eq!(entries[0].resource_id, song.id);
+        assert_eq!(entries[0].title.as_deref(), Some("Amazing Grace"));
+        assert_eq!(
+            entries[0].actor.as_ref().map(|a| a.id.as_str()),
+            Some(owner.id.as_str())
+        );
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.resource_type == ActivityResourceType::Song
+                    && e.action == ActivityAction::Created)
+        );
+    }
+
+    /// BLC-TACT-002: moving content is recorded on both the source and the destination team.
+    #[tokio::test]
+    async fn blc_tact_002_move_recorded_on_both_teams() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let personal = personal_team_id(&db, &fx.admin_user).await.expect("team");
+        let song = create_song_with_title(&db, &fx.admin_user, "Moving")
+            .await
+            .expect("song");
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&fx.admin_user, &songs.teams);
+        songs
+            .move_song_for_user(
+                &perms,
+                &song.id,
+                MoveOwner {
+                    owner: fx.shared_team_id.clone(),
+                },
+            )
+            .await
+            .expect("move");
+
+        let svc = activity_service(&db);
+        for (team, other) in [
+            (&personal, &fx.shared_team_id),
+            (&fx.shared_team_id, &personal),
+        ] {
+            let (entries, _) = svc
+                .list_activity_for_user(&fx.admin_user, team, ListQuery::default())
+                .await
+                .expect("list");
+            let moved = entries
+                .iter()
+                .find(|e| e.action == ActivityAction::Moved)
+                .expect("moved entry");
+            assert_eq!(moved.resource_id, song.id);
+            assert_eq!(moved.other_team_id.as_deref(), Some(other.as_str()));
+        }
+    }
+
+    /// BLC-TACT-003: non-members get 404; guests and platform admins may read the feed.
+    #[tokio::test]
+    async fn blc_tact_003_feed_acl() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = activity_service(&db);
+        let r = svc
+            .list_activity_for_user(&fx.non_member, &fx.shared_team_id, ListQuery::default())
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+        svc.list_activity_for_user(&fx.guest, &fx.shared_team_id, ListQuery::default())
+            .await
+            .expect("guest read");
+        svc.list_activity_for_user(&fx.platform_admin, &fx.shared_team_id, ListQuery::default())
+            .await
+            .expect("platform admin read");
+        let r = svc
+            .list_activity_for_user(&fx.admin_user, "public", ListQuery::default())
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+    }
+
+    /// BLC-TACT-004: member additions and role changes are recorded with the member's email.
+    #[tokio::test]
+    async fn blc_tact_004_member_changes_recorded() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "act-members@test.local").await.expect("o");
+        let guest = create_user(&db, "act-guest@test.local").await.expect("g");
+        let team_id = personal_team_id(&db, &owner).await.expect("team");
+        configure_personal_team_members(
+            &db,
+            &owner,
+            &team_id,
+            vec![(guest.id.clone(), TeamRole::Guest)],
+        )
+        .await
+        .expect("add");
+        configure_personal_team_members(
+            &db,
+            &owner,
+            &team_id,
+            vec![(guest.id.clone(), TeamRole::ContentMaintainer)],
+        )
+        .await
+        .expect("promote");
+
+        let (entries, _) = activity_service(&db)
+            .list_activity_for_user(&owner, &team_id, ListQuery::default())
+            .await
+            .expect("list");
+        let actions: Vec<ActivityAction> = entries
+            .iter()
+            .filter(|e| e.resource_type == ActivityResourceType::Member)
+            .map(|e| e.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ActivityAction::MemberRoleChanged,
+                ActivityAction::MemberAdded
+            ]
+        );
+        assert!(entries.iter().all(|e| e.resource_id == guest.id));
+        assert_eq!(entries[1].title.as_deref(), Some("act-guest@test.local"));
+    }
+
+    /// BLC-TACT-005: preferences default to `off` and round-trip.
+    #[tokio::test]
+    async fn blc_tact_005_notification_preferences_round_trip() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "act-prefs@test.local").await.expect("u");
+        let svc = activity_service(&db);
+        assert_eq!(
+            svc.get_notification_preferences_for_user(&user)
+                .await
+                .expect("get"),
+            NotificationPreferences::default()
+        );
+        let stored = svc
+            .set_notification_preferences_for_user(
+                &user,
+                NotificationPreferences {
+                    activity_digest: ActivityDigest::Weekly,
+                },
+            )
+            .await
+            .expect("set");
+        assert_eq!(stored.activity_digest, ActivityDigest::Weekly);
+        assert_eq!(
+            svc.get_notification_preferences_for_user(&user)
+                .await
+                .expect("get")
+                .activity_digest,
+            ActivityDigest::Weekly
+        );
+    }
+
+    /// BLC-TACT-006: digests include other members' changes only and respect the period.
+    #[tokio::test]
+    async fn blc_tact_006_digest_excludes_own_changes_and_waits_for_period() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "act-digest-owner@test.local")
+            .await
+            .expect("o");
+        let writer = create_user(&db, "act-digest-writer@test.local")
+            .await
+            .expect("w");
+        let team_id = personal_team_id(&db, &owner).await.expect("team");
+        configure_personal_team_members(
+            &db,
+            &owner,
+            &team_id,
+            vec![(writer.id.clone(), TeamRole::ContentMaintainer)],
+        )
+        .await
+        .expect("acl");
+
+        let svc = activity_service(&db);
+        let mail = MailService::noop_for_tests("noreply@test.local".into());
+        svc.set_notification_preferences_for_user(
+            &owner,
+            NotificationPreferences {
+                activity_digest: ActivityDigest::Daily,
+            },
+        )
+        .await
+        .expect("prefs");
+
+        // Only the owner has acted so far, so their first digest is skipped.
+        let now = Utc::now();
+        assert_eq!(svc.send_due_digests(&mail, now).await.expect("run"), 0);
+
+        let songs = song_service(&db);
+        let writer_perms = UserPermissions::from_ref(&writer, &songs.teams);
+        songs
+            .create_song_for_user(
+                &writer_perms,
+                shared::song::CreateSong {
+                    owner: Some(team_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: crate::test_helpers::minimal_song_data(),
+                },
+            )
+            .await
+            .expect("writer song");
+
+        // Within the period nothing is sent; after it, the owner gets the writer's change.
+        let soon = now + ChronoDuration::hours(1);
+        assert_eq!(svc.send_due_digests(&mail, soon).await.expect("run"), 0);
+        let later = now + ChronoDuration::days(1) + ChronoDuration::seconds(1);
+        assert_eq!(svc.send_due_digests(&mail, later).await.expect("run"), 1);
+        assert_eq!(svc.send_due_digests(&mail, later).await.expect("run"), 0);
+    }
+
+    /// BLC-TACT-009: team deletion drops its feed; user deletion keeps entries without actor.
+    #[tokio::test]
+    async fn blc_tact_009_cascades() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = activity_service(&db);
+        let songs = song_service(&db);
+        let writer_perms = UserPermissions::from_ref(&fx.writer, &songs.teams);
+        songs
+            .create_song_for_user(
+                &writer_perms,
+                shared::song::CreateSong {
+                    owner: Some(fx.shared_team_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: crate::test_helpers::minimal_song_data(),
+                },
+            )
+            .await
+            .expect("writer song");
+
+        let teams = crate::test_helpers::team_service(&db);
+        teams
+            .update_team_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                UpdateTeam {
+                    name: "Fixture Shared Team".into(),
+                    members: Some(vec![TeamMemberInput {
+                        user: TeamUserRef {
+                            id: fx.admin_user.id.clone(),
+                        },
+                        role: TeamRole::Admin,
+                    }]),
+                },
+            )
+            .await
+            .expect("remove writer");
+        crate::test_helpers::user_service(&db)
+            .delete_user(&fx.writer.id)
+            .await
+            .expect("delete writer");
+        let (entries, _) = svc
+            .list_activity_for_user(&fx.admin_user, &fx.shared_team_id, ListQuery::default())
+            .await
+            .expect("list");
+        let created = entries
+            .iter()
+            .find(|e| e.resource_type == ActivityResourceType::Song)
+            .expect("song entry survives");
+        assert!(created.actor.is_none());
+
+        teams
+            .delete_team_for_user(&fx.admin_user, &fx.shared_team_id)
+            .await
+            .expect("delete team");
+        let remaining = svc
+            .activity_repo
+            .count_team_activity(RecordId::new("team", fx.shared_team_id.as_str()))
+            .await
+            .expect("count");
+        assert_eq!(remaining, 0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::user::ActivityDigest;
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::{
+    DigestSubscriberRow, NewTeamActivity, NotificationPreferenceRow, TeamActivityCreate,
+    TeamActivityRow,
+};
+use super::repository::{ActivityRecorder, TeamActivityRepository};
+
+#[derive(Clone)]
+pub struct SurrealTeamActivityRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealTeamActivityRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+fn preference_thing(user_id: &str) -> RecordId {
+    RecordId::new("notification_preference", user_id.to_owned())
+}
+
+#[async_trait]
+impl ActivityRecorder for SurrealTeamActivityRepo {
+    async fn record_activity(&self, entry: NewTeamActivity) -> Result<(), AppError> {
+        let notice = entry.change_notice();
+        self.inner()
+            .query("CREATE team_activity CONTENT $entry RETURN NONE")
+            .bind(("entry", TeamActivityCreate::from(entry)))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "team_activity.create", e))?
+            .check()
+            .map_err(|e| crate::log_and_convert!(AppError::database, "team_activity.create", e))?;
+        if let Some(notice) = notice {
+            self.inner().publish_change(notice);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TeamActivityRepository for SurrealTeamActivityRepo {
+    async fn list_team_activity(
+        &self,
+        team: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<TeamActivityRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM team_activity WHERE team = $team ORDER BY created_at DESC LIMIT $limit START $start FETCH actor",
+            )
+            .bind(("team", team))
+            .bind(("limit", limit))
+            .bind(("start", offset))
+            .await?
+            .take(0)?)
+    }
+
+    async fn count_team_activity(&self, team: RecordId) -> Result<u64, AppError> {
+        #[derive(Deserialize, SurrealValue)]
+        struct CountResult {
+            count: u64,
+        }
+        Ok(self
+            .inner()
+            .query("SELECT count() FROM team_activity WHERE team = $team GROUP ALL")
+            .bind(("team", team))
+            .await?
+            .take::<Vec<CountResult>>(0)?
+            .into_iter()
+            .next()
+            .map(|r| r.count)
+            .unwrap_or(0))
+    }
+
+    async fn list_activity_since(
+        &self,
+        teams: Vec<RecordId>,
+        since: Datetime,
+        exclude_actor: RecordId,
+    ) -> Result<Vec<TeamActivityRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM team_activity WHERE team IN $teams AND created_at > $since AND actor != $actor ORDER BY created_at ASC FETCH actor",
+            )
+            .bind(("teams", teams))
+            .bind(("since", since))
+            .bind(("actor", exclude_actor))
+            .await?
+            .take(0)?)
+    }
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<NotificationPreferenceRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM $pid")
+            .bind(("pid", preference_thing(user_id)))
+            .await?
+            .take::<Option<NotificationPreferenceRow>>(0)?)
+    }
+
+    async fn set_activity_digest(
+        &self,
+        user_id: &str,
+        digest: ActivityDigest,
+    ) -> Result<NotificationPreferenceRow, AppError> {
+        self.inner()
+            .query("UPSERT $pid SET user = $user, activity_digest = $digest RETURN NONE")
+            .bind(("pid", preference_thing(user_id)))
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .bind(("digest", digest.as_str().to_owned()))
+            .await
+            .map_err(|e| {
+                crate::log_and_convert!(AppError::database, "notification_preference.upsert", e)
+            })?
+            .check()
+            .map_err(|e| {
+                crate::log_and_convert!(AppError::database, "notification_preference.upsert", e)
+            })?;
+        self.get_notification_preferences(user_id)
+            .await?
+            .ok_or_else(|| AppError::database("failed to store notification preferences"))
+    }
+
+    async fn digest_subscribers(&self) -> Result<Vec<DigestSubscriberRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM notification_preference WHERE activity_digest != 'off' FETCH user",
+            )
+            .await?
+            .take(0)?)
+    }
+
+    async fn mark_digest_sent(&self, user_id: &str, at: Datetime) -> Result<(), AppError> {
+        self.inner()
+            .query("UPDATE $pid SET last_digest_at = $at RETURN NONE")
+            .bind(("pid", preference_thing(user_id)))
+            .bind(("at", at))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
@@ -1,34 +1,155 @@
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::types::{Datetime, RecordId, SurrealValue};
 
-use shared::team::{TeamInvitation, TeamUser};
+use shared::team::{
+    CreateTeamInvitation, TeamInvitation, TeamInvitationStatus, TeamRole, TeamUser,
+};
+use shared::user::email_passes_basic_checks;
 
 use crate::database::record_id_string;
 use crate::error::AppError;
+use crate::resources::team::model::{TeamFetched, parse_role, role_str};
 use crate::resources::user::UserRecord;
 
+/// Lifetime of an invitation created without `expires_at`.
+pub const DEFAULT_INVITATION_TTL_DAYS: i64 = 14;
+/// Upper bound for a requested `expires_at`.
+pub const MAX_INVITATION_TTL_DAYS: i64 = 365;
+
 #[derive(Clone, Debug, Deserialize, SurrealValue)]
 pub struct InvitationRow {
     pub id: RecordId,
     pub team: RecordId,
     pub created_by: UserRecord,
     pub created_at: Datetime,
+    #[serde(default)]
+    pub email: Option<String>,
+    pub role: String,
+    #[serde(default)]
+    pub expires_at: Option<Datetime>,
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+    #[serde(default)]
+    pub accepted_by: Vec<RecordId>,
+    #[serde(default)]
+    pub revoked_at: Option<Datetime>,
 }
 
+/// Invitation with its team fully fetched, as read by the accept flow.
 #[derive(Clone, Debug, Deserialize, SurrealValue)]
 pub struct InvitationAcceptRow {
-    pub team: super::super::model::TeamFetched,
+    pub team: TeamFetched,
+    #[serde(default)]
+    pub email: Option<String>,
+    pub role: String,
+    #[serde(default)]
+    pub expires_at: Option<Datetime>,
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+    #[serde(default)]
+    pub accepted_by: Vec<RecordId>,
+    #[serde(default)]
+    pub revoked_at: Option<Datetime>,
+}
+
+impl InvitationAcceptRow {
+    pub fn status(&self, now: DateTime<Utc>) -> TeamInvitationStatus {
+        TeamInvitationStatus::derive(
+            self.revoked_at.is_some(),
+            self.expires_at.map(Into::into),
+            self.max_uses,
+            self.accepted_by.len() as u32,
+            now,
+        )
+    }
+
+    pub fn accepted_by_user(&self, user: &RecordId) -> bool {
+        self.accepted_by.iter().any(|u| u == user)
+    }
 }
 
 #[derive(Serialize, SurrealValue)]
 pub struct InvitationCreate {
     pub team: RecordId,
     pub created_by: RecordId,
+    pub email: Option<String>,
+    pub role: String,
+    pub expires_at: Option<Datetime>,
+    pub max_uses: Option<u32>,
+}
+
+impl InvitationCreate {
+    /// Validates the request and fills in defaults: guest role, 14-day expiry, and a single use
+    /// for email invitations. Invitations grant built-in roles only.
+    pub fn from_request(
+        team: RecordId,
+        created_by: RecordId,
+        request: CreateTeamInvitation,
+        now: DateTime<Utc>,
+    ) -> Result<Self, AppError> {
+        if let Some(TeamRole::Custom(_)) = request.role {
+            return Err(AppError::invalid_request(
+                "invitations can only grant guest, content_maintainer or admin",
+            ));
+        }
+        let email = match request.email {
+            Some(raw) => {
+                let email = raw.trim().to_lowercase();
+                if !email_passes_basic_checks(&email) {
+                    return Err(AppError::invalid_request("invalid email address"));
+                }
+                Some(email)
+            }
+            None => None,
+        };
+        let expires_at = request
+            .expires_at
+            .unwrap_or(now + Duration::days(DEFAULT_INVITATION_TTL_DAYS));
+        if expires_at <= now {
+            return Err(AppError::invalid_request(
+                "expires_at must be in the future",
+            ));
+        }
+        if expires_at > now + Duration::days(MAX_INVITATION_TTL_DAYS) {
+            return Err(AppError::invalid_request(format!(
+                "expires_at must be at most {MAX_INVITATION_TTL_DAYS} days ahead"
+            )));
+        }
+        let max_uses = match (&email, request.max_uses) {
+            (_, Some(0)) => return Err(AppError::invalid_request("max_uses must be at least 1")),
+            (Some(_), None | Some(1)) => Some(1),
+            (Some(_), Some(_)) => {
+                return Err(AppError::invalid_request(
+                    "email invitations can only be used once",
+                ));
+            }
+            (None, max_uses) => max_uses,
+        };
+        Ok(Self {
+            team,
+            created_by,
+            email,
+            role: role_str(&request.role.unwrap_or(TeamRole::Guest)).to_owned(),
+            expires_at: Some(expires_at.into()),
+            max_uses,
+        })
+    }
 }
 
 impl InvitationRow {
     pub fn into_invitation(self) -> Result<TeamInvitation, AppError> {
         let u = self.created_by.into_user();
+        let role: TeamRole = parse_role(&self.role)?;
+        let expires_at: Option<DateTime<Utc>> = self.expires_at.map(Into::into);
+        let use_count = self.accepted_by.len() as u32;
+        let status = TeamInvitationStatus::derive(
+            self.revoked_at.is_some(),
+            expires_at,
+            self.max_uses,
+            use_count,
+            Utc::now(),
+        );
         Ok(TeamInvitation {
             id: record_id_string(&self.id),
             team_id: record_id_string(&self.team),
@@ -37,6 +158,13 @@ impl InvitationRow {
                 email: u.email,
             },
             created_at: self.created_at.into(),
+            email: self.email,
+            role,
+            expires_at,
+            max_uses: self.max_uses,
+            use_count,
+            revoked_at: self.revoked_at.map(Into::into),
+            status,
         })
     }
 }
@@ -65,6 +193,94 @@ mod tests {
     use super::*;
     use crate::error::AppError;
 
+    fn create(
+        request: CreateTeamInvitation,
+        now: DateTime<Utc>,
+    ) -> Result<InvitationCreate, AppError> {
+        InvitationCreate::from_request(
+            make_thing("team", "t1"),
+            make_thing("user", "u1"),
+            request,
+            now,
+        )
+    }
+
+    /// BLC-TINV-003: an empty request is a guest link without use limit that expires in 14 days.
+    #[test]
+    fn blc_tinv_003_defaults() {
+        let now = Utc::now();
+        let c = create(CreateTeamInvitation::default(), now).unwrap();
+        assert_eq!(c.role, "guest");
+        assert_eq!(c.email, None);
+        assert_eq!(c.max_uses, None);
+        let expires: DateTime<Utc> = c.expires_at.unwrap().into();
+        assert_eq!(expires, now + Duration::days(DEFAULT_INVITATION_TTL_DAYS));
+    }
+
+    /// BLC-TINV-003, BLC-TINV-015: email invitations are normalized and single-use.
+    #[test]
+    fn blc_tinv_015_email_invitation_is_single_use() {
+        let now = Utc::now();
+        let c = create(
+            CreateTeamInvitation {
+                email: Some("  Singer@Example.com ".into()),
+                role: Some(TeamRole::ContentMaintainer),
+                ..Default::default()
+            },
+            now,
+        )
+        .unwrap();
+        assert_eq!(c.email.as_deref(), Some("singer@example.com"));
+        assert_eq!(c.role, "content_maintainer");
+        assert_eq!(c.max_uses, Some(1));
+        let reusable = CreateTeamInvitation {
+            email: Some("singer@example.com".into()),
+            max_uses: Some(3),
+            ..Default::default()
+        };
+        assert!(matches!(
+            create(reusable, now),
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+
+    /// BLC-TINV-003: invalid email, zero uses, out-of-range expiry and team-defined roles are
+    /// rejected.
+    #[test]
+    fn blc_tinv_003_rejects_invalid_requests() {
+        let now = Utc::now();
+        for request in [
+            CreateTeamInvitation {
+                email: Some("not-an-email".into()),
+                ..Default::default()
+            },
+            CreateTeamInvitation {
+                max_uses: Some(0),
+                ..Default::default()
+            },
+            CreateTeamInvitation {
+                expires_at: Some(now),
+                ..Default::default()
+            },
+            CreateTeamInvitation {
+                expires_at: Some(now + Duration::days(MAX_INVITATION_TTL_DAYS + 1)),
+                ..Default::default()
+            },
+            CreateTeamInvitation {
+                role: Some(TeamRole::Custom("planner".into())),
+                ..Default::default()
+            },
+        ] {
+            assert!(
+                matches!(
+                    create(request.clone(), now),
+                    Err(AppError::InvalidRequest(_))
+                ),
+                "{request:?}"
+            );
+        }
+    }
+
     fn make_thing(table: &str, id: &str) -> RecordId {
         RecordId::new(table, id.to_owned())
     }
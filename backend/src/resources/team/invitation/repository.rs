@@ -3,7 +3,7 @@ use surrealdb::types::RecordId;
 
 use crate::error::AppError;
 
-use super::model::{InvitationAcceptRow, InvitationRow};
+use super::model::{InvitationAcceptRow, InvitationCreate, InvitationRow};
 
 /// Pure invitation data access — no authorization. Service layer does all ACL checks.
 #[async_trait]
@@ -11,8 +11,7 @@ pub trait TeamInvitationRepository: Send + Sync {
     /// Insert a new invitation record.
     async fn create_invitation(
         &self,
-        team: RecordId,
-        created_by: RecordId,
+        create: InvitationCreate,
         inv_id: &str,
     ) -> Result<(), AppError>;
 
@@ -30,4 +29,11 @@ pub trait TeamInvitationRepository: Send + Sync {
         &self,
         inv_id: &str,
     ) -> Result<Option<InvitationAcceptRow>, AppError>;
+
+    /// Record `user` as having used the invitation, but only while it is not revoked, not
+    /// expired, has uses left and was not used by `user` before. Returns whether a use was taken.
+    async fn claim_invitation_use(&self, inv_id: &str, user: RecordId) -> Result<bool, AppError>;
+
+    /// Set `revoked_at` on an invitation that is not revoked yet. Returns whether it changed.
+    async fn revoke_invitation(&self, inv_id: &str) -> Result<bool, AppError>;
 }
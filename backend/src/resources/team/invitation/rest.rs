@@ -1,14 +1,16 @@
 #[allow(unused_imports)]
 use crate::docs::Problem;
 use crate::error::AppError;
+use crate::mail::MailService;
 use crate::resources::User;
 use actix_web::http::header;
 use actix_web::{
     HttpRequest, HttpResponse, Scope, delete, get, post,
-    web::{self, Data, Path, Query, ReqData},
+    web::{self, Bytes, Data, Path, Query, ReqData},
 };
 
 use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+use shared::team::CreateTeamInvitation;
 #[allow(unused_imports)]
 use shared::team::Team;
 #[allow(unused_imports)]
@@ -22,9 +24,18 @@ pub fn team_invitations_scope() -> Scope {
         .service(create_team_invitation)
         .service(list_team_invitations)
         .service(get_team_invitation)
+        .service(revoke_team_invitation)
         .service(delete_team_invitation)
 }
 
+/// The create body is optional: clients that POST nothing keep getting a guest link.
+fn parse_create_body(body: &[u8]) -> Result<CreateTeamInvitation, AppError> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(CreateTeamInvitation::default());
+    }
+    serde_json::from_slice(body).map_err(|e| AppError::invalid_request(e.to_string()))
+}
+
 pub fn invitations_accept_scope() -> Scope {
     web::scope("/invitations").service(accept_team_invitation)
 }
@@ -35,13 +46,15 @@ pub fn invitations_accept_scope() -> Scope {
     params(
         ("team_id" = String, Path, description = "Shared team identifier")
     ),
+    request_body(content = Option<CreateTeamInvitation>, description = "Optional; an empty body creates a guest link valid for 14 days"),
     responses(
-        (status = 201, description = "Invitation created", body = TeamInvitation),
+        (status = 201, description = "Invitation created. Invitations with an `email` have been sent to that address.", body = TeamInvitation),
+        (status = 400, description = "Invalid email, role, expiry or use limit", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
         (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
         (status = 404, description = "Team not found", body = Problem, content_type = "application/problem+json"),
-        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+        (status = 500, description = "Database error, or the invitation email could not be sent (the invitation is not kept)", body = Problem, content_type = "application/problem+json")
     ),
     tag = "Teams",
     security(
@@ -52,13 +65,17 @@ pub fn invitations_accept_scope() -> Scope {
 #[post("")]
 async fn create_team_invitation(
     svc: Data<InvitationServiceHandle>,
+    mail: Data<MailService>,
     user: ReqData<User>,
     team_id: Path<String>,
+    body: Bytes,
 ) -> Result<HttpResponse, AppError> {
-    Ok(HttpResponse::Created().json(
-        svc.create_invitation_for_user(&user, team_id.as_str())
-            .await?,
-    ))
+    let request = parse_create_body(&body)?;
+    let invitation = svc
+        .create_invitation_for_user(&user, team_id.as_str(), request)
+        .await?;
+    svc.send_invitation_email(&mail, &user, &invitation).await?;
+    Ok(HttpResponse::Created().json(invitation))
 }
 
 #[utoipa::path(
@@ -70,7 +87,7 @@ async fn create_team_invitation(
         ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50. Omit with `page` for full list.", minimum = 1, maximum = 500, example = 50, nullable = true),
     ),
     responses(
-        (status = 200, description = "Invitations for the team. `X-Total-Count` is the total before paging.", body = [TeamInvitation]),
+        (status = 200, description = "Invitations for the team, oldest first, each with its `status` (`pending`, `accepted`, `expired` or `revoked`). `X-Total-Count` is the total before paging.", body = [TeamInvitation]),
         (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
@@ -187,6 +204,40 @@ async fn delete_team_invitation(
     Ok(HttpResponse::NoContent().finish())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams/{team_id}/invitations/{invitation_id}/revoke",
+    params(
+        ("team_id" = String, Path, description = "Shared team identifier"),
+        ("invitation_id" = String, Path, description = "Invitation identifier")
+    ),
+    responses(
+        (status = 200, description = "Invitation revoked; it stays listed with status `revoked` but can no longer be accepted. Revoking again is a no-op.", body = TeamInvitation),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or invitation not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/{invitation_id}/revoke")]
+async fn revoke_team_invitation(
+    svc: Data<InvitationServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (team_id, invitation_id) = path.into_inner();
+    Ok(HttpResponse::Ok().json(
+        svc.revoke_invitation_for_user(&user, &team_id, &invitation_id)
+            .await?,
+    ))
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/teams/{team_id}/invitations/{invitation_id}/accept",
@@ -195,10 +246,12 @@ async fn delete_team_invitation(
         ("invitation_id" = String, Path, description = "Invitation identifier")
     ),
     responses(
-        (status = 200, description = "Current user is on the team (added as guest if needed)", body = Team),
+        (status = 200, description = "Current user is on the team with at least the invited role", body = Team),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
-        (status = 404, description = "Invitation not found or not usable", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The invitation is addressed to a different email", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Invitation not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Invitation is revoked, expired or used up", body = Problem, content_type = "application/problem+json"),
         (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
     ),
     tag = "Teams",
@@ -227,10 +280,12 @@ async fn accept_team_invitation_under_team(
         ("invitation_id" = String, Path, description = "Invitation identifier (deprecated path — prefer `/api/v1/teams/{team_id}/invitations/{invitation_id}/accept`)")
     ),
     responses(
-        (status = 200, description = "Current user is on the team (added as guest if needed). Deprecated route.", body = Team),
+        (status = 200, description = "Current user is on the team with at least the invited role. Deprecated route.", body = Team),
         (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
         (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
-        (status = 404, description = "Invitation not found or not usable", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The invitation is addressed to a different email", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Invitation not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Invitation is revoked, expired or used up", body = Problem, content_type = "application/problem+json"),
         (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
     ),
     tag = "Teams",
@@ -1,24 +1,33 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
+use chrono::Utc;
 use uuid::Uuid;
 
 use surrealdb::types::RecordId;
 
 use shared::api::ListQuery;
-use shared::team::{Team, TeamInvitation};
+use shared::team::{
+    ActivityAction, ActivityResourceType, CreateTeamInvitation, Team, TeamInvitation,
+    TeamInvitationStatus, TeamPermission,
+};
 use shared::user::User;
 use tracing::instrument;
 
 use crate::database::{Database, record_id_string};
 use crate::error::AppError;
+use crate::mail::MailService;
 
-use super::model::{invitation_thing, team_things_match};
+use super::model::{InvitationCreate, invitation_thing, team_things_match};
 use super::repository::TeamInvitationRepository;
 use super::surreal_repo::SurrealTeamInvitationRepo;
+use crate::resources::team::activity::{
+    ActivityRecorder, NewTeamActivity, SurrealTeamActivityRepo,
+};
 use crate::resources::team::model::{
-    DbTeamMember, effective_admin, is_public_resource, member_or_owner_readable,
-    team_fetched_to_stored, team_resource_or_reject_public, thing_user_id, user_thing,
+    DbTeamMember, TeamStored, effective_admin, is_public_resource, member_or_owner_readable,
+    role_permissions, role_str, team_fetched_to_stored, team_resource_or_reject_public,
+    thing_user_id, user_thing,
 };
 use crate::resources::team::repository::TeamRepository;
 use crate::resources::team::surreal_repo::SurrealTeamRepo;
@@ -33,37 +42,111 @@ fn audit_invitation_accepted(team_id: &str, invitation_id: &str, user_id: &str)
     );
 }
 
+/// Whether accepting an invitation for `invited` gives a member holding `current` more rights:
+/// `admin` outranks every other role, otherwise the invited role must grant every permission of
+/// the current one and at least one more.
+fn invitation_upgrades(current: &str, invited: &str, stored: &TeamStored) -> bool {
+    if current == "admin" {
+        return false;
+    }
+    if invited == "admin" {
+        return true;
+    }
+    let held: BTreeSet<TeamPermission> = role_permissions(current, stored).into_iter().collect();
+    let granted: BTreeSet<TeamPermission> = role_permissions(invited, stored).into_iter().collect();
+    granted.is_superset(&held) && granted.len() > held.len()
+}
+
+fn invitation_mail_body(team_name: &str, inviter: &str, invitation: &TeamInvitation) -> String {
+    let role = role_str(&invitation.role).replace('_', " ");
+    let expiry = invitation
+        .expires_at
+        .map(|at| {
+            format!(
+                "\nThe invitation is valid until {}.",
+                at.format("%Y-%m-%d %H:%M UTC")
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        "Hello,\n\n{inviter} invited you to join the team \"{team_name}\" on WorshipViewer as {role}.\n\nSign in to WorshipViewer with this email address and accept invitation {}.{expiry}\n\nIf you did not expect this invitation, you can ignore this message.\n\nBlessings,\nThe WorshipViewer Team",
+        invitation.id
+    )
+}
+
 /// Application service for team invitation management.
 #[derive(Clone)]
-pub struct InvitationService<R, IR> {
+pub struct InvitationService<R, IR, A> {
     pub team_repo: R,
     pub inv_repo: IR,
+    pub activity: A,
 }
 
-impl<R, IR> InvitationService<R, IR> {
-    pub fn new(team_repo: R, inv_repo: IR) -> Self {
+impl<R, IR, A> InvitationService<R, IR, A> {
+    pub fn new(team_repo: R, inv_repo: IR, activity: A) -> Self {
         Self {
             team_repo,
             inv_repo,
+            activity,
         }
     }
 }
 
-impl<R: TeamRepository, IR: TeamInvitationRepository> InvitationService<R, IR> {
-    #[instrument(level = "debug", err, skip(self, user))]
+impl<R: TeamRepository, IR: TeamInvitationRepository, A: ActivityRecorder>
+    InvitationService<R, IR, A>
+{
+    #[instrument(level = "debug", err, skip(self, user, request))]
     pub async fn create_invitation_for_user(
         &self,
         user: &User,
         team_id: &str,
+        request: CreateTeamInvitation,
     ) -> Result<TeamInvitation, AppError> {
         let team_thing = self
             .assert_team_admin_for_invitations(&user.id, team_id)
             .await?;
+        let create =
+            InvitationCreate::from_request(team_thing, user_thing(&user.id), request, Utc::now())?;
         let inv_id = Uuid::new_v4().to_string();
-        self.inv_repo
-            .create_invitation(team_thing, user_thing(&user.id), &inv_id)
+        self.inv_repo.create_invitation(create, &inv_id).await?;
+        let invitation = self.get_invitation_for_user(user, team_id, &inv_id).await?;
+        crate::audit!(
+            "audit.team.invitation.created",
+            team_id = tracing::field::display(team_id),
+            invitation_id = tracing::field::display(&invitation.id),
+            role = tracing::field::display(role_str(&invitation.role)),
+            emailed = invitation.email.is_some(),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "invitation created"
+        );
+        Ok(invitation)
+    }
+
+    /// Emails an invitation that targets an address; a no-op for link invitations. When the
+    /// mail cannot be sent the invitation is deleted again, so the admin can simply retry.
+    #[instrument(level = "debug", err, skip(self, mail, user, invitation))]
+    pub async fn send_invitation_email(
+        &self,
+        mail: &MailService,
+        user: &User,
+        invitation: &TeamInvitation,
+    ) -> Result<(), AppError> {
+        let Some(to) = invitation.email.as_deref() else {
+            return Ok(());
+        };
+        let team = self
+            .team_repo
+            .load_team_display(&invitation.team_id)
             .await?;
-        self.get_invitation_for_user(user, team_id, &inv_id).await
+        let body = invitation_mail_body(&team.name, &user.email, invitation);
+        let subject = format!("You are invited to join {} on WorshipViewer", team.name);
+        if let Err(e) = mail.send(to, &subject, &body).await {
+            if let Err(cleanup) = self.inv_repo.delete_invitation(&invitation.id).await {
+                tracing::warn!(error = %cleanup, invitation_id = %invitation.id, "failed to remove unsent invitation");
+            }
+            return Err(e);
+        }
+        Ok(())
     }
 
     #[instrument(level = "debug", err, skip(self, user, pagination))]
@@ -139,11 +222,59 @@ impl<R: TeamRepository, IR: TeamInvitationRepository> InvitationService<R, IR> {
         Ok(())
     }
 
+    /// Revoked invitations stay listed (status `revoked`) but can no longer be accepted.
+    /// Revoking twice is a no-op.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn revoke_invitation_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        invitation_id: &str,
+    ) -> Result<TeamInvitation, AppError> {
+        let invitation = self
+            .get_invitation_for_user(user, team_id, invitation_id)
+            .await?;
+        if self.inv_repo.revoke_invitation(&invitation.id).await? {
+            crate::audit!(
+                "audit.team.invitation.revoked",
+                team_id = tracing::field::display(team_id),
+                invitation_id = tracing::field::display(&invitation.id),
+                actor_user_id = tracing::field::display(&user.id)
+                ; "invitation revoked"
+            );
+        }
+        self.get_invitation_for_user(user, team_id, &invitation.id)
+            .await
+    }
+
     #[instrument(level = "debug", err, skip(self, user))]
     pub async fn accept_invitation_for_user(
         &self,
         user: &User,
         invitation_id: &str,
+    ) -> Result<Team, AppError> {
+        self.accept_invitation(user, None, invitation_id).await
+    }
+
+    /// Like [`accept_invitation_for_user`], but ensures the invitation belongs to `team_id`.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn accept_invitation_for_user_on_team(
+        &self,
+        user: &User,
+        team_id: &str,
+        invitation_id: &str,
+    ) -> Result<Team, AppError> {
+        self.accept_invitation(user, Some(team_id), invitation_id)
+            .await
+    }
+
+    /// Joins `user` to the invitation's team with the invited role. Every rejection happens
+    /// before a use is taken, so a failed accept never burns a single-use invitation.
+    async fn accept_invitation(
+        &self,
+        user: &User,
+        team_id: Option<&str>,
+        invitation_id: &str,
     ) -> Result<Team, AppError> {
         let inv_thing = invitation_thing(invitation_id)?;
         let row = self
@@ -151,16 +282,41 @@ impl<R: TeamRepository, IR: TeamInvitationRepository> InvitationService<R, IR> {
             .get_invitation_with_team(&record_id_string(&inv_thing))
             .await?
             .ok_or_else(|| AppError::NotFound("invitation not found".into()))?;
+        if team_id.is_some_and(|id| id != record_id_string(&row.team.id)) {
+            return Err(AppError::NotFound("invitation not found".into()));
+        }
 
-        let team_row = row.team;
         let res = (
-            team_row.id.table.to_string(),
-            crate::database::record_id_string(&team_row.id),
+            row.team.id.table.to_string(),
+            crate::database::record_id_string(&row.team.id),
         );
         if is_public_resource(&res) {
             return Err(AppError::NotFound("invitation not found".into()));
         }
 
+        // A user who already used the invitation may repeat the accept until it is revoked.
+        let user_ref = user_thing(&user.id);
+        let already_used = row.accepted_by_user(&user_ref);
+        match row.status(Utc::now()) {
+            TeamInvitationStatus::Pending => {}
+            TeamInvitationStatus::Revoked => {
+                return Err(AppError::conflict("invitation has been revoked"));
+            }
+            _ if already_used => {}
+            TeamInvitationStatus::Expired => {
+                return Err(AppError::conflict("invitation has expired"));
+            }
+            TeamInvitationStatus::Accepted => {
+                return Err(AppError::conflict("invitation has already been used"));
+            }
+        }
+        if let Some(email) = &row.email
+            && !email.eq_ignore_ascii_case(user.email.trim())
+        {
+            return Err(AppError::forbidden());
+        }
+
+        let team_row = row.team;
         let stored = team_fetched_to_stored(&team_row)?;
 
         let team_id_str = crate::database::record_id_string(&team_row.id);
@@ -179,23 +335,33 @@ impl<R: TeamRepository, IR: TeamInvitationRepository> InvitationService<R, IR> {
         for m in &stored.members {
             map.insert(thing_user_id(&m.user), m.clone());
         }
-        let needs_guest = match map.get(&uid).map(|m| m.role.as_str()) {
-            Some("admin") | Some("content_maintainer") | Some("guest") => false,
-            None => true,
-            Some(_) => true,
-        };
-
-        if !needs_guest {
+        let current_role = map.get(&uid).map(|m| m.role.clone());
+        // Existing members keep their role unless the invitation strictly adds rights; accepting
+        // never removes a permission.
+        if current_role
+            .as_deref()
+            .is_some_and(|r| !invitation_upgrades(r, &row.role, &stored))
+        {
             let team = self.team_repo.load_team_display(&team_id_str).await?;
             audit_invitation_accepted(&team_id_str, invitation_id, &user.id);
             return Ok(team);
         }
 
+        if !already_used
+            && !self
+                .inv_repo
+                .claim_invitation_use(&record_id_string(&inv_thing), user_ref.clone())
+                .await?
+        {
+            // Lost a race against another accept, a revoke or the expiry.
+            return Err(AppError::conflict("invitation is no longer valid"));
+        }
+
         map.insert(
             uid.clone(),
             DbTeamMember {
-                user: user_thing(&uid),
-                role: "guest".to_owned(),
+                user: user_ref,
+                role: row.role.clone(),
             },
         );
         let members: Vec<DbTeamMember> = map.into_values().collect();
@@ -209,21 +375,23 @@ impl<R: TeamRepository, IR: TeamInvitationRepository> InvitationService<R, IR> {
 
         let team = self.team_repo.load_team_display(&team_id_str).await?;
         audit_invitation_accepted(&team_id_str, invitation_id, &user.id);
-        Ok(team)
-    }
-
-    /// Like [`accept_invitation_for_user`], but ensures the invitation belongs to `team_id`.
-    #[instrument(level = "debug", err, skip(self, user))]
-    pub async fn accept_invitation_for_user_on_team(
-        &self,
-        user: &User,
-        team_id: &str,
-        invitation_id: &str,
-    ) -> Result<Team, AppError> {
-        let team = self.accept_invitation_for_user(user, invitation_id).await?;
-        if team.id != team_id {
-            return Err(AppError::NotFound("invitation not found".into()));
-        }
+        let action = if current_role.is_some() {
+            ActivityAction::MemberRoleChanged
+        } else {
+            ActivityAction::MemberAdded
+        };
+        self.activity
+            .record_activity_or_warn(
+                NewTeamActivity::new(
+                    &team_id_str,
+                    &user.id,
+                    ActivityResourceType::Member,
+                    &user.id,
+                    action,
+                )
+                .with_title(Some(&user.email)),
+            )
+            .await;
         Ok(team)
     }
 
@@ -255,13 +423,15 @@ impl<R: TeamRepository, IR: TeamInvitationRepository> InvitationService<R, IR> {
 }
 
 /// Production type alias used in HTTP wiring.
-pub type InvitationServiceHandle = InvitationService<SurrealTeamRepo, SurrealTeamInvitationRepo>;
+pub type InvitationServiceHandle =
+    InvitationService<SurrealTeamRepo, SurrealTeamInvitationRepo, SurrealTeamActivityRepo>;
 
 impl InvitationServiceHandle {
     pub fn build(db: Arc<Database>) -> Self {
         InvitationService::new(
             SurrealTeamRepo::new(db.clone()),
             SurrealTeamInvitationRepo::new(db.clone()),
+            SurrealTeamActivityRepo::new(db),
         )
     }
 }
@@ -274,19 +444,20 @@ mod tests {
     use surrealdb::types::{Datetime, RecordId};
 
     use shared::api::ListQuery;
-    use shared::team::Team;
+    use shared::team::{CreateTeamInvitation, Team};
     use shared::user::User;
 
     use crate::error::AppError;
     use crate::resources::team::model::{
-        DbTeamMember, TeamCreatePayload, TeamFetched, TeamMemberFetched,
+        DbTeamMember, DbTeamRole, TeamCreatePayload, TeamFetched, TeamMemberFetched,
     };
     use crate::resources::team::repository::TeamRepository;
     use crate::resources::user::UserRecord;
 
-    use super::super::model::{InvitationAcceptRow, InvitationRow};
+    use super::super::model::{InvitationAcceptRow, InvitationCreate, InvitationRow};
     use super::super::repository::TeamInvitationRepository;
     use super::InvitationService;
+    use crate::resources::team::activity::DiscardActivity;
 
     // ── Test data helpers ─────────────────────────────────────────────────────
 
@@ -317,6 +488,8 @@ mod tests {
             name: "Shared Team".to_owned(),
             owner: None,
             members,
+            organization: None,
+            roles: Vec::new(),
         }
     }
 
@@ -326,6 +499,8 @@ mod tests {
             name: "Personal".to_owned(),
             owner: Some(UserRecord::from_user(make_user(owner_id))),
             members: vec![],
+            organization: None,
+            roles: Vec::new(),
         }
     }
 
@@ -335,6 +510,8 @@ mod tests {
             name: "Public".to_owned(),
             owner: None,
             members: vec![],
+            organization: None,
+            roles: Vec::new(),
         }
     }
 
@@ -344,6 +521,8 @@ mod tests {
             owner: None,
             name: "Shared Team".to_owned(),
             members: vec![],
+            organization_id: None,
+            roles: Vec::new(),
         }
     }
 
@@ -353,11 +532,25 @@ mod tests {
             team: team_thing(for_team_id),
             created_by: UserRecord::from_user(make_user("creator")),
             created_at: Datetime::default(),
+            email: None,
+            role: "guest".to_owned(),
+            expires_at: None,
+            max_uses: None,
+            accepted_by: vec![],
+            revoked_at: None,
         }
     }
 
     fn inv_accept_row(_inv_id: &str, team: TeamFetched) -> InvitationAcceptRow {
-        InvitationAcceptRow { team }
+        InvitationAcceptRow {
+            team,
+            email: None,
+            role: "guest".to_owned(),
+            expires_at: None,
+            max_uses: None,
+            accepted_by: vec![],
+            revoked_at: None,
+        }
     }
 
     // ── MockTeamRepo ──────────────────────────────────────────────────────────
@@ -429,6 +622,14 @@ mod tests {
             unreachable!("not used in invitation tests")
         }
 
+        async fn update_team_roles(
+            &self,
+            _resource: (String, String),
+            _roles: Vec<DbTeamRole>,
+        ) -> Result<(), AppError> {
+            unreachable!("not used in invitation tests")
+        }
+
         async fn delete_team_record(&self, _resource: (String, String)) -> Result<(), AppError> {
             unreachable!("not used in invitation tests")
         }
@@ -445,6 +646,8 @@ mod tests {
         inv_with_team: Option<InvitationAcceptRow>,
         delete_ok: bool,
         list: Vec<InvitationRow>,
+        claim_ok: bool,
+        claim_called: Arc<Mutex<bool>>,
     }
 
     impl MockInvRepo {
@@ -454,6 +657,8 @@ mod tests {
                 inv_with_team: None,
                 delete_ok: false,
                 list: vec![],
+                claim_ok: true,
+                claim_called: Arc::new(Mutex::new(false)),
             }
         }
 
@@ -463,6 +668,8 @@ mod tests {
                 inv_with_team: None,
                 delete_ok: true,
                 list: vec![],
+                claim_ok: true,
+                claim_called: Arc::new(Mutex::new(false)),
             }
         }
 
@@ -472,6 +679,8 @@ mod tests {
                 inv_with_team: Some(row),
                 delete_ok: false,
                 list: vec![],
+                claim_ok: true,
+                claim_called: Arc::new(Mutex::new(false)),
             }
         }
 
@@ -481,6 +690,8 @@ mod tests {
                 inv_with_team: None,
                 delete_ok: false,
                 list: rows,
+                claim_ok: true,
+                claim_called: Arc::new(Mutex::new(false)),
             }
         }
     }
@@ -489,8 +700,7 @@ mod tests {
     impl TeamInvitationRepository for MockInvRepo {
         async fn create_invitation(
             &self,
-            _team: RecordId,
-            _created_by: RecordId,
+            _create: InvitationCreate,
             _inv_id: &str,
         ) -> Result<(), AppError> {
             Ok(())
@@ -514,13 +724,26 @@ mod tests {
         ) -> Result<Option<InvitationAcceptRow>, AppError> {
             Ok(self.inv_with_team.clone())
         }
+
+        async fn claim_invitation_use(
+            &self,
+            _inv_id: &str,
+            _user: RecordId,
+        ) -> Result<bool, AppError> {
+            *self.claim_called.lock().unwrap() = true;
+            Ok(self.claim_ok)
+        }
+
+        async fn revoke_invitation(&self, _inv_id: &str) -> Result<bool, AppError> {
+            Ok(self.invitation.is_some())
+        }
     }
 
     fn make_svc(
         team: MockTeamRepo,
         inv: MockInvRepo,
-    ) -> InvitationService<MockTeamRepo, MockInvRepo> {
-        InvitationService::new(team, inv)
+    ) -> InvitationService<MockTeamRepo, MockInvRepo, DiscardActivity> {
+        InvitationService::new(team, inv, DiscardActivity)
     }
 
     // ── Slice 2B: CRUD access control ─────────────────────────────────────────
@@ -534,7 +757,9 @@ mod tests {
             MockTeamRepo::with(team),
             MockInvRepo::with_inv(inv_row("any", "t1")),
         );
-        let r = svc.create_invitation_for_user(&user, "t1").await;
+        let r = svc
+            .create_invitation_for_user(&user, "t1", CreateTeamInvitation::default())
+            .await;
         assert!(r.is_ok());
     }
 
@@ -547,7 +772,9 @@ mod tests {
             MockTeamRepo::with(team),
             MockInvRepo::with_inv(inv_row("any", "t1")),
         );
-        let r = svc.create_invitation_for_user(&user, "t1").await;
+        let r = svc
+            .create_invitation_for_user(&user, "t1", CreateTeamInvitation::default())
+            .await;
         assert!(r.is_ok());
     }
 
@@ -556,7 +783,9 @@ mod tests {
     async fn blc_tinv_001_create_public_team_rejected() {
         let user = make_user("u1");
         let svc = make_svc(MockTeamRepo::missing(), MockInvRepo::empty());
-        let r = svc.create_invitation_for_user(&user, "public").await;
+        let r = svc
+            .create_invitation_for_user(&user, "public", CreateTeamInvitation::default())
+            .await;
         assert!(matches!(r, Err(AppError::NotFound(_))));
     }
 
@@ -566,7 +795,9 @@ mod tests {
         let user = make_user("u1");
         let team = shared_team("t1", vec![member_fetched("u1", "content_maintainer")]);
         let svc = make_svc(MockTeamRepo::with(team), MockInvRepo::empty());
-        let r = svc.create_invitation_for_user(&user, "t1").await;
+        let r = svc
+            .create_invitation_for_user(&user, "t1", CreateTeamInvitation::default())
+            .await;
         assert!(matches!(r, Err(AppError::Forbidden)));
     }
 
@@ -576,7 +807,9 @@ mod tests {
         let user = make_user("u1");
         let team = shared_team("t1", vec![member_fetched("u1", "guest")]);
         let svc = make_svc(MockTeamRepo::with(team), MockInvRepo::empty());
-        let r = svc.create_invitation_for_user(&user, "t1").await;
+        let r = svc
+            .create_invitation_for_user(&user, "t1", CreateTeamInvitation::default())
+            .await;
         assert!(matches!(r, Err(AppError::Forbidden)));
     }
 
@@ -586,7 +819,9 @@ mod tests {
         let user = make_user("u1");
         let team = shared_team("t1", vec![member_fetched("u2", "admin")]);
         let svc = make_svc(MockTeamRepo::with(team), MockInvRepo::empty());
-        let r = svc.create_invitation_for_user(&user, "t1").await;
+        let r = svc
+            .create_invitation_for_user(&user, "t1", CreateTeamInvitation::default())
+            .await;
         assert!(matches!(r, Err(AppError::NotFound(_))));
     }
 
@@ -823,12 +1058,164 @@ mod tests {
         assert!(matches!(r, Err(AppError::NotFound(_))));
     }
 
+    fn accept_row_with(
+        team: TeamFetched,
+        f: impl FnOnce(&mut InvitationAcceptRow),
+    ) -> InvitationAcceptRow {
+        let mut row = inv_accept_row("inv1", team);
+        f(&mut row);
+        row
+    }
+
+    /// BLC-TINV-004: a revoked invitation cannot be accepted, not even by a previous user.
+    #[tokio::test]
+    async fn blc_tinv_004_accept_revoked_conflict() {
+        let user = make_user("u1");
+        let team = shared_team("t1", vec![member_fetched("u2", "admin")]);
+        let row = accept_row_with(team.clone(), |r| {
+            r.revoked_at = Some(Datetime::default());
+            r.accepted_by = vec![RecordId::new("user", "u1")];
+        });
+        let mock_team = MockTeamRepo::with(team);
+        let update_called = mock_team.update_members_called.clone();
+        let svc = make_svc(mock_team, MockInvRepo::with_accept(row));
+        let r = svc.accept_invitation_for_user(&user, "inv1").await;
+        assert!(matches!(r, Err(AppError::Conflict(_))));
+        assert!(!*update_called.lock().unwrap());
+    }
+
+    /// BLC-TINV-003: expired and used-up invitations are rejected before a use is taken.
+    #[tokio::test]
+    async fn blc_tinv_003_accept_expired_or_used_up_conflict() {
+        let user = make_user("u1");
+        let team = shared_team("t1", vec![member_fetched("u2", "admin")]);
+        let expired = accept_row_with(team.clone(), |r| {
+            r.expires_at = Some(Datetime::from(
+                chrono::Utc::now() - chrono::Duration::hours(1),
+            ));
+        });
+        let used_up = accept_row_with(team.clone(), |r| {
+            r.max_uses = Some(1);
+            r.accepted_by = vec![RecordId::new("user", "u3")];
+        });
+        for row in [expired, used_up] {
+            let inv = MockInvRepo::with_accept(row);
+            let claim_called = inv.claim_called.clone();
+            let svc = make_svc(MockTeamRepo::with(team.clone()), inv);
+            let r = svc.accept_invitation_for_user(&user, "inv1").await;
+            assert!(matches!(r, Err(AppError::Conflict(_))));
+            assert!(!*claim_called.lock().unwrap());
+        }
+    }
+
+    /// BLC-TINV-003: losing the race for the last use yields a conflict and no membership change.
+    #[tokio::test]
+    async fn blc_tinv_003_accept_claim_lost_conflict() {
+        let user = make_user("u1");
+        let team = shared_team("t1", vec![member_fetched("u2", "admin")]);
+        let mut inv = MockInvRepo::with_accept(inv_accept_row("inv1", team.clone()));
+        inv.claim_ok = false;
+        let mock_team = MockTeamRepo::with(team);
+        let update_called = mock_team.update_members_called.clone();
+        let svc = make_svc(mock_team, inv);
+        let r = svc.accept_invitation_for_user(&user, "inv1").await;
+        assert!(matches!(r, Err(AppError::Conflict(_))));
+        assert!(!*update_called.lock().unwrap());
+    }
+
+    /// BLC-TINV-015: an email invitation is only accepted by the user with that email.
+    #[tokio::test]
+    async fn blc_tinv_015_accept_email_mismatch_forbidden() {
+        let mut user = make_user("u1");
+        user.email = "other@example.com".into();
+        let team = shared_team("t1", vec![member_fetched("u2", "admin")]);
+        let row = accept_row_with(team.clone(), |r| {
+            r.email = Some("test@example.com".into());
+            r.max_uses = Some(1);
+        });
+        let inv = MockInvRepo::with_accept(row.clone());
+        let claim_called = inv.claim_called.clone();
+        let svc = make_svc(MockTeamRepo::with(team.clone()), inv);
+        let r = svc.accept_invitation_for_user(&user, "inv1").await;
+        assert!(matches!(r, Err(AppError::Forbidden)));
+        assert!(!*claim_called.lock().unwrap());
+
+        user.email = "Test@Example.com".into();
+        let svc = make_svc(MockTeamRepo::with(team), MockInvRepo::with_accept(row));
+        assert!(svc.accept_invitation_for_user(&user, "inv1").await.is_ok());
+    }
+
+    /// BLC-TINV-010, BLC-TINV-011: the invited role upgrades a guest but an existing
+    /// content_maintainer keeps its role against a guest invitation without using it up.
+    #[tokio::test]
+    async fn blc_tinv_010_accept_upgrades_to_invited_role() {
+        let user = make_user("u1");
+        let team = shared_team(
+            "t1",
+            vec![member_fetched("u2", "admin"), member_fetched("u1", "guest")],
+        );
+        let row = accept_row_with(team.clone(), |r| r.role = "content_maintainer".into());
+        let mock_team = MockTeamRepo::with(team);
+        let update_called = mock_team.update_members_called.clone();
+        let inv = MockInvRepo::with_accept(row);
+        let claim_called = inv.claim_called.clone();
+        let svc = make_svc(mock_team, inv);
+        assert!(svc.accept_invitation_for_user(&user, "inv1").await.is_ok());
+        assert!(*update_called.lock().unwrap());
+        assert!(*claim_called.lock().unwrap());
+
+        let team = shared_team("t1", vec![member_fetched("u1", "content_maintainer")]);
+        let inv = MockInvRepo::with_accept(inv_accept_row("inv1", team.clone()));
+        let claim_called = inv.claim_called.clone();
+        let svc = make_svc(MockTeamRepo::with(team), inv);
+        assert!(svc.accept_invitation_for_user(&user, "inv1").await.is_ok());
+        assert!(!*claim_called.lock().unwrap());
+    }
+
+    /// BLC-TINV-008: accepting under the wrong team path is rejected before anything changes.
+    #[tokio::test]
+    async fn blc_tinv_008_accept_on_wrong_team_not_found() {
+        let user = make_user("u1");
+        let team = shared_team("t1", vec![member_fetched("u2", "admin")]);
+        let inv = MockInvRepo::with_accept(inv_accept_row("inv1", team.clone()));
+        let claim_called = inv.claim_called.clone();
+        let svc = make_svc(MockTeamRepo::with(team), inv);
+        let r = svc
+            .accept_invitation_for_user_on_team(&user, "t2", "inv1")
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+        assert!(!*claim_called.lock().unwrap());
+    }
+
+    /// BLC-TINV-004: admin can revoke; non-admins cannot.
+    #[tokio::test]
+    async fn blc_tinv_004_revoke_admin_only() {
+        let team = shared_team(
+            "t1",
+            vec![member_fetched("u1", "admin"), member_fetched("u2", "guest")],
+        );
+        let svc = make_svc(
+            MockTeamRepo::with(team),
+            MockInvRepo::with_inv(inv_row("inv1", "t1")),
+        );
+        assert!(
+            svc.revoke_invitation_for_user(&make_user("u1"), "t1", "inv1")
+                .await
+                .is_ok()
+        );
+        let r = svc
+            .revoke_invitation_for_user(&make_user("u2"), "t1", "inv1")
+            .await;
+        assert!(matches!(r, Err(AppError::Forbidden)));
+    }
+
     mod integration {
         use crate::error::AppError;
         use crate::test_helpers::{
             TeamFixture, create_user, invitation_service, team_service, test_db,
         };
         use shared::api::ListQuery;
+        use shared::team::{CreateTeamInvitation, TeamInvitationStatus, TeamRole};
 
         /// BLC-TINV-001, BLC-TINV-006, BLC-TINV-007: admin creates invitation; id is non-empty UUID.
         #[tokio::test]
@@ -837,7 +1224,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             assert!(!inv.id.is_empty());
@@ -851,7 +1242,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.owner, &fx.personal_team_id)
+                .create_invitation_for_user(
+                    &fx.owner,
+                    &fx.personal_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             assert_eq!(inv.team_id, fx.personal_team_id);
@@ -864,7 +1259,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.owner, &fx.personal_team_id)
+                .create_invitation_for_user(
+                    &fx.owner,
+                    &fx.personal_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             let team = svc
@@ -886,7 +1285,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let r = svc
-                .create_invitation_for_user(&fx.writer, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.writer,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await;
             assert!(matches!(r, Err(AppError::Forbidden)));
         }
@@ -897,9 +1300,13 @@ mod tests {
             let db = test_db().await.expect("db");
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
-            svc.create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
-                .await
-                .expect("create");
+            svc.create_invitation_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                CreateTeamInvitation::default(),
+            )
+            .await
+            .expect("create");
             let (list, _) = svc
                 .list_invitations_for_user(&fx.admin_user, &fx.shared_team_id, ListQuery::default())
                 .await
@@ -914,7 +1321,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             let fetched = svc
@@ -931,7 +1342,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             let team_svc = team_service(&db);
@@ -958,7 +1373,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             svc.delete_invitation_for_user(&fx.admin_user, &fx.shared_team_id, &inv.id)
@@ -977,7 +1396,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             svc.delete_invitation_for_user(&fx.admin_user, &fx.shared_team_id, &inv.id)
@@ -998,7 +1421,11 @@ mod tests {
             let svc = invitation_service(&db);
             let new_user = create_user(&db, "tinv010@test.local").await.expect("u");
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             let team = svc
@@ -1020,7 +1447,11 @@ mod tests {
             let svc = invitation_service(&db);
             let new_user = create_user(&db, "tinv005@test.local").await.expect("u");
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             svc.accept_invitation_for_user(&new_user, &inv.id)
@@ -1040,7 +1471,11 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             let team = svc
@@ -1059,6 +1494,88 @@ mod tests {
             );
         }
 
+        /// BLC-TINV-011: a member with a team-defined role keeps it against an invitation that
+        /// grants fewer permissions, and only moves to one that grants all of them and more.
+        #[tokio::test]
+        async fn blc_tinv_011_custom_role_member_not_downgraded_integration() {
+            use shared::team::{CreateTeamRole, TeamMemberInput, TeamPermission, TeamUserRef};
+
+            let db = test_db().await.expect("db");
+            let fx = TeamFixture::build(&db).await.expect("fixture");
+            let teams = team_service(&db);
+            let planner = teams
+                .create_team_role_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamRole {
+                        name: "Planner".into(),
+                        permissions: vec![TeamPermission::SetlistsWrite],
+                    },
+                )
+                .await
+                .expect("define role");
+            let planner_role = TeamRole::Custom(planner.id.clone());
+            let member = |user: &crate::resources::User, role: TeamRole| TeamMemberInput {
+                user: TeamUserRef {
+                    id: user.id.clone(),
+                },
+                role,
+            };
+            teams
+                .update_team_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    shared::team::UpdateTeam {
+                        name: "Fixture Shared Team".into(),
+                        members: Some(vec![
+                            member(&fx.admin_user, TeamRole::Admin),
+                            member(&fx.writer, planner_role.clone()),
+                        ]),
+                    },
+                )
+                .await
+                .expect("assign custom role");
+
+            let svc = invitation_service(&db);
+            let role_after_accepting = |role: TeamRole| {
+                let svc = svc.clone();
+                let admin = fx.admin_user.clone();
+                let writer = fx.writer.clone();
+                let team_id = fx.shared_team_id.clone();
+                async move {
+                    let inv = svc
+                        .create_invitation_for_user(
+                            &admin,
+                            &team_id,
+                            CreateTeamInvitation {
+                                role: Some(role),
+                                ..Default::default()
+                            },
+                        )
+                        .await
+                        .expect("create");
+                    let team = svc
+                        .accept_invitation_for_user(&writer, &inv.id)
+                        .await
+                        .expect("accept");
+                    team.members
+                        .into_iter()
+                        .find(|m| m.user.id == writer.id)
+                        .map(|m| m.role)
+                }
+            };
+
+            assert_eq!(
+                role_after_accepting(TeamRole::Guest).await,
+                Some(planner_role.clone()),
+                "a guest invitation must not strip the custom role's permissions"
+            );
+            assert_eq!(
+                role_after_accepting(TeamRole::ContentMaintainer).await,
+                Some(TeamRole::ContentMaintainer)
+            );
+        }
+
         /// BLC-TINV-012: guest accepting the same invitation twice results in exactly one entry.
         #[tokio::test]
         async fn blc_tinv_012_accept_twice_no_duplicate() {
@@ -1067,7 +1584,11 @@ mod tests {
             let svc = invitation_service(&db);
             let new_user = create_user(&db, "tinv012@test.local").await.expect("u");
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             svc.accept_invitation_for_user(&new_user, &inv.id)
@@ -1094,7 +1615,11 @@ mod tests {
             let user_a = create_user(&db, "tinv013a@test.local").await.expect("a");
             let user_b = create_user(&db, "tinv013b@test.local").await.expect("b");
             let inv = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("create");
             svc.accept_invitation_for_user(&user_a, &inv.id)
@@ -1115,14 +1640,102 @@ mod tests {
             let fx = TeamFixture::build(&db).await.expect("fixture");
             let svc = invitation_service(&db);
             let inv1 = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("inv1");
             let inv2 = svc
-                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id)
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation::default(),
+                )
                 .await
                 .expect("inv2");
             assert_ne!(inv1.id, inv2.id);
         }
+
+        /// BLC-TINV-013: a link with `max_uses` stops admitting users once used up.
+        #[tokio::test]
+        async fn blc_tinv_013_max_uses_limits_accepts() {
+            let db = test_db().await.expect("db");
+            let fx = TeamFixture::build(&db).await.expect("fixture");
+            let svc = invitation_service(&db);
+            let user_a = create_user(&db, "tinv013max_a@test.local")
+                .await
+                .expect("a");
+            let user_b = create_user(&db, "tinv013max_b@test.local")
+                .await
+                .expect("b");
+            let inv = svc
+                .create_invitation_for_user(
+                    &fx.admin_user,
+                    &fx.shared_team_id,
+                    CreateTeamInvitation {
+                        max_uses: Some(1),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .expect("create");
+            svc.accept_invitation_for_user(&user_a, &inv.id)
+                .await
+                .expect("a accept");
+            svc.accept_invitation_for_user(&user_a, &inv.id)
+                .await
+                .expect("a accepts again");
+            let r = svc.accept_invitation_for_user(&user_b, &inv.id).await;
+            assert!(matches!(r, Err(AppError::Conflict(_))));
+            let fetched = svc
+                .get_invitation_for_user(&fx.admin_user, &fx.shared_team_id, &inv.id)
+                .await
+                .expect("get");
+            assert_eq!(fetched.use_count, 1);
+            assert_eq!(fetched.status, TeamInvitationStatus::Accepted);
+        }
+
+        /// BLC-TINV-004, BLC-TINV-010: revocation blocks accepts; the invited role is granted.
+        #[tokio::test]
+        async fn blc_tinv_004_revoke_blocks_accept_integration() {
+            let db = test_db().await.expect("db");
+            let fx = TeamFixture::build(&db).await.expect("fixture");
+            let svc = invitation_service(&db);
+            let new_user = create_user(&db, "tinv004revoke@test.local")
+                .await
+                .expect("u");
+            let request = CreateTeamInvitation {
+                role: Some(TeamRole::ContentMaintainer),
+                ..Default::default()
+            };
+            let revoked = svc
+                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id, request.clone())
+                .await
+                .expect("create");
+            let revoked = svc
+                .revoke_invitation_for_user(&fx.admin_user, &fx.shared_team_id, &revoked.id)
+                .await
+                .expect("revoke");
+            assert_eq!(revoked.status, TeamInvitationStatus::Revoked);
+            let r = svc.accept_invitation_for_user(&new_user, &revoked.id).await;
+            assert!(matches!(r, Err(AppError::Conflict(_))));
+
+            let inv = svc
+                .create_invitation_for_user(&fx.admin_user, &fx.shared_team_id, request)
+                .await
+                .expect("create");
+            let team = svc
+                .accept_invitation_for_user(&new_user, &inv.id)
+                .await
+                .expect("accept");
+            let role = team
+                .members
+                .iter()
+                .find(|m| m.user.id == new_user.id)
+                .map(|m| m.role.clone());
+            assert_eq!(role, Some(TeamRole::ContentMaintainer));
+        }
     }
 }
@@ -35,14 +35,11 @@ impl SurrealTeamInvitationRepo {
 impl TeamInvitationRepository for SurrealTeamInvitationRepo {
     async fn create_invitation(
         &self,
-        team: RecordId,
-        created_by: RecordId,
+        create: InvitationCreate,
         inv_id: &str,
     ) -> Result<(), AppError> {
-        let create = InvitationCreate { team, created_by };
         let created: Option<InvitationCreated> = self
             .inner()
-            .db
             .create(("team_invitation", inv_id))
             .content(create)
             .await
@@ -62,7 +59,6 @@ impl TeamInvitationRepository for SurrealTeamInvitationRepo {
     async fn list_invitations(&self, team: RecordId) -> Result<Vec<InvitationRow>, AppError> {
         Ok(self
             .inner()
-            .db
             .query(
                 "SELECT * FROM team_invitation WHERE team = $team ORDER BY created_at ASC FETCH created_by",
             )
@@ -75,7 +71,6 @@ impl TeamInvitationRepository for SurrealTeamInvitationRepo {
         let inv_thing = invitation_thing_from_id(inv_id)?;
         Ok(self
             .inner()
-            .db
             .query("SELECT * FROM $iid FETCH created_by")
             .bind(("iid", inv_thing))
             .await?
@@ -87,7 +82,6 @@ impl TeamInvitationRepository for SurrealTeamInvitationRepo {
         let key = crate::database::record_id_string(&inv_thing);
         let deleted: Option<InvitationCreated> = self
             .inner()
-            .db
             .delete(("team_invitation", key.as_str()))
             .await?;
         Ok(deleted.is_some())
@@ -102,12 +96,42 @@ impl TeamInvitationRepository for SurrealTeamInvitationRepo {
         // Personal teams also need `team.owner` expanded to `UserRecord`.
         Ok(self
             .inner()
-            .db
             .query("SELECT * FROM $iid FETCH created_by, team, team.members.user, team.owner")
             .bind(("iid", inv_thing))
             .await?
             .take::<Option<InvitationAcceptRow>>(0)?)
     }
+
+    async fn claim_invitation_use(&self, inv_id: &str, user: RecordId) -> Result<bool, AppError> {
+        let inv_thing = invitation_thing_from_id(inv_id)?;
+        // Checked and written in one statement so concurrent accepts cannot exceed `max_uses`.
+        let claimed: Vec<InvitationCreated> = self
+            .inner()
+            .query(
+                "UPDATE $iid SET accepted_by += $uid \
+                 WHERE revoked_at = NONE \
+                 AND (expires_at = NONE OR expires_at > time::now()) \
+                 AND (max_uses = NONE OR array::len(accepted_by) < max_uses) \
+                 AND $uid NOTINSIDE accepted_by \
+                 RETURN id",
+            )
+            .bind(("iid", inv_thing))
+            .bind(("uid", user))
+            .await?
+            .take(0)?;
+        Ok(!claimed.is_empty())
+    }
+
+    async fn revoke_invitation(&self, inv_id: &str) -> Result<bool, AppError> {
+        let inv_thing = invitation_thing_from_id(inv_id)?;
+        let revoked: Vec<InvitationCreated> = self
+            .inner()
+            .query("UPDATE $iid SET revoked_at = time::now() WHERE revoked_at = NONE RETURN id")
+            .bind(("iid", inv_thing))
+            .await?
+            .take(0)?;
+        Ok(!revoked.is_empty())
+    }
 }
 
 fn invitation_thing_from_id(id: &str) -> Result<RecordId, crate::error::AppError> {
@@ -1,21 +1,26 @@
+pub mod activity;
 pub mod invitation;
 
 mod model;
+pub mod organization;
 pub mod repository;
 pub mod resolver;
 pub mod rest;
 pub mod service;
 mod surreal_repo;
+pub mod trash;
+pub mod webhook;
 
 pub use invitation::rest::invitations_accept_scope;
 pub use model::{
-    DbTeamMember, TeamCreatePayload, TeamFetched, parse_owner_record_id, thing_record_key,
-    user_thing,
+    DbTeamMember, DbTeamRole, TeamCreatePayload, TeamFetched, parse_owner_record_id,
+    thing_record_key, user_thing,
 };
+pub use organization::rest::organizations_scope;
 pub use repository::TeamRepository;
 pub use resolver::{
-    SurrealTeamResolver, TeamResolver, UserPermissions, content_read_team_things,
-    content_write_team_things,
+    SurrealTeamResolver, TeamGrant, TeamResolver, UserPermissions, content_read_team_things,
+    content_team_grants,
 };
 pub use service::{TeamService, TeamServiceHandle};
 pub use surreal_repo::SurrealTeamRepo;
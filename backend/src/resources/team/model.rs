@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use surrealdb::types::{RecordId, SurrealValue};
 
-use shared::team::{Team, TeamMember, TeamMemberInput, TeamRole, TeamUser, TeamUserRef};
+use shared::team::{
+    CreateTeamRole, Team, TeamMember, TeamMemberInput, TeamPermission, TeamRole,
+    TeamRoleDefinition, TeamUser, TeamUserRef,
+};
+use shared::validation_limits::MAX_TEAM_ROLE_NAME_LEN;
 
 use crate::database::record_id_string;
 use crate::error::AppError;
@@ -51,6 +55,7 @@ pub fn build_create_shared_members(
     }
     let members: Vec<DbTeamMember> = map.into_values().collect();
     validate_shared_has_admin(&members)?;
+    validate_member_roles(&members, &[])?;
     Ok(members)
 }
 
@@ -153,6 +158,8 @@ pub struct TeamCreatePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub owner: Option<RecordId>,
     pub members: Vec<DbTeamMember>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<RecordId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, SurrealValue)]
@@ -161,6 +168,46 @@ pub struct DbTeamMember {
     pub role: String,
 }
 
+/// Team-defined role as stored in `team.roles`.
+#[derive(Clone, Debug, Serialize, Deserialize, SurrealValue)]
+pub struct DbTeamRole {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+impl DbTeamRole {
+    pub fn from_definition(role: &TeamRoleDefinition) -> Self {
+        Self {
+            id: role.id.clone(),
+            name: role.name.clone(),
+            permissions: role
+                .permissions
+                .iter()
+                .map(|p| p.as_str().to_owned())
+                .collect(),
+        }
+    }
+
+    pub fn into_definition(self) -> TeamRoleDefinition {
+        let permissions = self.permissions();
+        TeamRoleDefinition {
+            id: self.id,
+            name: self.name,
+            permissions,
+        }
+    }
+
+    /// Stored permissions; unknown strings are ignored.
+    pub fn permissions(&self) -> Vec<TeamPermission> {
+        self.permissions
+            .iter()
+            .filter_map(|p| TeamPermission::parse(p))
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, SurrealValue)]
 pub struct TeamFetched {
     pub id: RecordId,
@@ -169,6 +216,10 @@ pub struct TeamFetched {
     pub owner: Option<UserRecord>,
     #[serde(default)]
     pub members: Vec<TeamMemberFetched>,
+    #[serde(default)]
+    pub organization: Option<RecordId>,
+    #[serde(default)]
+    pub roles: Vec<DbTeamRole>,
 }
 
 #[derive(Clone, Debug, Deserialize, SurrealValue)]
@@ -185,7 +236,7 @@ impl TeamFetched {
         for m in self.members {
             members.push(TeamMember {
                 user: user_record_to_team_user(m.user)?,
-                role: parse_role(&m.role)?,
+                role: TeamRole::parse(&m.role),
             });
         }
         Ok(Team {
@@ -193,6 +244,12 @@ impl TeamFetched {
             owner,
             name: self.name,
             members,
+            organization_id: self.organization.as_ref().map(record_id_string),
+            roles: self
+                .roles
+                .into_iter()
+                .map(DbTeamRole::into_definition)
+                .collect(),
         })
     }
 }
@@ -214,6 +271,7 @@ pub struct TeamIdRow {
 pub struct TeamStored {
     pub owner: Option<RecordId>,
     pub members: Vec<DbTeamMember>,
+    pub roles: Vec<DbTeamRole>,
 }
 
 pub fn team_fetched_to_stored(row: &TeamFetched) -> Result<TeamStored, AppError> {
@@ -229,7 +287,11 @@ pub fn team_fetched_to_stored(row: &TeamFetched) -> Result<TeamStored, AppError>
             role: m.role.clone(),
         });
     }
-    Ok(TeamStored { owner, members })
+    Ok(TeamStored {
+        owner,
+        members,
+        roles: row.roles.clone(),
+    })
 }
 
 pub fn user_thing(user_id: &str) -> RecordId {
@@ -308,7 +370,8 @@ pub fn effective_admin(user_id: &str, stored: &TeamStored) -> bool {
         .any(|m| m.role == "admin" && thing_user_id(&m.user) == user_id)
 }
 
-fn parse_role(s: &str) -> Result<TeamRole, AppError> {
+/// Built-in preset role; custom role ids are rejected.
+pub fn parse_role(s: &str) -> Result<TeamRole, AppError> {
     match s {
         "guest" => Ok(TeamRole::Guest),
         "content_maintainer" => Ok(TeamRole::ContentMaintainer),
@@ -317,11 +380,94 @@ fn parse_role(s: &str) -> Result<TeamRole, AppError> {
     }
 }
 
-fn role_str(r: &TeamRole) -> &'static str {
-    match r {
-        TeamRole::Guest => "guest",
-        TeamRole::ContentMaintainer => "content_maintainer",
-        TeamRole::Admin => "admin",
+pub fn role_str(r: &TeamRole) -> &str {
+    r.as_str()
+}
+
+/// Every member role must be a built-in preset or a role defined on the team.
+pub fn validate_member_roles(
+    members: &[DbTeamMember],
+    roles: &[DbTeamRole],
+) -> Result<(), AppError> {
+    for m in members {
+        if let TeamRole::Custom(id) = TeamRole::parse(&m.role)
+            && !roles.iter().any(|r| r.id == id)
+        {
+            return Err(AppError::invalid_request(format!(
+                "unknown team role `{id}`"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Validates a role body against the team's other roles (`others` excludes the role being
+/// updated): trimmed name of 1..=64 characters, unique per team ignoring case, and a preset
+/// name cannot be reused. Permissions are sorted and deduplicated.
+pub fn team_role_definition(
+    id: String,
+    payload: CreateTeamRole,
+    others: &[DbTeamRole],
+) -> Result<TeamRoleDefinition, AppError> {
+    let name = payload.name.trim().to_owned();
+    if name.is_empty() {
+        return Err(AppError::invalid_request("role name must not be empty"));
+    }
+    if name.chars().count() > MAX_TEAM_ROLE_NAME_LEN {
+        return Err(AppError::invalid_request(format!(
+            "role name is too long (max {MAX_TEAM_ROLE_NAME_LEN} characters)"
+        )));
+    }
+    if !matches!(TeamRole::parse(&name.to_lowercase()), TeamRole::Custom(_)) {
+        return Err(AppError::invalid_request(
+            "role name must not be a built-in role",
+        ));
+    }
+    if others
+        .iter()
+        .any(|r| r.name.trim().to_lowercase() == name.to_lowercase())
+    {
+        return Err(AppError::conflict("a role with this name already exists"));
+    }
+    let mut permissions = payload.permissions;
+    permissions.sort();
+    permissions.dedup();
+    Ok(TeamRoleDefinition {
+        id,
+        name,
+        permissions,
+    })
+}
+
+/// Content permissions of `user_id` on a team: everything for the personal owner, the preset's
+/// permissions, or the permissions of the member's team-defined role.
+pub fn member_permissions(user_id: &str, stored: &TeamStored) -> Vec<TeamPermission> {
+    if let Some(ref o) = stored.owner
+        && thing_user_id(o) == user_id
+    {
+        return TeamPermission::ALL.to_vec();
+    }
+    let Some(member) = stored
+        .members
+        .iter()
+        .find(|m| thing_user_id(&m.user) == user_id)
+    else {
+        return Vec::new();
+    };
+    role_permissions(&member.role, stored)
+}
+
+/// Content permissions `role` grants on the team: the preset's, or those of the team-defined
+/// role (none when the role is unknown).
+pub fn role_permissions(role: &str, stored: &TeamStored) -> Vec<TeamPermission> {
+    match TeamRole::parse(role) {
+        TeamRole::Custom(id) => stored
+            .roles
+            .iter()
+            .find(|r| r.id == id)
+            .map(DbTeamRole::permissions)
+            .unwrap_or_default(),
+        preset => preset.preset_permissions().to_vec(),
     }
 }
 
@@ -341,6 +487,7 @@ mod tests {
 
     fn make_stored(owner_id: Option<&str>, members: Vec<DbTeamMember>) -> TeamStored {
         TeamStored {
+            roles: Vec::new(),
             owner: owner_id.map(user_thing),
             members,
         }
@@ -0,0 +1,12 @@
+mod model;
+
+pub mod repository;
+pub use repository::OrganizationRepository;
+
+mod surreal_repo;
+pub use surreal_repo::SurrealOrganizationRepo;
+
+pub mod service;
+pub use service::{OrganizationService, OrganizationServiceHandle};
+
+pub mod rest;
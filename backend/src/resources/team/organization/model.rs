@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::team::{Organization, OrganizationTeam, TeamUser};
+
+use crate::database::record_id_string;
+use crate::error::AppError;
+use crate::resources::user::UserRecord;
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct OrganizationRow {
+    pub id: RecordId,
+    pub name: String,
+    #[serde(default)]
+    pub admins: Vec<UserRecord>,
+    #[serde(default)]
+    pub library: Option<RecordId>,
+    pub created_at: Datetime,
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct OrganizationTeamRow {
+    pub id: RecordId,
+    pub name: String,
+}
+
+#[derive(Serialize, SurrealValue)]
+pub struct OrganizationCreate {
+    pub name: String,
+    pub admins: Vec<RecordId>,
+}
+
+impl OrganizationRow {
+    pub fn admin_ids(&self) -> Vec<String> {
+        self.admins
+            .iter()
+            .map(|u| u.clone().into_user().id)
+            .collect()
+    }
+
+    pub fn is_admin(&self, user_id: &str) -> bool {
+        self.admin_ids().iter().any(|id| id == user_id)
+    }
+
+    pub fn into_organization(self, teams: Vec<OrganizationTeamRow>) -> Organization {
+        Organization {
+            id: record_id_string(&self.id),
+            name: self.name,
+            admins: self
+                .admins
+                .into_iter()
+                .map(|rec| {
+                    let u = rec.into_user();
+                    TeamUser {
+                        id: u.id,
+                        email: u.email,
+                    }
+                })
+                .collect(),
+            library_team_id: self.library.as_ref().map(record_id_string),
+            teams: teams
+                .into_iter()
+                .map(|t| OrganizationTeam {
+                    id: record_id_string(&t.id),
+                    name: t.name,
+                })
+                .collect(),
+            created_at: self.created_at.into(),
+        }
+    }
+}
+
+pub fn organization_thing(id: &str) -> Result<RecordId, AppError> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err(AppError::NotFound("organization not found".into()));
+    }
+    if let Ok(rid) = RecordId::parse_simple(id)
+        && rid.table.as_str() == "organization"
+    {
+        return Ok(rid);
+    }
+    Ok(RecordId::new("organization", id))
+}
+
+/// Trimmed organization name, 1–256 characters like team names.
+pub fn validate_organization_name(name: &str) -> Result<String, AppError> {
+    use shared::validation_limits::MAX_TEAM_NAME_LEN;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_request(
+            "organization name must not be empty",
+        ));
+    }
+    if name.len() > MAX_TEAM_NAME_LEN {
+        return Err(AppError::invalid_request(format!(
+            "organization name is too long (max {MAX_TEAM_NAME_LEN} characters)"
+        )));
+    }
+    Ok(name.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn organization_thing_accepts_plain_and_prefixed_ids() {
+        let plain = organization_thing("org1").unwrap();
+        assert_eq!(plain.table.as_str(), "organization");
+        assert_eq!(record_id_string(&plain), "org1");
+        let prefixed = organization_thing("organization:org1").unwrap();
+        assert_eq!(record_id_string(&prefixed), "org1");
+        assert!(matches!(
+            organization_thing("  "),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    /// BLC-ORG-001: organization names follow the team name rules.
+    #[test]
+    fn blc_org_001_validate_name() {
+        assert_eq!(validate_organization_name("  Grace  ").unwrap(), "Grace");
+        assert!(validate_organization_name("   ").is_err());
+        assert!(validate_organization_name(&"x".repeat(257)).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use surrealdb::types::RecordId;
+
+use crate::error::AppError;
+
+use super::model::{OrganizationCreate, OrganizationRow, OrganizationTeamRow};
+
+/// Pure organization data access — no authorization. Service layer does all ACL checks.
+#[async_trait]
+pub trait OrganizationRepository: Send + Sync {
+    /// Insert an organization without library; returns its id.
+    async fn create_organization(&self, create: OrganizationCreate) -> Result<RecordId, AppError>;
+
+    /// Get one organization with `admins` fetched.
+    async fn get_organization(&self, org: RecordId) -> Result<Option<OrganizationRow>, AppError>;
+
+    /// Organizations where `user` is an admin or a member of one of the teams, by name; every
+    /// organization when `all` is set.
+    async fn list_organizations_for_user(
+        &self,
+        user: RecordId,
+        all: bool,
+    ) -> Result<Vec<OrganizationRow>, AppError>;
+
+    /// Whether `user` owns or is a member of a team of the organization.
+    async fn has_member(&self, org: RecordId, user: RecordId) -> Result<bool, AppError>;
+
+    /// Teams of the organization, by name.
+    async fn list_teams(&self, org: RecordId) -> Result<Vec<OrganizationTeamRow>, AppError>;
+
+    async fn update_organization(
+        &self,
+        org: RecordId,
+        name: String,
+        admins: Vec<RecordId>,
+        library: Option<RecordId>,
+    ) -> Result<(), AppError>;
+
+    async fn set_library(&self, org: RecordId, library: Option<RecordId>) -> Result<(), AppError>;
+
+    /// Delete an organization; its teams are detached by a database event.
+    async fn delete_organization(&self, org: RecordId) -> Result<(), AppError>;
+
+    /// Attach a team to an organization, or detach it with `None`.
+    async fn set_team_organization(
+        &self,
+        team: RecordId,
+        org: Option<RecordId>,
+    ) -> Result<(), AppError>;
+}
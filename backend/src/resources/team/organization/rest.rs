@@ -0,0 +1,269 @@
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::resources::User;
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, Scope, delete, get, post, put,
+    web::{self, Data, Json, Path, Query, ReqData},
+};
+
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+#[allow(unused_imports)]
+use shared::team::Organization;
+use shared::team::{CreateOrganization, UpdateOrganization};
+
+use super::service::OrganizationServiceHandle;
+
+pub fn organizations_scope() -> Scope {
+    web::scope("/organizations")
+        .service(list_organizations)
+        .service(create_organization)
+        .service(get_organization)
+        .service(update_organization)
+        .service(delete_organization)
+        .service(add_organization_team)
+        .service(remove_organization_team)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations",
+    params(
+        ("page" = Option<u32>, Query, description = "Page index, zero-based. Omit with `page_size` for full list.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50. Omit with `page` for full list.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Organizations the user administers or belongs to through a team, by name; platform admins receive all. `X-Total-Count` is the total before paging.", body = [Organization]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to list organizations", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("")]
+async fn list_organizations(
+    req: HttpRequest,
+    svc: Data<OrganizationServiceHandle>,
+    user: ReqData<User>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let q_link = query.clone();
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let (organizations, total) = svc
+        .list_organizations_for_user(&user, query.as_list_query())
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                &req,
+                |p| q_link.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(organizations))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/organizations",
+    request_body = CreateOrganization,
+    responses(
+        (status = 201, description = "Organization created with the caller as admin and a new library team", body = Organization),
+        (status = 400, description = "Invalid name", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to create organization", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("")]
+async fn create_organization(
+    svc: Data<OrganizationServiceHandle>,
+    user: ReqData<User>,
+    payload: Json<CreateOrganization>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Created().json(
+        svc.create_organization_for_user(&user, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{id}",
+    params(
+        ("id" = String, Path, description = "Organization identifier")
+    ),
+    responses(
+        (status = 200, description = "Organization with its admins, teams and library", body = Organization),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Organization not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to fetch organization", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/{id}")]
+async fn get_organization(
+    svc: Data<OrganizationServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.get_organization_for_user(&user, &id).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{id}",
+    params(
+        ("id" = String, Path, description = "Organization identifier")
+    ),
+    request_body = UpdateOrganization,
+    responses(
+        (status = 200, description = "Organization updated", body = Organization),
+        (status = 400, description = "Invalid name, empty admin list, or library team outside the organization", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not an organization admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Organization not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to update organization", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[put("/{id}")]
+async fn update_organization(
+    svc: Data<OrganizationServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+    payload: Json<UpdateOrganization>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(
+        svc.update_organization_for_user(&user, &id, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{id}",
+    params(
+        ("id" = String, Path, description = "Organization identifier")
+    ),
+    responses(
+        (status = 204, description = "Organization deleted; its teams remain as standalone teams"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not an organization admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Organization not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to delete organization", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/{id}")]
+async fn delete_organization(
+    svc: Data<OrganizationServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    svc.delete_organization_for_user(&user, &id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{id}/teams/{team_id}",
+    params(
+        ("id" = String, Path, description = "Organization identifier"),
+        ("team_id" = String, Path, description = "Shared team identifier")
+    ),
+    responses(
+        (status = 200, description = "Team is part of the organization (idempotent)", body = Organization),
+        (status = 400, description = "Personal teams cannot join an organization", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not an admin of both the organization and the team", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Organization or team not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Team belongs to another organization", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to add team", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[put("/{id}/teams/{team_id}")]
+async fn add_organization_team(
+    svc: Data<OrganizationServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (id, team_id) = path.into_inner();
+    Ok(HttpResponse::Ok().json(svc.add_team_for_user(&user, &id, &team_id).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{id}/teams/{team_id}",
+    params(
+        ("id" = String, Path, description = "Organization identifier"),
+        ("team_id" = String, Path, description = "Team identifier")
+    ),
+    responses(
+        (status = 204, description = "Team removed; removing the library team leaves the organization without library"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Neither an organization admin nor a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Organization not found or team not part of it", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to remove team", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/{id}/teams/{team_id}")]
+async fn remove_organization_team(
+    svc: Data<OrganizationServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (id, team_id) = path.into_inner();
+    svc.remove_team_for_user(&user, &id, &team_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,688 @@
+use std::sync::Arc;
+
+use surrealdb::types::RecordId;
+
+use shared::api::ListQuery;
+use shared::team::{CreateOrganization, Organization, UpdateOrganization};
+use shared::user::{Role as UserRole, User};
+use tracing::instrument;
+
+use crate::database::{Database, record_id_string};
+use crate::error::AppError;
+use crate::resources::team::model::{
+    TeamCreatePayload, build_create_shared_members, effective_admin, member_user_id,
+    team_fetched_to_stored, team_resource_or_reject_public, user_thing,
+};
+use crate::resources::team::repository::TeamRepository;
+use crate::resources::team::surreal_repo::SurrealTeamRepo;
+
+use super::model::{
+    OrganizationCreate, OrganizationRow, organization_thing, validate_organization_name,
+};
+use super::repository::OrganizationRepository;
+use super::surreal_repo::SurrealOrganizationRepo;
+
+/// Application service for organizations: org admins, member teams and the shared library team.
+#[derive(Clone)]
+pub struct OrganizationService<R, O> {
+    pub team_repo: R,
+    pub org_repo: O,
+}
+
+impl<R, O> OrganizationService<R, O> {
+    pub fn new(team_repo: R, org_repo: O) -> Self {
+        Self {
+            team_repo,
+            org_repo,
+        }
+    }
+}
+
+impl<R: TeamRepository, O: OrganizationRepository> OrganizationService<R, O> {
+    async fn to_organization(&self, row: OrganizationRow) -> Result<Organization, AppError> {
+        let teams = self.org_repo.list_teams(row.id.clone()).await?;
+        Ok(row.into_organization(teams))
+    }
+
+    /// Post-mutation return; no ACL check since an admin may have just removed themselves.
+    async fn load_organization(&self, org: RecordId) -> Result<Organization, AppError> {
+        let row = self
+            .org_repo
+            .get_organization(org)
+            .await?
+            .ok_or_else(|| AppError::NotFound("organization not found".into()))?;
+        self.to_organization(row).await
+    }
+
+    /// Organization readable by `user`: org admins, members of its teams and platform admins.
+    async fn readable_organization(
+        &self,
+        user: &User,
+        id: &str,
+    ) -> Result<OrganizationRow, AppError> {
+        let org = organization_thing(id)?;
+        let row = self
+            .org_repo
+            .get_organization(org.clone())
+            .await?
+            .ok_or_else(|| AppError::NotFound("organization not found".into()))?;
+        if user.role == UserRole::Admin
+            || row.is_admin(&user.id)
+            || self.org_repo.has_member(org, user_thing(&user.id)).await?
+        {
+            return Ok(row);
+        }
+        Err(AppError::NotFound("organization not found".into()))
+    }
+
+    /// Readable organization that `user` administers; readers get 403.
+    async fn administered_organization(
+        &self,
+        user: &User,
+        id: &str,
+    ) -> Result<OrganizationRow, AppError> {
+        let row = self.readable_organization(user, id).await?;
+        if !row.is_admin(&user.id) {
+            return Err(AppError::forbidden());
+        }
+        Ok(row)
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_organizations_for_user(
+        &self,
+        user: &User,
+        pagination: ListQuery,
+    ) -> Result<(Vec<Organization>, u64), AppError> {
+        let rows = self
+            .org_repo
+            .list_organizations_for_user(user_thing(&user.id), user.role == UserRole::Admin)
+            .await?;
+        let (rows, total) = ListQuery::paginate_vec(rows, &pagination);
+        let mut organizations = Vec::with_capacity(rows.len());
+        for row in rows {
+            organizations.push(self.to_organization(row).await?);
+        }
+        Ok((organizations, total))
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn get_organization_for_user(
+        &self,
+        user: &User,
+        id: &str,
+    ) -> Result<Organization, AppError> {
+        let row = self.readable_organization(user, id).await?;
+        self.to_organization(row).await
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn create_organization_for_user(
+        &self,
+        user: &User,
+        payload: CreateOrganization,
+    ) -> Result<Organization, AppError> {
+        let name = validate_organization_name(&payload.name)?;
+        let library_name = format!("{name} Library");
+        if library_name.len() > shared::validation_limits::MAX_TEAM_NAME_LEN {
+            return Err(AppError::invalid_request(
+                "organization name is too long for its library team name",
+            ));
+        }
+        let org = self
+            .org_repo
+            .create_organization(OrganizationCreate {
+                name,
+                admins: vec![user_thing(&user.id)],
+            })
+            .await?;
+        let library = self
+            .team_repo
+            .create_team(TeamCreatePayload {
+                name: library_name,
+                owner: None,
+                members: build_create_shared_members(&user.id, &[])?,
+                organization: Some(org.clone()),
+            })
+            .await?;
+        let library = RecordId::new("team", library);
+        self.org_repo
+            .set_library(org.clone(), Some(library.clone()))
+            .await?;
+        let org_id = record_id_string(&org);
+        crate::audit!(
+            "audit.organization.created",
+            organization_id = tracing::field::display(&org_id),
+            library_team_id = tracing::field::display(record_id_string(&library)),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "organization created"
+        );
+        self.load_organization(org).await
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn update_organization_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        payload: UpdateOrganization,
+    ) -> Result<Organization, AppError> {
+        let row = self.administered_organization(user, id).await?;
+        let name = validate_organization_name(&payload.name)?;
+        let mut admin_ids = Vec::with_capacity(payload.admins.len());
+        for admin in &payload.admins {
+            let uid = member_user_id(admin)?;
+            if !admin_ids.contains(&uid) {
+                admin_ids.push(uid);
+            }
+        }
+        if admin_ids.is_empty() {
+            return Err(AppError::invalid_request(
+                "organization must have at least one admin",
+            ));
+        }
+        let library = match payload.library_team_id.as_deref().map(str::trim) {
+            None => None,
+            Some(team_id) => {
+                let resource = team_resource_or_reject_public(team_id)?;
+                let team = RecordId::new(resource.0, resource.1);
+                let in_org = self
+                    .org_repo
+                    .list_teams(row.id.clone())
+                    .await?
+                    .iter()
+                    .any(|t| t.id == team);
+                if !in_org {
+                    return Err(AppError::invalid_request(
+                        "library team must be a team of the organization",
+                    ));
+                }
+                Some(team)
+            }
+        };
+        self.org_repo
+            .update_organization(
+                row.id.clone(),
+                name,
+                admin_ids.iter().map(|a| user_thing(a)).collect(),
+                library,
+            )
+            .await?;
+        crate::audit!(
+            "audit.organization.updated",
+            organization_id = tracing::field::display(record_id_string(&row.id)),
+            admin_count = admin_ids.len(),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "organization updated"
+        );
+        self.load_organization(row.id).await
+    }
+
+    /// Deletes the organization only; its teams, including the library, stay as plain teams.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn delete_organization_for_user(
+        &self,
+        user: &User,
+        id: &str,
+    ) -> Result<Organization, AppError> {
+        let row = self.administered_organization(user, id).await?;
+        let org = row.id.clone();
+        let organization = self.to_organization(row).await?;
+        self.org_repo.delete_organization(org).await?;
+        crate::audit!(
+            "audit.organization.deleted",
+            organization_id = tracing::field::display(&organization.id),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "organization deleted"
+        );
+        Ok(organization)
+    }
+
+    /// Adds a shared team; the caller must administer both the organization and the team.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn add_team_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        team_id: &str,
+    ) -> Result<Organization, AppError> {
+        let row = self.administered_organization(user, id).await?;
+        team_resource_or_reject_public(team_id)?;
+        let team = self
+            .team_repo
+            .fetch_team(team_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("team not found".into()))?;
+        let stored = team_fetched_to_stored(&team)?;
+        if !effective_admin(&user.id, &stored) {
+            return Err(AppError::forbidden());
+        }
+        if stored.owner.is_some() {
+            return Err(AppError::invalid_request(
+                "personal teams cannot join an organization",
+            ));
+        }
+        match team.organization {
+            Some(ref current) if *current == row.id => {}
+            Some(_) => {
+                return Err(AppError::conflict(
+                    "team already belongs to another organization",
+                ));
+            }
+            None => {
+                self.org_repo
+                    .set_team_organization(team.id.clone(), Some(row.id.clone()))
+                    .await?;
+                crate::audit!(
+                    "audit.organization.team.added",
+                    organization_id = tracing::field::display(record_id_string(&row.id)),
+                    team_id = tracing::field::display(record_id_string(&team.id)),
+                    actor_user_id = tracing::field::display(&user.id)
+                    ; "team added to organization"
+                );
+            }
+        }
+        self.to_organization(row).await
+    }
+
+    /// Removes a team; org admins and admins of the team may do this. Removing the library team
+    /// leaves the organization without library. Returns nothing because a team admin may no
+    /// longer see the organization afterwards.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn remove_team_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        team_id: &str,
+    ) -> Result<(), AppError> {
+        let row = self.readable_organization(user, id).await?;
+        team_resource_or_reject_public(team_id)?;
+        let team = self
+            .team_repo
+            .fetch_team(team_id)
+            .await?
+            .filter(|t| t.organization.as_ref() == Some(&row.id))
+            .ok_or_else(|| AppError::NotFound("team not found".into()))?;
+        let stored = team_fetched_to_stored(&team)?;
+        if !row.is_admin(&user.id) && !effective_admin(&user.id, &stored) {
+            return Err(AppError::forbidden());
+        }
+        self.org_repo
+            .set_team_organization(team.id.clone(), None)
+            .await?;
+        if row.library.as_ref() == Some(&team.id) {
+            self.org_repo.set_library(row.id.clone(), None).await?;
+        }
+        crate::audit!(
+            "audit.organization.team.removed",
+            organization_id = tracing::field::display(record_id_string(&row.id)),
+            team_id = tracing::field::display(record_id_string(&team.id)),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "team removed from organization"
+        );
+        Ok(())
+    }
+}
+
+/// Production type alias used in HTTP wiring.
+pub type OrganizationServiceHandle = OrganizationService<SurrealTeamRepo, SurrealOrganizationRepo>;
+
+impl OrganizationServiceHandle {
+    pub fn build(db: Arc<Database>) -> Self {
+        OrganizationService::new(
+            SurrealTeamRepo::new(db.clone()),
+            SurrealOrganizationRepo::new(db),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::api::ListQuery;
+    use shared::song::CreateSong;
+    use shared::team::{CreateOrganization, Organization, TeamUserRef, UpdateOrganization};
+    use shared::user::User;
+
+    use crate::error::AppError;
+    use crate::resources::team::UserPermissions;
+    use crate::test_helpers::{
+        TeamFixture, minimal_song_data, organization_service, song_service, team_service, test_db,
+    };
+
+    async fn create_org(
+        db: &std::sync::Arc<crate::database::Database>,
+        user: &User,
+        name: &str,
+    ) -> Organization {
+        organization_service(db)
+            .create_organization_for_user(user, CreateOrganization { name: name.into() })
+            .await
+            .expect("create organization")
+    }
+
+    /// Organization of `fx.admin_user` with the fixture's shared team added.
+    async fn org_with_shared_team(
+        db: &std::sync::Arc<crate::database::Database>,
+        fx: &TeamFixture,
+    ) -> Organization {
+        let org = create_org(db, &fx.admin_user, "Grace").await;
+        organization_service(db)
+            .add_team_for_user(&fx.admin_user, &org.id, &fx.shared_team_id)
+            .await
+            .expect("add team")
+    }
+
+    fn update(org: &Organization) -> UpdateOrganization {
+        UpdateOrganization {
+            name: org.name.clone(),
+            admins: org
+                .admins
+                .iter()
+                .map(|a| TeamUserRef { id: a.id.clone() })
+                .collect(),
+            library_team_id: org.library_team_id.clone(),
+        }
+    }
+
+    /// BLC-ORG-002: the creator is the only admin and a library team is created inside the org.
+    #[tokio::test]
+    async fn blc_org_002_create_with_library() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let org = create_org(&db, &fx.admin_user, "  Grace  ").await;
+        assert_eq!(org.name, "Grace");
+        assert_eq!(org.admins.len(), 1);
+        assert_eq!(org.admins[0].id, fx.admin_user.id);
+        let library_id = org.library_team_id.clone().expect("library");
+        assert_eq!(org.teams.len(), 1);
+        assert_eq!(org.teams[0].id, library_id);
+        assert_eq!(org.teams[0].name, "Grace Library");
+
+        let library = team_service(&db)
+            .get_team_for_user(&fx.admin_user, &library_id)
+            .await
+            .expect("library team");
+        assert_eq!(library.organization_id.as_deref(), Some(org.id.as_str()));
+    }
+
+    /// BLC-ORG-003: org admins, members of org teams and platform admins can read; others get 404.
+    #[tokio::test]
+    async fn blc_org_003_visibility() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let org = org_with_shared_team(&db, &fx).await;
+        let svc = organization_service(&db);
+
+        for reader in [&fx.admin_user, &fx.guest, &fx.platform_admin] {
+            assert_eq!(
+                svc.get_organization_for_user(reader, &org.id)
+                    .await
+                    .expect("readable")
+                    .id,
+                org.id
+            );
+            let (list, total) = svc
+                .list_organizations_for_user(reader, ListQuery::default())
+                .await
+                .expect("list");
+            assert_eq!(total, 1);
+            assert_eq!(list[0].id, org.id);
+        }
+        let err = svc
+            .get_organization_for_user(&fx.non_member, &org.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+        let (_, total) = svc
+            .list_organizations_for_user(&fx.non_member, ListQuery::default())
+            .await
+            .expect("list");
+        assert_eq!(total, 0);
+    }
+
+    /// BLC-ORG-004: only org admins update; admins must be non-empty and the library an org team.
+    #[tokio::test]
+    async fn blc_org_004_update_rules() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let org = org_with_shared_team(&db, &fx).await;
+        let svc = organization_service(&db);
+
+        let err = svc
+            .update_organization_for_user(&fx.writer, &org.id, update(&org))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden));
+
+        let mut no_admins = update(&org);
+        no_admins.admins.clear();
+        let err = svc
+            .update_organization_for_user(&fx.admin_user, &org.id, no_admins)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+
+        let mut foreign_library = update(&org);
+        foreign_library.library_team_id = Some(fx.personal_team_id.clone());
+        let err = svc
+            .update_organization_for_user(&fx.admin_user, &org.id, foreign_library)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+
+        let mut ok = update(&org);
+        ok.name = "Grace Church".into();
+        ok.admins.push(TeamUserRef {
+            id: fx.writer.id.clone(),
+        });
+        ok.library_team_id = Some(fx.shared_team_id.clone());
+        let updated = svc
+            .update_organization_for_user(&fx.admin_user, &org.id, ok)
+            .await
+            .expect("update");
+        assert_eq!(updated.name, "Grace Church");
+        assert_eq!(updated.admins.len(), 2);
+        assert_eq!(
+            updated.library_team_id.as_deref(),
+            Some(fx.shared_team_id.as_str())
+        );
+    }
+
+    /// BLC-ORG-005: adding a team requires admin of both sides; personal teams and teams of another
+    /// organization are rejected; re-adding is a no-op.
+    #[tokio::test]
+    async fn blc_org_005_add_team_rules() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = organization_service(&db);
+        let org = org_with_shared_team(&db, &fx).await;
+        assert_eq!(org.teams.len(), 2);
+
+        let again = svc
+            .add_team_for_user(&fx.admin_user, &org.id, &fx.shared_team_id)
+            .await
+            .expect("idempotent");
+        assert_eq!(again.teams.len(), 2);
+
+        let other = create_org(&db, &fx.admin_user, "Other").await;
+        let err = svc
+            .add_team_for_user(&fx.admin_user, &other.id, &fx.shared_team_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+
+        let owners_org = create_org(&db, &fx.owner, "Owner Org").await;
+        let err = svc
+            .add_team_for_user(&fx.owner, &owners_org.id, &fx.shared_team_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden));
+        let err = svc
+            .add_team_for_user(&fx.owner, &owners_org.id, &fx.personal_team_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+    }
+
+    /// BLC-ORG-006: members of org teams read library content but cannot write it; leaving the
+    /// organization removes access.
+    #[tokio::test]
+    async fn blc_org_006_library_inheritance() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let org = org_with_shared_team(&db, &fx).await;
+        let library_id = org.library_team_id.clone().expect("library");
+
+        let songs = song_service(&db);
+        let admin_perms = UserPermissions::from_ref(&fx.admin_user, &songs.teams);
+        let song = songs
+            .create_song_for_user(
+                &admin_perms,
+                CreateSong {
+                    owner: Some(library_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .expect("library song");
+
+        let writer_perms = UserPermissions::from_ref(&fx.writer, &songs.teams);
+        songs
+            .get_song_for_user(&writer_perms, &song.id)
+            .await
+            .expect("inherited read");
+        let err = songs
+            .create_song_for_user(
+                &writer_perms,
+                CreateSong {
+                    owner: Some(library_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        let outsider = UserPermissions::from_ref(&fx.non_member, &songs.teams);
+        assert!(songs.get_song_for_user(&outsider, &song.id).await.is_err());
+
+        organization_service(&db)
+            .remove_team_for_user(&fx.admin_user, &org.id, &fx.shared_team_id)
+            .await
+            .expect("remove team");
+        let writer_perms = UserPermissions::from_ref(&fx.writer, &songs.teams);
+        assert!(
+            songs
+                .get_song_for_user(&writer_perms, &song.id)
+                .await
+                .is_err()
+        );
+    }
+
+    /// BLC-ORG-007: team admins may remove their own team; removing the library clears it.
+    #[tokio::test]
+    async fn blc_org_007_remove_team() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = organization_service(&db);
+        let org = create_org(&db, &fx.owner, "Owner Org").await;
+        let shared = team_service(&db)
+            .get_team_for_user(&fx.admin_user, &fx.shared_team_id)
+            .await
+            .expect("shared team");
+        // Org admin who also joins the shared team as admin to add it, then steps back.
+        let mut members = shared.members.clone();
+        members.push(shared::team::TeamMember {
+            user: shared::team::TeamUser {
+                id: fx.owner.id.clone(),
+                email: fx.owner.email.clone(),
+            },
+            role: shared::team::TeamRole::Admin,
+        });
+        team_service(&db)
+            .update_team_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                shared::team::UpdateTeam {
+                    name: shared.name.clone(),
+                    members: Some(
+                        members
+                            .iter()
+                            .map(|m| shared::team::TeamMemberInput {
+                                user: TeamUserRef {
+                                    id: m.user.id.clone(),
+                                },
+                                role: m.role.clone(),
+                            })
+                            .collect(),
+                    ),
+                },
+            )
+            .await
+            .expect("owner joins shared team");
+        svc.add_team_for_user(&fx.owner, &org.id, &fx.shared_team_id)
+            .await
+            .expect("add");
+
+        let err = svc
+            .remove_team_for_user(&fx.guest, &org.id, &fx.shared_team_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden));
+        svc.remove_team_for_user(&fx.admin_user, &org.id, &fx.shared_team_id)
+            .await
+            .expect("team admin removes own team");
+        let err = svc
+            .remove_team_for_user(&fx.owner, &org.id, &fx.shared_team_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        let library_id = org.library_team_id.clone().expect("library");
+        svc.remove_team_for_user(&fx.owner, &org.id, &library_id)
+            .await
+            .expect("remove library");
+        let org = svc
+            .get_organization_for_user(&fx.owner, &org.id)
+            .await
+            .expect("org");
+        assert!(org.library_team_id.is_none());
+        assert!(org.teams.is_empty());
+    }
+
+    /// BLC-ORG-008: deleting an organization keeps its teams and detaches them.
+    #[tokio::test]
+    async fn blc_org_008_delete_detaches_teams() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let org = org_with_shared_team(&db, &fx).await;
+        let svc = organization_service(&db);
+        let err = svc
+            .delete_organization_for_user(&fx.writer, &org.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden));
+        svc.delete_organization_for_user(&fx.admin_user, &org.id)
+            .await
+            .expect("delete");
+        let err = svc
+            .get_organization_for_user(&fx.admin_user, &org.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+        for team_id in [
+            fx.shared_team_id.clone(),
+            org.library_team_id.clone().expect("library"),
+        ] {
+            let team = team_service(&db)
+                .get_team_for_user(&fx.admin_user, &team_id)
+                .await
+                .expect("team kept");
+            assert!(team.organization_id.is_none());
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use surrealdb::types::{RecordId, SurrealValue};
+
+use crate::database::{Database, surreal_take_errors};
+use crate::error::AppError;
+
+use super::model::{OrganizationCreate, OrganizationRow, OrganizationTeamRow};
+use super::repository::OrganizationRepository;
+
+#[derive(Deserialize, SurrealValue)]
+struct OrganizationCreated {
+    id: RecordId,
+}
+
+#[derive(Clone)]
+pub struct SurrealOrganizationRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealOrganizationRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for SurrealOrganizationRepo {
+    async fn create_organization(&self, create: OrganizationCreate) -> Result<RecordId, AppError> {
+        let created: Option<OrganizationCreated> = self
+            .inner()
+            .create("organization")
+            .content(create)
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "organization.create", e))?;
+        created
+            .map(|row| row.id)
+            .ok_or_else(|| AppError::database("failed to create organization"))
+    }
+
+    async fn get_organization(&self, org: RecordId) -> Result<Option<OrganizationRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM $org FETCH admins")
+            .bind(("org", org))
+            .await?
+            .take::<Option<OrganizationRow>>(0)?)
+    }
+
+    async fn list_organizations_for_user(
+        &self,
+        user: RecordId,
+        all: bool,
+    ) -> Result<Vec<OrganizationRow>, AppError> {
+        let query = if all {
+            "SELECT * FROM organization ORDER BY name FETCH admins"
+        } else {
+            "SELECT * FROM organization WHERE admins CONTAINS $uid \
+             OR id INSIDE (SELECT VALUE organization FROM team WHERE organization != NONE \
+             AND (owner = $uid OR array::len(members[WHERE user = $uid]) > 0)) \
+             ORDER BY name FETCH admins"
+        };
+        Ok(self
+            .inner()
+            .query(query)
+            .bind(("uid", user))
+            .await?
+            .take::<Vec<OrganizationRow>>(0)?)
+    }
+
+    async fn has_member(&self, org: RecordId, user: RecordId) -> Result<bool, AppError> {
+        let teams: Vec<RecordId> = self
+            .inner()
+            .query(
+                "SELECT VALUE id FROM team WHERE organization = $org \
+                 AND (owner = $uid OR array::len(members[WHERE user = $uid]) > 0) LIMIT 1",
+            )
+            .bind(("org", org))
+            .bind(("uid", user))
+            .await?
+            .take(0)?;
+        Ok(!teams.is_empty())
+    }
+
+    async fn list_teams(&self, org: RecordId) -> Result<Vec<OrganizationTeamRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT id, name FROM team WHERE organization = $org ORDER BY name")
+            .bind(("org", org))
+            .await?
+            .take::<Vec<OrganizationTeamRow>>(0)?)
+    }
+
+    async fn update_organization(
+        &self,
+        org: RecordId,
+        name: String,
+        admins: Vec<RecordId>,
+        library: Option<RecordId>,
+    ) -> Result<(), AppError> {
+        let mut response = self
+            .inner()
+            .query("UPDATE $org SET name = $name, admins = $admins, library = $library")
+            .bind(("org", org))
+            .bind(("name", name))
+            .bind(("admins", admins))
+            .bind(("library", library))
+            .await?;
+        surreal_take_errors("organization.update", &mut response)
+    }
+
+    async fn set_library(&self, org: RecordId, library: Option<RecordId>) -> Result<(), AppError> {
+        let mut response = self
+            .inner()
+            .query("UPDATE $org SET library = $library")
+            .bind(("org", org))
+            .bind(("library", library))
+            .await?;
+        surreal_take_errors("organization.set_library", &mut response)
+    }
+
+    async fn delete_organization(&self, org: RecordId) -> Result<(), AppError> {
+        let mut response = self.inner().query("DELETE $org").bind(("org", org)).await?;
+        surreal_take_errors("organization.delete", &mut response)
+    }
+
+    async fn set_team_organization(
+        &self,
+        team: RecordId,
+        org: Option<RecordId>,
+    ) -> Result<(), AppError> {
+        let mut response = self
+            .inner()
+            .query("UPDATE $tid SET organization = $org")
+            .bind(("tid", team))
+            .bind(("org", org))
+            .await?;
+        surreal_take_errors("organization.set_team_organization", &mut response)
+    }
+}
@@ -5,7 +5,7 @@ use shared::team::Team;
 
 use crate::error::AppError;
 
-use super::model::{DbTeamMember, TeamCreatePayload, TeamFetched};
+use super::model::{DbTeamMember, DbTeamRole, TeamCreatePayload, TeamFetched};
 
 /// Pure team data access — no authorization. Callers are responsible for ACL checks.
 #[async_trait]
@@ -41,6 +41,13 @@ pub trait TeamRepository: Send + Sync {
         members: Vec<DbTeamMember>,
     ) -> Result<(), AppError>;
 
+    /// Replace the team-defined roles of a team.
+    async fn update_team_roles(
+        &self,
+        resource: (String, String),
+        roles: Vec<DbTeamRole>,
+    ) -> Result<(), AppError>;
+
     /// Delete a team record. Does NOT reassign content.
     async fn delete_team_record(&self, resource: (String, String)) -> Result<(), AppError>;
 
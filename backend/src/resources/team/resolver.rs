@@ -8,23 +8,35 @@ use surrealdb::types::{RecordId, SurrealValue};
 use tokio::sync::OnceCell;
 use tracing::instrument;
 
+use shared::team::TeamPermission;
 use shared::user::{Role as UserRole, User};
 
 use crate::database::Database;
 use crate::error::AppError;
 
-use super::model::{public_team_thing, thing_record_key, user_thing};
+use super::model::{
+    DbTeamMember, DbTeamRole, TeamStored, member_permissions, public_team_thing, thing_record_key,
+    user_thing,
+};
 
 #[derive(Debug, Deserialize, SurrealValue)]
 struct TeamIdRow {
     id: RecordId,
 }
 
-/// Resolves which team [`RecordId`]s apply for content ACL (read vs write).
+/// Content permissions a user holds on one team, from the personal owner, a preset role or a
+/// team-defined role.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TeamGrant {
+    pub team: RecordId,
+    pub permissions: Vec<TeamPermission>,
+}
+
+/// Resolves which team [`RecordId`]s apply for content ACL (read vs per-permission write).
 #[async_trait]
 pub trait TeamResolver: Send + Sync {
     async fn content_read_teams(&self, user: &User) -> Result<Vec<RecordId>, AppError>;
-    async fn content_write_teams(&self, user: &User) -> Result<Vec<RecordId>, AppError>;
+    async fn content_grants(&self, user: &User) -> Result<Vec<TeamGrant>, AppError>;
     async fn personal_team(&self, user_id: &str) -> Result<RecordId, AppError>;
 }
 
@@ -37,7 +49,7 @@ pub struct UserPermissions<T: TeamResolver> {
     user: Arc<User>,
     resolver: Arc<T>,
     read_teams: OnceCell<Vec<RecordId>>,
-    write_teams: OnceCell<Vec<RecordId>>,
+    grants: OnceCell<Vec<TeamGrant>>,
     personal_team: OnceCell<RecordId>,
 }
 
@@ -47,7 +59,7 @@ impl<T: TeamResolver> UserPermissions<T> {
             user,
             resolver,
             read_teams: OnceCell::new(),
-            write_teams: OnceCell::new(),
+            grants: OnceCell::new(),
             personal_team: OnceCell::new(),
         }
     }
@@ -71,16 +83,27 @@ impl<T: TeamResolver> UserPermissions<T> {
             .map(Vec::as_slice)
     }
 
-    /// Teams whose content the user may write. Resolved once; subsequent calls return the cached slice.
-    pub async fn write_teams(&self) -> Result<&[RecordId], AppError> {
+    /// Per-team content permissions. Resolved once; subsequent calls return the cached slice.
+    pub async fn grants(&self) -> Result<&[TeamGrant], AppError> {
         let user = Arc::clone(&self.user);
         let resolver = Arc::clone(&self.resolver);
-        self.write_teams
-            .get_or_try_init(|| async move { resolver.content_write_teams(user.as_ref()).await })
+        self.grants
+            .get_or_try_init(|| async move { resolver.content_grants(user.as_ref()).await })
             .await
             .map(Vec::as_slice)
     }
 
+    /// Teams on which the user holds `permission`.
+    pub async fn teams_with(&self, permission: TeamPermission) -> Result<Vec<RecordId>, AppError> {
+        Ok(self
+            .grants()
+            .await?
+            .iter()
+            .filter(|g| g.permissions.contains(&permission))
+            .map(|g| g.team.clone())
+            .collect())
+    }
+
     /// The user's personal team. Resolved once; subsequent calls return a clone.
     pub async fn personal_team(&self) -> Result<RecordId, AppError> {
         let user_id = self.user.id.clone();
@@ -91,13 +114,21 @@ impl<T: TeamResolver> UserPermissions<T> {
             .cloned()
     }
 
-    /// Ensures the user may create/update/delete library content for this owning **team** [`RecordId`]
-    /// (same id as the `owner` string in API responses). Same set as [`Self::write_teams`].
-    /// On failure, returns [`AppError::NotFound`] to match other library ACL responses.
-    pub async fn require_write_access_to_owner(&self, owner: &RecordId) -> Result<(), AppError> {
-        let write_teams = self.write_teams().await?;
+    /// Ensures the user holds `permission` on this owning **team** [`RecordId`] (same id as the
+    /// `owner` string in API responses). On failure, returns [`AppError::NotFound`] to match other
+    /// library ACL responses.
+    pub async fn require_permission_on_owner(
+        &self,
+        owner: &RecordId,
+        permission: TeamPermission,
+    ) -> Result<(), AppError> {
         let key = thing_record_key(owner);
-        if write_teams.iter().any(|t| thing_record_key(t) == key) {
+        let granted = self
+            .grants()
+            .await?
+            .iter()
+            .any(|g| thing_record_key(&g.team) == key && g.permissions.contains(&permission));
+        if granted {
             Ok(())
         } else {
             Err(AppError::NotFound("team not found".into()))
@@ -111,8 +142,8 @@ impl<T: TeamResolver + Send + Sync> TeamResolver for Arc<T> {
         self.as_ref().content_read_teams(user).await
     }
 
-    async fn content_write_teams(&self, user: &User) -> Result<Vec<RecordId>, AppError> {
-        self.as_ref().content_write_teams(user).await
+    async fn content_grants(&self, user: &User) -> Result<Vec<TeamGrant>, AppError> {
+        self.as_ref().content_grants(user).await
     }
 
     async fn personal_team(&self, user_id: &str) -> Result<RecordId, AppError> {
@@ -140,8 +171,8 @@ impl TeamResolver for SurrealTeamResolver {
     }
 
     #[instrument(level = "debug", err, skip(self, user), fields(user_id = %user.id))]
-    async fn content_write_teams(&self, user: &User) -> Result<Vec<RecordId>, AppError> {
-        content_write_team_things(&self.db, user).await
+    async fn content_grants(&self, user: &User) -> Result<Vec<TeamGrant>, AppError> {
+        content_team_grants(&self.db, user).await
     }
 
     #[instrument(level = "debug", err, skip(self), fields(user_id = %user_id))]
@@ -150,7 +181,8 @@ impl TeamResolver for SurrealTeamResolver {
     }
 }
 
-/// Teams whose content the user may list/read (GET), including `team:public` for catalog.
+/// Teams whose content the user may list/read (GET), including `team:public` for catalog and the
+/// library teams of the user's organizations.
 pub async fn content_read_team_things(
     db: &Database,
     user: &User,
@@ -172,57 +204,86 @@ pub async fn content_read_team_things(
     push(public_thing.clone());
 
     let rows: Vec<TeamIdRow> = if app_admin {
-        db.db
-            .query("SELECT id FROM team WHERE id != $public")
+        db.query("SELECT id FROM team WHERE id != $public")
             .bind(("public", public_thing.clone()))
             .await?
             .take(0)?
     } else {
-        db.db
-            .query(
-                "SELECT id FROM team WHERE id != $public AND (owner = $user \
+        db.query(
+            "SELECT id FROM team WHERE id != $public AND (owner = $user \
                  OR array::len(members[WHERE user = $user]) > 0)",
-            )
-            .bind(("public", public_thing.clone()))
-            .bind(("user", ut))
-            .await?
-            .take(0)?
+        )
+        .bind(("public", public_thing.clone()))
+        .bind(("user", ut.clone()))
+        .await?
+        .take(0)?
     };
 
     for row in rows {
         push(row.id);
     }
 
+    if !app_admin {
+        // Library teams of organizations the user administers or belongs to through a team.
+        let libraries: Vec<RecordId> = db
+            .query(
+                "SELECT VALUE library FROM organization WHERE library != NONE \
+                 AND (admins CONTAINS $user OR id INSIDE (SELECT VALUE organization FROM team \
+                 WHERE organization != NONE AND (owner = $user \
+                 OR array::len(members[WHERE user = $user]) > 0)))",
+            )
+            .bind(("user", ut))
+            .await?
+            .take(0)?;
+        for library in libraries {
+            push(library);
+        }
+    }
+
     Ok(out)
 }
 
-/// Teams whose content the user may create/update/delete. Platform admin does not imply global write.
-pub async fn content_write_team_things(
-    db: &Database,
-    user: &User,
-) -> Result<Vec<RecordId>, AppError> {
-    let public_thing = public_team_thing();
-    let ut = user_thing(&user.id);
+#[derive(Debug, Deserialize, SurrealValue)]
+struct TeamGrantRow {
+    id: RecordId,
+    #[serde(default)]
+    owner: Option<RecordId>,
+    #[serde(default)]
+    membership: Vec<DbTeamMember>,
+    #[serde(default)]
+    roles: Vec<DbTeamRole>,
+}
 
-    let rows: Vec<TeamIdRow> = db
-        .db
+/// Teams on which the user holds at least one content permission, with those permissions.
+/// Platform admin does not imply global write.
+pub async fn content_team_grants(db: &Database, user: &User) -> Result<Vec<TeamGrant>, AppError> {
+    let rows: Vec<TeamGrantRow> = db
         .query(
-            "SELECT id FROM team WHERE id != $public AND (owner = $user \
-             OR array::len(members[WHERE user = $user AND (role = 'admin' \
-             OR role = 'content_maintainer')]) > 0)",
+            "SELECT id, owner, members[WHERE user = $user] AS membership, roles FROM team \
+             WHERE id != $public AND (owner = $user OR array::len(members[WHERE user = $user]) > 0)",
         )
-        .bind(("public", public_thing))
-        .bind(("user", ut))
+        .bind(("public", public_team_thing()))
+        .bind(("user", user_thing(&user.id)))
         .await?
         .take(0)?;
 
-    let mut out: Vec<RecordId> = Vec::new();
+    let mut out: Vec<TeamGrant> = Vec::new();
     let mut seen: BTreeSet<String> = BTreeSet::new();
 
     for row in rows {
-        let key = thing_record_key(&row.id);
-        if seen.insert(key) {
-            out.push(row.id);
+        let stored = TeamStored {
+            owner: row.owner,
+            members: row.membership,
+            roles: row.roles,
+        };
+        let mut permissions = member_permissions(&user.id, &stored);
+        permissions.sort();
+        permissions.dedup();
+        if !permissions.is_empty() && seen.insert(thing_record_key(&row.id)) {
+            out.push(TeamGrant {
+                team: row.id,
+                permissions,
+            });
         }
     }
 
@@ -246,7 +307,6 @@ mod tests {
         let app_admin = user.role == UserRole::Admin;
         let public_thing = public_team_thing();
         let rows = db
-            .db
             .query("SELECT * FROM team WHERE id != $public FETCH owner, members.user")
             .bind(("public", public_thing.clone()))
             .await?
@@ -273,7 +333,6 @@ mod tests {
     async fn naive_write_teams(db: &Database, user: &User) -> Result<Vec<RecordId>, AppError> {
         let public_thing = public_team_thing();
         let rows = db
-            .db
             .query("SELECT * FROM team WHERE id != $public FETCH owner, members.user")
             .bind(("public", public_thing))
             .await?
@@ -310,9 +369,13 @@ mod tests {
         let db = test_db().await.expect("test db");
         let user = seed_user(&db).await.expect("user");
         let dbref: &Database = db.as_ref();
-        let a = content_write_team_things(dbref, &user)
-            .await
-            .expect("sql write");
+        let grants = content_team_grants(dbref, &user).await.expect("sql grants");
+        assert!(
+            grants
+                .iter()
+                .all(|g| g.permissions == TeamPermission::ALL.to_vec())
+        );
+        let a: Vec<RecordId> = grants.into_iter().map(|g| g.team).collect();
         let b = naive_write_teams(dbref, &user).await.expect("rust write");
         assert_eq!(thing_key_set(&a), thing_key_set(&b));
     }
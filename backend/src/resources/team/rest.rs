@@ -8,22 +8,29 @@ use actix_web::{
     web::{self, Data, Json, Path, Query, ReqData},
 };
 use shared::api::{ListQuery, PAGE_SIZE_DEFAULT};
+use shared::team::{CreateTeam, CreateTeamRole, PatchTeam, TransferTeam, UpdateTeam};
 #[allow(unused_imports)]
-use shared::team::Team;
-use shared::team::{CreateTeam, PatchTeam, UpdateTeam};
+use shared::team::{Team, TeamRoleDefinition};
 
-use super::invitation;
 use super::service::TeamServiceHandle;
+use super::{activity, invitation, trash, webhook};
 
 pub fn scope() -> Scope {
     web::scope("/teams")
+        .service(activity::rest::team_activity_scope())
         .service(invitation::rest::team_invitations_scope())
+        .service(trash::rest::team_trash_scope())
+        .service(webhook::rest::team_webhooks_scope())
         .service(get_teams)
         .service(get_team)
         .service(create_team)
         .service(update_team)
         .service(patch_team)
         .service(delete_team)
+        .service(transfer_team)
+        .service(create_team_role)
+        .service(update_team_role)
+        .service(delete_team_role)
 }
 
 #[utoipa::path(
@@ -238,6 +245,148 @@ async fn delete_team(
     Ok(HttpResponse::NoContent().finish())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams/{id}/transfer",
+    params(
+        ("id" = String, Path, description = "Shared team identifier")
+    ),
+    request_body = TransferTeam,
+    responses(
+        (status = 200, description = "Target member is now `admin`; the caller keeps `former_admin_role`", body = Team),
+        (status = 400, description = "Personal team, target is the caller, or target is not a member", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to transfer team", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/{id}/transfer")]
+async fn transfer_team(
+    svc: Data<TeamServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+    payload: Json<TransferTeam>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(
+        svc.transfer_team_for_user(&user, &id, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams/{id}/roles",
+    params(
+        ("id" = String, Path, description = "Team identifier")
+    ),
+    request_body = CreateTeamRole,
+    responses(
+        (status = 201, description = "Role defined; assign its `id` as a member `role`", body = TeamRoleDefinition),
+        (status = 400, description = "Invalid name or permission, or the team already defines the maximum number of roles", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The team already has a role with this name", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to define role", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/{id}/roles")]
+async fn create_team_role(
+    svc: Data<TeamServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+    payload: Json<CreateTeamRole>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Created().json(
+        svc.create_team_role_for_user(&user, &id, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/teams/{id}/roles/{role_id}",
+    params(
+        ("id" = String, Path, description = "Team identifier"),
+        ("role_id" = String, Path, description = "Team role identifier")
+    ),
+    request_body = CreateTeamRole,
+    responses(
+        (status = 200, description = "Role renamed and its permissions replaced; members holding it are affected immediately", body = TeamRoleDefinition),
+        (status = 400, description = "Invalid name or permission", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or role not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The team already has another role with this name", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to update role", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[put("/{id}/roles/{role_id}")]
+async fn update_team_role(
+    svc: Data<TeamServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+    payload: Json<CreateTeamRole>,
+) -> Result<HttpResponse, AppError> {
+    let (id, role_id) = path.into_inner();
+    Ok(HttpResponse::Ok().json(
+        svc.update_team_role_for_user(&user, &id, &role_id, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/teams/{id}/roles/{role_id}",
+    params(
+        ("id" = String, Path, description = "Team identifier"),
+        ("role_id" = String, Path, description = "Team role identifier")
+    ),
+    responses(
+        (status = 204, description = "Role deleted"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or role not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Role is still assigned to members", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to delete role", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/{id}/roles/{role_id}")]
+async fn delete_team_role(
+    svc: Data<TeamServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (id, role_id) = path.into_inner();
+    svc.delete_team_role_for_user(&user, &id, &role_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
 fn filter_teams_by_q(mut teams: Vec<Team>, query: &ListQuery) -> Vec<Team> {
     let Some(needle) = query.q.as_ref().and_then(|s| {
         let t = s.trim();
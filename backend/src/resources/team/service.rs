@@ -5,24 +5,30 @@ use std::sync::Arc;
 use surrealdb::types::RecordId;
 
 use shared::patch::Patch;
-use shared::team::{CreateTeam, PatchTeam, Team, UpdateTeam};
+use shared::team::{
+    ActivityAction, ActivityResourceType, CreateTeam, CreateTeamRole, PatchTeam, Team, TeamRole,
+    TeamRoleDefinition, TransferTeam, UpdateTeam,
+};
 use shared::user::{Role as UserRole, User};
+use shared::validation_limits::MAX_TEAM_ROLES;
 use tracing::instrument;
 
 use crate::database::Database;
 use crate::error::AppError;
 
+use super::activity::{ActivityRecorder, NewTeamActivity, SurrealTeamActivityRepo};
 use super::model::{
-    DbTeamMember, TeamCreatePayload, build_create_shared_members, can_read_team, effective_admin,
-    ensure_shared_team_has_admin_after_update, inputs_to_db_members, member_or_owner_readable,
-    member_self_leave_payload, team_fetched_to_stored, team_resource_or_reject_public,
-    thing_user_id, validate_personal_members_not_owner,
+    DbTeamMember, DbTeamRole, TeamCreatePayload, TeamStored, build_create_shared_members,
+    can_read_team, effective_admin, ensure_shared_team_has_admin_after_update,
+    inputs_to_db_members, member_or_owner_readable, member_self_leave_payload, member_user_id,
+    role_str, team_fetched_to_stored, team_resource_or_reject_public, team_role_definition,
+    thing_user_id, validate_member_roles, validate_personal_members_not_owner,
 };
 use super::repository::TeamRepository;
 use super::resolver::{TeamResolver, UserPermissions};
 use super::surreal_repo::SurrealTeamRepo;
 
-fn audit_team_member_role_changes(
+fn audit_team_member_changes(
     team_id: &str,
     actor_user_id: &str,
     before: &[DbTeamMember],
@@ -32,38 +38,103 @@ fn audit_team_member_role_changes(
         .iter()
         .map(|m| (thing_user_id(&m.user), m.role.as_str()))
         .collect();
+    let mut remaining = old_map.clone();
     for m in after {
         let uid = thing_user_id(&m.user);
-        let old = old_map.get(&uid).copied().unwrap_or("");
+        remaining.remove(&uid);
         let new = m.role.as_str();
-        if old != new {
-            crate::audit!(
-                "audit.team.role.changed",
-                team_id = tracing::field::display(team_id),
-                target_user_id = tracing::field::display(&uid),
-                old_role = tracing::field::display(old),
-                new_role = tracing::field::display(new),
-                actor_user_id = tracing::field::display(actor_user_id)
-                ; "team member role changed"
-            );
+        match old_map.get(&uid).copied() {
+            None => {
+                crate::audit!(
+                    "audit.team.member.added",
+                    team_id = tracing::field::display(team_id),
+                    target_user_id = tracing::field::display(&uid),
+                    new_role = tracing::field::display(new),
+                    actor_user_id = tracing::field::display(actor_user_id)
+                    ; "team member added"
+                );
+            }
+            Some(old) if old != new => {
+                crate::audit!(
+                    "audit.team.role.changed",
+                    team_id = tracing::field::display(team_id),
+                    target_user_id = tracing::field::display(&uid),
+                    old_role = tracing::field::display(old),
+                    new_role = tracing::field::display(new),
+                    actor_user_id = tracing::field::display(actor_user_id)
+                    ; "team member role changed"
+                );
+            }
+            Some(_) => {}
         }
     }
+    for (uid, old) in remaining {
+        crate::audit!(
+            "audit.team.member.removed",
+            team_id = tracing::field::display(team_id),
+            target_user_id = tracing::field::display(&uid),
+            old_role = tracing::field::display(old),
+            actor_user_id = tracing::field::display(actor_user_id)
+            ; "team member removed"
+        );
+    }
+}
+
+/// Activity entries for members added, removed or re-roled between `before` and `after`.
+fn member_activity(actor_user_id: &str, before: &Team, after: &Team) -> Vec<NewTeamActivity> {
+    let entry = |user: &shared::team::TeamUser, action| {
+        NewTeamActivity::new(
+            &after.id,
+            actor_user_id,
+            ActivityResourceType::Member,
+            &user.id,
+            action,
+        )
+        .with_title(Some(&user.email))
+    };
+    let mut entries = Vec::new();
+    for m in &after.members {
+        match before.members.iter().find(|b| b.user.id == m.user.id) {
+            None => entries.push(entry(&m.user, ActivityAction::MemberAdded)),
+            Some(b) if b.role != m.role => {
+                entries.push(entry(&m.user, ActivityAction::MemberRoleChanged))
+            }
+            Some(_) => {}
+        }
+    }
+    for b in &before.members {
+        if !after.members.iter().any(|m| m.user.id == b.user.id) {
+            entries.push(entry(&b.user, ActivityAction::MemberRemoved));
+        }
+    }
+    entries
 }
 
 /// Application service: authorization and orchestration for teams.
 #[derive(Clone)]
-pub struct TeamService<R, TR> {
+pub struct TeamService<R, TR, A> {
     pub repo: R,
     pub resolver: Arc<TR>,
+    pub activity: A,
 }
 
-impl<R, TR> TeamService<R, TR> {
-    pub fn new(repo: R, resolver: Arc<TR>) -> Self {
-        Self { repo, resolver }
+impl<R, TR, A> TeamService<R, TR, A> {
+    pub fn new(repo: R, resolver: Arc<TR>, activity: A) -> Self {
+        Self {
+            repo,
+            resolver,
+            activity,
+        }
     }
 }
 
-impl<R: TeamRepository, TR: TeamResolver> TeamService<R, TR> {
+impl<R: TeamRepository, TR: TeamResolver, A: ActivityRecorder> TeamService<R, TR, A> {
+    async fn record_member_activity(&self, actor_user_id: &str, before: &Team, after: &Team) {
+        for entry in member_activity(actor_user_id, before, after) {
+            self.activity.record_activity_or_warn(entry).await;
+        }
+    }
+
     #[instrument(level = "debug", err, skip(self, user))]
     pub async fn list_teams_for_user(&self, user: &User) -> Result<Vec<Team>, AppError> {
         let app_admin = user.role == UserRole::Admin;
@@ -119,6 +190,7 @@ impl<R: TeamRepository, TR: TeamResolver> TeamService<R, TR> {
                 name,
                 owner: None,
                 members,
+                organization: None,
             })
             .await?;
         self.repo.load_team_display(&id).await
@@ -180,9 +252,13 @@ impl<R: TeamRepository, TR: TeamResolver> TeamService<R, TR> {
             } else {
                 ensure_shared_team_has_admin_after_update(&new_members)?;
             }
-            audit_team_member_role_changes(id, &user.id, &stored.members, &new_members);
+            validate_member_roles(&new_members, &stored.roles)?;
+            audit_team_member_changes(id, &user.id, &stored.members, &new_members);
             self.repo.update_team_members(resource, new_members).await?;
-            return self.repo.load_team_display(id).await;
+            let updated = self.repo.load_team_display(id).await?;
+            self.record_member_activity(&user.id, &row.into_team()?, &updated)
+                .await;
+            return Ok(updated);
         }
 
         self.repo
@@ -201,11 +277,15 @@ impl<R: TeamRepository, TR: TeamResolver> TeamService<R, TR> {
             } else {
                 ensure_shared_team_has_admin_after_update(&new_members)?;
             }
-            audit_team_member_role_changes(id, &user.id, &stored.members, &new_members);
+            validate_member_roles(&new_members, &stored.roles)?;
+            audit_team_member_changes(id, &user.id, &stored.members, &new_members);
             self.repo.update_team_members(resource, new_members).await?;
         }
 
-        self.repo.load_team_display(id).await
+        let updated = self.repo.load_team_display(id).await?;
+        self.record_member_activity(&user.id, &row.into_team()?, &updated)
+            .await;
+        Ok(updated)
     }
 
     #[instrument(level = "debug", err, skip(self, user, patch))]
@@ -230,6 +310,202 @@ impl<R: TeamRepository, TR: TeamResolver> TeamService<R, TR> {
             .await
     }
 
+    /// Hands the admin role of a shared team to another member in one member-list write, so the
+    /// team never loses its last admin.
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn transfer_team_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        payload: TransferTeam,
+    ) -> Result<Team, AppError> {
+        let resource = team_resource_or_reject_public(id)?;
+        let row = self
+            .repo
+            .fetch_team(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("team not found".into()))?;
+        let stored = team_fetched_to_stored(&row)?;
+        if !member_or_owner_readable(&user.id, &stored) {
+            return Err(AppError::NotFound("team not found".into()));
+        }
+        if stored.owner.is_some() {
+            return Err(AppError::invalid_request(
+                "personal teams cannot be transferred",
+            ));
+        }
+        if !effective_admin(&user.id, &stored) {
+            return Err(AppError::forbidden());
+        }
+        let target_id = member_user_id(&payload.user)?;
+        if target_id == user.id {
+            return Err(AppError::invalid_request(
+                "cannot transfer a team to yourself",
+            ));
+        }
+        if !stored
+            .members
+            .iter()
+            .any(|m| thing_user_id(&m.user) == target_id)
+        {
+            return Err(AppError::invalid_request(
+                "new admin must already be a member of the team",
+            ));
+        }
+        let former_role = payload
+            .former_admin_role
+            .unwrap_or(TeamRole::ContentMaintainer);
+        if let TeamRole::Custom(ref role_id) = former_role
+            && !stored.roles.iter().any(|r| &r.id == role_id)
+        {
+            return Err(AppError::invalid_request(format!(
+                "unknown team role `{role_id}`"
+            )));
+        }
+        let new_members: Vec<DbTeamMember> = stored
+            .members
+            .iter()
+            .map(|m| {
+                let uid = thing_user_id(&m.user);
+                let role = if uid == target_id {
+                    role_str(&TeamRole::Admin).to_owned()
+                } else if uid == user.id {
+                    role_str(&former_role).to_owned()
+                } else {
+                    m.role.clone()
+                };
+                DbTeamMember {
+                    user: m.user.clone(),
+                    role,
+                }
+            })
+            .collect();
+        audit_team_member_changes(id, &user.id, &stored.members, &new_members);
+        self.repo.update_team_members(resource, new_members).await?;
+        crate::audit!(
+            "audit.team.transferred",
+            team_id = tracing::field::display(id),
+            new_admin_user_id = tracing::field::display(&target_id),
+            former_admin_role = tracing::field::display(role_str(&former_role)),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "team admin role transferred"
+        );
+        let updated = self.repo.load_team_display(id).await?;
+        self.record_member_activity(&user.id, &row.into_team()?, &updated)
+            .await;
+        Ok(updated)
+    }
+
+    /// Team the caller administers, for role management: non-readers get 404, other members 403.
+    async fn fetch_admin_team(
+        &self,
+        user: &User,
+        id: &str,
+    ) -> Result<((String, String), TeamStored), AppError> {
+        let resource = team_resource_or_reject_public(id)?;
+        let row = self
+            .repo
+            .fetch_team(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("team not found".into()))?;
+        let stored = team_fetched_to_stored(&row)?;
+        if !member_or_owner_readable(&user.id, &stored) {
+            return Err(AppError::NotFound("team not found".into()));
+        }
+        if !effective_admin(&user.id, &stored) {
+            return Err(AppError::forbidden());
+        }
+        Ok((resource, stored))
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn create_team_role_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        payload: CreateTeamRole,
+    ) -> Result<TeamRoleDefinition, AppError> {
+        let (resource, stored) = self.fetch_admin_team(user, id).await?;
+        if stored.roles.len() >= MAX_TEAM_ROLES {
+            return Err(AppError::invalid_request(format!(
+                "a team can define at most {MAX_TEAM_ROLES} roles"
+            )));
+        }
+        let role_id = uuid::Uuid::new_v4().simple().to_string();
+        let role = team_role_definition(role_id, payload, &stored.roles)?;
+        let mut roles = stored.roles;
+        roles.push(DbTeamRole::from_definition(&role));
+        self.repo.update_team_roles(resource, roles).await?;
+        crate::audit!(
+            "audit.team.custom_role.created",
+            team_id = tracing::field::display(id),
+            role_id = tracing::field::display(&role.id),
+            permissions = tracing::field::debug(&role.permissions),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "team role defined"
+        );
+        Ok(role)
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn update_team_role_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        role_id: &str,
+        payload: CreateTeamRole,
+    ) -> Result<TeamRoleDefinition, AppError> {
+        let (resource, stored) = self.fetch_admin_team(user, id).await?;
+        let Some(index) = stored.roles.iter().position(|r| r.id == role_id) else {
+            return Err(AppError::NotFound("team role not found".into()));
+        };
+        let mut roles = stored.roles;
+        let others: Vec<DbTeamRole> = roles.iter().filter(|r| r.id != role_id).cloned().collect();
+        let role = team_role_definition(role_id.to_owned(), payload, &others)?;
+        roles[index] = DbTeamRole::from_definition(&role);
+        self.repo.update_team_roles(resource, roles).await?;
+        crate::audit!(
+            "audit.team.custom_role.updated",
+            team_id = tracing::field::display(id),
+            role_id = tracing::field::display(role_id),
+            permissions = tracing::field::debug(&role.permissions),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "team role updated"
+        );
+        Ok(role)
+    }
+
+    /// Deletes a team-defined role; it must not be assigned to any member.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn delete_team_role_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        role_id: &str,
+    ) -> Result<(), AppError> {
+        let (resource, stored) = self.fetch_admin_team(user, id).await?;
+        if !stored.roles.iter().any(|r| r.id == role_id) {
+            return Err(AppError::NotFound("team role not found".into()));
+        }
+        if stored.members.iter().any(|m| m.role == role_id) {
+            return Err(AppError::conflict("role is still assigned to team members"));
+        }
+        let roles = stored
+            .roles
+            .into_iter()
+            .filter(|r| r.id != role_id)
+            .collect();
+        self.repo.update_team_roles(resource, roles).await?;
+        crate::audit!(
+            "audit.team.custom_role.deleted",
+            team_id = tracing::field::display(id),
+            role_id = tracing::field::display(role_id),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "team role deleted"
+        );
+        Ok(())
+    }
+
     #[instrument(level = "debug", err, skip(self, user))]
     pub async fn delete_team_for_user(&self, user: &User, id: &str) -> Result<Team, AppError> {
         let perms = UserPermissions::from_ref(user, &self.resolver);
@@ -260,7 +536,8 @@ impl<R: TeamRepository, TR: TeamResolver> TeamService<R, TR> {
 }
 
 /// Production type alias used in HTTP wiring.
-pub type TeamServiceHandle = TeamService<SurrealTeamRepo, super::resolver::SurrealTeamResolver>;
+pub type TeamServiceHandle =
+    TeamService<SurrealTeamRepo, super::resolver::SurrealTeamResolver, SurrealTeamActivityRepo>;
 
 impl TeamServiceHandle {
     pub fn build(db: Arc<Database>) -> Self {
@@ -274,7 +551,11 @@ impl TeamServiceHandle {
         db: Arc<Database>,
         resolver: Arc<super::resolver::SurrealTeamResolver>,
     ) -> Self {
-        TeamService::new(SurrealTeamRepo::new(db.clone()), resolver)
+        TeamService::new(
+            SurrealTeamRepo::new(db.clone()),
+            resolver,
+            SurrealTeamActivityRepo::new(db),
+        )
     }
 }
 
@@ -853,6 +1134,98 @@ mod tests {
         assert!(!guest_teams.contains(&fx.shared_team_id));
     }
 
+    /// BLC-TEAM-020: transfer promotes an existing member and demotes the caller in one write.
+    #[tokio::test]
+    async fn blc_team_020_transfer_admin_role() {
+        use shared::team::TransferTeam;
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = mk_team_svc(&db);
+
+        let team = svc
+            .transfer_team_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                TransferTeam {
+                    user: TeamUserRef {
+                        id: fx.guest.id.clone(),
+                    },
+                    former_admin_role: None,
+                },
+            )
+            .await
+            .expect("transfer");
+        let role_of = |id: &str| {
+            team.members
+                .iter()
+                .find(|m| m.user.id == id)
+                .map(|m| m.role.clone())
+        };
+        assert_eq!(role_of(&fx.guest.id), Some(TeamRole::Admin));
+        assert_eq!(
+            role_of(&fx.admin_user.id),
+            Some(TeamRole::ContentMaintainer)
+        );
+        assert_eq!(role_of(&fx.writer.id), Some(TeamRole::ContentMaintainer));
+
+        let err = svc
+            .transfer_team_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                TransferTeam {
+                    user: TeamUserRef {
+                        id: fx.writer.id.clone(),
+                    },
+                    former_admin_role: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden));
+    }
+
+    /// BLC-TEAM-020: transfer targets must be other existing members of a shared team.
+    #[tokio::test]
+    async fn blc_team_020_transfer_rejections() {
+        use shared::team::TransferTeam;
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = mk_team_svc(&db);
+        let to = |id: &str| TransferTeam {
+            user: TeamUserRef { id: id.to_owned() },
+            former_admin_role: Some(TeamRole::Admin),
+        };
+
+        for target in [&fx.admin_user.id, &fx.non_member.id] {
+            let err = svc
+                .transfer_team_for_user(&fx.admin_user, &fx.shared_team_id, to(target))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, AppError::InvalidRequest(_)));
+        }
+        let err = svc
+            .transfer_team_for_user(&fx.owner, &fx.personal_team_id, to(&fx.guest.id))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+        let err = svc
+            .transfer_team_for_user(&fx.non_member, &fx.shared_team_id, to(&fx.guest.id))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        let team = svc
+            .transfer_team_for_user(&fx.admin_user, &fx.shared_team_id, to(&fx.guest.id))
+            .await
+            .expect("share admin role");
+        let admins = team
+            .members
+            .iter()
+            .filter(|m| m.role == TeamRole::Admin)
+            .count();
+        assert_eq!(admins, 2);
+    }
+
     /// PATCH-TEAM-001: patch with only name changes name, members left unchanged.
     #[tokio::test]
     async fn patch_team_name_only_leaves_members_unchanged() {
@@ -1102,4 +1475,273 @@ mod tests {
             "DB-filtered list must match Rust-side filter"
         );
     }
+
+    fn role_body(name: &str, permissions: &[shared::team::TeamPermission]) -> CreateTeamRole {
+        CreateTeamRole {
+            name: name.into(),
+            permissions: permissions.to_vec(),
+        }
+    }
+
+    /// Reassigns `user_id` on the fixture's shared team to `role` as the team admin.
+    async fn assign_role(
+        svc: &TeamServiceHandle,
+        fx: &TeamFixture,
+        user_id: &str,
+        role: TeamRole,
+    ) -> Result<Team, AppError> {
+        let team = svc
+            .get_team_for_user(&fx.admin_user, &fx.shared_team_id)
+            .await?;
+        let members = team
+            .members
+            .into_iter()
+            .map(|m| TeamMemberInput {
+                role: if m.user.id == user_id {
+                    role.clone()
+                } else {
+                    m.role
+                },
+                user: TeamUserRef { id: m.user.id },
+            })
+            .collect();
+        svc.update_team_for_user(
+            &fx.admin_user,
+            &fx.shared_team_id,
+            UpdateTeam {
+                name: team.name,
+                members: Some(members),
+            },
+        )
+        .await
+    }
+
+    /// BLC-TROLE-001, BLC-TROLE-004: a member with a setlist-planner role writes setlists of the
+    /// team but not its songs.
+    #[tokio::test]
+    async fn blc_trole_001_custom_role_grants_listed_permissions_only() {
+        use shared::team::TeamPermission;
+
+        use crate::test_helpers::{setlist_service, setlist_with_songs, song_service};
+
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = mk_team_svc(&db);
+
+        let role = svc
+            .create_team_role_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                role_body(
+                    "  Setlist planner ",
+                    &[
+                        TeamPermission::SetlistsDelete,
+                        TeamPermission::SetlistsWrite,
+                        TeamPermission::SetlistsWrite,
+                    ],
+                ),
+            )
+            .await
+            .expect("define role");
+        assert_eq!(role.name, "Setlist planner");
+        assert_eq!(
+            role.permissions,
+            vec![
+                TeamPermission::SetlistsWrite,
+                TeamPermission::SetlistsDelete
+            ]
+        );
+        let team = assign_role(&svc, &fx, &fx.guest.id, TeamRole::Custom(role.id.clone()))
+            .await
+            .expect("assign");
+        assert_eq!(team.roles.len(), 1);
+        assert!(
+            team.members
+                .iter()
+                .any(|m| m.user.id == fx.guest.id && m.role == TeamRole::Custom(role.id.clone()))
+        );
+
+        let setlists = setlist_service(&db);
+        let perms = UserPermissions::from_ref(&fx.guest, &setlists.teams);
+        let mut setlist = setlist_with_songs("Sunday", &[]);
+        setlist.owner = Some(fx.shared_team_id.clone());
+        setlists
+            .create_setlist_for_user(&perms, setlist)
+            .await
+            .expect("planner creates setlist");
+
+        let songs = song_service(&db);
+        let perms = UserPermissions::from_ref(&fx.guest, &songs.teams);
+        let err = songs
+            .create_song_for_user(
+                &perms,
+                shared::song::CreateSong {
+                    owner: Some(fx.shared_team_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: crate::test_helpers::minimal_song_data(),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+    }
+
+    /// BLC-TROLE-001: `blobs:write` without `blobs:delete` creates blobs but cannot delete them;
+    /// BLC-TROLE-006: permission changes apply to members immediately.
+    #[tokio::test]
+    async fn blc_trole_001_write_without_delete() {
+        use shared::blob::{CreateBlob, FileType};
+        use shared::team::TeamPermission;
+
+        use crate::test_helpers::blob_service;
+
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = mk_team_svc(&db);
+        let role = svc
+            .create_team_role_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                role_body("Media tech", &[TeamPermission::BlobsWrite]),
+            )
+            .await
+            .expect("define role");
+        assign_role(&svc, &fx, &fx.writer.id, TeamRole::Custom(role.id.clone()))
+            .await
+            .expect("assign");
+
+        let blobs = blob_service(&db, blob_dir.path().to_string_lossy().into_owned());
+        let perms = UserPermissions::from_ref(&fx.writer, &blobs.teams);
+        let blob = blobs
+            .create_blob_for_user(
+                &perms,
+                CreateBlob {
+                    owner: Some(fx.shared_team_id.clone()),
+                    file_type: FileType::PNG,
+                    width: 10,
+                    height: 10,
+                    ocr: String::new(),
+                },
+            )
+            .await
+            .expect("media tech uploads");
+        let err = blobs
+            .delete_blob_for_user(&perms, &blob.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        svc.update_team_role_for_user(
+            &fx.admin_user,
+            &fx.shared_team_id,
+            &role.id,
+            role_body(
+                "Media tech",
+                &[TeamPermission::BlobsWrite, TeamPermission::BlobsDelete],
+            ),
+        )
+        .await
+        .expect("update role");
+        let perms = UserPermissions::from_ref(&fx.writer, &blobs.teams);
+        blobs
+            .delete_blob_for_user(&perms, &blob.id)
+            .await
+            .expect("delete after grant");
+    }
+
+    /// BLC-TROLE-002, BLC-TROLE-003: role names are validated and unique per team; members can
+    /// only hold presets or roles defined on the team.
+    #[tokio::test]
+    async fn blc_trole_002_validation() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = mk_team_svc(&db);
+        svc.create_team_role_for_user(&fx.admin_user, &fx.shared_team_id, role_body("Band", &[]))
+            .await
+            .expect("define role");
+
+        for (name, conflict) in [(" ", false), ("Admin", false), ("band", true)] {
+            let err = svc
+                .create_team_role_for_user(&fx.admin_user, &fx.shared_team_id, role_body(name, &[]))
+                .await
+                .unwrap_err();
+            if conflict {
+                assert!(matches!(err, AppError::Conflict(_)), "{name}");
+            } else {
+                assert!(matches!(err, AppError::InvalidRequest(_)), "{name}");
+            }
+        }
+
+        let err = assign_role(&svc, &fx, &fx.guest.id, TeamRole::Custom("nope".into()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+        let err = svc
+            .create_shared_team_for_user(
+                &fx.admin_user,
+                CreateTeam {
+                    name: "Fresh".into(),
+                    members: vec![TeamMemberInput {
+                        user: TeamUserRef {
+                            id: fx.guest.id.clone(),
+                        },
+                        role: TeamRole::Custom("nope".into()),
+                    }],
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+    }
+
+    /// BLC-TROLE-005: only team admins manage roles; assigned roles cannot be deleted.
+    #[tokio::test]
+    async fn blc_trole_005_management_acl_and_delete() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = mk_team_svc(&db);
+
+        let err = svc
+            .create_team_role_for_user(&fx.writer, &fx.shared_team_id, role_body("Choir", &[]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden));
+        let err = svc
+            .create_team_role_for_user(&fx.non_member, &fx.shared_team_id, role_body("Choir", &[]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        let role = svc
+            .create_team_role_for_user(&fx.admin_user, &fx.shared_team_id, role_body("Choir", &[]))
+            .await
+            .expect("define role");
+        assign_role(&svc, &fx, &fx.guest.id, TeamRole::Custom(role.id.clone()))
+            .await
+            .expect("assign");
+        let err = svc
+            .delete_team_role_for_user(&fx.writer, &fx.shared_team_id, &role.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden));
+        let err = svc
+            .delete_team_role_for_user(&fx.admin_user, &fx.shared_team_id, &role.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+
+        assign_role(&svc, &fx, &fx.guest.id, TeamRole::Guest)
+            .await
+            .expect("unassign");
+        svc.delete_team_role_for_user(&fx.admin_user, &fx.shared_team_id, &role.id)
+            .await
+            .expect("delete role");
+        let err = svc
+            .delete_team_role_for_user(&fx.admin_user, &fx.shared_team_id, &role.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+    }
 }
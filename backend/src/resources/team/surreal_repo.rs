@@ -9,8 +9,8 @@ use crate::database::Database;
 use crate::error::AppError;
 
 use super::model::{
-    DbTeamMember, TeamCreatePayload, TeamFetched, TeamIdRow, team_resource_or_reject_public,
-    user_thing,
+    DbTeamMember, DbTeamRole, TeamCreatePayload, TeamFetched, TeamIdRow,
+    team_resource_or_reject_public, user_thing,
 };
 use super::repository::TeamRepository;
 
@@ -35,7 +35,6 @@ impl TeamRepository for SurrealTeamRepo {
         let public_thing = super::model::public_team_thing();
         Ok(self
             .inner()
-            .db
             .query("SELECT * FROM team WHERE id != $public FETCH owner, members.user")
             .bind(("public", public_thing))
             .await?
@@ -51,7 +50,6 @@ impl TeamRepository for SurrealTeamRepo {
         let db = self.inner();
         if is_admin {
             Ok(db
-                .db
                 .query("SELECT * FROM team WHERE id != $public FETCH owner, members.user")
                 .bind(("public", public_thing))
                 .await?
@@ -59,7 +57,6 @@ impl TeamRepository for SurrealTeamRepo {
         } else {
             let ut = user_thing(user_id);
             Ok(db
-                .db
                 .query(
                     "SELECT * FROM team WHERE id != $public \
                      AND (owner = $user OR array::len(members[WHERE user = $user]) > 0) \
@@ -76,7 +73,6 @@ impl TeamRepository for SurrealTeamRepo {
         let resource = team_resource_or_reject_public(id)?;
         Ok(self
             .inner()
-            .db
             .query("SELECT * FROM $tid FETCH owner, members.user")
             .bind(("tid", RecordId::new(resource.0, resource.1)))
             .await?
@@ -84,7 +80,7 @@ impl TeamRepository for SurrealTeamRepo {
     }
 
     async fn create_team(&self, payload: TeamCreatePayload) -> Result<String, AppError> {
-        let created: Option<TeamIdRow> = self.inner().db.create("team").content(payload).await?;
+        let created: Option<TeamIdRow> = self.inner().create("team").content(payload).await?;
         created
             .ok_or_else(|| AppError::database("failed to create team"))
             .map(|row| crate::database::record_id_string(&row.id))
@@ -97,7 +93,6 @@ impl TeamRepository for SurrealTeamRepo {
     ) -> Result<(), AppError> {
         let mut response = self
             .inner()
-            .db
             .query("UPDATE $tid SET name = $name")
             .bind(("tid", RecordId::new(resource.0, resource.1)))
             .bind(("name", name.to_owned()))
@@ -116,7 +111,6 @@ impl TeamRepository for SurrealTeamRepo {
     ) -> Result<(), AppError> {
         let mut response = self
             .inner()
-            .db
             .query("UPDATE $tid SET members = $members")
             .bind(("tid", RecordId::new(resource.0, resource.1)))
             .bind(("members", members))
@@ -128,14 +122,24 @@ impl TeamRepository for SurrealTeamRepo {
         Ok(())
     }
 
-    async fn delete_team_record(&self, resource: (String, String)) -> Result<(), AppError> {
-        let tid = RecordId::new(resource.0, resource.1);
+    async fn update_team_roles(
+        &self,
+        resource: (String, String),
+        roles: Vec<DbTeamRole>,
+    ) -> Result<(), AppError> {
         let mut response = self
             .inner()
-            .db
-            .query("DELETE $tid")
-            .bind(("tid", tid))
+            .query("UPDATE $tid SET roles = $roles")
+            .bind(("tid", RecordId::new(resource.0, resource.1)))
+            .bind(("roles", roles))
             .await?;
+        crate::database::surreal_take_errors("team.update_team_roles", &mut response)?;
+        Ok(())
+    }
+
+    async fn delete_team_record(&self, resource: (String, String)) -> Result<(), AppError> {
+        let tid = RecordId::new(resource.0, resource.1);
+        let mut response = self.inner().query("DELETE $tid").bind(("tid", tid)).await?;
         crate::database::surreal_take_errors("team.delete_team_record", &mut response)?;
         let _ = response.check().map_err(|e| {
             crate::log_and_convert!(AppError::database, "team.delete_team_record.check", e)
@@ -144,11 +148,10 @@ impl TeamRepository for SurrealTeamRepo {
     }
 
     async fn reassign_content(&self, from: RecordId, to: RecordId) -> Result<(), AppError> {
-        for table in ["blob", "song", "collection", "setlist"] {
+        for table in ["blob", "song", "collection", "setlist", "trash"] {
             let q = format!("UPDATE {table} SET owner = $to WHERE owner = $from");
             let mut response = self
                 .inner()
-                .db
                 .query(&q)
                 .bind(("to", to.clone()))
                 .bind(("from", from.clone()))
@@ -165,7 +168,6 @@ impl TeamRepository for SurrealTeamRepo {
         let resource = team_resource_or_reject_public(id)?;
         let row = self
             .inner()
-            .db
             .query("SELECT * FROM $tid FETCH owner, members.user")
             .bind(("tid", RecordId::new(resource.0, resource.1)))
             .await?
@@ -0,0 +1,12 @@
+mod model;
+
+pub mod repository;
+pub use repository::TrashRepository;
+
+mod surreal_repo;
+pub use surreal_repo::SurrealTrashRepo;
+
+pub mod service;
+pub use service::{TrashService, TrashServiceHandle};
+
+pub mod rest;
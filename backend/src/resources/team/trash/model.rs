@@ -0,0 +1,96 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Deserialize;
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::team::{TeamUser, TrashItem, TrashResourceType};
+
+use crate::database::record_id_string;
+use crate::error::AppError;
+use crate::resources::user::UserRecord;
+
+fn parse_resource_type(value: &str) -> Result<TrashResourceType, AppError> {
+    TrashResourceType::parse(value)
+        .ok_or_else(|| AppError::database(format!("unknown trash.resource_type {value:?}")))
+}
+
+/// Row of `trash` with `deleted_by` fetched, as listed for a team.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct TrashRow {
+    pub id: RecordId,
+    pub owner: RecordId,
+    pub resource: RecordId,
+    pub resource_type: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub deleted_at: Datetime,
+    #[serde(default)]
+    pub deleted_by: Option<UserRecord>,
+}
+
+impl TrashRow {
+    /// `retention` is how long entries stay before purge; it only feeds `purge_at`.
+    pub fn into_item(self, retention: ChronoDuration) -> Result<TrashItem, AppError> {
+        let deleted_at: DateTime<Utc> = self.deleted_at.into();
+        Ok(TrashItem {
+            id: record_id_string(&self.id),
+            team_id: record_id_string(&self.owner),
+            resource_type: parse_resource_type(&self.resource_type)?,
+            resource_id: record_id_string(&self.resource),
+            title: self.title,
+            deleted_by: self.deleted_by.map(|u| {
+                let u = u.into_user();
+                TeamUser {
+                    id: u.id,
+                    email: u.email,
+                }
+            }),
+            deleted_at,
+            purge_at: deleted_at + retention,
+        })
+    }
+}
+
+/// The parts of a `trash` entry needed to restore or purge the resource it points at.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct TrashEntryRow {
+    pub owner: RecordId,
+    pub resource: RecordId,
+    pub resource_type: String,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl TrashEntryRow {
+    pub fn kind(&self) -> Result<TrashResourceType, AppError> {
+        parse_resource_type(&self.resource_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purge_at_is_deleted_at_plus_retention() {
+        let deleted_at: DateTime<Utc> = "2026-10-01T12:00:00Z".parse().unwrap();
+        let item = TrashRow {
+            id: RecordId::new("trash", "e1"),
+            owner: RecordId::new("team", "t1"),
+            resource: RecordId::new("song", "s1"),
+            resource_type: "song".into(),
+            title: Some("Amazing Grace".into()),
+            deleted_at: deleted_at.into(),
+            deleted_by: None,
+        }
+        .into_item(ChronoDuration::days(30))
+        .unwrap();
+        assert_eq!(item.id, "e1");
+        assert_eq!(item.team_id, "t1");
+        assert_eq!(item.resource_id, "s1");
+        assert_eq!(item.resource_type, TrashResourceType::Song);
+        assert_eq!(
+            item.purge_at,
+            "2026-10-31T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use surrealdb::types::{Datetime, RecordId};
+
+use crate::error::AppError;
+use crate::resources::blob::Blob;
+
+use super::model::{TrashEntryRow, TrashRow};
+
+/// Pure trash data access — no authorization. Entries are written by DB events when a resource's
+/// `trashed_at` is set or cleared; this trait only reads them and acts on the resources.
+#[async_trait]
+pub trait TrashRepository: Send + Sync {
+    /// Entries for one team, most recently deleted first.
+    async fn list_team_trash(
+        &self,
+        team: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<TrashRow>, AppError>;
+
+    async fn count_team_trash(&self, team: RecordId) -> Result<u64, AppError>;
+
+    async fn get_trash_entry(
+        &self,
+        team: RecordId,
+        entry_id: &str,
+    ) -> Result<Option<TrashEntryRow>, AppError>;
+
+    /// Clears `trashed_at` / `trashed_by`, which also removes the trash entry.
+    async fn restore_resource(&self, resource: RecordId) -> Result<(), AppError>;
+
+    /// Entries deleted before `cutoff`, across all teams.
+    async fn expired_trash(&self, cutoff: Datetime) -> Result<Vec<TrashEntryRow>, AppError>;
+
+    /// Deletes a still-trashed song, setlist or collection permanently.
+    async fn purge_resource(&self, resource: RecordId) -> Result<(), AppError>;
+
+    /// Deletes a still-trashed blob record permanently and returns it so its bytes can be removed.
+    async fn purge_blob(&self, resource: RecordId) -> Result<Option<Blob>, AppError>;
+
+    /// Blob records, trashed ones included, still referencing content `sha256`.
+    async fn count_blob_content_references(&self, sha256: &str) -> Result<u64, AppError>;
+}
@@ -0,0 +1,115 @@
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::resources::User;
+use crate::resources::team::UserPermissions;
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, Scope, get, post,
+    web::{self, Data, Path, Query, ReqData},
+};
+
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+#[allow(unused_imports)]
+use shared::team::TrashItem;
+
+use super::service::TrashServiceHandle;
+
+pub fn team_trash_scope() -> Scope {
+    web::scope("/{team_id}/trash")
+        .service(list_team_trash)
+        .service(restore_trash_item)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{team_id}/trash",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("page" = Option<u32>, Query, description = "Page index, zero-based.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Deleted songs, setlists, collections and blobs, most recently deleted first. `X-Total-Count` is the total before paging.", body = [TrashItem]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team not found or not readable", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("")]
+async fn list_team_trash(
+    req: HttpRequest,
+    svc: Data<TrashServiceHandle>,
+    user: ReqData<User>,
+    team_id: Path<String>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let q_link = query.clone();
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let perms = UserPermissions::from_ref(&user, &svc.teams);
+    let (items, total) = svc
+        .list_trash_for_user(&perms, team_id.as_str(), query.as_list_query())
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                &req,
+                |p| q_link.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(items))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams/{team_id}/trash/{id}/restore",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("id" = String, Path, description = "Trash item identifier (`TrashItem.id`)")
+    ),
+    responses(
+        (status = 204, description = "Resource restored; it is visible again and the trash item is gone"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Caller may read the team but lacks the delete permission for this kind of resource", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team not readable or trash item not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/{id}/restore")]
+async fn restore_trash_item(
+    svc: Data<TrashServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (team_id, id) = path.into_inner();
+    let perms = UserPermissions::from_ref(&user, &svc.teams);
+    svc.restore_trash_item_for_user(&perms, &team_id, &id)
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
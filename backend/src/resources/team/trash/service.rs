@@ -0,0 +1,486 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use surrealdb::types::RecordId;
+use tracing::instrument;
+
+use shared::api::ListQuery;
+use shared::team::{ActivityAction, TeamPermission, TrashItem, TrashResourceType};
+use shared::user::Role as UserRole;
+
+use crate::database::{Database, record_id_string};
+use crate::error::AppError;
+use crate::resources::blob::storage::{BlobBackend, BlobStorage, lock_content};
+use crate::resources::job::{JobContext, JobHandler};
+use crate::resources::team::activity::{ActivityRecorder, NewTeamActivity};
+use crate::resources::team::model::{
+    can_read_team, team_fetched_to_stored, team_resource_or_reject_public,
+};
+use crate::resources::team::repository::TeamRepository;
+use crate::resources::team::resolver::{SurrealTeamResolver, TeamResolver, UserPermissions};
+use crate::resources::team::surreal_repo::SurrealTeamRepo;
+use crate::resources::team::webhook::ContentEventRecorder;
+
+use super::repository::TrashRepository;
+use super::surreal_repo::SurrealTrashRepo;
+
+/// Restoring undoes a delete, so it takes the same permission as deleting.
+fn restore_permission(kind: TrashResourceType) -> TeamPermission {
+    match kind {
+        TrashResourceType::Song => TeamPermission::SongsDelete,
+        TrashResourceType::Setlist => TeamPermission::SetlistsDelete,
+        TrashResourceType::Collection => TeamPermission::CollectionsDelete,
+        TrashResourceType::Blob => TeamPermission::BlobsDelete,
+    }
+}
+
+/// Application service for a team's trash: listing, restore and the retention purge.
+#[derive(Clone)]
+pub struct TrashService<R, TR, T, A, S> {
+    pub team_repo: R,
+    pub trash_repo: TR,
+    pub teams: Arc<T>,
+    activity: A,
+    storage: S,
+    retention: ChronoDuration,
+}
+
+impl<R, TR, T, A, S> TrashService<R, TR, T, A, S> {
+    pub fn new(
+        team_repo: R,
+        trash_repo: TR,
+        teams: Arc<T>,
+        activity: A,
+        storage: S,
+        retention_days: u32,
+    ) -> Self {
+        Self {
+            team_repo,
+            trash_repo,
+            teams,
+            activity,
+            storage,
+            retention: ChronoDuration::days(i64::from(retention_days)),
+        }
+    }
+}
+
+impl<R, TR, T, A, S> TrashService<R, TR, T, A, S>
+where
+    R: TeamRepository,
+    TR: TrashRepository,
+    T: TeamResolver,
+    A: ActivityRecorder,
+    S: BlobStorage,
+{
+    /// Resolves `team_id` to its record, rejecting teams the user cannot read with 404.
+    async fn readable_team(
+        &self,
+        perms: &UserPermissions<T>,
+        team_id: &str,
+    ) -> Result<RecordId, AppError> {
+        let resource = team_resource_or_reject_public(team_id)?;
+        let row = self
+            .team_repo
+            .fetch_team(team_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("team not found".into()))?;
+        let stored = team_fetched_to_stored(&row)?;
+        let user = perms.user();
+        if !can_read_team(&user.id, &stored, user.role == UserRole::Admin) {
+            return Err(AppError::NotFound("team not found".into()));
+        }
+        Ok(RecordId::new(resource.0, resource.1))
+    }
+
+    /// Trash of one team, most recently deleted first. Readable by anyone who can read the team.
+    #[instrument(level = "debug", err, skip(self, perms, pagination))]
+    pub async fn list_trash_for_user(
+        &self,
+        perms: &UserPermissions<T>,
+        team_id: &str,
+        pagination: ListQuery,
+    ) -> Result<(Vec<TrashItem>, u64), AppError> {
+        let team = self.readable_team(perms, team_id).await?;
+        let (offset, limit) = pagination.effective_offset_limit();
+        let (rows, total) = tokio::try_join!(
+            self.trash_repo.list_team_trash(team.clone(), offset, limit),
+            self.trash_repo.count_team_trash(team),
+        )?;
+        let page = rows
+            .into_iter()
+            .map(|r| r.into_item(self.retention))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((page, total))
+    }
+
+    /// Puts a trashed resource back where it was. Requires the delete permission for its kind.
+    #[instrument(level = "debug", err, skip(self, perms))]
+    pub async fn restore_trash_item_for_user(
+        &self,
+        perms: &UserPermissions<T>,
+        team_id: &str,
+        entry_id: &str,
+    ) -> Result<(), AppError> {
+        let team = self.readable_team(perms, team_id).await?;
+        let entry = self
+            .trash_repo
+            .get_trash_entry(team.clone(), entry_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("trash item not found".into()))?;
+        let kind = entry.kind()?;
+        if !perms
+            .teams_with(restore_permission(kind))
+            .await?
+            .contains(&team)
+        {
+            return Err(AppError::forbidden());
+        }
+        self.trash_repo
+            .restore_resource(entry.resource.clone())
+            .await?;
+        self.activity
+            .record_activity_or_warn(
+                NewTeamActivity::new(
+                    &record_id_string(&entry.owner),
+                    &perms.user().id,
+                    kind.activity_type(),
+                    &record_id_string(&entry.resource),
+                    ActivityAction::Restored,
+                )
+                .with_title(entry.title.as_deref()),
+            )
+            .await;
+        Ok(())
+    }
+
+    /// Permanently deletes everything that has been in a trash longer than the retention,
+    /// including blob bytes no other blob shares. Returns the number of resources removed.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let expired = self
+            .trash_repo
+            .expired_trash((now - self.retention).into())
+            .await?;
+        let mut purged = 0;
+        for entry in expired {
+            if entry.kind()? == TrashResourceType::Blob {
+                if let Some(blob) = self.trash_repo.purge_blob(entry.resource).await? {
+                    match &blob.sha256 {
+                        Some(hash) => {
+                            let _lock = lock_content(hash).await;
+                            if self.trash_repo.count_blob_content_references(hash).await? == 0 {
+                                self.storage.delete_content(hash).await;
+                            }
+                        }
+                        None => self.storage.delete_legacy_file(&blob).await,
+                    }
+                }
+            } else {
+                self.trash_repo.purge_resource(entry.resource).await?;
+            }
+            purged += 1;
+        }
+        Ok(purged)
+    }
+}
+
+/// Runs scheduled `trash.purge` jobs: [`purge_expired`](TrashService::purge_expired).
+#[async_trait]
+impl<R, TR, T, A, S> JobHandler for TrashService<R, TR, T, A, S>
+where
+    R: TeamRepository,
+    TR: TrashRepository,
+    T: TeamResolver,
+    A: ActivityRecorder,
+    S: BlobStorage,
+{
+    async fn run(&self, _ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+        let purged = self.purge_expired(Utc::now()).await?;
+        Ok(Some(serde_json::json!({ "purged": purged })))
+    }
+}
+
+/// Production type alias used in HTTP wiring.
+pub type TrashServiceHandle = TrashService<
+    SurrealTeamRepo,
+    SurrealTrashRepo,
+    SurrealTeamResolver,
+    ContentEventRecorder,
+    BlobBackend,
+>;
+
+impl TrashServiceHandle {
+    pub fn build(
+        db: Arc<Database>,
+        team_resolver: Arc<SurrealTeamResolver>,
+        storage: BlobBackend,
+        retention_days: u32,
+    ) -> Self {
+        TrashService::new(
+            SurrealTeamRepo::new(db.clone()),
+            SurrealTrashRepo::new(db.clone()),
+            team_resolver,
+            ContentEventRecorder::build(db),
+            storage,
+            retention_days,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration as ChronoDuration, Utc};
+
+    use shared::api::ListQuery;
+    use shared::blob::{CreateBlob, FileType};
+    use shared::song::CreateSong;
+    use shared::team::{ActivityAction, TrashResourceType};
+
+    use crate::error::AppError;
+    use crate::resources::blob::storage::content_path;
+    use crate::resources::team::UserPermissions;
+    use crate::test_helpers::{
+        TeamFixture, activity_service, blob_service, create_song_with_title, create_user,
+        minimal_song_data, personal_team_id, setlist_service, setlist_with_songs, song_service,
+        test_db, trash_service,
+    };
+
+    /// BLC-TRASH-001, BLC-TRASH-002, BLC-TRASH-004: a deleted song leaves lists and setlists,
+    /// shows up in the trash, and restoring it brings back the setlist link.
+    #[tokio::test]
+    async fn blc_trash_001_delete_and_restore_song() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "trash-owner@test.local").await.expect("u");
+        let team_id = personal_team_id(&db, &owner).await.expect("team");
+        let song = create_song_with_title(&db, &owner, "Amazing Grace")
+            .await
+            .expect("song");
+        let setlists = setlist_service(&db);
+        let sl_perms = UserPermissions::from_ref(&owner, &setlists.teams);
+        let setlist = setlists
+            .create_setlist_for_user(
+                &sl_perms,
+                setlist_with_songs("Sunday", &[(song.id.as_str(), None)]),
+            )
+            .await
+            .expect("setlist");
+
+        let songs = song_service(&db);
+        let song_perms = UserPermissions::from_ref(&owner, &songs.teams);
+        songs
+            .delete_song_for_user(&song_perms, &song.id)
+            .await
+            .expect("delete");
+        assert!(matches!(
+            songs.get_song_for_user(&song_perms, &song.id).await,
+            Err(AppError::NotFound(_))
+        ));
+        let hidden = setlists
+            .get_setlist_for_user(&sl_perms, &setlist.id)
+            .await
+            .expect("setlist");
+        assert!(hidden.songs.is_empty());
+        let player = setlists
+            .setlist_player_for_user(&sl_perms, &setlist.id)
+            .await
+            .expect("player");
+        assert!(player.toc().is_empty());
+
+        let trash = trash_service(&db, String::new());
+        let perms = UserPermissions::from_ref(&owner, &trash.teams);
+        let (items, total) = trash
+            .list_trash_for_user(&perms, &team_id, ListQuery::default())
+            .await
+            .expect("list");
+        assert_eq!(total, 1);
+        let item = &items[0];
+        assert_eq!(item.resource_type, TrashResourceType::Song);
+        assert_eq!(item.resource_id, song.id);
+        assert_eq!(item.team_id, team_id);
+        assert_eq!(item.title.as_deref(), Some("Amazing Grace"));
+        assert_eq!(
+            item.deleted_by.as_ref().map(|u| u.id.as_str()),
+            Some(owner.id.as_str())
+        );
+        assert_eq!(item.purge_at - item.deleted_at, ChronoDuration::days(30));
+
+        trash
+            .restore_trash_item_for_user(&perms, &team_id, &item.id)
+            .await
+            .expect("restore");
+        songs
+            .get_song_for_user(&song_perms, &song.id)
+            .await
+            .expect("song is back");
+        let restored = setlists
+            .get_setlist_for_user(&sl_perms, &setlist.id)
+            .await
+            .expect("setlist");
+        assert_eq!(restored.songs.len(), 1);
+        let (items, total) = trash
+            .list_trash_for_user(&perms, &team_id, ListQuery::default())
+            .await
+            .expect("list");
+        assert!(items.is_empty());
+        assert_eq!(total, 0);
+
+        let (activity, _) = activity_service(&db)
+            .list_activity_for_user(&owner, &team_id, ListQuery::default())
+            .await
+            .expect("activity");
+        assert_eq!(activity[0].action, ActivityAction::Restored);
+        assert_eq!(activity[0].resource_id, song.id);
+    }
+
+    /// BLC-TRASH-003, BLC-TRASH-004: team readers list the trash; only members who may delete
+    /// that kind of resource restore it.
+    #[tokio::test]
+    async fn blc_trash_003_listing_and_restore_acl() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let songs = song_service(&db);
+        let writer_songs = UserPermissions::from_ref(&fx.writer, &songs.teams);
+        let song = songs
+            .create_song_for_user(
+                &writer_songs,
+                CreateSong {
+                    owner: Some(fx.shared_team_id.clone()),
+                    not_a_song: false,
+                    blobs: vec![],
+                    data: minimal_song_data(),
+                },
+            )
+            .await
+            .expect("song");
+        songs
+            .delete_song_for_user(&writer_songs, &song.id)
+            .await
+            .expect("delete");
+
+        let trash = trash_service(&db, String::new());
+        let guest = UserPermissions::from_ref(&fx.guest, &trash.teams);
+        let (items, _) = trash
+            .list_trash_for_user(&guest, &fx.shared_team_id, ListQuery::default())
+            .await
+            .expect("guest lists");
+        assert_eq!(items.len(), 1);
+        let entry_id = items[0].id.clone();
+
+        let outsider = UserPermissions::from_ref(&fx.non_member, &trash.teams);
+        assert!(matches!(
+            trash
+                .list_trash_for_user(&outsider, &fx.shared_team_id, ListQuery::default())
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            trash
+                .restore_trash_item_for_user(&outsider, &fx.shared_team_id, &entry_id)
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            trash
+                .restore_trash_item_for_user(&guest, &fx.shared_team_id, &entry_id)
+                .await,
+            Err(AppError::Forbidden)
+        ));
+
+        let writer = UserPermissions::from_ref(&fx.writer, &trash.teams);
+        assert!(matches!(
+            trash
+                .restore_trash_item_for_user(&writer, &fx.shared_team_id, "never-trashed")
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        // The entry belongs to the shared team, not to the writer's personal one.
+        let writer_team = personal_team_id(&db, &fx.writer).await.expect("team");
+        assert!(matches!(
+            trash
+                .restore_trash_item_for_user(&writer, &writer_team, &entry_id)
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        trash
+            .restore_trash_item_for_user(&writer, &fx.shared_team_id, &entry_id)
+            .await
+            .expect("writer restores");
+    }
+
+    /// BLC-TRASH-005: the purge only removes entries older than the retention, and deletes
+    /// blob bytes with the blob.
+    #[tokio::test]
+    async fn blc_trash_005_purge_after_retention() {
+        let blob_dir = tempfile::tempdir().expect("tempdir");
+        let dir = blob_dir.path().to_string_lossy().into_owned();
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "trash-purge@test.local").await.expect("u");
+        let team_id = personal_team_id(&db, &owner).await.expect("team");
+
+        let blobs = blob_service(&db, dir.clone());
+        let blob_perms = UserPermissions::from_ref(&owner, &blobs.teams);
+        let new_blob = || CreateBlob {
+            owner: None,
+            file_type: FileType::PNG,
+            width: 1,
+            height: 1,
+            ocr: String::new(),
+        };
+        let content_file = |hash: &Option<String>| {
+            std::path::Path::new(&dir).join(content_path(hash.as_deref().expect("sha256")))
+        };
+        let mut uploaded = Vec::new();
+        for data in [&b"png"[..], b"shared", b"shared"] {
+            let blob = blobs
+                .create_blob_for_user(&blob_perms, new_blob())
+                .await
+                .expect("blob");
+            uploaded.push(
+                blobs
+                    .upload_blob_data_for_user(&blob_perms, &blob.id, data)
+                    .await
+                    .expect("upload"),
+            );
+        }
+        // The unique blob and one of the two sharing "shared" go to the trash.
+        for blob in &uploaded[..2] {
+            blobs
+                .delete_blob_for_user(&blob_perms, &blob.id)
+                .await
+                .expect("delete blob");
+        }
+        assert!(content_file(&uploaded[0].sha256).is_file());
+
+        let song = create_song_with_title(&db, &owner, "Old")
+            .await
+            .expect("song");
+        let songs = song_service(&db);
+        let song_perms = UserPermissions::from_ref(&owner, &songs.teams);
+        songs
+            .delete_song_for_user(&song_perms, &song.id)
+            .await
+            .expect("delete song");
+
+        let trash = trash_service(&db, dir.clone());
+        assert_eq!(trash.purge_expired(Utc::now()).await.expect("purge"), 0);
+        let later = Utc::now() + ChronoDuration::days(31);
+        assert_eq!(trash.purge_expired(later).await.expect("purge"), 3);
+
+        assert!(!content_file(&uploaded[0].sha256).exists());
+        assert!(
+            content_file(&uploaded[2].sha256).is_file(),
+            "content still referenced by a live blob is kept"
+        );
+        let perms = UserPermissions::from_ref(&owner, &trash.teams);
+        let (items, _) = trash
+            .list_trash_for_user(&perms, &team_id, ListQuery::default())
+            .await
+            .expect("list");
+        assert!(items.is_empty());
+        let remaining: Option<crate::resources::song::SongRecord> =
+            db.select(("song", song.id.as_str())).await.expect("select");
+        assert!(remaining.is_none());
+    }
+}
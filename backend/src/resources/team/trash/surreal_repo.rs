@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::resources::blob::{Blob, BlobRecord};
+
+use super::model::{TrashEntryRow, TrashRow};
+use super::repository::TrashRepository;
+
+#[derive(Clone)]
+pub struct SurrealTrashRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealTrashRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl TrashRepository for SurrealTrashRepo {
+    async fn list_team_trash(
+        &self,
+        team: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<TrashRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM trash WHERE owner = $team ORDER BY deleted_at DESC LIMIT $limit START $start FETCH deleted_by",
+            )
+            .bind(("team", team))
+            .bind(("limit", limit))
+            .bind(("start", offset))
+            .await?
+            .take(0)?)
+    }
+
+    async fn count_team_trash(&self, team: RecordId) -> Result<u64, AppError> {
+        #[derive(Deserialize, SurrealValue)]
+        struct CountResult {
+            count: u64,
+        }
+        Ok(self
+            .inner()
+            .query("SELECT count() FROM trash WHERE owner = $team GROUP ALL")
+            .bind(("team", team))
+            .await?
+            .take::<Vec<CountResult>>(0)?
+            .into_iter()
+            .next()
+            .map(|r| r.count)
+            .unwrap_or(0))
+    }
+
+    async fn get_trash_entry(
+        &self,
+        team: RecordId,
+        entry_id: &str,
+    ) -> Result<Option<TrashEntryRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT owner, resource, resource_type, title FROM $entry WHERE owner = $team")
+            .bind(("entry", RecordId::new("trash", entry_id.to_owned())))
+            .bind(("team", team))
+            .await?
+            .take::<Vec<TrashEntryRow>>(0)?
+            .into_iter()
+            .next())
+    }
+
+    async fn restore_resource(&self, resource: RecordId) -> Result<(), AppError> {
+        let mut response = self
+            .inner()
+            .query(
+                "UPDATE $resource SET trashed_at = NONE, trashed_by = NONE WHERE trashed_at != NONE RETURN NONE",
+            )
+            .bind(("resource", resource))
+            .await?;
+        crate::database::surreal_take_errors("trash.restore", &mut response)?;
+        Ok(())
+    }
+
+    async fn expired_trash(&self, cutoff: Datetime) -> Result<Vec<TrashEntryRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT owner, resource, resource_type, title FROM trash WHERE deleted_at < $cutoff",
+            )
+            .bind(("cutoff", cutoff))
+            .await?
+            .take(0)?)
+    }
+
+    async fn purge_resource(&self, resource: RecordId) -> Result<(), AppError> {
+        let mut response = self
+            .inner()
+            .query("DELETE $resource WHERE trashed_at != NONE RETURN NONE")
+            .bind(("resource", resource))
+            .await?;
+        crate::database::surreal_take_errors("trash.purge", &mut response)?;
+        Ok(())
+    }
+
+    async fn purge_blob(&self, resource: RecordId) -> Result<Option<Blob>, AppError> {
+        let rows: Vec<BlobRecord> = self
+            .inner()
+            .query("DELETE $resource WHERE trashed_at != NONE RETURN BEFORE")
+            .bind(("resource", resource))
+            .await?
+            .take(0)?;
+        Ok(rows.into_iter().next().map(BlobRecord::into_blob))
+    }
+
+    async fn count_blob_content_references(&self, sha256: &str) -> Result<u64, AppError> {
+        #[derive(Deserialize, SurrealValue)]
+        struct CountResult {
+            count: u64,
+        }
+        let rows: Vec<CountResult> = self
+            .inner()
+            .query("SELECT count() FROM blob WHERE sha256 = $sha256 GROUP ALL")
+            .bind(("sha256", sha256.to_owned()))
+            .await?
+            .take(0)?;
+        Ok(rows.into_iter().next().map(|r| r.count).unwrap_or(0))
+    }
+}
@@ -0,0 +1,18 @@
+mod model;
+
+pub mod repository;
+pub use repository::WebhookRepository;
+
+mod surreal_repo;
+pub use surreal_repo::SurrealWebhookRepo;
+
+pub mod sender;
+pub use sender::{HttpWebhookSender, WebhookSender};
+
+mod publisher;
+pub use publisher::{ActivityAndWebhooks, ContentEventRecorder};
+
+pub mod service;
+pub use service::{WebhookService, WebhookServiceHandle};
+
+pub mod rest;
@@ -0,0 +1,349 @@
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::team::{Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent};
+
+use crate::database::record_id_string;
+use crate::error::AppError;
+use crate::resources::team::activity::NewTeamActivity;
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct WebhookRow {
+    pub id: RecordId,
+    pub team: RecordId,
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub secret: String,
+    pub created_at: Datetime,
+}
+
+impl WebhookRow {
+    pub fn into_webhook(self) -> Result<Webhook, AppError> {
+        Ok(Webhook {
+            id: record_id_string(&self.id),
+            team_id: record_id_string(&self.team),
+            url: self.url,
+            events: self
+                .events
+                .iter()
+                .map(|e| parse_event(e))
+                .collect::<Result<Vec<_>, _>>()?,
+            active: self.active,
+            created_at: self.created_at.into(),
+        })
+    }
+}
+
+#[derive(Serialize, SurrealValue)]
+pub struct WebhookCreate {
+    pub team: RecordId,
+    pub created_by: RecordId,
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub secret: String,
+}
+
+#[derive(Serialize, SurrealValue)]
+pub struct WebhookUpdate {
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct DeliveryRow {
+    pub id: RecordId,
+    pub webhook: RecordId,
+    pub event: String,
+    pub status: String,
+    pub attempts: u32,
+    #[serde(default)]
+    pub response_status: Option<u16>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub next_attempt_at: Option<Datetime>,
+    pub payload: String,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub delivered_at: Option<Datetime>,
+}
+
+impl DeliveryRow {
+    pub fn into_delivery(self) -> Result<WebhookDelivery, AppError> {
+        Ok(WebhookDelivery {
+            id: record_id_string(&self.id),
+            webhook_id: record_id_string(&self.webhook),
+            event: parse_event(&self.event)?,
+            status: WebhookDeliveryStatus::parse(&self.status).ok_or_else(|| {
+                AppError::database(format!("unknown webhook_delivery.status {:?}", self.status))
+            })?,
+            attempts: self.attempts,
+            response_status: self.response_status,
+            last_error: self.last_error,
+            next_attempt_at: self.next_attempt_at.map(Into::into),
+            payload: self.payload,
+            created_at: self.created_at.into(),
+            delivered_at: self.delivered_at.map(Into::into),
+        })
+    }
+}
+
+/// Pending delivery with its webhook fetched, as picked up by the delivery worker.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct DueDeliveryRow {
+    pub id: RecordId,
+    pub webhook: WebhookRow,
+    pub event: String,
+    pub attempts: u32,
+    pub payload: String,
+}
+
+#[derive(Serialize, SurrealValue)]
+pub struct DeliveryCreate {
+    pub webhook: RecordId,
+    pub event: String,
+    pub payload: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<Datetime>,
+}
+
+/// State of a delivery after one attempt.
+#[derive(Clone, Debug, PartialEq, Serialize, SurrealValue)]
+pub struct DeliveryAttempt {
+    pub status: String,
+    pub attempts: u32,
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<Datetime>,
+    pub delivered_at: Option<Datetime>,
+}
+
+/// Result of one HTTP attempt: the response status, or a transport error.
+pub type SendResult = Result<u16, String>;
+
+/// Wait before retry number `attempt` (1-based): 30 s doubling per attempt, capped at 6 h.
+pub fn retry_delay(attempt: u32) -> chrono::Duration {
+    const BASE_SECONDS: i64 = 30;
+    const MAX_SECONDS: i64 = 6 * 60 * 60;
+    let factor = 1i64 << attempt.saturating_sub(1).min(20);
+    chrono::Duration::seconds((BASE_SECONDS * factor).min(MAX_SECONDS))
+}
+
+/// Next delivery state after an attempt. `attempts` already includes this attempt; with
+/// `retry == false` (test deliveries) a failure is final.
+pub fn attempt_outcome(
+    result: SendResult,
+    attempts: u32,
+    max_attempts: u32,
+    retry: bool,
+    now: DateTime<Utc>,
+) -> DeliveryAttempt {
+    let (response_status, error) = match result {
+        Ok(status) if (200..300).contains(&status) => {
+            return DeliveryAttempt {
+                status: WebhookDeliveryStatus::Succeeded.as_str().to_owned(),
+                attempts,
+                response_status: Some(status),
+                last_error: None,
+                next_attempt_at: None,
+                delivered_at: Some(now.into()),
+            };
+        }
+        Ok(status) => (
+            Some(status),
+            format!("endpoint responded with HTTP {status}"),
+        ),
+        Err(e) => (None, e),
+    };
+    let retry_at = (retry && attempts < max_attempts).then(|| now + retry_delay(attempts));
+    DeliveryAttempt {
+        status: if retry_at.is_some() {
+            WebhookDeliveryStatus::Pending
+        } else {
+            WebhookDeliveryStatus::Failed
+        }
+        .as_str()
+        .to_owned(),
+        attempts,
+        response_status,
+        last_error: Some(error),
+        next_attempt_at: retry_at.map(Into::into),
+        delivered_at: None,
+    }
+}
+
+#[derive(Serialize)]
+struct EventPayload<'a> {
+    event: &'a str,
+    occurred_at: DateTime<Utc>,
+    team_id: String,
+    resource: Option<ResourcePayload<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actor_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    other_team_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ResourcePayload<'a> {
+    #[serde(rename = "type")]
+    resource_type: &'a str,
+    id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+}
+
+/// JSON body for a content event; receivers fetch the resource itself through the API.
+pub fn event_payload(event: WebhookEvent, entry: &NewTeamActivity, at: DateTime<Utc>) -> String {
+    serde_json::json!(EventPayload {
+        event: event.as_str(),
+        occurred_at: at,
+        team_id: record_id_string(&entry.team),
+        resource: Some(ResourcePayload {
+            resource_type: entry.resource_type.as_str(),
+            id: &entry.resource_id,
+            title: entry.title.as_deref(),
+        }),
+        actor_id: entry.actor.as_ref().map(record_id_string),
+        other_team_id: entry.other_team.as_ref().map(record_id_string),
+    })
+    .to_string()
+}
+
+/// JSON body of a test delivery.
+pub fn ping_payload(team: &RecordId, actor_user_id: &str, at: DateTime<Utc>) -> String {
+    serde_json::json!(EventPayload {
+        event: WebhookEvent::Ping.as_str(),
+        occurred_at: at,
+        team_id: record_id_string(team),
+        resource: None,
+        actor_id: Some(actor_user_id.to_owned()),
+        other_team_id: None,
+    })
+    .to_string()
+}
+
+/// Hex HMAC-SHA256 of `body` keyed with `secret`, as sent in `X-WorshipViewer-Signature`.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hex::encode(hmac::sign(&key, body).as_ref())
+}
+
+pub fn generate_secret() -> Result<String, AppError> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Internal("failed to generate webhook secret".into()))?;
+    Ok(hex::encode(bytes))
+}
+
+pub fn webhook_thing(webhook_id: &str) -> Result<RecordId, AppError> {
+    let id = webhook_id.trim();
+    if id.is_empty() {
+        return Err(AppError::NotFound("webhook not found".into()));
+    }
+    if let Ok(rid) = RecordId::parse_simple(id)
+        && rid.table.as_str() == "webhook"
+    {
+        return Ok(rid);
+    }
+    Ok(RecordId::new("webhook", id))
+}
+
+fn parse_event(value: &str) -> Result<WebhookEvent, AppError> {
+    WebhookEvent::parse(value)
+        .ok_or_else(|| AppError::database(format!("unknown webhook event {value:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use shared::team::{ActivityAction, ActivityResourceType};
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-10-19T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    /// BLC-WHK-006: retries back off exponentially up to a cap.
+    #[test]
+    fn blc_whk_006_retry_delay_doubles_and_caps() {
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(30));
+        assert_eq!(retry_delay(2), chrono::Duration::seconds(60));
+        assert_eq!(retry_delay(5), chrono::Duration::seconds(480));
+        assert_eq!(retry_delay(30), chrono::Duration::hours(6));
+    }
+
+    /// BLC-WHK-006: non-2xx and transport errors are retried until `max_attempts`.
+    #[test]
+    fn blc_whk_006_attempt_outcome() {
+        let ok = attempt_outcome(Ok(204), 1, 3, true, now());
+        assert_eq!(ok.status, "succeeded");
+        assert!(ok.delivered_at.is_some());
+
+        let retry = attempt_outcome(Ok(500), 1, 3, true, now());
+        assert_eq!(retry.status, "pending");
+        assert_eq!(retry.response_status, Some(500));
+        assert_eq!(
+            retry.next_attempt_at,
+            Some((now() + chrono::Duration::seconds(30)).into())
+        );
+
+        let last = attempt_outcome(Err("connection refused".into()), 3, 3, true, now());
+        assert_eq!(last.status, "failed");
+        assert_eq!(last.last_error.as_deref(), Some("connection refused"));
+        assert!(last.next_attempt_at.is_none());
+
+        let test_delivery = attempt_outcome(Ok(500), 1, 3, false, now());
+        assert_eq!(test_delivery.status, "failed");
+    }
+
+    /// BLC-WHK-005: signature is the hex HMAC-SHA256 of the body keyed with the secret.
+    #[test]
+    fn blc_whk_005_sign_payload_matches_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn event_payload_carries_resource_and_teams() {
+        let entry = NewTeamActivity::new(
+            "t1",
+            "u1",
+            ActivityResourceType::Setlist,
+            "s1",
+            ActivityAction::Moved,
+        )
+        .with_title(Some("Sunday"))
+        .with_other_team("t2");
+        let body: serde_json::Value =
+            serde_json::from_str(&event_payload(WebhookEvent::SetlistMoved, &entry, now()))
+                .unwrap();
+        assert_eq!(body["event"], "setlist.moved");
+        assert_eq!(body["team_id"], "t1");
+        assert_eq!(body["other_team_id"], "t2");
+        assert_eq!(body["actor_id"], "u1");
+        assert_eq!(body["resource"]["type"], "setlist");
+        assert_eq!(body["resource"]["id"], "s1");
+        assert_eq!(body["resource"]["title"], "Sunday");
+    }
+
+    #[test]
+    fn generated_secrets_are_distinct_hex() {
+        let a = generate_secret().unwrap();
+        let b = generate_secret().unwrap();
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use shared::team::WebhookEvent;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::resources::team::activity::{
+    ActivityRecorder, NewTeamActivity, SurrealTeamActivityRepo,
+};
+
+use super::model::event_payload;
+use super::repository::WebhookRepository;
+use super::surreal_repo::SurrealWebhookRepo;
+
+/// [`ActivityRecorder`] for the content services: writes the activity entry and queues a
+/// webhook delivery for every subscription of the team that wants this kind of change.
+#[derive(Clone)]
+pub struct ActivityAndWebhooks<A, W> {
+    pub activity: A,
+    pub webhooks: W,
+}
+
+#[async_trait]
+impl<A: ActivityRecorder, W: WebhookRepository> ActivityRecorder for ActivityAndWebhooks<A, W> {
+    async fn record_activity(&self, entry: NewTeamActivity) -> Result<(), AppError> {
+        let Some(event) = WebhookEvent::from_activity(entry.resource_type, entry.action) else {
+            return self.activity.record_activity(entry).await;
+        };
+        let payload = event_payload(event, &entry, Utc::now());
+        let team = entry.team.clone();
+        let recorded = self.activity.record_activity(entry).await;
+        self.webhooks
+            .enqueue_event(team, event.as_str(), payload)
+            .await?;
+        recorded
+    }
+}
+
+/// Production recorder used by the song, collection and setlist services.
+pub type ContentEventRecorder = ActivityAndWebhooks<SurrealTeamActivityRepo, SurrealWebhookRepo>;
+
+impl ContentEventRecorder {
+    pub fn build(db: Arc<Database>) -> Self {
+        Self {
+            activity: SurrealTeamActivityRepo::new(db.clone()),
+            webhooks: SurrealWebhookRepo::new(db),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use surrealdb::types::{Datetime, RecordId};
+
+use crate::error::AppError;
+
+use super::model::{
+    DeliveryAttempt, DeliveryCreate, DeliveryRow, DueDeliveryRow, WebhookCreate, WebhookRow,
+    WebhookUpdate,
+};
+
+/// Pure webhook / delivery data access — no authorization. Service layer does all ACL checks.
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn create_webhook(
+        &self,
+        webhook_id: &str,
+        create: WebhookCreate,
+    ) -> Result<WebhookRow, AppError>;
+
+    /// All webhooks of a team, oldest first.
+    async fn list_webhooks(&self, team: RecordId) -> Result<Vec<WebhookRow>, AppError>;
+
+    async fn get_webhook(&self, webhook: RecordId) -> Result<Option<WebhookRow>, AppError>;
+
+    async fn update_webhook(
+        &self,
+        webhook: RecordId,
+        update: WebhookUpdate,
+    ) -> Result<WebhookRow, AppError>;
+
+    /// Delete a webhook (and, via cascade, its delivery log); returns whether it existed.
+    async fn delete_webhook(&self, webhook: RecordId) -> Result<bool, AppError>;
+
+    /// Queue one pending delivery of `payload` for every active webhook of `team` subscribed to
+    /// `event`. Returns the number of deliveries queued.
+    async fn enqueue_event(
+        &self,
+        team: RecordId,
+        event: &str,
+        payload: String,
+    ) -> Result<u64, AppError>;
+
+    async fn create_delivery(&self, create: DeliveryCreate) -> Result<DeliveryRow, AppError>;
+
+    /// Delivery log of one webhook, newest first.
+    async fn list_deliveries(
+        &self,
+        webhook: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<DeliveryRow>, AppError>;
+
+    async fn count_deliveries(&self, webhook: RecordId) -> Result<u64, AppError>;
+
+    /// Pending deliveries whose next attempt is due at `now`, oldest first.
+    async fn due_deliveries(
+        &self,
+        now: Datetime,
+        limit: u32,
+    ) -> Result<Vec<DueDeliveryRow>, AppError>;
+
+    async fn record_attempt(
+        &self,
+        delivery: RecordId,
+        attempt: DeliveryAttempt,
+    ) -> Result<DeliveryRow, AppError>;
+}
@@ -0,0 +1,310 @@
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::resources::User;
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, Scope, delete, get, post, put,
+    web::{self, Data, Json, Path, Query, ReqData},
+};
+
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+use shared::team::CreateWebhook;
+#[allow(unused_imports)]
+use shared::team::{CreatedWebhook, Webhook, WebhookDelivery};
+
+use super::service::WebhookServiceHandle;
+
+pub fn team_webhooks_scope() -> Scope {
+    web::scope("/{team_id}/webhooks")
+        .service(create_team_webhook)
+        .service(list_team_webhooks)
+        .service(get_team_webhook)
+        .service(update_team_webhook)
+        .service(delete_team_webhook)
+        .service(list_team_webhook_deliveries)
+        .service(test_team_webhook)
+}
+
+fn paged_response<T: serde::Serialize>(
+    req: &HttpRequest,
+    query: &PageQuery,
+    items: Vec<T>,
+    total: u64,
+) -> HttpResponse {
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                req,
+                |p| query.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(items)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams/{team_id}/webhooks",
+    params(
+        ("team_id" = String, Path, description = "Team identifier")
+    ),
+    request_body = CreateWebhook,
+    responses(
+        (status = 201, description = "Webhook created; the signing `secret` is only returned here", body = CreatedWebhook),
+        (status = 400, description = "Invalid URL or event list", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("")]
+async fn create_team_webhook(
+    svc: Data<WebhookServiceHandle>,
+    user: ReqData<User>,
+    team_id: Path<String>,
+    payload: Json<CreateWebhook>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Created().json(
+        svc.create_webhook_for_user(&user, team_id.as_str(), payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{team_id}/webhooks",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("page" = Option<u32>, Query, description = "Page index, zero-based. Omit with `page_size` for full list.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50. Omit with `page` for full list.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Webhooks of the team, oldest first. `X-Total-Count` is the total before paging.", body = [Webhook]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("")]
+async fn list_team_webhooks(
+    req: HttpRequest,
+    svc: Data<WebhookServiceHandle>,
+    user: ReqData<User>,
+    team_id: Path<String>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let (webhooks, total) = svc
+        .list_webhooks_for_user(&user, team_id.as_str(), query.as_list_query())
+        .await?;
+    Ok(paged_response(&req, &query, webhooks, total))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{team_id}/webhooks/{webhook_id}",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("webhook_id" = String, Path, description = "Webhook identifier")
+    ),
+    responses(
+        (status = 200, description = "Webhook", body = Webhook),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or webhook not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/{webhook_id}")]
+async fn get_team_webhook(
+    svc: Data<WebhookServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (team_id, webhook_id) = path.into_inner();
+    Ok(HttpResponse::Ok().json(
+        svc.get_webhook_for_user(&user, &team_id, &webhook_id)
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/teams/{team_id}/webhooks/{webhook_id}",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("webhook_id" = String, Path, description = "Webhook identifier")
+    ),
+    request_body = CreateWebhook,
+    responses(
+        (status = 200, description = "Webhook updated; the secret is unchanged", body = Webhook),
+        (status = 400, description = "Invalid URL or event list", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or webhook not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[put("/{webhook_id}")]
+async fn update_team_webhook(
+    svc: Data<WebhookServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+    payload: Json<CreateWebhook>,
+) -> Result<HttpResponse, AppError> {
+    let (team_id, webhook_id) = path.into_inner();
+    Ok(HttpResponse::Ok().json(
+        svc.update_webhook_for_user(&user, &team_id, &webhook_id, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/teams/{team_id}/webhooks/{webhook_id}",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("webhook_id" = String, Path, description = "Webhook identifier")
+    ),
+    responses(
+        (status = 204, description = "Webhook and its delivery log removed"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or webhook not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/{webhook_id}")]
+async fn delete_team_webhook(
+    svc: Data<WebhookServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (team_id, webhook_id) = path.into_inner();
+    svc.delete_webhook_for_user(&user, &team_id, &webhook_id)
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{team_id}/webhooks/{webhook_id}/deliveries",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("webhook_id" = String, Path, description = "Webhook identifier"),
+        ("page" = Option<u32>, Query, description = "Page index, zero-based.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Delivery log, newest first. `X-Total-Count` is the total before paging.", body = [WebhookDelivery]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or webhook not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/{webhook_id}/deliveries")]
+async fn list_team_webhook_deliveries(
+    req: HttpRequest,
+    svc: Data<WebhookServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let (team_id, webhook_id) = path.into_inner();
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let (deliveries, total) = svc
+        .list_deliveries_for_user(&user, &team_id, &webhook_id, query.as_list_query())
+        .await?;
+    Ok(paged_response(&req, &query, deliveries, total))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams/{team_id}/webhooks/{webhook_id}/test",
+    params(
+        ("team_id" = String, Path, description = "Team identifier"),
+        ("webhook_id" = String, Path, description = "Webhook identifier")
+    ),
+    responses(
+        (status = 200, description = "A `ping` was sent once (no retries); the logged delivery reports the outcome", body = WebhookDelivery),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a team admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Team or webhook not found", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Teams",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/{webhook_id}/test")]
+async fn test_team_webhook(
+    svc: Data<WebhookServiceHandle>,
+    user: ReqData<User>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (team_id, webhook_id) = path.into_inner();
+    Ok(HttpResponse::Ok().json(
+        svc.test_webhook_for_user(&user, &team_id, &webhook_id)
+            .await?,
+    ))
+}
@@ -0,0 +1,227 @@
+use std::error::Error as StdError;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+use super::model::SendResult;
+
+pub const EVENT_HEADER: &str = "x-worshipviewer-event";
+pub const DELIVERY_HEADER: &str = "x-worshipviewer-delivery";
+pub const SIGNATURE_HEADER: &str = "x-worshipviewer-signature";
+
+/// One signed webhook `POST`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WebhookRequest {
+    pub url: String,
+    pub event: String,
+    pub delivery_id: String,
+    /// Value for the signature header (`sha256=<hex>`).
+    pub signature: String,
+    pub body: String,
+}
+
+/// Transport for webhook deliveries; swapped for a recording fake in tests.
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    async fn send(&self, request: WebhookRequest) -> SendResult;
+}
+
+const NON_PUBLIC_TARGET: &str = "webhook target is not a public address";
+
+/// Whether `ip` is reachable on the public internet: not loopback, private, link-local,
+/// unique-local, shared (CGNAT), documentation, multicast, broadcast or unspecified.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_ip(IpAddr::V4(v4)),
+            None => {
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || v6.is_unique_local()
+                    || v6.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// Resolves webhook hosts and drops non-public addresses. reqwest connects to exactly the
+/// addresses returned here, so a DNS answer that changes after the check cannot redirect the
+/// request.
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(NON_PUBLIC_TARGET.into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// `err` with its causes, so a rejected target shows up in the delivery log.
+fn error_chain(err: &reqwest::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[derive(Clone)]
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+    allow_private_targets: bool,
+}
+
+impl HttpWebhookSender {
+    /// Unless `allow_private_targets`, requests only reach public addresses; proxies from the
+    /// environment are ignored so they cannot relay to internal hosts.
+    pub fn new(timeout: Duration, allow_private_targets: bool) -> Result<Self, reqwest::Error> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent(concat!(
+                "WorshipViewer-Webhooks/",
+                env!("CARGO_PKG_VERSION")
+            ));
+        if !allow_private_targets {
+            builder = builder
+                .no_proxy()
+                .dns_resolver(Arc::new(PublicOnlyResolver));
+        }
+        Ok(Self {
+            client: builder.build()?,
+            allow_private_targets,
+        })
+    }
+
+    /// IP-literal hosts bypass the resolver, so they are checked here.
+    fn check_ip_literal(&self, url: &str) -> Result<(), String> {
+        if self.allow_private_targets {
+            return Ok(());
+        }
+        let url = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+        let host = url.host_str().unwrap_or_default();
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let Ok(ip) = host.parse::<IpAddr>() else {
+            return Ok(());
+        };
+        if is_public_ip(ip) {
+            Ok(())
+        } else {
+            Err(NON_PUBLIC_TARGET.to_owned())
+        }
+    }
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, request: WebhookRequest) -> SendResult {
+        self.check_ip_literal(&request.url)?;
+        self.client
+            .post(&request.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &request.event)
+            .header(DELIVERY_HEADER, &request.delivery_id)
+            .header(SIGNATURE_HEADER, &request.signature)
+            .body(request.body)
+            .send()
+            .await
+            .map(|response| response.status().as_u16())
+            .map_err(|e| error_chain(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    use super::{
+        HttpWebhookSender, NON_PUBLIC_TARGET, WebhookRequest, WebhookSender, is_public_ip,
+    };
+
+    fn request(url: &str) -> WebhookRequest {
+        WebhookRequest {
+            url: url.to_owned(),
+            event: "ping".into(),
+            delivery_id: "d1".into(),
+            signature: "sha256=00".into(),
+            body: "{}".into(),
+        }
+    }
+
+    /// BLC-WHK-009: loopback, private, link-local, unique-local and unspecified addresses are
+    /// not public; ordinary internet addresses are.
+    #[test]
+    fn blc_whk_009_non_public_address_classes() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+    }
+
+    /// BLC-WHK-009: deliveries to internal targets fail before any connection, whether the URL
+    /// names an IP or a host resolving to one.
+    #[tokio::test]
+    async fn blc_whk_009_sender_refuses_internal_targets() {
+        let sender = HttpWebhookSender::new(Duration::from_secs(2), false).expect("client");
+        for url in [
+            "http://127.0.0.1:9/hook",
+            "http://[::1]:9/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost:9/hook",
+        ] {
+            let err = sender.send(request(url)).await.expect_err(url);
+            assert!(err.contains(NON_PUBLIC_TARGET), "{url}: {err}");
+        }
+
+        let permissive = HttpWebhookSender::new(Duration::from_secs(2), true).expect("client");
+        let err = permissive
+            .send(request("http://127.0.0.1:9/hook"))
+            .await
+            .expect_err("nothing listens on the discard port");
+        assert!(!err.contains(NON_PUBLIC_TARGET), "{err}");
+    }
+}
@@ -0,0 +1,840 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use surrealdb::types::RecordId;
+
+use shared::api::ListQuery;
+use shared::team::{CreateWebhook, CreatedWebhook, Webhook, WebhookDelivery, WebhookEvent};
+use shared::user::User;
+use tracing::instrument;
+
+use crate::database::{Database, record_id_string};
+use crate::error::AppError;
+use crate::resources::job::{JobContext, JobHandler};
+use crate::resources::team::model::{
+    effective_admin, member_or_owner_readable, team_fetched_to_stored,
+    team_resource_or_reject_public, user_thing,
+};
+use crate::resources::team::repository::TeamRepository;
+use crate::resources::team::surreal_repo::SurrealTeamRepo;
+
+use super::model::{
+    DeliveryAttempt, DeliveryCreate, DeliveryRow, WebhookCreate, WebhookRow, WebhookUpdate,
+    attempt_outcome, generate_secret, ping_payload, sign_payload, webhook_thing,
+};
+use super::repository::WebhookRepository;
+use super::sender::{HttpWebhookSender, WebhookRequest, WebhookSender};
+use super::surreal_repo::SurrealWebhookRepo;
+
+/// Pending deliveries handled per worker tick.
+const DELIVERY_BATCH: u32 = 50;
+const MAX_URL_LEN: usize = 2048;
+
+/// Validated `(url, events)` of a create/update body; events are sorted and de-duplicated.
+fn validate_webhook(payload: &CreateWebhook) -> Result<(String, Vec<String>), AppError> {
+    let url = payload.url.trim();
+    if url.len() > MAX_URL_LEN {
+        return Err(AppError::invalid_request(
+            "webhook url is too long (max 2048 characters)",
+        ));
+    }
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|_| AppError::invalid_request("webhook url must be an absolute URL"))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(AppError::invalid_request(
+            "webhook url must use http or https and name a host",
+        ));
+    }
+    if payload.events.is_empty() {
+        return Err(AppError::invalid_request(
+            "webhook must subscribe to at least one event",
+        ));
+    }
+    if payload.events.contains(&WebhookEvent::Ping) {
+        return Err(AppError::invalid_request(
+            "`ping` is sent by the test endpoint and cannot be subscribed to",
+        ));
+    }
+    let mut events = payload.events.clone();
+    events.sort();
+    events.dedup();
+    Ok((
+        url.to_owned(),
+        events.iter().map(|e| e.as_str().to_owned()).collect(),
+    ))
+}
+
+/// Application service for team webhook subscriptions and their delivery.
+#[derive(Clone)]
+pub struct WebhookService<R, W, S> {
+    pub team_repo: R,
+    pub webhook_repo: W,
+    pub sender: S,
+    /// Attempts per event delivery before it is marked `failed`.
+    pub max_attempts: u32,
+}
+
+impl<R, W, S> WebhookService<R, W, S> {
+    pub fn new(team_repo: R, webhook_repo: W, sender: S, max_attempts: u32) -> Self {
+        Self {
+            team_repo,
+            webhook_repo,
+            sender,
+            max_attempts: max_attempts.max(1),
+        }
+    }
+}
+
+impl<R: TeamRepository, W: WebhookRepository, S: WebhookSender> WebhookService<R, W, S> {
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn create_webhook_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        payload: CreateWebhook,
+    ) -> Result<CreatedWebhook, AppError> {
+        let team = self
+            .assert_team_admin_for_webhooks(&user.id, team_id)
+            .await?;
+        let (url, events) = validate_webhook(&payload)?;
+        let secret = generate_secret()?;
+        let row = self
+            .webhook_repo
+            .create_webhook(
+                &Uuid::new_v4().to_string(),
+                WebhookCreate {
+                    team,
+                    created_by: user_thing(&user.id),
+                    url,
+                    events,
+                    active: payload.active,
+                    secret: secret.clone(),
+                },
+            )
+            .await?;
+        crate::audit!(
+            "audit.team.webhook.created",
+            team_id = tracing::field::display(team_id),
+            webhook_id = tracing::field::display(record_id_string(&row.id)),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "webhook created"
+        );
+        Ok(CreatedWebhook {
+            webhook: row.into_webhook()?,
+            secret,
+        })
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_webhooks_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        pagination: ListQuery,
+    ) -> Result<(Vec<Webhook>, u64), AppError> {
+        let team = self
+            .assert_team_admin_for_webhooks(&user.id, team_id)
+            .await?;
+        let webhooks = self
+            .webhook_repo
+            .list_webhooks(team)
+            .await?
+            .into_iter()
+            .map(WebhookRow::into_webhook)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ListQuery::paginate_vec(webhooks, &pagination))
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn get_webhook_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        webhook_id: &str,
+    ) -> Result<Webhook, AppError> {
+        let team = self
+            .assert_team_admin_for_webhooks(&user.id, team_id)
+            .await?;
+        self.load_team_webhook(&team, webhook_id)
+            .await?
+            .into_webhook()
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn update_webhook_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        webhook_id: &str,
+        payload: CreateWebhook,
+    ) -> Result<Webhook, AppError> {
+        let team = self
+            .assert_team_admin_for_webhooks(&user.id, team_id)
+            .await?;
+        let (url, events) = validate_webhook(&payload)?;
+        let row = self.load_team_webhook(&team, webhook_id).await?;
+        self.webhook_repo
+            .update_webhook(
+                row.id,
+                WebhookUpdate {
+                    url,
+                    events,
+                    active: payload.active,
+                },
+            )
+            .await?
+            .into_webhook()
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn delete_webhook_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        webhook_id: &str,
+    ) -> Result<(), AppError> {
+        let team = self
+            .assert_team_admin_for_webhooks(&user.id, team_id)
+            .await?;
+        let row = self.load_team_webhook(&team, webhook_id).await?;
+        if !self.webhook_repo.delete_webhook(row.id).await? {
+            return Err(AppError::NotFound("webhook not found".into()));
+        }
+        crate::audit!(
+            "audit.team.webhook.deleted",
+            team_id = tracing::field::display(team_id),
+            webhook_id = tracing::field::display(webhook_id),
+            actor_user_id = tracing::field::display(&user.id)
+            ; "webhook deleted"
+        );
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_deliveries_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        webhook_id: &str,
+        pagination: ListQuery,
+    ) -> Result<(Vec<WebhookDelivery>, u64), AppError> {
+        let team = self
+            .assert_team_admin_for_webhooks(&user.id, team_id)
+            .await?;
+        let row = self.load_team_webhook(&team, webhook_id).await?;
+        let (offset, limit) = pagination.effective_offset_limit();
+        let total = self.webhook_repo.count_deliveries(row.id.clone()).await?;
+        let deliveries = self
+            .webhook_repo
+            .list_deliveries(row.id, offset, limit)
+            .await?
+            .into_iter()
+            .map(DeliveryRow::into_delivery)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((deliveries, total))
+    }
+
+    /// Sends a `ping` to the webhook right away (single attempt, no retries) and returns the
+    /// resulting log entry. Works for inactive webhooks too.
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn test_webhook_for_user(
+        &self,
+        user: &User,
+        team_id: &str,
+        webhook_id: &str,
+    ) -> Result<WebhookDelivery, AppError> {
+        let team = self
+            .assert_team_admin_for_webhooks(&user.id, team_id)
+            .await?;
+        let webhook = self.load_team_webhook(&team, webhook_id).await?;
+        let now = Utc::now();
+        let delivery = self
+            .webhook_repo
+            .create_delivery(DeliveryCreate {
+                webhook: webhook.id.clone(),
+                event: WebhookEvent::Ping.as_str().to_owned(),
+                payload: ping_payload(&team, &user.id, now),
+                next_attempt_at: None,
+            })
+            .await?;
+        self.attempt_delivery(
+            &webhook,
+            delivery.id,
+            &delivery.event,
+            0,
+            delivery.payload,
+            false,
+            now,
+        )
+        .await?
+        .into_delivery()
+    }
+
+    /// Attempts every pending delivery that is due at `now`. Returns how many were attempted.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn deliver_due(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let due = self
+            .webhook_repo
+            .due_deliveries(now.into(), DELIVERY_BATCH)
+            .await?;
+        let mut attempted = 0;
+        for delivery in due {
+            if !delivery.webhook.active {
+                self.webhook_repo
+                    .record_attempt(
+                        delivery.id,
+                        DeliveryAttempt {
+                            status: "failed".into(),
+                            attempts: delivery.attempts,
+                            response_status: None,
+                            last_error: Some("webhook is inactive".into()),
+                            next_attempt_at: None,
+                            delivered_at: None,
+                        },
+                    )
+                    .await?;
+                continue;
+            }
+            let delivery_id = record_id_string(&delivery.id);
+            if let Err(e) = self
+                .attempt_delivery(
+                    &delivery.webhook,
+                    delivery.id,
+                    &delivery.event,
+                    delivery.attempts,
+                    delivery.payload,
+                    true,
+                    now,
+                )
+                .await
+            {
+                tracing::warn!(
+                    delivery_id = %delivery_id,
+                    error = %e,
+                    "failed to record webhook delivery attempt"
+                );
+                continue;
+            }
+            attempted += 1;
+        }
+        Ok(attempted)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn attempt_delivery(
+        &self,
+        webhook: &WebhookRow,
+        delivery: RecordId,
+        event: &str,
+        previous_attempts: u32,
+        payload: String,
+        retry: bool,
+        now: DateTime<Utc>,
+    ) -> Result<DeliveryRow, AppError> {
+        let signature = format!(
+            "sha256={}",
+            sign_payload(&webhook.secret, payload.as_bytes())
+        );
+        let result = self
+            .sender
+            .send(WebhookRequest {
+                url: webhook.url.clone(),
+                event: event.to_owned(),
+                delivery_id: record_id_string(&delivery),
+                signature,
+                body: payload,
+            })
+            .await;
+        if let Err(ref e) = result {
+            tracing::debug!(
+                webhook_id = %record_id_string(&webhook.id),
+                error = %e,
+                "webhook delivery attempt failed"
+            );
+        }
+        let attempt = attempt_outcome(result, previous_attempts + 1, self.max_attempts, retry, now);
+        self.webhook_repo.record_attempt(delivery, attempt).await
+    }
+
+    async fn load_team_webhook(
+        &self,
+        team: &RecordId,
+        webhook_id: &str,
+    ) -> Result<WebhookRow, AppError> {
+        let row = self
+            .webhook_repo
+            .get_webhook(webhook_thing(webhook_id)?)
+            .await?
+            .ok_or_else(|| AppError::NotFound("webhook not found".into()))?;
+        if row.team != *team {
+            return Err(AppError::NotFound("webhook not found".into()));
+        }
+        Ok(row)
+    }
+
+    /// Same gate as invitation management: [`effective_admin`] on a non-public team; other
+    /// members get 403, everyone else 404.
+    async fn assert_team_admin_for_webhooks(
+        &self,
+        user_id: &str,
+        team_id: &str,
+    ) -> Result<RecordId, AppError> {
+        let resource = team_resource_or_reject_public(team_id)?;
+        let team_thing = RecordId::new(resource.0, resource.1);
+        let row = self
+            .team_repo
+            .fetch_team(team_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("team not found".into()))?;
+
+        let stored = team_fetched_to_stored(&row)?;
+        if !member_or_owner_readable(user_id, &stored) {
+            return Err(AppError::NotFound("team not found".into()));
+        }
+        if !effective_admin(user_id, &stored) {
+            return Err(AppError::forbidden());
+        }
+        Ok(team_thing)
+    }
+}
+
+/// Runs scheduled `webhook.deliver` jobs: [`deliver_due`](WebhookService::deliver_due).
+#[async_trait]
+impl<R: TeamRepository, W: WebhookRepository, S: WebhookSender> JobHandler
+    for WebhookService<R, W, S>
+{
+    async fn run(&self, _ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+        let attempted = self.deliver_due(Utc::now()).await?;
+        Ok(Some(serde_json::json!({ "attempted": attempted })))
+    }
+}
+
+/// Production type alias used in HTTP wiring.
+pub type WebhookServiceHandle =
+    WebhookService<SurrealTeamRepo, SurrealWebhookRepo, HttpWebhookSender>;
+
+impl WebhookServiceHandle {
+    pub fn build(
+        db: Arc<Database>,
+        max_attempts: u32,
+        request_timeout: Duration,
+        allow_private_targets: bool,
+    ) -> Result<Self, AppError> {
+        Ok(WebhookService::new(
+            SurrealTeamRepo::new(db.clone()),
+            SurrealWebhookRepo::new(db),
+            HttpWebhookSender::new(request_timeout, allow_private_targets)?,
+            max_attempts,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use chrono::{Duration as ChronoDuration, Utc};
+
+    use shared::MoveOwner;
+    use shared::api::ListQuery;
+    use shared::setlist::CreateSetlist;
+    use shared::team::{CreateWebhook, WebhookDeliveryStatus, WebhookEvent};
+
+    use crate::database::Database;
+    use crate::error::AppError;
+    use crate::resources::team::UserPermissions;
+    use crate::resources::team::surreal_repo::SurrealTeamRepo;
+    use crate::test_helpers::{TeamFixture, personal_team_id, setlist_service, test_db};
+
+    use super::super::model::{SendResult, sign_payload};
+    use super::super::repository::WebhookRepository;
+    use super::super::sender::{WebhookRequest, WebhookSender};
+    use super::super::surreal_repo::SurrealWebhookRepo;
+    use super::WebhookService;
+
+    /// Records every request and answers with queued results (default `200`).
+    #[derive(Default)]
+    struct RecordingSender {
+        responses: Mutex<VecDeque<SendResult>>,
+        sent: Mutex<Vec<WebhookRequest>>,
+    }
+
+    #[async_trait]
+    impl WebhookSender for RecordingSender {
+        async fn send(&self, request: WebhookRequest) -> SendResult {
+            self.sent.lock().unwrap().push(request);
+            self.responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or(Ok(200))
+        }
+    }
+
+    type TestService = WebhookService<SurrealTeamRepo, SurrealWebhookRepo, RecordingSender>;
+
+    fn service(db: &Arc<Database>, responses: Vec<SendResult>) -> TestService {
+        WebhookService::new(
+            SurrealTeamRepo::new(db.clone()),
+            SurrealWebhookRepo::new(db.clone()),
+            RecordingSender {
+                responses: Mutex::new(responses.into()),
+                sent: Mutex::default(),
+            },
+            3,
+        )
+    }
+
+    fn subscription(events: Vec<WebhookEvent>) -> CreateWebhook {
+        CreateWebhook {
+            url: "https://hooks.example.com/worship".into(),
+            events,
+            active: true,
+        }
+    }
+
+    fn setlist(owner: &str) -> CreateSetlist {
+        CreateSetlist {
+            owner: Some(owner.to_owned()),
+            title: "Sunday".into(),
+            songs: vec![],
+        }
+    }
+
+    /// BLC-WHK-001: team admins manage webhooks; members get 403 and outsiders 404.
+    #[tokio::test]
+    async fn blc_whk_001_admin_only() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = service(&db, vec![]);
+        let created = svc
+            .create_webhook_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                subscription(vec![WebhookEvent::SetlistUpdated]),
+            )
+            .await
+            .expect("create");
+        assert_eq!(created.secret.len(), 64);
+        let (listed, total) = svc
+            .list_webhooks_for_user(&fx.admin_user, &fx.shared_team_id, ListQuery::default())
+            .await
+            .expect("list");
+        assert_eq!(total, 1);
+        assert_eq!(listed[0], created.webhook);
+
+        let r = svc
+            .list_webhooks_for_user(&fx.writer, &fx.shared_team_id, ListQuery::default())
+            .await;
+        assert!(matches!(r, Err(AppError::Forbidden)));
+        let r = svc
+            .get_webhook_for_user(&fx.non_member, &fx.shared_team_id, &created.webhook.id)
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+        let r = svc
+            .get_webhook_for_user(&fx.owner, &fx.personal_team_id, &created.webhook.id)
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+    }
+
+    /// BLC-WHK-002: invalid URLs, empty event lists and `ping` subscriptions are rejected.
+    #[tokio::test]
+    async fn blc_whk_002_validation() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = service(&db, vec![]);
+        for body in [
+            CreateWebhook {
+                url: "ftp://example.com/hook".into(),
+                ..subscription(vec![WebhookEvent::SongCreated])
+            },
+            CreateWebhook {
+                url: "not a url".into(),
+                ..subscription(vec![WebhookEvent::SongCreated])
+            },
+            subscription(vec![]),
+            subscription(vec![WebhookEvent::Ping]),
+        ] {
+            let r = svc
+                .create_webhook_for_user(&fx.admin_user, &fx.shared_team_id, body)
+                .await;
+            assert!(matches!(r, Err(AppError::InvalidRequest(_))), "{r:?}");
+        }
+    }
+
+    /// BLC-WHK-003, BLC-WHK-005: content changes reach subscribed active webhooks only, signed
+    /// with the webhook secret.
+    #[tokio::test]
+    async fn blc_whk_003_events_are_queued_and_signed() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = service(&db, vec![]);
+        let wanted = svc
+            .create_webhook_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                subscription(vec![WebhookEvent::SetlistCreated]),
+            )
+            .await
+            .expect("create");
+        svc.create_webhook_for_user(
+            &fx.admin_user,
+            &fx.shared_team_id,
+            subscription(vec![WebhookEvent::SongCreated]),
+        )
+        .await
+        .expect("other event");
+        svc.create_webhook_for_user(
+            &fx.admin_user,
+            &fx.shared_team_id,
+            CreateWebhook {
+                active: false,
+                ..subscription(vec![WebhookEvent::SetlistCreated])
+            },
+        )
+        .await
+        .expect("inactive");
+
+        let setlists = setlist_service(&db);
+        let perms = UserPermissions::from_ref(&fx.writer, &setlists.teams);
+        let created = setlists
+            .create_setlist_for_user(&perms, setlist(&fx.shared_team_id))
+            .await
+            .expect("setlist");
+
+        assert_eq!(svc.deliver_due(Utc::now()).await.expect("deliver"), 1);
+        let sent = svc.sender.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        let request = &sent[0];
+        assert_eq!(request.event, "setlist.created");
+        assert_eq!(
+            request.signature,
+            format!(
+                "sha256={}",
+                sign_payload(&wanted.secret, request.body.as_bytes())
+            )
+        );
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["resource"]["id"], created.id.as_str());
+        assert_eq!(body["team_id"], fx.shared_team_id.as_str());
+
+        let (log, _) = svc
+            .list_deliveries_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                &wanted.webhook.id,
+                ListQuery::default(),
+            )
+            .await
+            .expect("log");
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].status, WebhookDeliveryStatus::Succeeded);
+        assert_eq!(log[0].id, request.delivery_id);
+        assert_eq!(log[0].payload, request.body);
+    }
+
+    /// BLC-WHK-004: a move is published to webhooks of both the source and destination team.
+    #[tokio::test]
+    async fn blc_whk_004_move_reaches_both_teams() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let personal = personal_team_id(&db, &fx.admin_user).await.expect("team");
+        let svc = service(&db, vec![]);
+        for team in [&personal, &fx.shared_team_id] {
+            svc.create_webhook_for_user(
+                &fx.admin_user,
+                team,
+                subscription(vec![WebhookEvent::SetlistMoved]),
+            )
+            .await
+            .expect("create");
+        }
+        let setlists = setlist_service(&db);
+        let perms = UserPermissions::from_ref(&fx.admin_user, &setlists.teams);
+        let created = setlists
+            .create_setlist_for_user(&perms, setlist(&personal))
+            .await
+            .expect("setlist");
+        setlists
+            .move_setlist_for_user(
+                &perms,
+                &created.id,
+                MoveOwner {
+                    owner: fx.shared_team_id.clone(),
+                },
+            )
+            .await
+            .expect("move");
+
+        assert_eq!(svc.deliver_due(Utc::now()).await.expect("deliver"), 2);
+        let mut teams: Vec<String> = svc
+            .sender
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| {
+                let body: serde_json::Value = serde_json::from_str(&r.body).unwrap();
+                body["team_id"].as_str().unwrap().to_owned()
+            })
+            .collect();
+        teams.sort();
+        let mut expected = vec![personal, fx.shared_team_id.clone()];
+        expected.sort();
+        assert_eq!(teams, expected);
+    }
+
+    /// BLC-WHK-006: failed deliveries are retried with backoff and give up after `max_attempts`.
+    #[tokio::test]
+    async fn blc_whk_006_retries_then_fails() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = service(
+            &db,
+            vec![Ok(500), Err("connection refused".into()), Ok(503)],
+        );
+        let hook = svc
+            .create_webhook_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                subscription(vec![WebhookEvent::SetlistCreated]),
+            )
+            .await
+            .expect("create");
+        let setlists = setlist_service(&db);
+        let perms = UserPermissions::from_ref(&fx.writer, &setlists.teams);
+        setlists
+            .create_setlist_for_user(&perms, setlist(&fx.shared_team_id))
+            .await
+            .expect("setlist");
+
+        let now = Utc::now();
+        assert_eq!(svc.deliver_due(now).await.expect("first"), 1);
+        // Not due again until the backoff has elapsed.
+        assert_eq!(svc.deliver_due(now).await.expect("early"), 0);
+        let now = now + ChronoDuration::seconds(31);
+        assert_eq!(svc.deliver_due(now).await.expect("second"), 1);
+        let now = now + ChronoDuration::seconds(61);
+        assert_eq!(svc.deliver_due(now).await.expect("third"), 1);
+        let now = now + ChronoDuration::days(1);
+        assert_eq!(svc.deliver_due(now).await.expect("done"), 0);
+
+        let (log, _) = svc
+            .list_deliveries_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                &hook.webhook.id,
+                ListQuery::default(),
+            )
+            .await
+            .expect("log");
+        assert_eq!(log[0].status, WebhookDeliveryStatus::Failed);
+        assert_eq!(log[0].attempts, 3);
+        assert_eq!(log[0].response_status, Some(503));
+        assert!(log[0].next_attempt_at.is_none());
+    }
+
+    /// BLC-WHK-007: the test endpoint sends one signed `ping` and logs it; failures are not retried.
+    #[tokio::test]
+    async fn blc_whk_007_test_delivery() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = service(&db, vec![Ok(204), Ok(500)]);
+        let hook = svc
+            .create_webhook_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                subscription(vec![WebhookEvent::SongDeleted]),
+            )
+            .await
+            .expect("create");
+
+        let ok = svc
+            .test_webhook_for_user(&fx.admin_user, &fx.shared_team_id, &hook.webhook.id)
+            .await
+            .expect("ping");
+        assert_eq!(ok.event, WebhookEvent::Ping);
+        assert_eq!(ok.status, WebhookDeliveryStatus::Succeeded);
+        assert_eq!(ok.response_status, Some(204));
+
+        let failed = svc
+            .test_webhook_for_user(&fx.admin_user, &fx.shared_team_id, &hook.webhook.id)
+            .await
+            .expect("ping");
+        assert_eq!(failed.status, WebhookDeliveryStatus::Failed);
+        assert_eq!(
+            svc.deliver_due(Utc::now() + ChronoDuration::days(1))
+                .await
+                .expect("due"),
+            0
+        );
+
+        let r = svc
+            .test_webhook_for_user(&fx.writer, &fx.shared_team_id, &hook.webhook.id)
+            .await;
+        assert!(matches!(r, Err(AppError::Forbidden)));
+    }
+
+    /// BLC-WHK-008: updating replaces url/events/active; deleting removes the delivery log.
+    #[tokio::test]
+    async fn blc_whk_008_update_and_delete() {
+        let db = test_db().await.expect("db");
+        let fx = TeamFixture::build(&db).await.expect("fixture");
+        let svc = service(&db, vec![]);
+        let hook = svc
+            .create_webhook_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                subscription(vec![WebhookEvent::SongDeleted]),
+            )
+            .await
+            .expect("create");
+        let updated = svc
+            .update_webhook_for_user(
+                &fx.admin_user,
+                &fx.shared_team_id,
+                &hook.webhook.id,
+                CreateWebhook {
+                    url: "https://hooks.example.com/v2".into(),
+                    events: vec![
+                        WebhookEvent::SongUpdated,
+                        WebhookEvent::SongCreated,
+                        WebhookEvent::SongUpdated,
+                    ],
+                    active: false,
+                },
+            )
+            .await
+            .expect("update");
+        assert_eq!(updated.url, "https://hooks.example.com/v2");
+        assert_eq!(
+            updated.events,
+            vec![WebhookEvent::SongCreated, WebhookEvent::SongUpdated]
+        );
+        assert!(!updated.active);
+
+        svc.test_webhook_for_user(&fx.admin_user, &fx.shared_team_id, &hook.webhook.id)
+            .await
+            .expect("ping");
+        svc.delete_webhook_for_user(&fx.admin_user, &fx.shared_team_id, &hook.webhook.id)
+            .await
+            .expect("delete");
+        let r = svc
+            .get_webhook_for_user(&fx.admin_user, &fx.shared_team_id, &hook.webhook.id)
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+        assert_eq!(
+            svc.webhook_repo
+                .count_deliveries(super::webhook_thing(&hook.webhook.id).unwrap())
+                .await
+                .expect("count"),
+            0
+        );
+    }
+}
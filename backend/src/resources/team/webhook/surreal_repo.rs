@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::{
+    DeliveryAttempt, DeliveryCreate, DeliveryRow, DueDeliveryRow, WebhookCreate, WebhookRow,
+    WebhookUpdate,
+};
+use super::repository::WebhookRepository;
+
+#[derive(Deserialize, SurrealValue)]
+struct CountResult {
+    count: u64,
+}
+
+#[derive(Clone)]
+pub struct SurrealWebhookRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealWebhookRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for SurrealWebhookRepo {
+    async fn create_webhook(
+        &self,
+        webhook_id: &str,
+        create: WebhookCreate,
+    ) -> Result<WebhookRow, AppError> {
+        let created: Option<WebhookRow> = self
+            .inner()
+            .create(("webhook", webhook_id))
+            .content(create)
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "webhook.create", e))?;
+        created.ok_or_else(|| AppError::database("failed to create webhook"))
+    }
+
+    async fn list_webhooks(&self, team: RecordId) -> Result<Vec<WebhookRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM webhook WHERE team = $team ORDER BY created_at ASC")
+            .bind(("team", team))
+            .await?
+            .take(0)?)
+    }
+
+    async fn get_webhook(&self, webhook: RecordId) -> Result<Option<WebhookRow>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM $wid")
+            .bind(("wid", webhook))
+            .await?
+            .take::<Option<WebhookRow>>(0)?)
+    }
+
+    async fn update_webhook(
+        &self,
+        webhook: RecordId,
+        update: WebhookUpdate,
+    ) -> Result<WebhookRow, AppError> {
+        self.inner()
+            .query("UPDATE $wid MERGE $update RETURN AFTER")
+            .bind(("wid", webhook))
+            .bind(("update", update))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "webhook.update", e))?
+            .take::<Option<WebhookRow>>(0)?
+            .ok_or_else(|| AppError::NotFound("webhook not found".into()))
+    }
+
+    async fn delete_webhook(&self, webhook: RecordId) -> Result<bool, AppError> {
+        let deleted: Vec<WebhookRow> = self
+            .inner()
+            .query("DELETE $wid RETURN BEFORE")
+            .bind(("wid", webhook))
+            .await?
+            .take(0)?;
+        Ok(!deleted.is_empty())
+    }
+
+    async fn enqueue_event(
+        &self,
+        team: RecordId,
+        event: &str,
+        payload: String,
+    ) -> Result<u64, AppError> {
+        let mut response = self
+            .inner()
+            .query(
+                r#"
+                LET $hooks = SELECT VALUE id FROM webhook WHERE team = $team AND active = true AND $event IN events;
+                FOR $hook IN $hooks {
+                    CREATE webhook_delivery CONTENT {
+                        webhook: $hook,
+                        event: $event,
+                        payload: $payload,
+                        next_attempt_at: time::now()
+                    } RETURN NONE;
+                };
+                RETURN array::len($hooks);
+                "#,
+            )
+            .bind(("team", team))
+            .bind(("event", event.to_owned()))
+            .bind(("payload", payload))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "webhook.enqueue", e))?;
+        crate::database::surreal_take_errors("webhook.enqueue", &mut response)?;
+        Ok(response.take::<Option<u64>>(2)?.unwrap_or(0))
+    }
+
+    async fn create_delivery(&self, create: DeliveryCreate) -> Result<DeliveryRow, AppError> {
+        self.inner()
+            .query("CREATE webhook_delivery CONTENT $create RETURN AFTER")
+            .bind(("create", create))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "webhook_delivery.create", e))?
+            .take::<Option<DeliveryRow>>(0)?
+            .ok_or_else(|| AppError::database("failed to create webhook delivery"))
+    }
+
+    async fn list_deliveries(
+        &self,
+        webhook: RecordId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<DeliveryRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM webhook_delivery WHERE webhook = $wid ORDER BY created_at DESC LIMIT $limit START $start",
+            )
+            .bind(("wid", webhook))
+            .bind(("limit", limit))
+            .bind(("start", offset))
+            .await?
+            .take(0)?)
+    }
+
+    async fn count_deliveries(&self, webhook: RecordId) -> Result<u64, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT count() FROM webhook_delivery WHERE webhook = $wid GROUP ALL")
+            .bind(("wid", webhook))
+            .await?
+            .take::<Vec<CountResult>>(0)?
+            .into_iter()
+            .next()
+            .map(|r| r.count)
+            .unwrap_or(0))
+    }
+
+    async fn due_deliveries(
+        &self,
+        now: Datetime,
+        limit: u32,
+    ) -> Result<Vec<DueDeliveryRow>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM webhook_delivery WHERE status = 'pending' AND next_attempt_at != NONE AND next_attempt_at <= $now ORDER BY next_attempt_at ASC LIMIT $limit FETCH webhook",
+            )
+            .bind(("now", now))
+            .bind(("limit", limit))
+            .await?
+            .take(0)?)
+    }
+
+    async fn record_attempt(
+        &self,
+        delivery: RecordId,
+        attempt: DeliveryAttempt,
+    ) -> Result<DeliveryRow, AppError> {
+        self.inner()
+            .query("UPDATE $did MERGE $attempt RETURN AFTER")
+            .bind(("did", delivery))
+            .bind(("attempt", attempt))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "webhook_delivery.update", e))?
+            .take::<Option<DeliveryRow>>(0)?
+            .ok_or_else(|| AppError::NotFound("webhook delivery not found".into()))
+    }
+}
@@ -0,0 +1,69 @@
+//! Zip layout of `GET /api/v1/users/me/export`.
+
+use std::io::{Cursor, Write};
+
+use serde::Serialize;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use shared::blob::Blob;
+use shared::collection::Collection;
+use shared::setlist::Setlist;
+use shared::song::Song;
+use shared::team::Team;
+use shared::user::User;
+
+use crate::error::AppError;
+
+/// Everything the archive holds; `files` pairs each blob's file name with its uploaded bytes.
+pub(super) struct AccountExport {
+    pub profile: User,
+    pub teams: Vec<Team>,
+    pub songs: Vec<Song>,
+    pub collections: Vec<Collection>,
+    pub setlists: Vec<Setlist>,
+    pub blobs: Vec<Blob>,
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+fn export_err<E: std::error::Error + 'static>(e: E) -> AppError {
+    AppError::internal_from_err("account.export", e)
+}
+
+fn write_json<T: Serialize>(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    value: &T,
+) -> Result<(), AppError> {
+    let json = serde_json::to_vec_pretty(value).map_err(export_err)?;
+    write_file(zip, name, &json)
+}
+
+fn write_file(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    data: &[u8],
+) -> Result<(), AppError> {
+    zip.start_file(
+        name,
+        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+    )
+    .map_err(export_err)?;
+    zip.write_all(data).map_err(export_err)
+}
+
+/// `profile.json`, `teams.json`, `library/{songs,collections,setlists,blobs}.json` and the blob
+/// bytes under `files/`.
+pub(super) fn build_archive(export: AccountExport) -> Result<Vec<u8>, AppError> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    write_json(&mut zip, "profile.json", &export.profile)?;
+    write_json(&mut zip, "teams.json", &export.teams)?;
+    write_json(&mut zip, "library/songs.json", &export.songs)?;
+    write_json(&mut zip, "library/collections.json", &export.collections)?;
+    write_json(&mut zip, "library/setlists.json", &export.setlists)?;
+    write_json(&mut zip, "library/blobs.json", &export.blobs)?;
+    for (name, data) in &export.files {
+        write_file(&mut zip, &format!("files/{name}"), data)?;
+    }
+    Ok(zip.finish().map_err(export_err)?.into_inner())
+}
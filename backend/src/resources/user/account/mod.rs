@@ -0,0 +1,9 @@
+//! Self-service account management under `/api/v1/users/me`: email change, data export and
+//! scheduled account deletion.
+
+mod export;
+mod repo;
+pub mod rest;
+pub mod service;
+
+pub use service::{AccountService, AccountServiceHandle};
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use surrealdb::types::{RecordId, SurrealValue, Value};
+
+use crate::database::Database;
+use crate::error::AppError;
+
+pub(super) struct AccountRepo;
+
+impl AccountRepo {
+    /// Content hashes of every blob in `team`, trashed ones included.
+    pub(super) async fn blob_hashes(
+        db: &Database,
+        team: RecordId,
+    ) -> Result<Vec<String>, AppError> {
+        #[derive(Deserialize, SurrealValue)]
+        struct HashRow {
+            sha256: String,
+        }
+        let rows: Vec<HashRow> = db
+            .query("SELECT sha256 FROM blob WHERE owner = $team AND sha256 != NONE")
+            .bind(("team", team))
+            .await?
+            .take(0)?;
+        let mut hashes: Vec<String> = rows.into_iter().map(|r| r.sha256).collect();
+        hashes.sort();
+        hashes.dedup();
+        Ok(hashes)
+    }
+
+    /// Deletes every session of `user` except `keep_session`, and all of their API tokens.
+    /// Returns how many sessions and tokens went.
+    pub(super) async fn revoke_other_credentials(
+        db: &Database,
+        user: RecordId,
+        keep_session: RecordId,
+    ) -> Result<(usize, usize), AppError> {
+        let mut response = db
+            .query(
+                "DELETE session WHERE user = $user AND id != $keep RETURN BEFORE;
+                 DELETE api_token WHERE user = $user RETURN BEFORE;",
+            )
+            .bind(("user", user))
+            .bind(("keep", keep_session))
+            .await?;
+        let sessions: Vec<Value> = response.take(0)?;
+        let tokens: Vec<Value> = response.take(1)?;
+        Ok((sessions.len(), tokens.len()))
+    }
+}
@@ -0,0 +1,161 @@
+use actix_web::http::header;
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse, delete, get, patch,
+    web::{Bytes, Data, Json, ReqData},
+};
+use chrono::Utc;
+
+use shared::user::{DeleteAccount, PatchUser, User};
+
+use super::AccountServiceHandle;
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+use crate::http_audit::AuditSessionId;
+use crate::mail::MailService;
+use crate::settings::OtpConfig;
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/me",
+    request_body = PatchUser,
+    responses(
+        (status = 200, description = "Email changed (or nothing to change); returns the current `User`. A change signs out every other session and revokes all API tokens", body = User),
+        (status = 202, description = "Confirmation code mailed to the new `email`; repeat the request with `otp_code` to apply it"),
+        (status = 400, description = "Invalid email, unchanged email, `otp_code` without `email`, or wrong code", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Another account uses that email", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded, too many email change requests for this user or address, or too many wrong codes", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to store or deliver the code", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[patch("/me")]
+pub async fn patch_users_me(
+    req: HttpRequest,
+    user: ReqData<User>,
+    svc: Data<AccountServiceHandle>,
+    mail: Data<MailService>,
+    otp: Data<OtpConfig>,
+    payload: Json<PatchUser>,
+) -> Result<HttpResponse, AppError> {
+    let user = user.into_inner();
+    let PatchUser { email, otp_code } = payload.into_inner();
+    match (email, otp_code) {
+        (None, None) => Ok(HttpResponse::Ok().json(svc.get_user(&user.id).await?)),
+        (None, Some(_)) => Err(AppError::invalid_request("otp_code requires email")),
+        (Some(email), None) => {
+            svc.request_email_change(&user, &email, &mail, &otp).await?;
+            Ok(HttpResponse::Accepted().finish())
+        }
+        (Some(email), Some(code)) => {
+            let session_id = req
+                .extensions()
+                .get::<AuditSessionId>()
+                .map(|a| a.0.clone())
+                .ok_or_else(|| {
+                    AppError::Internal(
+                        "authenticated request missing credential session identifier".into(),
+                    )
+                })?;
+            Ok(HttpResponse::Ok().json(
+                svc.confirm_email_change(&user, &email, &code, &mail, &otp, &session_id)
+                    .await?,
+            ))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/export",
+    responses(
+        (status = 200, description = "Zip archive with `profile.json`, `teams.json`, the personal team's songs, collections, setlists and blobs under `library/`, and the blob files under `files/`", body = Vec<u8>, content_type = "application/zip"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The personal library's files exceed `ACCOUNT_EXPORT_MAX_BYTES`", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to build the export", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/me/export")]
+pub async fn export_users_me(
+    user: ReqData<User>,
+    svc: Data<AccountServiceHandle>,
+) -> Result<HttpResponse, AppError> {
+    let archive = svc.export_for_user(&user.into_inner()).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"worship-viewer-export.zip\"",
+        ))
+        .body(archive))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me",
+    request_body(content = DeleteAccount, description = "Optional; required when the caller is the only admin of a shared team with other members"),
+    responses(
+        (status = 202, description = "Deletion scheduled; returns the `User` with `deletion_scheduled_at`. Sign in and call `DELETE /api/v1/users/me/deletion` before then to keep the account", body = User),
+        (status = 400, description = "Invalid body, or a handover to a non-member or for a team that needs none", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Deletion already scheduled, or shared teams still need a new admin", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to schedule deletion", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/me")]
+pub async fn delete_users_me(
+    user: ReqData<User>,
+    svc: Data<AccountServiceHandle>,
+    body: Bytes,
+) -> Result<HttpResponse, AppError> {
+    let payload = if body.is_empty() {
+        DeleteAccount::default()
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|e| AppError::invalid_request(format!("invalid body: {e}")))?
+    };
+    let updated = svc
+        .schedule_deletion(&user.into_inner(), payload, Utc::now())
+        .await?;
+    Ok(HttpResponse::Accepted().json(updated))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/deletion",
+    responses(
+        (status = 200, description = "Scheduled deletion cancelled (if any); returns the `User`", body = User),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to cancel deletion", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/me/deletion")]
+pub async fn cancel_users_me_deletion(
+    user: ReqData<User>,
+    svc: Data<AccountServiceHandle>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.cancel_deletion(&user.into_inner()).await?))
+}
@@ -0,0 +1,905 @@
+use std::collections::BTreeSet;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use actix_governor::governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rand::RngExt;
+use surrealdb::types::{Datetime, RecordId};
+use tracing::instrument;
+
+use shared::team::{Team, TeamMemberInput, TeamRole, TeamUserRef, TransferTeam, UpdateTeam};
+use shared::user::{DeleteAccount, User, email_passes_basic_checks};
+
+use crate::auth::otp::Model as OtpModel;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::mail::MailService;
+use crate::resources::blob::storage::{BlobBackend, BlobStorage, lock_content};
+use crate::resources::blob::{BlobRepository, SurrealBlobRepo};
+use crate::resources::job::{JobContext, JobHandler};
+use crate::resources::sync::{SurrealSyncRepo, SyncRepository};
+use crate::resources::team::TeamServiceHandle;
+use crate::resources::team::resolver::SurrealTeamResolver;
+use crate::resources::user::{SurrealUserRepo, UserRepository};
+use crate::settings::OtpConfig;
+
+use super::export::{AccountExport, build_archive};
+use super::repo::AccountRepo;
+
+/// OTP store key for a pending change of `user_id`'s email to `email`; a code only confirms the
+/// address it was mailed to.
+fn email_change_key(user_id: &str, email: &str) -> String {
+    format!("email_change:{user_id}:{email}")
+}
+
+/// Limits email change requests per user and per new address, at the rate of the login OTP
+/// endpoints (`AUTH_RATE_LIMIT_RPS`, `AUTH_RATE_LIMIT_BURST`).
+/// Settings reject zero limits; they are still clamped to one here rather than trusted.
+fn email_change_limiter(rps: u64, burst: u32) -> DefaultKeyedRateLimiter<String> {
+    let rps = NonZeroU32::new(u32::try_from(rps).unwrap_or(u32::MAX)).unwrap_or(NonZeroU32::MIN);
+    let burst = NonZeroU32::new(burst).unwrap_or(NonZeroU32::MIN);
+    RateLimiter::keyed(Quota::per_second(rps).allow_burst(burst))
+}
+
+fn normalize_email(raw: &str) -> Result<String, AppError> {
+    let email = raw.trim().to_lowercase();
+    if !email_passes_basic_checks(&email) {
+        return Err(AppError::invalid_request("email is not a valid address"));
+    }
+    Ok(email)
+}
+
+fn is_member(team: &Team, user_id: &str) -> bool {
+    team.owner.as_ref().is_some_and(|o| o.id == user_id)
+        || team.members.iter().any(|m| m.user.id == user_id)
+}
+
+/// Shared team `user_id` is the only admin of while others remain: it needs a new admin before
+/// the account can go.
+fn needs_handover(team: &Team, user_id: &str) -> bool {
+    if team.owner.is_some() {
+        return false;
+    }
+    let admins: Vec<_> = team
+        .members
+        .iter()
+        .filter(|m| m.role == TeamRole::Admin)
+        .collect();
+    let sole_admin = matches!(admins.as_slice(), [only] if only.user.id == user_id);
+    sole_admin && team.members.iter().any(|m| m.user.id != user_id)
+}
+
+/// Self-service account management: email change, data export and scheduled deletion.
+#[derive(Clone)]
+pub struct AccountService {
+    db: Arc<Database>,
+    users: SurrealUserRepo,
+    teams: TeamServiceHandle,
+    library: SurrealSyncRepo,
+    blobs: SurrealBlobRepo,
+    storage: BlobBackend,
+    grace: ChronoDuration,
+    export_max_bytes: u64,
+    email_change_limiter: Arc<DefaultKeyedRateLimiter<String>>,
+}
+
+/// Production type alias used in HTTP wiring.
+pub type AccountServiceHandle = AccountService;
+
+impl AccountService {
+    pub fn build(
+        db: Arc<Database>,
+        team_resolver: Arc<SurrealTeamResolver>,
+        storage: BlobBackend,
+        grace_days: u32,
+        export_max_bytes: u64,
+        email_change_rps: u64,
+        email_change_burst: u32,
+    ) -> Self {
+        Self {
+            users: SurrealUserRepo::new(db.clone()),
+            teams: TeamServiceHandle::build_with_team_resolver(db.clone(), team_resolver),
+            library: SurrealSyncRepo::new(db.clone()),
+            blobs: SurrealBlobRepo::new(db.clone()),
+            storage,
+            grace: ChronoDuration::days(i64::from(grace_days)),
+            export_max_bytes,
+            email_change_limiter: Arc::new(email_change_limiter(
+                email_change_rps,
+                email_change_burst,
+            )),
+            db,
+        }
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn get_user(&self, id: &str) -> Result<User, AppError> {
+        self.users.get_user(id).await
+    }
+
+    async fn ensure_email_available(&self, email: &str) -> Result<(), AppError> {
+        if self.users.get_user_by_email(email).await?.is_some() {
+            return Err(AppError::conflict("email is already in use"));
+        }
+        Ok(())
+    }
+
+    /// Rejects the request when `user` or `email` is over the email change rate limit, so codes
+    /// cannot be mailed to arbitrary addresses without bound.
+    fn throttle_email_change(&self, user: &User, email: &str) -> Result<(), AppError> {
+        self.email_change_limiter.retain_recent();
+        let limited = [format!("user:{}", user.id), format!("email:{email}")]
+            .iter()
+            .any(|key| self.email_change_limiter.check_key(key).is_err());
+        if limited {
+            crate::audit!(
+                "audit.user.email_change.failure",
+                user_id = tracing::field::display(&user.id),
+                reason = tracing::field::display("rate limited")
+                ; "email change request throttled"
+            );
+            return Err(AppError::too_many_requests(
+                "too many email change requests; try again later",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Mails a confirmation code to the new address; the email stays unchanged until
+    /// [`confirm_email_change`](Self::confirm_email_change).
+    #[instrument(level = "debug", err, skip(self, user, mail, otp), fields(user_id = %user.id))]
+    pub async fn request_email_change(
+        &self,
+        user: &User,
+        email: &str,
+        mail: &MailService,
+        otp: &OtpConfig,
+    ) -> Result<(), AppError> {
+        let email = normalize_email(email)?;
+        if email == user.email {
+            return Err(AppError::invalid_request("email is unchanged"));
+        }
+        self.throttle_email_change(user, &email)?;
+        self.ensure_email_available(&email).await?;
+
+        let code = format!("{:06}", rand::rng().random_range(0..1_000_000));
+        self.db
+            .remember_otp(
+                &email_change_key(&user.id, &email),
+                &code,
+                &otp.pepper,
+                otp.ttl_seconds,
+            )
+            .await?;
+        mail.send(
+            &email,
+            "Confirm your new WorshipViewer email",
+            &format!("Hello,\n\nto use {email} for your WorshipViewer account, enter this confirmation code:\n\n🔐 Code: {code}\n\nIf you did not request this, please ignore this message; your account is unchanged.\n\nBlessings,\nThe WorshipViewer Team"),
+        )
+        .await?;
+
+        crate::audit!(
+            "audit.user.email_change.requested",
+            user_id = tracing::field::display(&user.id),
+            new_email_hash = tracing::field::display(&crate::observability::audit_email_hash(&email))
+            ; "email change requested"
+        );
+        Ok(())
+    }
+
+    /// Applies the change once `code` matches the one mailed to `email`, signs out every other
+    /// session and revokes the API tokens of `user` (`current_session` stays valid), then tells the
+    /// old address about it.
+    #[instrument(
+        level = "debug",
+        err,
+        skip(self, user, code, mail, otp, current_session),
+        fields(user_id = %user.id)
+    )]
+    pub async fn confirm_email_change(
+        &self,
+        user: &User,
+        email: &str,
+        code: &str,
+        mail: &MailService,
+        otp: &OtpConfig,
+        current_session: &str,
+    ) -> Result<User, AppError> {
+        let email = normalize_email(email)?;
+        let code = code.trim();
+        if code.is_empty() {
+            return Err(AppError::invalid_request("otp code is required"));
+        }
+        if let Err(e) = self
+            .db
+            .validate_otp(
+                &email_change_key(&user.id, &email),
+                code,
+                &otp.pepper,
+                otp.max_attempts,
+            )
+            .await
+        {
+            crate::audit!(
+                "audit.user.email_change.failure",
+                user_id = tracing::field::display(&user.id),
+                reason = tracing::field::display(&e)
+                ; "email change confirmation rejected"
+            );
+            return Err(e);
+        }
+        self.ensure_email_available(&email).await?;
+        let updated = self.users.set_email(&user.id, &email).await?;
+        let (revoked_sessions, revoked_api_tokens) = AccountRepo::revoke_other_credentials(
+            &self.db,
+            RecordId::new("user", user.id.clone()),
+            RecordId::new("session", current_session.to_owned()),
+        )
+        .await?;
+
+        if let Err(e) = mail
+            .send(
+                &user.email,
+                "Your WorshipViewer email was changed",
+                &format!("Hello,\n\nthe email of your WorshipViewer account was changed from {} to {email}.\n\nIf you did not do this, please contact us right away.\n\nBlessings,\nThe WorshipViewer Team", user.email),
+            )
+            .await
+        {
+            tracing::warn!(user_id = %user.id, error = %e, "failed to notify old email address");
+        }
+        crate::audit!(
+            "audit.user.email.changed",
+            user_id = tracing::field::display(&user.id),
+            old_email_hash = tracing::field::display(&crate::observability::audit_email_hash(&user.email)),
+            new_email_hash = tracing::field::display(&crate::observability::audit_email_hash(&email)),
+            revoked_sessions = tracing::field::display(&revoked_sessions),
+            revoked_api_tokens = tracing::field::display(&revoked_api_tokens)
+            ; "user email changed"
+        );
+        Ok(updated)
+    }
+
+    async fn teams_of(&self, user: &User) -> Result<Vec<Team>, AppError> {
+        let mut teams = self.teams.list_teams_for_user(user).await?;
+        teams.retain(|t| is_member(t, &user.id));
+        Ok(teams)
+    }
+
+    /// Zip archive of the profile, team memberships and the personal library with its files.
+    /// The archive is built in memory, so libraries whose files exceed `export_max_bytes` are
+    /// refused.
+    #[instrument(level = "debug", err, skip(self, user), fields(user_id = %user.id))]
+    pub async fn export_for_user(&self, user: &User) -> Result<Vec<u8>, AppError> {
+        let profile = self.users.get_user(&user.id).await?;
+        let teams = self.teams_of(user).await?;
+        let personal = [self.db.personal_team_thing_for_user(&user.id).await?];
+        let songs = self.library.changed_songs(&personal, None).await?;
+        let collections = self.library.changed_collections(&personal, None).await?;
+        let setlists = self.library.changed_setlists(&personal, None).await?;
+        let blobs = self.library.changed_blobs(&personal, None).await?;
+
+        let mut files = Vec::new();
+        let mut total = 0u64;
+        for blob in &blobs {
+            let Some(name) = blob.file_name() else {
+                continue;
+            };
+            let data = match &blob.sha256 {
+                Some(hash) => self.storage.read_content(hash).await?,
+                None => self.storage.read_legacy_file(blob).await?,
+            };
+            if let Some(data) = data {
+                total = total.saturating_add(data.len() as u64);
+                if total > self.export_max_bytes {
+                    return Err(AppError::conflict(format!(
+                        "library files exceed the export limit of {} bytes",
+                        self.export_max_bytes
+                    )));
+                }
+                files.push((name, data));
+            }
+        }
+
+        let export = AccountExport {
+            profile,
+            teams,
+            songs,
+            collections,
+            setlists,
+            blobs,
+            files,
+        };
+        let archive = actix_web::rt::task::spawn_blocking(move || build_archive(export))
+            .await
+            .map_err(|e| AppError::internal_from_err("account.export.join", e))??;
+        crate::audit!(
+            "audit.user.exported",
+            user_id = tracing::field::display(&user.id),
+            bytes = tracing::field::display(&archive.len())
+            ; "account data exported"
+        );
+        Ok(archive)
+    }
+
+    /// Hands over the teams that need a new admin and schedules the account for deletion after
+    /// the grace period.
+    #[instrument(level = "debug", err, skip(self, user, payload), fields(user_id = %user.id))]
+    pub async fn schedule_deletion(
+        &self,
+        user: &User,
+        payload: DeleteAccount,
+        now: DateTime<Utc>,
+    ) -> Result<User, AppError> {
+        let current = self.users.get_user(&user.id).await?;
+        if current.deletion_scheduled_at.is_some() {
+            return Err(AppError::conflict("account deletion is already scheduled"));
+        }
+
+        let needing: Vec<Team> = self
+            .teams_of(user)
+            .await?
+            .into_iter()
+            .filter(|t| needs_handover(t, &user.id))
+            .collect();
+        let mut handed: BTreeSet<&str> = BTreeSet::new();
+        for handover in &payload.handovers {
+            let Some(team) = needing.iter().find(|t| t.id == handover.team_id) else {
+                return Err(AppError::invalid_request(format!(
+                    "team `{}` does not need a handover",
+                    handover.team_id
+                )));
+            };
+            if !handed.insert(team.id.as_str()) {
+                return Err(AppError::invalid_request(format!(
+                    "team `{}` is handed over more than once",
+                    team.id
+                )));
+            }
+            if handover.user.id == user.id
+                || !team.members.iter().any(|m| m.user.id == handover.user.id)
+            {
+                return Err(AppError::invalid_request(format!(
+                    "new admin of team `{}` must be another member of it",
+                    team.id
+                )));
+            }
+        }
+        let missing: Vec<&str> = needing
+            .iter()
+            .map(|t| t.id.as_str())
+            .filter(|id| !handed.contains(id))
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::conflict(format!(
+                "a new admin is required for teams: {}",
+                missing.join(", ")
+            )));
+        }
+
+        for handover in payload.handovers {
+            self.teams
+                .transfer_team_for_user(
+                    user,
+                    &handover.team_id,
+                    TransferTeam {
+                        user: handover.user,
+                        former_admin_role: None,
+                    },
+                )
+                .await?;
+        }
+        let at = now + self.grace;
+        let updated = self
+            .users
+            .set_deletion_scheduled_at(&user.id, Some(Datetime::from(at)))
+            .await?;
+        crate::audit!(
+            "audit.user.deletion.scheduled",
+            user_id = tracing::field::display(&user.id),
+            delete_at = tracing::field::display(&at.to_rfc3339())
+            ; "account deletion scheduled"
+        );
+        Ok(updated)
+    }
+
+    /// Clears a scheduled deletion; a no-op when none is scheduled.
+    #[instrument(level = "debug", err, skip(self, user), fields(user_id = %user.id))]
+    pub async fn cancel_deletion(&self, user: &User) -> Result<User, AppError> {
+        let current = self.users.get_user(&user.id).await?;
+        if current.deletion_scheduled_at.is_none() {
+            return Ok(current);
+        }
+        let updated = self.users.set_deletion_scheduled_at(&user.id, None).await?;
+        crate::audit!(
+            "audit.user.deletion.cancelled",
+            user_id = tracing::field::display(&user.id)
+            ; "account deletion cancelled"
+        );
+        Ok(updated)
+    }
+
+    /// Leaves every shared team, deletes teams nobody else is in, then removes the user and the
+    /// blob content only their personal team referenced.
+    async fn delete_account(&self, user: &User) -> Result<(), AppError> {
+        for team in self.teams_of(user).await? {
+            if team.owner.is_some() {
+                continue;
+            }
+            if team.members.iter().all(|m| m.user.id == user.id) {
+                self.teams.delete_team_for_user(user, &team.id).await?;
+                continue;
+            }
+            let team = if needs_handover(&team, &user.id) {
+                let successor = team
+                    .members
+                    .iter()
+                    .find(|m| m.user.id != user.id)
+                    .map(|m| m.user.id.clone())
+                    .ok_or_else(|| AppError::database("team has no other member"))?;
+                self.teams
+                    .transfer_team_for_user(
+                        user,
+                        &team.id,
+                        TransferTeam {
+                            user: TeamUserRef { id: successor },
+                            former_admin_role: None,
+                        },
+                    )
+                    .await?
+            } else {
+                team
+            };
+            let members = team
+                .members
+                .iter()
+                .filter(|m| m.user.id != user.id)
+                .map(|m| TeamMemberInput {
+                    user: TeamUserRef {
+                        id: m.user.id.clone(),
+                    },
+                    role: m.role.clone(),
+                })
+                .collect();
+            self.teams
+                .update_team_for_user(
+                    user,
+                    &team.id,
+                    UpdateTeam {
+                        name: team.name.clone(),
+                        members: Some(members),
+                    },
+                )
+                .await?;
+        }
+
+        let personal = self.db.personal_team_thing_for_user(&user.id).await?;
+        let hashes = AccountRepo::blob_hashes(&self.db, personal).await?;
+        self.users.delete_user(&user.id).await?;
+        for hash in hashes {
+            let _lock = lock_content(&hash).await;
+            if self.blobs.count_content_references(&hash).await? == 0 {
+                self.storage.delete_content(&hash).await;
+            }
+        }
+        crate::audit!(
+            "audit.user.deleted",
+            target_user_id = tracing::field::display(&user.id),
+            reason = tracing::field::display(&"scheduled")
+            ; "user deleted"
+        );
+        Ok(())
+    }
+
+    /// Deletes every account whose grace period ended by `now`. A failing account is logged and
+    /// retried on the next run. Returns how many accounts were deleted.
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn delete_due_accounts(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let due = self.users.users_due_for_deletion(now.into()).await?;
+        let mut deleted = 0;
+        for user in due {
+            match self.delete_account(&user).await {
+                Ok(()) => deleted += 1,
+                Err(e) => {
+                    tracing::warn!(user_id = %user.id, error = %e, "scheduled account deletion failed");
+                }
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+/// Runs scheduled `account.deletion` jobs:
+/// [`delete_due_accounts`](AccountService::delete_due_accounts).
+#[async_trait]
+impl JobHandler for AccountService {
+    async fn run(&self, _ctx: &JobContext<'_>) -> Result<Option<serde_json::Value>, AppError> {
+        let deleted = self.delete_due_accounts(Utc::now()).await?;
+        Ok(Some(serde_json::json!({ "deleted": deleted })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::sync::Arc;
+
+    use chrono::{Duration as ChronoDuration, Utc};
+
+    use shared::blob::{CreateBlob, FileType};
+    use shared::team::{TeamRole, TeamUserRef};
+    use shared::user::{ApiTokenScope, CreateApiToken, DeleteAccount, Session, TeamHandover};
+
+    use crate::auth::otp::Model as OtpModel;
+    use crate::error::AppError;
+    use crate::mail::MailService;
+    use crate::resources::blob::FsBlobStorage;
+    use crate::resources::team::{SurrealTeamResolver, UserPermissions};
+    use crate::settings::Settings;
+    use crate::test_helpers::{
+        TeamFixture, account_service, api_token_service, blob_service, create_song_with_title,
+        create_user, session_service, team_service, test_db, user_service,
+    };
+
+    use super::AccountServiceHandle;
+
+    use super::{email_change_key, email_change_limiter};
+
+    fn blob_dir() -> String {
+        std::env::temp_dir()
+            .join("worshipviewer_account_tests_blobs")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn email_change_limiter_clamps_zero_limits() {
+        let limiter = email_change_limiter(0, 0);
+        let key = "user:someone".to_owned();
+        assert!(limiter.check_key(&key).is_ok());
+        assert!(limiter.check_key(&key).is_err());
+    }
+
+    /// BLC-USER-017, BLC-USER-018: the email changes only with the code mailed to the new address,
+    /// and never to an address another account uses.
+    #[tokio::test]
+    async fn blc_user_017_email_change_needs_code_for_new_address() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "old@test.local").await.unwrap();
+        create_user(&db, "taken@test.local").await.unwrap();
+        let svc = account_service(&db, blob_dir());
+        let mail = MailService::noop_for_tests("noreply@test.local".into());
+        let otp = Settings::default().otp_config();
+        let session = session_service(&db)
+            .create_session(Session::new(user.clone(), 3600))
+            .await
+            .unwrap();
+
+        let err = svc
+            .request_email_change(&user, "Taken@Test.local", &mail, &otp)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+        let err = svc
+            .request_email_change(&user, "old@test.local", &mail, &otp)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+
+        svc.request_email_change(&user, " New@Test.local ", &mail, &otp)
+            .await
+            .unwrap();
+        // Replace the mailed code with a known one.
+        db.remember_otp(
+            &email_change_key(&user.id, "new@test.local"),
+            "123456",
+            &otp.pepper,
+            otp.ttl_seconds,
+        )
+        .await
+        .unwrap();
+        let err = svc
+            .confirm_email_change(&user, "new@test.local", "000000", &mail, &otp, &session.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+        assert_eq!(
+            svc.get_user(&user.id).await.unwrap().email,
+            "old@test.local"
+        );
+
+        let updated = svc
+            .confirm_email_change(&user, "new@test.local", "123456", &mail, &otp, &session.id)
+            .await
+            .unwrap();
+        assert_eq!(updated.email, "new@test.local");
+        assert!(
+            user_service(&db)
+                .get_user_by_email("old@test.local")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    /// BLC-USER-024: codes go out at the login OTP rate, per requesting user and per new address.
+    #[tokio::test]
+    async fn blc_user_024_email_change_requests_are_throttled() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "throttled@test.local").await.unwrap();
+        let asker = create_user(&db, "asker@test.local").await.unwrap();
+        let other = create_user(&db, "other@test.local").await.unwrap();
+        let svc = account_service(&db, blob_dir());
+        let mail = MailService::noop_for_tests("noreply@test.local".into());
+        let otp = Settings::default().otp_config();
+        let burst = Settings::default().auth_rate_limit_burst;
+
+        for i in 0..burst {
+            svc.throttle_email_change(&user, &format!("new{i}@test.local"))
+                .unwrap();
+        }
+        let err = svc
+            .request_email_change(&user, "fresh@test.local", &mail, &otp)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::TooManyRequests(_)));
+
+        // Another user asking for an address that was already asked for too often.
+        for _ in 0..burst {
+            svc.throttle_email_change(&asker, "target@test.local")
+                .unwrap();
+        }
+        let err = svc
+            .request_email_change(&other, "target@test.local", &mail, &otp)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::TooManyRequests(_)));
+        svc.request_email_change(&other, "elsewhere@test.local", &mail, &otp)
+            .await
+            .unwrap();
+    }
+
+    /// BLC-USER-025: a confirmed change signs out the other sessions and revokes all API tokens.
+    #[tokio::test]
+    async fn blc_user_025_email_change_revokes_other_credentials() {
+        let db = test_db().await.unwrap();
+        let user = create_user(&db, "revoke@test.local").await.unwrap();
+        let other = create_user(&db, "bystander@test.local").await.unwrap();
+        let svc = account_service(&db, blob_dir());
+        let sessions = session_service(&db);
+        let tokens = api_token_service(&db);
+        let mail = MailService::noop_for_tests("noreply@test.local".into());
+        let otp = Settings::default().otp_config();
+        let current = sessions
+            .create_session(Session::new(user.clone(), 3600))
+            .await
+            .unwrap();
+        let elsewhere = sessions
+            .create_session(Session::new(user.clone(), 3600))
+            .await
+            .unwrap();
+        let bystander = sessions
+            .create_session(Session::new(other.clone(), 3600))
+            .await
+            .unwrap();
+        for owner in [&user, &other] {
+            tokens
+                .create_token_for_user(
+                    owner,
+                    CreateApiToken {
+                        name: "script".into(),
+                        scopes: vec![ApiTokenScope::Read],
+                        expires_at: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        db.remember_otp(
+            &email_change_key(&user.id, "revoked@test.local"),
+            "123456",
+            &otp.pepper,
+            otp.ttl_seconds,
+        )
+        .await
+        .unwrap();
+        svc.confirm_email_change(
+            &user,
+            "revoked@test.local",
+            "123456",
+            &mail,
+            &otp,
+            &current.id,
+        )
+        .await
+        .unwrap();
+
+        let left: Vec<String> = sessions
+            .get_sessions_by_user_id(&user.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(left, [current.id]);
+        assert!(sessions.get_session(&elsewhere.id).await.is_err());
+        assert!(sessions.get_session(&bystander.id).await.is_ok());
+        let (mine, _) = tokens
+            .list_tokens_for_user(&user, Default::default())
+            .await
+            .unwrap();
+        assert!(mine.is_empty());
+        let (theirs, _) = tokens
+            .list_tokens_for_user(&other, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(theirs.len(), 1);
+    }
+
+    /// BLC-USER-019: the export holds the profile, team memberships and the personal library.
+    #[tokio::test]
+    async fn blc_user_019_export_contains_profile_teams_and_library() {
+        let db = test_db().await.unwrap();
+        let fx = TeamFixture::build(&db).await.unwrap();
+        create_song_with_title(&db, &fx.writer, "Amazing Grace")
+            .await
+            .unwrap();
+
+        let archive = account_service(&db, blob_dir())
+            .export_for_user(&fx.writer)
+            .await
+            .unwrap();
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).unwrap();
+        let mut read_json = |name: &str| -> serde_json::Value {
+            let mut text = String::new();
+            zip.by_name(name)
+                .unwrap()
+                .read_to_string(&mut text)
+                .unwrap();
+            serde_json::from_str(&text).unwrap()
+        };
+        assert_eq!(read_json("profile.json")["email"], "fx-writer@test.local");
+        let teams = read_json("teams.json");
+        let team_ids: Vec<&str> = teams
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert!(team_ids.contains(&fx.shared_team_id.as_str()));
+        assert!(!team_ids.contains(&fx.personal_team_id.as_str()));
+        let songs = read_json("library/songs.json");
+        assert_eq!(songs.as_array().unwrap().len(), 1);
+        assert_eq!(read_json("library/blobs.json"), serde_json::json!([]));
+    }
+
+    /// BLC-USER-019: an export whose files exceed the limit is refused before it is zipped.
+    #[tokio::test]
+    async fn blc_user_019_export_over_the_size_limit_is_refused() {
+        let db = test_db().await.unwrap();
+        let owner = create_user(&db, "export-limit@test.local").await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let blob_dir = dir.path().to_string_lossy().into_owned();
+        let blobs = blob_service(&db, blob_dir.clone());
+        let perms = UserPermissions::from_ref(&owner, &blobs.teams);
+        let blob = blobs
+            .create_blob_for_user(
+                &perms,
+                CreateBlob {
+                    owner: None,
+                    file_type: FileType::PNG,
+                    width: 1,
+                    height: 1,
+                    ocr: String::new(),
+                },
+            )
+            .await
+            .unwrap();
+        blobs
+            .upload_blob_data_for_user(&perms, &blob.id, b"hymn page")
+            .await
+            .unwrap();
+        let limited = |max_bytes| {
+            let settings = Settings::default();
+            AccountServiceHandle::build(
+                db.clone(),
+                Arc::new(SurrealTeamResolver::new(db.clone())),
+                FsBlobStorage::new(blob_dir.clone()).into(),
+                settings.account_deletion_grace_days,
+                max_bytes,
+                settings.auth_rate_limit_rps,
+                settings.auth_rate_limit_burst,
+            )
+        };
+
+        let err = limited(8).export_for_user(&owner).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+        let archive = limited(9).export_for_user(&owner).await.unwrap();
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).unwrap();
+        assert!(
+            zip.by_name(&format!("files/{}", blob.file_name().unwrap()))
+                .is_ok()
+        );
+    }
+
+    /// BLC-USER-020, BLC-USER-021, BLC-USER-022: the sole admin of a shared team must hand it
+    /// over before scheduling deletion; cancelling keeps the account, and once the grace period
+    /// ends the account is deleted and leaves its shared teams.
+    #[tokio::test]
+    async fn blc_user_020_deletion_needs_handover_then_purges_after_grace() {
+        let db = test_db().await.unwrap();
+        let fx = TeamFixture::build(&db).await.unwrap();
+        let svc = account_service(&db, blob_dir());
+        let now = Utc::now();
+        let handover = |id: &str| DeleteAccount {
+            handovers: vec![TeamHandover {
+                team_id: fx.shared_team_id.clone(),
+                user: TeamUserRef { id: id.to_owned() },
+            }],
+        };
+
+        let err = svc
+            .schedule_deletion(&fx.admin_user, DeleteAccount::default(), now)
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, AppError::Conflict(msg) if msg.contains(&fx.shared_team_id)));
+        let err = svc
+            .schedule_deletion(&fx.admin_user, handover(&fx.non_member.id), now)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+        let err = svc
+            .schedule_deletion(&fx.writer, handover(&fx.guest.id), now)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidRequest(_)));
+
+        let scheduled = svc
+            .schedule_deletion(&fx.admin_user, handover(&fx.writer.id), now)
+            .await
+            .unwrap();
+        let delete_at = scheduled.deletion_scheduled_at.unwrap();
+        assert_eq!(delete_at, now + ChronoDuration::days(14));
+        let team = team_service(&db)
+            .get_team_for_user(&fx.writer, &fx.shared_team_id)
+            .await
+            .unwrap();
+        let writer = team
+            .members
+            .iter()
+            .find(|m| m.user.id == fx.writer.id)
+            .unwrap();
+        assert_eq!(writer.role, TeamRole::Admin);
+        let err = svc
+            .schedule_deletion(&fx.admin_user, DeleteAccount::default(), now)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+
+        let cancelled = svc.cancel_deletion(&fx.admin_user).await.unwrap();
+        assert!(cancelled.deletion_scheduled_at.is_none());
+        assert_eq!(svc.delete_due_accounts(delete_at).await.unwrap(), 0);
+
+        svc.schedule_deletion(&fx.admin_user, DeleteAccount::default(), now)
+            .await
+            .unwrap();
+        assert_eq!(
+            svc.delete_due_accounts(delete_at - ChronoDuration::seconds(1))
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(svc.delete_due_accounts(delete_at).await.unwrap(), 1);
+        assert!(matches!(
+            svc.get_user(&fx.admin_user.id).await.unwrap_err(),
+            AppError::NotFound(_)
+        ));
+        let team = team_service(&db)
+            .get_team_for_user(&fx.writer, &fx.shared_team_id)
+            .await
+            .unwrap();
+        assert!(team.members.iter().all(|m| m.user.id != fx.admin_user.id));
+    }
+}
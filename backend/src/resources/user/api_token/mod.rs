@@ -0,0 +1,15 @@
+pub use shared::user::{ApiToken, ApiTokenScope};
+
+mod model;
+pub use model::{ApiTokenAuth, is_api_token};
+
+pub mod repository;
+pub use repository::ApiTokenRepository;
+
+mod surreal_repo;
+pub use surreal_repo::SurrealApiTokenRepo;
+
+pub mod service;
+pub use service::{ApiTokenService, ApiTokenServiceHandle};
+
+pub mod rest;
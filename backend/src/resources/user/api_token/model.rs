@@ -0,0 +1,226 @@
+use actix_web::http::Method;
+use ring::digest;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::user::{ApiToken, ApiTokenScope, User};
+
+use crate::database::record_id_string;
+use crate::error::AppError;
+use crate::resources::user::UserRecord;
+
+/// Prefix that tells personal API tokens apart from session ids in `Authorization: Bearer`.
+pub const TOKEN_PREFIX: &str = "wvp_";
+
+pub fn is_api_token(bearer: &str) -> bool {
+    bearer.starts_with(TOKEN_PREFIX)
+}
+
+/// New token value: prefix plus 32 random bytes, hex encoded.
+pub fn generate_token() -> Result<String, AppError> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Internal("failed to generate api token".into()))?;
+    Ok(format!("{TOKEN_PREFIX}{}", hex::encode(bytes)))
+}
+
+/// Only this digest is stored; the token value is shown once on creation.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(digest::digest(&digest::SHA256, token.as_bytes()))
+}
+
+fn parse_scopes(scopes: &[String]) -> Result<Vec<ApiTokenScope>, AppError> {
+    scopes
+        .iter()
+        .map(|s| {
+            ApiTokenScope::parse(s)
+                .ok_or_else(|| AppError::database(format!("unknown api token scope `{s}`")))
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, SurrealValue)]
+pub struct ApiTokenRecord {
+    pub id: RecordId,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub expires_at: Option<Datetime>,
+    #[serde(default)]
+    pub last_used_at: Option<Datetime>,
+}
+
+impl ApiTokenRecord {
+    pub fn into_api_token(self) -> Result<ApiToken, AppError> {
+        Ok(ApiToken {
+            id: record_id_string(&self.id),
+            name: self.name,
+            scopes: parse_scopes(&self.scopes)?,
+            created_at: self.created_at.into(),
+            expires_at: self.expires_at.map(Into::into),
+            last_used_at: self.last_used_at.map(Into::into),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, SurrealValue)]
+pub struct ApiTokenCreateRecord {
+    pub user: RecordId,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub token_hash: String,
+    pub expires_at: Option<Datetime>,
+}
+
+/// Token row joined with its user, as read when authenticating a request.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct ApiTokenAuthRecord {
+    pub id: RecordId,
+    pub user: UserRecord,
+    pub scopes: Vec<String>,
+}
+
+impl ApiTokenAuthRecord {
+    pub fn into_auth(self) -> Result<ApiTokenAuth, AppError> {
+        Ok(ApiTokenAuth {
+            token_id: record_id_string(&self.id),
+            user: self.user.into_user(),
+            scopes: parse_scopes(&self.scopes)?,
+        })
+    }
+}
+
+/// A request authenticated with a personal API token. Inserted into request extensions next
+/// to the [`User`] so handlers can tell token requests from session requests.
+#[derive(Clone, Debug)]
+pub struct ApiTokenAuth {
+    pub token_id: String,
+    pub user: User,
+    pub scopes: Vec<ApiTokenScope>,
+}
+
+impl ApiTokenAuth {
+    /// Whether the token's scopes allow `method` on the API `path` (`/api/v1/...`).
+    pub fn permits(&self, method: &Method, path: &str) -> bool {
+        scopes_permit(&self.scopes, method, path)
+    }
+}
+
+fn under(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Session, token, passkey and identity management stay with interactive logins: a token can neither
+/// mint new credentials nor read session ids, whatever its scopes. The same holds for changing,
+/// exporting or deleting the account itself.
+fn credential_path(method: &Method, path: &str) -> bool {
+    if path == "/api/v1/users/me" {
+        return !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    }
+    let Some(rest) = path.strip_prefix("/api/v1/users/") else {
+        return false;
+    };
+    let mut segments = rest.split('/');
+    let _user = segments.next();
+    matches!(
+        segments.next(),
+        Some("tokens" | "passkeys" | "identities" | "session" | "sessions" | "export" | "deletion")
+    )
+}
+
+pub fn scopes_permit(scopes: &[ApiTokenScope], method: &Method, path: &str) -> bool {
+    if credential_path(method, path) {
+        return false;
+    }
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return !scopes.is_empty();
+    }
+    scopes.iter().any(|scope| match scope {
+        ApiTokenScope::Admin => true,
+        ApiTokenScope::SongsWrite => under(path, "/api/v1/songs"),
+        ApiTokenScope::SetlistsWrite => under(path, "/api/v1/setlists"),
+        ApiTokenScope::Read => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// BLC-TOK-004: every scope reads; writes need the matching scope or `admin`.
+    #[test]
+    fn blc_tok_004_scopes_gate_writes() {
+        use ApiTokenScope::*;
+        let cases = [
+            (Read, Method::GET, "/api/v1/songs", true),
+            (Read, Method::GET, "/api/v1/setlists/s1/player", true),
+            (Read, Method::POST, "/api/v1/songs", false),
+            (Read, Method::DELETE, "/api/v1/songs/s1", false),
+            (SongsWrite, Method::POST, "/api/v1/songs", true),
+            (SongsWrite, Method::PUT, "/api/v1/songs/s1/like", true),
+            (SongsWrite, Method::POST, "/api/v1/songsets", false),
+            (SongsWrite, Method::DELETE, "/api/v1/setlists/x", false),
+            (SongsWrite, Method::DELETE, "/api/v1/collections/x", false),
+            (SetlistsWrite, Method::PATCH, "/api/v1/setlists/x", true),
+            (SetlistsWrite, Method::POST, "/api/v1/blobs", false),
+            (Admin, Method::DELETE, "/api/v1/collections/x", true),
+            (Admin, Method::PUT, "/api/v1/teams/t1", true),
+        ];
+        for (scope, method, path, allowed) in cases {
+            assert_eq!(
+                scopes_permit(&[scope], &method, path),
+                allowed,
+                "{scope:?} {method} {path}"
+            );
+        }
+    }
+
+    /// BLC-TOK-005: tokens never reach session, token or account management, even with `admin`.
+    #[test]
+    fn blc_tok_005_credential_routes_are_session_only() {
+        for (method, path) in [
+            (Method::GET, "/api/v1/users/me/tokens"),
+            (Method::POST, "/api/v1/users/me/tokens"),
+            (Method::GET, "/api/v1/users/me/session"),
+            (Method::GET, "/api/v1/users/me/sessions"),
+            (Method::DELETE, "/api/v1/users/me/sessions/s1"),
+            (Method::GET, "/api/v1/users/u1/sessions"),
+            (Method::POST, "/api/v1/users/u1/sessions"),
+            (Method::GET, "/api/v1/users/me/passkeys"),
+            (Method::POST, "/api/v1/users/me/passkeys/options"),
+            (Method::GET, "/api/v1/users/me/identities"),
+            (Method::POST, "/api/v1/users/me/identities"),
+            (Method::PATCH, "/api/v1/users/me"),
+            (Method::DELETE, "/api/v1/users/me"),
+            (Method::GET, "/api/v1/users/me/export"),
+            (Method::DELETE, "/api/v1/users/me/deletion"),
+        ] {
+            assert!(
+                !scopes_permit(&[ApiTokenScope::Admin], &method, path),
+                "{method} {path}"
+            );
+        }
+        let me = "/api/v1/users/me";
+        assert!(scopes_permit(&[ApiTokenScope::Read], &Method::GET, me));
+        assert!(scopes_permit(
+            &[ApiTokenScope::Admin],
+            &Method::DELETE,
+            "/api/v1/users/u1"
+        ));
+    }
+
+    #[test]
+    fn tokens_are_prefixed_and_hashed() {
+        let token = generate_token().unwrap();
+        assert!(is_api_token(&token));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_token().unwrap());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_eq!(hash_token(&token).len(), 64);
+        assert!(!is_api_token("550e8400-e29b-41d4-a716-446655440000"));
+    }
+}
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use shared::user::ApiToken;
+
+use crate::error::AppError;
+
+use super::model::{ApiTokenAuth, ApiTokenCreateRecord};
+
+/// Pure personal API token data access — no authorization.
+#[async_trait]
+pub trait ApiTokenRepository: Send + Sync {
+    async fn create_token(&self, create: ApiTokenCreateRecord) -> Result<ApiToken, AppError>;
+    /// Tokens of a user, newest first (expired ones included).
+    async fn get_tokens_by_user_id(&self, user_id: &str) -> Result<Vec<ApiToken>, AppError>;
+    async fn delete_token_for_user(&self, id: &str, user_id: &str) -> Result<ApiToken, AppError>;
+    /// Looks up an unexpired token by hash and stamps `last_used_at`.
+    /// Returns `None` when no such token exists or it has expired.
+    async fn validate_token_and_touch(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<ApiTokenAuth>, AppError>;
+}
@@ -0,0 +1,131 @@
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, delete, get, post,
+    web::{Data, Json, Path, Query, ReqData},
+};
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+#[allow(unused_imports)]
+use shared::user::{ApiToken, CreatedApiToken};
+use shared::user::{CreateApiToken, User};
+
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+
+use super::service::ApiTokenServiceHandle;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/tokens",
+    request_body = CreateApiToken,
+    responses(
+        (status = 201, description = "Token created; `token` is only returned here", body = CreatedApiToken),
+        (status = 400, description = "Invalid name, scopes, or expiry", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; token management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to create token", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/me/tokens")]
+pub async fn create_api_token_for_current_user(
+    svc: Data<ApiTokenServiceHandle>,
+    user: ReqData<User>,
+    payload: Json<CreateApiToken>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Created().json(
+        svc.create_token_for_user(&user, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/tokens",
+    params(
+        ("page" = Option<u32>, Query, description = "Page index, zero-based. Omit with `page_size` for full list.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50. Omit with `page` for full list.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Personal API tokens of the current user, newest first (token values are never listed). `X-Total-Count` is the total before paging.", body = [ApiToken]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; token management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to list tokens", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/me/tokens")]
+pub async fn get_api_tokens_for_current_user(
+    req: HttpRequest,
+    svc: Data<ApiTokenServiceHandle>,
+    user: ReqData<User>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let q_link = query.clone();
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let (tokens, total) = svc
+        .list_tokens_for_user(&user, query.as_list_query())
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                &req,
+                |p| q_link.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(tokens))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/tokens/{id}",
+    params(
+        ("id" = String, Path, description = "Token identifier")
+    ),
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; token management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Token not found for current user", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to revoke token", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/me/tokens/{id}")]
+pub async fn delete_api_token_for_current_user(
+    svc: Data<ApiTokenServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    svc.delete_token_for_user(&user, &id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
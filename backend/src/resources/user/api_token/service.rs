@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use surrealdb::types::RecordId;
+use tracing::instrument;
+
+use shared::api::ListQuery;
+use shared::user::{ApiToken, CreateApiToken, CreatedApiToken, User};
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::{ApiTokenAuth, ApiTokenCreateRecord, generate_token, hash_token};
+use super::repository::ApiTokenRepository;
+use super::surreal_repo::SurrealApiTokenRepo;
+
+const MAX_NAME_LEN: usize = 100;
+
+/// Application service for personal API tokens.
+#[derive(Clone)]
+pub struct ApiTokenService<R> {
+    pub repo: R,
+}
+
+impl<R> ApiTokenService<R> {
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+}
+
+impl<R: ApiTokenRepository> ApiTokenService<R> {
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn create_token_for_user(
+        &self,
+        user: &User,
+        payload: CreateApiToken,
+    ) -> Result<CreatedApiToken, AppError> {
+        let name = payload.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(AppError::invalid_request(
+                "token name must be 1–100 characters",
+            ));
+        }
+        let mut scopes = payload.scopes;
+        scopes.sort();
+        scopes.dedup();
+        if scopes.is_empty() {
+            return Err(AppError::invalid_request(
+                "token must have at least one scope",
+            ));
+        }
+        if payload.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(AppError::invalid_request(
+                "expires_at must be in the future",
+            ));
+        }
+
+        let token = generate_token()?;
+        let api_token = self
+            .repo
+            .create_token(ApiTokenCreateRecord {
+                user: RecordId::new("user", user.id.clone()),
+                name: name.to_owned(),
+                scopes: scopes.iter().map(|s| s.as_str().to_owned()).collect(),
+                token_hash: hash_token(&token),
+                expires_at: payload.expires_at.map(Into::into),
+            })
+            .await?;
+        crate::audit!(
+            "audit.api_token.created",
+            token_id = tracing::field::display(&api_token.id),
+            user_id = tracing::field::display(&user.id),
+            scopes = tracing::field::debug(&api_token.scopes)
+            ; "api token created"
+        );
+        Ok(CreatedApiToken { api_token, token })
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_tokens_for_user(
+        &self,
+        user: &User,
+        pagination: ListQuery,
+    ) -> Result<(Vec<ApiToken>, u64), AppError> {
+        let tokens = self.repo.get_tokens_by_user_id(&user.id).await?;
+        Ok(ListQuery::paginate_vec(tokens, &pagination))
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn delete_token_for_user(&self, user: &User, id: &str) -> Result<ApiToken, AppError> {
+        let deleted = self.repo.delete_token_for_user(id, &user.id).await?;
+        crate::audit!(
+            "audit.api_token.revoked",
+            token_id = tracing::field::display(&deleted.id),
+            user_id = tracing::field::display(&user.id)
+            ; "api token revoked"
+        );
+        Ok(deleted)
+    }
+
+    /// Resolves a bearer value carrying the token prefix; `None` for unknown or expired tokens.
+    #[instrument(level = "debug", err, skip(self, token))]
+    pub async fn validate_token(&self, token: &str) -> Result<Option<ApiTokenAuth>, AppError> {
+        self.repo.validate_token_and_touch(&hash_token(token)).await
+    }
+}
+
+/// Production type alias used in HTTP wiring.
+pub type ApiTokenServiceHandle = ApiTokenService<SurrealApiTokenRepo>;
+
+impl ApiTokenServiceHandle {
+    pub fn build(db: Arc<Database>) -> Self {
+        ApiTokenService::new(SurrealApiTokenRepo::new(db))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use shared::api::ListQuery;
+    use shared::user::{ApiTokenScope, CreateApiToken};
+
+    use crate::error::AppError;
+    use crate::test_helpers::{api_token_service, create_user, test_db};
+
+    fn create(name: &str, scopes: Vec<ApiTokenScope>) -> CreateApiToken {
+        CreateApiToken {
+            name: name.into(),
+            scopes,
+            expires_at: None,
+        }
+    }
+
+    /// BLC-TOK-001: the token value is returned once and resolves to its owner and scopes.
+    #[tokio::test]
+    async fn blc_tok_001_created_token_authenticates() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "tok-001@test.local").await.expect("user");
+        let svc = api_token_service(&db);
+        let created = svc
+            .create_token_for_user(
+                &user,
+                create(
+                    " import ",
+                    vec![ApiTokenScope::SongsWrite, ApiTokenScope::SongsWrite],
+                ),
+            )
+            .await
+            .expect("create");
+        assert_eq!(created.api_token.name, "import");
+        assert_eq!(created.api_token.scopes, vec![ApiTokenScope::SongsWrite]);
+        assert!(created.api_token.last_used_at.is_none());
+
+        let auth = svc
+            .validate_token(&created.token)
+            .await
+            .expect("validate")
+            .expect("known token");
+        assert_eq!(auth.user.id, user.id);
+        assert_eq!(auth.token_id, created.api_token.id);
+        assert_eq!(auth.scopes, vec![ApiTokenScope::SongsWrite]);
+
+        let (listed, total) = svc
+            .list_tokens_for_user(&user, ListQuery::default())
+            .await
+            .expect("list");
+        assert_eq!(total, 1);
+        assert!(listed[0].last_used_at.is_some(), "use is tracked");
+
+        assert!(
+            svc.validate_token("wvp_not-a-token")
+                .await
+                .expect("validate")
+                .is_none()
+        );
+    }
+
+    /// BLC-TOK-002: names, scopes and expiry are validated.
+    #[tokio::test]
+    async fn blc_tok_002_validation() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "tok-002@test.local").await.expect("user");
+        let svc = api_token_service(&db);
+        for payload in [
+            create("  ", vec![ApiTokenScope::Read]),
+            create(&"x".repeat(101), vec![ApiTokenScope::Read]),
+            create("empty", vec![]),
+            CreateApiToken {
+                expires_at: Some(Utc::now() - Duration::minutes(1)),
+                ..create("past", vec![ApiTokenScope::Read])
+            },
+        ] {
+            let r = svc.create_token_for_user(&user, payload).await;
+            assert!(matches!(r, Err(AppError::InvalidRequest(_))), "{r:?}");
+        }
+    }
+
+    /// BLC-TOK-003: expired and revoked tokens no longer authenticate; users only see and
+    /// revoke their own tokens.
+    #[tokio::test]
+    async fn blc_tok_003_expiry_and_revocation() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "tok-003@test.local").await.expect("user");
+        let other = create_user(&db, "tok-003-other@test.local")
+            .await
+            .expect("user");
+        let svc = api_token_service(&db);
+        let expiring = svc
+            .create_token_for_user(
+                &user,
+                CreateApiToken {
+                    expires_at: Some(Utc::now() + Duration::milliseconds(300)),
+                    ..create("short", vec![ApiTokenScope::Read])
+                },
+            )
+            .await
+            .expect("create");
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        assert!(
+            svc.validate_token(&expiring.token)
+                .await
+                .expect("validate")
+                .is_none()
+        );
+
+        let kept = svc
+            .create_token_for_user(&user, create("kept", vec![ApiTokenScope::Admin]))
+            .await
+            .expect("create");
+        let r = svc.delete_token_for_user(&other, &kept.api_token.id).await;
+        assert!(matches!(r, Err(AppError::NotFound(_))));
+        let (others, _) = svc
+            .list_tokens_for_user(&other, ListQuery::default())
+            .await
+            .expect("list");
+        assert!(others.is_empty());
+
+        svc.delete_token_for_user(&user, &kept.api_token.id)
+            .await
+            .expect("revoke");
+        assert!(
+            svc.validate_token(&kept.token)
+                .await
+                .expect("validate")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use surrealdb::types::RecordId;
+
+use shared::user::ApiToken;
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::{ApiTokenAuth, ApiTokenAuthRecord, ApiTokenCreateRecord, ApiTokenRecord};
+use super::repository::ApiTokenRepository;
+
+#[derive(Clone)]
+pub struct SurrealApiTokenRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealApiTokenRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl ApiTokenRepository for SurrealApiTokenRepo {
+    async fn create_token(&self, create: ApiTokenCreateRecord) -> Result<ApiToken, AppError> {
+        self.inner()
+            .query("CREATE api_token CONTENT $create RETURN AFTER")
+            .bind(("create", create))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "api_token.create", e))?
+            .take::<Option<ApiTokenRecord>>(0)?
+            .ok_or_else(|| AppError::database("failed to create api token"))?
+            .into_api_token()
+    }
+
+    async fn get_tokens_by_user_id(&self, user_id: &str) -> Result<Vec<ApiToken>, AppError> {
+        self.inner()
+            .query("SELECT * FROM api_token WHERE user = $user ORDER BY created_at DESC")
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .await?
+            .take::<Vec<ApiTokenRecord>>(0)?
+            .into_iter()
+            .map(ApiTokenRecord::into_api_token)
+            .collect()
+    }
+
+    async fn delete_token_for_user(&self, id: &str, user_id: &str) -> Result<ApiToken, AppError> {
+        self.inner()
+            .query("DELETE api_token WHERE id = $id AND user = $user RETURN BEFORE")
+            .bind(("id", RecordId::new("api_token", id.to_owned())))
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .await?
+            .take::<Option<ApiTokenRecord>>(0)?
+            .ok_or_else(|| AppError::NotFound("api token not found".into()))?
+            .into_api_token()
+    }
+
+    async fn validate_token_and_touch(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<ApiTokenAuth>, AppError> {
+        self.inner()
+            .query(
+                r#"
+            LET $found = UPDATE api_token SET last_used_at = time::now()
+                WHERE token_hash = $hash
+                  AND (expires_at = NONE OR expires_at > time::now())
+                RETURN AFTER;
+
+            RETURN IF $found[0] = NONE { NONE } ELSE { (SELECT * FROM $found[0].id FETCH user)[0] };
+                "#,
+            )
+            .bind(("hash", token_hash.to_owned()))
+            .await
+            .map_err(|e| {
+                crate::log_and_convert!(AppError::database, "api_token.validate.query", e)
+            })?
+            .take::<Option<ApiTokenAuthRecord>>(1)
+            .map_err(|e| crate::log_and_convert!(AppError::database, "api_token.validate.take", e))?
+            .map(ApiTokenAuthRecord::into_auth)
+            .transpose()
+    }
+}
@@ -0,0 +1,14 @@
+pub use shared::user::{IdentityLinkStart, LinkIdentity, UserIdentity};
+
+mod model;
+
+pub mod repository;
+pub use repository::UserIdentityRepository;
+
+mod surreal_repo;
+pub use surreal_repo::SurrealUserIdentityRepo;
+
+pub mod service;
+pub use service::{UserIdentityService, UserIdentityServiceHandle};
+
+pub mod rest;
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::user::{User, UserIdentity};
+
+use crate::database::record_id_string;
+use crate::resources::user::UserRecord;
+
+#[derive(Clone, Debug, Deserialize, Serialize, SurrealValue)]
+pub struct UserIdentityRecord {
+    pub id: RecordId,
+    pub provider: String,
+    pub subject: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub last_login_at: Option<Datetime>,
+}
+
+impl UserIdentityRecord {
+    pub fn into_identity(self) -> UserIdentity {
+        UserIdentity {
+            id: record_id_string(&self.id),
+            provider: self.provider,
+            subject: self.subject,
+            email: self.email,
+            created_at: self.created_at.into(),
+            last_login_at: self.last_login_at.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, SurrealValue)]
+pub struct UserIdentityCreateRecord {
+    pub user: RecordId,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub last_login_at: Option<Datetime>,
+}
+
+/// Identity row joined with its user, as read on OIDC login.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct UserIdentityOwnerRecord {
+    pub id: RecordId,
+    pub provider: String,
+    pub subject: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub last_login_at: Option<Datetime>,
+    pub user: UserRecord,
+}
+
+impl UserIdentityOwnerRecord {
+    pub fn into_owned_identity(self) -> (UserIdentity, User) {
+        let identity = UserIdentityRecord {
+            id: self.id,
+            provider: self.provider,
+            subject: self.subject,
+            email: self.email,
+            created_at: self.created_at,
+            last_login_at: self.last_login_at,
+        }
+        .into_identity();
+        (identity, self.user.into_user())
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+
+use shared::user::{User, UserIdentity};
+
+use crate::error::AppError;
+
+use super::model::UserIdentityCreateRecord;
+
+/// Pure linked-identity data access — no authorization.
+#[async_trait]
+pub trait UserIdentityRepository: Send + Sync {
+    /// Fails with `Conflict` when the provider subject is already linked.
+    async fn create_identity(
+        &self,
+        create: UserIdentityCreateRecord,
+    ) -> Result<UserIdentity, AppError>;
+    /// Identities of a user, oldest first.
+    async fn get_identities_by_user_id(&self, user_id: &str)
+    -> Result<Vec<UserIdentity>, AppError>;
+    /// The identity for a provider subject together with the user it belongs to.
+    async fn get_identity_by_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<(UserIdentity, User)>, AppError>;
+    /// Stamps `last_login_at` and refreshes the email the provider reported.
+    async fn record_identity_login(&self, id: &str, email: Option<&str>) -> Result<(), AppError>;
+    async fn delete_identity_for_user(
+        &self,
+        id: &str,
+        user_id: &str,
+    ) -> Result<UserIdentity, AppError>;
+}
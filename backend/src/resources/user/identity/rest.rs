@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, delete, get, post,
+    web::{Data, Json, Path, Query, ReqData},
+};
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+#[allow(unused_imports)]
+use shared::user::UserIdentity;
+use shared::user::{IdentityLinkStart, LinkIdentity, User};
+
+use crate::auth::oidc::{OidcClients, start_authorization};
+use crate::database::Database;
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+
+use super::service::UserIdentityServiceHandle;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/identities",
+    params(
+        ("page" = Option<u32>, Query, description = "Page index, zero-based. Omit with `page_size` for full list.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50. Omit with `page` for full list.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OIDC accounts linked to the current user, oldest first. `X-Total-Count` is the total before paging.", body = [UserIdentity]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; identity management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to list identities", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/me/identities")]
+pub async fn get_identities_for_current_user(
+    req: HttpRequest,
+    svc: Data<UserIdentityServiceHandle>,
+    user: ReqData<User>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let q_link = query.clone();
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let (identities, total) = svc
+        .list_identities_for_user(&user, query.as_list_query())
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                &req,
+                |p| q_link.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(identities))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/identities",
+    request_body = LinkIdentity,
+    responses(
+        (status = 200, description = "Send the browser to `authorization_url`. After the provider login, `/auth/callback` links that account to the current user and redirects to `redirect_to`; the session stays unchanged.", body = IdentityLinkStart),
+        (status = 400, description = "Unknown provider", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; identity management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to prepare the link flow", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/me/identities")]
+pub async fn create_identity_link_for_current_user(
+    db: Data<Database>,
+    oidc_clients: Data<Arc<OidcClients>>,
+    user: ReqData<User>,
+    payload: Json<LinkIdentity>,
+) -> Result<HttpResponse, AppError> {
+    let payload = payload.into_inner();
+    let registration = oidc_clients
+        .resolve(Some(&payload.provider))
+        .ok_or_else(|| AppError::invalid_request("oauth provider not configured"))?;
+    let authorization_url = start_authorization(
+        &db,
+        registration,
+        payload.redirect_to.as_deref(),
+        Some(user.id.clone()),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(IdentityLinkStart { authorization_url }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/identities/{id}",
+    params(
+        ("id" = String, Path, description = "Identity identifier")
+    ),
+    responses(
+        (status = 204, description = "Identity unlinked; that provider account no longer logs in as this user unless its verified email matches the account email"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; identity management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Identity not found for current user", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to unlink identity", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/me/identities/{id}")]
+pub async fn delete_identity_for_current_user(
+    svc: Data<UserIdentityServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    svc.unlink_identity_for_user(&user, &id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
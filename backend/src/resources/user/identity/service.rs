@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use surrealdb::types::RecordId;
+use tracing::instrument;
+
+use shared::api::ListQuery;
+use shared::user::{User, UserIdentity};
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::UserIdentityCreateRecord;
+use super::repository::UserIdentityRepository;
+use super::surreal_repo::SurrealUserIdentityRepo;
+
+/// Application service for external OIDC identities linked to users.
+#[derive(Clone)]
+pub struct UserIdentityService<R> {
+    pub repo: R,
+}
+
+impl<R> UserIdentityService<R> {
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+}
+
+impl<R: UserIdentityRepository> UserIdentityService<R> {
+    /// The user a provider subject logs in as, if it is linked; records the login.
+    #[instrument(level = "debug", err, skip(self, email))]
+    pub async fn user_for_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+    ) -> Result<Option<User>, AppError> {
+        let Some((identity, user)) = self.repo.get_identity_by_subject(provider, subject).await?
+        else {
+            return Ok(None);
+        };
+        self.repo.record_identity_login(&identity.id, email).await?;
+        Ok(Some(user))
+    }
+
+    /// Links a provider subject to `user`. Linking it again to the same user is a no-op; a
+    /// subject that already belongs to another user is a `Conflict`.
+    #[instrument(level = "debug", err, skip(self, user, email))]
+    pub async fn link_identity(
+        &self,
+        user: &User,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+    ) -> Result<UserIdentity, AppError> {
+        if let Some((identity, owner)) =
+            self.repo.get_identity_by_subject(provider, subject).await?
+        {
+            if owner.id != user.id {
+                return Err(AppError::conflict(
+                    "identity is already linked to another account",
+                ));
+            }
+            return Ok(identity);
+        }
+        let identity = self
+            .repo
+            .create_identity(UserIdentityCreateRecord {
+                user: RecordId::new("user", user.id.clone()),
+                provider: provider.to_owned(),
+                subject: subject.to_owned(),
+                email: email.map(str::to_owned),
+                last_login_at: Some(Utc::now().into()),
+            })
+            .await?;
+        crate::audit!(
+            "audit.user_identity.linked",
+            identity_id = tracing::field::display(&identity.id),
+            provider = tracing::field::display(&identity.provider),
+            user_id = tracing::field::display(&user.id)
+            ; "identity linked"
+        );
+        Ok(identity)
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_identities_for_user(
+        &self,
+        user: &User,
+        pagination: ListQuery,
+    ) -> Result<(Vec<UserIdentity>, u64), AppError> {
+        let identities = self.repo.get_identities_by_user_id(&user.id).await?;
+        Ok(ListQuery::paginate_vec(identities, &pagination))
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn unlink_identity_for_user(
+        &self,
+        user: &User,
+        id: &str,
+    ) -> Result<UserIdentity, AppError> {
+        let deleted = self.repo.delete_identity_for_user(id, &user.id).await?;
+        crate::audit!(
+            "audit.user_identity.unlinked",
+            identity_id = tracing::field::display(&deleted.id),
+            provider = tracing::field::display(&deleted.provider),
+            user_id = tracing::field::display(&user.id)
+            ; "identity unlinked"
+        );
+        Ok(deleted)
+    }
+}
+
+/// Production type alias used in HTTP wiring.
+pub type UserIdentityServiceHandle = UserIdentityService<SurrealUserIdentityRepo>;
+
+impl UserIdentityServiceHandle {
+    pub fn build(db: Arc<Database>) -> Self {
+        UserIdentityService::new(SurrealUserIdentityRepo::new(db))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::api::ListQuery;
+
+    use crate::error::AppError;
+    use crate::test_helpers::{create_user, identity_service, test_db};
+
+    /// BLC-IDENT-001: a user links subjects of several providers; a linked subject logs in as
+    /// that user and records the login, and linking it again changes nothing.
+    #[tokio::test]
+    async fn blc_ident_001_link_several_identities() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "ident-001@test.local")
+            .await
+            .expect("user");
+        let svc = identity_service(&db);
+
+        let google = svc
+            .link_identity(&user, "google", "g-123", Some("ident-001@test.local"))
+            .await
+            .expect("link google");
+        let keycloak = svc
+            .link_identity(&user, "keycloak", "g-123", None)
+            .await
+            .expect("same subject at another provider");
+        let again = svc
+            .link_identity(&user, "google", "g-123", None)
+            .await
+            .expect("relink");
+        assert_eq!(again.id, google.id);
+
+        let found = svc
+            .user_for_identity("keycloak", "g-123", Some("choir@church.example"))
+            .await
+            .expect("lookup")
+            .expect("linked user");
+        assert_eq!(found.id, user.id);
+        assert!(
+            svc.user_for_identity("microsoft", "g-123", None)
+                .await
+                .expect("lookup")
+                .is_none()
+        );
+
+        let (listed, total) = svc
+            .list_identities_for_user(&user, ListQuery::default())
+            .await
+            .expect("list");
+        assert_eq!(total, 2);
+        assert_eq!(listed[0].id, google.id, "oldest first");
+        assert_eq!(listed[1].id, keycloak.id);
+        assert_eq!(listed[1].email.as_deref(), Some("choir@church.example"));
+    }
+
+    /// BLC-IDENT-002: a provider subject belongs to at most one user.
+    #[tokio::test]
+    async fn blc_ident_002_subject_links_one_user() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "ident-002a@test.local")
+            .await
+            .expect("user");
+        let other = create_user(&db, "ident-002b@test.local")
+            .await
+            .expect("user");
+        let svc = identity_service(&db);
+
+        svc.link_identity(&owner, "microsoft", "ms-1", None)
+            .await
+            .expect("link");
+        let err = svc
+            .link_identity(&other, "microsoft", "ms-1", None)
+            .await
+            .expect_err("linked elsewhere");
+        assert!(matches!(err, AppError::Conflict(_)), "{err:?}");
+    }
+
+    /// BLC-IDENT-003: users only unlink their own identities; an unlinked subject no longer
+    /// logs in as the user.
+    #[tokio::test]
+    async fn blc_ident_003_owner_only_unlink() {
+        let db = test_db().await.expect("db");
+        let owner = create_user(&db, "ident-003a@test.local")
+            .await
+            .expect("user");
+        let other = create_user(&db, "ident-003b@test.local")
+            .await
+            .expect("user");
+        let svc = identity_service(&db);
+        let identity = svc
+            .link_identity(&owner, "keycloak", "kc-1", None)
+            .await
+            .expect("link");
+
+        let err = svc
+            .unlink_identity_for_user(&other, &identity.id)
+            .await
+            .expect_err("not owner");
+        assert!(matches!(err, AppError::NotFound(_)), "{err:?}");
+
+        svc.unlink_identity_for_user(&owner, &identity.id)
+            .await
+            .expect("unlink");
+        assert!(
+            svc.user_for_identity("keycloak", "kc-1", None)
+                .await
+                .expect("lookup")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use surrealdb::types::RecordId;
+
+use shared::user::{User, UserIdentity};
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::{UserIdentityCreateRecord, UserIdentityOwnerRecord, UserIdentityRecord};
+use super::repository::UserIdentityRepository;
+
+#[derive(Clone)]
+pub struct SurrealUserIdentityRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealUserIdentityRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl UserIdentityRepository for SurrealUserIdentityRepo {
+    async fn create_identity(
+        &self,
+        create: UserIdentityCreateRecord,
+    ) -> Result<UserIdentity, AppError> {
+        Ok(self
+            .inner()
+            .query("CREATE user_identity CONTENT $create RETURN AFTER")
+            .bind(("create", create))
+            .await?
+            .take::<Option<UserIdentityRecord>>(0)?
+            .ok_or_else(|| AppError::database("failed to create user identity"))?
+            .into_identity())
+    }
+
+    async fn get_identities_by_user_id(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserIdentity>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM user_identity WHERE user = $user ORDER BY created_at ASC")
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .await?
+            .take::<Vec<UserIdentityRecord>>(0)?
+            .into_iter()
+            .map(UserIdentityRecord::into_identity)
+            .collect())
+    }
+
+    async fn get_identity_by_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<(UserIdentity, User)>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM user_identity WHERE provider = $provider AND subject = $subject FETCH user",
+            )
+            .bind(("provider", provider.to_owned()))
+            .bind(("subject", subject.to_owned()))
+            .await?
+            .take::<Option<UserIdentityOwnerRecord>>(0)?
+            .map(UserIdentityOwnerRecord::into_owned_identity))
+    }
+
+    async fn record_identity_login(&self, id: &str, email: Option<&str>) -> Result<(), AppError> {
+        self.inner()
+            .query(
+                "UPDATE $id SET last_login_at = time::now(), email = $email ?? email RETURN NONE",
+            )
+            .bind(("id", RecordId::new("user_identity", id.to_owned())))
+            .bind(("email", email.map(str::to_owned)))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn delete_identity_for_user(
+        &self,
+        id: &str,
+        user_id: &str,
+    ) -> Result<UserIdentity, AppError> {
+        Ok(self
+            .inner()
+            .query("DELETE user_identity WHERE id = $id AND user = $user RETURN BEFORE")
+            .bind(("id", RecordId::new("user_identity", id.to_owned())))
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .await?
+            .take::<Option<UserIdentityRecord>>(0)?
+            .ok_or_else(|| AppError::NotFound("identity not found".into()))?
+            .into_identity())
+    }
+}
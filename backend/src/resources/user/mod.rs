@@ -17,3 +17,11 @@ mod profile_picture;
 pub mod rest;
 
 pub mod session;
+
+pub mod api_token;
+
+pub mod passkey;
+
+pub mod identity;
+
+pub mod account;
@@ -64,6 +64,8 @@ pub struct UserRecord {
     oauth_avatar_blob: Option<RecordId>,
     #[serde(default)]
     avatar_blob: Option<RecordId>,
+    #[serde(default)]
+    deletion_scheduled_at: Option<Datetime>,
 }
 
 impl UserRecord {
@@ -79,6 +81,7 @@ impl UserRecord {
             oauth_picture_url: self.oauth_picture_url,
             oauth_avatar_blob_id: self.oauth_avatar_blob.map(|id| record_id_string(&id)),
             avatar_blob_id: self.avatar_blob.map(|id| record_id_string(&id)),
+            deletion_scheduled_at: self.deletion_scheduled_at.map(Into::into),
         }
     }
 
@@ -106,6 +109,7 @@ impl UserRecord {
                 .avatar_blob_id
                 .as_deref()
                 .map(|id| RecordId::new("blob", id)),
+            deletion_scheduled_at: user.deletion_scheduled_at.map(Into::into),
         }
     }
 }
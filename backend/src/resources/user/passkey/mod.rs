@@ -0,0 +1,19 @@
+pub use shared::user::{Passkey, RegisterPasskey, UpdatePasskey};
+
+mod model;
+
+pub mod repository;
+pub use repository::PasskeyRepository;
+
+mod surreal_repo;
+pub use surreal_repo::SurrealPasskeyRepo;
+
+pub mod service;
+pub use service::{PasskeyService, PasskeyServiceHandle};
+
+pub mod rest;
+
+mod webauthn;
+
+#[cfg(test)]
+pub(crate) mod soft_authenticator;
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use surrealdb::types::{Datetime, RecordId, SurrealValue};
+
+use shared::user::{Passkey, User};
+
+use crate::database::record_id_string;
+use crate::resources::user::UserRecord;
+
+/// What a stored challenge may be answered with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengePurpose {
+    Registration,
+    Authentication,
+}
+
+impl ChallengePurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Registration => "registration",
+            Self::Authentication => "authentication",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct ChallengeRecord {
+    /// Set for registration challenges; login challenges are issued before the user is known.
+    #[serde(default)]
+    pub user: Option<RecordId>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, SurrealValue)]
+pub struct PasskeyRecord {
+    pub id: RecordId,
+    pub name: String,
+    pub created_at: Datetime,
+    #[serde(default)]
+    pub last_used_at: Option<Datetime>,
+}
+
+impl PasskeyRecord {
+    pub fn into_passkey(self) -> Passkey {
+        Passkey {
+            id: record_id_string(&self.id),
+            name: self.name,
+            created_at: self.created_at.into(),
+            last_used_at: self.last_used_at.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, SurrealValue)]
+pub struct PasskeyCreateRecord {
+    pub user: RecordId,
+    pub name: String,
+    /// Base64url credential id, as the browser reports it.
+    pub credential_id: String,
+    /// Base64url COSE_Key.
+    pub public_key: String,
+    pub sign_count: i64,
+}
+
+/// Passkey row joined with its user, as read when verifying a login assertion.
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub struct PasskeyCredentialRecord {
+    pub id: RecordId,
+    pub user: UserRecord,
+    pub public_key: String,
+    pub sign_count: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct PasskeyCredential {
+    pub id: String,
+    pub user: User,
+    pub public_key: String,
+    pub sign_count: u32,
+}
+
+impl PasskeyCredentialRecord {
+    pub fn into_credential(self) -> PasskeyCredential {
+        PasskeyCredential {
+            id: record_id_string(&self.id),
+            user: self.user.into_user(),
+            public_key: self.public_key,
+            sign_count: u32::try_from(self.sign_count).unwrap_or(u32::MAX),
+        }
+    }
+}
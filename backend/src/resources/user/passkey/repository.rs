@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+
+use shared::user::Passkey;
+
+use crate::error::AppError;
+
+use super::model::{ChallengePurpose, ChallengeRecord, PasskeyCreateRecord, PasskeyCredential};
+
+/// Pure passkey and WebAuthn challenge data access — no authorization.
+#[async_trait]
+pub trait PasskeyRepository: Send + Sync {
+    async fn create_challenge(
+        &self,
+        challenge: &str,
+        purpose: ChallengePurpose,
+        user_id: Option<&str>,
+        ttl_seconds: u64,
+    ) -> Result<(), AppError>;
+    /// Deletes and returns an unexpired challenge issued for `purpose`, so each one is
+    /// answered at most once. `None` when unknown, expired or issued for the other ceremony.
+    async fn take_challenge(
+        &self,
+        challenge: &str,
+        purpose: ChallengePurpose,
+    ) -> Result<Option<ChallengeRecord>, AppError>;
+    /// Fails with `Conflict` when the credential id is already registered.
+    async fn create_passkey(&self, create: PasskeyCreateRecord) -> Result<Passkey, AppError>;
+    /// Passkeys of a user, newest first.
+    async fn get_passkeys_by_user_id(&self, user_id: &str) -> Result<Vec<Passkey>, AppError>;
+    async fn get_credential_ids_by_user_id(&self, user_id: &str) -> Result<Vec<String>, AppError>;
+    async fn update_passkey_name_for_user(
+        &self,
+        id: &str,
+        user_id: &str,
+        name: &str,
+    ) -> Result<Passkey, AppError>;
+    async fn delete_passkey_for_user(&self, id: &str, user_id: &str) -> Result<Passkey, AppError>;
+    async fn get_credential(
+        &self,
+        credential_id: &str,
+    ) -> Result<Option<PasskeyCredential>, AppError>;
+    /// Stores the counter of an accepted assertion and stamps `last_used_at`, but only while the
+    /// stored counter is lower (or both are zero). `false` when another assertion got there first.
+    async fn record_passkey_use(&self, id: &str, sign_count: u32) -> Result<bool, AppError>;
+}
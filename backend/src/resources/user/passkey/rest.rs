@@ -0,0 +1,193 @@
+use actix_web::http::header;
+use actix_web::{
+    HttpRequest, HttpResponse, delete, get, post, put,
+    web::{Data, Json, Path, Query, ReqData},
+};
+use shared::api::{PAGE_SIZE_DEFAULT, PageQuery};
+#[allow(unused_imports)]
+use shared::auth::passkey::PasskeyCreationOptions;
+#[allow(unused_imports)]
+use shared::user::Passkey;
+use shared::user::{RegisterPasskey, UpdatePasskey, User};
+
+#[allow(unused_imports)]
+use crate::docs::Problem;
+use crate::error::AppError;
+
+use super::service::PasskeyServiceHandle;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/passkeys/options",
+    responses(
+        (status = 200, description = "Creation options for `navigator.credentials.create({ publicKey })`. The challenge is single-use and expires after five minutes.", body = PasskeyCreationOptions),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; passkey management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to issue a challenge", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/me/passkeys/options")]
+pub async fn create_passkey_options_for_current_user(
+    svc: Data<PasskeyServiceHandle>,
+    user: ReqData<User>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(svc.registration_options_for_user(&user).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/passkeys",
+    request_body = RegisterPasskey,
+    responses(
+        (status = 201, description = "Passkey registered; it can now be used on `/auth/passkey/verify`", body = Passkey),
+        (status = 400, description = "Invalid name, or the attestation does not answer an open challenge of this user for this site", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; passkey management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "This credential is already registered", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to store passkey", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[post("/me/passkeys")]
+pub async fn create_passkey_for_current_user(
+    svc: Data<PasskeyServiceHandle>,
+    user: ReqData<User>,
+    payload: Json<RegisterPasskey>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Created().json(
+        svc.register_passkey_for_user(&user, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/passkeys",
+    params(
+        ("page" = Option<u32>, Query, description = "Page index, zero-based. Omit with `page_size` for full list.", minimum = 0, nullable = true),
+        ("page_size" = Option<u32>, Query, description = "Items per page. Must be 1–500. Defaults to 50. Omit with `page` for full list.", minimum = 1, maximum = 500, example = 50, nullable = true),
+    ),
+    responses(
+        (status = 200, description = "Passkeys of the current user, newest first. `X-Total-Count` is the total before paging.", body = [Passkey]),
+        (status = 400, description = "Invalid pagination parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; passkey management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to list passkeys", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[get("/me/passkeys")]
+pub async fn get_passkeys_for_current_user(
+    req: HttpRequest,
+    svc: Data<PasskeyServiceHandle>,
+    user: ReqData<User>,
+    query: Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let query = query
+        .into_inner()
+        .validate()
+        .map_err(crate::error::map_list_query_error)?;
+    let q_link = query.clone();
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(PAGE_SIZE_DEFAULT);
+    let (passkeys, total) = svc
+        .list_passkeys_for_user(&user, query.as_list_query())
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::HeaderName::from_static("x-total-count"),
+            total.to_string(),
+        ))
+        .insert_header((
+            header::LINK,
+            crate::request_link::list_link_header(
+                &req,
+                |p| q_link.query_string_for_page(p),
+                page,
+                page_size,
+                total,
+            ),
+        ))
+        .json(passkeys))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/passkeys/{id}",
+    params(
+        ("id" = String, Path, description = "Passkey identifier")
+    ),
+    request_body = UpdatePasskey,
+    responses(
+        (status = 200, description = "Passkey renamed", body = Passkey),
+        (status = 400, description = "Invalid name", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; passkey management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Passkey not found for current user", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to rename passkey", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[put("/me/passkeys/{id}")]
+pub async fn update_passkey_for_current_user(
+    svc: Data<PasskeyServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+    payload: Json<UpdatePasskey>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(
+        svc.update_passkey_for_user(&user, &id, payload.into_inner())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/passkeys/{id}",
+    params(
+        ("id" = String, Path, description = "Passkey identifier")
+    ),
+    responses(
+        (status = 204, description = "Passkey removed; it can no longer log in"),
+        (status = 401, description = "Authentication required", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Called with an API token; passkey management requires a login session", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Passkey not found for current user", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "API rate limit exceeded; see `Retry-After` and `X-RateLimit-*` response headers", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Failed to remove passkey", body = Problem, content_type = "application/problem+json")
+    ),
+    tag = "Users",
+    security(
+        ("SessionCookie" = []),
+        ("SessionToken" = [])
+    )
+)]
+#[delete("/me/passkeys/{id}")]
+pub async fn delete_passkey_for_current_user(
+    svc: Data<PasskeyServiceHandle>,
+    user: ReqData<User>,
+    id: Path<String>,
+) -> Result<HttpResponse, AppError> {
+    svc.delete_passkey_for_user(&user, &id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,706 @@
+use std::sync::Arc;
+
+use surrealdb::types::RecordId;
+use tracing::instrument;
+
+use shared::api::ListQuery;
+use shared::auth::passkey::{
+    AuthenticationCredential, AuthenticatorSelection, CredentialDescriptor, CredentialParameter,
+    PasskeyCreationOptions, PasskeyRequestOptions, PasskeyUserEntity, RelyingParty,
+    SUPPORTED_ALGORITHMS,
+};
+use shared::user::{Passkey, RegisterPasskey, UpdatePasskey, User};
+
+use crate::database::{Database, record_id_string};
+use crate::error::AppError;
+use crate::settings::WebauthnConfig;
+
+use super::model::{ChallengePurpose, PasskeyCreateRecord};
+use super::repository::PasskeyRepository;
+use super::surreal_repo::SurrealPasskeyRepo;
+use super::webauthn::{
+    AuthenticatorData, CLIENT_DATA_CREATE, CLIENT_DATA_GET, CoseKey, assertion_message,
+    attestation_auth_data, b64url_decode, b64url_encode, new_challenge, parse_client_data,
+};
+
+const MAX_NAME_LEN: usize = 100;
+const CHALLENGE_TTL_SECONDS: u64 = 300;
+const PUBLIC_KEY_TYPE: &str = "public-key";
+
+fn validated_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::invalid_request(
+            "passkey name must be 1–100 characters",
+        ));
+    }
+    Ok(name.to_owned())
+}
+
+/// Opaque WebAuthn user handle: the user's record id.
+fn user_handle(user: &User) -> String {
+    b64url_encode(user.id.as_bytes())
+}
+
+/// Application service for passkey management and passkey login.
+#[derive(Clone)]
+pub struct PasskeyService<R> {
+    pub repo: R,
+    pub config: WebauthnConfig,
+}
+
+impl<R> PasskeyService<R> {
+    pub fn new(repo: R, config: WebauthnConfig) -> Self {
+        Self { repo, config }
+    }
+}
+
+impl<R: PasskeyRepository> PasskeyService<R> {
+    /// Decodes client data and consumes its challenge, returning the user it was issued to.
+    /// The challenge is spent even when a later check fails, so a rejected response cannot
+    /// be retried.
+    async fn take_client_data(
+        &self,
+        client_data_json: &[u8],
+        kind: &str,
+        purpose: ChallengePurpose,
+    ) -> Result<Option<RecordId>, AppError> {
+        let client_data = parse_client_data(client_data_json)?;
+        let challenge = self
+            .repo
+            .take_challenge(&client_data.challenge, purpose)
+            .await?
+            .ok_or_else(|| AppError::invalid_request("challenge is unknown or expired"))?;
+        if client_data.kind != kind {
+            return Err(AppError::invalid_request(format!(
+                "client data type must be `{kind}`"
+            )));
+        }
+        if client_data.origin != self.config.origin {
+            return Err(AppError::invalid_request(
+                "client data origin does not match this site",
+            ));
+        }
+        Ok(challenge.user)
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn registration_options_for_user(
+        &self,
+        user: &User,
+    ) -> Result<PasskeyCreationOptions, AppError> {
+        let challenge = new_challenge()?;
+        self.repo
+            .create_challenge(
+                &challenge,
+                ChallengePurpose::Registration,
+                Some(&user.id),
+                CHALLENGE_TTL_SECONDS,
+            )
+            .await?;
+        let exclude_credentials = self
+            .repo
+            .get_credential_ids_by_user_id(&user.id)
+            .await?
+            .into_iter()
+            .map(|id| CredentialDescriptor {
+                kind: PUBLIC_KEY_TYPE.into(),
+                id,
+            })
+            .collect();
+        Ok(PasskeyCreationOptions {
+            rp: RelyingParty {
+                id: self.config.rp_id.clone(),
+                name: self.config.rp_name.clone(),
+            },
+            user: PasskeyUserEntity {
+                id: user_handle(user),
+                name: user.email.clone(),
+                display_name: user.email.clone(),
+            },
+            challenge,
+            pub_key_cred_params: SUPPORTED_ALGORITHMS
+                .iter()
+                .map(|&alg| CredentialParameter {
+                    kind: PUBLIC_KEY_TYPE.into(),
+                    alg,
+                })
+                .collect(),
+            timeout: CHALLENGE_TTL_SECONDS * 1000,
+            exclude_credentials,
+            authenticator_selection: AuthenticatorSelection {
+                resident_key: "required".into(),
+                require_resident_key: true,
+                user_verification: "required".into(),
+            },
+            attestation: "none".into(),
+        })
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn register_passkey_for_user(
+        &self,
+        user: &User,
+        payload: RegisterPasskey,
+    ) -> Result<Passkey, AppError> {
+        let name = validated_name(&payload.name)?;
+        let credential = payload.credential;
+        if credential.kind != PUBLIC_KEY_TYPE {
+            return Err(AppError::invalid_request(
+                "credential type must be `public-key`",
+            ));
+        }
+        let client_data_json =
+            b64url_decode(&credential.response.client_data_json, "client_data_json")?;
+        let challenge_user = self
+            .take_client_data(
+                &client_data_json,
+                CLIENT_DATA_CREATE,
+                ChallengePurpose::Registration,
+            )
+            .await?;
+        if challenge_user.as_ref().map(record_id_string).as_deref() != Some(user.id.as_str()) {
+            return Err(AppError::invalid_request("challenge is unknown or expired"));
+        }
+
+        let auth_data = AuthenticatorData::parse(&attestation_auth_data(&b64url_decode(
+            &credential.response.attestation_object,
+            "attestation_object",
+        )?)?)?;
+        if !auth_data.matches_rp(&self.config.rp_id) {
+            return Err(AppError::invalid_request(
+                "passkey was created for a different relying party",
+            ));
+        }
+        if !auth_data.user_present() {
+            return Err(AppError::invalid_request("user presence was not confirmed"));
+        }
+        // A passkey signs in on its own, so it must stand for a PIN or biometric as well.
+        if !auth_data.user_verified() {
+            return Err(AppError::invalid_request(
+                "user verification was not performed",
+            ));
+        }
+        let attested = auth_data
+            .attested_credential
+            .ok_or_else(|| AppError::invalid_request("attestation carries no credential"))?;
+        CoseKey::parse(&attested.public_key)?;
+        let credential_id = b64url_encode(&attested.credential_id);
+        if b64url_decode(&credential.id, "credential id")? != attested.credential_id {
+            return Err(AppError::invalid_request(
+                "credential id does not match the attested credential",
+            ));
+        }
+
+        let passkey = self
+            .repo
+            .create_passkey(PasskeyCreateRecord {
+                user: RecordId::new("user", user.id.clone()),
+                name,
+                credential_id,
+                public_key: b64url_encode(&attested.public_key),
+                sign_count: auth_data.sign_count as i64,
+            })
+            .await?;
+        crate::audit!(
+            "audit.passkey.registered",
+            passkey_id = tracing::field::display(&passkey.id),
+            user_id = tracing::field::display(&user.id)
+            ; "passkey registered"
+        );
+        Ok(passkey)
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, pagination))]
+    pub async fn list_passkeys_for_user(
+        &self,
+        user: &User,
+        pagination: ListQuery,
+    ) -> Result<(Vec<Passkey>, u64), AppError> {
+        let passkeys = self.repo.get_passkeys_by_user_id(&user.id).await?;
+        Ok(ListQuery::paginate_vec(passkeys, &pagination))
+    }
+
+    #[instrument(level = "debug", err, skip(self, user, payload))]
+    pub async fn update_passkey_for_user(
+        &self,
+        user: &User,
+        id: &str,
+        payload: UpdatePasskey,
+    ) -> Result<Passkey, AppError> {
+        let name = validated_name(&payload.name)?;
+        self.repo
+            .update_passkey_name_for_user(id, &user.id, &name)
+            .await
+    }
+
+    #[instrument(level = "debug", err, skip(self, user))]
+    pub async fn delete_passkey_for_user(
+        &self,
+        user: &User,
+        id: &str,
+    ) -> Result<Passkey, AppError> {
+        let deleted = self.repo.delete_passkey_for_user(id, &user.id).await?;
+        crate::audit!(
+            "audit.passkey.deleted",
+            passkey_id = tracing::field::display(&deleted.id),
+            user_id = tracing::field::display(&user.id)
+            ; "passkey deleted"
+        );
+        Ok(deleted)
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    pub async fn login_options(&self) -> Result<PasskeyRequestOptions, AppError> {
+        let challenge = new_challenge()?;
+        self.repo
+            .create_challenge(
+                &challenge,
+                ChallengePurpose::Authentication,
+                None,
+                CHALLENGE_TTL_SECONDS,
+            )
+            .await?;
+        Ok(PasskeyRequestOptions {
+            challenge,
+            timeout: CHALLENGE_TTL_SECONDS * 1000,
+            rp_id: self.config.rp_id.clone(),
+            allow_credentials: vec![],
+            user_verification: "required".into(),
+        })
+    }
+
+    /// Verifies a login assertion and returns the passkey's user. Malformed payloads are
+    /// `InvalidRequest`; well-formed assertions that do not check out are `Unauthorized`.
+    #[instrument(level = "debug", err, skip(self, credential))]
+    pub async fn authenticate(
+        &self,
+        credential: AuthenticationCredential,
+    ) -> Result<User, AppError> {
+        if credential.kind != PUBLIC_KEY_TYPE {
+            return Err(AppError::invalid_request(
+                "credential type must be `public-key`",
+            ));
+        }
+        let response = &credential.response;
+        let client_data_json = b64url_decode(&response.client_data_json, "client_data_json")?;
+        let auth_data_bytes = b64url_decode(&response.authenticator_data, "authenticator_data")?;
+        let signature = b64url_decode(&response.signature, "signature")?;
+        let credential_id = b64url_encode(&b64url_decode(&credential.id, "credential id")?);
+
+        self.take_client_data(
+            &client_data_json,
+            CLIENT_DATA_GET,
+            ChallengePurpose::Authentication,
+        )
+        .await
+        .map_err(|_| AppError::unauthorized())?;
+
+        let stored = self
+            .repo
+            .get_credential(&credential_id)
+            .await?
+            .ok_or_else(AppError::unauthorized)?;
+        if response
+            .user_handle
+            .as_deref()
+            .is_some_and(|handle| handle.trim_end_matches('=') != user_handle(&stored.user))
+        {
+            return Err(AppError::unauthorized());
+        }
+
+        let auth_data = AuthenticatorData::parse(&auth_data_bytes)?;
+        // No second factor follows a passkey login, so a bare touch is not enough.
+        if !auth_data.matches_rp(&self.config.rp_id)
+            || !auth_data.user_present()
+            || !auth_data.user_verified()
+        {
+            return Err(AppError::unauthorized());
+        }
+        let key = CoseKey::parse(&b64url_decode(&stored.public_key, "stored public key")?)
+            .map_err(|e| crate::log_and_convert!(AppError::database, "passkey.public_key", e))?;
+        if !key.verify(
+            &assertion_message(&auth_data_bytes, &client_data_json),
+            &signature,
+        ) {
+            return Err(AppError::unauthorized());
+        }
+        // Authenticators that keep no counter always report 0. Any other value must grow;
+        // a repeated or lower value means the credential was cloned.
+        if (auth_data.sign_count != 0 || stored.sign_count != 0)
+            && auth_data.sign_count <= stored.sign_count
+        {
+            tracing::warn!(
+                passkey_id = %stored.id,
+                stored = stored.sign_count,
+                received = auth_data.sign_count,
+                "passkey signature counter did not increase"
+            );
+            return Err(AppError::unauthorized());
+        }
+        // Two assertions racing past the check above cannot both store their counter.
+        if !self
+            .repo
+            .record_passkey_use(&stored.id, auth_data.sign_count)
+            .await?
+        {
+            tracing::warn!(
+                passkey_id = %stored.id,
+                received = auth_data.sign_count,
+                "passkey signature counter was advanced concurrently"
+            );
+            return Err(AppError::unauthorized());
+        }
+        Ok(stored.user)
+    }
+}
+
+/// Production type alias used in HTTP wiring.
+pub type PasskeyServiceHandle = PasskeyService<SurrealPasskeyRepo>;
+
+impl PasskeyServiceHandle {
+    pub fn build(db: Arc<Database>, config: WebauthnConfig) -> Self {
+        PasskeyService::new(SurrealPasskeyRepo::new(db), config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::api::ListQuery;
+    use shared::user::{RegisterPasskey, UpdatePasskey, User};
+
+    use crate::error::AppError;
+    use crate::resources::user::passkey::PasskeyRepository;
+    use crate::resources::user::passkey::PasskeyServiceHandle;
+    use crate::resources::user::passkey::soft_authenticator::SoftAuthenticator;
+    use crate::test_helpers::{create_user, passkey_service, test_db};
+
+    const RP_ID: &str = "localhost";
+    const ORIGIN: &str = "http://localhost:8080";
+
+    async fn register(
+        svc: &PasskeyServiceHandle,
+        user: &User,
+        name: &str,
+    ) -> (SoftAuthenticator, shared::user::Passkey) {
+        let mut authenticator = SoftAuthenticator::new(RP_ID, ORIGIN);
+        let options = svc
+            .registration_options_for_user(user)
+            .await
+            .expect("options");
+        let passkey = svc
+            .register_passkey_for_user(
+                user,
+                RegisterPasskey {
+                    name: name.into(),
+                    credential: authenticator.register(&options),
+                },
+            )
+            .await
+            .expect("register");
+        (authenticator, passkey)
+    }
+
+    /// BLC-PASSKEY-001: a user can hold several named passkeys; options exclude the ones
+    /// already registered.
+    #[tokio::test]
+    async fn blc_passkey_001_register_multiple_named_passkeys() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "passkey-001@test.local")
+            .await
+            .expect("user");
+        let svc = passkey_service(&db);
+        let (phone, first) = register(&svc, &user, " Phone ").await;
+        assert_eq!(first.name, "Phone");
+        assert!(first.last_used_at.is_none());
+        let (_, second) = register(&svc, &user, "Laptop").await;
+
+        let options = svc
+            .registration_options_for_user(&user)
+            .await
+            .expect("options");
+        assert_eq!(options.rp.id, RP_ID);
+        assert_eq!(options.exclude_credentials.len(), 2);
+        assert!(
+            options
+                .exclude_credentials
+                .iter()
+                .any(|c| c.id == super::b64url_encode(&phone.credential_id))
+        );
+
+        let (listed, total) = svc
+            .list_passkeys_for_user(&user, ListQuery::default())
+            .await
+            .expect("list");
+        assert_eq!(total, 2);
+        assert_eq!(listed[0].id, second.id, "newest first");
+    }
+
+    /// BLC-PASSKEY-002: a valid assertion returns the passkey's user and records the use;
+    /// each challenge works once.
+    #[tokio::test]
+    async fn blc_passkey_002_login_with_passkey() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "passkey-002@test.local")
+            .await
+            .expect("user");
+        let svc = passkey_service(&db);
+        let (mut authenticator, _) = register(&svc, &user, "Phone").await;
+
+        let options = svc.login_options().await.expect("options");
+        assert!(options.allow_credentials.is_empty());
+        let assertion = authenticator.login(&options);
+        let logged_in = svc.authenticate(assertion.clone()).await.expect("login");
+        assert_eq!(logged_in.id, user.id);
+        let r = svc.authenticate(assertion).await;
+        assert!(matches!(r, Err(AppError::Unauthorized)), "{r:?}");
+
+        let (listed, _) = svc
+            .list_passkeys_for_user(&user, ListQuery::default())
+            .await
+            .expect("list");
+        assert!(listed[0].last_used_at.is_some());
+
+        let options = svc.login_options().await.expect("options");
+        svc.authenticate(authenticator.login(&options))
+            .await
+            .expect("second login");
+    }
+
+    /// BLC-PASSKEY-003: responses for another origin or relying party, without user
+    /// verification, with a forged signature, or with a counter that went backwards are
+    /// rejected.
+    #[tokio::test]
+    async fn blc_passkey_003_rejects_untrusted_assertions() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "passkey-003@test.local")
+            .await
+            .expect("user");
+        let svc = passkey_service(&db);
+        let (mut authenticator, _) = register(&svc, &user, "Phone").await;
+
+        authenticator.origin = "https://evil.example".into();
+        let options = svc.login_options().await.expect("options");
+        let r = svc.authenticate(authenticator.login(&options)).await;
+        assert!(matches!(r, Err(AppError::Unauthorized)), "{r:?}");
+        authenticator.origin = ORIGIN.into();
+
+        authenticator.rp_id = "evil.example".into();
+        let options = svc.login_options().await.expect("options");
+        let r = svc.authenticate(authenticator.login(&options)).await;
+        assert!(matches!(r, Err(AppError::Unauthorized)), "{r:?}");
+        authenticator.rp_id = RP_ID.into();
+
+        authenticator.user_verified = false;
+        let options = svc.login_options().await.expect("options");
+        assert_eq!(options.user_verification, "required");
+        let r = svc.authenticate(authenticator.login(&options)).await;
+        assert!(matches!(r, Err(AppError::Unauthorized)), "{r:?}");
+        authenticator.user_verified = true;
+
+        let options = svc.login_options().await.expect("options");
+        let mut forged = authenticator.login(&options);
+        forged.response.signature = super::b64url_encode(&authenticator.sign(b"other"));
+        let r = svc.authenticate(forged).await;
+        assert!(matches!(r, Err(AppError::Unauthorized)), "{r:?}");
+
+        let options = svc.login_options().await.expect("options");
+        svc.authenticate(authenticator.login(&options))
+            .await
+            .expect("valid login");
+        authenticator.sign_count -= 2;
+        let options = svc.login_options().await.expect("options");
+        let r = svc.authenticate(authenticator.login(&options)).await;
+        assert!(
+            matches!(r, Err(AppError::Unauthorized)),
+            "cloned key: {r:?}"
+        );
+
+        let mut stranger = SoftAuthenticator::new(RP_ID, ORIGIN);
+        let options = svc.login_options().await.expect("options");
+        let r = svc.authenticate(stranger.login(&options)).await;
+        assert!(
+            matches!(r, Err(AppError::Unauthorized)),
+            "unknown passkey: {r:?}"
+        );
+    }
+
+    /// BLC-PASSKEY-003: the counter is stored only while it grows, so of two assertions
+    /// carrying the same counter at most one logs in, even when they race.
+    #[tokio::test]
+    async fn blc_passkey_003_counter_is_stored_only_when_it_grows() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "passkey-003-race@test.local")
+            .await
+            .expect("user");
+        let svc = passkey_service(&db);
+        let (mut authenticator, passkey) = register(&svc, &user, "Phone").await;
+
+        let first = svc.login_options().await.expect("options");
+        let second = svc.login_options().await.expect("options");
+        let a = authenticator.login(&first);
+        authenticator.sign_count -= 1;
+        let b = authenticator.login(&second);
+        let (a, b) = tokio::join!(svc.authenticate(a), svc.authenticate(b));
+        assert!(a.is_ok() != b.is_ok(), "{a:?} {b:?}");
+
+        assert!(svc.repo.record_passkey_use(&passkey.id, 5).await.unwrap());
+        assert!(!svc.repo.record_passkey_use(&passkey.id, 5).await.unwrap());
+        assert!(!svc.repo.record_passkey_use(&passkey.id, 4).await.unwrap());
+        assert!(svc.repo.record_passkey_use(&passkey.id, 6).await.unwrap());
+
+        let (_, counterless) = register(&svc, &user, "Key").await;
+        assert!(
+            svc.repo
+                .record_passkey_use(&counterless.id, 0)
+                .await
+                .unwrap()
+        );
+        assert!(
+            svc.repo
+                .record_passkey_use(&counterless.id, 0)
+                .await
+                .unwrap()
+        );
+    }
+
+    /// BLC-PASSKEY-004: registration only accepts a user-verified response to a registration
+    /// challenge issued to the same user, for this site, and each credential once.
+    #[tokio::test]
+    async fn blc_passkey_004_registration_checks() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "passkey-004@test.local")
+            .await
+            .expect("user");
+        let other = create_user(&db, "passkey-004-other@test.local")
+            .await
+            .expect("user");
+        let svc = passkey_service(&db);
+        let payload = |credential| RegisterPasskey {
+            name: "Key".into(),
+            credential,
+        };
+
+        let mut authenticator = SoftAuthenticator::new(RP_ID, ORIGIN);
+        let options = svc
+            .registration_options_for_user(&other)
+            .await
+            .expect("options");
+        let r = svc
+            .register_passkey_for_user(&user, payload(authenticator.register(&options)))
+            .await;
+        assert!(matches!(r, Err(AppError::InvalidRequest(_))), "{r:?}");
+
+        let login = svc.login_options().await.expect("options");
+        let mut options = svc
+            .registration_options_for_user(&user)
+            .await
+            .expect("options");
+        options.challenge = login.challenge;
+        let r = svc
+            .register_passkey_for_user(&user, payload(authenticator.register(&options)))
+            .await;
+        assert!(matches!(r, Err(AppError::InvalidRequest(_))), "{r:?}");
+
+        authenticator.origin = "https://evil.example".into();
+        let options = svc
+            .registration_options_for_user(&user)
+            .await
+            .expect("options");
+        let r = svc
+            .register_passkey_for_user(&user, payload(authenticator.register(&options)))
+            .await;
+        assert!(matches!(r, Err(AppError::InvalidRequest(_))), "{r:?}");
+        authenticator.origin = ORIGIN.into();
+
+        authenticator.user_verified = false;
+        let options = svc
+            .registration_options_for_user(&user)
+            .await
+            .expect("options");
+        assert_eq!(
+            options.authenticator_selection.user_verification,
+            "required"
+        );
+        let r = svc
+            .register_passkey_for_user(&user, payload(authenticator.register(&options)))
+            .await;
+        assert!(matches!(r, Err(AppError::InvalidRequest(_))), "{r:?}");
+        authenticator.user_verified = true;
+
+        let options = svc
+            .registration_options_for_user(&user)
+            .await
+            .expect("options");
+        let r = svc
+            .register_passkey_for_user(
+                &user,
+                RegisterPasskey {
+                    name: " ".into(),
+                    credential: authenticator.register(&options),
+                },
+            )
+            .await;
+        assert!(matches!(r, Err(AppError::InvalidRequest(_))), "{r:?}");
+
+        let options = svc
+            .registration_options_for_user(&user)
+            .await
+            .expect("options");
+        svc.register_passkey_for_user(&user, payload(authenticator.register(&options)))
+            .await
+            .expect("register");
+        let options = svc
+            .registration_options_for_user(&other)
+            .await
+            .expect("options");
+        let r = svc
+            .register_passkey_for_user(&other, payload(authenticator.register(&options)))
+            .await;
+        assert!(matches!(r, Err(AppError::Conflict(_))), "{r:?}");
+    }
+
+    /// BLC-PASSKEY-005: users only rename and remove their own passkeys; a removed passkey
+    /// can no longer log in.
+    #[tokio::test]
+    async fn blc_passkey_005_owner_only_management() {
+        let db = test_db().await.expect("db");
+        let user = create_user(&db, "passkey-005@test.local")
+            .await
+            .expect("user");
+        let other = create_user(&db, "passkey-005-other@test.local")
+            .await
+            .expect("user");
+        let svc = passkey_service(&db);
+        let (mut authenticator, passkey) = register(&svc, &user, "Phone").await;
+
+        let rename = UpdatePasskey {
+            name: "Stolen".into(),
+        };
+        let r = svc
+            .update_passkey_for_user(&other, &passkey.id, rename)
+            .await;
+        assert!(matches!(r, Err(AppError::NotFound(_))), "{r:?}");
+        let r = svc.delete_passkey_for_user(&other, &passkey.id).await;
+        assert!(matches!(r, Err(AppError::NotFound(_))), "{r:?}");
+
+        let renamed = svc
+            .update_passkey_for_user(
+                &user,
+                &passkey.id,
+                UpdatePasskey {
+                    name: "Tablet".into(),
+                },
+            )
+            .await
+            .expect("rename");
+        assert_eq!(renamed.name, "Tablet");
+
+        svc.delete_passkey_for_user(&user, &passkey.id)
+            .await
+            .expect("delete");
+        let options = svc.login_options().await.expect("options");
+        let r = svc.authenticate(authenticator.login(&options)).await;
+        assert!(matches!(r, Err(AppError::Unauthorized)), "{r:?}");
+    }
+}
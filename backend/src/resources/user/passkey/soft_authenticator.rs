@@ -0,0 +1,152 @@
+//! In-process ES256 authenticator for tests: produces the same JSON a browser would send
+//! after `navigator.credentials.create` / `get`.
+
+use ciborium::Value;
+use ring::rand::SystemRandom;
+use ring::signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair, KeyPair};
+
+use shared::auth::passkey::{
+    AssertionResponse, AttestationResponse, AuthenticationCredential, PasskeyCreationOptions,
+    PasskeyRequestOptions, RegistrationCredential,
+};
+
+use super::webauthn::{
+    CLIENT_DATA_CREATE, CLIENT_DATA_GET, FLAG_ATTESTED_CREDENTIAL, FLAG_USER_PRESENT,
+    FLAG_USER_VERIFIED, b64url_encode, sha256,
+};
+
+pub struct SoftAuthenticator {
+    pub rp_id: String,
+    /// Origin written into client data; change it to simulate a phishing page.
+    pub origin: String,
+    pub credential_id: Vec<u8>,
+    /// Counter sent with the next assertion is `sign_count + 1`.
+    pub sign_count: u32,
+    /// Set by [`Self::register`]; sent back as `user_handle`.
+    pub user_handle: Option<String>,
+    /// Whether responses carry the user-verified flag; clear it to simulate a bare touch.
+    pub user_verified: bool,
+    key: EcdsaKeyPair,
+    rng: SystemRandom,
+}
+
+impl SoftAuthenticator {
+    pub fn new(rp_id: &str, origin: &str) -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+            .expect("generate key");
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+            .expect("load key");
+        let credential_id = sha256(pkcs8.as_ref())[..16].to_vec();
+        Self {
+            rp_id: rp_id.into(),
+            origin: origin.into(),
+            credential_id,
+            sign_count: 0,
+            user_handle: None,
+            user_verified: true,
+            key,
+            rng,
+        }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.key
+            .sign(&self.rng, message)
+            .expect("sign")
+            .as_ref()
+            .to_vec()
+    }
+
+    fn cose_key(&self) -> Vec<u8> {
+        let point = self.key.public_key().as_ref();
+        let mut out = Vec::new();
+        ciborium::into_writer(
+            &Value::Map(vec![
+                (Value::from(1), Value::from(2)),
+                (Value::from(3), Value::from(-7)),
+                (Value::from(-1), Value::from(1)),
+                (Value::from(-2), Value::Bytes(point[1..33].to_vec())),
+                (Value::from(-3), Value::Bytes(point[33..].to_vec())),
+            ]),
+            &mut out,
+        )
+        .expect("encode cose key");
+        out
+    }
+
+    fn client_data(&self, kind: &str, challenge: &str) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "type": kind,
+            "challenge": challenge,
+            "origin": self.origin,
+            "crossOrigin": false
+        }))
+        .expect("client data")
+    }
+
+    fn flags(&self) -> u8 {
+        if self.user_verified {
+            FLAG_USER_PRESENT | FLAG_USER_VERIFIED
+        } else {
+            FLAG_USER_PRESENT
+        }
+    }
+
+    pub fn registration_auth_data(&self) -> Vec<u8> {
+        let mut data = sha256(self.rp_id.as_bytes());
+        data.push(self.flags() | FLAG_ATTESTED_CREDENTIAL);
+        data.extend_from_slice(&self.sign_count.to_be_bytes());
+        data.extend_from_slice(&[0u8; 16]);
+        data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+        data.extend_from_slice(&self.credential_id);
+        data.extend_from_slice(&self.cose_key());
+        data
+    }
+
+    pub fn register(&mut self, options: &PasskeyCreationOptions) -> RegistrationCredential {
+        self.user_handle = Some(options.user.id.clone());
+        let mut attestation_object = Vec::new();
+        ciborium::into_writer(
+            &Value::Map(vec![
+                (Value::from("fmt"), Value::from("none")),
+                (Value::from("attStmt"), Value::Map(vec![])),
+                (
+                    Value::from("authData"),
+                    Value::Bytes(self.registration_auth_data()),
+                ),
+            ]),
+            &mut attestation_object,
+        )
+        .expect("encode attestation object");
+        RegistrationCredential {
+            id: b64url_encode(&self.credential_id),
+            kind: "public-key".into(),
+            response: AttestationResponse {
+                client_data_json: b64url_encode(
+                    &self.client_data(CLIENT_DATA_CREATE, &options.challenge),
+                ),
+                attestation_object: b64url_encode(&attestation_object),
+            },
+        }
+    }
+
+    pub fn login(&mut self, options: &PasskeyRequestOptions) -> AuthenticationCredential {
+        self.sign_count += 1;
+        let mut auth_data = sha256(self.rp_id.as_bytes());
+        auth_data.push(self.flags());
+        auth_data.extend_from_slice(&self.sign_count.to_be_bytes());
+        let client_data = self.client_data(CLIENT_DATA_GET, &options.challenge);
+        let message = [&auth_data[..], &sha256(&client_data)].concat();
+        AuthenticationCredential {
+            id: b64url_encode(&self.credential_id),
+            kind: "public-key".into(),
+            response: AssertionResponse {
+                client_data_json: b64url_encode(&client_data),
+                authenticator_data: b64url_encode(&auth_data),
+                signature: b64url_encode(&self.sign(&message)),
+                user_handle: self.user_handle.clone(),
+            },
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use surrealdb::types::RecordId;
+
+use shared::user::Passkey;
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::model::{
+    ChallengePurpose, ChallengeRecord, PasskeyCreateRecord, PasskeyCredential,
+    PasskeyCredentialRecord, PasskeyRecord,
+};
+use super::repository::PasskeyRepository;
+
+#[derive(Clone)]
+pub struct SurrealPasskeyRepo {
+    db: Arc<Database>,
+}
+
+impl SurrealPasskeyRepo {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[async_trait]
+impl PasskeyRepository for SurrealPasskeyRepo {
+    async fn create_challenge(
+        &self,
+        challenge: &str,
+        purpose: ChallengePurpose,
+        user_id: Option<&str>,
+        ttl_seconds: u64,
+    ) -> Result<(), AppError> {
+        self.inner()
+            .query(
+                r#"
+                DELETE webauthn_challenge WHERE expires_at <= time::now();
+                CREATE type::record('webauthn_challenge', $challenge) CONTENT {
+                  purpose: $purpose,
+                  user: $user,
+                  expires_at: time::now() + duration::from_secs($ttl_secs)
+                } RETURN NONE;
+                "#,
+            )
+            .bind(("challenge", challenge.to_owned()))
+            .bind(("purpose", purpose.as_str()))
+            .bind((
+                "user",
+                user_id.map(|id| RecordId::new("user", id.to_owned())),
+            ))
+            .bind(("ttl_secs", ttl_seconds as i64))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "passkey.challenge.query", e))?
+            .check()
+            .map_err(|e| {
+                crate::log_and_convert!(AppError::database, "passkey.challenge.create", e)
+            })?;
+        Ok(())
+    }
+
+    async fn take_challenge(
+        &self,
+        challenge: &str,
+        purpose: ChallengePurpose,
+    ) -> Result<Option<ChallengeRecord>, AppError> {
+        self.inner()
+            .query(
+                r#"
+                DELETE type::record('webauthn_challenge', $challenge)
+                    WHERE purpose = $purpose AND expires_at > time::now()
+                    RETURN BEFORE;
+                "#,
+            )
+            .bind(("challenge", challenge.to_owned()))
+            .bind(("purpose", purpose.as_str()))
+            .await
+            .map_err(|e| crate::log_and_convert!(AppError::database, "passkey.challenge.take", e))?
+            .take::<Option<ChallengeRecord>>(0)
+            .map_err(|e| crate::log_and_convert!(AppError::database, "passkey.challenge.take", e))
+    }
+
+    async fn create_passkey(&self, create: PasskeyCreateRecord) -> Result<Passkey, AppError> {
+        Ok(self
+            .inner()
+            .query("CREATE passkey CONTENT $create RETURN AFTER")
+            .bind(("create", create))
+            .await?
+            .take::<Option<PasskeyRecord>>(0)?
+            .ok_or_else(|| AppError::database("failed to create passkey"))?
+            .into_passkey())
+    }
+
+    async fn get_passkeys_by_user_id(&self, user_id: &str) -> Result<Vec<Passkey>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM passkey WHERE user = $user ORDER BY created_at DESC")
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .await?
+            .take::<Vec<PasskeyRecord>>(0)?
+            .into_iter()
+            .map(PasskeyRecord::into_passkey)
+            .collect())
+    }
+
+    async fn get_credential_ids_by_user_id(&self, user_id: &str) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT VALUE credential_id FROM passkey WHERE user = $user")
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .await?
+            .take::<Vec<String>>(0)?)
+    }
+
+    async fn update_passkey_name_for_user(
+        &self,
+        id: &str,
+        user_id: &str,
+        name: &str,
+    ) -> Result<Passkey, AppError> {
+        Ok(self
+            .inner()
+            .query("UPDATE passkey SET name = $name WHERE id = $id AND user = $user RETURN AFTER")
+            .bind(("id", RecordId::new("passkey", id.to_owned())))
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .bind(("name", name.to_owned()))
+            .await?
+            .take::<Option<PasskeyRecord>>(0)?
+            .ok_or_else(|| AppError::NotFound("passkey not found".into()))?
+            .into_passkey())
+    }
+
+    async fn delete_passkey_for_user(&self, id: &str, user_id: &str) -> Result<Passkey, AppError> {
+        Ok(self
+            .inner()
+            .query("DELETE passkey WHERE id = $id AND user = $user RETURN BEFORE")
+            .bind(("id", RecordId::new("passkey", id.to_owned())))
+            .bind(("user", RecordId::new("user", user_id.to_owned())))
+            .await?
+            .take::<Option<PasskeyRecord>>(0)?
+            .ok_or_else(|| AppError::NotFound("passkey not found".into()))?
+            .into_passkey())
+    }
+
+    async fn get_credential(
+        &self,
+        credential_id: &str,
+    ) -> Result<Option<PasskeyCredential>, AppError> {
+        Ok(self
+            .inner()
+            .query("SELECT * FROM passkey WHERE credential_id = $credential_id FETCH user")
+            .bind(("credential_id", credential_id.to_owned()))
+            .await?
+            .take::<Option<PasskeyCredentialRecord>>(0)?
+            .map(PasskeyCredentialRecord::into_credential))
+    }
+
+    async fn record_passkey_use(&self, id: &str, sign_count: u32) -> Result<bool, AppError> {
+        let updated: Vec<RecordId> = self
+            .inner()
+            .query(
+                "UPDATE $id SET sign_count = $sign_count, last_used_at = time::now() \
+                 WHERE sign_count < $sign_count OR (sign_count = 0 AND $sign_count = 0) \
+                 RETURN VALUE id",
+            )
+            .bind(("id", RecordId::new("passkey", id.to_owned())))
+            .bind(("sign_count", sign_count as i64))
+            .await?
+            .take(0)?;
+        Ok(!updated.is_empty())
+    }
+}
@@ -0,0 +1,255 @@
+//! The parts of WebAuthn Level 2 the server checks: client data, authenticator data, and
+//! COSE public keys. Attestation statements are not verified (`attestation: "none"`), so a
+//! passkey proves possession of its key, not the make of the authenticator.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ciborium::Value;
+use ring::digest;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+
+use crate::error::AppError;
+
+pub const FLAG_USER_PRESENT: u8 = 0x01;
+pub const FLAG_USER_VERIFIED: u8 = 0x04;
+pub const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+pub const CLIENT_DATA_CREATE: &str = "webauthn.create";
+pub const CLIENT_DATA_GET: &str = "webauthn.get";
+
+pub fn b64url_encode(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Browsers send unpadded base64url; tolerate padding from hand-written clients.
+pub fn b64url_decode(value: &str, what: &str) -> Result<Vec<u8>, AppError> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| AppError::invalid_request(format!("{what} is not valid base64url")))
+}
+
+/// 32 random bytes, base64url encoded.
+pub fn new_challenge() -> Result<String, AppError> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Internal("failed to generate webauthn challenge".into()))?;
+    Ok(b64url_encode(&bytes))
+}
+
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    digest::digest(&digest::SHA256, data).as_ref().to_vec()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub challenge: String,
+    pub origin: String,
+}
+
+pub fn parse_client_data(raw: &[u8]) -> Result<ClientData, AppError> {
+    serde_json::from_slice(raw)
+        .map_err(|_| AppError::invalid_request("client_data_json is not valid client data"))
+}
+
+#[derive(Debug)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE_Key bytes exactly as the authenticator encoded them.
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: Vec<u8>,
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    pub fn parse(bytes: &[u8]) -> Result<Self, AppError> {
+        let malformed = || AppError::invalid_request("authenticator data is malformed");
+        if bytes.len() < 37 {
+            return Err(malformed());
+        }
+        let flags = bytes[32];
+        let sign_count = u32::from_be_bytes(bytes[33..37].try_into().map_err(|_| malformed())?);
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            // aaguid (16) | credential id length (2) | credential id | COSE key
+            let rest = bytes.get(37 + 16..).ok_or_else(malformed)?;
+            let len = u16::from_be_bytes(rest.get(..2).ok_or_else(malformed)?.try_into().unwrap())
+                as usize;
+            let credential_id = rest.get(2..2 + len).ok_or_else(malformed)?.to_vec();
+            let mut key_bytes = &rest[2 + len..];
+            let before = key_bytes.len();
+            let _: Value = ciborium::from_reader(&mut key_bytes).map_err(|_| malformed())?;
+            let key_len = before - key_bytes.len();
+            Some(AttestedCredential {
+                credential_id,
+                public_key: rest[2 + len..2 + len + key_len].to_vec(),
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            rp_id_hash: bytes[..32].to_vec(),
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+
+    /// The authenticator checked a PIN or biometric, not just a touch.
+    pub fn user_verified(&self) -> bool {
+        self.flags & FLAG_USER_VERIFIED != 0
+    }
+
+    pub fn matches_rp(&self, rp_id: &str) -> bool {
+        self.rp_id_hash == sha256(rp_id.as_bytes())
+    }
+}
+
+/// Extracts `authData` from a CBOR attestation object; `fmt` and `attStmt` are ignored.
+pub fn attestation_auth_data(attestation_object: &[u8]) -> Result<Vec<u8>, AppError> {
+    let malformed = || AppError::invalid_request("attestation_object is malformed");
+    let value: Value = ciborium::from_reader(attestation_object).map_err(|_| malformed())?;
+    value
+        .as_map()
+        .and_then(|entries| {
+            entries
+                .iter()
+                .find(|(key, _)| key.as_text() == Some("authData"))
+        })
+        .and_then(|(_, data)| data.as_bytes().cloned())
+        .ok_or_else(malformed)
+}
+
+#[derive(Debug)]
+pub enum CoseKey {
+    /// ES256 on P-256; stored as the uncompressed SEC1 point.
+    Es256(Vec<u8>),
+    EdDsa(Vec<u8>),
+    Rs256 {
+        n: Vec<u8>,
+        e: Vec<u8>,
+    },
+}
+
+impl CoseKey {
+    pub fn parse(bytes: &[u8]) -> Result<Self, AppError> {
+        let unsupported =
+            || AppError::invalid_request("unsupported or malformed passkey public key");
+        let value: Value = ciborium::from_reader(bytes).map_err(|_| unsupported())?;
+        let entries = value.as_map().ok_or_else(unsupported)?;
+        let label = |label: i64| {
+            entries.iter().find_map(|(key, value)| {
+                let key = i128::from(key.as_integer()?);
+                (key == label as i128).then_some(value)
+            })
+        };
+        let int = |l: i64| label(l).and_then(Value::as_integer).map(i128::from);
+        let bytes = |l: i64| label(l).and_then(Value::as_bytes).cloned();
+
+        match (int(1), int(3)) {
+            // kty EC2, alg ES256, crv P-256
+            (Some(2), Some(-7)) if int(-1) == Some(1) => {
+                let (x, y) = (
+                    bytes(-2).ok_or_else(unsupported)?,
+                    bytes(-3).ok_or_else(unsupported)?,
+                );
+                if x.len() != 32 || y.len() != 32 {
+                    return Err(unsupported());
+                }
+                Ok(Self::Es256([&[0x04][..], &x, &y].concat()))
+            }
+            // kty OKP, alg EdDSA, crv Ed25519
+            (Some(1), Some(-8)) if int(-1) == Some(6) => {
+                let x = bytes(-2).ok_or_else(unsupported)?;
+                if x.len() != 32 {
+                    return Err(unsupported());
+                }
+                Ok(Self::EdDsa(x))
+            }
+            // kty RSA, alg RS256
+            (Some(3), Some(-257)) => Ok(Self::Rs256 {
+                n: bytes(-1).ok_or_else(unsupported)?,
+                e: bytes(-2).ok_or_else(unsupported)?,
+            }),
+            _ => Err(unsupported()),
+        }
+    }
+
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Es256(point) => UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                .verify(message, signature)
+                .is_ok(),
+            Self::EdDsa(x) => UnparsedPublicKey::new(&signature::ED25519, x)
+                .verify(message, signature)
+                .is_ok(),
+            Self::Rs256 { n, e } => RsaPublicKeyComponents { n, e }
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, signature)
+                .is_ok(),
+        }
+    }
+}
+
+/// Signed data of an assertion: `authenticatorData || SHA-256(clientDataJSON)`.
+pub fn assertion_message(authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+    [authenticator_data, &sha256(client_data_json)].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::user::passkey::soft_authenticator::SoftAuthenticator;
+
+    #[test]
+    fn parses_attested_credential_and_verifies_signatures() {
+        let authenticator = SoftAuthenticator::new("localhost", "http://localhost:8080");
+        let data = AuthenticatorData::parse(&authenticator.registration_auth_data()).unwrap();
+        assert!(data.user_present());
+        assert!(data.user_verified());
+        assert!(data.matches_rp("localhost"));
+        assert!(!data.matches_rp("example.com"));
+        let attested = data.attested_credential.expect("attested credential");
+        assert_eq!(attested.credential_id, authenticator.credential_id);
+
+        let key = CoseKey::parse(&attested.public_key).unwrap();
+        let message = assertion_message(b"auth-data", b"{}");
+        assert!(key.verify(&message, &authenticator.sign(&message)));
+        assert!(!key.verify(b"other message", &authenticator.sign(&message)));
+    }
+
+    #[test]
+    fn rejects_truncated_and_unsupported_input() {
+        assert!(AuthenticatorData::parse(&[0u8; 36]).is_err());
+        let mut truncated = vec![0u8; 37];
+        truncated[32] = FLAG_ATTESTED_CREDENTIAL;
+        assert!(AuthenticatorData::parse(&truncated).is_err());
+
+        // kty EC2 with alg ES384
+        let mut es384 = Vec::new();
+        ciborium::into_writer(
+            &Value::Map(vec![
+                (Value::from(1), Value::from(2)),
+                (Value::from(3), Value::from(-35)),
+            ]),
+            &mut es384,
+        )
+        .unwrap();
+        assert!(CoseKey::parse(&es384).is_err());
+        assert!(attestation_auth_data(b"not cbor").is_err());
+        assert!(b64url_decode("***", "id").is_err());
+        assert_eq!(b64url_decode("YQ==", "id").unwrap(), b"a");
+    }
+}
@@ -1,5 +1,7 @@
 use async_trait::async_trait;
 
+use surrealdb::types::Datetime;
+
 use shared::api::ListQuery;
 use shared::user::User;
 
@@ -34,4 +36,17 @@ pub trait UserRepository: Send + Sync {
         user_id: &str,
         avatar_blob_id: Option<&str>,
     ) -> Result<(), AppError>;
+
+    /// Replace the login email; a taken address is a conflict.
+    async fn set_email(&self, user_id: &str, email: &str) -> Result<User, AppError>;
+
+    /// Set or clear the time a self-service deletion removes the account.
+    async fn set_deletion_scheduled_at(
+        &self,
+        user_id: &str,
+        at: Option<Datetime>,
+    ) -> Result<User, AppError>;
+
+    /// Users whose scheduled deletion is at or before `now`.
+    async fn users_due_for_deletion(&self, now: Datetime) -> Result<Vec<User>, AppError>;
 }
@@ -1,10 +1,11 @@
-use super::{CreateUser, User, session};
+use super::{CreateUser, User, account, api_token, identity, passkey, session};
 use crate::auth::middleware::RequireAdmin;
 #[allow(unused_imports)]
 use crate::docs::Problem;
 use crate::error::AppError;
 use crate::resources::blob::service::BlobServiceHandle;
 use crate::resources::user::service::UserServiceHandle;
+use crate::resources::{job, monitoring, team};
 use crate::settings::ProfilePictureLimits;
 use actix_web::http::header;
 use actix_web::{
@@ -16,16 +17,35 @@ use shared::api::{ListQuery, PAGE_SIZE_DEFAULT};
 pub fn scope(avatar_upload_max_bytes: usize) -> Scope {
     web::scope("/users")
         .service(get_users_me)
+        .service(account::rest::patch_users_me)
+        .service(account::rest::delete_users_me)
+        .service(account::rest::export_users_me)
+        .service(account::rest::cancel_users_me_deletion)
         .service(
             web::resource("/me/profile-picture")
                 .app_data(web::PayloadConfig::new(avatar_upload_max_bytes))
                 .route(web::put().to(put_profile_picture))
                 .route(web::delete().to(delete_profile_picture)),
         )
+        .service(team::activity::rest::get_notification_preferences)
+        .service(team::activity::rest::put_notification_preferences)
         .service(session::rest::get_current_session_for_user)
         .service(session::rest::get_sessions_for_current_user)
         .service(session::rest::get_session_for_current_user)
         .service(session::rest::delete_session_for_current_user)
+        .service(api_token::rest::create_api_token_for_current_user)
+        .service(api_token::rest::get_api_tokens_for_current_user)
+        .service(api_token::rest::delete_api_token_for_current_user)
+        .service(job::rest::get_jobs_for_current_user)
+        .service(monitoring::rest::get_security_events_for_current_user)
+        .service(passkey::rest::create_passkey_options_for_current_user)
+        .service(passkey::rest::create_passkey_for_current_user)
+        .service(passkey::rest::get_passkeys_for_current_user)
+        .service(passkey::rest::update_passkey_for_current_user)
+        .service(passkey::rest::delete_passkey_for_current_user)
+        .service(identity::rest::get_identities_for_current_user)
+        .service(identity::rest::create_identity_link_for_current_user)
+        .service(identity::rest::delete_identity_for_current_user)
         .service(
             web::scope("")
                 .wrap(RequireAdmin)
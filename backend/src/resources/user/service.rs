@@ -60,6 +60,7 @@ impl<R: UserRepository, T: TeamRepository> UserService<R, T> {
                 name: "Personal".to_owned(),
                 owner: Some(user_thing(&created.id)),
                 members: vec![],
+                organization: None,
             })
             .await?;
         crate::audit!(
@@ -290,7 +291,7 @@ mod tests {
     use std::sync::{Arc, Mutex};
 
     use async_trait::async_trait;
-    use surrealdb::types::RecordId;
+    use surrealdb::types::{Datetime, RecordId};
 
     use shared::api::ListQuery;
     use shared::team::Team;
@@ -299,7 +300,7 @@ mod tests {
     use crate::database::record_id_string;
     use crate::error::AppError;
     use crate::resources::team::repository::TeamRepository;
-    use crate::resources::team::{DbTeamMember, TeamCreatePayload, TeamFetched};
+    use crate::resources::team::{DbTeamMember, DbTeamRole, TeamCreatePayload, TeamFetched};
     use crate::resources::user::repository::UserRepository;
 
     use super::UserService;
@@ -399,6 +400,22 @@ mod tests {
         ) -> Result<(), AppError> {
             unreachable!("not used in these tests")
         }
+
+        async fn set_email(&self, _user_id: &str, _email: &str) -> Result<User, AppError> {
+            unreachable!("not used in these tests")
+        }
+
+        async fn set_deletion_scheduled_at(
+            &self,
+            _user_id: &str,
+            _at: Option<Datetime>,
+        ) -> Result<User, AppError> {
+            unreachable!("not used in these tests")
+        }
+
+        async fn users_due_for_deletion(&self, _now: Datetime) -> Result<Vec<User>, AppError> {
+            unreachable!("not used in these tests")
+        }
     }
 
     // ── MockTeamRepo ──────────────────────────────────────────────────────────
@@ -454,6 +471,14 @@ mod tests {
             unreachable!("not used in user tests")
         }
 
+        async fn update_team_roles(
+            &self,
+            _resource: (String, String),
+            _roles: Vec<DbTeamRole>,
+        ) -> Result<(), AppError> {
+            unreachable!("not used in user tests")
+        }
+
         async fn delete_team_record(&self, _resource: (String, String)) -> Result<(), AppError> {
             unreachable!("not used in user tests")
         }
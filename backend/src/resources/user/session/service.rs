@@ -288,6 +288,25 @@ mod tests {
         ) -> Result<(), AppError> {
             unreachable!("not used in session tests")
         }
+
+        async fn set_email(&self, _user_id: &str, _email: &str) -> Result<User, AppError> {
+            unreachable!("not used in session tests")
+        }
+
+        async fn set_deletion_scheduled_at(
+            &self,
+            _user_id: &str,
+            _at: Option<surrealdb::types::Datetime>,
+        ) -> Result<User, AppError> {
+            unreachable!("not used in session tests")
+        }
+
+        async fn users_due_for_deletion(
+            &self,
+            _now: surrealdb::types::Datetime,
+        ) -> Result<Vec<User>, AppError> {
+            unreachable!("not used in session tests")
+        }
     }
 
     // ── Slice 2E: session scoping ─────────────────────────────────────────────
@@ -30,7 +30,6 @@ impl SurrealSessionRepo {
 impl SessionRepository for SurrealSessionRepo {
     async fn get_session(&self, id: &str) -> Result<Session, AppError> {
         self.inner()
-            .db
             .query("SELECT * FROM session WHERE id = $id FETCH user")
             .bind(("id", RecordId::new("session", id.to_string())))
             .await?
@@ -41,7 +40,6 @@ impl SessionRepository for SurrealSessionRepo {
 
     async fn get_session_for_user(&self, id: &str, user_id: &str) -> Result<Session, AppError> {
         self.inner()
-            .db
             .query("SELECT * FROM session WHERE id = $id AND user = $user FETCH user")
             .bind(("id", RecordId::new("session", id.to_string())))
             .bind(("user", RecordId::new("user", user_id.to_owned())))
@@ -54,7 +52,6 @@ impl SessionRepository for SurrealSessionRepo {
     async fn create_session(&self, session: Session) -> Result<Session, AppError> {
         let record: SessionIdRecord = self
             .inner()
-            .db
             .create(("session", session.id.clone()))
             .content(SessionCreateRecord::from_session(session))
             .await?
@@ -65,20 +62,19 @@ impl SessionRepository for SurrealSessionRepo {
 
     async fn delete_session(&self, id: &str) -> Result<Session, AppError> {
         let session = self.get_session(id).await?;
-        let _: Option<SessionIdRecord> = self.inner().db.delete(("session", id)).await?;
+        let _: Option<SessionIdRecord> = self.inner().delete(("session", id)).await?;
         Ok(session)
     }
 
     async fn delete_session_for_user(&self, id: &str, user_id: &str) -> Result<Session, AppError> {
         let session = self.get_session_for_user(id, user_id).await?;
-        let _: Option<SessionIdRecord> = self.inner().db.delete(("session", id)).await?;
+        let _: Option<SessionIdRecord> = self.inner().delete(("session", id)).await?;
         Ok(session)
     }
 
     async fn get_sessions_by_user_id(&self, user_id: &str) -> Result<Vec<Session>, AppError> {
         Ok(self
             .inner()
-            .db
             .query("SELECT * FROM session WHERE user = $user FETCH user")
             .bind(("user", RecordId::new("user", user_id.to_owned())))
             .await?
@@ -94,7 +90,6 @@ impl SessionRepository for SurrealSessionRepo {
     ) -> Result<Option<Session>, AppError> {
         let raw = self
             .inner()
-            .db
             .query(
                 r#"
             LET $sid = type::record("session", $id);
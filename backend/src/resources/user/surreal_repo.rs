@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use surrealdb::types::RecordId;
+use surrealdb::types::{Datetime, RecordId};
 
 use serde::Deserialize;
 use surrealdb::types::SurrealValue;
@@ -44,7 +44,6 @@ impl UserRepository for SurrealUserRepo {
         });
         let mut response = if let Some(needle) = needle {
             self.inner()
-                .db
                 .query(
                     "SELECT * FROM user WHERE string::contains(string::lowercase(email), $needle) \
                      OR string::contains(string::lowercase(type::string(id)), $needle) \
@@ -56,7 +55,6 @@ impl UserRepository for SurrealUserRepo {
                 .await?
         } else {
             self.inner()
-                .db
                 .query("SELECT * FROM user LIMIT $limit START $start")
                 .bind(("limit", limit))
                 .bind(("start", offset))
@@ -84,7 +82,6 @@ impl UserRepository for SurrealUserRepo {
         });
         let mut response = if let Some(needle) = needle {
             self.inner()
-                .db
                 .query(
                     "SELECT count() FROM user WHERE string::contains(string::lowercase(email), $needle) \
                      OR string::contains(string::lowercase(type::string(id)), $needle) GROUP ALL",
@@ -93,7 +90,6 @@ impl UserRepository for SurrealUserRepo {
                 .await?
         } else {
             self.inner()
-                .db
                 .query("SELECT count() FROM user GROUP ALL")
                 .await?
         };
@@ -107,7 +103,6 @@ impl UserRepository for SurrealUserRepo {
 
     async fn get_user(&self, id: &str) -> Result<User, AppError> {
         self.inner()
-            .db
             .select(user_resource(id)?)
             .await?
             .map(UserRecord::into_user)
@@ -117,7 +112,6 @@ impl UserRepository for SurrealUserRepo {
     async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, AppError> {
         Ok(self
             .inner()
-            .db
             .query("SELECT * FROM user WHERE email = $email LIMIT 1")
             .bind(("email", email.to_lowercase()))
             .await?
@@ -127,7 +121,6 @@ impl UserRepository for SurrealUserRepo {
 
     async fn create_user_record(&self, user: User) -> Result<User, AppError> {
         self.inner()
-            .db
             .create("user")
             .content(UserRecord::from_user(user))
             .await?
@@ -137,7 +130,6 @@ impl UserRepository for SurrealUserRepo {
 
     async fn delete_user(&self, id: &str) -> Result<User, AppError> {
         self.inner()
-            .db
             .delete(user_resource(id)?)
             .await?
             .map(UserRecord::into_user)
@@ -151,7 +143,6 @@ impl UserRepository for SurrealUserRepo {
     ) -> Result<(), AppError> {
         let mut response = self
             .inner()
-            .db
             .query("UPDATE $user SET default_collection = $collection")
             .bind(("user", RecordId::new("user", user_id)))
             .bind(("collection", RecordId::new("collection", collection_id)))
@@ -171,7 +162,6 @@ impl UserRepository for SurrealUserRepo {
     ) -> Result<(), AppError> {
         let mut response = self
             .inner()
-            .db
             .query("UPDATE $user SET oauth_picture_url = $url, oauth_avatar_blob = $blob_ref")
             .bind(("user", RecordId::new("user", user_id)))
             .bind(("url", picture_url.to_owned()))
@@ -198,14 +188,12 @@ impl UserRepository for SurrealUserRepo {
     ) -> Result<(), AppError> {
         let mut response = if let Some(bid) = avatar_blob_id {
             self.inner()
-                .db
                 .query("UPDATE $user SET avatar_blob = $blob_ref")
                 .bind(("user", RecordId::new("user", user_id)))
                 .bind(("blob_ref", RecordId::new("blob", bid)))
                 .await?
         } else {
             self.inner()
-                .db
                 .query("UPDATE $user SET avatar_blob = NONE")
                 .bind(("user", RecordId::new("user", user_id)))
                 .await?
@@ -216,13 +204,52 @@ impl UserRepository for SurrealUserRepo {
         })?;
         Ok(())
     }
+    async fn set_email(&self, user_id: &str, email: &str) -> Result<User, AppError> {
+        self.inner()
+            .query("UPDATE $user SET email = $email RETURN AFTER")
+            .bind(("user", RecordId::new("user", user_id)))
+            .bind(("email", email.to_owned()))
+            .await?
+            .take::<Option<UserRecord>>(0)?
+            .map(UserRecord::into_user)
+            .ok_or(AppError::NotFound("user not found".into()))
+    }
+
+    async fn set_deletion_scheduled_at(
+        &self,
+        user_id: &str,
+        at: Option<Datetime>,
+    ) -> Result<User, AppError> {
+        self.inner()
+            .query("UPDATE $user SET deletion_scheduled_at = $at RETURN AFTER")
+            .bind(("user", RecordId::new("user", user_id)))
+            .bind(("at", at))
+            .await?
+            .take::<Option<UserRecord>>(0)?
+            .map(UserRecord::into_user)
+            .ok_or(AppError::NotFound("user not found".into()))
+    }
+
+    async fn users_due_for_deletion(&self, now: Datetime) -> Result<Vec<User>, AppError> {
+        Ok(self
+            .inner()
+            .query(
+                "SELECT * FROM user WHERE deletion_scheduled_at != NONE \
+                 AND deletion_scheduled_at <= $now ORDER BY deletion_scheduled_at",
+            )
+            .bind(("now", now))
+            .await?
+            .take::<Vec<UserRecord>>(0)?
+            .into_iter()
+            .map(UserRecord::into_user)
+            .collect())
+    }
 }
 
 impl SurrealUserRepo {
     pub async fn clear_default_collection(&self, user_id: &str) -> Result<(), AppError> {
         let mut response = self
             .inner()
-            .db
             .query("UPDATE $user SET default_collection = NONE")
             .bind(("user", RecordId::new("user", user_id)))
             .await?;
@@ -0,0 +1,170 @@
+//! Persists [`crate::audit!`] events raised while handling a request into `security_event`.
+//!
+//! [`crate::http_audit::HttpAudit`] runs each request inside [`collect`]; every audit event the
+//! handler emits is buffered in a task-local and written once the response is ready, with the
+//! request id, client address and authenticated user of that request.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::future::Future;
+
+use crate::database::Database;
+
+tokio::task_local! {
+    static CAPTURED: RefCell<Vec<CapturedEvent>>;
+}
+
+/// High-volume events already covered by `http_request_audit` (status 429) are not persisted.
+const SKIPPED_EVENT_PREFIXES: &[&str] = &["audit.rate_limit."];
+
+/// One audit event with its fields rendered as strings.
+#[derive(Debug, Clone)]
+pub struct CapturedEvent {
+    pub event: &'static str,
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Called by [`crate::audit!`]; a no-op outside [`collect`] (background workers, tests without
+/// the middleware).
+pub fn capture(event: &'static str, fields: &[(&'static str, &dyn Debug)]) {
+    if SKIPPED_EVENT_PREFIXES
+        .iter()
+        .any(|prefix| event.starts_with(prefix))
+    {
+        return;
+    }
+    let _ = CAPTURED.try_with(|captured| {
+        captured.borrow_mut().push(CapturedEvent {
+            event,
+            fields: fields
+                .iter()
+                .map(|(key, value)| ((*key).to_owned(), format!("{value:?}")))
+                .collect(),
+        });
+    });
+}
+
+/// Runs `fut` and returns its output with the audit events raised while it ran.
+pub async fn collect<F: Future>(fut: F) -> (F::Output, Vec<CapturedEvent>) {
+    CAPTURED
+        .scope(RefCell::new(Vec::new()), async move {
+            let output = fut.await;
+            (output, CAPTURED.with(RefCell::take))
+        })
+        .await
+}
+
+/// Request context stored next to each captured event.
+#[derive(Debug, Clone, Default)]
+pub struct SecurityEventContext {
+    pub request_id: Option<String>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Authenticated user of the request, the actor when the event names none.
+    pub request_user_id: Option<String>,
+}
+
+/// Insert one `security_event` row per captured event.
+///
+/// The subject `user_id` is the event's `target_user_id` or `user_id` field, else the actor; the
+/// actor is its `actor_user_id` field, else the authenticated user of the request.
+pub async fn insert_events(
+    db: &Database,
+    events: Vec<CapturedEvent>,
+    ctx: &SecurityEventContext,
+) -> Result<(), surrealdb::Error> {
+    for CapturedEvent { event, mut fields } in events {
+        let actor = fields
+            .remove("actor_user_id")
+            .or_else(|| ctx.request_user_id.clone());
+        let subject = fields
+            .remove("target_user_id")
+            .or_else(|| fields.remove("user_id"))
+            .or_else(|| actor.clone());
+        let response = db
+            .db
+            .query(
+                "CREATE security_event SET event = $event, user_id = $user_id, \
+                 actor_user_id = $actor_user_id, fields = $fields, request_id = $request_id, \
+                 client_ip = $client_ip, user_agent = $user_agent;",
+            )
+            .bind(("event", event.to_owned()))
+            .bind(("user_id", subject))
+            .bind(("actor_user_id", actor))
+            .bind(("fields", fields))
+            .bind(("request_id", ctx.request_id.clone()))
+            .bind(("client_ip", ctx.client_ip.clone()))
+            .bind(("user_agent", ctx.user_agent.clone()))
+            .await?;
+        response.check()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::team::{TeamMemberInput, TeamRole, TeamUserRef, UpdateTeam};
+
+    use crate::test_helpers::{TeamFixture, create_user, team_service, test_db};
+
+    use super::{CapturedEvent, capture, collect};
+
+    fn target_of<'a>(events: &'a [CapturedEvent], event: &str) -> Vec<&'a str> {
+        events
+            .iter()
+            .filter(|e| e.event == event)
+            .map(|e| e.fields["target_user_id"].as_str())
+            .collect()
+    }
+
+    /// BLC-MON-012: membership updates raise one event per added, re-roled and removed member;
+    /// events outside a request scope are dropped.
+    #[tokio::test]
+    async fn member_changes_are_captured_inside_collect_only() {
+        let db = test_db().await.unwrap();
+        let fx = TeamFixture::build(&db).await.unwrap();
+        let newcomer = create_user(&db, "sec-newcomer@test.local").await.unwrap();
+        let member = |id: &str, role| TeamMemberInput {
+            user: TeamUserRef { id: id.to_owned() },
+            role,
+        };
+        let update = UpdateTeam {
+            name: "Fixture Shared Team".into(),
+            members: Some(vec![
+                member(&fx.admin_user.id, TeamRole::Admin),
+                member(&fx.writer.id, TeamRole::Guest),
+                member(&newcomer.id, TeamRole::ContentMaintainer),
+            ]),
+        };
+
+        let (updated, events) = collect(team_service(&db).update_team_for_user(
+            &fx.admin_user,
+            &fx.shared_team_id,
+            update,
+        ))
+        .await;
+        updated.unwrap();
+        assert_eq!(
+            target_of(&events, "audit.team.member.added"),
+            [newcomer.id.as_str()]
+        );
+        assert_eq!(
+            target_of(&events, "audit.team.role.changed"),
+            [fx.writer.id.as_str()]
+        );
+        assert_eq!(
+            target_of(&events, "audit.team.member.removed"),
+            [fx.guest.id.as_str()]
+        );
+        assert!(
+            events
+                .iter()
+                .all(|e| e.fields["actor_user_id"] == fx.admin_user.id)
+        );
+
+        capture("audit.user.created", &[]);
+        let ((), events) = collect(async { capture("audit.rate_limit.rejected", &[]) }).await;
+        assert!(events.is_empty());
+    }
+}
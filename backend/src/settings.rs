@@ -2,6 +2,8 @@ use std::fmt;
 
 use serde::Deserialize;
 
+use crate::observability::OtlpConfig;
+
 #[derive(Clone, Debug)]
 pub struct CookieConfig {
     pub name: String,
@@ -19,6 +21,96 @@ pub struct OtpConfig {
     pub allow_self_signup: bool,
 }
 
+/// Relying party identity for passkey (WebAuthn) registration and login.
+#[derive(Clone, Debug)]
+pub struct WebauthnConfig {
+    pub rp_id: String,
+    pub rp_name: String,
+    pub origin: String,
+}
+
+/// Where blob bytes are kept (`BLOB_STORAGE`).
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlobStorageKind {
+    /// Files under `BLOB_DIR`.
+    #[default]
+    Fs,
+    /// Objects in an S3-compatible bucket (AWS S3, MinIO, …).
+    S3,
+}
+
+/// Connection details for the S3-compatible blob backend.
+#[derive(Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// `endpoint/bucket/key` instead of `bucket.endpoint/key`; MinIO needs this.
+    pub path_style: bool,
+    /// Prepended to every object key, e.g. `blobs/`.
+    pub key_prefix: String,
+    pub presign_ttl_seconds: u64,
+}
+
+impl fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3Config")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field("path_style", &self.path_style)
+            .field("key_prefix", &self.key_prefix)
+            .field("presign_ttl_seconds", &self.presign_ttl_seconds)
+            .finish()
+    }
+}
+
+/// One entry of `OIDC_PROVIDERS`. `redirect_url` and `scopes` fall back to `OIDC_REDIRECT_URL`
+/// and `OIDC_SCOPES`, since every provider returns to the same callback.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct OidcProviderSettings {
+    /// Value of `/auth/login?provider=`; lowercase letters, digits, `-` and `_`.
+    pub id: String,
+    /// Label for login buttons. Defaults to the id.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub issuer_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub redirect_url: Option<String>,
+    #[serde(default)]
+    pub scopes: Option<Vec<String>>,
+    /// Accept the `email` claim for account matching when `email_verified` is absent. Only for
+    /// providers that never issue unverified addresses. Default false.
+    #[serde(default)]
+    pub trusted_email: bool,
+}
+
+impl fmt::Debug for OidcProviderSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OidcProviderSettings")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("issuer_url", &self.issuer_url)
+            .field("client_id", &self.client_id)
+            .field(
+                "client_secret",
+                &self.client_secret.as_ref().map(|_| "<redacted>"),
+            )
+            .field("redirect_url", &self.redirect_url)
+            .field("scopes", &self.scopes)
+            .field("trusted_email", &self.trusted_email)
+            .finish()
+    }
+}
+
 #[derive(Clone, Deserialize)]
 #[serde(default)]
 pub struct Settings {
@@ -36,6 +128,15 @@ pub struct Settings {
     #[serde(default = "default_otp_allow_self_signup")]
     pub otp_allow_self_signup: bool,
 
+    /// WebAuthn relying party id: the registrable domain passkeys are bound to (no scheme or
+    /// port). Changing it invalidates every registered passkey. Default: `localhost`.
+    pub webauthn_rp_id: String,
+    /// Name authenticators show next to the passkey. Default: `WorshipViewer`.
+    pub webauthn_rp_name: String,
+    /// Exact origin (scheme, host and port) the frontend is served from; ceremonies from other
+    /// origins are rejected. Default: `http://localhost:8080`.
+    pub webauthn_origin: String,
+
     pub db_address: String,
     pub db_namespace: String,
     pub db_database: String,
@@ -48,6 +149,12 @@ pub struct Settings {
     pub oidc_client_secret: Option<String>,
     pub oidc_redirect_url: String,
     pub oidc_scopes: Vec<String>,
+    /// Further OIDC providers as a JSON array of [`OidcProviderSettings`], e.g.
+    /// `[{"id":"microsoft","name":"Microsoft","issuer_url":"…","client_id":"…"}]`. The
+    /// `OIDC_*` values above stay registered as provider `google` when `OIDC_CLIENT_ID` is set
+    /// or this list is empty.
+    #[serde(deserialize_with = "deserialize_json_list")]
+    pub oidc_providers: Vec<OidcProviderSettings>,
 
     pub initial_admin_user_email: Option<String>,
     pub initial_admin_user_test_session: bool,
@@ -60,20 +167,112 @@ pub struct Settings {
     /// Maximum allowed size (in bytes) for binary blob uploads via `PUT /blobs/{id}/data`.
     /// Default: 20 MiB.
     pub blob_upload_max_bytes: usize,
+    /// `fs` (default) or `s3`. With `s3`, the `S3_*` settings below must point at a bucket and
+    /// `GET /blobs/{id}/data` redirects to presigned URLs.
+    pub blob_storage: BlobStorageKind,
+    /// Base URL of the S3 API, e.g. `https://s3.eu-central-1.amazonaws.com` or
+    /// `http://minio:9000`.
+    pub s3_endpoint: String,
+    /// Default: `us-east-1` (also what MinIO expects unless configured otherwise).
+    pub s3_region: String,
+    pub s3_bucket: String,
+    pub s3_access_key_id: String,
+    pub s3_secret_access_key: String,
+    /// Path-style addressing (`endpoint/bucket/key`). Default: true.
+    pub s3_path_style: bool,
+    /// Prefix for object keys inside the bucket. Default: empty.
+    pub s3_key_prefix: String,
+    /// Lifetime of presigned download URLs. Default: 300.
+    pub s3_presign_ttl_seconds: u64,
+
+    /// Tesseract executable used to extract text from uploaded PNG and JPEG blobs into `ocr`,
+    /// e.g. `tesseract`. Empty (default) disables OCR.
+    pub ocr_command: String,
+    /// Tesseract language models (`-l`), e.g. `eng+deu`. Default: `eng`.
+    pub ocr_languages: String,
+    /// Attempts per upload before its OCR is marked failed. Default: 3.
+    pub ocr_max_attempts: u32,
+    /// Wall-clock limit for one Tesseract run; it is killed after that. Default: 120.
+    pub ocr_timeout_seconds: u64,
 
     /// Max size for profile picture uploads and OAuth profile image fetches. Default: 2 MiB.
     #[serde(default = "default_avatar_upload_max_bytes")]
     pub avatar_upload_max_bytes: usize,
 
     /// Requests per second allowed per IP on sensitive auth endpoints (OTP + login).
-    /// Default: 1 request per second with a burst of 5.
+    /// Default: 1 request per second with a burst of 5. Both must be at least 1.
     pub auth_rate_limit_rps: u64,
     pub auth_rate_limit_burst: u32,
 
     /// Per-IP rate limit for `/api/v1/*` (token bucket). Defaults are generous for local development.
+    /// Both must be at least 1.
     pub api_rate_limit_rps: u64,
     pub api_rate_limit_burst: u32,
 
+    /// How often the team activity digest sender checks for due emails. `0` disables digests.
+    /// Default: 3600 (hourly).
+    pub activity_digest_interval_seconds: u64,
+
+    /// How often the webhook worker sends due deliveries. `0` disables outbound webhooks
+    /// (deliveries stay queued). Default: 10.
+    pub webhook_delivery_interval_seconds: u64,
+    /// Attempts per webhook delivery before it is marked failed. Default: 8.
+    pub webhook_max_attempts: u32,
+    /// Lets webhooks reach loopback, private, link-local and unique-local addresses (e.g. a
+    /// receiver on the same host during development). Default: false.
+    pub webhook_allow_private_targets: bool,
+
+    /// Background jobs (e.g. blob OCR) run at the same time. Default: 2.
+    pub job_workers: usize,
+    /// How often idle job workers check the queue for due jobs. `0` disables the workers (jobs
+    /// stay queued). Default: 2.
+    pub job_poll_interval_seconds: u64,
+    /// How long a running job stays claimed by its worker, which renews the claim while the job
+    /// runs. Jobs whose claim ran out (their process stopped) are queued again. Default: 60.
+    pub job_lease_seconds: u64,
+
+    /// Days a deleted song, setlist, collection or blob stays in its team's trash before it is
+    /// removed permanently. Default: 30.
+    pub trash_retention_days: u32,
+    /// How often expired trash is purged. `0` disables purging (trash is kept). Default: 3600.
+    pub trash_purge_interval_seconds: u64,
+
+    /// Days deletions stay available to `GET /api/v1/sync`; older cursors get a full snapshot.
+    /// Default: 90.
+    pub sync_tombstone_retention_days: u32,
+    /// How often expired sync tombstones are pruned. `0` disables pruning. Default: 3600.
+    pub sync_prune_interval_seconds: u64,
+
+    /// Days `http_request_audit` rows are kept as-is. Older whole UTC days are folded into the
+    /// `http_request_audit_daily` rollup and deleted. Default: 30.
+    pub audit_retention_days: u32,
+    /// How often audit rows past the retention are rolled up. `0` disables the rollup (raw rows
+    /// are kept). Default: 3600.
+    pub audit_rollup_interval_seconds: u64,
+
+    /// Days between `DELETE /api/v1/users/me` and the account actually being removed; signing in
+    /// and cancelling within that time keeps it. Default: 14.
+    pub account_deletion_grace_days: u32,
+    /// How often accounts past their grace period are deleted. `0` disables the deletion (accounts
+    /// stay scheduled). Default: 3600.
+    pub account_deletion_interval_seconds: u64,
+    /// Largest total of blob file bytes one account export may hold; a larger personal library
+    /// is refused instead of being zipped in memory. Default: 256 MiB.
+    pub account_export_max_bytes: u64,
+
+    /// Bearer token Prometheus sends to scrape `GET /metrics`. Unset disables the endpoint.
+    #[serde(default)]
+    pub metrics_bearer_token: Option<String>,
+    /// How often the total size of stored blob content is measured for `/metrics` (it lists the
+    /// whole store). `0` disables the measurement. Default: 300.
+    pub metrics_blob_size_interval_seconds: u64,
+    /// OTLP/HTTP collector base URL (e.g. `http://tempo:4318`); spans are posted as JSON to
+    /// `<url>/v1/traces`. Unset disables trace export.
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` resource attribute of exported spans. Default: `worship-viewer`.
+    pub otlp_service_name: String,
+
     /// Shown under `info.contact.email` in OpenAPI when set (`OPENAPI_CONTACT_EMAIL`).
     #[serde(default)]
     pub openapi_contact_email: Option<String>,
@@ -95,6 +294,9 @@ impl fmt::Debug for Settings {
             .field("otp_pepper", &"<redacted>")
             .field("otp_max_attempts", &self.otp_max_attempts)
             .field("otp_allow_self_signup", &self.otp_allow_self_signup)
+            .field("webauthn_rp_id", &self.webauthn_rp_id)
+            .field("webauthn_rp_name", &self.webauthn_rp_name)
+            .field("webauthn_origin", &self.webauthn_origin)
             .field("db_address", &self.db_address)
             .field("db_namespace", &self.db_namespace)
             .field("db_database", &self.db_database)
@@ -112,6 +314,7 @@ impl fmt::Debug for Settings {
             )
             .field("oidc_redirect_url", &self.oidc_redirect_url)
             .field("oidc_scopes", &self.oidc_scopes)
+            .field("oidc_providers", &self.oidc_providers)
             .field("initial_admin_user_email", &self.initial_admin_user_email)
             .field(
                 "initial_admin_user_test_session",
@@ -122,11 +325,77 @@ impl fmt::Debug for Settings {
             .field("static_dir", &self.static_dir)
             .field("blob_dir", &self.blob_dir)
             .field("blob_upload_max_bytes", &self.blob_upload_max_bytes)
+            .field("blob_storage", &self.blob_storage)
+            .field("s3_endpoint", &self.s3_endpoint)
+            .field("s3_region", &self.s3_region)
+            .field("s3_bucket", &self.s3_bucket)
+            .field("s3_access_key_id", &self.s3_access_key_id)
+            .field("s3_secret_access_key", &"<redacted>")
+            .field("s3_path_style", &self.s3_path_style)
+            .field("s3_key_prefix", &self.s3_key_prefix)
+            .field("s3_presign_ttl_seconds", &self.s3_presign_ttl_seconds)
+            .field("ocr_command", &self.ocr_command)
+            .field("ocr_languages", &self.ocr_languages)
+            .field("ocr_max_attempts", &self.ocr_max_attempts)
+            .field("ocr_timeout_seconds", &self.ocr_timeout_seconds)
             .field("avatar_upload_max_bytes", &self.avatar_upload_max_bytes)
             .field("auth_rate_limit_rps", &self.auth_rate_limit_rps)
             .field("auth_rate_limit_burst", &self.auth_rate_limit_burst)
             .field("api_rate_limit_rps", &self.api_rate_limit_rps)
             .field("api_rate_limit_burst", &self.api_rate_limit_burst)
+            .field(
+                "activity_digest_interval_seconds",
+                &self.activity_digest_interval_seconds,
+            )
+            .field(
+                "webhook_delivery_interval_seconds",
+                &self.webhook_delivery_interval_seconds,
+            )
+            .field("webhook_max_attempts", &self.webhook_max_attempts)
+            .field(
+                "webhook_allow_private_targets",
+                &self.webhook_allow_private_targets,
+            )
+            .field("job_workers", &self.job_workers)
+            .field("job_poll_interval_seconds", &self.job_poll_interval_seconds)
+            .field("job_lease_seconds", &self.job_lease_seconds)
+            .field("trash_retention_days", &self.trash_retention_days)
+            .field(
+                "trash_purge_interval_seconds",
+                &self.trash_purge_interval_seconds,
+            )
+            .field(
+                "sync_tombstone_retention_days",
+                &self.sync_tombstone_retention_days,
+            )
+            .field(
+                "sync_prune_interval_seconds",
+                &self.sync_prune_interval_seconds,
+            )
+            .field("audit_retention_days", &self.audit_retention_days)
+            .field(
+                "audit_rollup_interval_seconds",
+                &self.audit_rollup_interval_seconds,
+            )
+            .field(
+                "account_deletion_grace_days",
+                &self.account_deletion_grace_days,
+            )
+            .field(
+                "account_deletion_interval_seconds",
+                &self.account_deletion_interval_seconds,
+            )
+            .field("account_export_max_bytes", &self.account_export_max_bytes)
+            .field(
+                "metrics_bearer_token",
+                &self.metrics_bearer_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field(
+                "metrics_blob_size_interval_seconds",
+                &self.metrics_blob_size_interval_seconds,
+            )
+            .field("otlp_endpoint", &self.otlp_endpoint)
+            .field("otlp_service_name", &self.otlp_service_name)
             .field("openapi_contact_email", &self.openapi_contact_email)
             .field("openapi_imprint_url", &self.openapi_imprint_url)
             .finish()
@@ -146,6 +415,9 @@ impl Default for Settings {
             otp_pepper: "changeme".into(),
             otp_max_attempts: 5,
             otp_allow_self_signup: true,
+            webauthn_rp_id: "localhost".into(),
+            webauthn_rp_name: "WorshipViewer".into(),
+            webauthn_origin: "http://localhost:8080".into(),
             db_address: "mem://".into(),
             db_namespace: "main".into(),
             db_database: "main".into(),
@@ -157,6 +429,7 @@ impl Default for Settings {
             oidc_client_secret: None,
             oidc_redirect_url: "http://localhost:8080/auth/callback".into(),
             oidc_scopes: vec!["openid".into(), "profile".into(), "email".into()],
+            oidc_providers: Vec::new(),
             initial_admin_user_email: None,
             initial_admin_user_test_session: false,
             gmail_app_password: String::new(),
@@ -164,11 +437,44 @@ impl Default for Settings {
             static_dir: "static".into(),
             blob_dir: "blobs".into(),
             blob_upload_max_bytes: 20 * 1024 * 1024,
+            blob_storage: BlobStorageKind::Fs,
+            s3_endpoint: String::new(),
+            s3_region: "us-east-1".into(),
+            s3_bucket: String::new(),
+            s3_access_key_id: String::new(),
+            s3_secret_access_key: String::new(),
+            s3_path_style: true,
+            s3_key_prefix: String::new(),
+            s3_presign_ttl_seconds: 300,
+            ocr_command: String::new(),
+            ocr_languages: "eng".into(),
+            ocr_max_attempts: 3,
+            ocr_timeout_seconds: 120,
             avatar_upload_max_bytes: default_avatar_upload_max_bytes(),
             auth_rate_limit_rps: 1,
             auth_rate_limit_burst: 5,
             api_rate_limit_rps: 50,
             api_rate_limit_burst: 200,
+            activity_digest_interval_seconds: 3600,
+            webhook_delivery_interval_seconds: 10,
+            webhook_max_attempts: 8,
+            webhook_allow_private_targets: false,
+            job_workers: 2,
+            job_poll_interval_seconds: 2,
+            job_lease_seconds: 60,
+            trash_retention_days: 30,
+            trash_purge_interval_seconds: 3600,
+            sync_tombstone_retention_days: 90,
+            sync_prune_interval_seconds: 3600,
+            audit_retention_days: 30,
+            audit_rollup_interval_seconds: 3600,
+            account_deletion_grace_days: 14,
+            account_deletion_interval_seconds: 3600,
+            account_export_max_bytes: 256 * 1024 * 1024,
+            metrics_bearer_token: None,
+            metrics_blob_size_interval_seconds: 300,
+            otlp_endpoint: None,
+            otlp_service_name: "worship-viewer".into(),
             openapi_contact_email: None,
             openapi_imprint_url: None,
         }
@@ -183,6 +489,23 @@ fn default_avatar_upload_max_bytes() -> usize {
     2 * 1024 * 1024
 }
 
+/// Environment values are strings; list-valued settings are passed as JSON.
+fn deserialize_json_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let raw = String::deserialize(deserializer)?;
+    if raw.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&raw).map_err(serde::de::Error::custom)
+}
+
+/// Provider id of the single-provider `OIDC_*` settings, and of `oidc_state` rows written
+/// before providers were recorded.
+pub const LEGACY_OIDC_PROVIDER_ID: &str = "google";
+
 /// Limits for `PUT /users/me/profile-picture` and OAuth profile image fetches.
 #[derive(Clone, Copy, Debug)]
 pub struct ProfilePictureLimits {
@@ -202,7 +525,22 @@ impl Settings {
             s.otp_allow_self_signup =
                 !(v == "0" || v.eq_ignore_ascii_case("false") || v.eq_ignore_ascii_case("no"));
         }
-        Ok(s)
+        s.validate()
+    }
+
+    /// Rejects values the server cannot start with: a rate limit needs at least one request per
+    /// second and a burst of at least one.
+    fn validate(self) -> Result<Self, envy::Error> {
+        let limits = [
+            ("AUTH_RATE_LIMIT_RPS", self.auth_rate_limit_rps),
+            ("AUTH_RATE_LIMIT_BURST", self.auth_rate_limit_burst.into()),
+            ("API_RATE_LIMIT_RPS", self.api_rate_limit_rps),
+            ("API_RATE_LIMIT_BURST", self.api_rate_limit_burst.into()),
+        ];
+        if let Some((name, _)) = limits.iter().find(|(_, value)| *value == 0) {
+            return Err(envy::Error::Custom(format!("{name} must be at least 1")));
+        }
+        Ok(self)
     }
 
     pub fn cookie_config(&self) -> CookieConfig {
@@ -222,11 +560,80 @@ impl Settings {
             allow_self_signup: self.otp_allow_self_signup,
         }
     }
+
+    pub fn webauthn_config(&self) -> WebauthnConfig {
+        WebauthnConfig {
+            rp_id: self.webauthn_rp_id.clone(),
+            rp_name: self.webauthn_rp_name.clone(),
+            origin: self.webauthn_origin.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    /// Trace export target, when `OTLP_ENDPOINT` is set.
+    pub fn otlp_config(&self) -> Option<OtlpConfig> {
+        self.otlp_endpoint
+            .as_ref()
+            .filter(|endpoint| !endpoint.trim().is_empty())
+            .map(|endpoint| OtlpConfig {
+                endpoint: endpoint.trim().to_owned(),
+                service_name: self.otlp_service_name.clone(),
+            })
+    }
+
+    pub fn s3_config(&self) -> S3Config {
+        S3Config {
+            endpoint: self.s3_endpoint.trim_end_matches('/').to_owned(),
+            region: self.s3_region.clone(),
+            bucket: self.s3_bucket.clone(),
+            access_key_id: self.s3_access_key_id.clone(),
+            secret_access_key: self.s3_secret_access_key.clone(),
+            path_style: self.s3_path_style,
+            key_prefix: self.s3_key_prefix.clone(),
+            presign_ttl_seconds: self.s3_presign_ttl_seconds,
+        }
+    }
+
+    /// Every OIDC provider to register, legacy `OIDC_*` settings first, with defaults filled in.
+    pub fn oidc_provider_settings(&self) -> Vec<OidcProviderSettings> {
+        let legacy =
+            (!self.oidc_client_id.is_empty() || self.oidc_providers.is_empty()).then(|| {
+                OidcProviderSettings {
+                    id: LEGACY_OIDC_PROVIDER_ID.into(),
+                    name: Some("Google".into()),
+                    issuer_url: self.oidc_issuer_url.clone(),
+                    client_id: self.oidc_client_id.clone(),
+                    client_secret: self.oidc_client_secret.clone(),
+                    redirect_url: None,
+                    scopes: None,
+                    trusted_email: false,
+                }
+            });
+        legacy
+            .into_iter()
+            .chain(self.oidc_providers.iter().cloned())
+            .map(|provider| OidcProviderSettings {
+                name: Some(provider.name.clone().unwrap_or_else(|| provider.id.clone())),
+                redirect_url: Some(
+                    provider
+                        .redirect_url
+                        .clone()
+                        .unwrap_or_else(|| self.oidc_redirect_url.clone()),
+                ),
+                scopes: Some(
+                    provider
+                        .scopes
+                        .clone()
+                        .unwrap_or_else(|| self.oidc_scopes.clone()),
+                ),
+                ..provider
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Settings;
+    use super::{BlobStorageKind, LEGACY_OIDC_PROVIDER_ID, Settings};
 
     #[test]
     fn settings_debug_redacts_secrets() {
@@ -235,6 +642,8 @@ mod tests {
             gmail_app_password: "unique_gmail_secret_456".into(),
             db_password: Some("unique_db_pass_789".into()),
             oidc_client_secret: Some("unique_oidc_secret_abc".into()),
+            s3_secret_access_key: "unique_s3_secret_def".into(),
+            metrics_bearer_token: Some("unique_metrics_token_ghi".into()),
             ..Default::default()
         };
 
@@ -243,6 +652,87 @@ mod tests {
         assert!(!out.contains("unique_gmail_secret_456"));
         assert!(!out.contains("unique_db_pass_789"));
         assert!(!out.contains("unique_oidc_secret_abc"));
+        assert!(!out.contains("unique_s3_secret_def"));
+        assert!(!out.contains("unique_metrics_token_ghi"));
+        assert!(!format!("{:?}", s.s3_config()).contains("unique_s3_secret_def"));
         assert!(out.contains("<redacted>"));
     }
+
+    #[test]
+    fn oidc_providers_parse_from_json_env() {
+        let env = [
+            ("OIDC_CLIENT_ID".to_owned(), "google-client".to_owned()),
+            (
+                "OIDC_PROVIDERS".to_owned(),
+                r#"[{"id":"keycloak","issuer_url":"https://sso.example.org/realms/church","client_id":"wv","client_secret":"unique_keycloak_secret","scopes":["openid","email"],"trusted_email":true}]"#
+                    .to_owned(),
+            ),
+        ];
+        let s: Settings = envy::from_iter(env).expect("settings");
+        assert!(!format!("{s:?}").contains("unique_keycloak_secret"));
+
+        let providers = s.oidc_provider_settings();
+        assert_eq!(providers.len(), 2);
+        assert_eq!(providers[0].id, LEGACY_OIDC_PROVIDER_ID);
+        assert_eq!(providers[0].client_id, "google-client");
+        assert_eq!(providers[1].name.as_deref(), Some("keycloak"));
+        assert!(providers[1].trusted_email);
+        assert!(!providers[0].trusted_email);
+        assert_eq!(
+            providers[1].redirect_url.as_deref(),
+            Some(s.oidc_redirect_url.as_str())
+        );
+        assert_eq!(
+            providers[1].scopes.as_deref(),
+            Some(&["openid".to_owned(), "email".to_owned()][..])
+        );
+
+        let only_listed: Settings = envy::from_iter([(
+            "OIDC_PROVIDERS".to_owned(),
+            r#"[{"id":"microsoft","issuer_url":"https://login.microsoftonline.com/common/v2.0","client_id":"ms"}]"#.to_owned(),
+        )])
+        .expect("settings");
+        let providers = only_listed.oidc_provider_settings();
+        assert_eq!(providers.len(), 1, "legacy provider needs OIDC_CLIENT_ID");
+        assert_eq!(providers[0].id, "microsoft");
+        assert!(Settings::default().oidc_provider_settings()[0].id == LEGACY_OIDC_PROVIDER_ID);
+    }
+
+    #[test]
+    fn s3_blob_storage_parses_from_env() {
+        let s: Settings = envy::from_iter([
+            ("BLOB_STORAGE".to_owned(), "s3".to_owned()),
+            ("S3_ENDPOINT".to_owned(), "http://minio:9000/".to_owned()),
+            ("S3_BUCKET".to_owned(), "worship".to_owned()),
+        ])
+        .expect("settings");
+        assert_eq!(s.blob_storage, BlobStorageKind::S3);
+        let config = s.s3_config();
+        assert_eq!(config.endpoint, "http://minio:9000");
+        assert_eq!(config.region, "us-east-1");
+        assert!(config.path_style);
+        assert_eq!(Settings::default().blob_storage, BlobStorageKind::Fs);
+        assert!(
+            envy::from_iter::<_, Settings>([("BLOB_STORAGE".to_owned(), "gcs".to_owned())])
+                .is_err()
+        );
+    }
+    #[test]
+    fn zero_rate_limits_are_rejected() {
+        let zero = |name: &str| {
+            envy::from_iter::<_, Settings>([(name.to_owned(), "0".to_owned())])
+                .expect("settings")
+                .validate()
+        };
+        assert!(Settings::default().validate().is_ok());
+        for name in [
+            "AUTH_RATE_LIMIT_RPS",
+            "AUTH_RATE_LIMIT_BURST",
+            "API_RATE_LIMIT_RPS",
+            "API_RATE_LIMIT_BURST",
+        ] {
+            let err = zero(name).expect_err("zero limit");
+            assert!(err.to_string().contains(name), "{err}");
+        }
+    }
 }
@@ -5,12 +5,25 @@ use chordlib::types::Song as SongData;
 
 use crate::database::Database;
 use crate::resources::User;
+use crate::resources::batch::BatchServiceHandle;
+use crate::resources::blob::FsBlobStorage;
 use crate::resources::blob::service::BlobServiceHandle;
 use crate::resources::collection::service::CollectionServiceHandle;
+use crate::resources::event::EventServiceHandle;
+use crate::resources::job::JobServiceHandle;
 use crate::resources::setlist::{SetlistService, SetlistServiceHandle, SurrealSetlistRepo};
 use crate::resources::song::service::SongServiceHandle;
+use crate::resources::sync::SyncServiceHandle;
+use crate::resources::team::activity::ActivityServiceHandle;
 use crate::resources::team::invitation::InvitationServiceHandle;
+use crate::resources::team::organization::OrganizationServiceHandle;
+use crate::resources::team::trash::TrashServiceHandle;
+use crate::resources::team::webhook::{ContentEventRecorder, WebhookServiceHandle};
 use crate::resources::team::{SurrealTeamResolver, TeamServiceHandle, UserPermissions};
+use crate::resources::user::account::AccountServiceHandle;
+use crate::resources::user::api_token::ApiTokenServiceHandle;
+use crate::resources::user::identity::UserIdentityServiceHandle;
+use crate::resources::user::passkey::PasskeyServiceHandle;
 use crate::resources::user::service::UserServiceHandle;
 use crate::resources::user::session::service::SessionServiceHandle;
 use shared::setlist::CreateSetlist;
@@ -115,6 +128,7 @@ pub fn setlist_service(db: &Arc<Database>) -> SetlistServiceHandle {
         SurrealSetlistRepo::new(db.clone()),
         Arc::new(SurrealTeamResolver::new(db.clone())),
         db.clone(),
+        ContentEventRecorder::build(db.clone()),
     )
 }
 
@@ -128,6 +142,32 @@ pub fn invitation_service(db: &Arc<Database>) -> InvitationServiceHandle {
     InvitationServiceHandle::build(db.clone())
 }
 
+/// Organization application service (same wiring as HTTP `main`).
+pub fn organization_service(db: &Arc<Database>) -> OrganizationServiceHandle {
+    OrganizationServiceHandle::build(db.clone())
+}
+
+/// Team activity application service (same wiring as HTTP `main`).
+pub fn activity_service(db: &Arc<Database>) -> ActivityServiceHandle {
+    ActivityServiceHandle::build(db.clone())
+}
+
+/// Team trash service with an explicit blob directory and the default 30-day retention.
+pub fn trash_service(db: &Arc<Database>, blob_dir: String) -> TrashServiceHandle {
+    TrashServiceHandle::build(
+        db.clone(),
+        Arc::new(SurrealTeamResolver::new(db.clone())),
+        FsBlobStorage::new(blob_dir).into(),
+        crate::settings::Settings::default().trash_retention_days,
+    )
+}
+
+/// Team webhook service with the real HTTP sender (deliveries are only sent when due runs).
+pub fn webhook_service(db: &Arc<Database>) -> WebhookServiceHandle {
+    WebhookServiceHandle::build(db.clone(), 3, std::time::Duration::from_secs(1), false)
+        .expect("webhook http client")
+}
+
 /// User application service (same wiring as HTTP `main`).
 pub fn user_service(db: &Arc<Database>) -> UserServiceHandle {
     UserServiceHandle::build(db.clone())
@@ -138,6 +178,71 @@ pub fn session_service(db: &Arc<Database>) -> SessionServiceHandle {
     SessionServiceHandle::build(db.clone())
 }
 
+/// Personal API token service (same wiring as HTTP `main`).
+pub fn api_token_service(db: &Arc<Database>) -> ApiTokenServiceHandle {
+    ApiTokenServiceHandle::build(db.clone())
+}
+
+/// Background job service without handlers and with the default lease; add handlers with
+/// `with_handler`.
+pub fn job_service(db: &Arc<Database>) -> JobServiceHandle {
+    JobServiceHandle::build(
+        db.clone(),
+        std::time::Duration::from_secs(crate::settings::Settings::default().job_lease_seconds),
+    )
+}
+
+/// Delta sync service with the default 90-day tombstone retention.
+pub fn sync_service(db: &Arc<Database>) -> SyncServiceHandle {
+    SyncServiceHandle::build(
+        db.clone(),
+        Arc::new(SurrealTeamResolver::new(db.clone())),
+        crate::settings::Settings::default().sync_tombstone_retention_days,
+    )
+}
+
+/// Batch service over filesystem blob storage under `blob_dir`.
+pub fn batch_service(db: &Arc<Database>, blob_dir: String) -> BatchServiceHandle {
+    BatchServiceHandle::new(
+        db.clone(),
+        Arc::new(SurrealTeamResolver::new(db.clone())),
+        FsBlobStorage::new(blob_dir).into(),
+    )
+}
+
+/// Change stream service over the database's change bus.
+pub fn event_service(db: &Arc<Database>) -> EventServiceHandle {
+    EventServiceHandle::new(db.clone(), Arc::new(SurrealTeamResolver::new(db.clone())))
+}
+
+/// Passkey service for the default relying party (`localhost`, `http://localhost:8080`).
+pub fn passkey_service(db: &Arc<Database>) -> PasskeyServiceHandle {
+    PasskeyServiceHandle::build(
+        db.clone(),
+        crate::settings::Settings::default().webauthn_config(),
+    )
+}
+
+/// Linked OIDC identity service (same wiring as HTTP `main`).
+pub fn identity_service(db: &Arc<Database>) -> UserIdentityServiceHandle {
+    UserIdentityServiceHandle::build(db.clone())
+}
+
+/// Account self-service with an explicit blob directory, the default 14-day grace period and
+/// export limit, and the default auth rate limit for email changes.
+pub fn account_service(db: &Arc<Database>, blob_dir: String) -> AccountServiceHandle {
+    let settings = crate::settings::Settings::default();
+    AccountServiceHandle::build(
+        db.clone(),
+        Arc::new(SurrealTeamResolver::new(db.clone())),
+        FsBlobStorage::new(blob_dir).into(),
+        settings.account_deletion_grace_days,
+        settings.account_export_max_bytes,
+        settings.auth_rate_limit_rps,
+        settings.auth_rate_limit_burst,
+    )
+}
+
 /// Multi-role test fixture that creates a shared team with owner, admin, writer, guest,
 /// non-member, and platform admin users. Use `TeamFixture::build(&db).await` in integration tests
 /// that need to exercise ACL across multiple roles.
@@ -6,18 +6,26 @@ use shared::blob::{CreateBlob, UpdateBlob};
 use shared::collection::Collection;
 use shared::collection::{CreateCollection, UpdateCollection};
 use shared::error::NetworkClientError;
-use shared::net::{DefaultHttpClient, HttpClientConfig};
+use shared::net::{DefaultHttpClient, HttpClientConfig, Tagged};
 use shared::player::Player;
 use shared::setlist::Setlist;
 use shared::setlist::{CreateSetlist, UpdateSetlist};
-use shared::song::Song;
+use shared::song::{Song, SongDraft};
 use shared::song::{CreateSong, UpdateSong};
 use shared::user::{CreateUser, SessionBody, User};
 
 use super::error::{ApiError, OperationType};
+use super::idb;
+use super::outbox::{Outbox, OutboxEntry, PendingWrite};
+use super::replica::Replica;
+use crate::components::toast_notifications::{show_error, show_success, show_warning};
 use crate::route::Route;
+use serde::de::DeserializeOwned;
 use shared::api::{ApiClient, ListQuery, SongListQuery};
+use shared::song::Link as SongLink;
+use yew::Callback;
 
+use std::cell::Cell;
 use std::rc::Rc;
 
 /// `X-Worship-Client` value: `worshipviewer-frontend/<version>`, matching the backend
@@ -30,10 +38,26 @@ fn worshipviewer_frontend_client_ident() -> String {
     )
 }
 
+/// Failures where the server was never reached; the write is kept for replay instead.
+fn unreachable(err: &NetworkClientError) -> bool {
+    matches!(
+        err,
+        NetworkClientError::Connection
+            | NetworkClientError::Unexpected { .. }
+            | NetworkClientError::RequestFailed { status: None, .. }
+    )
+}
+
 #[derive(Clone)]
 pub struct Api {
     client: Rc<ApiClient<DefaultHttpClient>>,
     navigator: Navigator,
+    replica: Rc<Replica>,
+    outbox: Rc<Outbox>,
+    /// Receives the outbox entries waiting for the user to resolve a conflict.
+    on_conflicts: Callback<Vec<OutboxEntry>>,
+    syncing: Rc<Cell<bool>>,
+    resync: Rc<Cell<bool>>,
 }
 
 impl PartialEq for Api {
@@ -43,7 +67,11 @@ impl PartialEq for Api {
 }
 
 impl Api {
-    pub fn new(navigator: Navigator, base_url: String) -> Self {
+    pub fn new(
+        navigator: Navigator,
+        base_url: String,
+        on_conflicts: Callback<Vec<OutboxEntry>>,
+    ) -> Self {
         let config = HttpClientConfig {
             base_url,
             timeout: None,
@@ -53,7 +81,18 @@ impl Api {
         };
         let client = Rc::new(ApiClient::with_default(config));
 
-        Self { client, navigator }
+        let replica = Rc::new(Replica::default());
+        let outbox = Rc::new(Outbox::new(replica.clone()));
+
+        Self {
+            client,
+            navigator,
+            replica,
+            outbox,
+            on_conflicts,
+            syncing: Rc::new(Cell::new(false)),
+            resync: Rc::new(Cell::new(false)),
+        }
     }
 
     fn build_path(path: &str) -> String {
@@ -263,28 +302,35 @@ impl Api {
 
     #[allow(dead_code)]
     pub async fn get_songs(&self) -> Result<Vec<Song>, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .get_songs(ListQuery::default().into())
-            .await
-            .map_err(|e| self.handle_error(e))
+        self.get_songs_query(ListQuery::default().into()).await
     }
 
     pub async fn get_songs_query(&self, query: SongListQuery) -> Result<Vec<Song>, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .get_songs(query)
-            .await
-            .map_err(|e| self.handle_error(e))
+        if ApiError::check_and_notify_offline(OperationType::Read) {
+            return Ok(self.replica_songs(&query).await);
+        }
+        match self.client.get_songs(query.clone()).await {
+            Err(e) if unreachable(&e) => Ok(self.replica_songs(&query).await),
+            other => other.map_err(|e| self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
     pub async fn get_song(&self, id: &str) -> Result<Song, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .get_song(id)
-            .await
-            .map_err(|e| self.handle_error(e))
+        let id = self.outbox.resolve(id);
+        if ApiError::check_and_notify_offline(OperationType::Read)
+            || self.outbox.has_pending(&id).await
+        {
+            return self.replica_record(idb::SONGS, &id).await;
+        }
+        match self.client.get_song_tagged(&id).await {
+            Ok(song) => {
+                self.replica.put_tagged(idb::SONGS, &id, &song).await;
+                Ok(song.value)
+            }
+            Err(e) if unreachable(&e) => self.replica_record(idb::SONGS, &id).await,
+            Err(e) => Err(self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
@@ -296,32 +342,89 @@ impl Api {
             .map_err(|e| self.handle_error(e))
     }
 
-    #[allow(dead_code)]
     #[allow(dead_code)]
     pub async fn create_song(&self, payload: &CreateSong) -> Result<Song, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Write);
-        self.client
-            .create_song(payload.clone())
-            .await
-            .map_err(|e| self.handle_error(e))
+        let write = PendingWrite::CreateSong {
+            id: self.outbox.next_local_id(),
+            payload: payload.clone(),
+        };
+        if ApiError::check_and_notify_offline(OperationType::QueuedWrite) {
+            return self.queue(write).await;
+        }
+        match self.client.create_song_tagged(payload.clone()).await {
+            Ok(song) => {
+                self.replica
+                    .put_tagged(idb::SONGS, &song.value.id, &song)
+                    .await;
+                Ok(song.value)
+            }
+            Err(e) if unreachable(&e) => self.queue(write).await,
+            Err(e) => Err(self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
     pub async fn update_song(&self, id: &str, payload: &UpdateSong) -> Result<Song, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Write);
+        let id = self.outbox.resolve(id);
+        let write = PendingWrite::UpdateSong {
+            id: id.clone(),
+            payload: payload.clone(),
+        };
+        if ApiError::check_and_notify_offline(OperationType::QueuedWrite)
+            || self.outbox.has_pending(&id).await
+        {
+            return self.queue(write).await;
+        }
+        let etag = self.replica.etag(idb::SONGS, &id).await;
+        let result = self
+            .client
+            .update_song_tagged(&id, payload.clone(), etag.as_deref())
+            .await;
+        match result {
+            Ok(song) => {
+                self.replica
+                    .put_tagged(idb::SONGS, &song.value.id, &song)
+                    .await;
+                Ok(song.value)
+            }
+            Err(e) if unreachable(&e) => self.queue(write).await,
+            Err(e) => Err(self.write_failed(write, e).await),
+        }
+    }
+
+    pub async fn draft_song_from_blobs(&self, id: &str) -> Result<SongDraft, ApiError> {
+        ApiError::check_and_notify_offline(OperationType::Read);
         self.client
-            .update_song(id, payload.clone())
+            .draft_song_from_blobs(id)
             .await
             .map_err(|e| self.handle_error(e))
     }
 
     #[allow(dead_code)]
     pub async fn delete_song(&self, id: &str) -> Result<(), ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Write);
-        self.client
-            .delete_song(id)
-            .await
-            .map_err(|e| self.handle_error(e))
+        let id = self.outbox.resolve(id);
+        let write = PendingWrite::DeleteSong { id: id.clone() };
+        if ApiError::check_and_notify_offline(OperationType::QueuedWrite)
+            || self.outbox.has_pending(&id).await
+        {
+            self.enqueue(write).await;
+            return Ok(());
+        }
+        let result = match self.replica.etag(idb::SONGS, &id).await {
+            Some(etag) => self.client.delete_song_if_match(&id, &etag).await,
+            None => self.client.delete_song(&id).await,
+        };
+        match result {
+            Ok(()) => {
+                self.replica.remove(idb::SONGS, &id).await;
+                Ok(())
+            }
+            Err(e) if unreachable(&e) => {
+                self.enqueue(write).await;
+                Ok(())
+            }
+            Err(e) => Err(self.write_failed(write, e).await),
+        }
     }
 
     #[allow(dead_code)]
@@ -339,34 +442,63 @@ impl Api {
         self.client
             .update_song_like_status(id, liked)
             .await
-            .map_err(|e| self.handle_error(e))
+            .map_err(|e| self.handle_error(e))?;
+        // The like flag is part of the song's ETag, so refresh the replica's copy and its ETag.
+        match self.client.get_song_tagged(id).await {
+            Ok(song) => self.replica.put_tagged(idb::SONGS, id, &song).await,
+            Err(_) => {
+                if let Some(mut song) = self.replica.get::<Song>(idb::SONGS, id).await {
+                    song.user_specific_addons.liked = liked;
+                    let song = Tagged {
+                        value: song,
+                        etag: None,
+                    };
+                    self.replica.put_tagged(idb::SONGS, id, &song).await;
+                }
+            }
+        }
+        Ok(())
     }
 
     #[allow(dead_code)]
     pub async fn get_collections(&self) -> Result<Vec<Collection>, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .list_collections(ListQuery::default())
-            .await
-            .map_err(|e| self.handle_error(e))
+        if ApiError::check_and_notify_offline(OperationType::Read) {
+            return Ok(self.replica.all(idb::COLLECTIONS).await);
+        }
+        match self.client.list_collections(ListQuery::default()).await {
+            Err(e) if unreachable(&e) => Ok(self.replica.all(idb::COLLECTIONS).await),
+            other => other.map_err(|e| self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
     pub async fn get_collection(&self, id: &str) -> Result<Collection, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .get_collection(id)
-            .await
-            .map_err(|e| self.handle_error(e))
+        if ApiError::check_and_notify_offline(OperationType::Read) {
+            return self.replica_record(idb::COLLECTIONS, id).await;
+        }
+        match self.client.get_collection(id).await {
+            Err(e) if unreachable(&e) => self.replica_record(idb::COLLECTIONS, id).await,
+            other => other.map_err(|e| self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
     pub async fn get_collection_songs(&self, id: &str) -> Result<Vec<Song>, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
+        if ApiError::check_and_notify_offline(OperationType::Read) {
+            let collection: Collection = self.replica_record(idb::COLLECTIONS, id).await?;
+            return Ok(self.replica_linked_songs(&collection.songs).await);
+        }
+        match self
+            .client
             .get_collection_songs(id, ListQuery::default())
             .await
-            .map_err(|e| self.handle_error(e))
+        {
+            Err(e) if unreachable(&e) => {
+                let collection: Collection = self.replica_record(idb::COLLECTIONS, id).await?;
+                Ok(self.replica_linked_songs(&collection.songs).await)
+            }
+            other => other.map_err(|e| self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
@@ -414,29 +546,53 @@ impl Api {
 
     #[allow(dead_code)]
     pub async fn get_setlists(&self) -> Result<Vec<Setlist>, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .list_setlists(ListQuery::default())
-            .await
-            .map_err(|e| self.handle_error(e))
+        if ApiError::check_and_notify_offline(OperationType::Read) {
+            return Ok(self.replica.all(idb::SETLISTS).await);
+        }
+        match self.client.list_setlists(ListQuery::default()).await {
+            Err(e) if unreachable(&e) => Ok(self.replica.all(idb::SETLISTS).await),
+            other => other.map_err(|e| self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
     pub async fn get_setlist(&self, id: &str) -> Result<Setlist, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .get_setlist(id)
-            .await
-            .map_err(|e| self.handle_error(e))
+        let id = self.outbox.resolve(id);
+        if ApiError::check_and_notify_offline(OperationType::Read)
+            || self.outbox.has_pending(&id).await
+        {
+            return self.replica_record(idb::SETLISTS, &id).await;
+        }
+        match self.client.get_setlist_tagged(&id).await {
+            Ok(setlist) => {
+                self.replica.put_tagged(idb::SETLISTS, &id, &setlist).await;
+                Ok(setlist.value)
+            }
+            Err(e) if unreachable(&e) => self.replica_record(idb::SETLISTS, &id).await,
+            Err(e) => Err(self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
     pub async fn get_setlist_songs(&self, id: &str) -> Result<Vec<Song>, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Read);
-        self.client
-            .get_setlist_songs(id, ListQuery::default())
-            .await
-            .map_err(|e| self.handle_error(e))
+        let id = self.outbox.resolve(id);
+        if ApiError::check_and_notify_offline(OperationType::Read)
+            || self.outbox.has_pending(&id).await
+        {
+            let setlist: Setlist = self.replica_record(idb::SETLISTS, &id).await?;
+            return Ok(self.replica_linked_songs(&setlist.songs).await);
+        }
+        match self
+            .client
+            .get_setlist_songs(&id, ListQuery::default())
+            .await
+        {
+            Err(e) if unreachable(&e) => {
+                let setlist: Setlist = self.replica_record(idb::SETLISTS, &id).await?;
+                Ok(self.replica_linked_songs(&setlist.songs).await)
+            }
+            other => other.map_err(|e| self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
@@ -450,11 +606,23 @@ impl Api {
 
     #[allow(dead_code)]
     pub async fn create_setlist(&self, payload: &CreateSetlist) -> Result<Setlist, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Write);
-        self.client
-            .create_setlist(payload.clone())
-            .await
-            .map_err(|e| self.handle_error(e))
+        let write = PendingWrite::CreateSetlist {
+            id: self.outbox.next_local_id(),
+            payload: payload.clone(),
+        };
+        if ApiError::check_and_notify_offline(OperationType::QueuedWrite) {
+            return self.queue(write).await;
+        }
+        match self.client.create_setlist_tagged(payload.clone()).await {
+            Ok(setlist) => {
+                self.replica
+                    .put_tagged(idb::SETLISTS, &setlist.value.id, &setlist)
+                    .await;
+                Ok(setlist.value)
+            }
+            Err(e) if unreachable(&e) => self.queue(write).await,
+            Err(e) => Err(self.handle_error(e)),
+        }
     }
 
     #[allow(dead_code)]
@@ -463,20 +631,58 @@ impl Api {
         id: &str,
         payload: &UpdateSetlist,
     ) -> Result<Setlist, ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Write);
-        self.client
-            .update_setlist(id, payload.clone())
-            .await
-            .map_err(|e| self.handle_error(e))
+        let id = self.outbox.resolve(id);
+        let write = PendingWrite::UpdateSetlist {
+            id: id.clone(),
+            payload: payload.clone(),
+        };
+        if ApiError::check_and_notify_offline(OperationType::QueuedWrite)
+            || self.outbox.has_pending(&id).await
+        {
+            return self.queue(write).await;
+        }
+        let etag = self.replica.etag(idb::SETLISTS, &id).await;
+        let result = self
+            .client
+            .update_setlist_tagged(&id, payload.clone(), etag.as_deref())
+            .await;
+        match result {
+            Ok(setlist) => {
+                self.replica
+                    .put_tagged(idb::SETLISTS, &setlist.value.id, &setlist)
+                    .await;
+                Ok(setlist.value)
+            }
+            Err(e) if unreachable(&e) => self.queue(write).await,
+            Err(e) => Err(self.write_failed(write, e).await),
+        }
     }
 
     #[allow(dead_code)]
     pub async fn delete_setlist(&self, id: &str) -> Result<(), ApiError> {
-        ApiError::check_and_notify_offline(OperationType::Write);
-        self.client
-            .delete_setlist(id)
-            .await
-            .map_err(|e| self.handle_error(e))
+        let id = self.outbox.resolve(id);
+        let write = PendingWrite::DeleteSetlist { id: id.clone() };
+        if ApiError::check_and_notify_offline(OperationType::QueuedWrite)
+            || self.outbox.has_pending(&id).await
+        {
+            self.enqueue(write).await;
+            return Ok(());
+        }
+        let result = match self.replica.etag(idb::SETLISTS, &id).await {
+            Some(etag) => self.client.delete_setlist_if_match(&id, &etag).await,
+            None => self.client.delete_setlist(&id).await,
+        };
+        match result {
+            Ok(()) => {
+                self.replica.remove(idb::SETLISTS, &id).await;
+                Ok(())
+            }
+            Err(e) if unreachable(&e) => {
+                self.enqueue(write).await;
+                Ok(())
+            }
+            Err(e) => Err(self.write_failed(write, e).await),
+        }
     }
 
     #[allow(dead_code)]
@@ -523,4 +729,139 @@ impl Api {
             .await
             .map_err(|e| self.handle_error(e))
     }
+
+    /// Upload queued offline writes, then pull library changes into the replica.
+    ///
+    /// Runs on start-up and whenever the browser comes back online. Errors are not surfaced:
+    /// anything that did not go through stays queued for the next attempt. A call made while a
+    /// pass is running makes that pass go round once more instead of replaying concurrently.
+    pub async fn synchronize(&self) {
+        if ApiError::is_offline() {
+            return;
+        }
+        if self.syncing.replace(true) {
+            self.resync.set(true);
+            return;
+        }
+        loop {
+            self.resync.set(false);
+            self.sync_pass().await;
+            if !self.resync.get() {
+                break;
+            }
+        }
+        self.syncing.set(false);
+    }
+
+    async fn sync_pass(&self) {
+        let report = self.outbox.replay(&self.client).await;
+        for (write, message) in &report.rejected {
+            show_error(
+                "Offline change rejected",
+                &format!(
+                    "The {} for {} was refused: {message}",
+                    write.describe(),
+                    write.target_id()
+                ),
+            );
+        }
+        if report.sent > 0 {
+            show_success(
+                "Back online",
+                &format!("Uploaded {} change(s) made while offline.", report.sent),
+            );
+        }
+        if !report.interrupted {
+            let cursor = self.replica.cursor().await;
+            if let Ok(changes) = self.client.sync(cursor.as_deref()).await {
+                self.replica.apply(&changes).await;
+            }
+        }
+        self.publish_conflicts().await;
+    }
+
+    async fn publish_conflicts(&self) {
+        self.on_conflicts.emit(self.outbox.conflicts().await);
+    }
+
+    /// Settle a conflict either by sending the local change again on top of the server's copy
+    /// (`keep_mine`) or by discarding it.
+    pub async fn resolve_conflict(&self, key: &str, keep_mine: bool) {
+        if keep_mine {
+            self.outbox.keep_mine(key).await;
+        } else {
+            self.outbox.use_server(key).await;
+        }
+        self.synchronize().await;
+        self.publish_conflicts().await;
+    }
+
+    async fn enqueue(&self, write: PendingWrite) {
+        self.outbox.queue(write).await;
+        self.synchronize().await;
+    }
+
+    /// Queue `write` and return the record as the replica now holds it.
+    async fn queue<T: DeserializeOwned>(&self, write: PendingWrite) -> Result<T, ApiError> {
+        let store = write.store();
+        let id = write.target_id().to_string();
+        self.enqueue(write).await;
+        self.replica_record(store, &self.outbox.resolve(&id)).await
+    }
+
+    /// Map a failed write to an error, parking it in the outbox as a conflict on `412`.
+    async fn write_failed(&self, write: PendingWrite, err: NetworkClientError) -> ApiError {
+        let error = self.handle_error(err);
+        if matches!(error, ApiError::PreconditionFailed(_)) {
+            show_warning(
+                "Changed elsewhere",
+                "Someone else saved a newer version. Choose which one to keep.",
+            );
+            // Replay hits the same 412, fetches the server copy and raises the merge dialog.
+            self.enqueue(write).await;
+        }
+        error
+    }
+
+    async fn replica_record<T: DeserializeOwned>(
+        &self,
+        store: &str,
+        id: &str,
+    ) -> Result<T, ApiError> {
+        self.replica
+            .get(store, id)
+            .await
+            .ok_or_else(|| ApiError::Network(format!("{id} is not available offline")))
+    }
+
+    async fn replica_songs(&self, query: &SongListQuery) -> Vec<Song> {
+        let needle = query.q.as_deref().unwrap_or_default().to_lowercase();
+        let mut songs: Vec<Song> = self.replica.all(idb::SONGS).await;
+        songs.retain(|song| {
+            song.data.title().to_lowercase().contains(&needle)
+                && query
+                    .lang
+                    .as_ref()
+                    .is_none_or(|lang| song.data.languages.contains(lang))
+        });
+        if let Some(page_size) = query.page_size {
+            let page = query.page.unwrap_or(0) as usize;
+            songs = songs
+                .into_iter()
+                .skip(page * page_size as usize)
+                .take(page_size as usize)
+                .collect();
+        }
+        songs
+    }
+
+    async fn replica_linked_songs(&self, links: &[SongLink]) -> Vec<Song> {
+        let mut songs = Vec::with_capacity(links.len());
+        for link in links {
+            if let Some(song) = self.replica.get(idb::SONGS, &link.id).await {
+                songs.push(song);
+            }
+        }
+        songs
+    }
 }
@@ -7,6 +7,8 @@ pub enum ApiError {
     Unauthorized(String),
     Forbidden(String),
     Conflict(String),
+    /// `412`: the record changed on the server since it was loaded.
+    PreconditionFailed(String),
     InternalServerError(String),
     Network(String),
 }
@@ -15,6 +17,8 @@ pub enum ApiError {
 pub enum OperationType {
     Read,
     Write,
+    /// A song or setlist write that is kept on the device and uploaded on reconnect.
+    QueuedWrite,
 }
 
 impl ApiError {
@@ -24,39 +28,50 @@ impl ApiError {
             401 => Self::Unauthorized(msg),
             403 => Self::Forbidden(msg),
             409 => Self::Conflict(msg),
+            412 => Self::PreconditionFailed(msg),
             500 => Self::InternalServerError(msg),
             _ => Self::Network(msg),
         }
     }
 
-    pub fn check_and_notify_offline(operation_type: OperationType) -> bool {
-        if let Some(window) = web_sys::window() {
-            let navigator = window.navigator();
-            // Use js_sys to access the onLine property
-            let on_line = js_sys::Reflect::get(&navigator, &"onLine".into())
-                .ok()
-                .and_then(|val| val.as_bool())
-                .unwrap_or(true);
+    pub fn is_offline() -> bool {
+        let Some(window) = web_sys::window() else {
+            return false;
+        };
+        let navigator = window.navigator();
+        // Use js_sys to access the onLine property
+        let on_line = js_sys::Reflect::get(&navigator, &"onLine".into())
+            .ok()
+            .and_then(|val| val.as_bool())
+            .unwrap_or(true);
+        !on_line
+    }
 
-            if !on_line {
-                match operation_type {
-                    OperationType::Read => {
-                        show_warning(
-                            "Offline",
-                            "You are currently offline. Some data may not be available.",
-                        );
-                    }
-                    OperationType::Write => {
-                        show_error(
-                            "Offline",
-                            "Cannot modify data while offline. Please check your connection.",
-                        );
-                    }
-                }
-                return true;
+    pub fn check_and_notify_offline(operation_type: OperationType) -> bool {
+        if !Self::is_offline() {
+            return false;
+        }
+        match operation_type {
+            OperationType::Read => {
+                show_warning(
+                    "Offline",
+                    "You are currently offline. Some data may not be available.",
+                );
+            }
+            OperationType::Write => {
+                show_error(
+                    "Offline",
+                    "Cannot modify data while offline. Please check your connection.",
+                );
+            }
+            OperationType::QueuedWrite => {
+                show_warning(
+                    "Offline",
+                    "Saved on this device. Your changes will be uploaded once you are back online.",
+                );
             }
         }
-        false
+        true
     }
 }
 
@@ -90,6 +105,7 @@ impl std::fmt::Display for ApiError {
             ApiError::Unauthorized(msg) => write!(f, "Unauthorized: {msg}"),
             ApiError::Forbidden(msg) => write!(f, "Forbidden: {msg}"),
             ApiError::Conflict(msg) => write!(f, "Conflict: {msg}"),
+            ApiError::PreconditionFailed(msg) => write!(f, "Precondition Failed: {msg}"),
             ApiError::InternalServerError(msg) => write!(f, "Internal Server Error: {msg}"),
             ApiError::Network(msg) => write!(f, "Network Error: {msg}"),
         }
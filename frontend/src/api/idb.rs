@@ -0,0 +1,154 @@
+//! Minimal promise-based wrapper around IndexedDB.
+//!
+//! Every value is stored as a JSON string under an explicit string key, so callers only deal in
+//! `serde` types. Each call opens its own transaction; IndexedDB commits a transaction as soon as
+//! it has no pending requests, so a transaction must never be held across an `.await`.
+
+use js_sys::{Array, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransaction, IdbTransactionMode};
+
+const DB_NAME: &str = "worshipviewer";
+const DB_VERSION: u32 = 2;
+
+pub const SONGS: &str = "songs";
+pub const SETLISTS: &str = "setlists";
+pub const COLLECTIONS: &str = "collections";
+pub const BLOBS: &str = "blobs";
+pub const OUTBOX: &str = "outbox";
+pub const META: &str = "meta";
+/// Server `ETag` of each replica record, keyed `<store>/<id>`.
+pub const ETAGS: &str = "etags";
+
+/// Stores holding the library replica (cleared on a full sync).
+pub const LIBRARY_STORES: [&str; 4] = [SONGS, SETLISTS, COLLECTIONS, BLOBS];
+const ALL_STORES: [&str; 7] = [SONGS, SETLISTS, COLLECTIONS, BLOBS, OUTBOX, META, ETAGS];
+
+#[derive(Clone)]
+pub struct Database {
+    db: IdbDatabase,
+}
+
+/// Resolve with `request.result` once the request succeeds.
+fn request_done(request: &IdbRequest) -> JsFuture {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let req = request.clone();
+        let on_success = Closure::once_into_js(move |_: JsValue| {
+            let _ = resolve.call1(&JsValue::NULL, &req.result().unwrap_or(JsValue::UNDEFINED));
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        let on_error = Closure::once_into_js(move |_: JsValue| {
+            let _ = reject.call1(&JsValue::NULL, &"IndexedDB request failed".into());
+        });
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise)
+}
+
+/// Resolve once every request issued on `tx` has been committed.
+fn transaction_done(tx: &IdbTransaction) -> JsFuture {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_complete = Closure::once_into_js(move |_: JsValue| {
+            let _ = resolve.call0(&JsValue::NULL);
+        });
+        tx.set_oncomplete(Some(on_complete.unchecked_ref()));
+        let on_abort = Closure::once_into_js(move |_: JsValue| {
+            let _ = reject.call1(&JsValue::NULL, &"IndexedDB transaction aborted".into());
+        });
+        tx.set_onabort(Some(on_abort.unchecked_ref()));
+    });
+    JsFuture::from(promise)
+}
+
+impl Database {
+    pub async fn open() -> Result<Self, JsValue> {
+        let factory = web_sys::window()
+            .ok_or_else(|| JsValue::from_str("no window"))?
+            .indexed_db()?
+            .ok_or_else(|| JsValue::from_str("IndexedDB is not available"))?;
+        let request = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+
+        let upgrading = request.clone();
+        let on_upgrade = Closure::once_into_js(move |_: JsValue| {
+            let Ok(result) = upgrading.result() else {
+                return;
+            };
+            let db: IdbDatabase = result.unchecked_into();
+            let names = db.object_store_names();
+            // Records cached by version 1 came without ETags; dropping the sync cursor makes the
+            // next sync a full one, which brings them.
+            if names.contains(META) && !names.contains(ETAGS) {
+                if let Some(Ok(meta)) = upgrading.transaction().map(|tx| tx.object_store(META)) {
+                    let _ = meta.clear();
+                }
+            }
+            for store in ALL_STORES {
+                if !names.contains(store) {
+                    let _ = db.create_object_store(store);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+        let db = request_done(&request).await?;
+        Ok(Self {
+            db: db.unchecked_into(),
+        })
+    }
+
+    fn store(
+        &self,
+        name: &str,
+        mode: IdbTransactionMode,
+    ) -> Result<(IdbTransaction, IdbObjectStore), JsValue> {
+        let tx = self.db.transaction_with_str_and_mode(name, mode)?;
+        let store = tx.object_store(name)?;
+        Ok((tx, store))
+    }
+
+    pub async fn get(&self, store: &str, key: &str) -> Result<Option<String>, JsValue> {
+        let (_, store) = self.store(store, IdbTransactionMode::Readonly)?;
+        let value = request_done(&store.get(&JsValue::from_str(key))?).await?;
+        Ok(value.as_string())
+    }
+
+    /// All values of `store`, in key order.
+    pub async fn get_all(&self, store: &str) -> Result<Vec<String>, JsValue> {
+        let (_, store) = self.store(store, IdbTransactionMode::Readonly)?;
+        let values: Array = request_done(&store.get_all()?).await?.unchecked_into();
+        Ok(values.iter().filter_map(|v| v.as_string()).collect())
+    }
+
+    pub async fn put(&self, store: &str, entries: &[(String, String)]) -> Result<(), JsValue> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let (tx, store) = self.store(store, IdbTransactionMode::Readwrite)?;
+        for (key, value) in entries {
+            store.put_with_key(&JsValue::from_str(value), &JsValue::from_str(key))?;
+        }
+        transaction_done(&tx).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, store: &str, keys: &[String]) -> Result<(), JsValue> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let (tx, store) = self.store(store, IdbTransactionMode::Readwrite)?;
+        for key in keys {
+            store.delete(&JsValue::from_str(key))?;
+        }
+        transaction_done(&tx).await?;
+        Ok(())
+    }
+
+    pub async fn clear(&self, store: &str) -> Result<(), JsValue> {
+        let (tx, store) = self.store(store, IdbTransactionMode::Readwrite)?;
+        store.clear()?;
+        transaction_done(&tx).await?;
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
 mod api;
 pub use api::Api;
 
+mod idb;
+mod outbox;
+pub use outbox::{OutboxEntry, PendingWrite, ServerCopy};
+mod replica;
+
 mod provider;
-pub use provider::{use_api, ApiProvider};
+pub use provider::{use_api, use_library_version, ApiProvider, LibraryVersion};
 
 #[allow(unused_imports)]
 pub use shared::auth::otp::{OtpRequest, OtpVerify};
@@ -12,3 +17,4 @@ pub use shared::error::ErrorResponse;
 pub use shared::user::{CreateUser, SessionBody, User};
 
 mod error;
+pub use error::ApiError;
@@ -0,0 +1,562 @@
+//! Song and setlist writes made while offline, persisted until they reach the server.
+//!
+//! Each entry remembers the ETag the server sent for the copy it was based on. Replay sends it
+//! as `If-Match`, so an edit made on top of a copy someone else has since changed comes back as
+//! `412` and is parked as a conflict for the user to resolve instead of overwriting their work.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use shared::api::ApiClient;
+use shared::error::NetworkClientError;
+use shared::net::{HttpClient, Tagged};
+use shared::setlist::{CreateSetlist, Setlist, UpdateSetlist};
+use shared::song::{CreateSong, Song, UpdateSong};
+
+use super::idb;
+use super::replica::Replica;
+
+const LOCAL_ID_PREFIX: &str = "offline-";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PendingWrite {
+    /// `id` is the temporary local id until the server assigns one.
+    CreateSong {
+        id: String,
+        payload: CreateSong,
+    },
+    UpdateSong {
+        id: String,
+        payload: UpdateSong,
+    },
+    DeleteSong {
+        id: String,
+    },
+    CreateSetlist {
+        id: String,
+        payload: CreateSetlist,
+    },
+    UpdateSetlist {
+        id: String,
+        payload: UpdateSetlist,
+    },
+    DeleteSetlist {
+        id: String,
+    },
+}
+
+impl PendingWrite {
+    pub fn target_id(&self) -> &str {
+        match self {
+            Self::CreateSong { id, .. }
+            | Self::UpdateSong { id, .. }
+            | Self::DeleteSong { id }
+            | Self::CreateSetlist { id, .. }
+            | Self::UpdateSetlist { id, .. }
+            | Self::DeleteSetlist { id } => id,
+        }
+    }
+
+    /// Short human-readable name of the operation, e.g. `song update`.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::CreateSong { .. } => "new song",
+            Self::UpdateSong { .. } => "song update",
+            Self::DeleteSong { .. } => "song deletion",
+            Self::CreateSetlist { .. } => "new setlist",
+            Self::UpdateSetlist { .. } => "setlist update",
+            Self::DeleteSetlist { .. } => "setlist deletion",
+        }
+    }
+
+    pub fn is_song(&self) -> bool {
+        matches!(
+            self,
+            Self::CreateSong { .. } | Self::UpdateSong { .. } | Self::DeleteSong { .. }
+        )
+    }
+
+    fn is_create(&self) -> bool {
+        matches!(self, Self::CreateSong { .. } | Self::CreateSetlist { .. })
+    }
+
+    pub fn store(&self) -> &'static str {
+        if self.is_song() {
+            idb::SONGS
+        } else {
+            idb::SETLISTS
+        }
+    }
+
+    /// Point references to a locally created record at the id the server gave it.
+    fn remap(&mut self, local_id: &str, server_id: &str) {
+        match self {
+            Self::CreateSong { id, .. }
+            | Self::UpdateSong { id, .. }
+            | Self::DeleteSong { id }
+            | Self::DeleteSetlist { id } => {
+                if id == local_id {
+                    *id = server_id.to_string();
+                }
+            }
+            Self::CreateSetlist { id, payload } => {
+                if id == local_id {
+                    *id = server_id.to_string();
+                }
+                remap_links(&mut payload.songs, local_id, server_id);
+            }
+            Self::UpdateSetlist { id, payload } => {
+                if id == local_id {
+                    *id = server_id.to_string();
+                }
+                remap_links(&mut payload.songs, local_id, server_id);
+            }
+        }
+    }
+}
+
+fn remap_links(links: &mut [shared::song::Link], local_id: &str, server_id: &str) {
+    for link in links.iter_mut().filter(|link| link.id == local_id) {
+        link.id = server_id.to_string();
+    }
+}
+
+/// The server's side of a conflict, fetched when replay was rejected with `412`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ServerCopy {
+    Song(Song),
+    Setlist(Setlist),
+    /// The record was deleted on the server (or is no longer visible to the caller).
+    Deleted,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    /// Sortable IndexedDB key; entries replay in key order.
+    pub key: String,
+    pub write: PendingWrite,
+    /// ETag of the server copy this write was made against; `None` for creates.
+    pub base_etag: Option<String>,
+    #[serde(default)]
+    pub conflict: Option<ServerCopy>,
+    /// ETag of the server copy in `conflict`.
+    #[serde(default)]
+    pub conflict_etag: Option<String>,
+}
+
+/// Outcome of one replay pass.
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    pub sent: usize,
+    /// Writes the server refused for a reason other than a conflict, with its message.
+    pub rejected: Vec<(PendingWrite, String)>,
+    /// Replay stopped early (connection lost or session expired); the rest stays queued.
+    pub interrupted: bool,
+}
+
+enum Sent {
+    Song(Tagged<Song>),
+    Setlist(Tagged<Setlist>),
+    Deleted,
+}
+
+fn status(err: &NetworkClientError) -> Option<u16> {
+    match err {
+        NetworkClientError::RequestFailed { status, .. } => *status,
+        _ => None,
+    }
+}
+
+fn message(err: &NetworkClientError) -> String {
+    match err {
+        NetworkClientError::RequestFailed { message, .. } => message.clone(),
+        other => format!("{other:?}"),
+    }
+}
+
+async fn send<H: HttpClient>(
+    client: &ApiClient<H>,
+    write: &PendingWrite,
+    base_etag: Option<&str>,
+) -> Result<Sent, NetworkClientError> {
+    match (write, base_etag) {
+        (PendingWrite::CreateSong { payload, .. }, _) => client
+            .create_song_tagged(payload.clone())
+            .await
+            .map(Sent::Song),
+        (PendingWrite::UpdateSong { id, payload }, etag) => client
+            .update_song_tagged(id, payload.clone(), etag)
+            .await
+            .map(Sent::Song),
+        (PendingWrite::DeleteSong { id }, Some(etag)) => client
+            .delete_song_if_match(id, etag)
+            .await
+            .map(|_| Sent::Deleted),
+        (PendingWrite::DeleteSong { id }, None) => {
+            client.delete_song(id).await.map(|_| Sent::Deleted)
+        }
+        (PendingWrite::CreateSetlist { payload, .. }, _) => client
+            .create_setlist_tagged(payload.clone())
+            .await
+            .map(Sent::Setlist),
+        (PendingWrite::UpdateSetlist { id, payload }, etag) => client
+            .update_setlist_tagged(id, payload.clone(), etag)
+            .await
+            .map(Sent::Setlist),
+        (PendingWrite::DeleteSetlist { id }, Some(etag)) => client
+            .delete_setlist_if_match(id, etag)
+            .await
+            .map(|_| Sent::Deleted),
+        (PendingWrite::DeleteSetlist { id }, None) => {
+            client.delete_setlist(id).await.map(|_| Sent::Deleted)
+        }
+    }
+}
+
+/// The server's current copy of the record `write` targets, with its ETag.
+async fn fetch_server_copy<H: HttpClient>(
+    client: &ApiClient<H>,
+    write: &PendingWrite,
+) -> Result<(ServerCopy, Option<String>), NetworkClientError> {
+    let id = write.target_id();
+    let fetched = if write.is_song() {
+        client
+            .get_song_tagged(id)
+            .await
+            .map(|tagged| (ServerCopy::Song(tagged.value), tagged.etag))
+    } else {
+        client
+            .get_setlist_tagged(id)
+            .await
+            .map(|tagged| (ServerCopy::Setlist(tagged.value), tagged.etag))
+    };
+    match fetched {
+        Err(err) if status(&err) == Some(404) => Ok((ServerCopy::Deleted, None)),
+        other => other,
+    }
+}
+
+pub struct Outbox {
+    replica: Rc<Replica>,
+    counter: Cell<u32>,
+    /// Local ids of records created offline, mapped to the id the server assigned on replay.
+    remapped: RefCell<HashMap<String, String>>,
+}
+
+impl Outbox {
+    pub fn new(replica: Rc<Replica>) -> Self {
+        Self {
+            replica,
+            counter: Cell::new(0),
+            remapped: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn next_key(&self) -> String {
+        let n = self.counter.get();
+        self.counter.set(n.wrapping_add(1) % 10_000);
+        format!("{:015}-{:04}", js_sys::Date::now() as u64, n)
+    }
+
+    /// Allocate a key and a matching temporary id for a record created offline.
+    pub fn next_local_id(&self) -> String {
+        format!("{LOCAL_ID_PREFIX}{}", self.next_key())
+    }
+
+    /// The id to use for `id`, following a local id to its server id once it has been replayed.
+    pub fn resolve(&self, id: &str) -> String {
+        self.remapped
+            .borrow()
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    pub async fn entries(&self) -> Vec<OutboxEntry> {
+        self.replica.all(idb::OUTBOX).await
+    }
+
+    pub async fn conflicts(&self) -> Vec<OutboxEntry> {
+        let mut entries = self.entries().await;
+        entries.retain(|entry| entry.conflict.is_some());
+        entries
+    }
+
+    /// Whether writes to `id` are still waiting to be replayed, so a new write must queue
+    /// behind them rather than go straight to the server.
+    pub async fn has_pending(&self, id: &str) -> bool {
+        self.entries()
+            .await
+            .iter()
+            .any(|entry| entry.conflict.is_none() && entry.write.target_id() == id)
+    }
+
+    async fn save(&self, entry: &OutboxEntry) {
+        self.replica.put(idb::OUTBOX, &entry.key, entry).await;
+    }
+
+    async fn remove(&self, key: &str) {
+        self.replica.remove(idb::OUTBOX, key).await;
+    }
+
+    /// Queue `write`, folding it into any write to the same record that has not been sent yet,
+    /// and apply it to the replica so offline reads see it.
+    pub async fn queue(&self, write: PendingWrite) {
+        let store = write.store();
+        let id = write.target_id().to_string();
+        let previous = self
+            .entries()
+            .await
+            .into_iter()
+            .find(|entry| entry.conflict.is_none() && entry.write.target_id() == id);
+
+        let base_etag = match &previous {
+            Some(previous) => previous.base_etag.clone(),
+            None if write.is_create() => None,
+            None => self.replica.etag(store, &id).await,
+        };
+
+        let created_offline = previous
+            .as_ref()
+            .is_some_and(|previous| previous.write.is_create());
+        let folded = match (write, created_offline) {
+            (PendingWrite::UpdateSong { id, payload }, true) => Some(PendingWrite::CreateSong {
+                id,
+                payload: payload.into(),
+            }),
+            (PendingWrite::UpdateSetlist { id, payload }, true) => {
+                Some(PendingWrite::CreateSetlist {
+                    id,
+                    payload: payload.into(),
+                })
+            }
+            // Never reached the server, so there is nothing left to delete.
+            (PendingWrite::DeleteSong { .. } | PendingWrite::DeleteSetlist { .. }, true) => None,
+            (write, _) => Some(write),
+        };
+
+        if let Some(previous) = &previous {
+            self.remove(&previous.key).await;
+        }
+        if let Some(write) = &folded {
+            let entry = OutboxEntry {
+                key: previous
+                    .map(|previous| previous.key)
+                    .unwrap_or_else(|| self.next_key()),
+                write: write.clone(),
+                base_etag,
+                conflict: None,
+                conflict_etag: None,
+            };
+            self.save(&entry).await;
+        }
+        self.apply_locally(folded.as_ref(), store, &id).await;
+    }
+
+    /// Mirror a queued write into the replica.
+    async fn apply_locally(&self, write: Option<&PendingWrite>, store: &str, id: &str) {
+        match write {
+            Some(PendingWrite::CreateSong { payload, .. }) => {
+                let mut song = Song::from(payload.clone());
+                song.id = id.to_string();
+                song.owner = payload.owner.clone().unwrap_or_default();
+                self.replica.put(store, id, &song).await;
+            }
+            Some(PendingWrite::UpdateSong { payload, .. }) => {
+                let mut song = self
+                    .replica
+                    .get::<Song>(store, id)
+                    .await
+                    .unwrap_or_else(|| Song {
+                        id: id.to_string(),
+                        ..Song::from(CreateSong::default())
+                    });
+                song.not_a_song = payload.not_a_song;
+                song.blobs = payload.blobs.clone();
+                song.data = payload.data.clone();
+                if let Some(owner) = &payload.owner {
+                    song.owner = owner.clone();
+                }
+                self.replica.put(store, id, &song).await;
+            }
+            Some(PendingWrite::CreateSetlist { payload, .. }) => {
+                let setlist = Setlist {
+                    id: id.to_string(),
+                    owner: payload.owner.clone().unwrap_or_default(),
+                    title: payload.title.clone(),
+                    songs: payload.songs.clone(),
+                };
+                self.replica.put(store, id, &setlist).await;
+            }
+            Some(PendingWrite::UpdateSetlist { payload, .. }) => {
+                let owner = match &payload.owner {
+                    Some(owner) => owner.clone(),
+                    None => self
+                        .replica
+                        .get::<Setlist>(store, id)
+                        .await
+                        .map(|setlist| setlist.owner)
+                        .unwrap_or_default(),
+                };
+                let setlist = Setlist {
+                    id: id.to_string(),
+                    owner,
+                    title: payload.title.clone(),
+                    songs: payload.songs.clone(),
+                };
+                self.replica.put(store, id, &setlist).await;
+            }
+            Some(PendingWrite::DeleteSong { .. } | PendingWrite::DeleteSetlist { .. }) | None => {
+                self.replica.remove(store, id).await;
+            }
+        }
+    }
+
+    /// Send queued writes in order. Conflicts stay in the outbox until resolved.
+    pub async fn replay<H: HttpClient>(&self, client: &ApiClient<H>) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        let mut entries = self.entries().await;
+        let mut index = 0;
+        while index < entries.len() {
+            let entry = entries[index].clone();
+            index += 1;
+            if entry.conflict.is_some() {
+                continue;
+            }
+            let store = entry.write.store();
+            match send(client, &entry.write, entry.base_etag.as_deref()).await {
+                Ok(sent) => {
+                    report.sent += 1;
+                    self.remove(&entry.key).await;
+                    let local_id = entry.write.target_id();
+                    let server_id = match sent {
+                        Sent::Song(song) => {
+                            self.replica.put_tagged(store, &song.value.id, &song).await;
+                            song.value.id
+                        }
+                        Sent::Setlist(setlist) => {
+                            self.replica
+                                .put_tagged(store, &setlist.value.id, &setlist)
+                                .await;
+                            setlist.value.id
+                        }
+                        Sent::Deleted => continue,
+                    };
+                    if entry.write.is_create() && server_id != local_id {
+                        self.replica.remove(store, local_id).await;
+                        self.remapped
+                            .borrow_mut()
+                            .insert(local_id.to_string(), server_id.clone());
+                        for later in entries[index..].iter_mut() {
+                            let before = later.write.clone();
+                            later.write.remap(local_id, &server_id);
+                            if later.write != before {
+                                self.save(later).await;
+                            }
+                        }
+                    }
+                }
+                Err(err) => match status(&err) {
+                    Some(412) => match fetch_server_copy(client, &entry.write).await {
+                        Ok((server, etag)) => self.park_conflict(entry, server, etag).await,
+                        Err(_) => {
+                            report.interrupted = true;
+                            break;
+                        }
+                    },
+                    // Deleting something that is already gone is a success.
+                    Some(404)
+                        if matches!(
+                            entry.write,
+                            PendingWrite::DeleteSong { .. } | PendingWrite::DeleteSetlist { .. }
+                        ) =>
+                    {
+                        report.sent += 1;
+                        self.remove(&entry.key).await;
+                    }
+                    Some(404) => self.park_conflict(entry, ServerCopy::Deleted, None).await,
+                    None | Some(401) | Some(408) | Some(429) | Some(500..) => {
+                        report.interrupted = true;
+                        break;
+                    }
+                    Some(_) => {
+                        self.remove(&entry.key).await;
+                        report.rejected.push((entry.write, message(&err)));
+                    }
+                },
+            }
+        }
+        report
+    }
+
+    async fn park_conflict(
+        &self,
+        mut entry: OutboxEntry,
+        server: ServerCopy,
+        etag: Option<String>,
+    ) {
+        entry.conflict = Some(server);
+        entry.conflict_etag = etag;
+        self.save(&entry).await;
+    }
+
+    /// Resolve a conflict by re-queueing the local change on top of the server's current copy.
+    pub async fn keep_mine(&self, key: &str) {
+        let Some(mut entry) = self.find(key).await else {
+            return;
+        };
+        let Some(server) = entry.conflict.take() else {
+            return;
+        };
+        entry.base_etag = entry.conflict_etag.take();
+        if server == ServerCopy::Deleted {
+            entry.write = match entry.write {
+                // Song PUT recreates a missing song, setlist PUT does not.
+                PendingWrite::UpdateSetlist { id, payload } => PendingWrite::CreateSetlist {
+                    id,
+                    payload: payload.into(),
+                },
+                PendingWrite::DeleteSong { .. } | PendingWrite::DeleteSetlist { .. } => {
+                    self.remove(key).await;
+                    return;
+                }
+                write => write,
+            };
+        }
+        self.save(&entry).await;
+    }
+
+    /// Resolve a conflict by dropping the local change and taking the server's copy.
+    pub async fn use_server(&self, key: &str) {
+        let Some(entry) = self.find(key).await else {
+            return;
+        };
+        self.remove(key).await;
+        let store = entry.write.store();
+        let id = entry.write.target_id();
+        let etag = entry.conflict_etag;
+        match entry.conflict {
+            Some(ServerCopy::Song(value)) => {
+                self.replica
+                    .put_tagged(store, id, &Tagged { value, etag })
+                    .await
+            }
+            Some(ServerCopy::Setlist(value)) => {
+                self.replica
+                    .put_tagged(store, id, &Tagged { value, etag })
+                    .await
+            }
+            Some(ServerCopy::Deleted) => self.replica.remove(store, id).await,
+            None => {}
+        }
+    }
+
+    async fn find(&self, key: &str) -> Option<OutboxEntry> {
+        self.replica.get(idb::OUTBOX, key).await
+    }
+}
@@ -1,7 +1,20 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gloo::events::EventListener;
+use gloo::timers::callback::Timeout;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use super::Api;
+use super::{Api, OutboxEntry};
+use crate::components::ConflictResolver;
+
+/// Quiet period after a change event before lists refetch, so a batch of writes refreshes once.
+const CHANGE_DEBOUNCE_MS: u32 = 300;
+
+/// Bumped whenever the server reports a library change; list pages refetch when it changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LibraryVersion(pub u32);
 
 #[derive(Properties, PartialEq)]
 pub struct ApiProviderProps {
@@ -12,19 +25,85 @@ pub struct ApiProviderProps {
 #[function_component(ApiProvider)]
 pub fn api_provider(props: &ApiProviderProps) -> Html {
     let navigator = use_navigator().unwrap();
+    let conflicts = use_state(Vec::<OutboxEntry>::new);
     let api = {
         let navigator = navigator.clone();
+        let set_conflicts = conflicts.setter();
         use_memo((), move |_| {
             let base_url = web_sys::window()
                 .and_then(|w| w.location().origin().ok())
                 .unwrap_or_default();
-            Api::new(navigator, base_url)
+            Api::new(
+                navigator,
+                base_url,
+                Callback::from(move |entries| set_conflicts.set(entries)),
+            )
         })
     };
 
+    // Replay offline writes and refresh the replica on start-up and on every reconnect.
+    {
+        let api = (*api).clone();
+        use_effect_with((), move |_| {
+            let initial = api.clone();
+            wasm_bindgen_futures::spawn_local(async move { initial.synchronize().await });
+            let listener = web_sys::window().map(|window| {
+                EventListener::new(&window, "online", move |_| {
+                    let api = api.clone();
+                    wasm_bindgen_futures::spawn_local(async move { api.synchronize().await });
+                })
+            });
+            move || drop(listener)
+        });
+    }
+
+    // Live change notifications from `GET /api/v1/events`; `resync` means some were dropped.
+    let library_version = use_state(LibraryVersion::default);
+    {
+        let api = (*api).clone();
+        let set_version = library_version.setter();
+        use_effect_with((), move |_| {
+            let counter = Rc::new(Cell::new(0u32));
+            let pending = Rc::new(RefCell::new(None::<Timeout>));
+            let bump = Rc::new(move || {
+                let counter = counter.clone();
+                let set_version = set_version.clone();
+                *pending.borrow_mut() = Some(Timeout::new(CHANGE_DEBOUNCE_MS, move || {
+                    counter.set(counter.get().wrapping_add(1));
+                    set_version.set(LibraryVersion(counter.get()));
+                }));
+            });
+            let source = web_sys::EventSource::new("/api/v1/events").ok();
+            let listeners = source.as_ref().map(|source| {
+                let on_change = {
+                    let bump = bump.clone();
+                    EventListener::new(source, "change", move |_| bump())
+                };
+                let on_resync = EventListener::new(source, "resync", move |_| {
+                    let api = api.clone();
+                    let bump = bump.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        api.synchronize().await;
+                        bump();
+                    });
+                });
+                (on_change, on_resync)
+            });
+            move || {
+                drop(listeners);
+                if let Some(source) = source {
+                    source.close();
+                }
+            }
+        });
+    }
+
     html! {
         <ContextProvider<Api> context={(*api).clone()}>
-            { for props.children.iter() }
+            <ContextProvider<LibraryVersion> context={*library_version}>
+                { for props.children.iter() }
+                <ConflictResolver conflicts={(*conflicts).clone()} />
+            </ContextProvider<LibraryVersion>>
         </ContextProvider<Api>>
     }
 }
@@ -33,3 +112,8 @@ pub fn api_provider(props: &ApiProviderProps) -> Html {
 pub fn use_api() -> Api {
     use_context::<Api>().expect("Api context is missing")
 }
+
+#[hook]
+pub fn use_library_version() -> LibraryVersion {
+    use_context::<LibraryVersion>().unwrap_or_default()
+}
@@ -0,0 +1,211 @@
+//! Local copy of the caller's library, kept current through `GET /api/v1/sync`.
+//!
+//! Reads fall back to the replica while offline. Each record is kept with the `ETag` the server
+//! sent for it, which an edit made on top of that copy is later replayed with as `If-Match`.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+
+use shared::net::Tagged;
+use shared::sync::SyncChanges;
+use shared::team::TrashResourceType;
+
+use super::idb::{self, Database};
+
+const CURSOR_KEY: &str = "sync_cursor";
+
+#[derive(Default)]
+pub struct Replica {
+    db: RefCell<Option<Database>>,
+}
+
+fn log_failure(action: &str, err: JsValue) {
+    gloo::console::warn!(&format!("offline replica: {action} failed: {err:?}"));
+}
+
+fn store_for(resource_type: TrashResourceType) -> &'static str {
+    match resource_type {
+        TrashResourceType::Song => idb::SONGS,
+        TrashResourceType::Setlist => idb::SETLISTS,
+        TrashResourceType::Collection => idb::COLLECTIONS,
+        TrashResourceType::Blob => idb::BLOBS,
+    }
+}
+
+fn entries<T: Serialize>(items: &[T], id: fn(&T) -> &str) -> Vec<(String, String)> {
+    items
+        .iter()
+        .filter_map(|item| {
+            serde_json::to_string(item)
+                .ok()
+                .map(|json| (id(item).to_string(), json))
+        })
+        .collect()
+}
+
+fn etag_key(store: &str, id: &str) -> String {
+    format!("{store}/{id}")
+}
+
+/// `ETAGS` entries for the records of `store` that `etags` has a value for.
+fn etag_entries<T>(
+    store: &str,
+    items: &[T],
+    id: fn(&T) -> &str,
+    etags: &BTreeMap<String, String>,
+) -> Vec<(String, String)> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let etag = etags.get(id(item))?;
+            serde_json::to_string(etag)
+                .ok()
+                .map(|json| (etag_key(store, id(item)), json))
+        })
+        .collect()
+}
+
+impl Replica {
+    /// Open the database on first use. `None` when IndexedDB is unavailable (e.g. private mode).
+    pub async fn db(&self) -> Option<Database> {
+        if let Some(db) = self.db.borrow().clone() {
+            return Some(db);
+        }
+        match Database::open().await {
+            Ok(db) => {
+                *self.db.borrow_mut() = Some(db.clone());
+                Some(db)
+            }
+            Err(err) => {
+                log_failure("open", err);
+                None
+            }
+        }
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, store: &str, key: &str) -> Option<T> {
+        let db = self.db().await?;
+        match db.get(store, key).await {
+            Ok(json) => json.and_then(|json| serde_json::from_str(&json).ok()),
+            Err(err) => {
+                log_failure("read", err);
+                None
+            }
+        }
+    }
+
+    pub async fn all<T: DeserializeOwned>(&self, store: &str) -> Vec<T> {
+        let Some(db) = self.db().await else {
+            return Vec::new();
+        };
+        match db.get_all(store).await {
+            Ok(values) => values
+                .iter()
+                .filter_map(|json| serde_json::from_str(json).ok())
+                .collect(),
+            Err(err) => {
+                log_failure("read", err);
+                Vec::new()
+            }
+        }
+    }
+
+    pub async fn put<T: Serialize>(&self, store: &str, key: &str, value: &T) {
+        let (Some(db), Ok(json)) = (self.db().await, serde_json::to_string(value)) else {
+            return;
+        };
+        if let Err(err) = db.put(store, &[(key.to_string(), json)]).await {
+            log_failure("write", err);
+        }
+    }
+
+    /// Store a record fetched from or written to the server, with the `ETag` it came with.
+    pub async fn put_tagged<T: Serialize>(&self, store: &str, key: &str, tagged: &Tagged<T>) {
+        self.put(store, key, &tagged.value).await;
+        match &tagged.etag {
+            Some(etag) => self.put(idb::ETAGS, &etag_key(store, key), etag).await,
+            None => self.remove(idb::ETAGS, &etag_key(store, key)).await,
+        }
+    }
+
+    /// The server `ETag` of the record the replica holds, if one was sent with it.
+    pub async fn etag(&self, store: &str, key: &str) -> Option<String> {
+        self.get(idb::ETAGS, &etag_key(store, key)).await
+    }
+
+    pub async fn remove(&self, store: &str, key: &str) {
+        let Some(db) = self.db().await else {
+            return;
+        };
+        if let Err(err) = db.delete(store, &[key.to_string()]).await {
+            log_failure("delete", err);
+        }
+        if idb::LIBRARY_STORES.contains(&store) {
+            if let Err(err) = db.delete(idb::ETAGS, &[etag_key(store, key)]).await {
+                log_failure("delete", err);
+            }
+        }
+    }
+
+    pub async fn cursor(&self) -> Option<String> {
+        self.get(idb::META, CURSOR_KEY).await
+    }
+
+    /// Apply one sync response; the cursor is only stored once every change has been written.
+    pub async fn apply(&self, changes: &SyncChanges) {
+        let Some(db) = self.db().await else {
+            return;
+        };
+        let result: Result<(), JsValue> = async {
+            if changes.full {
+                for store in idb::LIBRARY_STORES {
+                    db.clear(store).await?;
+                }
+                db.clear(idb::ETAGS).await?;
+            }
+            db.put(idb::SONGS, &entries(&changes.songs, |s| &s.id))
+                .await?;
+            db.put(idb::SETLISTS, &entries(&changes.setlists, |s| &s.id))
+                .await?;
+            db.put(idb::COLLECTIONS, &entries(&changes.collections, |c| &c.id))
+                .await?;
+            db.put(idb::BLOBS, &entries(&changes.blobs, |b| &b.id))
+                .await?;
+            let mut etags = etag_entries(idb::SONGS, &changes.songs, |s| &s.id, &changes.etags);
+            etags.extend(etag_entries(
+                idb::SETLISTS,
+                &changes.setlists,
+                |s| &s.id,
+                &changes.etags,
+            ));
+            etags.extend(etag_entries(
+                idb::COLLECTIONS,
+                &changes.collections,
+                |c| &c.id,
+                &changes.etags,
+            ));
+            db.put(idb::ETAGS, &etags).await?;
+            for store in idb::LIBRARY_STORES {
+                let gone: Vec<String> = changes
+                    .deleted
+                    .iter()
+                    .filter(|tombstone| store_for(tombstone.resource_type) == store)
+                    .map(|tombstone| tombstone.resource_id.clone())
+                    .collect();
+                db.delete(store, &gone).await?;
+                let gone: Vec<String> = gone.iter().map(|id| etag_key(store, id)).collect();
+                db.delete(idb::ETAGS, &gone).await?;
+            }
+            let cursor = serde_json::to_string(&changes.cursor).unwrap_or_default();
+            db.put(idb::META, &[(CURSOR_KEY.to_string(), cursor)]).await
+        }
+        .await;
+        if let Err(err) = result {
+            log_failure("sync", err);
+        }
+    }
+}
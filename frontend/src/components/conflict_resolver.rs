@@ -0,0 +1,165 @@
+use crate::api::{use_api, OutboxEntry, PendingWrite, ServerCopy};
+use shared::song::{CreateSong, Link as SongLink};
+use stylist::Style;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ConflictResolverProps {
+    pub conflicts: Vec<OutboxEntry>,
+}
+
+/// Title and readable body of one side of a conflict.
+struct Version {
+    title: String,
+    body: String,
+}
+
+fn setlist_body(songs: &[SongLink]) -> String {
+    songs
+        .iter()
+        .enumerate()
+        .map(|(idx, link)| {
+            let nr = link.nr.clone().unwrap_or_else(|| (idx + 1).to_string());
+            format!("{nr}. {}", link.id)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn song_version(song: CreateSong) -> Version {
+    Version {
+        title: song.data.title().to_string(),
+        body: song.format_chord_pro(None, None, None, true),
+    }
+}
+
+fn local_version(write: &PendingWrite) -> Version {
+    match write {
+        PendingWrite::CreateSong { payload, .. } => song_version(payload.clone()),
+        PendingWrite::UpdateSong { payload, .. } => song_version(payload.clone().into()),
+        PendingWrite::CreateSetlist { payload, .. } => Version {
+            title: payload.title.clone(),
+            body: setlist_body(&payload.songs),
+        },
+        PendingWrite::UpdateSetlist { payload, .. } => Version {
+            title: payload.title.clone(),
+            body: setlist_body(&payload.songs),
+        },
+        PendingWrite::DeleteSong { .. } | PendingWrite::DeleteSetlist { .. } => Version {
+            title: String::new(),
+            body: "You deleted this while offline.".to_string(),
+        },
+    }
+}
+
+fn server_version(copy: &ServerCopy) -> Version {
+    match copy {
+        ServerCopy::Song(song) => song_version(song.clone().into()),
+        ServerCopy::Setlist(setlist) => Version {
+            title: setlist.title.clone(),
+            body: setlist_body(&setlist.songs),
+        },
+        ServerCopy::Deleted => Version {
+            title: String::new(),
+            body: "Someone else deleted this.".to_string(),
+        },
+    }
+}
+
+/// Side-by-side view of an offline change the server rejected with `412`, letting the user
+/// keep their version or take the one that is now on the server.
+#[function_component(ConflictResolver)]
+pub fn conflict_resolver(props: &ConflictResolverProps) -> Html {
+    let api = use_api();
+    let busy = use_state(|| false);
+
+    let Some(entry) = props.conflicts.first() else {
+        return html! {};
+    };
+    let Some(server) = entry.conflict.as_ref() else {
+        return html! {};
+    };
+
+    let resolve = |keep_mine: bool| {
+        let api = api.clone();
+        let busy = busy.clone();
+        let key = entry.key.clone();
+        Callback::from(move |_: MouseEvent| {
+            if *busy {
+                return;
+            }
+            busy.set(true);
+            let api = api.clone();
+            let busy = busy.clone();
+            let key = key.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                api.resolve_conflict(&key, keep_mine).await;
+                busy.set(false);
+            });
+        })
+    };
+
+    let kind = if entry.write.is_song() {
+        "song"
+    } else {
+        "setlist"
+    };
+    let mine = local_version(&entry.write);
+    let theirs = server_version(server);
+    let title = [&mine.title, &theirs.title]
+        .into_iter()
+        .find(|title| !title.is_empty())
+        .cloned()
+        .unwrap_or_else(|| entry.write.target_id().to_string());
+    let remaining = props.conflicts.len();
+
+    html! {
+        <div class={Style::new(include_str!("conflict_resolver.css")).expect("Unwrapping CSS should work!")}>
+            <div class="dialog-backdrop">
+                <div class="dialog" role="dialog" aria-modal="true">
+                    <span class="dialog__title">
+                        {format!("\"{title}\" was changed elsewhere")}
+                    </span>
+                    <p class="dialog__body">
+                        {format!(
+                            "This {kind} changed on the server while you were offline. Choose which version to keep."
+                        )}
+                        if remaining > 1 {
+                            {format!(" ({} more conflicts waiting.)", remaining - 1)}
+                        }
+                    </p>
+                    <div class="versions">
+                        <div class="version">
+                            <span class="version__label">{"Your version"}</span>
+                            <pre class="version__body">{&mine.body}</pre>
+                        </div>
+                        <div class="version">
+                            <span class="version__label">{"Server version"}</span>
+                            <pre class="version__body">{&theirs.body}</pre>
+                        </div>
+                    </div>
+                    <div class="dialog__actions">
+                        <button
+                            type="button"
+                            class="dialog__button dialog__button--ghost"
+                            disabled={*busy}
+                            onclick={resolve(false)}
+                        >
+                            <span class="material-symbols-outlined">{"cloud_download"}</span>
+                            <span>{"Use server version"}</span>
+                        </button>
+                        <button
+                            type="button"
+                            class="dialog__button dialog__button--primary"
+                            disabled={*busy}
+                            onclick={resolve(true)}
+                        >
+                            <span class="material-symbols-outlined">{"cloud_upload"}</span>
+                            <span>{"Keep mine"}</span>
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -12,7 +12,7 @@ fn profile_picture_src(user: &User) -> Option<String> {
     user.avatar_blob_id
         .as_ref()
         .or(user.oauth_avatar_blob_id.as_ref())
-        .map(|id| format!("/api/v1/blobs/{id}/data"))
+        .map(|id| format!("/api/v1/blobs/{id}/data?variant=thumb"))
 }
 
 #[derive(Properties, PartialEq)]
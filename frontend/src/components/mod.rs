@@ -1,4 +1,5 @@
 mod aspect_ratio;
+mod conflict_resolver;
 pub mod editor;
 pub mod layouts;
 mod legal_links;
@@ -12,6 +13,7 @@ pub mod toast_notifications;
 mod topbar;
 
 pub use aspect_ratio::AspectRatio;
+pub use conflict_resolver::ConflictResolver;
 pub use legal_links::LegalLinks;
 pub use presenter::{Presenter, Query as PresenterQuery};
 pub use setlist_editor::{SetlistEditor, SetlistSavePayload};
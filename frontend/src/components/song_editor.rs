@@ -100,6 +100,8 @@ pub struct Props {
     pub ondelete: Callback<String>,
     pub onback: Callback<MouseEvent>,
     pub oncreate_blank: Callback<()>,
+    /// Replaces the editor content with a draft read from the OCR text of the song's blobs.
+    pub ondraft_from_blobs: Callback<()>,
 }
 
 #[function_component(SongEditor)]
@@ -122,6 +124,11 @@ pub fn song_editor(props: &Props) -> Html {
         })
     };
     let can_delete = props.song_id.is_some();
+    let can_draft = can_delete && !props.song.blobs.is_empty();
+    let draft_from_blobs = {
+        let ondraft_from_blobs = props.ondraft_from_blobs.clone();
+        Callback::from(move |_: MouseEvent| ondraft_from_blobs.emit(()))
+    };
     let delete_song = {
         let show_delete_dialog = show_delete_dialog.clone();
         Callback::from(move |_: MouseEvent| show_delete_dialog.set(true))
@@ -219,6 +226,19 @@ pub fn song_editor(props: &Props) -> Html {
                 >{"arrow_back"}</span>
                 <div class="seperator"></div>
                 <div class="editor-actions">
+                    {
+                        if can_draft {
+                            html! {
+                                <span
+                                    class="material-symbols-outlined button"
+                                    title="Draft from scanned sheets"
+                                    onclick={draft_from_blobs}
+                                >{"document_scanner"}</span>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
                     {
                         if can_delete {
                             html! {
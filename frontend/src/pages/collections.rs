@@ -5,17 +5,18 @@ use stylist::Style;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use crate::api::use_api;
+use crate::api::{use_api, use_library_version};
 
 #[function_component(CollectionsPage)]
 pub fn collection_page() -> Html {
     let collections = use_state(|| Vec::<Collection>::new());
     let api = use_api();
+    let library_version = use_library_version();
     let navigator = use_navigator().unwrap();
 
     {
         let collections = collections.clone();
-        use_effect_with((), move |_| {
+        use_effect_with(library_version, move |_| {
             let collections = collections.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 collections.set(api.get_collections().await.unwrap());
@@ -27,7 +28,7 @@ pub fn collection_page() -> Html {
     let collections = collections
         .iter()
         .map(|collection| {
-            let cover = "/api/v1/blobs/".to_string() + &collection.cover + "/data";
+            let cover = "/api/v1/blobs/".to_string() + &collection.cover + "/data?variant=thumb";
             let title = &collection.title;
             let onclick = {
                 let navigator = navigator.clone();
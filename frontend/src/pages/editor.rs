@@ -1,4 +1,5 @@
-use crate::api::use_api;
+use crate::api::{use_api, ApiError};
+use crate::components::toast_notifications::show_error;
 use crate::components::{SongEditor, SongSavePayload};
 use crate::route::Route;
 use serde::Deserialize;
@@ -82,11 +83,12 @@ pub fn editor_page() -> Html {
             if let Some(id) = payload.id.clone() {
                 let api = api.clone();
                 wasm_bindgen_futures::spawn_local(async move {
-                    let updated = api
-                        .update_song(&id, &UpdateSong::from(data.clone()))
-                        .await
-                        .unwrap();
-                    song_handle.set(Some(updated.into()));
+                    match api.update_song(&id, &UpdateSong::from(data.clone())).await {
+                        Ok(updated) => song_handle.set(Some(updated.into())),
+                        // Handed to the conflict dialog.
+                        Err(ApiError::PreconditionFailed(_)) => {}
+                        Err(e) => show_error("Could not save song", &format!("{e}")),
+                    }
                 });
             } else {
                 if data.data.title().is_empty() {
@@ -126,9 +128,40 @@ pub fn editor_page() -> Html {
             let song_handle = song_handle.clone();
             let api = api.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                api.delete_song(&target_id).await.unwrap();
-                song_handle.set(Some(EditorState::new()));
-                navigator.push(&Route::Songs);
+                match api.delete_song(&target_id).await {
+                    Ok(()) => {
+                        song_handle.set(Some(EditorState::new()));
+                        navigator.push(&Route::Songs);
+                    }
+                    Err(ApiError::PreconditionFailed(_)) => {}
+                    Err(e) => show_error("Could not delete song", &format!("{e}")),
+                }
+            });
+        })
+    };
+
+    let ondraft_from_blobs = {
+        let song_handle = song.clone();
+        let api = api.clone();
+        Callback::from(move |_: ()| {
+            let Some(current) = (*song_handle).clone() else {
+                return;
+            };
+            let Some(id) = current.id.clone() else {
+                return;
+            };
+            let song_handle = song_handle.clone();
+            let api = api.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api.draft_song_from_blobs(&id).await {
+                    // Only the editor content changes; the draft is saved like any other edit.
+                    Ok(draft) => {
+                        let mut state = current;
+                        state.data.data = draft.data;
+                        song_handle.set(Some(state));
+                    }
+                    Err(e) => show_error("Could not draft from scans", &format!("{e}")),
+                }
             });
         })
     };
@@ -154,6 +187,7 @@ pub fn editor_page() -> Html {
                 ondelete={ondelete}
                 onback={onback}
                 oncreate_blank={oncreate_blank}
+                ondraft_from_blobs={ondraft_from_blobs}
             />
         </div>
     }
@@ -18,7 +18,7 @@ pub fn page_components(props: &Props) -> Html {
     match &props.item {
         PlayerItem::Blob(b) => html! {
             <div class={Style::new(include_str!("page.css")).expect("Unwrapping CSS should work!")}>
-                <img src={format!("/api/v1/blobs/{}/data", b.blob_id)}/>
+                <img src={format!("/api/v1/blobs/{}/data?variant=display", b.blob_id)}/>
             </div>
         },
         PlayerItem::Chords(c) => {
@@ -1,4 +1,5 @@
-use crate::api::use_api;
+use crate::api::{use_api, ApiError};
+use crate::components::toast_notifications::show_error;
 use crate::components::{SetlistEditor, SetlistSavePayload};
 use crate::route::Route;
 use serde::Deserialize;
@@ -80,11 +81,15 @@ pub fn setlist_editor_page() -> Html {
             if let Some(id) = payload.id.clone() {
                 let api = api.clone();
                 wasm_bindgen_futures::spawn_local(async move {
-                    let updated = api
+                    match api
                         .update_setlist(&id, &UpdateSetlist::from(data.clone()))
                         .await
-                        .unwrap();
-                    setlist_handle.set(Some(updated.into()));
+                    {
+                        Ok(updated) => setlist_handle.set(Some(updated.into())),
+                        // Handed to the conflict dialog.
+                        Err(ApiError::PreconditionFailed(_)) => {}
+                        Err(e) => show_error("Could not save setlist", &format!("{e}")),
+                    }
                 });
             } else {
                 if data.title.trim().is_empty() {
@@ -117,9 +122,14 @@ pub fn setlist_editor_page() -> Html {
             let setlist_handle = setlist_handle.clone();
             let api = api.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                api.delete_setlist(&target_id).await.unwrap();
-                setlist_handle.set(Some(EditorState::new()));
-                navigator.push(&Route::Setlists);
+                match api.delete_setlist(&target_id).await {
+                    Ok(()) => {
+                        setlist_handle.set(Some(EditorState::new()));
+                        navigator.push(&Route::Setlists);
+                    }
+                    Err(ApiError::PreconditionFailed(_)) => {}
+                    Err(e) => show_error("Could not delete setlist", &format!("{e}")),
+                }
             });
         })
     };
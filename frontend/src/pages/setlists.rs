@@ -1,4 +1,4 @@
-use crate::api::use_api;
+use crate::api::{use_api, use_library_version};
 use crate::route::Route;
 use shared::setlist::Setlist;
 use std::collections::HashMap;
@@ -10,11 +10,12 @@ use yew_router::prelude::*;
 pub fn setlists_page() -> Html {
     let setlists = use_state(|| Vec::<Setlist>::new());
     let api = use_api();
+    let library_version = use_library_version();
 
     {
         let setlists = setlists.clone();
         let api = api.clone();
-        use_effect_with((), move |_| {
+        use_effect_with(library_version, move |_| {
             let setlists = setlists.clone();
             let api = api.clone();
             wasm_bindgen_futures::spawn_local(async move {
@@ -1,4 +1,4 @@
-use crate::api::use_api;
+use crate::api::{use_api, use_library_version};
 use crate::route::Route;
 use shared::song::{ChordRepresentation, SimpleChord, Song};
 use std::collections::HashMap;
@@ -10,11 +10,12 @@ use yew_router::prelude::*;
 pub fn songs_page() -> Html {
     let songs = use_state(|| Vec::<Song>::new());
     let api = use_api();
+    let library_version = use_library_version();
 
     {
         let songs = songs.clone();
         let api = api.clone();
-        use_effect_with((), move |_| {
+        use_effect_with(library_version, move |_| {
             let songs = songs.clone();
             let api = api.clone();
             wasm_bindgen_futures::spawn_local(async move {
@@ -3,15 +3,16 @@ use crate::blob::{Blob, CreateBlob, UpdateBlob};
 use crate::collection::{Collection, CreateCollection, UpdateCollection};
 use crate::error::NetworkClientError;
 use crate::like::LikeStatus;
-use crate::net::HttpClient;
 #[cfg(any(
     all(feature = "cli", not(target_arch = "wasm32")),
     all(feature = "frontend", target_arch = "wasm32")
 ))]
 use crate::net::{DefaultHttpClient, HttpClientConfig};
+use crate::net::{HttpClient, Tagged};
 use crate::player::Player;
 use crate::setlist::{CreateSetlist, Setlist, UpdateSetlist};
-use crate::song::{CreateSong, Song, UpdateSong};
+use crate::song::{CreateSong, Song, SongDraft, UpdateSong};
+use crate::sync::SyncChanges;
 use crate::team::{CreateTeam, Team, UpdateTeam};
 use crate::user::{CreateUser, SessionBody, User};
 use std::vec::Vec;
@@ -256,12 +257,55 @@ impl<C: HttpClient> ApiClient<C> {
             .await
     }
 
+    /// Like [`Self::get_song`], also returning the song's `ETag`.
+    pub async fn get_song_tagged(&self, id: &str) -> Result<Tagged<Song>, NetworkClientError> {
+        self.client.get_tagged(&format!("api/v1/songs/{id}")).await
+    }
+
+    /// Like [`Self::create_song`], also returning the new song's `ETag`.
+    pub async fn create_song_tagged(
+        &self,
+        payload: CreateSong,
+    ) -> Result<Tagged<Song>, NetworkClientError> {
+        self.client.post_tagged("api/v1/songs", &payload).await
+    }
+
+    /// Like [`Self::update_song`], also returning the song's new `ETag`. With `if_match` it only
+    /// applies while the song still matches that ETag.
+    pub async fn update_song_tagged(
+        &self,
+        id: &str,
+        payload: UpdateSong,
+        if_match: Option<&str>,
+    ) -> Result<Tagged<Song>, NetworkClientError> {
+        self.client
+            .put_tagged(&format!("api/v1/songs/{id}"), &payload, if_match)
+            .await
+    }
+
+    pub async fn draft_song_from_blobs(&self, id: &str) -> Result<SongDraft, NetworkClientError> {
+        self.client
+            .post(&format!("api/v1/songs/{id}/draft-from-blobs"), &())
+            .await
+    }
+
     pub async fn delete_song(&self, id: &str) -> Result<(), NetworkClientError> {
         self.client
             .delete_no_content(&format!("api/v1/songs/{id}"))
             .await
     }
 
+    /// Like [`Self::delete_song`], but only deletes while the song still matches `etag`.
+    pub async fn delete_song_if_match(
+        &self,
+        id: &str,
+        etag: &str,
+    ) -> Result<(), NetworkClientError> {
+        self.client
+            .delete_no_content_if_match(&format!("api/v1/songs/{id}"), etag)
+            .await
+    }
+
     pub async fn patch_song(
         &self,
         id: &str,
@@ -399,12 +443,54 @@ impl<C: HttpClient> ApiClient<C> {
             .await
     }
 
+    /// Like [`Self::get_setlist`], also returning the setlist's `ETag`.
+    pub async fn get_setlist_tagged(
+        &self,
+        id: &str,
+    ) -> Result<Tagged<Setlist>, NetworkClientError> {
+        self.client
+            .get_tagged(&format!("api/v1/setlists/{id}"))
+            .await
+    }
+
+    /// Like [`Self::create_setlist`], also returning the new setlist's `ETag`.
+    pub async fn create_setlist_tagged(
+        &self,
+        payload: CreateSetlist,
+    ) -> Result<Tagged<Setlist>, NetworkClientError> {
+        self.client.post_tagged("api/v1/setlists", &payload).await
+    }
+
+    /// Like [`Self::update_setlist`], also returning the setlist's new `ETag`. With `if_match` it only
+    /// applies while the setlist still matches that ETag.
+    pub async fn update_setlist_tagged(
+        &self,
+        id: &str,
+        payload: UpdateSetlist,
+        if_match: Option<&str>,
+    ) -> Result<Tagged<Setlist>, NetworkClientError> {
+        self.client
+            .put_tagged(&format!("api/v1/setlists/{id}"), &payload, if_match)
+            .await
+    }
+
     pub async fn delete_setlist(&self, id: &str) -> Result<(), NetworkClientError> {
         self.client
             .delete_no_content(&format!("api/v1/setlists/{id}"))
             .await
     }
 
+    /// Like [`Self::delete_setlist`], but only deletes while the setlist still matches `etag`.
+    pub async fn delete_setlist_if_match(
+        &self,
+        id: &str,
+        etag: &str,
+    ) -> Result<(), NetworkClientError> {
+        self.client
+            .delete_no_content_if_match(&format!("api/v1/setlists/{id}"), etag)
+            .await
+    }
+
     pub async fn patch_setlist(
         &self,
         id: &str,
@@ -457,6 +543,15 @@ impl<C: HttpClient> ApiClient<C> {
     pub async fn download_blob_image_url(&self, id: &str) -> String {
         format!("api/v1/blobs/{id}/data")
     }
+
+    /// Library changes since `since` (a cursor from a previous sync), or everything when `None`.
+    pub async fn sync(&self, since: Option<&str>) -> Result<SyncChanges, NetworkClientError> {
+        let path = match since {
+            Some(cursor) => append_query_param("api/v1/sync".to_string(), "since", cursor),
+            None => "api/v1/sync".to_string(),
+        };
+        self.client.get(&path).await
+    }
 }
 
 fn append_query_param(path: String, key: &str, value: &str) -> String {
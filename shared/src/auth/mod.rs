@@ -1,3 +1,9 @@
+pub mod oidc;
 pub mod otp;
+pub mod passkey;
 
+pub use oidc::OidcProviderInfo;
 pub use otp::{OtpRequest, OtpVerify};
+pub use passkey::{
+    AuthenticationCredential, PasskeyCreationOptions, PasskeyRequestOptions, RegistrationCredential,
+};
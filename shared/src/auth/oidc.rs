@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// An OIDC provider this deployment accepts; `id` is the value for `/auth/login?provider=`.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct OidcProviderInfo {
+    pub id: String,
+    pub name: String,
+}
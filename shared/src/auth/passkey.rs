@@ -0,0 +1,157 @@
+//! WebAuthn ceremony payloads. They mirror the browser's `PublicKeyCredential` JSON
+//! dictionaries, with binary values as unpadded base64url strings, but use snake_case field
+//! names like the rest of the API; clients map them to `navigator.credentials` arguments.
+
+use serde::{Deserialize, Serialize};
+
+/// COSE algorithm identifiers accepted for passkeys: ES256, EdDSA and RS256.
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [-7, -8, -257];
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PasskeyUserEntity {
+    /// Opaque user handle (base64url); returned as `user_handle` on login.
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CredentialParameter {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub alg: i64,
+}
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CredentialDescriptor {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Credential id (base64url).
+    pub id: String,
+}
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AuthenticatorSelection {
+    pub resident_key: String,
+    pub require_resident_key: bool,
+    pub user_verification: String,
+}
+
+/// Response of `POST /api/v1/users/me/passkeys/options`; pass to `navigator.credentials.create`.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PasskeyCreationOptions {
+    pub rp: RelyingParty,
+    pub user: PasskeyUserEntity,
+    /// Single-use challenge (base64url), valid for a few minutes.
+    pub challenge: String,
+    pub pub_key_cred_params: Vec<CredentialParameter>,
+    /// Milliseconds.
+    pub timeout: u64,
+    /// Passkeys the user already registered, so the authenticator does not create a duplicate.
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+    pub authenticator_selection: AuthenticatorSelection,
+    pub attestation: String,
+}
+
+/// Response of `POST /auth/passkey/options`; pass to `navigator.credentials.get`.
+/// `allow_credentials` is empty: the authenticator offers its discoverable passkeys.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PasskeyRequestOptions {
+    pub challenge: String,
+    /// Milliseconds.
+    pub timeout: u64,
+    pub rp_id: String,
+    pub allow_credentials: Vec<CredentialDescriptor>,
+    pub user_verification: String,
+}
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AttestationResponse {
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+/// A newly created credential (`PublicKeyCredential.toJSON()` with snake_case keys). Extra
+/// members such as `client_extension_results` or `transports` are ignored.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RegistrationCredential {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub response: AttestationResponse,
+}
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AssertionResponse {
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    #[serde(default)]
+    pub user_handle: Option<String>,
+}
+
+/// A login assertion; body of `POST /auth/passkey/verify`.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AuthenticationCredential {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub response: AssertionResponse,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_ignore_extra_members() {
+        let credential: AuthenticationCredential = serde_json::from_value(serde_json::json!({
+            "id": "Y3JlZA",
+            "raw_id": "Y3JlZA",
+            "type": "public-key",
+            "authenticator_attachment": "platform",
+            "client_extension_results": {},
+            "response": {
+                "client_data_json": "e30",
+                "authenticator_data": "AA",
+                "signature": "AA",
+                "user_handle": null
+            }
+        }))
+        .expect("assertion");
+        assert_eq!(credential.kind, "public-key");
+        assert_eq!(credential.response.client_data_json, "e30");
+        assert!(credential.response.user_handle.is_none());
+    }
+
+    #[test]
+    fn options_serialize_as_snake_case() {
+        let options = PasskeyRequestOptions {
+            challenge: "Y2g".into(),
+            timeout: 1000,
+            rp_id: "localhost".into(),
+            allow_credentials: vec![],
+            user_verification: "preferred".into(),
+        };
+        let value = serde_json::to_value(options).unwrap();
+        assert_eq!(value["rp_id"], "localhost");
+        assert_eq!(value["allow_credentials"], serde_json::json!([]));
+    }
+}
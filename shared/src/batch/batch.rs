@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Problem;
+use crate::team::TrashResourceType;
+
+/// What a batch operation does to its resource; mirrors the single-resource endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum BatchAction {
+    /// `POST /{resource}s`; `body` is the create payload.
+    Create,
+    /// `PATCH /{resource}s/{id}`; `body` is the patch payload.
+    Patch,
+    /// `POST /{resource}s/{id}/move`; `body` is `{"owner": "<team id>"}`.
+    Move,
+    /// `DELETE /{resource}s/{id}` (moves the resource to its team's trash).
+    Delete,
+}
+
+/// One step of a batch.
+///
+/// `id` (and the ids in `body.songs[].id` / `body.blobs[].id`) may be `$<ref>` to name
+/// a resource created by an earlier operation of the same batch that carried `"ref": "<ref>"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct BatchOperation {
+    pub op: BatchAction,
+    pub resource: TrashResourceType,
+    /// Target id; required for everything but `create`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Label for the id this `create` produces, usable as `$<ref>` by later operations.
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Weak ETag the target must still have (`patch`, `delete`), like the `If-Match` header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_match: Option<String>,
+    /// Request body of the matching single-resource endpoint; omitted for `delete`.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    #[cfg_attr(feature = "backend", schema(value_type = Option<Object>))]
+    pub body: Value,
+}
+
+/// Body of `POST /api/v1/batch`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct BatchRequest {
+    /// Executed in order; all of them are applied or none is.
+    pub operations: Vec<BatchOperation>,
+}
+
+/// Outcome of one operation, at the same index as in the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct BatchResult {
+    /// Status the single-resource endpoint would have answered with.
+    pub status: u16,
+    /// Id of the affected resource (the new id for `create`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The resource after the operation; omitted for `delete` and for failed operations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "backend", schema(value_type = Option<Object>))]
+    pub resource: Option<Value>,
+    /// Why the operation failed, or `batch_rolled_back` for operations undone by a later failure
+    /// and `batch_not_attempted` for those after it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub problem: Option<Problem>,
+}
+
+/// Per-operation results of a batch; `committed` is `false` when any operation failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct BatchResponse {
+    pub committed: bool,
+    pub results: Vec<BatchResult>,
+}
@@ -1,4 +1,4 @@
-use super::FileType;
+use super::{BlobVariant, FileType};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "backend")]
@@ -33,8 +33,48 @@ pub struct Blob {
     pub file_type: FileType,
     pub width: u32,
     pub height: u32,
-    /// OCR or extracted text used for search (may be empty).
+    /// OCR or extracted text used for search (may be empty). Overwritten with the server's
+    /// result when `ocr_status` becomes `done`; songs linking the blob are found by it too.
     pub ocr: String,
+    /// Lowercase hex SHA-256 of the stored bytes; absent until data has been uploaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Resized variants already generated from the current bytes. Others are generated when
+    /// first requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<BlobVariant>,
+    /// Server-side text extraction from the uploaded bytes into `ocr`; absent when none was
+    /// queued (SVGs, no bytes yet, or OCR not configured on the server).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_status: Option<BlobOcrStatus>,
+    /// Why the last extraction attempt failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_error: Option<String>,
+}
+
+/// Progress of the background OCR job for a blob's current bytes.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "backend", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BlobOcrStatus {
+    /// Waiting for the worker (also between retries).
+    Pending,
+    Running,
+    /// `ocr` holds the extracted text.
+    Done,
+    /// Gave up after the configured number of attempts; see `ocr_error`.
+    Failed,
+}
+
+impl BlobOcrStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Done => "done",
+            Self::Failed => "failed",
+        }
+    }
 }
 
 impl Blob {
@@ -1,5 +1,9 @@
 mod blob;
 mod file_type;
+mod variant;
+mod verify;
 
-pub use blob::{Blob, BlobLink, CreateBlob, PatchBlob, UpdateBlob};
+pub use blob::{Blob, BlobLink, BlobOcrStatus, CreateBlob, PatchBlob, UpdateBlob};
 pub use file_type::FileType;
+pub use variant::BlobVariant;
+pub use verify::{BlobContentProblem, BlobVerifyReport};
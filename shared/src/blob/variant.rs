@@ -0,0 +1,88 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "backend")]
+use utoipa::ToSchema;
+
+/// Rendition of a blob image selected with `GET /api/v1/blobs/{id}/data?variant=`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BlobVariant {
+    /// List thumbnail, at most [`BlobVariant::THUMB_MAX_SIDE`] pixels on its longest side.
+    Thumb,
+    /// Screen-sized image, at most [`BlobVariant::DISPLAY_MAX_SIDE`] pixels on its longest side.
+    Display,
+    /// The uploaded bytes, unchanged.
+    #[default]
+    Original,
+}
+
+impl BlobVariant {
+    pub const THUMB_MAX_SIDE: u32 = 320;
+    pub const DISPLAY_MAX_SIDE: u32 = 1600;
+
+    /// Variants generated from uploaded raster images.
+    pub const RESIZED: [Self; 2] = [Self::Thumb, Self::Display];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Thumb => "thumb",
+            Self::Display => "display",
+            Self::Original => "original",
+        }
+    }
+
+    /// Bound on the longest side in pixels; `None` for [`BlobVariant::Original`].
+    pub fn max_side(&self) -> Option<u32> {
+        match self {
+            Self::Thumb => Some(Self::THUMB_MAX_SIDE),
+            Self::Display => Some(Self::DISPLAY_MAX_SIDE),
+            Self::Original => None,
+        }
+    }
+}
+
+impl fmt::Display for BlobVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BlobVariant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "thumb" => Ok(Self::Thumb),
+            "display" => Ok(Self::Display),
+            "original" => Ok(Self::Original),
+            _ => Err(format!(
+                "unknown blob variant `{s}` (expected thumb, display or original)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlobVariant;
+
+    #[test]
+    fn parses_wire_names() {
+        for variant in [
+            BlobVariant::Thumb,
+            BlobVariant::Display,
+            BlobVariant::Original,
+        ] {
+            assert_eq!(variant.as_str().parse::<BlobVariant>(), Ok(variant));
+            assert_eq!(
+                serde_json::to_string(&variant).unwrap(),
+                format!("\"{variant}\"")
+            );
+        }
+        assert!("full".parse::<BlobVariant>().is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Stored content that one or more blob records point at but that is missing or damaged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct BlobContentProblem {
+    pub sha256: String,
+    pub blob_ids: Vec<String>,
+}
+
+/// Result of `POST /api/v1/admin/blobs/verify`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct BlobVerifyReport {
+    /// Distinct content hashes referenced by blob records.
+    pub referenced: u64,
+    /// Content hashes found in storage.
+    pub stored: u64,
+    /// Referenced content that is not in storage.
+    pub missing: Vec<BlobContentProblem>,
+    /// Stored content whose bytes no longer hash to their name.
+    pub corrupted: Vec<BlobContentProblem>,
+    /// Stored content no blob record references.
+    pub orphaned: Vec<String>,
+}
+
+impl BlobVerifyReport {
+    pub fn is_healthy(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty() && self.orphaned.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlobContentProblem, BlobVerifyReport};
+
+    #[test]
+    fn report_round_trips_and_reports_health() {
+        let report = BlobVerifyReport {
+            referenced: 2,
+            stored: 2,
+            missing: vec![BlobContentProblem {
+                sha256: "ab".repeat(32),
+                blob_ids: vec!["b1".into()],
+            }],
+            corrupted: Vec::new(),
+            orphaned: vec!["cd".repeat(32)],
+        };
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BlobVerifyReport>(&json).unwrap(),
+            report
+        );
+        assert!(!report.is_healthy());
+        assert!(BlobVerifyReport::default().is_healthy());
+    }
+}
@@ -14,6 +14,8 @@ pub enum ErrorCode {
     NotAcceptable,
     /// `If-Match` precondition failed (optimistic concurrency).
     PreconditionFailed,
+    /// Batch operation undone or skipped because another operation of the batch failed.
+    BatchAborted,
     Internal,
 }
 
@@ -29,6 +31,7 @@ impl ErrorCode {
             ErrorCode::TooManyRequests => "too_many_requests",
             ErrorCode::NotAcceptable => "not_acceptable",
             ErrorCode::PreconditionFailed => "precondition_failed",
+            ErrorCode::BatchAborted => "batch_aborted",
             ErrorCode::Internal => "internal",
         }
     }
@@ -44,6 +47,7 @@ impl ErrorCode {
         "too_many_requests",
         "not_acceptable",
         "precondition_failed",
+        "batch_aborted",
         "internal",
     ];
 }
@@ -75,6 +79,7 @@ mod tests {
             TooManyRequests,
             NotAcceptable,
             PreconditionFailed,
+            BatchAborted,
             Internal,
         ] {
             assert!(
@@ -99,6 +104,7 @@ mod tests {
                     ErrorCode::TooManyRequests,
                     ErrorCode::NotAcceptable,
                     ErrorCode::PreconditionFailed,
+                    ErrorCode::BatchAborted,
                     ErrorCode::Internal,
                 ]
                 .into_iter()
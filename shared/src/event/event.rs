@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::team::{ActivityAction, TrashResourceType};
+
+/// Notification pushed on `GET /api/v1/events` after a song, collection, setlist or blob the
+/// caller can read was written. Carries no content; clients refetch what they display.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct ChangeEvent {
+    pub resource_type: TrashResourceType,
+    pub id: String,
+    pub action: ActivityAction,
+    /// Weak ETag of the resource after the write, as returned to the writer; compare it with a
+    /// cached copy's ETag to skip needless refetches. Omitted for deletions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+}
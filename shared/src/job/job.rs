@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Kind of work a background job performs; selects the worker handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum JobKind {
+    /// Text extraction from a blob's uploaded bytes into its `ocr` field.
+    #[serde(rename = "blob.ocr")]
+    BlobOcr,
+    /// Scheduled: emails the activity digests that are due.
+    #[serde(rename = "activity.digest")]
+    ActivityDigest,
+    /// Scheduled: sends webhook deliveries whose next attempt is due.
+    #[serde(rename = "webhook.deliver")]
+    WebhookDelivery,
+    /// Scheduled: purges trash past its retention.
+    #[serde(rename = "trash.purge")]
+    TrashPurge,
+    /// Scheduled: drops sync tombstones past their retention.
+    #[serde(rename = "sync.prune")]
+    SyncPrune,
+    /// Scheduled: deletes accounts whose deletion grace period ended.
+    #[serde(rename = "account.deletion")]
+    AccountDeletion,
+    /// Scheduled: rolls HTTP audit rows past their retention up into daily summaries.
+    #[serde(rename = "audit.rollup")]
+    AuditRollup,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 7] = [
+        Self::BlobOcr,
+        Self::ActivityDigest,
+        Self::WebhookDelivery,
+        Self::TrashPurge,
+        Self::SyncPrune,
+        Self::AccountDeletion,
+        Self::AuditRollup,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BlobOcr => "blob.ocr",
+            Self::ActivityDigest => "activity.digest",
+            Self::WebhookDelivery => "webhook.deliver",
+            Self::TrashPurge => "trash.purge",
+            Self::SyncPrune => "sync.prune",
+            Self::AccountDeletion => "account.deletion",
+            Self::AuditRollup => "audit.rollup",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum JobStatus {
+    /// Waiting for a worker, either for its first attempt or for the next retry at `run_at`.
+    Queued,
+    Running,
+    Succeeded,
+    /// Gave up after `max_attempts`; see `last_error`.
+    Failed,
+    /// Cancelled before it finished.
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(Self::Queued),
+            "running" => Some(Self::Running),
+            "succeeded" => Some(Self::Succeeded),
+            "failed" => Some(Self::Failed),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// Whether the job will not run again.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A background job as returned by `GET /api/v1/jobs/{id}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    /// User the job runs for; absent for jobs the server started on its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    pub status: JobStatus,
+    /// Kind-specific input, e.g. `{"blob_id": "…"}` for `blob.ocr`.
+    #[cfg_attr(feature = "backend", schema(value_type = Object))]
+    pub payload: serde_json::Value,
+    /// Kind-specific output of a succeeded job, if the kind has any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "backend", schema(value_type = Option<Object>))]
+    pub result: Option<serde_json::Value>,
+    /// Completion of the current attempt in percent (0–100).
+    pub progress: u8,
+    /// Attempts started so far.
+    pub attempts: u32,
+    pub max_attempts: u32,
+    /// Error of the last failed attempt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Set by `POST /api/v1/jobs/{id}/cancel` while the job is running; the worker stops at
+    /// its next checkpoint.
+    pub cancel_requested: bool,
+    /// Earliest time the job (or its next retry) is picked up.
+    pub run_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_and_statuses_round_trip_through_str() {
+        for kind in JobKind::ALL {
+            assert_eq!(JobKind::parse(kind.as_str()), Some(kind));
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                serde_json::json!(kind.as_str())
+            );
+        }
+        for status in [
+            JobStatus::Queued,
+            JobStatus::Running,
+            JobStatus::Succeeded,
+            JobStatus::Failed,
+            JobStatus::Cancelled,
+        ] {
+            assert_eq!(JobStatus::parse(status.as_str()), Some(status));
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                serde_json::json!(status.as_str())
+            );
+        }
+        assert_eq!(JobStatus::parse("pending"), None);
+    }
+}
@@ -1,10 +1,13 @@
 pub mod api;
 pub mod auth;
+pub mod batch;
 pub mod blob;
 pub mod validation_limits;
 pub use patch::Patch;
 pub mod collection;
 pub mod error;
+pub mod event;
+pub mod job;
 pub mod like;
 pub mod move_owner;
 pub use move_owner::MoveOwner;
@@ -13,6 +16,7 @@ pub mod patch;
 pub mod player;
 pub mod setlist;
 pub mod song;
+pub mod sync;
 pub mod team;
 pub mod user;
 pub use chordlib::Error as ChordlibError;
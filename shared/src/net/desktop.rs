@@ -2,7 +2,7 @@ use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use super::{HttpClient, HttpClientConfig};
+use super::{HttpClient, HttpClientConfig, Tagged};
 use crate::error::NetworkClientError;
 
 #[derive(Clone)]
@@ -45,6 +45,21 @@ impl DesktopHttpClient {
     }
 }
 
+/// Parse a JSON body and keep the response's `ETag`.
+async fn tagged<T: DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<Tagged<T>, NetworkClientError> {
+    let response = response.error_for_status()?;
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let value = response.json::<T>().await?;
+
+    Ok(Tagged { value, etag })
+}
+
 #[async_trait::async_trait]
 impl HttpClient for DesktopHttpClient {
     async fn get<T>(&self, path: &str) -> Result<T, NetworkClientError>
@@ -94,6 +109,50 @@ impl HttpClient for DesktopHttpClient {
         Ok(value)
     }
 
+    async fn get_tagged<T>(&self, path: &str) -> Result<Tagged<T>, NetworkClientError>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let url = self.make_url(path);
+
+        let request = self.with_common_headers(self.client.get(url));
+
+        tagged(request.send().await?).await
+    }
+
+    async fn post_tagged<B, T>(&self, path: &str, body: &B) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static,
+    {
+        let url = self.make_url(path);
+
+        let request = self.with_common_headers(self.client.post(url).json(body));
+
+        tagged(request.send().await?).await
+    }
+
+    async fn put_tagged<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+        if_match: Option<&str>,
+    ) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static,
+    {
+        let url = self.make_url(path);
+
+        let mut request = self.client.put(url).json(body);
+        if let Some(etag) = if_match {
+            request = request.header(reqwest::header::IF_MATCH, etag);
+        }
+        let request = self.with_common_headers(request);
+
+        tagged(request.send().await?).await
+    }
+
     async fn patch<B, T>(&self, path: &str, body: &B) -> Result<T, NetworkClientError>
     where
         B: Serialize + Send + Sync,
@@ -149,6 +208,24 @@ impl HttpClient for DesktopHttpClient {
         Ok(())
     }
 
+    async fn delete_no_content_if_match(
+        &self,
+        path: &str,
+        etag: &str,
+    ) -> Result<(), NetworkClientError> {
+        let url = self.make_url(path);
+
+        let request = self.with_common_headers(
+            self.client
+                .delete(url)
+                .header(reqwest::header::IF_MATCH, etag),
+        );
+
+        let response = request.send().await?;
+        response.error_for_status()?;
+        Ok(())
+    }
+
     async fn put_no_content(&self, path: &str) -> Result<(), NetworkClientError> {
         let url = self.make_url(path);
 
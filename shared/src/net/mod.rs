@@ -18,6 +18,13 @@ pub struct HttpClientConfig {
     pub client_ident: Option<String>,
 }
 
+/// A response body together with the `ETag` header it was sent with.
+#[derive(Clone, Debug)]
+pub struct Tagged<T> {
+    pub value: T,
+    pub etag: Option<String>,
+}
+
 #[cfg(target_arch = "wasm32")]
 #[async_trait::async_trait(?Send)]
 pub trait HttpClient: Send + Sync {
@@ -35,6 +42,33 @@ pub trait HttpClient: Send + Sync {
         B: Serialize + Send + Sync,
         T: DeserializeOwned + Send + 'static;
 
+    /// Like [`Self::get`], also returning the response's `ETag`.
+    async fn get_tagged<T>(&self, path: &str) -> Result<Tagged<T>, NetworkClientError>
+    where
+        T: DeserializeOwned + Send + 'static;
+
+    /// Like [`Self::post`], also returning the response's `ETag`.
+    async fn post_tagged<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static;
+
+    /// Like [`Self::put`], also returning the response's `ETag`. With `if_match` the request
+    /// carries that `If-Match` precondition; a stale ETag yields `412`.
+    async fn put_tagged<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+        if_match: Option<&str>,
+    ) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static;
+
     async fn patch<B, T>(&self, path: &str, body: &B) -> Result<T, NetworkClientError>
     where
         B: Serialize + Send + Sync,
@@ -51,6 +85,13 @@ pub trait HttpClient: Send + Sync {
     /// Send a DELETE request and treat `204 No Content` (empty body) as success.
     async fn delete_no_content(&self, path: &str) -> Result<(), NetworkClientError>;
 
+    /// Like [`Self::delete_no_content`], with an `If-Match` precondition.
+    async fn delete_no_content_if_match(
+        &self,
+        path: &str,
+        etag: &str,
+    ) -> Result<(), NetworkClientError>;
+
     /// Send a PUT with no body and treat `204 No Content` as success.
     async fn put_no_content(&self, path: &str) -> Result<(), NetworkClientError>;
 
@@ -82,6 +123,33 @@ pub trait HttpClient: Send + Sync {
         B: Serialize + Send + Sync,
         T: DeserializeOwned + Send + 'static;
 
+    /// Like [`Self::get`], also returning the response's `ETag`.
+    async fn get_tagged<T>(&self, path: &str) -> Result<Tagged<T>, NetworkClientError>
+    where
+        T: DeserializeOwned + Send + 'static;
+
+    /// Like [`Self::post`], also returning the response's `ETag`.
+    async fn post_tagged<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static;
+
+    /// Like [`Self::put`], also returning the response's `ETag`. With `if_match` the request
+    /// carries that `If-Match` precondition; a stale ETag yields `412`.
+    async fn put_tagged<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+        if_match: Option<&str>,
+    ) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static;
+
     async fn patch<B, T>(&self, path: &str, body: &B) -> Result<T, NetworkClientError>
     where
         B: Serialize + Send + Sync,
@@ -98,6 +166,13 @@ pub trait HttpClient: Send + Sync {
     /// Send a DELETE request and treat `204 No Content` (empty body) as success.
     async fn delete_no_content(&self, path: &str) -> Result<(), NetworkClientError>;
 
+    /// Like [`Self::delete_no_content`], with an `If-Match` precondition.
+    async fn delete_no_content_if_match(
+        &self,
+        path: &str,
+        etag: &str,
+    ) -> Result<(), NetworkClientError>;
+
     /// Send a PUT with no body and treat `204 No Content` as success.
     async fn put_no_content(&self, path: &str) -> Result<(), NetworkClientError>;
 
@@ -2,7 +2,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use wasm_bindgen::JsValue;
 
-use super::{HttpClient, HttpClientConfig};
+use super::{HttpClient, HttpClientConfig, Tagged};
 use crate::error::NetworkClientError;
 use js_sys::Uint8Array;
 use web_sys::RequestCredentials;
@@ -35,6 +35,25 @@ impl WasmHttpClient {
     }
 }
 
+/// Parse a JSON body and keep the response's `ETag`.
+async fn tagged<T: DeserializeOwned>(
+    response: gloo_net::http::Response,
+) -> Result<Tagged<T>, NetworkClientError> {
+    let status = response.status();
+    let etag = response.headers().get("ETag");
+    let text = response.text().await.unwrap_or_default();
+
+    if !(200..300).contains(&status) {
+        return Err(NetworkClientError::RequestFailed {
+            status: Some(status as u16),
+            message: text,
+        });
+    }
+
+    let value = serde_json::from_str::<T>(&text)?;
+    Ok(Tagged { value, etag })
+}
+
 #[async_trait::async_trait(?Send)]
 impl HttpClient for WasmHttpClient {
     async fn get<T>(&self, path: &str) -> Result<T, NetworkClientError>
@@ -124,6 +143,69 @@ impl HttpClient for WasmHttpClient {
         Ok(value)
     }
 
+    async fn get_tagged<T>(&self, path: &str) -> Result<Tagged<T>, NetworkClientError>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let url = self.make_url(path);
+
+        let response = self
+            .with_client(gloo_net::http::Request::get(&url))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await?;
+
+        tagged(response).await
+    }
+
+    async fn post_tagged<B, T>(&self, path: &str, body: &B) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static,
+    {
+        let url = self.make_url(path);
+
+        let payload = serde_json::to_string(body)?;
+        let response = self
+            .with_client(
+                gloo_net::http::Request::post(&url).header("Content-Type", "application/json"),
+            )
+            .credentials(RequestCredentials::Include)
+            .body(payload)?
+            .send()
+            .await?;
+
+        tagged(response).await
+    }
+
+    async fn put_tagged<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+        if_match: Option<&str>,
+    ) -> Result<Tagged<T>, NetworkClientError>
+    where
+        B: Serialize + Send + Sync,
+        T: DeserializeOwned + Send + 'static,
+    {
+        let url = self.make_url(path);
+
+        let payload = serde_json::to_string(body)?;
+        let mut request =
+            gloo_net::http::Request::put(&url).header("Content-Type", "application/json");
+        if let Some(etag) = if_match {
+            request = request.header("If-Match", etag);
+        }
+        let response = self
+            .with_client(request)
+            .credentials(RequestCredentials::Include)
+            .body(payload)?
+            .send()
+            .await?;
+
+        tagged(response).await
+    }
+
     async fn patch<B, T>(&self, path: &str, body: &B) -> Result<T, NetworkClientError>
     where
         B: Serialize + Send + Sync,
@@ -229,6 +311,31 @@ impl HttpClient for WasmHttpClient {
         Ok(())
     }
 
+    async fn delete_no_content_if_match(
+        &self,
+        path: &str,
+        etag: &str,
+    ) -> Result<(), NetworkClientError> {
+        let url = self.make_url(path);
+
+        let response = self
+            .with_client(gloo_net::http::Request::delete(&url).header("If-Match", etag))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await?;
+        let status = response.status();
+
+        if !(200..300).contains(&status) {
+            let text = response.text().await.unwrap_or_default();
+            return Err(NetworkClientError::RequestFailed {
+                status: Some(status as u16),
+                message: text,
+            });
+        }
+
+        Ok(())
+    }
+
     async fn put_no_content(&self, path: &str) -> Result<(), NetworkClientError> {
         let url = self.make_url(path);
 
@@ -0,0 +1,25 @@
+use chordlib::types::Song as ChordSong;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "backend")]
+use utoipa::ToSchema;
+
+#[cfg(feature = "backend")]
+#[allow(unused_imports)]
+use super::song_data_schema::SongDataSchema;
+
+/// Response of `POST /api/v1/songs/{id}/draft-from-blobs`: song data recovered from the OCR
+/// text of the song's blobs. Nothing is saved; send `data` back with `PUT /api/v1/songs/{id}`
+/// once it has been reviewed.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
+#[cfg_attr(feature = "backend", derive(ToSchema))]
+pub struct SongDraft {
+    /// The song's current metadata with `sections` (and, when the scan shows chords, `key`)
+    /// taken from the OCR text. `titles` is only filled from the scan when the song has none.
+    #[cfg_attr(feature = "backend", schema(value_type = SongDataSchema))]
+    pub data: ChordSong,
+    /// Ids of the blobs whose OCR text went into `data`, in `Song.blobs` order.
+    pub blobs: Vec<String>,
+    /// Ids of linked blobs without OCR text (OCR pending, running, failed or not applicable).
+    pub skipped_blobs: Vec<String>,
+}
@@ -1,3 +1,4 @@
+mod draft;
 mod link;
 mod song;
 #[cfg(feature = "backend")]
@@ -5,6 +6,7 @@ mod song_data_schema;
 
 pub use chordlib::outputs::{wrap_html, CharPageSet, FormatOutputLines, OutputLine};
 pub use chordlib::types::{ChordRepresentation, SimpleChord};
+pub use draft::SongDraft;
 pub use link::{Link, LinkOwned};
 pub use song::{CreateSong, PatchSong, PatchSongData, Song, SongUserSpecificAddons, UpdateSong};
 #[cfg(feature = "backend")]
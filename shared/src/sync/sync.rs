@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::blob::Blob;
+use crate::collection::Collection;
+use crate::setlist::Setlist;
+use crate::song::Song;
+use crate::team::TrashResourceType;
+
+/// A resource that left the caller's view: it was deleted (trashed or purged) or moved to a team
+/// the caller cannot read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct SyncTombstone {
+    pub resource_type: TrashResourceType,
+    pub resource_id: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Changes to the caller's library since a sync cursor.
+///
+/// Apply `songs`, `collections`, `setlists` and `blobs` as upserts and drop everything listed in
+/// `deleted`, then send `cursor` as `since` on the next sync. When `full` is set the lists hold the
+/// complete library and the local replica must be replaced instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct SyncChanges {
+    /// Opaque cursor for the next sync.
+    pub cursor: String,
+    /// `true` when this is a full snapshot: no cursor was sent, it expired, or the set of teams
+    /// the caller can read changed since it was issued.
+    pub full: bool,
+    pub songs: Vec<Song>,
+    pub collections: Vec<Collection>,
+    pub setlists: Vec<Setlist>,
+    /// Blob metadata only; fetch bytes from `/api/v1/blobs/{id}/data`.
+    pub blobs: Vec<Blob>,
+    /// Always empty when `full` is set.
+    pub deleted: Vec<SyncTombstone>,
+    /// Weak `ETag` of every returned song, collection and setlist by id, as its `GET` would send
+    /// it; keep it with the record and send it as `If-Match` when writing that record.
+    pub etags: BTreeMap<String, String>,
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::TeamUser;
+
+/// Kind of resource a [`TeamActivity`] entry refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum ActivityResourceType {
+    Song,
+    Collection,
+    Setlist,
+    Blob,
+    /// Team membership; `resource_id` is the affected user's id.
+    Member,
+}
+
+impl ActivityResourceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Song => "song",
+            Self::Collection => "collection",
+            Self::Setlist => "setlist",
+            Self::Blob => "blob",
+            Self::Member => "member",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "song" => Some(Self::Song),
+            "collection" => Some(Self::Collection),
+            "setlist" => Some(Self::Setlist),
+            "blob" => Some(Self::Blob),
+            "member" => Some(Self::Member),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum ActivityAction {
+    Created,
+    Updated,
+    Deleted,
+    /// Ownership changed; recorded on both the source and the destination team.
+    Moved,
+    MemberAdded,
+    MemberRemoved,
+    MemberRoleChanged,
+    /// Brought back out of the team's trash.
+    Restored,
+}
+
+impl ActivityAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Deleted => "deleted",
+            Self::Moved => "moved",
+            Self::MemberAdded => "member_added",
+            Self::MemberRemoved => "member_removed",
+            Self::MemberRoleChanged => "member_role_changed",
+            Self::Restored => "restored",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "created" => Some(Self::Created),
+            "updated" => Some(Self::Updated),
+            "deleted" => Some(Self::Deleted),
+            "moved" => Some(Self::Moved),
+            "member_added" => Some(Self::MemberAdded),
+            "member_removed" => Some(Self::MemberRemoved),
+            "member_role_changed" => Some(Self::MemberRoleChanged),
+            "restored" => Some(Self::Restored),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of a team's activity feed (newest first on `GET /teams/{id}/activity`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct TeamActivity {
+    pub id: String,
+    pub team_id: String,
+    /// User who performed the change; `None` once that account has been deleted.
+    pub actor: Option<TeamUser>,
+    pub resource_type: ActivityResourceType,
+    pub resource_id: String,
+    pub action: ActivityAction,
+    /// Human-readable label captured at write time (song title, collection title, member email, …).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// For `moved`: the other team involved (destination on the source team, source on the destination).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub other_team_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_type_and_action_round_trip_through_str() {
+        for t in [
+            ActivityResourceType::Song,
+            ActivityResourceType::Collection,
+            ActivityResourceType::Setlist,
+            ActivityResourceType::Blob,
+            ActivityResourceType::Member,
+        ] {
+            assert_eq!(ActivityResourceType::parse(t.as_str()), Some(t));
+            assert_eq!(
+                serde_json::to_value(t).unwrap(),
+                serde_json::json!(t.as_str())
+            );
+        }
+        for a in [
+            ActivityAction::Created,
+            ActivityAction::Updated,
+            ActivityAction::Deleted,
+            ActivityAction::Moved,
+            ActivityAction::MemberAdded,
+            ActivityAction::MemberRemoved,
+            ActivityAction::MemberRoleChanged,
+            ActivityAction::Restored,
+        ] {
+            assert_eq!(ActivityAction::parse(a.as_str()), Some(a));
+            assert_eq!(
+                serde_json::to_value(a).unwrap(),
+                serde_json::json!(a.as_str())
+            );
+        }
+    }
+}
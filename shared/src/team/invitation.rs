@@ -1,7 +1,46 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::TeamUser;
+#[cfg(feature = "backend")]
+#[allow(unused_imports)]
+use serde_json::json;
+
+use super::{TeamRole, TeamUser};
+
+/// Where an invitation stands, derived from its revocation, use count and expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum TeamInvitationStatus {
+    /// Can still be accepted.
+    Pending,
+    /// All uses are taken.
+    Accepted,
+    Expired,
+    Revoked,
+}
+
+impl TeamInvitationStatus {
+    /// Revocation wins over everything else; an invitation that was used up before it expired
+    /// stays `accepted`.
+    pub fn derive(
+        revoked: bool,
+        expires_at: Option<DateTime<Utc>>,
+        max_uses: Option<u32>,
+        use_count: u32,
+        now: DateTime<Utc>,
+    ) -> Self {
+        if revoked {
+            Self::Revoked
+        } else if max_uses.is_some_and(|max| use_count >= max) {
+            Self::Accepted
+        } else if expires_at.is_some_and(|at| at <= now) {
+            Self::Expired
+        } else {
+            Self::Pending
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
@@ -10,4 +49,84 @@ pub struct TeamInvitation {
     pub team_id: String,
     pub created_by: TeamUser,
     pub created_at: DateTime<Utc>,
+    /// Only a user with this email may accept; `null` for shareable link invitations.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Role granted on accept. Existing members are upgraded to it, never downgraded.
+    pub role: TeamRole,
+    /// `null` only for invitations created before expiry was introduced.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// `null` means unlimited.
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+    #[serde(default)]
+    pub use_count: u32,
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub status: TeamInvitationStatus,
+}
+
+/// Body of `POST /api/v1/teams/{team_id}/invitations`. Every field is optional; an empty body
+/// creates a shareable guest link that expires after 14 days.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "backend",
+    schema(example = json!({ "email": "singer@example.com", "role": "content_maintainer" }))
+)]
+pub struct CreateTeamInvitation {
+    /// Send the invitation to this address; only the user with this email can accept it.
+    /// Email invitations are single-use.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Defaults to `guest`.
+    #[serde(default)]
+    pub role: Option<TeamRole>,
+    /// Must be in the future and at most 365 days ahead. Defaults to 14 days from now.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// At least 1. Defaults to 1 for email invitations and to unlimited for links.
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn status_prefers_revoked_then_accepted_then_expired() {
+        use TeamInvitationStatus::*;
+        let now = Utc::now();
+        let past = Some(now - Duration::days(1));
+        let future = Some(now + Duration::days(1));
+        assert_eq!(
+            TeamInvitationStatus::derive(false, future, None, 3, now),
+            Pending
+        );
+        assert_eq!(
+            TeamInvitationStatus::derive(false, None, None, 0, now),
+            Pending
+        );
+        assert_eq!(
+            TeamInvitationStatus::derive(false, future, Some(1), 1, now),
+            Accepted
+        );
+        assert_eq!(
+            TeamInvitationStatus::derive(false, past, Some(1), 1, now),
+            Accepted
+        );
+        assert_eq!(
+            TeamInvitationStatus::derive(false, past, Some(2), 1, now),
+            Expired
+        );
+        assert_eq!(
+            TeamInvitationStatus::derive(true, future, Some(1), 1, now),
+            Revoked
+        );
+    }
 }
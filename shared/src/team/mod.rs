@@ -1,8 +1,20 @@
+mod activity;
 mod invitation;
+mod organization;
+mod role;
 mod team;
+mod trash;
+mod webhook;
 
-pub use invitation::TeamInvitation;
+pub use activity::{ActivityAction, ActivityResourceType, TeamActivity};
+pub use invitation::{CreateTeamInvitation, TeamInvitation, TeamInvitationStatus};
+pub use organization::{CreateOrganization, Organization, OrganizationTeam, UpdateOrganization};
+pub use role::{CreateTeamRole, TeamPermission, TeamRole, TeamRoleDefinition};
 pub use team::{
-    CreateTeam, PatchTeam, Team, TeamMember, TeamMemberInput, TeamRole, TeamUser, TeamUserRef,
+    CreateTeam, PatchTeam, Team, TeamMember, TeamMemberInput, TeamUser, TeamUserRef, TransferTeam,
     UpdateTeam,
 };
+pub use trash::{TrashItem, TrashResourceType};
+pub use webhook::{
+    CreateWebhook, CreatedWebhook, Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent,
+};
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "backend")]
+#[allow(unused_imports)]
+use serde_json::json;
+
+use super::{TeamUser, TeamUserRef};
+
+/// Team slice listed on an [`Organization`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct OrganizationTeam {
+    pub id: String,
+    pub name: String,
+}
+
+/// Groups shared teams. Every team of an organization can read the songs, collections,
+/// setlists and blobs of the organization's library team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+    /// Users who manage the organization: its name, admins, teams and library.
+    pub admins: Vec<TeamUser>,
+    /// `null` after the library team was deleted or left the organization.
+    pub library_team_id: Option<String>,
+    /// Teams of the organization, by name.
+    pub teams: Vec<OrganizationTeam>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Body of `POST /api/v1/organizations`. The caller becomes the only org admin, and a new
+/// shared team named "<name> Library" (caller as team admin) becomes the library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "backend",
+    schema(example = json!({ "name": "Grace Church" }))
+)]
+pub struct CreateOrganization {
+    pub name: String,
+}
+
+/// Body of `PUT /api/v1/organizations/{id}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct UpdateOrganization {
+    pub name: String,
+    /// Replaces the admin list; must not be empty.
+    pub admins: Vec<TeamUserRef>,
+    /// A team of this organization, or `null` for no library.
+    #[serde(default)]
+    pub library_team_id: Option<String>,
+}
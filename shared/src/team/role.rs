@@ -0,0 +1,195 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "backend")]
+#[allow(unused_imports)]
+use serde_json::json;
+
+/// Role of a team member: one of the built-in presets or the `id` of a role defined on the team
+/// (see [`TeamRoleDefinition`]). Serialized as a plain string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TeamRole {
+    /// Reads team content.
+    Guest,
+    /// Reads and writes all team content.
+    ContentMaintainer,
+    /// Writes all team content and manages the team.
+    Admin,
+    /// Team-defined role: reads team content and holds the role's permissions.
+    Custom(String),
+}
+
+impl TeamRole {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Guest => "guest",
+            Self::ContentMaintainer => "content_maintainer",
+            Self::Admin => "admin",
+            Self::Custom(id) => id,
+        }
+    }
+
+    /// Built-in preset for `value`, otherwise a custom role id.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "guest" => Self::Guest,
+            "content_maintainer" => Self::ContentMaintainer,
+            "admin" => Self::Admin,
+            other => Self::Custom(other.to_owned()),
+        }
+    }
+
+    /// Permissions granted by a preset; custom roles resolve theirs from the team.
+    pub fn preset_permissions(&self) -> &'static [TeamPermission] {
+        match self {
+            Self::Guest | Self::Custom(_) => &[],
+            Self::ContentMaintainer | Self::Admin => &TeamPermission::ALL,
+        }
+    }
+}
+
+impl Serialize for TeamRole {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TeamRole {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        if value.trim().is_empty() {
+            return Err(serde::de::Error::custom("team role must not be empty"));
+        }
+        Ok(Self::parse(&value))
+    }
+}
+
+#[cfg(feature = "backend")]
+impl utoipa::PartialSchema for TeamRole {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .description(Some(
+                "`guest`, `content_maintainer`, `admin`, or the `id` of a role defined on the team",
+            ))
+            .examples([json!("content_maintainer")])
+            .into()
+    }
+}
+
+#[cfg(feature = "backend")]
+impl utoipa::ToSchema for TeamRole {}
+
+/// Content action a team role may perform, per resource type. Every member can read; `write`
+/// covers create, update and moving content into the team, `delete` covers deleting and moving
+/// content out of it.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TeamPermission {
+    #[serde(rename = "songs:write")]
+    SongsWrite,
+    #[serde(rename = "songs:delete")]
+    SongsDelete,
+    #[serde(rename = "setlists:write")]
+    SetlistsWrite,
+    #[serde(rename = "setlists:delete")]
+    SetlistsDelete,
+    #[serde(rename = "collections:write")]
+    CollectionsWrite,
+    #[serde(rename = "collections:delete")]
+    CollectionsDelete,
+    #[serde(rename = "blobs:write")]
+    BlobsWrite,
+    #[serde(rename = "blobs:delete")]
+    BlobsDelete,
+}
+
+impl TeamPermission {
+    pub const ALL: [TeamPermission; 8] = [
+        Self::SongsWrite,
+        Self::SongsDelete,
+        Self::SetlistsWrite,
+        Self::SetlistsDelete,
+        Self::CollectionsWrite,
+        Self::CollectionsDelete,
+        Self::BlobsWrite,
+        Self::BlobsDelete,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SongsWrite => "songs:write",
+            Self::SongsDelete => "songs:delete",
+            Self::SetlistsWrite => "setlists:write",
+            Self::SetlistsDelete => "setlists:delete",
+            Self::CollectionsWrite => "collections:write",
+            Self::CollectionsDelete => "collections:delete",
+            Self::BlobsWrite => "blobs:write",
+            Self::BlobsDelete => "blobs:delete",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_str() == value)
+    }
+}
+
+/// Role defined by a team, assignable to its members like the built-in presets.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "backend",
+    schema(example = json!({
+        "id": "3f0c6a3e9d0b4f7e8c1a2b3c4d5e6f70",
+        "name": "Setlist planner",
+        "permissions": ["setlists:write", "setlists:delete"]
+    }))
+)]
+pub struct TeamRoleDefinition {
+    /// Value used as a member's `role`.
+    pub id: String,
+    pub name: String,
+    /// Sorted, without duplicates.
+    pub permissions: Vec<TeamPermission>,
+}
+
+/// Body of `POST /api/v1/teams/{id}/roles` and `PUT /api/v1/teams/{id}/roles/{role_id}`.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(
+    feature = "backend",
+    schema(example = json!({ "name": "Media tech", "permissions": ["blobs:write"] }))
+)]
+pub struct CreateTeamRole {
+    pub name: String,
+    pub permissions: Vec<TeamPermission>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn team_role_round_trips_presets_and_custom_ids() {
+        for (role, wire) in [
+            (TeamRole::Guest, "\"guest\""),
+            (TeamRole::ContentMaintainer, "\"content_maintainer\""),
+            (TeamRole::Admin, "\"admin\""),
+            (TeamRole::Custom("planner".into()), "\"planner\""),
+        ] {
+            assert_eq!(serde_json::to_string(&role).unwrap(), wire);
+            assert_eq!(serde_json::from_str::<TeamRole>(wire).unwrap(), role);
+        }
+        assert!(serde_json::from_str::<TeamRole>("\" \"").is_err());
+    }
+
+    #[test]
+    fn team_permission_strings_parse_back() {
+        for permission in TeamPermission::ALL {
+            let wire = serde_json::to_string(&permission).unwrap();
+            assert_eq!(wire, format!("\"{}\"", permission.as_str()));
+            assert_eq!(TeamPermission::parse(permission.as_str()), Some(permission));
+        }
+        assert_eq!(TeamPermission::parse("songs:read"), None);
+    }
+}
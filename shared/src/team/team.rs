@@ -1,4 +1,6 @@
 use crate::patch::Patch;
+
+use super::{TeamRole, TeamRoleDefinition};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "backend")]
@@ -7,15 +9,6 @@ use serde_json::json;
 #[cfg(feature = "backend")]
 use utoipa::ToSchema;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-#[cfg_attr(feature = "backend", derive(ToSchema))]
-pub enum TeamRole {
-    Guest,
-    ContentMaintainer,
-    Admin,
-}
-
 /// User slice returned on team **GET** (`id` + `email` only, same naming as `User`).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(ToSchema))]
@@ -49,7 +42,9 @@ pub struct TeamMember {
         "name": "Worship team",
         "members": [
             { "user": { "id": "usr_example", "email": "owner@example.com" }, "role": "admin" }
-        ]
+        ],
+        "organization_id": null,
+        "roles": []
     }))
 )]
 pub struct Team {
@@ -59,6 +54,12 @@ pub struct Team {
     pub name: String,
     /// Everyone except the personal-team owner (if any).
     pub members: Vec<TeamMember>,
+    /// Organization the team belongs to; always `null` for personal teams.
+    #[serde(default)]
+    pub organization_id: Option<String>,
+    /// Roles defined by this team in addition to the built-in presets.
+    #[serde(default)]
+    pub roles: Vec<TeamRoleDefinition>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -85,6 +86,23 @@ pub struct UpdateTeam {
     pub members: Option<Vec<TeamMemberInput>>,
 }
 
+/// Body of `POST /api/v1/teams/{id}/transfer`: hand the admin role to another member.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "backend", derive(ToSchema))]
+#[cfg_attr(
+    feature = "backend",
+    schema(example = json!({ "user": { "id": "usr_example" }, "former_admin_role": "content_maintainer" }))
+)]
+pub struct TransferTeam {
+    /// Existing member who becomes `admin`.
+    pub user: TeamUserRef,
+    /// Role the calling admin keeps afterwards. Defaults to `content_maintainer`; `admin`
+    /// shares the role instead of handing it over.
+    #[serde(default)]
+    pub former_admin_role: Option<TeamRole>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 #[cfg_attr(feature = "backend", derive(ToSchema))]
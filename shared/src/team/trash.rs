@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{ActivityResourceType, TeamUser};
+
+/// Kind of library resource that can sit in a team's trash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum TrashResourceType {
+    Song,
+    Setlist,
+    Collection,
+    Blob,
+}
+
+impl TrashResourceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Song => "song",
+            Self::Setlist => "setlist",
+            Self::Collection => "collection",
+            Self::Blob => "blob",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "song" => Some(Self::Song),
+            "setlist" => Some(Self::Setlist),
+            "collection" => Some(Self::Collection),
+            "blob" => Some(Self::Blob),
+            _ => None,
+        }
+    }
+
+    pub fn activity_type(&self) -> ActivityResourceType {
+        match self {
+            Self::Song => ActivityResourceType::Song,
+            Self::Setlist => ActivityResourceType::Setlist,
+            Self::Collection => ActivityResourceType::Collection,
+            Self::Blob => ActivityResourceType::Blob,
+        }
+    }
+}
+
+/// A deleted resource waiting in a team's trash (newest first on `GET /teams/{id}/trash`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct TrashItem {
+    pub id: String,
+    pub team_id: String,
+    pub resource_type: TrashResourceType,
+    pub resource_id: String,
+    /// Song, setlist or collection title; `None` for blobs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// User who deleted the resource; `None` once that account has been deleted.
+    pub deleted_by: Option<TeamUser>,
+    pub deleted_at: DateTime<Utc>,
+    /// When the resource will be removed permanently unless restored first.
+    pub purge_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_type_round_trips_through_str() {
+        for t in [
+            TrashResourceType::Song,
+            TrashResourceType::Setlist,
+            TrashResourceType::Collection,
+            TrashResourceType::Blob,
+        ] {
+            assert_eq!(TrashResourceType::parse(t.as_str()), Some(t));
+            assert_eq!(t.activity_type().as_str(), t.as_str());
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{ActivityAction, ActivityResourceType};
+
+/// Event a webhook can subscribe to; serialized as `<resource>.<action>` (e.g. `setlist.updated`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum WebhookEvent {
+    #[serde(rename = "song.created")]
+    SongCreated,
+    #[serde(rename = "song.updated")]
+    SongUpdated,
+    #[serde(rename = "song.deleted")]
+    SongDeleted,
+    #[serde(rename = "song.moved")]
+    SongMoved,
+    #[serde(rename = "setlist.created")]
+    SetlistCreated,
+    #[serde(rename = "setlist.updated")]
+    SetlistUpdated,
+    #[serde(rename = "setlist.deleted")]
+    SetlistDeleted,
+    #[serde(rename = "setlist.moved")]
+    SetlistMoved,
+    #[serde(rename = "collection.created")]
+    CollectionCreated,
+    #[serde(rename = "collection.updated")]
+    CollectionUpdated,
+    #[serde(rename = "collection.deleted")]
+    CollectionDeleted,
+    #[serde(rename = "collection.moved")]
+    CollectionMoved,
+    /// Sent only by the test-delivery endpoint; cannot be subscribed to.
+    #[serde(rename = "ping")]
+    Ping,
+}
+
+impl WebhookEvent {
+    pub const SUBSCRIBABLE: [WebhookEvent; 12] = [
+        Self::SongCreated,
+        Self::SongUpdated,
+        Self::SongDeleted,
+        Self::SongMoved,
+        Self::SetlistCreated,
+        Self::SetlistUpdated,
+        Self::SetlistDeleted,
+        Self::SetlistMoved,
+        Self::CollectionCreated,
+        Self::CollectionUpdated,
+        Self::CollectionDeleted,
+        Self::CollectionMoved,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SongCreated => "song.created",
+            Self::SongUpdated => "song.updated",
+            Self::SongDeleted => "song.deleted",
+            Self::SongMoved => "song.moved",
+            Self::SetlistCreated => "setlist.created",
+            Self::SetlistUpdated => "setlist.updated",
+            Self::SetlistDeleted => "setlist.deleted",
+            Self::SetlistMoved => "setlist.moved",
+            Self::CollectionCreated => "collection.created",
+            Self::CollectionUpdated => "collection.updated",
+            Self::CollectionDeleted => "collection.deleted",
+            Self::CollectionMoved => "collection.moved",
+            Self::Ping => "ping",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::SUBSCRIBABLE
+            .into_iter()
+            .chain([Self::Ping])
+            .find(|e| e.as_str() == value)
+    }
+
+    /// The webhook event for a team activity entry, if that kind of change is published.
+    pub fn from_activity(
+        resource_type: ActivityResourceType,
+        action: ActivityAction,
+    ) -> Option<Self> {
+        use ActivityAction as A;
+        use ActivityResourceType as R;
+        Some(match (resource_type, action) {
+            (R::Song, A::Created) => Self::SongCreated,
+            (R::Song, A::Updated) => Self::SongUpdated,
+            (R::Song, A::Deleted) => Self::SongDeleted,
+            (R::Song, A::Moved) => Self::SongMoved,
+            (R::Setlist, A::Created) => Self::SetlistCreated,
+            (R::Setlist, A::Updated) => Self::SetlistUpdated,
+            (R::Setlist, A::Deleted) => Self::SetlistDeleted,
+            (R::Setlist, A::Moved) => Self::SetlistMoved,
+            (R::Collection, A::Created) => Self::CollectionCreated,
+            (R::Collection, A::Updated) => Self::CollectionUpdated,
+            (R::Collection, A::Deleted) => Self::CollectionDeleted,
+            (R::Collection, A::Moved) => Self::CollectionMoved,
+            _ => return None,
+        })
+    }
+}
+
+/// Outbound webhook subscription of a team. The signing secret is only returned on create.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct Webhook {
+    pub id: String,
+    pub team_id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Inactive webhooks keep their configuration and log but receive no new events.
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Response of `POST /teams/{team_id}/webhooks`: the webhook plus its signing secret.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    /// Signing secret. Each delivery carries `X-WorshipViewer-Signature: sha256=<hex>`, the
+    /// HMAC-SHA256 of the raw request body keyed with this string.
+    pub secret: String,
+}
+
+/// Body of **POST** / **PUT** `/teams/{team_id}/webhooks`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct CreateWebhook {
+    /// `http` or `https` endpoint that receives `POST` requests with a JSON body.
+    pub url: String,
+    /// At least one subscribable event; `ping` is not allowed here.
+    pub events: Vec<WebhookEvent>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or for the next retry.
+    Pending,
+    Succeeded,
+    /// Gave up after the last retry (or the single attempt of a test delivery).
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "succeeded" => Some(Self::Succeeded),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of a webhook's delivery log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    pub status: WebhookDeliveryStatus,
+    /// Attempts made so far.
+    pub attempts: u32,
+    /// HTTP status of the last attempt, when the endpoint answered at all.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<u16>,
+    /// Transport error or non-2xx summary of the last failed attempt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// When the next retry is due (`pending` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// Exact JSON body that is (or was) signed and sent.
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_round_trip_through_str() {
+        for e in WebhookEvent::SUBSCRIBABLE
+            .into_iter()
+            .chain([WebhookEvent::Ping])
+        {
+            assert_eq!(WebhookEvent::parse(e.as_str()), Some(e));
+            assert_eq!(
+                serde_json::to_value(e).unwrap(),
+                serde_json::json!(e.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn blob_and_member_activity_is_not_published() {
+        assert_eq!(
+            WebhookEvent::from_activity(ActivityResourceType::Setlist, ActivityAction::Moved),
+            Some(WebhookEvent::SetlistMoved)
+        );
+        assert_eq!(
+            WebhookEvent::from_activity(ActivityResourceType::Blob, ActivityAction::Created),
+            None
+        );
+        assert_eq!(
+            WebhookEvent::from_activity(ActivityResourceType::Member, ActivityAction::MemberAdded),
+            None
+        );
+    }
+
+    #[test]
+    fn create_webhook_defaults_to_active() {
+        let body: CreateWebhook =
+            serde_json::from_str(r#"{"url":"https://example.com/hook","events":["song.created"]}"#)
+                .unwrap();
+        assert!(body.active);
+        assert_eq!(body.events, vec![WebhookEvent::SongCreated]);
+    }
+}
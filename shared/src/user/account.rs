@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::team::TeamUserRef;
+
+/// Body of `PATCH /api/v1/users/me`. Omitted fields stay unchanged.
+///
+/// Changing `email` takes two calls: without `otp_code` a code is mailed to the new address;
+/// repeating the request with that code applies the change.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PatchUser {
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Code mailed to the new `email` by the previous request.
+    #[serde(default)]
+    pub otp_code: Option<String>,
+}
+
+/// Optional body of `DELETE /api/v1/users/me`.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteAccount {
+    /// Successor for every shared team the caller is the only admin of (and that has other
+    /// members).
+    #[serde(default)]
+    pub handovers: Vec<TeamHandover>,
+}
+
+/// Member who becomes `admin` of `team_id` when the caller deletes their account.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TeamHandover {
+    pub team_id: String,
+    pub user: TeamUserRef,
+}
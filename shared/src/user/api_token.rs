@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a personal API token may do. Every scope can read; write access is limited to the
+/// resources named by the scope, and only `admin` acts with the full rights of the user.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ApiTokenScope {
+    #[serde(rename = "read")]
+    Read,
+    #[serde(rename = "songs:write")]
+    SongsWrite,
+    #[serde(rename = "setlists:write")]
+    SetlistsWrite,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl ApiTokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::SongsWrite => "songs:write",
+            Self::SetlistsWrite => "setlists:write",
+            Self::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(Self::Read),
+            "songs:write" => Some(Self::SongsWrite),
+            "setlists:write" => Some(Self::SetlistsWrite),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Personal API token metadata; the token value itself is only returned on creation.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub created_at: DateTime<Utc>,
+    /// `null` for tokens that never expire.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Response of `POST /api/v1/users/me/tokens`: the token metadata plus the bearer value.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    /// Send as `Authorization: Bearer <token>`. Not retrievable later.
+    pub token: String,
+}
+
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CreateApiToken {
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+    /// Omit or `null` for a token without expiry.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_round_trip_through_str() {
+        for scope in [
+            ApiTokenScope::Read,
+            ApiTokenScope::SongsWrite,
+            ApiTokenScope::SetlistsWrite,
+            ApiTokenScope::Admin,
+        ] {
+            assert_eq!(ApiTokenScope::parse(scope.as_str()), Some(scope));
+            assert_eq!(
+                serde_json::to_value(scope).unwrap(),
+                serde_json::Value::String(scope.as_str().into())
+            );
+        }
+        assert_eq!(ApiTokenScope::parse("songs:delete"), None);
+    }
+
+    #[test]
+    fn create_rejects_unknown_fields() {
+        let err = serde_json::from_value::<CreateApiToken>(serde_json::json!({
+            "name": "import",
+            "scopes": ["read"],
+            "token": "chosen"
+        }));
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An external OIDC account (provider plus subject) that logs in as this user.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UserIdentity {
+    pub id: String,
+    pub provider: String,
+    /// The provider's stable `sub` claim.
+    pub subject: String,
+    /// Email the provider reported at the last login, if any.
+    #[serde(default)]
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// Body of `POST /api/v1/users/me/identities`.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LinkIdentity {
+    pub provider: String,
+    /// Same-origin path to return to once the provider sent the browser back.
+    #[serde(default)]
+    pub redirect_to: Option<String>,
+}
+
+/// Where to send the browser to confirm the link with the provider.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct IdentityLinkStart {
+    pub authorization_url: String,
+}
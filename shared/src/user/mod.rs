@@ -1,11 +1,21 @@
+mod account;
+mod api_token;
+mod identity;
+mod notification;
+mod passkey;
 mod request;
 mod role;
 mod session;
 mod user;
 
+pub use account::{DeleteAccount, PatchUser, TeamHandover};
+pub use api_token::{ApiToken, ApiTokenScope, CreateApiToken, CreatedApiToken};
+pub use identity::{IdentityLinkStart, LinkIdentity, UserIdentity};
+pub use notification::{ActivityDigest, NotificationPreferences};
+pub use passkey::{Passkey, RegisterPasskey, UpdatePasskey};
 pub use request::CreateUser;
 #[cfg(feature = "backend")]
-pub use request::CreateUserError;
+pub use request::{email_passes_basic_checks, CreateUserError};
 pub use role::Role;
 pub use session::{Session, SessionBody, SessionUserBody};
 pub use user::User;
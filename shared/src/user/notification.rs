@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// How often a user receives the email digest of activity in their teams.
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityDigest {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl ActivityDigest {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "off" => Some(Self::Off),
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            _ => None,
+        }
+    }
+
+    /// Minimum time between two digests, or `None` when digests are disabled.
+    pub fn period_seconds(&self) -> Option<i64> {
+        match self {
+            Self::Off => None,
+            Self::Daily => Some(24 * 60 * 60),
+            Self::Weekly => Some(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// Per-user notification settings (`GET`/`PUT /api/v1/users/me/notification-preferences`).
+#[cfg_attr(feature = "backend", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationPreferences {
+    #[serde(default)]
+    pub activity_digest: ActivityDigest,
+}
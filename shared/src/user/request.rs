@@ -21,8 +21,9 @@ pub enum CreateUserError {
     InvalidEmail,
 }
 
+/// Shape check for a trimmed, lowercased address: one `@`, a local part and a dotted domain.
 #[cfg(feature = "backend")]
-fn email_passes_basic_checks(normalized: &str) -> bool {
+pub fn email_passes_basic_checks(normalized: &str) -> bool {
     let parts: Vec<&str> = normalized.split('@').collect();
     if parts.len() != 2 {
         return false;
@@ -73,6 +74,7 @@ impl CreateUser {
             oauth_picture_url: None,
             oauth_avatar_blob_id: None,
             avatar_blob_id: None,
+            deletion_scheduled_at: None,
         })
     }
 }
@@ -21,7 +21,8 @@ use super::Role;
         "request_count": 0,
         "oauth_picture_url": null,
         "oauth_avatar_blob_id": null,
-        "avatar_blob_id": null
+        "avatar_blob_id": null,
+        "deletion_scheduled_at": null
     }))
 )]
 pub struct User {
@@ -46,6 +47,10 @@ pub struct User {
     /// User-uploaded profile image; takes precedence over [`Self::oauth_avatar_blob_id`].
     #[serde(default)]
     pub avatar_blob_id: Option<String>,
+    /// Set while a self-service account deletion is pending; the account and its personal team
+    /// are removed at this time unless the deletion is cancelled.
+    #[serde(default)]
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
 }
 
 impl User {
@@ -62,6 +67,7 @@ impl User {
             oauth_picture_url: None,
             oauth_avatar_blob_id: None,
             avatar_blob_id: None,
+            deletion_scheduled_at: None,
         }
     }
 }
@@ -8,3 +8,12 @@ pub const MAX_BLOBS_PER_SONG: usize = 64;
 
 /// Maximum additional member entries in create/update team payloads (excluding the creating user).
 pub const MAX_TEAM_MEMBER_INPUTS: usize = 500;
+
+/// Maximum length of a team-defined role name (trimmed).
+pub const MAX_TEAM_ROLE_NAME_LEN: usize = 64;
+
+/// Maximum number of roles a single team may define.
+pub const MAX_TEAM_ROLES: usize = 32;
+
+/// Maximum operations in one `POST /api/v1/batch` request.
+pub const MAX_BATCH_OPERATIONS: usize = 100;